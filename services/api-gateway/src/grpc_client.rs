@@ -4,7 +4,20 @@ pub mod pb {
     tonic::include_proto!("auth");
 }
 
+pub use pb::admin_user_service_client::AdminUserServiceClient;
 pub use pb::auth_service_client::AuthServiceClient;
+pub use pb::organization_service_client::OrganizationServiceClient;
 pub use pb::{
-    GetMeRequest, LoginRequest, RegisterRequest, ValidateTokenRequest,
+    AcceptInvitationRequest, AdminUser as AdminUserResponse, ApiKey as ApiKeyResponse,
+    AuditEvent as AuditEventResponse, ChangeEmailRequest, ChangePasswordRequest,
+    ConfirmTotpEnrollmentRequest, ConsumeMagicLinkRequest, CreateApiKeyRequest,
+    CreateOrganizationRequest, DeleteAccountRequest, DeleteUserRequest,
+    EmailMatchMode as PbEmailMatchMode, EnrollTotpRequest, ExportMyDataRequest,
+    ExportUserDataRequest, GetMeRequest, GetMeResponse, ImpersonateUserRequest,
+    InviteMemberRequest, ListApiKeysRequest, ListAuditEventsRequest, ListMyOrganizationsRequest,
+    ListSessionsRequest, ListUsersRequest, LoginRequest, LoginResponse,
+    Organization as OrganizationResponse, RegisterRequest, RequestMagicLinkRequest,
+    RevokeApiKeyRequest, RevokeOtherSessionsRequest, RevokeSessionRequest,
+    Session as SessionResponse, SetUserActiveRequest, SwitchOrganizationRequest,
+    UpdateProfileRequest, ValidateTokenRequest, VerifyMfaRequest,
 };
@@ -17,6 +17,7 @@ mod grpc_client;
 mod router;
 mod schema;
 
+use std::net::SocketAddr;
 use std::time::Duration;
 
 use async_graphql::Schema;
@@ -124,9 +125,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     info!("API Gateway listening on {}", addr);
     info!("GraphiQL playground: http://{}/graphql", addr);
 
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
-        .await?;
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_signal())
+    .await?;
 
     info!("API Gateway shut down gracefully");
     Ok(())
@@ -4,13 +4,14 @@
 //! playground at `GET /graphql` for development.
 //! Includes security headers, request body limits, and rate limiting.
 
+use std::net::SocketAddr;
 use std::num::NonZeroU32;
 use std::sync::Arc;
 
 use async_graphql::http::GraphiQLSource;
 use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
 use axum::{
-    extract::{Request, State},
+    extract::{ConnectInfo, Request, State},
     http::{HeaderMap, HeaderName, HeaderValue, StatusCode},
     middleware::{self, Next},
     response::{Html, IntoResponse, Response},
@@ -26,27 +27,45 @@ use tower_http::set_header::SetResponseHeaderLayer;
 use tower_http::trace::TraceLayer;
 
 use crate::grpc_client::AuthServiceClient;
-use crate::schema::Token;
+use crate::schema::{ClientIp, ClientUserAgent, Token};
 use crate::AppState;
 
 /// Shared rate limiter type
 type SharedRateLimiter = Arc<RateLimiter<NotKeyed, InMemoryState, DefaultClock>>;
 
+/// Header carrying an API key for machine clients
+const API_KEY_HEADER: &str = "x-api-key";
+
+/// Credential presented with a request: the Bearer token from the
+/// Authorization header, or else the `X-Api-Key` header; empty if neither
+fn request_token(headers: &HeaderMap) -> String {
+    headers
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .or_else(|| headers.get(API_KEY_HEADER).and_then(|v| v.to_str().ok()))
+        .unwrap_or_default()
+        .trim()
+        .to_string()
+}
+
 /// Handle incoming GraphQL requests
 async fn graphql_handler(
     State(state): State<AppState>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
     headers: HeaderMap,
     req: GraphQLRequest,
 ) -> GraphQLResponse {
-    // Extract Bearer token from Authorization header (if present)
-    let token = headers
-        .get(axum::http::header::AUTHORIZATION)
+    let mut request = req
+        .into_inner()
+        .data(Token(request_token(&headers)))
+        .data(ClientIp(peer.ip()));
+    if let Some(user_agent) = headers
+        .get(axum::http::header::USER_AGENT)
         .and_then(|v| v.to_str().ok())
-        .and_then(|v| v.strip_prefix("Bearer "))
-        .unwrap_or_default()
-        .to_string();
-
-    let request = req.into_inner().data(Token(token));
+    {
+        request = request.data(ClientUserAgent(user_agent.to_string()));
+    }
     state.schema.execute(request).await.into()
 }
 
@@ -61,11 +80,8 @@ async fn graphiql() -> impl IntoResponse {
 async fn health(State(state): State<AppState>) -> impl IntoResponse {
     let auth_ok = check_auth_service_health(&state.grpc_channel).await;
 
-    let status = if auth_ok {
-        StatusCode::OK
-    } else {
-        StatusCode::OK // Gateway itself is healthy, downstream may not be
-    };
+    // Gateway itself is healthy even when downstream is not
+    let status = StatusCode::OK;
 
     (
         status,
@@ -155,3 +171,26 @@ pub fn create_router(state: AppState, rate_limit_per_second: u32) -> Router {
         // State
         .with_state(state)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_token_prefers_bearer_over_api_key() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(request_token(&headers), "");
+
+        headers.insert(
+            API_KEY_HEADER,
+            HeaderValue::from_static("tsk_0123abcd_secret"),
+        );
+        assert_eq!(request_token(&headers), "tsk_0123abcd_secret");
+
+        headers.insert(
+            axum::http::header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer jwt"),
+        );
+        assert_eq!(request_token(&headers), "jwt");
+    }
+}
@@ -5,10 +5,24 @@
 //! A circuit breaker protects against cascading failures when the
 //! auth-service is unavailable.
 
-use async_graphql::{Context, ErrorExtensions, InputObject, Object, SimpleObject};
+use std::net::IpAddr;
+
+use async_graphql::{Context, Enum, ErrorExtensions, InputObject, Object, SimpleObject};
 
 use crate::circuit_breaker::CircuitBreaker;
-use crate::grpc_client::{AuthServiceClient, GetMeRequest, LoginRequest, RegisterRequest};
+use crate::grpc_client::{
+    AcceptInvitationRequest, AdminUserResponse, AdminUserServiceClient, ApiKeyResponse,
+    AuditEventResponse, AuthServiceClient, ChangeEmailRequest, ChangePasswordRequest,
+    ConfirmTotpEnrollmentRequest, ConsumeMagicLinkRequest, CreateApiKeyRequest,
+    CreateOrganizationRequest, DeleteAccountRequest, DeleteUserRequest, EnrollTotpRequest,
+    ExportMyDataRequest, ExportUserDataRequest, GetMeRequest, GetMeResponse,
+    ImpersonateUserRequest, InviteMemberRequest, ListApiKeysRequest, ListAuditEventsRequest,
+    ListMyOrganizationsRequest, ListSessionsRequest, ListUsersRequest, LoginRequest,
+    LoginResponse, OrganizationResponse, OrganizationServiceClient,
+    PbEmailMatchMode, RegisterRequest, RequestMagicLinkRequest, RevokeApiKeyRequest,
+    RevokeOtherSessionsRequest, RevokeSessionRequest, SessionResponse, SetUserActiveRequest,
+    SwitchOrganizationRequest, UpdateProfileRequest, VerifyMfaRequest,
+};
 
 // ============================================================================
 // GraphQL types
@@ -23,12 +37,32 @@ pub struct RegisterPayload {
 }
 
 /// Login result containing a JWT token and user details
+///
+/// For accounts with two-factor authentication, `mfaRequired` is set and only
+/// `challengeToken` is returned; pass it to `verifyMfa` to get the token.
 #[derive(SimpleObject)]
 pub struct LoginPayload {
-    pub token: String,
-    pub user_id: String,
-    pub email: String,
+    pub token: Option<String>,
+    pub user_id: Option<String>,
+    pub email: Option<String>,
     pub display_name: Option<String>,
+    pub mfa_required: bool,
+    pub challenge_token: Option<String>,
+}
+
+/// Authenticator secret for a started TOTP enrollment
+#[derive(SimpleObject)]
+pub struct TotpEnrollmentPayload {
+    /// Base32 secret for manual entry
+    pub secret: String,
+    /// `otpauth://` URI, usually rendered as a QR code
+    pub otpauth_uri: String,
+}
+
+/// Single-use recovery codes, shown once when TOTP is enabled
+#[derive(SimpleObject)]
+pub struct RecoveryCodesPayload {
+    pub recovery_codes: Vec<String>,
 }
 
 /// Current authenticated user info
@@ -38,12 +72,258 @@ pub struct User {
     pub email: String,
     pub display_name: Option<String>,
     pub is_active: bool,
+    /// Whether the user has confirmed their email address
+    pub email_verified: bool,
+    /// Roles held by the user (e.g. `customer`, `organizer`, `admin`)
+    pub roles: Vec<String>,
+    /// Permissions granted by the roles (e.g. `events:manage`)
+    pub permissions: Vec<String>,
+}
+
+impl From<GetMeResponse> for User {
+    fn from(resp: GetMeResponse) -> Self {
+        Self {
+            user_id: resp.user_id,
+            email: resp.email,
+            display_name: resp.display_name,
+            is_active: resp.is_active,
+            email_verified: resp.email_verified,
+            roles: resp.roles,
+            permissions: resp.permissions,
+        }
+    }
+}
+
+/// User account as seen by back-office staff
+#[derive(SimpleObject)]
+pub struct AdminUser {
+    pub user_id: String,
+    pub email: String,
+    pub display_name: Option<String>,
+    pub is_active: bool,
+    pub email_verified: bool,
+    pub roles: Vec<String>,
+    /// RFC 3339
+    pub created_at: String,
+    /// RFC 3339
+    pub updated_at: String,
+}
+
+impl From<AdminUserResponse> for AdminUser {
+    fn from(resp: AdminUserResponse) -> Self {
+        Self {
+            user_id: resp.user_id,
+            email: resp.email,
+            display_name: resp.display_name,
+            is_active: resp.is_active,
+            email_verified: resp.email_verified,
+            roles: resp.roles,
+            created_at: resp.created_at,
+            updated_at: resp.updated_at,
+        }
+    }
+}
+
+/// Short-lived token for acting as a user. It names the administrator in
+/// its `act` claim and cannot be refreshed.
+#[derive(SimpleObject)]
+pub struct ImpersonationPayload {
+    pub token: String,
+    /// Seconds until the token expires
+    pub expires_in: i64,
+    pub user_id: String,
+    pub email: String,
+}
+
+/// One page of users, newest first
+#[derive(SimpleObject)]
+pub struct UserPage {
+    pub users: Vec<AdminUser>,
+    /// Pass as `after` to get the next page; null on the last page
+    pub next_cursor: Option<String>,
+}
+
+/// Entry of the security audit log
+#[derive(SimpleObject)]
+pub struct AuditEvent {
+    pub id: String,
+    /// RFC 3339
+    pub occurred_at: String,
+    /// Action identifier, e.g. `login.password`
+    pub action: String,
+    pub outcome: AuditOutcome,
+    /// User who acted; null for failed sign-ins and rejected tokens
+    pub actor_id: Option<String>,
+    /// User acted upon
+    pub target_id: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub detail: Option<String>,
+}
+
+impl From<AuditEventResponse> for AuditEvent {
+    fn from(resp: AuditEventResponse) -> Self {
+        Self {
+            id: resp.id,
+            occurred_at: resp.occurred_at,
+            action: resp.action,
+            outcome: match resp.outcome.as_str() {
+                "success" => AuditOutcome::Success,
+                _ => AuditOutcome::Failure,
+            },
+            actor_id: resp.actor_id,
+            target_id: resp.target_id,
+            ip_address: resp.ip_address,
+            user_agent: resp.user_agent,
+            detail: resp.detail,
+        }
+    }
+}
+
+/// One page of audit events, newest first
+#[derive(SimpleObject)]
+pub struct AuditEventPage {
+    pub events: Vec<AuditEvent>,
+    /// Pass as `after` to get the next page; null on the last page
+    pub next_cursor: Option<String>,
+}
+
+/// API key for a machine client; the key itself is only shown on creation
+#[derive(SimpleObject)]
+pub struct ApiKey {
+    pub key_id: String,
+    pub name: String,
+    /// Start of the key, to tell keys apart
+    pub prefix: String,
+    /// Permissions the key is limited to
+    pub scopes: Vec<String>,
+    /// RFC 3339
+    pub created_at: String,
+    /// RFC 3339; null if never used
+    pub last_used_at: Option<String>,
+    /// RFC 3339; null if the key never expires
+    pub expires_at: Option<String>,
+}
+
+impl From<ApiKeyResponse> for ApiKey {
+    fn from(resp: ApiKeyResponse) -> Self {
+        Self {
+            key_id: resp.key_id,
+            name: resp.name,
+            prefix: resp.prefix,
+            scopes: resp.scopes,
+            created_at: resp.created_at,
+            last_used_at: resp.last_used_at,
+            expires_at: resp.expires_at,
+        }
+    }
+}
+
+/// Newly created API key; send `key` in the `X-Api-Key` header. It is not
+/// shown again.
+#[derive(SimpleObject)]
+pub struct CreatedApiKeyPayload {
+    pub key: String,
+    pub api_key: ApiKey,
+}
+
+/// Signed-in device of the current user
+#[derive(SimpleObject)]
+pub struct Session {
+    pub session_id: String,
+    /// Browser or app that signed in, as it reported itself
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    /// RFC 3339
+    pub created_at: String,
+    /// RFC 3339; last login or token refresh
+    pub last_seen_at: String,
+    /// RFC 3339
+    pub expires_at: String,
+    /// Whether this is the session making the request
+    pub current: bool,
+}
+
+impl From<SessionResponse> for Session {
+    fn from(resp: SessionResponse) -> Self {
+        Self {
+            session_id: resp.session_id,
+            user_agent: resp.user_agent,
+            ip_address: resp.ip_address,
+            created_at: resp.created_at,
+            last_seen_at: resp.last_seen_at,
+            expires_at: resp.expires_at,
+            current: resp.current,
+        }
+    }
+}
+
+/// Organization the current user belongs to
+#[derive(SimpleObject)]
+pub struct Organization {
+    pub organization_id: String,
+    pub name: String,
+    /// The current user's role: `member`, `admin` or `owner`
+    pub role: String,
+    /// RFC 3339
+    pub created_at: String,
+    /// RFC 3339; when the current user became a member
+    pub joined_at: String,
+}
+
+impl From<OrganizationResponse> for Organization {
+    fn from(resp: OrganizationResponse) -> Self {
+        Self {
+            organization_id: resp.organization_id,
+            name: resp.name,
+            role: resp.role,
+            created_at: resp.created_at,
+            joined_at: resp.joined_at,
+        }
+    }
+}
+
+/// Invitation mailed to a prospective member
+#[derive(SimpleObject)]
+pub struct InvitationPayload {
+    pub invitation_id: String,
+    /// RFC 3339
+    pub expires_at: String,
+}
+
+/// Access token re-issued for another organization. It stays bound to the
+/// current session, which keeps the organization when refreshed.
+#[derive(SimpleObject)]
+pub struct SwitchOrganizationPayload {
+    pub token: String,
+    /// Null in the personal context
+    pub organization_id: Option<String>,
+    pub role: Option<String>,
 }
 
 // ============================================================================
 // Input types
 // ============================================================================
 
+/// Input for inviting someone to an organization
+#[derive(InputObject)]
+pub struct InviteMemberInput {
+    pub organization_id: String,
+    pub email: String,
+    /// `member` or `admin`
+    pub role: String,
+}
+
+/// Input for creating an API key
+#[derive(InputObject)]
+pub struct CreateApiKeyInput {
+    pub name: String,
+    /// Permissions the key is limited to, e.g. `tickets:purchase`; each must be held by the caller
+    pub scopes: Vec<String>,
+    /// Days until the key expires; omit for a key that never expires
+    pub expires_in_days: Option<u32>,
+}
+
 /// Input for user registration
 #[derive(InputObject)]
 pub struct RegisterInput {
@@ -59,6 +339,94 @@ pub struct LoginInput {
     pub password: String,
 }
 
+/// Input for completing a two-factor login
+#[derive(InputObject)]
+pub struct VerifyMfaInput {
+    /// Challenge token returned by `login`
+    pub challenge_token: String,
+    /// TOTP code or recovery code
+    pub code: String,
+}
+
+/// Input for changing the password of the current user
+#[derive(InputObject)]
+pub struct ChangePasswordInput {
+    /// Empty for accounts without a password, which must have signed in recently instead
+    pub current_password: String,
+    pub new_password: String,
+}
+
+/// Input for changing the email address of the current user
+#[derive(InputObject)]
+pub struct ChangeEmailInput {
+    /// Empty for accounts without a password, which must have signed in recently instead
+    pub current_password: String,
+    pub new_email: String,
+}
+
+/// Input for updating the profile of the current user
+#[derive(InputObject)]
+pub struct UpdateProfileInput {
+    /// New display name; null or empty clears it
+    pub display_name: Option<String>,
+}
+
+/// How `UserFilterInput.email` is matched
+#[derive(Enum, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EmailMatchMode {
+    /// Addresses starting with the term
+    #[default]
+    Prefix,
+    /// Addresses containing the term anywhere
+    Contains,
+}
+
+/// Criteria for listing users; unset fields match everyone
+#[derive(InputObject, Default)]
+pub struct UserFilterInput {
+    pub is_active: Option<bool>,
+    /// Role identifier, e.g. `organizer`
+    pub role: Option<String>,
+    /// RFC 3339; users created at or after this instant
+    pub created_after: Option<String>,
+    /// RFC 3339; users created before this instant
+    pub created_before: Option<String>,
+    /// Email search term
+    pub email: Option<String>,
+    #[graphql(default)]
+    pub email_match: EmailMatchMode,
+}
+
+/// Whether an audited action went through
+#[derive(Enum, Clone, Copy, PartialEq, Eq)]
+pub enum AuditOutcome {
+    Success,
+    Failure,
+}
+
+impl AuditOutcome {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::Failure => "failure",
+        }
+    }
+}
+
+/// Criteria for searching the audit log; unset fields match every entry
+#[derive(InputObject, Default)]
+pub struct AuditEventFilterInput {
+    /// Action identifier, e.g. `login.password`
+    pub action: Option<String>,
+    pub outcome: Option<AuditOutcome>,
+    pub actor_id: Option<String>,
+    pub target_id: Option<String>,
+    /// RFC 3339; events at or after this instant
+    pub occurred_after: Option<String>,
+    /// RFC 3339; events before this instant
+    pub occurred_before: Option<String>,
+}
+
 // ============================================================================
 // Helpers
 // ============================================================================
@@ -69,7 +437,7 @@ pub struct LoginInput {
 /// so cloning is cheap (just an `Arc` bump).
 fn auth_channel(ctx: &Context<'_>) -> async_graphql::Result<tonic::transport::Channel> {
     ctx.data::<tonic::transport::Channel>()
-        .map(|ch| ch.clone())
+        .cloned()
         .map_err(|_| {
             async_graphql::Error::new("Internal configuration error: missing gRPC channel")
                 .extend_with(|_, e| e.set("code", "INTERNAL"))
@@ -79,13 +447,29 @@ fn auth_channel(ctx: &Context<'_>) -> async_graphql::Result<tonic::transport::Ch
 /// Get the circuit breaker from the GraphQL context.
 fn circuit_breaker(ctx: &Context<'_>) -> async_graphql::Result<CircuitBreaker> {
     ctx.data::<CircuitBreaker>()
-        .map(|cb| cb.clone())
+        .cloned()
         .map_err(|_| {
             async_graphql::Error::new("Internal configuration error: missing circuit breaker")
                 .extend_with(|_, e| e.set("code", "INTERNAL"))
         })
 }
 
+/// Get the bearer token or API key from the GraphQL context, rejecting missing ones.
+fn bearer_token(ctx: &Context<'_>) -> async_graphql::Result<String> {
+    let token = ctx
+        .data_opt::<Token>()
+        .map(|t| t.0.clone())
+        .unwrap_or_default();
+
+    if token.is_empty() {
+        return Err(
+            async_graphql::Error::new("Missing or invalid Authorization or X-Api-Key header")
+                .extend_with(|_, e| e.set("code", "UNAUTHENTICATED")),
+        );
+    }
+    Ok(token)
+}
+
 /// Check the circuit breaker before making a call.
 fn check_circuit(cb: &CircuitBreaker) -> async_graphql::Result<()> {
     if !cb.is_available() {
@@ -97,6 +481,45 @@ fn check_circuit(cb: &CircuitBreaker) -> async_graphql::Result<()> {
     Ok(())
 }
 
+/// Pass the address and user agent of the client on to the auth-service,
+/// which throttles failed logins per client and records them with sessions
+/// and in the audit log.
+fn forward_client<T>(ctx: &Context<'_>, request: &mut tonic::Request<T>) {
+    if let Some(ClientIp(ip)) = ctx.data_opt::<ClientIp>() {
+        if let Ok(value) = ip.to_string().parse() {
+            request.metadata_mut().insert("x-forwarded-for", value);
+        }
+    }
+    // tonic sets its own user-agent, so the browser's travels separately
+    if let Some(ClientUserAgent(user_agent)) = ctx.data_opt::<ClientUserAgent>() {
+        if let Ok(value) = user_agent.parse() {
+            request.metadata_mut().insert("x-forwarded-user-agent", value);
+        }
+    }
+}
+
+/// Render a login response: the token, or only the MFA challenge.
+fn login_payload(resp: LoginResponse) -> LoginPayload {
+    if resp.mfa_required {
+        return LoginPayload {
+            token: None,
+            user_id: None,
+            email: None,
+            display_name: None,
+            mfa_required: true,
+            challenge_token: Some(resp.challenge_token),
+        };
+    }
+    LoginPayload {
+        token: Some(resp.token),
+        user_id: Some(resp.user_id),
+        email: Some(resp.email),
+        display_name: resp.display_name,
+        mfa_required: false,
+        challenge_token: None,
+    }
+}
+
 /// Map a tonic gRPC status to an async-graphql error with appropriate code.
 fn grpc_err(status: tonic::Status) -> async_graphql::Error {
     let code = match status.code() {
@@ -105,11 +528,30 @@ fn grpc_err(status: tonic::Status) -> async_graphql::Error {
         tonic::Code::AlreadyExists => "ALREADY_EXISTS",
         tonic::Code::PermissionDenied => "FORBIDDEN",
         tonic::Code::Unauthenticated => "UNAUTHENTICATED",
+        tonic::Code::FailedPrecondition => "FAILED_PRECONDITION",
+        tonic::Code::ResourceExhausted => "TOO_MANY_REQUESTS",
         tonic::Code::Unavailable => "SERVICE_UNAVAILABLE",
         _ => "INTERNAL_SERVER_ERROR",
     };
-    async_graphql::Error::new(status.message().to_string())
-        .extend_with(|_, e| e.set("code", code))
+    let retry_after = status
+        .metadata()
+        .get("retry-after")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<i64>().ok());
+    let password_rules: Option<Vec<String>> = status
+        .metadata()
+        .get("password-rules")
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.split(',').map(String::from).collect());
+    async_graphql::Error::new(status.message().to_string()).extend_with(|_, e| {
+        e.set("code", code);
+        if let Some(secs) = retry_after {
+            e.set("retryAfter", secs);
+        }
+        if let Some(rules) = &password_rules {
+            e.set("passwordRules", rules.clone());
+        }
+    })
 }
 
 // ============================================================================
@@ -123,17 +565,7 @@ pub struct QueryRoot;
 impl QueryRoot {
     /// Get the currently authenticated user. Requires `Authorization: Bearer <token>` header.
     async fn me(&self, ctx: &Context<'_>) -> async_graphql::Result<User> {
-        let token = ctx
-            .data_opt::<Token>()
-            .map(|t| t.0.clone())
-            .unwrap_or_default();
-
-        if token.is_empty() {
-            return Err(
-                async_graphql::Error::new("Missing or invalid Authorization header")
-                    .extend_with(|_, e| e.set("code", "UNAUTHENTICATED")),
-            );
-        }
+        let token = bearer_token(ctx)?;
 
         let channel = auth_channel(ctx)?;
         let cb = circuit_breaker(ctx)?;
@@ -147,13 +579,7 @@ impl QueryRoot {
         match result {
             Ok(resp) => {
                 cb.record_success();
-                let resp = resp.into_inner();
-                Ok(User {
-                    user_id: resp.user_id,
-                    email: resp.email,
-                    display_name: resp.display_name,
-                    is_active: resp.is_active,
-                })
+                Ok(User::from(resp.into_inner()))
             }
             Err(status) => {
                 // Only record infrastructure failures, not business errors
@@ -168,37 +594,89 @@ impl QueryRoot {
         }
     }
 
-    /// Gateway health check
-    async fn health(&self) -> &str {
-        "ok"
-    }
-}
+    /// List users, newest first. Requires the `users:read` permission and
+    /// `Authorization: Bearer <token>` header.
+    async fn users(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<UserFilterInput>,
+        #[graphql(desc = "Page size (default 50, at most 100)")] first: Option<u32>,
+        #[graphql(desc = "`nextCursor` of the previous page")] after: Option<String>,
+    ) -> async_graphql::Result<UserPage> {
+        let token = bearer_token(ctx)?;
+        let filter = filter.unwrap_or_default();
 
-// ============================================================================
-// Mutation
-// ============================================================================
+        let channel = auth_channel(ctx)?;
+        let cb = circuit_breaker(ctx)?;
+        check_circuit(&cb)?;
 
-/// GraphQL mutation root
-pub struct MutationRoot;
+        let mut client = AdminUserServiceClient::new(channel);
+        let result = client
+            .list_users(tonic::Request::new(ListUsersRequest {
+                token,
+                is_active: filter.is_active,
+                role: filter.role,
+                created_after: filter.created_after,
+                created_before: filter.created_before,
+                email_query: filter.email,
+                email_match: match filter.email_match {
+                    EmailMatchMode::Prefix => PbEmailMatchMode::Prefix,
+                    EmailMatchMode::Contains => PbEmailMatchMode::Contains,
+                } as i32,
+                limit: first.unwrap_or(0),
+                cursor: after,
+            }))
+            .await;
 
-#[Object]
-impl MutationRoot {
-    /// Register a new user account
-    async fn register(
+        match result {
+            Ok(resp) => {
+                cb.record_success();
+                let resp = resp.into_inner();
+                Ok(UserPage {
+                    users: resp.users.into_iter().map(AdminUser::from).collect(),
+                    next_cursor: resp.next_cursor,
+                })
+            }
+            Err(status) => {
+                if matches!(
+                    status.code(),
+                    tonic::Code::Unavailable | tonic::Code::DeadlineExceeded | tonic::Code::Internal
+                ) {
+                    cb.record_failure();
+                }
+                Err(grpc_err(status))
+            }
+        }
+    }
+
+    /// Search the security audit log, newest first. Requires the `audit:read`
+    /// permission and `Authorization: Bearer <token>` header.
+    async fn audit_events(
         &self,
         ctx: &Context<'_>,
-        input: RegisterInput,
-    ) -> async_graphql::Result<RegisterPayload> {
+        filter: Option<AuditEventFilterInput>,
+        #[graphql(desc = "Page size (default 50, at most 100)")] first: Option<u32>,
+        #[graphql(desc = "`nextCursor` of the previous page")] after: Option<String>,
+    ) -> async_graphql::Result<AuditEventPage> {
+        let token = bearer_token(ctx)?;
+        let filter = filter.unwrap_or_default();
+
         let channel = auth_channel(ctx)?;
         let cb = circuit_breaker(ctx)?;
         check_circuit(&cb)?;
 
-        let mut client = AuthServiceClient::new(channel);
+        let mut client = AdminUserServiceClient::new(channel);
         let result = client
-            .register(tonic::Request::new(RegisterRequest {
-                email: input.email,
-                password: input.password,
-                display_name: input.display_name,
+            .list_audit_events(tonic::Request::new(ListAuditEventsRequest {
+                token,
+                action: filter.action,
+                outcome: filter.outcome.map(|outcome| outcome.as_str().to_string()),
+                actor_id: filter.actor_id,
+                target_id: filter.target_id,
+                occurred_after: filter.occurred_after,
+                occurred_before: filter.occurred_before,
+                limit: first.unwrap_or(0),
+                cursor: after,
             }))
             .await;
 
@@ -206,10 +684,9 @@ impl MutationRoot {
             Ok(resp) => {
                 cb.record_success();
                 let resp = resp.into_inner();
-                Ok(RegisterPayload {
-                    user_id: resp.user_id,
-                    email: resp.email,
-                    display_name: resp.display_name,
+                Ok(AuditEventPage {
+                    events: resp.events.into_iter().map(AuditEvent::from).collect(),
+                    next_cursor: resp.next_cursor,
                 })
             }
             Err(status) => {
@@ -224,34 +701,96 @@ impl MutationRoot {
         }
     }
 
-    /// Login with email and password, returns a JWT token
-    async fn login(
+    /// Everything stored about the current user, as a JSON document.
+    /// Requires `Authorization: Bearer <token>` header.
+    async fn export_my_data(&self, ctx: &Context<'_>) -> async_graphql::Result<String> {
+        let token = bearer_token(ctx)?;
+
+        let channel = auth_channel(ctx)?;
+        let cb = circuit_breaker(ctx)?;
+        check_circuit(&cb)?;
+
+        let mut client = AuthServiceClient::new(channel);
+        let result = client
+            .export_my_data(tonic::Request::new(ExportMyDataRequest { token }))
+            .await;
+
+        match result {
+            Ok(resp) => {
+                cb.record_success();
+                Ok(resp.into_inner().data)
+            }
+            Err(status) => {
+                if matches!(
+                    status.code(),
+                    tonic::Code::Unavailable | tonic::Code::DeadlineExceeded | tonic::Code::Internal
+                ) {
+                    cb.record_failure();
+                }
+                Err(grpc_err(status))
+            }
+        }
+    }
+
+    /// Everything stored about a user, as a JSON document. Requires the
+    /// `users:manage` permission and `Authorization: Bearer <token>` header.
+    async fn export_user_data(
         &self,
         ctx: &Context<'_>,
-        input: LoginInput,
-    ) -> async_graphql::Result<LoginPayload> {
+        user_id: String,
+    ) -> async_graphql::Result<String> {
+        let token = bearer_token(ctx)?;
+
+        let channel = auth_channel(ctx)?;
+        let cb = circuit_breaker(ctx)?;
+        check_circuit(&cb)?;
+
+        let mut request = tonic::Request::new(ExportUserDataRequest { token, user_id });
+        forward_client(ctx, &mut request);
+
+        let mut client = AdminUserServiceClient::new(channel);
+        let result = client.export_user_data(request).await;
+
+        match result {
+            Ok(resp) => {
+                cb.record_success();
+                Ok(resp.into_inner().data)
+            }
+            Err(status) => {
+                if matches!(
+                    status.code(),
+                    tonic::Code::Unavailable | tonic::Code::DeadlineExceeded | tonic::Code::Internal
+                ) {
+                    cb.record_failure();
+                }
+                Err(grpc_err(status))
+            }
+        }
+    }
+
+    /// API keys of the current user, newest first. Requires
+    /// `Authorization: Bearer <token>` header.
+    async fn api_keys(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<ApiKey>> {
+        let token = bearer_token(ctx)?;
+
         let channel = auth_channel(ctx)?;
         let cb = circuit_breaker(ctx)?;
         check_circuit(&cb)?;
 
         let mut client = AuthServiceClient::new(channel);
         let result = client
-            .login(tonic::Request::new(LoginRequest {
-                email: input.email,
-                password: input.password,
-            }))
+            .list_api_keys(tonic::Request::new(ListApiKeysRequest { token }))
             .await;
 
         match result {
             Ok(resp) => {
                 cb.record_success();
-                let resp = resp.into_inner();
-                Ok(LoginPayload {
-                    token: resp.token,
-                    user_id: resp.user_id,
-                    email: resp.email,
-                    display_name: resp.display_name,
-                })
+                Ok(resp
+                    .into_inner()
+                    .api_keys
+                    .into_iter()
+                    .map(ApiKey::from)
+                    .collect())
             }
             Err(status) => {
                 if matches!(
@@ -264,11 +803,990 @@ impl MutationRoot {
             }
         }
     }
-}
 
-// ============================================================================
-// Token wrapper (inserted per-request from Authorization header)
-// ============================================================================
+    /// Signed-in devices of the current user, most recently active first.
+    /// Requires `Authorization: Bearer <token>` header.
+    async fn sessions(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<Session>> {
+        let token = bearer_token(ctx)?;
 
-/// Bearer token extracted from the HTTP Authorization header
-pub struct Token(pub String);
+        let channel = auth_channel(ctx)?;
+        let cb = circuit_breaker(ctx)?;
+        check_circuit(&cb)?;
+
+        let mut client = AuthServiceClient::new(channel);
+        let result = client
+            .list_sessions(tonic::Request::new(ListSessionsRequest { token }))
+            .await;
+
+        match result {
+            Ok(resp) => {
+                cb.record_success();
+                Ok(resp
+                    .into_inner()
+                    .sessions
+                    .into_iter()
+                    .map(Session::from)
+                    .collect())
+            }
+            Err(status) => {
+                if matches!(
+                    status.code(),
+                    tonic::Code::Unavailable | tonic::Code::DeadlineExceeded | tonic::Code::Internal
+                ) {
+                    cb.record_failure();
+                }
+                Err(grpc_err(status))
+            }
+        }
+    }
+
+    /// Organizations the current user belongs to, oldest membership first.
+    /// Requires `Authorization: Bearer <token>` header.
+    async fn organizations(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<Organization>> {
+        let token = bearer_token(ctx)?;
+
+        let channel = auth_channel(ctx)?;
+        let cb = circuit_breaker(ctx)?;
+        check_circuit(&cb)?;
+
+        let mut client = OrganizationServiceClient::new(channel);
+        let result = client
+            .list_my_organizations(tonic::Request::new(ListMyOrganizationsRequest { token }))
+            .await;
+
+        match result {
+            Ok(resp) => {
+                cb.record_success();
+                Ok(resp
+                    .into_inner()
+                    .organizations
+                    .into_iter()
+                    .map(Organization::from)
+                    .collect())
+            }
+            Err(status) => {
+                if matches!(
+                    status.code(),
+                    tonic::Code::Unavailable | tonic::Code::DeadlineExceeded | tonic::Code::Internal
+                ) {
+                    cb.record_failure();
+                }
+                Err(grpc_err(status))
+            }
+        }
+    }
+
+    /// Gateway health check
+    async fn health(&self) -> &str {
+        "ok"
+    }
+}
+
+// ============================================================================
+// Mutation
+// ============================================================================
+
+/// GraphQL mutation root
+pub struct MutationRoot;
+
+#[Object]
+impl MutationRoot {
+    /// Register a new user account
+    async fn register(
+        &self,
+        ctx: &Context<'_>,
+        input: RegisterInput,
+    ) -> async_graphql::Result<RegisterPayload> {
+        let channel = auth_channel(ctx)?;
+        let cb = circuit_breaker(ctx)?;
+        check_circuit(&cb)?;
+
+        let mut request = tonic::Request::new(RegisterRequest {
+            email: input.email,
+            password: input.password,
+            display_name: input.display_name,
+        });
+        forward_client(ctx, &mut request);
+
+        let mut client = AuthServiceClient::new(channel);
+        let result = client.register(request).await;
+
+        match result {
+            Ok(resp) => {
+                cb.record_success();
+                let resp = resp.into_inner();
+                Ok(RegisterPayload {
+                    user_id: resp.user_id,
+                    email: resp.email,
+                    display_name: resp.display_name,
+                })
+            }
+            Err(status) => {
+                if matches!(
+                    status.code(),
+                    tonic::Code::Unavailable | tonic::Code::DeadlineExceeded | tonic::Code::Internal
+                ) {
+                    cb.record_failure();
+                }
+                Err(grpc_err(status))
+            }
+        }
+    }
+
+    /// Login with email and password, returns a JWT token or an MFA challenge
+    async fn login(
+        &self,
+        ctx: &Context<'_>,
+        input: LoginInput,
+    ) -> async_graphql::Result<LoginPayload> {
+        let channel = auth_channel(ctx)?;
+        let cb = circuit_breaker(ctx)?;
+        check_circuit(&cb)?;
+
+        let mut request = tonic::Request::new(LoginRequest {
+            email: input.email,
+            password: input.password,
+        });
+        forward_client(ctx, &mut request);
+
+        let mut client = AuthServiceClient::new(channel);
+        let result = client.login(request).await;
+
+        match result {
+            Ok(resp) => {
+                cb.record_success();
+                Ok(login_payload(resp.into_inner()))
+            }
+            Err(status) => {
+                if matches!(
+                    status.code(),
+                    tonic::Code::Unavailable | tonic::Code::DeadlineExceeded | tonic::Code::Internal
+                ) {
+                    cb.record_failure();
+                }
+                Err(grpc_err(status))
+            }
+        }
+    }
+
+    /// Mail a single-use login link. Succeeds whether or not the account
+    /// exists, so it cannot be used to probe for accounts.
+    async fn request_magic_link(
+        &self,
+        ctx: &Context<'_>,
+        email: String,
+    ) -> async_graphql::Result<bool> {
+        let channel = auth_channel(ctx)?;
+        let cb = circuit_breaker(ctx)?;
+        check_circuit(&cb)?;
+
+        let mut client = AuthServiceClient::new(channel);
+        let result = client
+            .request_magic_link(tonic::Request::new(RequestMagicLinkRequest { email }))
+            .await;
+
+        match result {
+            Ok(_) => {
+                cb.record_success();
+                Ok(true)
+            }
+            Err(status) => {
+                if matches!(
+                    status.code(),
+                    tonic::Code::Unavailable | tonic::Code::DeadlineExceeded | tonic::Code::Internal
+                ) {
+                    cb.record_failure();
+                }
+                Err(grpc_err(status))
+            }
+        }
+    }
+
+    /// Login with the token from a magic link, returns a JWT token or an MFA challenge
+    async fn consume_magic_link(
+        &self,
+        ctx: &Context<'_>,
+        token: String,
+    ) -> async_graphql::Result<LoginPayload> {
+        let channel = auth_channel(ctx)?;
+        let cb = circuit_breaker(ctx)?;
+        check_circuit(&cb)?;
+
+        let mut request = tonic::Request::new(ConsumeMagicLinkRequest { token });
+        forward_client(ctx, &mut request);
+
+        let mut client = AuthServiceClient::new(channel);
+        let result = client.consume_magic_link(request).await;
+
+        match result {
+            Ok(resp) => {
+                cb.record_success();
+                Ok(login_payload(resp.into_inner()))
+            }
+            Err(status) => {
+                if matches!(
+                    status.code(),
+                    tonic::Code::Unavailable | tonic::Code::DeadlineExceeded | tonic::Code::Internal
+                ) {
+                    cb.record_failure();
+                }
+                Err(grpc_err(status))
+            }
+        }
+    }
+
+    /// Complete a two-factor login with a TOTP code or a recovery code
+    async fn verify_mfa(
+        &self,
+        ctx: &Context<'_>,
+        input: VerifyMfaInput,
+    ) -> async_graphql::Result<LoginPayload> {
+        let channel = auth_channel(ctx)?;
+        let cb = circuit_breaker(ctx)?;
+        check_circuit(&cb)?;
+
+        let mut request = tonic::Request::new(VerifyMfaRequest {
+            challenge_token: input.challenge_token,
+            code: input.code,
+        });
+        forward_client(ctx, &mut request);
+
+        let mut client = AuthServiceClient::new(channel);
+        let result = client.verify_mfa(request).await;
+
+        match result {
+            Ok(resp) => {
+                cb.record_success();
+                let resp = resp.into_inner();
+                Ok(LoginPayload {
+                    token: Some(resp.token),
+                    user_id: Some(resp.user_id),
+                    email: Some(resp.email),
+                    display_name: resp.display_name,
+                    mfa_required: false,
+                    challenge_token: None,
+                })
+            }
+            Err(status) => {
+                if matches!(
+                    status.code(),
+                    tonic::Code::Unavailable | tonic::Code::DeadlineExceeded | tonic::Code::Internal
+                ) {
+                    cb.record_failure();
+                }
+                Err(grpc_err(status))
+            }
+        }
+    }
+
+    /// Start TOTP enrollment. Requires `Authorization: Bearer <token>` header.
+    async fn enroll_totp(&self, ctx: &Context<'_>) -> async_graphql::Result<TotpEnrollmentPayload> {
+        let token = bearer_token(ctx)?;
+
+        let channel = auth_channel(ctx)?;
+        let cb = circuit_breaker(ctx)?;
+        check_circuit(&cb)?;
+
+        let mut client = AuthServiceClient::new(channel);
+        let result = client
+            .enroll_totp(tonic::Request::new(EnrollTotpRequest { token }))
+            .await;
+
+        match result {
+            Ok(resp) => {
+                cb.record_success();
+                let resp = resp.into_inner();
+                Ok(TotpEnrollmentPayload {
+                    secret: resp.secret,
+                    otpauth_uri: resp.otpauth_uri,
+                })
+            }
+            Err(status) => {
+                if matches!(
+                    status.code(),
+                    tonic::Code::Unavailable | tonic::Code::DeadlineExceeded | tonic::Code::Internal
+                ) {
+                    cb.record_failure();
+                }
+                Err(grpc_err(status))
+            }
+        }
+    }
+
+    /// Enable TOTP with the first code from the authenticator app.
+    /// Requires `Authorization: Bearer <token>` header.
+    async fn confirm_totp_enrollment(
+        &self,
+        ctx: &Context<'_>,
+        code: String,
+    ) -> async_graphql::Result<RecoveryCodesPayload> {
+        let token = bearer_token(ctx)?;
+
+        let channel = auth_channel(ctx)?;
+        let cb = circuit_breaker(ctx)?;
+        check_circuit(&cb)?;
+
+        let mut client = AuthServiceClient::new(channel);
+        let result = client
+            .confirm_totp_enrollment(tonic::Request::new(ConfirmTotpEnrollmentRequest {
+                token,
+                code,
+            }))
+            .await;
+
+        match result {
+            Ok(resp) => {
+                cb.record_success();
+                Ok(RecoveryCodesPayload {
+                    recovery_codes: resp.into_inner().recovery_codes,
+                })
+            }
+            Err(status) => {
+                if matches!(
+                    status.code(),
+                    tonic::Code::Unavailable | tonic::Code::DeadlineExceeded | tonic::Code::Internal
+                ) {
+                    cb.record_failure();
+                }
+                Err(grpc_err(status))
+            }
+        }
+    }
+
+    /// Change the password of the current user; signs out all other sessions.
+    /// Requires `Authorization: Bearer <token>` header.
+    async fn change_password(
+        &self,
+        ctx: &Context<'_>,
+        input: ChangePasswordInput,
+    ) -> async_graphql::Result<bool> {
+        let token = bearer_token(ctx)?;
+
+        let channel = auth_channel(ctx)?;
+        let cb = circuit_breaker(ctx)?;
+        check_circuit(&cb)?;
+
+        let mut request = tonic::Request::new(ChangePasswordRequest {
+            token,
+            current_password: input.current_password,
+            new_password: input.new_password,
+        });
+        forward_client(ctx, &mut request);
+
+        let mut client = AuthServiceClient::new(channel);
+        let result = client.change_password(request).await;
+
+        match result {
+            Ok(_) => {
+                cb.record_success();
+                Ok(true)
+            }
+            Err(status) => {
+                if matches!(
+                    status.code(),
+                    tonic::Code::Unavailable | tonic::Code::DeadlineExceeded | tonic::Code::Internal
+                ) {
+                    cb.record_failure();
+                }
+                Err(grpc_err(status))
+            }
+        }
+    }
+
+    /// Change the email address of the current user. The new address must be
+    /// verified again. Requires `Authorization: Bearer <token>` header.
+    async fn change_email(
+        &self,
+        ctx: &Context<'_>,
+        input: ChangeEmailInput,
+    ) -> async_graphql::Result<User> {
+        let token = bearer_token(ctx)?;
+
+        let channel = auth_channel(ctx)?;
+        let cb = circuit_breaker(ctx)?;
+        check_circuit(&cb)?;
+
+        let mut request = tonic::Request::new(ChangeEmailRequest {
+            token,
+            current_password: input.current_password,
+            new_email: input.new_email,
+        });
+        forward_client(ctx, &mut request);
+
+        let mut client = AuthServiceClient::new(channel);
+        let result = client.change_email(request).await;
+
+        match result {
+            Ok(resp) => {
+                cb.record_success();
+                Ok(User::from(resp.into_inner()))
+            }
+            Err(status) => {
+                if matches!(
+                    status.code(),
+                    tonic::Code::Unavailable | tonic::Code::DeadlineExceeded | tonic::Code::Internal
+                ) {
+                    cb.record_failure();
+                }
+                Err(grpc_err(status))
+            }
+        }
+    }
+
+    /// Update the profile of the current user.
+    /// Requires `Authorization: Bearer <token>` header.
+    async fn update_profile(
+        &self,
+        ctx: &Context<'_>,
+        input: UpdateProfileInput,
+    ) -> async_graphql::Result<User> {
+        let token = bearer_token(ctx)?;
+
+        let channel = auth_channel(ctx)?;
+        let cb = circuit_breaker(ctx)?;
+        check_circuit(&cb)?;
+
+        let mut request = tonic::Request::new(UpdateProfileRequest {
+            token,
+            display_name: input.display_name,
+        });
+        forward_client(ctx, &mut request);
+
+        let mut client = AuthServiceClient::new(channel);
+        let result = client.update_profile(request).await;
+
+        match result {
+            Ok(resp) => {
+                cb.record_success();
+                Ok(User::from(resp.into_inner()))
+            }
+            Err(status) => {
+                if matches!(
+                    status.code(),
+                    tonic::Code::Unavailable | tonic::Code::DeadlineExceeded | tonic::Code::Internal
+                ) {
+                    cb.record_failure();
+                }
+                Err(grpc_err(status))
+            }
+        }
+    }
+
+    /// Deactivate a user account and sign it out. Requires the `users:manage`
+    /// permission and `Authorization: Bearer <token>` header.
+    async fn deactivate_user(
+        &self,
+        ctx: &Context<'_>,
+        user_id: String,
+    ) -> async_graphql::Result<AdminUser> {
+        let token = bearer_token(ctx)?;
+
+        let channel = auth_channel(ctx)?;
+        let cb = circuit_breaker(ctx)?;
+        check_circuit(&cb)?;
+
+        let mut request = tonic::Request::new(SetUserActiveRequest { token, user_id });
+        forward_client(ctx, &mut request);
+
+        let mut client = AdminUserServiceClient::new(channel);
+        let result = client.deactivate_user(request).await;
+
+        match result {
+            Ok(resp) => {
+                cb.record_success();
+                Ok(AdminUser::from(resp.into_inner()))
+            }
+            Err(status) => {
+                if matches!(
+                    status.code(),
+                    tonic::Code::Unavailable | tonic::Code::DeadlineExceeded | tonic::Code::Internal
+                ) {
+                    cb.record_failure();
+                }
+                Err(grpc_err(status))
+            }
+        }
+    }
+
+    /// Issue a short-lived token for acting as a customer or organizer.
+    /// Requires the `users:impersonate` permission and
+    /// `Authorization: Bearer <token>` header.
+    async fn impersonate_user(
+        &self,
+        ctx: &Context<'_>,
+        user_id: String,
+    ) -> async_graphql::Result<ImpersonationPayload> {
+        let token = bearer_token(ctx)?;
+
+        let channel = auth_channel(ctx)?;
+        let cb = circuit_breaker(ctx)?;
+        check_circuit(&cb)?;
+
+        let mut request = tonic::Request::new(ImpersonateUserRequest { token, user_id });
+        forward_client(ctx, &mut request);
+
+        let mut client = AdminUserServiceClient::new(channel);
+        let result = client.impersonate_user(request).await;
+
+        match result {
+            Ok(resp) => {
+                cb.record_success();
+                let resp = resp.into_inner();
+                Ok(ImpersonationPayload {
+                    token: resp.token,
+                    expires_in: resp.expires_in,
+                    user_id: resp.user_id,
+                    email: resp.email,
+                })
+            }
+            Err(status) => {
+                if matches!(
+                    status.code(),
+                    tonic::Code::Unavailable | tonic::Code::DeadlineExceeded | tonic::Code::Internal
+                ) {
+                    cb.record_failure();
+                }
+                Err(grpc_err(status))
+            }
+        }
+    }
+
+    /// Reactivate a deactivated user account. Requires the `users:manage`
+    /// permission and `Authorization: Bearer <token>` header.
+    async fn reactivate_user(
+        &self,
+        ctx: &Context<'_>,
+        user_id: String,
+    ) -> async_graphql::Result<AdminUser> {
+        let token = bearer_token(ctx)?;
+
+        let channel = auth_channel(ctx)?;
+        let cb = circuit_breaker(ctx)?;
+        check_circuit(&cb)?;
+
+        let mut request = tonic::Request::new(SetUserActiveRequest { token, user_id });
+        forward_client(ctx, &mut request);
+
+        let mut client = AdminUserServiceClient::new(channel);
+        let result = client.reactivate_user(request).await;
+
+        match result {
+            Ok(resp) => {
+                cb.record_success();
+                Ok(AdminUser::from(resp.into_inner()))
+            }
+            Err(status) => {
+                if matches!(
+                    status.code(),
+                    tonic::Code::Unavailable | tonic::Code::DeadlineExceeded | tonic::Code::Internal
+                ) {
+                    cb.record_failure();
+                }
+                Err(grpc_err(status))
+            }
+        }
+    }
+
+    /// Erase the current user's account and sign it out. Requires the current
+    /// password (accounts without one must have signed in recently) and
+    /// `Authorization: Bearer <token>` header.
+    async fn delete_account(
+        &self,
+        ctx: &Context<'_>,
+        current_password: String,
+    ) -> async_graphql::Result<bool> {
+        let token = bearer_token(ctx)?;
+
+        let channel = auth_channel(ctx)?;
+        let cb = circuit_breaker(ctx)?;
+        check_circuit(&cb)?;
+
+        let mut request = tonic::Request::new(DeleteAccountRequest {
+            token,
+            current_password,
+        });
+        forward_client(ctx, &mut request);
+
+        let mut client = AuthServiceClient::new(channel);
+        let result = client.delete_account(request).await;
+
+        match result {
+            Ok(_) => {
+                cb.record_success();
+                Ok(true)
+            }
+            Err(status) => {
+                if matches!(
+                    status.code(),
+                    tonic::Code::Unavailable | tonic::Code::DeadlineExceeded | tonic::Code::Internal
+                ) {
+                    cb.record_failure();
+                }
+                Err(grpc_err(status))
+            }
+        }
+    }
+
+    /// Erase a user's account; other services are told to erase their data
+    /// too. Requires the `users:manage` permission and `Authorization: Bearer <token>` header.
+    async fn delete_user(
+        &self,
+        ctx: &Context<'_>,
+        user_id: String,
+    ) -> async_graphql::Result<bool> {
+        let token = bearer_token(ctx)?;
+
+        let channel = auth_channel(ctx)?;
+        let cb = circuit_breaker(ctx)?;
+        check_circuit(&cb)?;
+
+        let mut request = tonic::Request::new(DeleteUserRequest { token, user_id });
+        forward_client(ctx, &mut request);
+
+        let mut client = AdminUserServiceClient::new(channel);
+        let result = client.delete_user(request).await;
+
+        match result {
+            Ok(_) => {
+                cb.record_success();
+                Ok(true)
+            }
+            Err(status) => {
+                if matches!(
+                    status.code(),
+                    tonic::Code::Unavailable | tonic::Code::DeadlineExceeded | tonic::Code::Internal
+                ) {
+                    cb.record_failure();
+                }
+                Err(grpc_err(status))
+            }
+        }
+    }
+
+    /// Create an API key for a machine client, limited to some of the
+    /// caller's permissions. Requires `Authorization: Bearer <token>` header.
+    async fn create_api_key(
+        &self,
+        ctx: &Context<'_>,
+        input: CreateApiKeyInput,
+    ) -> async_graphql::Result<CreatedApiKeyPayload> {
+        let token = bearer_token(ctx)?;
+
+        let channel = auth_channel(ctx)?;
+        let cb = circuit_breaker(ctx)?;
+        check_circuit(&cb)?;
+
+        let mut client = AuthServiceClient::new(channel);
+        let result = client
+            .create_api_key(tonic::Request::new(CreateApiKeyRequest {
+                token,
+                name: input.name,
+                scopes: input.scopes,
+                expires_in_days: input.expires_in_days.unwrap_or_default(),
+            }))
+            .await;
+
+        match result {
+            Ok(resp) => {
+                cb.record_success();
+                let resp = resp.into_inner();
+                let api_key = resp
+                    .api_key
+                    .ok_or_else(|| async_graphql::Error::new("Missing API key in response"))?;
+                Ok(CreatedApiKeyPayload {
+                    key: resp.key,
+                    api_key: ApiKey::from(api_key),
+                })
+            }
+            Err(status) => {
+                if matches!(
+                    status.code(),
+                    tonic::Code::Unavailable | tonic::Code::DeadlineExceeded | tonic::Code::Internal
+                ) {
+                    cb.record_failure();
+                }
+                Err(grpc_err(status))
+            }
+        }
+    }
+
+    /// Revoke an API key; it stops working at once. Own keys only, unless
+    /// the caller has the `users:manage` permission. Requires
+    /// `Authorization: Bearer <token>` header.
+    async fn revoke_api_key(
+        &self,
+        ctx: &Context<'_>,
+        key_id: String,
+    ) -> async_graphql::Result<bool> {
+        let token = bearer_token(ctx)?;
+
+        let channel = auth_channel(ctx)?;
+        let cb = circuit_breaker(ctx)?;
+        check_circuit(&cb)?;
+
+        let mut client = AuthServiceClient::new(channel);
+        let result = client
+            .revoke_api_key(tonic::Request::new(RevokeApiKeyRequest { token, key_id }))
+            .await;
+
+        match result {
+            Ok(_) => {
+                cb.record_success();
+                Ok(true)
+            }
+            Err(status) => {
+                if matches!(
+                    status.code(),
+                    tonic::Code::Unavailable | tonic::Code::DeadlineExceeded | tonic::Code::Internal
+                ) {
+                    cb.record_failure();
+                }
+                Err(grpc_err(status))
+            }
+        }
+    }
+
+    /// Sign a device out; its tokens stop working at once. Requires
+    /// `Authorization: Bearer <token>` header.
+    async fn revoke_session(
+        &self,
+        ctx: &Context<'_>,
+        session_id: String,
+    ) -> async_graphql::Result<bool> {
+        let token = bearer_token(ctx)?;
+
+        let channel = auth_channel(ctx)?;
+        let cb = circuit_breaker(ctx)?;
+        check_circuit(&cb)?;
+
+        let mut client = AuthServiceClient::new(channel);
+        let result = client
+            .revoke_session(tonic::Request::new(RevokeSessionRequest { token, session_id }))
+            .await;
+
+        match result {
+            Ok(_) => {
+                cb.record_success();
+                Ok(true)
+            }
+            Err(status) => {
+                if matches!(
+                    status.code(),
+                    tonic::Code::Unavailable | tonic::Code::DeadlineExceeded | tonic::Code::Internal
+                ) {
+                    cb.record_failure();
+                }
+                Err(grpc_err(status))
+            }
+        }
+    }
+
+    /// Sign out every device except the one making the request; returns
+    /// how many were signed out. Requires `Authorization: Bearer <token>` header.
+    async fn revoke_other_sessions(&self, ctx: &Context<'_>) -> async_graphql::Result<i32> {
+        let token = bearer_token(ctx)?;
+
+        let channel = auth_channel(ctx)?;
+        let cb = circuit_breaker(ctx)?;
+        check_circuit(&cb)?;
+
+        let mut client = AuthServiceClient::new(channel);
+        let result = client
+            .revoke_other_sessions(tonic::Request::new(RevokeOtherSessionsRequest { token }))
+            .await;
+
+        match result {
+            Ok(resp) => {
+                cb.record_success();
+                Ok(i32::try_from(resp.into_inner().revoked_count).unwrap_or(i32::MAX))
+            }
+            Err(status) => {
+                if matches!(
+                    status.code(),
+                    tonic::Code::Unavailable | tonic::Code::DeadlineExceeded | tonic::Code::Internal
+                ) {
+                    cb.record_failure();
+                }
+                Err(grpc_err(status))
+            }
+        }
+    }
+
+    /// Found an organization; the caller becomes its owner. Requires
+    /// `Authorization: Bearer <token>` header.
+    async fn create_organization(
+        &self,
+        ctx: &Context<'_>,
+        name: String,
+    ) -> async_graphql::Result<Organization> {
+        let token = bearer_token(ctx)?;
+
+        let channel = auth_channel(ctx)?;
+        let cb = circuit_breaker(ctx)?;
+        check_circuit(&cb)?;
+
+        let mut request = tonic::Request::new(CreateOrganizationRequest { token, name });
+        forward_client(ctx, &mut request);
+
+        let mut client = OrganizationServiceClient::new(channel);
+        let result = client.create_organization(request).await;
+
+        match result {
+            Ok(resp) => {
+                cb.record_success();
+                Ok(Organization::from(resp.into_inner()))
+            }
+            Err(status) => {
+                if matches!(
+                    status.code(),
+                    tonic::Code::Unavailable | tonic::Code::DeadlineExceeded | tonic::Code::Internal
+                ) {
+                    cb.record_failure();
+                }
+                Err(grpc_err(status))
+            }
+        }
+    }
+
+    /// Email an invitation to join an organization. Requires the `admin` or
+    /// `owner` role in it and `Authorization: Bearer <token>` header.
+    async fn invite_member(
+        &self,
+        ctx: &Context<'_>,
+        input: InviteMemberInput,
+    ) -> async_graphql::Result<InvitationPayload> {
+        let token = bearer_token(ctx)?;
+
+        let channel = auth_channel(ctx)?;
+        let cb = circuit_breaker(ctx)?;
+        check_circuit(&cb)?;
+
+        let mut request = tonic::Request::new(InviteMemberRequest {
+            token,
+            organization_id: input.organization_id,
+            email: input.email,
+            role: input.role,
+        });
+        forward_client(ctx, &mut request);
+
+        let mut client = OrganizationServiceClient::new(channel);
+        let result = client.invite_member(request).await;
+
+        match result {
+            Ok(resp) => {
+                cb.record_success();
+                let resp = resp.into_inner();
+                Ok(InvitationPayload {
+                    invitation_id: resp.invitation_id,
+                    expires_at: resp.expires_at,
+                })
+            }
+            Err(status) => {
+                if matches!(
+                    status.code(),
+                    tonic::Code::Unavailable | tonic::Code::DeadlineExceeded | tonic::Code::Internal
+                ) {
+                    cb.record_failure();
+                }
+                Err(grpc_err(status))
+            }
+        }
+    }
+
+    /// Join an organization with the token from an invitation email sent to
+    /// the current user's address. Requires `Authorization: Bearer <token>` header.
+    async fn accept_invitation(
+        &self,
+        ctx: &Context<'_>,
+        invitation_token: String,
+    ) -> async_graphql::Result<Organization> {
+        let token = bearer_token(ctx)?;
+
+        let channel = auth_channel(ctx)?;
+        let cb = circuit_breaker(ctx)?;
+        check_circuit(&cb)?;
+
+        let mut request = tonic::Request::new(AcceptInvitationRequest {
+            token,
+            invitation_token,
+        });
+        forward_client(ctx, &mut request);
+
+        let mut client = OrganizationServiceClient::new(channel);
+        let result = client.accept_invitation(request).await;
+
+        match result {
+            Ok(resp) => {
+                cb.record_success();
+                Ok(Organization::from(resp.into_inner()))
+            }
+            Err(status) => {
+                if matches!(
+                    status.code(),
+                    tonic::Code::Unavailable | tonic::Code::DeadlineExceeded | tonic::Code::Internal
+                ) {
+                    cb.record_failure();
+                }
+                Err(grpc_err(status))
+            }
+        }
+    }
+
+    /// Re-issue the access token for one of the current user's organizations,
+    /// or omit `organizationId` to return to the personal context. Requires
+    /// `Authorization: Bearer <token>` header.
+    async fn switch_organization(
+        &self,
+        ctx: &Context<'_>,
+        organization_id: Option<String>,
+    ) -> async_graphql::Result<SwitchOrganizationPayload> {
+        let token = bearer_token(ctx)?;
+
+        let channel = auth_channel(ctx)?;
+        let cb = circuit_breaker(ctx)?;
+        check_circuit(&cb)?;
+
+        let mut client = OrganizationServiceClient::new(channel);
+        let result = client
+            .switch_organization(tonic::Request::new(SwitchOrganizationRequest {
+                token,
+                organization_id,
+            }))
+            .await;
+
+        match result {
+            Ok(resp) => {
+                cb.record_success();
+                let resp = resp.into_inner();
+                Ok(SwitchOrganizationPayload {
+                    token: resp.token,
+                    organization_id: resp.organization_id,
+                    role: resp.role,
+                })
+            }
+            Err(status) => {
+                if matches!(
+                    status.code(),
+                    tonic::Code::Unavailable | tonic::Code::DeadlineExceeded | tonic::Code::Internal
+                ) {
+                    cb.record_failure();
+                }
+                Err(grpc_err(status))
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Token wrapper (inserted per-request from Authorization or X-Api-Key header)
+// ============================================================================
+
+/// Bearer token from the HTTP Authorization header, or else the API key
+/// from the `X-Api-Key` header
+pub struct Token(pub String);
+
+/// Address of the client that sent the HTTP request
+pub struct ClientIp(pub IpAddr);
+
+/// `User-Agent` header of the HTTP request, if it sent one
+pub struct ClientUserAgent(pub String);
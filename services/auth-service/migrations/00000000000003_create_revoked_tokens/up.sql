@@ -0,0 +1,10 @@
+-- Create revoked_tokens table (JWT revocation list keyed by jti)
+CREATE TABLE revoked_tokens (
+    jti VARCHAR(64) PRIMARY KEY,
+    user_id UUID NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    revoked_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Create index on expires_at for purging entries of expired tokens
+CREATE INDEX idx_revoked_tokens_expires_at ON revoked_tokens(expires_at);
@@ -0,0 +1,14 @@
+-- Create user_roles table for role-based access control
+CREATE TABLE user_roles (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role VARCHAR(32) NOT NULL,
+    granted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, role)
+);
+
+-- Create index on role for listing users by role
+CREATE INDEX idx_user_roles_role ON user_roles(role);
+
+-- Every existing user starts out as a customer
+INSERT INTO user_roles (user_id, role)
+SELECT id, 'customer' FROM users;
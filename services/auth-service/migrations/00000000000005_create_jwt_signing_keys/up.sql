@@ -0,0 +1,13 @@
+-- Create jwt_signing_keys table (asymmetric JWT key ring)
+CREATE TABLE jwt_signing_keys (
+    kid VARCHAR(64) PRIMARY KEY,
+    algorithm VARCHAR(16) NOT NULL,
+    private_key TEXT NOT NULL,
+    public_key TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    retired_at TIMESTAMPTZ,
+    verify_until TIMESTAMPTZ
+);
+
+-- Create index on verify_until for loading and purging verifiable keys
+CREATE INDEX idx_jwt_signing_keys_verify_until ON jwt_signing_keys(verify_until);
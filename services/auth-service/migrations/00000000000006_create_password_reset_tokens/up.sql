@@ -0,0 +1,15 @@
+-- Create password_reset_tokens table (single-use, hashed reset secrets)
+CREATE TABLE password_reset_tokens (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    token_hash VARCHAR(64) NOT NULL UNIQUE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    used_at TIMESTAMPTZ
+);
+
+-- Create index on user_id for consuming a user's outstanding tokens
+CREATE INDEX idx_password_reset_tokens_user_id ON password_reset_tokens(user_id);
+
+-- Create index on expires_at for purging expired tokens
+CREATE INDEX idx_password_reset_tokens_expires_at ON password_reset_tokens(expires_at);
@@ -0,0 +1,22 @@
+-- Track when a user confirmed ownership of their email address
+ALTER TABLE users ADD COLUMN email_verified_at TIMESTAMPTZ;
+
+-- Accounts created before verification existed are trusted as verified
+UPDATE users SET email_verified_at = created_at;
+
+-- Create email_verification_tokens table (single-use, hashed verification secrets)
+CREATE TABLE email_verification_tokens (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    email VARCHAR(255) NOT NULL,
+    token_hash VARCHAR(64) NOT NULL UNIQUE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    used_at TIMESTAMPTZ
+);
+
+-- Create index on user_id for consuming a user's outstanding tokens
+CREATE INDEX idx_email_verification_tokens_user_id ON email_verification_tokens(user_id);
+
+-- Create index on expires_at for purging expired tokens
+CREATE INDEX idx_email_verification_tokens_expires_at ON email_verification_tokens(expires_at);
@@ -0,0 +1,4 @@
+-- Drop the two-factor authentication tables
+DROP TABLE IF EXISTS mfa_challenges;
+DROP TABLE IF EXISTS mfa_recovery_codes;
+DROP TABLE IF EXISTS totp_credentials;
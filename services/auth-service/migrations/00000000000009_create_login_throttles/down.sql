@@ -0,0 +1,2 @@
+-- Drop the login_throttles table
+DROP TABLE IF EXISTS login_throttles;
@@ -0,0 +1,12 @@
+-- Create login_throttles table (failed login counters per email address and source IP)
+CREATE TABLE login_throttles (
+    scope VARCHAR(16) NOT NULL,
+    subject VARCHAR(255) NOT NULL,
+    failed_attempts INTEGER NOT NULL DEFAULT 0,
+    last_failed_at TIMESTAMPTZ NOT NULL,
+    locked_until TIMESTAMPTZ,
+    PRIMARY KEY (scope, subject)
+);
+
+-- Create index on last_failed_at for purging stale counters
+CREATE INDEX idx_login_throttles_last_failed_at ON login_throttles(last_failed_at);
@@ -0,0 +1,3 @@
+-- Drop the user search indexes
+DROP INDEX IF EXISTS idx_users_email_pattern;
+DROP INDEX IF EXISTS idx_users_created_at_id;
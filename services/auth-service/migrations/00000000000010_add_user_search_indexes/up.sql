@@ -0,0 +1,5 @@
+-- Create index for listing users newest first with keyset pagination
+CREATE INDEX idx_users_created_at_id ON users(created_at DESC, id DESC);
+
+-- Create index for email prefix searches (LIKE 'term%')
+CREATE INDEX idx_users_email_pattern ON users(email varchar_pattern_ops);
@@ -0,0 +1,9 @@
+-- Create oauth_clients table (service identities for the client_credentials grant; only the secret hash is stored)
+CREATE TABLE oauth_clients (
+    client_id UUID PRIMARY KEY,
+    name VARCHAR(100) NOT NULL,
+    secret_hash VARCHAR(64) NOT NULL,
+    scopes TEXT[] NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    revoked_at TIMESTAMPTZ
+);
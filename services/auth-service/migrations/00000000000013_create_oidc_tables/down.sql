@@ -0,0 +1,3 @@
+-- Drop OpenID Connect tables
+DROP TABLE IF EXISTS authorization_codes;
+DROP TABLE IF EXISTS oidc_clients;
@@ -0,0 +1,23 @@
+-- Create oidc_clients table (first-party web apps signing users in with OpenID Connect)
+CREATE TABLE oidc_clients (
+    client_id VARCHAR(64) PRIMARY KEY,
+    name VARCHAR(100) NOT NULL,
+    redirect_uris TEXT[] NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Create authorization_codes table (single-use codes of the authorization-code flow; only the hash is stored)
+CREATE TABLE authorization_codes (
+    code_hash VARCHAR(64) PRIMARY KEY,
+    client_id VARCHAR(64) NOT NULL REFERENCES oidc_clients(client_id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    redirect_uri TEXT NOT NULL,
+    scopes TEXT[] NOT NULL,
+    code_challenge VARCHAR(43) NOT NULL,
+    nonce TEXT,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Create index on expires_at for purging expired codes
+CREATE INDEX idx_authorization_codes_expires_at ON authorization_codes(expires_at);
@@ -0,0 +1,7 @@
+-- Drop federated login tables
+DROP TABLE IF EXISTS federated_login_requests;
+DROP TABLE IF EXISTS user_identities;
+
+-- Users without a password get a hash no password verifies against
+UPDATE users SET hashed_password = '!' WHERE hashed_password IS NULL;
+ALTER TABLE users ALTER COLUMN hashed_password SET NOT NULL;
@@ -0,0 +1,29 @@
+-- Users signing in only through an identity provider have no password
+ALTER TABLE users ALTER COLUMN hashed_password DROP NOT NULL;
+
+-- Create user_identities table (links between users and accounts at external OpenID Connect providers)
+CREATE TABLE user_identities (
+    provider VARCHAR(64) NOT NULL,
+    subject VARCHAR(255) NOT NULL,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    email VARCHAR(255),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_login_at TIMESTAMPTZ,
+    PRIMARY KEY (provider, subject)
+);
+
+-- Create index on user_id for listing and erasing a user's identities
+CREATE INDEX idx_user_identities_user_id ON user_identities(user_id);
+
+-- Create federated_login_requests table (sign-ins pending at an identity provider; only the state hash is stored)
+CREATE TABLE federated_login_requests (
+    state_hash VARCHAR(64) PRIMARY KEY,
+    provider VARCHAR(64) NOT NULL,
+    nonce VARCHAR(128) NOT NULL,
+    code_verifier VARCHAR(128) NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Create index on expires_at for purging expired requests
+CREATE INDEX idx_federated_login_requests_expires_at ON federated_login_requests(expires_at);
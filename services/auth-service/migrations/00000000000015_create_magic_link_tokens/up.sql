@@ -0,0 +1,15 @@
+-- Create magic_link_tokens table (single-use, hashed passwordless login secrets)
+CREATE TABLE magic_link_tokens (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    token_hash VARCHAR(64) NOT NULL UNIQUE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    used_at TIMESTAMPTZ
+);
+
+-- Create index on (user_id, created_at) for rate limiting and consuming a user's outstanding links
+CREATE INDEX idx_magic_link_tokens_user_id_created_at ON magic_link_tokens(user_id, created_at);
+
+-- Create index on expires_at for purging expired links
+CREATE INDEX idx_magic_link_tokens_expires_at ON magic_link_tokens(expires_at);
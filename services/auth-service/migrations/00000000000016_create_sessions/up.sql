@@ -0,0 +1,20 @@
+-- Create sessions table (one row per refresh token family, with the device that logged in)
+CREATE TABLE sessions (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    user_agent VARCHAR(512),
+    ip_address VARCHAR(45),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL,
+    revoked_at TIMESTAMPTZ
+);
+
+-- Create index on (user_id, last_seen_at) for listing a user's sessions
+CREATE INDEX idx_sessions_user_id_last_seen_at ON sessions(user_id, last_seen_at);
+
+-- Backfill sessions for existing refresh token families; their devices are unknown
+INSERT INTO sessions (id, user_id, created_at, last_seen_at, expires_at, revoked_at)
+SELECT family_id, user_id, MIN(created_at), MAX(created_at), MAX(expires_at), MIN(revoked_at)
+FROM refresh_tokens
+GROUP BY family_id, user_id;
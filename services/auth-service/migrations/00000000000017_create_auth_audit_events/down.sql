@@ -0,0 +1,3 @@
+-- Drop auth_audit_events table
+DROP TABLE IF EXISTS auth_audit_events;
+DROP FUNCTION IF EXISTS reject_auth_audit_event_update();
@@ -0,0 +1,31 @@
+-- Create auth_audit_events table (append-only security audit log)
+-- Actors and targets are not foreign keys, so entries outlive the rows they mention
+CREATE TABLE auth_audit_events (
+    id UUID PRIMARY KEY,
+    occurred_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    action VARCHAR(64) NOT NULL,
+    outcome VARCHAR(16) NOT NULL,
+    actor_id UUID,
+    target_id UUID,
+    ip_address VARCHAR(45),
+    user_agent VARCHAR(512),
+    detail VARCHAR(512)
+);
+
+-- Create index on (occurred_at, id) for paging newest first and purging old entries
+CREATE INDEX idx_auth_audit_events_occurred_at_id ON auth_audit_events(occurred_at, id);
+
+-- Create indexes on actor_id and target_id for filtering by user
+CREATE INDEX idx_auth_audit_events_actor_id ON auth_audit_events(actor_id);
+CREATE INDEX idx_auth_audit_events_target_id ON auth_audit_events(target_id);
+
+-- Reject updates; entries are only ever inserted, and deleted by the retention purge
+CREATE FUNCTION reject_auth_audit_event_update() RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'auth_audit_events is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER auth_audit_events_append_only
+    BEFORE UPDATE ON auth_audit_events
+    FOR EACH ROW EXECUTE FUNCTION reject_auth_audit_event_update();
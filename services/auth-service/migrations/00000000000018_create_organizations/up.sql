@@ -0,0 +1,37 @@
+-- Create organizations table (teams of users acting for one event organizer)
+CREATE TABLE organizations (
+    id UUID PRIMARY KEY,
+    name VARCHAR(100) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Create organization_memberships table (per-organization roles of users)
+CREATE TABLE organization_memberships (
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role VARCHAR(32) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (organization_id, user_id)
+);
+
+-- Create index on user_id for listing a user's organizations
+CREATE INDEX idx_organization_memberships_user_id ON organization_memberships(user_id);
+
+-- Create organization_invitations table (single-use, hashed invitation secrets)
+CREATE TABLE organization_invitations (
+    id UUID PRIMARY KEY,
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    email VARCHAR(255) NOT NULL,
+    role VARCHAR(32) NOT NULL,
+    token_hash VARCHAR(64) NOT NULL UNIQUE,
+    invited_by UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    accepted_at TIMESTAMPTZ
+);
+
+-- Create index on organization_id for deleting an organization's invitations
+CREATE INDEX idx_organization_invitations_organization_id ON organization_invitations(organization_id);
+
+-- Create index on expires_at for purging expired invitations
+CREATE INDEX idx_organization_invitations_expires_at ON organization_invitations(expires_at);
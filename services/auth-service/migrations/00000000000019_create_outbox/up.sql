@@ -0,0 +1,19 @@
+-- Create outbox table (domain events waiting to be published to Kafka)
+-- Events are inserted in the same transaction as the change they announce
+-- and relayed in sequence order; aggregate_id is the user the event is about
+CREATE TABLE outbox (
+    sequence BIGSERIAL PRIMARY KEY,
+    id UUID NOT NULL UNIQUE,
+    aggregate_id UUID NOT NULL,
+    event_type VARCHAR(64) NOT NULL,
+    schema_version INTEGER NOT NULL,
+    payload TEXT NOT NULL,
+    occurred_at TIMESTAMPTZ NOT NULL,
+    published_at TIMESTAMPTZ
+);
+
+-- Create partial index for the relay, which only reads unpublished events
+CREATE INDEX idx_outbox_unpublished ON outbox(sequence) WHERE published_at IS NULL;
+
+-- Create index on published_at for purging published events
+CREATE INDEX idx_outbox_published_at ON outbox(published_at);
@@ -0,0 +1,2 @@
+-- Drop the session organization
+ALTER TABLE sessions DROP COLUMN IF EXISTS organization_id;
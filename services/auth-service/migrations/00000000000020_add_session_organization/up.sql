@@ -0,0 +1,3 @@
+-- Remember the organization a session switched to, so refreshed access tokens keep it
+ALTER TABLE sessions
+    ADD COLUMN organization_id UUID REFERENCES organizations(id) ON DELETE SET NULL;
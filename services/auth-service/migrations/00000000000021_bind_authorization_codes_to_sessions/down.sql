@@ -0,0 +1,2 @@
+-- Drop the session of authorization codes
+ALTER TABLE authorization_codes DROP COLUMN IF EXISTS session_id;
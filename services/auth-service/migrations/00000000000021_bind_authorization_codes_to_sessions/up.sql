@@ -0,0 +1,7 @@
+-- Bind authorization codes to the session they were issued from, so the access
+-- token they redeem for ends with it. Outstanding codes expire within minutes
+-- and cannot be bound after the fact.
+DELETE FROM authorization_codes;
+
+ALTER TABLE authorization_codes
+    ADD COLUMN session_id UUID NOT NULL REFERENCES sessions(id) ON DELETE CASCADE;
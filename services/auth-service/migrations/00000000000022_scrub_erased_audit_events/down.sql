@@ -0,0 +1,6 @@
+-- Reject every update of audit entries again
+CREATE OR REPLACE FUNCTION reject_auth_audit_event_update() RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'auth_audit_events is append-only';
+END;
+$$ LANGUAGE plpgsql;
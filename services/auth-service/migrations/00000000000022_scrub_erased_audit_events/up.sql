@@ -0,0 +1,19 @@
+-- Let account erasure blank the client and detail of audit entries about the
+-- user; every other update is still rejected
+CREATE OR REPLACE FUNCTION reject_auth_audit_event_update() RETURNS TRIGGER AS $$
+BEGIN
+    IF NEW.id = OLD.id
+        AND NEW.occurred_at = OLD.occurred_at
+        AND NEW.action = OLD.action
+        AND NEW.outcome = OLD.outcome
+        AND NEW.actor_id IS NOT DISTINCT FROM OLD.actor_id
+        AND NEW.target_id IS NOT DISTINCT FROM OLD.target_id
+        AND NEW.ip_address IS NULL
+        AND NEW.user_agent IS NULL
+        AND NEW.detail IS NULL
+    THEN
+        RETURN NEW;
+    END IF;
+    RAISE EXCEPTION 'auth_audit_events is append-only';
+END;
+$$ LANGUAGE plpgsql;
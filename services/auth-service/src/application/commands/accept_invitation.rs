@@ -0,0 +1,452 @@
+//! Accept invitation use case
+//!
+//! Adds the signed-in user to the organization an invitation was sent for.
+//! The invitation must have been addressed to the user's current email
+//! address, so a forwarded or leaked link does not let anyone else in.
+
+use chrono::Utc;
+use tracing::info;
+
+use crate::domain::audit::{AuditAction, AuditEvent, AuditLog};
+use crate::domain::auth::{OpaqueTokenGenerator, TokenData, TokenService, UserRepository};
+use crate::domain::error::AuthError;
+use crate::domain::organization::{
+    InvitationRepository, Membership, Organization, OrganizationRepository,
+};
+
+/// Input for accepting an invitation
+#[derive(Debug)]
+pub struct AcceptInvitationCommand {
+    /// Access token of the invitee
+    pub token: String,
+    /// Secret from the invitation email
+    pub invitation_token: String,
+}
+
+/// Organization joined
+#[derive(Debug)]
+pub struct AcceptInvitationResult {
+    pub organization: Organization,
+    /// Membership held in the organization; members who were already in it
+    /// keep their role
+    pub membership: Membership,
+}
+
+/// Use case for accepting an invitation
+pub struct AcceptInvitationUseCase<
+    'a,
+    R: ?Sized,
+    O: ?Sized,
+    I: ?Sized,
+    T: ?Sized,
+    G: ?Sized,
+    L: ?Sized,
+> {
+    user_repository: &'a R,
+    organization_repository: &'a O,
+    invitation_repository: &'a I,
+    token_service: &'a T,
+    token_generator: &'a G,
+    audit_log: &'a L,
+}
+
+impl<'a, R, O, I, T, G, L> AcceptInvitationUseCase<'a, R, O, I, T, G, L>
+where
+    R: UserRepository + ?Sized,
+    O: OrganizationRepository + ?Sized,
+    I: InvitationRepository + ?Sized,
+    T: TokenService + ?Sized,
+    G: OpaqueTokenGenerator + ?Sized,
+    L: AuditLog + ?Sized,
+{
+    /// Create a new use case instance
+    pub fn new(
+        user_repository: &'a R,
+        organization_repository: &'a O,
+        invitation_repository: &'a I,
+        token_service: &'a T,
+        token_generator: &'a G,
+        audit_log: &'a L,
+    ) -> Self {
+        Self {
+            user_repository,
+            organization_repository,
+            invitation_repository,
+            token_service,
+            token_generator,
+            audit_log,
+        }
+    }
+
+    /// Execute the acceptance
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidToken` / `AuthError::TokenExpired` if the access token is invalid
+    /// - `AuthError::Forbidden` if the caller is not signed in as themselves,
+    ///   or the invitation was sent to another address
+    /// - `AuthError::AccountInactive` if user account is deactivated
+    /// - `AuthError::InvalidInvitation` if the invitation is unknown, expired
+    ///   or already accepted
+    /// - `AuthError::Internal` on infrastructure failures
+    pub fn execute(
+        &self,
+        command: AcceptInvitationCommand,
+    ) -> Result<AcceptInvitationResult, AuthError> {
+        let token_data = self.token_service.validate_token(&command.token)?;
+
+        let result = self.accept(&token_data, &command.invitation_token);
+        let mut event = AuditEvent::new(AuditAction::InvitationAcceptance)
+            .with_actor(token_data.audit_actor())
+            .with_result(&result);
+        if let Ok(accepted) = &result {
+            event = event.with_detail(accepted.organization.id().to_string());
+        }
+        self.audit_log.record(event);
+        result
+    }
+
+    fn accept(
+        &self,
+        token_data: &TokenData,
+        invitation_token: &str,
+    ) -> Result<AcceptInvitationResult, AuthError> {
+        if !token_data.is_session() {
+            return Err(AuthError::Forbidden);
+        }
+
+        let invitation = self
+            .invitation_repository
+            .find_by_hash(&self.token_generator.hash(invitation_token))?;
+        if invitation.is_accepted() || invitation.is_expired(Utc::now()) {
+            return Err(AuthError::InvalidInvitation);
+        }
+
+        let user = self.user_repository.find_by_id(token_data.user_id)?;
+        if !user.is_active() {
+            return Err(AuthError::AccountInactive);
+        }
+        if user.email().as_str() != invitation.email() {
+            return Err(AuthError::Forbidden);
+        }
+
+        let membership = Membership::new(
+            invitation.organization_id(),
+            token_data.user_id,
+            invitation.role(),
+        );
+        if !self
+            .invitation_repository
+            .accept(&invitation, &membership)?
+        {
+            // Another request accepted it first
+            return Err(AuthError::InvalidInvitation);
+        }
+
+        let membership = self
+            .organization_repository
+            .find_membership(invitation.organization_id(), token_data.user_id)?
+            .unwrap_or(membership);
+        let organization = self
+            .organization_repository
+            .find_by_id(invitation.organization_id())?;
+
+        info!(
+            user_id = %token_data.user_id,
+            organization_id = %organization.id(),
+            "Organization invitation accepted"
+        );
+        Ok(AcceptInvitationResult {
+            organization,
+            membership,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use chrono::{DateTime, Duration};
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::application::commands::test_support::{token_data, MockAuditLog, MockUserRepository};
+    use crate::domain::audit::AuditOutcome;
+    use crate::domain::organization::{Invitation, OrgRole};
+    use crate::domain::user::{Email, HashedPassword, User};
+
+    // Organization store sharing its members with the invitation store
+    struct MockOrganizationRepository<'a> {
+        organization: Organization,
+        members: &'a RefCell<Vec<Membership>>,
+    }
+
+    impl OrganizationRepository for MockOrganizationRepository<'_> {
+        fn create(
+            &self,
+            _organization: &Organization,
+            _owner: &Membership,
+        ) -> Result<(), AuthError> {
+            Err(AuthError::Internal("Not implemented".to_string()))
+        }
+
+        fn find_by_id(&self, id: Uuid) -> Result<Organization, AuthError> {
+            if self.organization.id() == id {
+                Ok(self.organization.clone())
+            } else {
+                Err(AuthError::OrganizationNotFound)
+            }
+        }
+
+        fn find_membership(
+            &self,
+            organization_id: Uuid,
+            user_id: Uuid,
+        ) -> Result<Option<Membership>, AuthError> {
+            Ok(self
+                .members
+                .borrow()
+                .iter()
+                .find(|m| m.organization_id() == organization_id && m.user_id() == user_id)
+                .cloned())
+        }
+
+        fn list_for_user(
+            &self,
+            _user_id: Uuid,
+        ) -> Result<Vec<(Organization, Membership)>, AuthError> {
+            Ok(Vec::new())
+        }
+    }
+
+    // Invitation store holding one invitation
+    struct MockInvitationRepository<'a> {
+        invitation: RefCell<Invitation>,
+        members: &'a RefCell<Vec<Membership>>,
+    }
+
+    impl InvitationRepository for MockInvitationRepository<'_> {
+        fn create(&self, _invitation: &Invitation) -> Result<(), AuthError> {
+            Err(AuthError::Internal("Not implemented".to_string()))
+        }
+
+        fn find_by_hash(&self, token_hash: &str) -> Result<Invitation, AuthError> {
+            let invitation = self.invitation.borrow();
+            if invitation.token_hash() == token_hash {
+                Ok(invitation.clone())
+            } else {
+                Err(AuthError::InvalidInvitation)
+            }
+        }
+
+        fn accept(
+            &self,
+            invitation: &Invitation,
+            membership: &Membership,
+        ) -> Result<bool, AuthError> {
+            let mut stored = self.invitation.borrow_mut();
+            if stored.is_accepted() {
+                return Ok(false);
+            }
+            *stored = Invitation::from_persistence(
+                invitation.id(),
+                invitation.organization_id(),
+                invitation.email().to_string(),
+                invitation.role(),
+                invitation.token_hash().to_string(),
+                invitation.invited_by(),
+                invitation.expires_at(),
+                invitation.created_at(),
+                Some(Utc::now()),
+            );
+            let mut members = self.members.borrow_mut();
+            if !members.iter().any(|m| {
+                m.organization_id() == membership.organization_id()
+                    && m.user_id() == membership.user_id()
+            }) {
+                members.push(membership.clone());
+            }
+            Ok(true)
+        }
+
+        fn purge_expired(&self, _cutoff: DateTime<Utc>) -> Result<usize, AuthError> {
+            Ok(0)
+        }
+    }
+
+    // Token service accepting a session token for one user
+    struct MockTokenService {
+        user_id: Uuid,
+    }
+
+    impl TokenService for MockTokenService {
+        fn create_token(
+            &self,
+            _user: &User,
+            _session_id: Option<Uuid>,
+        ) -> Result<String, AuthError> {
+            Ok("token".to_string())
+        }
+
+        fn validate_token(&self, token: &str) -> Result<TokenData, AuthError> {
+            if token != "session" {
+                return Err(AuthError::InvalidToken);
+            }
+            Ok(token_data(self.user_id))
+        }
+
+        fn revoke_token(&self, _token: &TokenData) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn is_revoked(&self, _token: &TokenData) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+    }
+
+    // Mock generator hashing by prefixing
+    struct MockTokenGenerator;
+
+    impl OpaqueTokenGenerator for MockTokenGenerator {
+        fn generate(&self) -> String {
+            "secret".to_string()
+        }
+
+        fn hash(&self, token: &str) -> String {
+            format!("hashed:{}", token)
+        }
+    }
+
+    fn invitee() -> User {
+        let email = Email::new("invitee@example.com").unwrap();
+        User::new(email, HashedPassword::from_hash("hashed".to_string()), None)
+    }
+
+    fn invitation(organization: &Organization, email: &str, ttl: Duration) -> Invitation {
+        Invitation::issue(
+            organization.id(),
+            email.to_string(),
+            OrgRole::Admin,
+            "hashed:secret".to_string(),
+            Uuid::new_v4(),
+            ttl,
+        )
+    }
+
+    fn accept(
+        user: User,
+        invitation: Invitation,
+        organization: Organization,
+        members: &RefCell<Vec<Membership>>,
+        audit_log: &MockAuditLog,
+    ) -> Result<AcceptInvitationResult, AuthError> {
+        let user_id = user.id().as_uuid();
+        let users = MockUserRepository::new(user);
+        let organizations = MockOrganizationRepository {
+            organization,
+            members,
+        };
+        let invitations = MockInvitationRepository {
+            invitation: RefCell::new(invitation),
+            members,
+        };
+        let tokens = MockTokenService { user_id };
+        let use_case = AcceptInvitationUseCase::new(
+            &users,
+            &organizations,
+            &invitations,
+            &tokens,
+            &MockTokenGenerator,
+            audit_log,
+        );
+        let command = || AcceptInvitationCommand {
+            token: "session".to_string(),
+            invitation_token: "secret".to_string(),
+        };
+
+        let result = use_case.execute(command());
+        // Invitations work once
+        if result.is_ok() {
+            assert!(matches!(
+                use_case.execute(command()),
+                Err(AuthError::InvalidInvitation)
+            ));
+        }
+        result
+    }
+
+    #[test]
+    fn test_invitee_joins_with_invited_role() {
+        let organization = Organization::new("Riverside Concerts".to_string());
+        let user = invitee();
+        let user_id = user.id().as_uuid();
+        let members = RefCell::new(Vec::new());
+        let audit_log = MockAuditLog::default();
+
+        let result = accept(
+            user,
+            invitation(&organization, "invitee@example.com", Duration::days(7)),
+            organization.clone(),
+            &members,
+            &audit_log,
+        )
+        .unwrap();
+
+        assert_eq!(result.organization.id(), organization.id());
+        assert_eq!(result.membership.role(), OrgRole::Admin);
+        assert_eq!(members.borrow().len(), 1);
+        assert_eq!(members.borrow()[0].user_id(), user_id);
+
+        let events = audit_log.events.lock().unwrap();
+        assert_eq!(events[0].action(), AuditAction::InvitationAcceptance);
+        assert_eq!(events[0].outcome(), AuditOutcome::Success);
+    }
+
+    #[test]
+    fn test_existing_member_keeps_role() {
+        let organization = Organization::new("Riverside Concerts".to_string());
+        let user = invitee();
+        let members = RefCell::new(vec![Membership::new(
+            organization.id(),
+            user.id().as_uuid(),
+            OrgRole::Owner,
+        )]);
+
+        let result = accept(
+            user,
+            invitation(&organization, "invitee@example.com", Duration::days(7)),
+            organization,
+            &members,
+            &MockAuditLog::default(),
+        )
+        .unwrap();
+
+        assert_eq!(result.membership.role(), OrgRole::Owner);
+        assert_eq!(members.borrow().len(), 1);
+    }
+
+    #[test]
+    fn test_other_address_and_expired_invitation_refused() {
+        let organization = Organization::new("Riverside Concerts".to_string());
+        let members = RefCell::new(Vec::new());
+
+        let other_address = accept(
+            invitee(),
+            invitation(&organization, "someone@example.com", Duration::days(7)),
+            organization.clone(),
+            &members,
+            &MockAuditLog::default(),
+        );
+        let expired = accept(
+            invitee(),
+            invitation(&organization, "invitee@example.com", Duration::seconds(-1)),
+            organization,
+            &members,
+            &MockAuditLog::default(),
+        );
+
+        assert!(matches!(other_address, Err(AuthError::Forbidden)));
+        assert!(matches!(expired, Err(AuthError::InvalidInvitation)));
+        assert!(members.borrow().is_empty());
+    }
+}
@@ -0,0 +1,202 @@
+//! Assign role use case
+//!
+//! Grants a role to a user. Only callers whose token carries the
+//! `roles:manage` permission may do so.
+
+use uuid::Uuid;
+
+use crate::domain::audit::{AuditAction, AuditEvent, AuditLog};
+use crate::domain::auth::{TokenData, TokenService, UserRepository};
+use crate::domain::error::AuthError;
+use crate::domain::role::{authorize, Permission, Role};
+
+/// Input for assigning a role
+#[derive(Debug)]
+pub struct AssignRoleCommand {
+    /// Access token of the acting administrator
+    pub token: String,
+    pub user_id: Uuid,
+    pub role: String,
+}
+
+/// Output after a successful assignment
+#[derive(Debug)]
+pub struct AssignRoleResult {
+    pub user_id: Uuid,
+    pub roles: Vec<Role>,
+}
+
+/// Use case for assigning a role
+pub struct AssignRoleUseCase<'a, R: ?Sized, T: ?Sized, L: ?Sized> {
+    user_repository: &'a R,
+    token_service: &'a T,
+    audit_log: &'a L,
+}
+
+impl<'a, R, T, L> AssignRoleUseCase<'a, R, T, L>
+where
+    R: UserRepository + ?Sized,
+    T: TokenService + ?Sized,
+    L: AuditLog + ?Sized,
+{
+    /// Create a new use case instance
+    pub fn new(user_repository: &'a R, token_service: &'a T, audit_log: &'a L) -> Self {
+        Self {
+            user_repository,
+            token_service,
+            audit_log,
+        }
+    }
+
+    /// Execute the assignment
+    ///
+    /// Assigning a role the user already holds is a no-op.
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidToken` / `AuthError::TokenExpired` if the token is invalid
+    /// - `AuthError::Forbidden` if the caller may not manage roles
+    /// - `AuthError::InvalidRole` if the role is unknown
+    /// - `AuthError::UserNotFound` if the target user does not exist
+    /// - `AuthError::Internal` on infrastructure failures
+    pub fn execute(&self, command: AssignRoleCommand) -> Result<AssignRoleResult, AuthError> {
+        let actor = self.token_service.validate_token(&command.token)?;
+        let user_id = command.user_id;
+        let role = command.role.clone();
+
+        let result = self.assign(&actor, command);
+        self.audit_log.record(
+            AuditEvent::new(AuditAction::RoleAssignment)
+                .with_actor(actor.audit_actor())
+                .with_target(user_id)
+                .with_detail(role)
+                .with_result(&result),
+        );
+        result
+    }
+
+    fn assign(
+        &self,
+        actor: &TokenData,
+        command: AssignRoleCommand,
+    ) -> Result<AssignRoleResult, AuthError> {
+        authorize(actor, Permission::ManageRoles)?;
+
+        let role: Role = command.role.parse()?;
+        let mut user = self.user_repository.find_by_id(command.user_id)?;
+
+        if user.assign_role(role) {
+            user = self.user_repository.update(&user)?;
+        }
+
+        Ok(AssignRoleResult {
+            user_id: user.id().as_uuid(),
+            roles: user.roles().to_vec(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::commands::test_support::{token_data, MockAuditLog, MockUserRepository};
+    use crate::domain::audit::AuditOutcome;
+    use crate::domain::user::{Email, HashedPassword, User};
+
+    // Token service mapping "admin" and "customer" tokens to roles
+    struct MockTokenService;
+
+    impl TokenService for MockTokenService {
+        fn create_token(
+            &self,
+            _user: &User,
+            _session_id: Option<Uuid>,
+        ) -> Result<String, AuthError> {
+            Ok("token".to_string())
+        }
+
+        fn validate_token(&self, token: &str) -> Result<TokenData, AuthError> {
+            let roles = match token {
+                "admin" => vec![Role::Admin],
+                "customer" => vec![Role::Customer],
+                _ => return Err(AuthError::InvalidToken),
+            };
+            Ok(TokenData {
+                roles,
+                ..token_data(Uuid::new_v4())
+            })
+        }
+
+        fn revoke_token(&self, _token: &TokenData) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn is_revoked(&self, _token: &TokenData) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+    }
+
+    fn create_repo() -> MockUserRepository {
+        let email = Email::new("test@example.com").unwrap();
+        let password = HashedPassword::from_hash("hashed".to_string());
+        MockUserRepository::new(User::new(email, password, None))
+    }
+
+    fn assign(
+        repo: &MockUserRepository,
+        audit_log: &MockAuditLog,
+        token: &str,
+        role: &str,
+    ) -> Result<AssignRoleResult, AuthError> {
+        let user_id = repo.user_id();
+        AssignRoleUseCase::new(repo, &MockTokenService, audit_log).execute(AssignRoleCommand {
+            token: token.to_string(),
+            user_id,
+            role: role.to_string(),
+        })
+    }
+
+    #[test]
+    fn test_admin_assigns_role() {
+        let repo = create_repo();
+        let audit_log = MockAuditLog::default();
+
+        let result = assign(&repo, &audit_log, "admin", "organizer").unwrap();
+
+        assert_eq!(result.roles, vec![Role::Customer, Role::Organizer]);
+        assert!(repo.user.lock().unwrap().has_role(Role::Organizer));
+        assert_eq!(audit_log.events.lock().unwrap()[0].detail(), Some("organizer"));
+
+        // Assigning again does not write
+        assign(&repo, &audit_log, "admin", "organizer").unwrap();
+        assert_eq!(repo.updates(), 1);
+    }
+
+    #[test]
+    fn test_non_admin_forbidden() {
+        let repo = create_repo();
+        let audit_log = MockAuditLog::default();
+
+        let result = assign(&repo, &audit_log, "customer", "admin");
+
+        assert!(matches!(result, Err(AuthError::Forbidden)));
+        assert!(!repo.user.lock().unwrap().has_role(Role::Admin));
+
+        let events = audit_log.events.lock().unwrap();
+        assert_eq!(events[0].action(), AuditAction::RoleAssignment);
+        assert_eq!(events[0].outcome(), AuditOutcome::Failure);
+        assert_eq!(
+            events[0].target_id(),
+            Some(repo.user_id())
+        );
+    }
+
+    #[test]
+    fn test_unknown_role() {
+        let repo = create_repo();
+        let audit_log = MockAuditLog::default();
+
+        let result = assign(&repo, &audit_log, "admin", "superuser");
+
+        assert!(matches!(result, Err(AuthError::InvalidRole)));
+    }
+}
@@ -0,0 +1,420 @@
+//! Authorize use case
+//!
+//! First step of the OpenID Connect authorization-code flow. A web app sends
+//! the user here; once signed in, the user is sent back to the app with a
+//! short-lived code that the app exchanges for tokens. Clients are
+//! first-party, so no consent is asked for. Codes are bound to the session
+//! that authorized them, so logging out also ends the app's access.
+
+use chrono::Duration;
+use tracing::info;
+
+use crate::domain::auth::{OpaqueTokenGenerator, TokenService, UserRepository};
+use crate::domain::error::AuthError;
+use crate::domain::oidc::{
+    is_valid_pkce_challenge, parse_oidc_scope, AuthorizationCode, AuthorizationCodeRepository,
+    OidcClientRepository,
+};
+
+/// Parameters of an authorization request
+#[derive(Debug, Clone)]
+pub struct AuthorizationRequest {
+    /// Must be `code`
+    pub response_type: String,
+    pub client_id: String,
+    /// Must match a URI registered for the client exactly
+    pub redirect_uri: String,
+    /// Space-delimited scopes; must include `openid`
+    pub scope: String,
+    /// Opaque value returned to the client with the code
+    pub state: Option<String>,
+    /// S256 PKCE challenge
+    pub code_challenge: String,
+    /// Must be `S256`
+    pub code_challenge_method: String,
+    /// Value to echo in the ID token
+    pub nonce: Option<String>,
+}
+
+/// Input for authorizing a client on behalf of the signed-in user
+#[derive(Debug)]
+pub struct AuthorizeCommand {
+    /// Access token of the signed-in user
+    pub token: String,
+    pub request: AuthorizationRequest,
+}
+
+/// Code to send back to the client
+#[derive(Debug)]
+pub struct AuthorizationGrant {
+    /// The authorization code, shown to the client only this once
+    pub code: String,
+    pub redirect_uri: String,
+    pub state: Option<String>,
+}
+
+/// Use case for authorizing a client
+pub struct AuthorizeUseCase<'a, C: ?Sized, A: ?Sized, U: ?Sized, T: ?Sized, G: ?Sized> {
+    client_repository: &'a C,
+    code_repository: &'a A,
+    user_repository: &'a U,
+    token_service: &'a T,
+    token_generator: &'a G,
+    code_ttl: Duration,
+}
+
+impl<'a, C, A, U, T, G> AuthorizeUseCase<'a, C, A, U, T, G>
+where
+    C: OidcClientRepository + ?Sized,
+    A: AuthorizationCodeRepository + ?Sized,
+    U: UserRepository + ?Sized,
+    T: TokenService + ?Sized,
+    G: OpaqueTokenGenerator + ?Sized,
+{
+    /// Create a new use case instance
+    pub fn new(
+        client_repository: &'a C,
+        code_repository: &'a A,
+        user_repository: &'a U,
+        token_service: &'a T,
+        token_generator: &'a G,
+        code_ttl: Duration,
+    ) -> Self {
+        Self {
+            client_repository,
+            code_repository,
+            user_repository,
+            token_service,
+            token_generator,
+            code_ttl,
+        }
+    }
+
+    /// Check an authorization request before the user is asked to sign in
+    ///
+    /// Returns the scopes that would be granted.
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidClient` if the client is unknown
+    /// - `AuthError::InvalidRequest` if the redirect URI is not registered,
+    ///   or the response type or PKCE parameters are missing or unsupported
+    /// - `AuthError::InvalidScope` if `openid` was not requested
+    /// - `AuthError::Internal` on infrastructure failures
+    pub fn validate(&self, request: &AuthorizationRequest) -> Result<Vec<String>, AuthError> {
+        let client = self.client_repository.find_by_id(&request.client_id)?;
+        if !client.allows_redirect_uri(&request.redirect_uri) {
+            return Err(AuthError::InvalidRequest(
+                "redirect_uri is not registered for the client".to_string(),
+            ));
+        }
+        if request.response_type != "code" {
+            return Err(AuthError::InvalidRequest(
+                "response_type must be \"code\"".to_string(),
+            ));
+        }
+        // Public clients have no secret, so PKCE is what binds the code to the app
+        if request.code_challenge_method != "S256" {
+            return Err(AuthError::InvalidRequest(
+                "code_challenge_method must be \"S256\"".to_string(),
+            ));
+        }
+        if !is_valid_pkce_challenge(&request.code_challenge) {
+            return Err(AuthError::InvalidRequest(
+                "code_challenge is missing or malformed".to_string(),
+            ));
+        }
+        parse_oidc_scope(&request.scope)
+    }
+
+    /// Issue a code for the signed-in user
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidToken` / `AuthError::TokenExpired` if the token is invalid
+    /// - `AuthError::Forbidden` if the caller did not sign in with a session
+    ///   (API keys, services)
+    /// - `AuthError::AccountInactive` if user account is deactivated
+    /// - Any error of [`Self::validate`]
+    pub fn execute(&self, command: AuthorizeCommand) -> Result<AuthorizationGrant, AuthError> {
+        let token_data = self.token_service.validate_token(&command.token)?;
+        let session_id = match token_data.session_id {
+            Some(session_id) if token_data.is_session() => session_id,
+            _ => return Err(AuthError::Forbidden),
+        };
+        let request = command.request;
+        let scopes = self.validate(&request)?;
+
+        let user = self.user_repository.find_by_id(token_data.user_id)?;
+        if !user.is_active() {
+            return Err(AuthError::AccountInactive);
+        }
+
+        let code = self.token_generator.generate();
+        self.code_repository.create(&AuthorizationCode::issue(
+            self.token_generator.hash(&code),
+            request.client_id.clone(),
+            token_data.user_id,
+            session_id,
+            request.redirect_uri.clone(),
+            scopes,
+            request.code_challenge,
+            request.nonce,
+            self.code_ttl,
+        ))?;
+
+        info!(
+            user_id = %token_data.user_id,
+            client_id = %request.client_id,
+            "Authorization code issued"
+        );
+        Ok(AuthorizationGrant {
+            code,
+            redirect_uri: request.redirect_uri,
+            state: request.state,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use chrono::{DateTime, Utc};
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::application::commands::test_support::{token_data, MockUserRepository};
+    use crate::domain::auth::TokenData;
+    use crate::domain::oidc::{pkce_challenge, OidcClient};
+    use crate::domain::role::{Permission, Role};
+    use crate::domain::user::{Email, HashedPassword, User};
+
+    const VERIFIER: &str = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+
+    // Mock repository holding a single client
+    struct MockOidcClientRepository {
+        client: OidcClient,
+    }
+
+    impl OidcClientRepository for MockOidcClientRepository {
+        fn save(&self, client: &OidcClient) -> Result<OidcClient, AuthError> {
+            Ok(client.clone())
+        }
+
+        fn find_by_id(&self, client_id: &str) -> Result<OidcClient, AuthError> {
+            if self.client.client_id() == client_id {
+                Ok(self.client.clone())
+            } else {
+                Err(AuthError::InvalidClient)
+            }
+        }
+    }
+
+    // Code store recording issued codes
+    #[derive(Default)]
+    struct MockAuthorizationCodeRepository {
+        codes: RefCell<Vec<AuthorizationCode>>,
+    }
+
+    impl AuthorizationCodeRepository for MockAuthorizationCodeRepository {
+        fn create(&self, code: &AuthorizationCode) -> Result<(), AuthError> {
+            self.codes.borrow_mut().push(code.clone());
+            Ok(())
+        }
+
+        fn take(&self, _code_hash: &str) -> Result<Option<AuthorizationCode>, AuthError> {
+            Ok(None)
+        }
+
+        fn purge_expired(&self, _now: DateTime<Utc>) -> Result<usize, AuthError> {
+            Ok(0)
+        }
+    }
+
+    // Token service accepting "session", "sessionless" and "key" tokens for the stored user
+    struct MockTokenService {
+        user_id: Uuid,
+        session_id: Uuid,
+    }
+
+    impl TokenService for MockTokenService {
+        fn create_token(
+            &self,
+            _user: &User,
+            _session_id: Option<Uuid>,
+        ) -> Result<String, AuthError> {
+            Ok("token".to_string())
+        }
+
+        fn validate_token(&self, token: &str) -> Result<TokenData, AuthError> {
+            let (session_id, scopes) = match token {
+                "session" => (Some(self.session_id), None),
+                "sessionless" => (None, None),
+                "key" => (None, Some(vec![Permission::PurchaseTickets])),
+                _ => return Err(AuthError::InvalidToken),
+            };
+            Ok(TokenData {
+                session_id,
+                roles: vec![Role::Customer],
+                scopes,
+                ..token_data(self.user_id)
+            })
+        }
+
+        fn revoke_token(&self, _token: &TokenData) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn is_revoked(&self, _token: &TokenData) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+    }
+
+    // Generator with a fixed code and a reversible "hash"
+    struct MockTokenGenerator;
+
+    impl OpaqueTokenGenerator for MockTokenGenerator {
+        fn generate(&self) -> String {
+            "code".to_string()
+        }
+
+        fn hash(&self, token: &str) -> String {
+            format!("hashed:{}", token)
+        }
+    }
+
+    struct Fixture {
+        clients: MockOidcClientRepository,
+        codes: MockAuthorizationCodeRepository,
+        users: MockUserRepository,
+        tokens: MockTokenService,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            let email = Email::new("user@example.com").unwrap();
+            let user = User::new(email, HashedPassword::from_hash("hashed".to_string()), None);
+            Self {
+                clients: MockOidcClientRepository {
+                    client: OidcClient::register(
+                        "web".to_string(),
+                        "Web app".to_string(),
+                        vec!["https://app.example.com/callback".to_string()],
+                    ),
+                },
+                codes: MockAuthorizationCodeRepository::default(),
+                tokens: MockTokenService {
+                    user_id: user.id().as_uuid(),
+                    session_id: Uuid::new_v4(),
+                },
+                users: MockUserRepository::new(user),
+            }
+        }
+
+        fn use_case(
+            &self,
+        ) -> AuthorizeUseCase<
+            '_,
+            MockOidcClientRepository,
+            MockAuthorizationCodeRepository,
+            MockUserRepository,
+            MockTokenService,
+            MockTokenGenerator,
+        > {
+            AuthorizeUseCase::new(
+                &self.clients,
+                &self.codes,
+                &self.users,
+                &self.tokens,
+                &MockTokenGenerator,
+                Duration::seconds(60),
+            )
+        }
+    }
+
+    fn request() -> AuthorizationRequest {
+        AuthorizationRequest {
+            response_type: "code".to_string(),
+            client_id: "web".to_string(),
+            redirect_uri: "https://app.example.com/callback".to_string(),
+            scope: "openid email".to_string(),
+            state: Some("xyz".to_string()),
+            code_challenge: pkce_challenge(VERIFIER),
+            code_challenge_method: "S256".to_string(),
+            nonce: Some("nonce".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_code_is_bound_to_request() {
+        let fixture = Fixture::new();
+
+        let grant = fixture
+            .use_case()
+            .execute(AuthorizeCommand {
+                token: "session".to_string(),
+                request: request(),
+            })
+            .unwrap();
+
+        assert_eq!(grant.code, "code");
+        assert_eq!(grant.state.as_deref(), Some("xyz"));
+        let codes = fixture.codes.codes.borrow();
+        assert_eq!(codes.len(), 1);
+        assert_eq!(codes[0].code_hash(), "hashed:code");
+        assert_eq!(codes[0].user_id(), fixture.users.user_id());
+        assert_eq!(codes[0].session_id(), fixture.tokens.session_id);
+        assert_eq!(codes[0].scopes(), ["openid", "email"]);
+        assert_eq!(codes[0].nonce(), Some("nonce"));
+        assert!(codes[0]
+            .redeem("web", &grant.redirect_uri, VERIFIER, Utc::now())
+            .is_ok());
+    }
+
+    #[test]
+    fn test_malformed_requests_rejected() {
+        let fixture = Fixture::new();
+        let use_case = fixture.use_case();
+
+        let mut unknown_client = request();
+        unknown_client.client_id = "other".to_string();
+        assert_eq!(
+            use_case.validate(&unknown_client),
+            Err(AuthError::InvalidClient)
+        );
+
+        let mut without_openid = request();
+        without_openid.scope = "email".to_string();
+        assert_eq!(
+            use_case.validate(&without_openid),
+            Err(AuthError::InvalidScope)
+        );
+
+        let mut unregistered_uri = request();
+        unregistered_uri.redirect_uri = "https://evil.example.com/callback".to_string();
+        let mut plain_pkce = request();
+        plain_pkce.code_challenge_method = "plain".to_string();
+        let mut implicit = request();
+        implicit.response_type = "token".to_string();
+        for request in [unregistered_uri, plain_pkce, implicit] {
+            assert!(matches!(
+                use_case.validate(&request),
+                Err(AuthError::InvalidRequest(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_only_sessions_can_authorize() {
+        let fixture = Fixture::new();
+
+        for token in ["key", "sessionless"] {
+            let result = fixture.use_case().execute(AuthorizeCommand {
+                token: token.to_string(),
+                request: request(),
+            });
+
+            assert!(matches!(result, Err(AuthError::Forbidden)));
+        }
+        assert!(fixture.codes.codes.borrow().is_empty());
+    }
+}
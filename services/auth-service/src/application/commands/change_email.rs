@@ -0,0 +1,518 @@
+//! Change email use case
+//!
+//! Moves a signed-in user to a new email address. The current password must
+//! be presented (or, without one, a recent login), the new address must not
+//! belong to another account, and it starts out unverified: a verification
+//! link goes to the new address and a notice to the old one. Verification
+//! links sent to the old address stop working.
+
+use chrono::Duration;
+use tracing::info;
+
+use super::change_password::reauthenticate;
+use super::send_email_verification::send_verification_email;
+use crate::domain::audit::{AuditAction, AuditEvent, AuditLog};
+use crate::domain::auth::{
+    OpaqueTokenGenerator, PasswordHasher, TokenData, TokenService, UserRepository,
+};
+use crate::domain::email_verification::EmailVerificationTokenRepository;
+use crate::domain::error::AuthError;
+use crate::domain::lockout::{LockoutPolicy, LoginThrottleRepository};
+use crate::domain::mailer::{EmailMessage, Mailer};
+use crate::domain::session::SessionRepository;
+use crate::domain::user::{Email, User};
+
+/// Input for changing an email address
+#[derive(Debug)]
+pub struct ChangeEmailCommand {
+    /// Access token of the user
+    pub token: String,
+    /// Ignored for users without a password
+    pub current_password: String,
+    pub new_email: String,
+}
+
+/// Use case for changing an email address
+pub struct ChangeEmailUseCase<
+    'a,
+    R: ?Sized,
+    H: ?Sized,
+    T: ?Sized,
+    V: ?Sized,
+    G: ?Sized,
+    M: ?Sized,
+    S: ?Sized,
+    L: ?Sized,
+    A: ?Sized,
+> {
+    user_repository: &'a R,
+    password_hasher: &'a H,
+    token_service: &'a T,
+    verification_token_repository: &'a V,
+    token_generator: &'a G,
+    mailer: &'a M,
+    session_repository: &'a S,
+    throttle_repository: &'a L,
+    lockout_policy: LockoutPolicy,
+    verification_token_ttl: Duration,
+    verification_url: &'a str,
+    audit_log: &'a A,
+}
+
+impl<'a, R, H, T, V, G, M, S, L, A> ChangeEmailUseCase<'a, R, H, T, V, G, M, S, L, A>
+where
+    R: UserRepository + ?Sized,
+    H: PasswordHasher + ?Sized,
+    T: TokenService + ?Sized,
+    V: EmailVerificationTokenRepository + ?Sized,
+    G: OpaqueTokenGenerator + ?Sized,
+    M: Mailer + ?Sized,
+    S: SessionRepository + ?Sized,
+    L: LoginThrottleRepository + ?Sized,
+    A: AuditLog + ?Sized,
+{
+    /// Create a new use case instance
+    ///
+    /// `verification_url` is the page that completes email verification;
+    /// the secret is appended as a `token` query parameter.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        user_repository: &'a R,
+        password_hasher: &'a H,
+        token_service: &'a T,
+        verification_token_repository: &'a V,
+        token_generator: &'a G,
+        mailer: &'a M,
+        session_repository: &'a S,
+        throttle_repository: &'a L,
+        lockout_policy: LockoutPolicy,
+        verification_token_ttl: Duration,
+        verification_url: &'a str,
+        audit_log: &'a A,
+    ) -> Self {
+        Self {
+            user_repository,
+            password_hasher,
+            token_service,
+            verification_token_repository,
+            token_generator,
+            mailer,
+            session_repository,
+            throttle_repository,
+            lockout_policy,
+            verification_token_ttl,
+            verification_url,
+            audit_log,
+        }
+    }
+
+    /// Execute the email change, returning the updated user
+    ///
+    /// Changing to the current address is a no-op.
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidToken` / `AuthError::TokenExpired` if the token is invalid
+    /// - `AuthError::Forbidden` for impersonation tokens
+    /// - `AuthError::AccountInactive` if user account is deactivated
+    /// - `AuthError::AccountLocked` after too many failed attempts
+    /// - `AuthError::InvalidCredentials` if the current password is wrong
+    /// - `AuthError::RecentLoginRequired` if the user has no password and
+    ///   signed in too long ago
+    /// - `AuthError::InvalidEmail` if the new address is malformed
+    /// - `AuthError::UserAlreadyExists` if another account uses the new address
+    /// - `AuthError::Internal` on infrastructure failures
+    pub fn execute(&self, command: ChangeEmailCommand) -> Result<User, AuthError> {
+        let token_data = self.token_service.validate_token(&command.token)?;
+
+        let result = self.change(&token_data, command);
+        self.audit_log.record(
+            AuditEvent::new(AuditAction::EmailChange)
+                .with_actor(token_data.audit_actor())
+                .with_target(token_data.user_id)
+                .with_result(&result),
+        );
+        result
+    }
+
+    fn change(
+        &self,
+        token_data: &TokenData,
+        command: ChangeEmailCommand,
+    ) -> Result<User, AuthError> {
+        // Acting as a user does not extend to their credentials
+        if token_data.is_impersonated() {
+            return Err(AuthError::Forbidden);
+        }
+        let mut user = self.user_repository.find_by_id(token_data.user_id)?;
+
+        reauthenticate(
+            &user,
+            token_data,
+            Some(command.current_password.as_str()),
+            self.password_hasher,
+            self.session_repository,
+            self.throttle_repository,
+            &self.lockout_policy,
+        )?;
+
+        let new_email = Email::new(&command.new_email)?;
+        let old_email = user.email().clone();
+        if !user.change_email(new_email) {
+            return Ok(user);
+        }
+
+        if self
+            .user_repository
+            .exists_by_email(user.email().as_str())?
+        {
+            return Err(AuthError::UserAlreadyExists);
+        }
+
+        let user = self.user_repository.update(&user)?;
+
+        send_verification_email(
+            &user,
+            self.verification_token_repository,
+            self.token_generator,
+            self.mailer,
+            self.verification_token_ttl,
+            self.verification_url,
+        )?;
+
+        self.mailer.send(&EmailMessage {
+            to: old_email.as_str().to_string(),
+            subject: "Your email address was changed".to_string(),
+            body: format!(
+                "The email address of your account was changed to {}.\n\n\
+                 If you did not make this change, please contact support immediately.\n",
+                user.email().as_str()
+            ),
+        })?;
+
+        info!(user_id = %token_data.user_id, "Email address changed");
+        Ok(user)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use chrono::{DateTime, Utc};
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::application::commands::test_support::{token_data, MockAuditLog, MockPasswordHasher};
+    use crate::domain::email_verification::EmailVerificationToken;
+    use crate::domain::lockout::{LoginThrottle, ThrottleKey};
+    use crate::domain::session::Session;
+    use crate::domain::user::HashedPassword;
+
+    // Mock repository holding one user and a list of taken addresses
+    struct MockUserRepository {
+        user: RefCell<User>,
+        taken_emails: Vec<String>,
+    }
+
+    impl UserRepository for MockUserRepository {
+        fn find_by_id(&self, id: Uuid) -> Result<User, AuthError> {
+            let user = self.user.borrow();
+            if user.id().as_uuid() == id {
+                Ok(user.clone())
+            } else {
+                Err(AuthError::UserNotFound)
+            }
+        }
+
+        fn find_by_email(&self, _email: &str) -> Result<User, AuthError> {
+            Err(AuthError::UserNotFound)
+        }
+
+        fn exists_by_email(&self, email: &str) -> Result<bool, AuthError> {
+            Ok(self.taken_emails.iter().any(|e| e == email))
+        }
+
+        fn create(&self, _user: &User) -> Result<User, AuthError> {
+            Err(AuthError::Internal("Not implemented".to_string()))
+        }
+
+        fn update(&self, user: &User) -> Result<User, AuthError> {
+            *self.user.borrow_mut() = user.clone();
+            Ok(user.clone())
+        }
+    }
+
+    // Token service accepting any token for one user
+    struct MockTokenService {
+        user_id: Uuid,
+    }
+
+    impl TokenService for MockTokenService {
+        fn create_token(
+            &self,
+            _user: &User,
+            _session_id: Option<Uuid>,
+        ) -> Result<String, AuthError> {
+            Ok("token".to_string())
+        }
+
+        fn validate_token(&self, _token: &str) -> Result<TokenData, AuthError> {
+            Ok(token_data(self.user_id))
+        }
+
+        fn revoke_token(&self, _token: &TokenData) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn is_revoked(&self, _token: &TokenData) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+    }
+
+    // Verification token store recording issued tokens
+    #[derive(Default)]
+    struct MockVerificationTokenRepository {
+        created: RefCell<Vec<EmailVerificationToken>>,
+    }
+
+    impl EmailVerificationTokenRepository for MockVerificationTokenRepository {
+        fn create(&self, token: &EmailVerificationToken) -> Result<(), AuthError> {
+            self.created.borrow_mut().push(token.clone());
+            Ok(())
+        }
+
+        fn find_by_hash(&self, _token_hash: &str) -> Result<EmailVerificationToken, AuthError> {
+            Err(AuthError::InvalidToken)
+        }
+
+        fn consume(&self, _token: &EmailVerificationToken) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+
+        fn purge_expired(&self, _now: DateTime<Utc>) -> Result<usize, AuthError> {
+            Ok(0)
+        }
+    }
+
+    // Mock generator with a fixed secret
+    struct MockTokenGenerator;
+
+    impl OpaqueTokenGenerator for MockTokenGenerator {
+        fn generate(&self) -> String {
+            "verify_secret".to_string()
+        }
+
+        fn hash(&self, token: &str) -> String {
+            format!("hashed_{}", token)
+        }
+    }
+
+    // Mailer collecting sent messages
+    #[derive(Default)]
+    struct MockMailer {
+        sent: RefCell<Vec<EmailMessage>>,
+    }
+
+    impl Mailer for MockMailer {
+        fn send(&self, message: &EmailMessage) -> Result<(), AuthError> {
+            self.sent.borrow_mut().push(message.clone());
+            Ok(())
+        }
+    }
+
+    // Session store without sessions, as for tokens that have none
+    struct MockSessionRepository;
+
+    impl SessionRepository for MockSessionRepository {
+        fn create(&self, _session: &Session) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn find_by_id(&self, _id: Uuid) -> Result<Session, AuthError> {
+            Err(AuthError::SessionNotFound)
+        }
+
+        fn find_active_for_user(
+            &self,
+            _user_id: Uuid,
+            _now: DateTime<Utc>,
+        ) -> Result<Vec<Session>, AuthError> {
+            Ok(Vec::new())
+        }
+
+        fn touch(
+            &self,
+            _id: Uuid,
+            _now: DateTime<Utc>,
+            _expires_at: DateTime<Utc>,
+        ) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn set_organization(
+            &self,
+            _id: Uuid,
+            _organization_id: Option<Uuid>,
+        ) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn is_revoked(&self, _id: Uuid) -> Result<bool, AuthError> {
+            Ok(true)
+        }
+    }
+
+    // Counter store that never locks anyone out
+    struct MockLoginThrottleRepository;
+
+    impl LoginThrottleRepository for MockLoginThrottleRepository {
+        fn find(&self, _key: &ThrottleKey) -> Result<Option<LoginThrottle>, AuthError> {
+            Ok(None)
+        }
+
+        fn record_failure(
+            &self,
+            key: &ThrottleKey,
+            _policy: &LockoutPolicy,
+            now: DateTime<Utc>,
+        ) -> Result<LoginThrottle, AuthError> {
+            Ok(LoginThrottle::new(key.clone(), now))
+        }
+
+        fn clear(&self, _key: &ThrottleKey) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+
+        fn purge_stale(&self, _cutoff: DateTime<Utc>) -> Result<usize, AuthError> {
+            Ok(0)
+        }
+    }
+
+    struct Fixture {
+        users: MockUserRepository,
+        token_service: MockTokenService,
+        verification_tokens: MockVerificationTokenRepository,
+        mailer: MockMailer,
+    }
+
+    impl Fixture {
+        fn new(taken_emails: Vec<String>) -> Self {
+            let email = Email::new("old@example.com").unwrap();
+            let mut user = User::new(
+                email,
+                HashedPassword::from_hash("hashed_password".to_string()),
+                None,
+            );
+            user.verify_email();
+            Self {
+                token_service: MockTokenService {
+                    user_id: user.id().as_uuid(),
+                },
+                users: MockUserRepository {
+                    user: RefCell::new(user),
+                    taken_emails,
+                },
+                verification_tokens: MockVerificationTokenRepository::default(),
+                mailer: MockMailer::default(),
+            }
+        }
+
+        fn change(&self, current_password: &str, new_email: &str) -> Result<User, AuthError> {
+            ChangeEmailUseCase::new(
+                &self.users,
+                &MockPasswordHasher,
+                &self.token_service,
+                &self.verification_tokens,
+                &MockTokenGenerator,
+                &self.mailer,
+                &MockSessionRepository,
+                &MockLoginThrottleRepository,
+                LockoutPolicy {
+                    email_threshold: 5,
+                    ip_threshold: 20,
+                    base_lockout: Duration::seconds(30),
+                    max_lockout: Duration::minutes(10),
+                    reset_after: Duration::minutes(15),
+                },
+                Duration::hours(24),
+                "https://example.com/verify-email",
+                &MockAuditLog::default(),
+            )
+            .execute(ChangeEmailCommand {
+                token: "token".to_string(),
+                current_password: current_password.to_string(),
+                new_email: new_email.to_string(),
+            })
+        }
+    }
+
+    #[test]
+    fn test_change_email_requires_reverification() {
+        let fixture = Fixture::new(vec![]);
+
+        let user = fixture.change("password", "New@Example.com").unwrap();
+
+        assert_eq!(user.email().as_str(), "new@example.com");
+        assert!(!user.is_email_verified());
+        assert_eq!(
+            fixture.users.user.borrow().email().as_str(),
+            "new@example.com"
+        );
+
+        let created = fixture.verification_tokens.created.borrow();
+        assert_eq!(created.len(), 1);
+        assert_eq!(created[0].email(), "new@example.com");
+
+        let sent = fixture.mailer.sent.borrow();
+        let recipients: Vec<&str> = sent.iter().map(|m| m.to.as_str()).collect();
+        assert_eq!(recipients, vec!["new@example.com", "old@example.com"]);
+    }
+
+    #[test]
+    fn test_taken_email_rejected() {
+        let fixture = Fixture::new(vec!["taken@example.com".to_string()]);
+
+        let result = fixture.change("password", "taken@example.com");
+
+        assert!(matches!(result, Err(AuthError::UserAlreadyExists)));
+        assert_eq!(
+            fixture.users.user.borrow().email().as_str(),
+            "old@example.com"
+        );
+        assert!(fixture.mailer.sent.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_wrong_password_rejected() {
+        let fixture = Fixture::new(vec![]);
+
+        let result = fixture.change("wrong", "new@example.com");
+
+        assert!(matches!(result, Err(AuthError::InvalidCredentials)));
+        assert!(fixture.mailer.sent.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_same_email_is_a_no_op() {
+        let fixture = Fixture::new(vec![]);
+
+        let user = fixture.change("password", "OLD@example.com").unwrap();
+
+        assert!(user.is_email_verified());
+        assert!(fixture.mailer.sent.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_user_without_password_needs_recent_login() {
+        let mut fixture = Fixture::new(vec![]);
+        let email = Email::new("old@example.com").unwrap();
+        let user = User::new_federated(email, None);
+        fixture.token_service.user_id = user.id().as_uuid();
+        *fixture.users.user.borrow_mut() = user;
+
+        let result = fixture.change("", "new@example.com");
+
+        assert!(matches!(result, Err(AuthError::RecentLoginRequired)));
+        assert!(fixture.mailer.sent.borrow().is_empty());
+    }
+}
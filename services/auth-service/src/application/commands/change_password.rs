@@ -0,0 +1,571 @@
+//! Change password use case
+//!
+//! Lets a signed-in user replace their password. The current password must
+//! be presented; wrong guesses count towards the login lockout, so a stolen
+//! access token cannot be used to brute-force it. Users who signed up through
+//! an identity provider have no password yet and set their first one from a
+//! recent login instead. Every session and refresh token of the user is
+//! revoked, so access tokens issued to them stop working straight away.
+
+use chrono::{Duration, Utc};
+use tracing::info;
+
+use super::login_user::{lockout, warn_on_lockout};
+use crate::domain::audit::{AuditAction, AuditEvent, AuditLog};
+use crate::domain::auth::{PasswordHasher, TokenData, TokenService, UserRepository};
+use crate::domain::error::AuthError;
+use crate::domain::lockout::{LockoutPolicy, LoginThrottleRepository, ThrottleKey};
+use crate::domain::password_policy::PasswordPolicy;
+use crate::domain::refresh_token::RefreshTokenRepository;
+use crate::domain::session::SessionRepository;
+use crate::domain::user::User;
+
+/// How recently a user without a password must have signed in, in minutes,
+/// to make a sensitive change
+const RECENT_LOGIN_MINUTES: i64 = 10;
+
+/// Input for changing a password
+#[derive(Debug)]
+pub struct ChangePasswordCommand {
+    /// Access token of the user
+    pub token: String,
+    /// Ignored for users without a password
+    pub current_password: String,
+    pub new_password: String,
+}
+
+/// Use case for changing a password
+pub struct ChangePasswordUseCase<
+    'a,
+    R: ?Sized,
+    H: ?Sized,
+    T: ?Sized,
+    S: ?Sized,
+    D: ?Sized,
+    L: ?Sized,
+    A: ?Sized,
+> {
+    user_repository: &'a R,
+    password_hasher: &'a H,
+    password_policy: &'a PasswordPolicy,
+    token_service: &'a T,
+    refresh_token_repository: &'a S,
+    session_repository: &'a D,
+    throttle_repository: &'a L,
+    lockout_policy: LockoutPolicy,
+    audit_log: &'a A,
+}
+
+impl<'a, R, H, T, S, D, L, A> ChangePasswordUseCase<'a, R, H, T, S, D, L, A>
+where
+    R: UserRepository + ?Sized,
+    H: PasswordHasher + ?Sized,
+    T: TokenService + ?Sized,
+    S: RefreshTokenRepository + ?Sized,
+    D: SessionRepository + ?Sized,
+    L: LoginThrottleRepository + ?Sized,
+    A: AuditLog + ?Sized,
+{
+    /// Create a new use case instance
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        user_repository: &'a R,
+        password_hasher: &'a H,
+        password_policy: &'a PasswordPolicy,
+        token_service: &'a T,
+        refresh_token_repository: &'a S,
+        session_repository: &'a D,
+        throttle_repository: &'a L,
+        lockout_policy: LockoutPolicy,
+        audit_log: &'a A,
+    ) -> Self {
+        Self {
+            user_repository,
+            password_hasher,
+            password_policy,
+            token_service,
+            refresh_token_repository,
+            session_repository,
+            throttle_repository,
+            lockout_policy,
+            audit_log,
+        }
+    }
+
+    /// Execute the password change
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidToken` / `AuthError::TokenExpired` if the token is invalid
+    /// - `AuthError::Forbidden` for impersonation tokens
+    /// - `AuthError::AccountInactive` if user account is deactivated
+    /// - `AuthError::AccountLocked` after too many failed attempts
+    /// - `AuthError::InvalidCredentials` if the current password is wrong
+    /// - `AuthError::RecentLoginRequired` if the user has no password and
+    ///   signed in too long ago
+    /// - `AuthError::WeakPassword` if the new password breaks the password policy
+    /// - `AuthError::Internal` on infrastructure failures
+    pub fn execute(&self, command: ChangePasswordCommand) -> Result<(), AuthError> {
+        let token_data = self.token_service.validate_token(&command.token)?;
+
+        let result = self.change(&token_data, command);
+        self.audit_log.record(
+            AuditEvent::new(AuditAction::PasswordChange)
+                .with_actor(token_data.audit_actor())
+                .with_target(token_data.user_id)
+                .with_result(&result),
+        );
+        result
+    }
+
+    fn change(
+        &self,
+        token_data: &TokenData,
+        command: ChangePasswordCommand,
+    ) -> Result<(), AuthError> {
+        // Acting as a user does not extend to their credentials
+        if token_data.is_impersonated() {
+            return Err(AuthError::Forbidden);
+        }
+        let mut user = self.user_repository.find_by_id(token_data.user_id)?;
+
+        reauthenticate(
+            &user,
+            token_data,
+            Some(command.current_password.as_str()),
+            self.password_hasher,
+            self.session_repository,
+            self.throttle_repository,
+            &self.lockout_policy,
+        )?;
+
+        self.password_policy
+            .validate(&command.new_password, user.email().as_str())?;
+
+        let hashed_password = self.password_hasher.hash(&command.new_password)?;
+        user.change_password(hashed_password);
+        self.user_repository.update(&user)?;
+
+        // Sign the user out everywhere
+        self.refresh_token_repository
+            .revoke_all_for_user(token_data.user_id)?;
+
+        info!(user_id = %token_data.user_id, "Password changed");
+        Ok(())
+    }
+}
+
+/// Confirm a signed-in user's identity before a sensitive change
+///
+/// Users with a password must present it. This uses the same counters as
+/// login: a locked account is refused up front and a wrong password counts
+/// as a failed login. Users without one have nothing to present, so their
+/// session must instead have started in the last few minutes; signing in
+/// again at their identity provider starts a new one. They never count
+/// towards the lockout.
+///
+/// # Errors
+/// - `AuthError::AccountInactive` if user account is deactivated
+/// - `AuthError::AccountLocked` after too many failed attempts
+/// - `AuthError::InvalidCredentials` if the password is missing or wrong
+/// - `AuthError::RecentLoginRequired` if the user has no password and the
+///   token's session is missing or too old
+/// - `AuthError::Internal` on infrastructure failures
+pub(crate) fn reauthenticate<H, D, L>(
+    user: &User,
+    token_data: &TokenData,
+    password: Option<&str>,
+    password_hasher: &H,
+    session_repository: &D,
+    throttle_repository: &L,
+    lockout_policy: &LockoutPolicy,
+) -> Result<(), AuthError>
+where
+    H: PasswordHasher + ?Sized,
+    D: SessionRepository + ?Sized,
+    L: LoginThrottleRepository + ?Sized,
+{
+    if !user.is_active() {
+        return Err(AuthError::AccountInactive);
+    }
+
+    let now = Utc::now();
+    let Some(hashed) = user.hashed_password() else {
+        let session = match token_data.session_id {
+            Some(session_id) => session_repository.find_by_id(session_id),
+            None => Err(AuthError::SessionNotFound),
+        };
+        return match session {
+            Ok(session) if session.created_at() + Duration::minutes(RECENT_LOGIN_MINUTES) > now => {
+                Ok(())
+            }
+            Ok(_) | Err(AuthError::SessionNotFound) => Err(AuthError::RecentLoginRequired),
+            Err(e) => Err(e),
+        };
+    };
+    let password = password
+        .filter(|password| !password.is_empty())
+        .ok_or(AuthError::InvalidCredentials)?;
+
+    let key = ThrottleKey::email(user.email().as_str());
+    lockout(throttle_repository.find(&key)?.and_then(|t| t.retry_after(now)))?;
+
+    if !password_hasher.verify(password, hashed)? {
+        let throttle = throttle_repository.record_failure(&key, lockout_policy, now)?;
+        warn_on_lockout(&key, &throttle, now);
+        return Err(AuthError::InvalidCredentials);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use chrono::{DateTime, Duration};
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::application::commands::test_support::{
+        token_data, MockAuditLog, MockPasswordHasher, MockUserRepository,
+    };
+    use crate::domain::audit::AuditOutcome;
+    use crate::domain::lockout::LoginThrottle;
+    use crate::domain::password_policy::PasswordRule;
+    use crate::domain::refresh_token::RefreshToken;
+    use crate::domain::session::Session;
+    use crate::domain::user::{Email, HashedPassword};
+
+    // Token service accepting any token for one user's session
+    struct MockTokenService {
+        user_id: Uuid,
+        session_id: Uuid,
+    }
+
+    impl TokenService for MockTokenService {
+        fn create_token(
+            &self,
+            _user: &User,
+            _session_id: Option<Uuid>,
+        ) -> Result<String, AuthError> {
+            Ok("token".to_string())
+        }
+
+        fn validate_token(&self, _token: &str) -> Result<TokenData, AuthError> {
+            Ok(TokenData {
+                session_id: Some(self.session_id),
+                ..token_data(self.user_id)
+            })
+        }
+
+        fn revoke_token(&self, _token: &TokenData) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn is_revoked(&self, _token: &TokenData) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+    }
+
+    // Refresh token store recording which users were signed out
+    #[derive(Default)]
+    struct MockRefreshTokenRepository {
+        revoked_users: RefCell<Vec<Uuid>>,
+    }
+
+    impl RefreshTokenRepository for MockRefreshTokenRepository {
+        fn create(&self, _token: &RefreshToken) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn find_by_hash(&self, _token_hash: &str) -> Result<RefreshToken, AuthError> {
+            Err(AuthError::InvalidToken)
+        }
+
+        fn rotate(&self, _current_id: Uuid, _next: &RefreshToken) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+
+        fn revoke_family(&self, _family_id: Uuid) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn revoke_all_for_user(&self, user_id: Uuid) -> Result<(), AuthError> {
+            self.revoked_users.borrow_mut().push(user_id);
+            Ok(())
+        }
+    }
+
+    // Session store holding the caller's session
+    struct MockSessionRepository {
+        session: RefCell<Session>,
+    }
+
+    impl SessionRepository for MockSessionRepository {
+        fn create(&self, _session: &Session) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn find_by_id(&self, id: Uuid) -> Result<Session, AuthError> {
+            let session = self.session.borrow();
+            if session.id() == id {
+                Ok(session.clone())
+            } else {
+                Err(AuthError::SessionNotFound)
+            }
+        }
+
+        fn find_active_for_user(
+            &self,
+            _user_id: Uuid,
+            _now: DateTime<Utc>,
+        ) -> Result<Vec<Session>, AuthError> {
+            Ok(Vec::new())
+        }
+
+        fn touch(
+            &self,
+            _id: Uuid,
+            _now: DateTime<Utc>,
+            _expires_at: DateTime<Utc>,
+        ) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn set_organization(
+            &self,
+            _id: Uuid,
+            _organization_id: Option<Uuid>,
+        ) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn is_revoked(&self, _id: Uuid) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+    }
+
+    /// Session of the user that started `age` ago
+    fn session_started(session_id: Uuid, user_id: Uuid, age: Duration) -> Session {
+        let started_at = Utc::now() - age;
+        Session::from_persistence(
+            session_id,
+            user_id,
+            None,
+            None,
+            started_at,
+            started_at,
+            started_at + Duration::days(30),
+            None,
+            None,
+        )
+    }
+
+    // In-memory failure counters
+    #[derive(Default)]
+    struct MockLoginThrottleRepository {
+        throttles: RefCell<Vec<LoginThrottle>>,
+    }
+
+    impl LoginThrottleRepository for MockLoginThrottleRepository {
+        fn find(&self, key: &ThrottleKey) -> Result<Option<LoginThrottle>, AuthError> {
+            Ok(self
+                .throttles
+                .borrow()
+                .iter()
+                .find(|t| t.key() == key)
+                .cloned())
+        }
+
+        fn record_failure(
+            &self,
+            key: &ThrottleKey,
+            policy: &LockoutPolicy,
+            now: DateTime<Utc>,
+        ) -> Result<LoginThrottle, AuthError> {
+            let mut throttles = self.throttles.borrow_mut();
+            let index = match throttles.iter().position(|t| t.key() == key) {
+                Some(index) => index,
+                None => {
+                    throttles.push(LoginThrottle::new(key.clone(), now));
+                    throttles.len() - 1
+                }
+            };
+            throttles[index].record_failure(now, policy);
+            Ok(throttles[index].clone())
+        }
+
+        fn clear(&self, key: &ThrottleKey) -> Result<bool, AuthError> {
+            let mut throttles = self.throttles.borrow_mut();
+            let before = throttles.len();
+            throttles.retain(|t| t.key() != key);
+            Ok(throttles.len() < before)
+        }
+
+        fn purge_stale(&self, _cutoff: DateTime<Utc>) -> Result<usize, AuthError> {
+            Ok(0)
+        }
+    }
+
+    struct Fixture {
+        users: MockUserRepository,
+        token_service: MockTokenService,
+        refresh_tokens: MockRefreshTokenRepository,
+        sessions: MockSessionRepository,
+        throttles: MockLoginThrottleRepository,
+        audit_log: MockAuditLog,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            let email = Email::new("test@example.com").unwrap();
+            Self::with_user(User::new(
+                email,
+                HashedPassword::from_hash("hashed_old_password".to_string()),
+                None,
+            ))
+        }
+
+        fn with_user(user: User) -> Self {
+            let session_id = Uuid::new_v4();
+            Self {
+                token_service: MockTokenService {
+                    user_id: user.id().as_uuid(),
+                    session_id,
+                },
+                sessions: MockSessionRepository {
+                    session: RefCell::new(session_started(
+                        session_id,
+                        user.id().as_uuid(),
+                        Duration::minutes(1),
+                    )),
+                },
+                users: MockUserRepository::new(user),
+                refresh_tokens: MockRefreshTokenRepository::default(),
+                throttles: MockLoginThrottleRepository::default(),
+                audit_log: MockAuditLog::default(),
+            }
+        }
+
+        fn change(&self, current_password: &str, new_password: &str) -> Result<(), AuthError> {
+            ChangePasswordUseCase::new(
+                &self.users,
+                &MockPasswordHasher,
+                &PasswordPolicy::default(),
+                &self.token_service,
+                &self.refresh_tokens,
+                &self.sessions,
+                &self.throttles,
+                LockoutPolicy {
+                    email_threshold: 2,
+                    ip_threshold: 10,
+                    base_lockout: Duration::seconds(30),
+                    max_lockout: Duration::minutes(10),
+                    reset_after: Duration::minutes(15),
+                },
+                &self.audit_log,
+            )
+            .execute(ChangePasswordCommand {
+                token: "token".to_string(),
+                current_password: current_password.to_string(),
+                new_password: new_password.to_string(),
+            })
+        }
+    }
+
+    #[test]
+    fn test_change_password_ends_sessions() {
+        let fixture = Fixture::new();
+        let before = fixture.users.user.lock().unwrap().updated_at();
+
+        fixture.change("old_password", "new_password").unwrap();
+
+        let user = fixture.users.user.lock().unwrap();
+        assert_eq!(
+            user.hashed_password().unwrap().as_str(),
+            "hashed_new_password"
+        );
+        assert!(user.updated_at() >= before);
+        assert_eq!(
+            *fixture.refresh_tokens.revoked_users.borrow(),
+            vec![user.id().as_uuid()]
+        );
+
+        let events = fixture.audit_log.events.lock().unwrap();
+        assert_eq!(events[0].action(), AuditAction::PasswordChange);
+        assert_eq!(events[0].outcome(), AuditOutcome::Success);
+        assert_eq!(events[0].actor_id(), Some(user.id().as_uuid()));
+    }
+
+    #[test]
+    fn test_wrong_current_password_counts_towards_lockout() {
+        let fixture = Fixture::new();
+
+        for _ in 0..2 {
+            let result = fixture.change("wrong_password", "new_password");
+            assert!(matches!(result, Err(AuthError::InvalidCredentials)));
+        }
+
+        let result = fixture.change("old_password", "new_password");
+        assert!(matches!(result, Err(AuthError::AccountLocked { .. })));
+        assert_eq!(
+            fixture
+                .users
+                .user
+                .lock()
+                .unwrap()
+                .hashed_password()
+                .unwrap()
+                .as_str(),
+            "hashed_old_password"
+        );
+    }
+
+    #[test]
+    fn test_new_password_must_meet_policy() {
+        let fixture = Fixture::new();
+
+        let result = fixture.change("old_password", "short");
+
+        assert_eq!(
+            result,
+            Err(AuthError::WeakPassword(vec![PasswordRule::MinLength(8)]))
+        );
+        assert!(fixture.refresh_tokens.revoked_users.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_user_without_password_sets_one_after_recent_login() {
+        let email = Email::new("test@example.com").unwrap();
+        let fixture = Fixture::with_user(User::new_federated(email, None));
+
+        fixture.change("", "new_password").unwrap();
+
+        let user = fixture.users.user.lock().unwrap();
+        assert_eq!(
+            user.hashed_password().unwrap().as_str(),
+            "hashed_new_password"
+        );
+    }
+
+    #[test]
+    fn test_user_without_password_needs_recent_login() {
+        let email = Email::new("test@example.com").unwrap();
+        let fixture = Fixture::with_user(User::new_federated(email, None));
+        let user_id = fixture.token_service.user_id;
+        *fixture.sessions.session.borrow_mut() = session_started(
+            fixture.token_service.session_id,
+            user_id,
+            Duration::hours(1),
+        );
+
+        for _ in 0..3 {
+            let result = fixture.change("guess", "new_password");
+            assert!(matches!(result, Err(AuthError::RecentLoginRequired)));
+        }
+
+        // Nothing to guess, so nothing counts towards the lockout
+        assert!(fixture.throttles.throttles.borrow().is_empty());
+        assert!(!fixture.users.user.lock().unwrap().has_password());
+    }
+}
@@ -0,0 +1,227 @@
+//! Client credentials use case
+//!
+//! Implements the OAuth2 client_credentials grant: a backend service
+//! authenticates with its client ID and secret and receives a short-lived
+//! access token for calling other services.
+
+use tracing::info;
+use uuid::Uuid;
+
+use crate::domain::auth::OpaqueTokenGenerator;
+use crate::domain::error::AuthError;
+use crate::domain::oauth_client::{
+    parse_scope, OAuthClientRepository, ServiceToken, ServiceTokenIssuer,
+};
+use crate::domain::role::Permission;
+
+/// Input for the client_credentials grant
+#[derive(Debug)]
+pub struct ClientCredentialsCommand {
+    pub client_id: String,
+    pub client_secret: String,
+    /// Space-delimited scopes; all registered scopes when absent
+    pub scope: Option<String>,
+}
+
+/// Result of a successful grant
+#[derive(Debug)]
+pub struct ClientCredentialsResult {
+    pub token: ServiceToken,
+    /// Scopes the token was granted
+    pub scopes: Vec<Permission>,
+}
+
+/// Use case for the client_credentials grant
+pub struct ClientCredentialsUseCase<'a, C: ?Sized, G: ?Sized, I: ?Sized> {
+    client_repository: &'a C,
+    token_generator: &'a G,
+    token_issuer: &'a I,
+}
+
+impl<'a, C, G, I> ClientCredentialsUseCase<'a, C, G, I>
+where
+    C: OAuthClientRepository + ?Sized,
+    G: OpaqueTokenGenerator + ?Sized,
+    I: ServiceTokenIssuer + ?Sized,
+{
+    /// Create a new use case instance
+    pub fn new(client_repository: &'a C, token_generator: &'a G, token_issuer: &'a I) -> Self {
+        Self {
+            client_repository,
+            token_generator,
+            token_issuer,
+        }
+    }
+
+    /// Execute the grant
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidClient` if the client is unknown or revoked, or
+    ///   the secret is wrong
+    /// - `AuthError::InvalidScope` if a requested scope is unknown or was not
+    ///   registered for the client
+    /// - `AuthError::Internal` on infrastructure failures
+    pub fn execute(
+        &self,
+        command: ClientCredentialsCommand,
+    ) -> Result<ClientCredentialsResult, AuthError> {
+        let client_id =
+            Uuid::parse_str(command.client_id.trim()).map_err(|_| AuthError::InvalidClient)?;
+        let client = match self.client_repository.find_by_id(client_id) {
+            Ok(client) => client,
+            Err(AuthError::OAuthClientNotFound) => return Err(AuthError::InvalidClient),
+            Err(e) => return Err(e),
+        };
+        // Secrets are random, so comparing their hashes reveals nothing usable
+        if client.is_revoked()
+            || self.token_generator.hash(&command.client_secret) != client.secret_hash()
+        {
+            return Err(AuthError::InvalidClient);
+        }
+
+        let requested = parse_scope(command.scope.as_deref().unwrap_or_default())?;
+        let scopes = client.grant_scopes(&requested)?;
+        let token = self.token_issuer.create_service_token(&client, &scopes)?;
+
+        info!(client_id = %client_id, "Service token issued");
+        Ok(ClientCredentialsResult { token, scopes })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+
+    use super::*;
+    use crate::domain::oauth_client::OAuthClient;
+
+    // Mock repository holding a single client
+    struct MockOAuthClientRepository {
+        client: OAuthClient,
+    }
+
+    impl OAuthClientRepository for MockOAuthClientRepository {
+        fn create(&self, _client: &OAuthClient) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn find_by_id(&self, client_id: Uuid) -> Result<OAuthClient, AuthError> {
+            if self.client.client_id() == client_id {
+                Ok(self.client.clone())
+            } else {
+                Err(AuthError::OAuthClientNotFound)
+            }
+        }
+
+        fn list(&self) -> Result<Vec<OAuthClient>, AuthError> {
+            Ok(vec![self.client.clone()])
+        }
+
+        fn revoke(&self, _client_id: Uuid) -> Result<bool, AuthError> {
+            Ok(true)
+        }
+    }
+
+    // Generator with a reversible "hash"
+    struct MockTokenGenerator;
+
+    impl OpaqueTokenGenerator for MockTokenGenerator {
+        fn generate(&self) -> String {
+            "secret".to_string()
+        }
+
+        fn hash(&self, token: &str) -> String {
+            format!("hashed:{}", token)
+        }
+    }
+
+    // Issuer encoding the client and scopes in the token
+    struct MockServiceTokenIssuer;
+
+    impl ServiceTokenIssuer for MockServiceTokenIssuer {
+        fn create_service_token(
+            &self,
+            client: &OAuthClient,
+            scopes: &[Permission],
+        ) -> Result<ServiceToken, AuthError> {
+            Ok(ServiceToken {
+                access_token: format!("{}:{}", client.client_id(), scopes.len()),
+                expires_at: Utc::now() + Duration::minutes(15),
+            })
+        }
+    }
+
+    fn repository(revoked_at: Option<chrono::DateTime<Utc>>) -> MockOAuthClientRepository {
+        MockOAuthClientRepository {
+            client: OAuthClient::from_persistence(
+                Uuid::new_v4(),
+                "orders-service".to_string(),
+                "hashed:secret".to_string(),
+                vec![Permission::ReadAllOrders, Permission::ReadUsers],
+                Utc::now(),
+                revoked_at,
+            ),
+        }
+    }
+
+    fn grant(
+        clients: &MockOAuthClientRepository,
+        client_id: String,
+        secret: &str,
+        scope: Option<&str>,
+    ) -> Result<ClientCredentialsResult, AuthError> {
+        ClientCredentialsUseCase::new(clients, &MockTokenGenerator, &MockServiceTokenIssuer)
+            .execute(ClientCredentialsCommand {
+                client_id,
+                client_secret: secret.to_string(),
+                scope: scope.map(String::from),
+            })
+    }
+
+    #[test]
+    fn test_grant_issues_token_for_requested_scopes() {
+        let clients = repository(None);
+        let client_id = clients.client.client_id().to_string();
+
+        let all = grant(&clients, client_id.clone(), "secret", None).unwrap();
+        assert_eq!(all.scopes, clients.client.scopes());
+        assert_eq!(all.token.access_token, format!("{}:2", client_id));
+
+        let narrowed = grant(&clients, client_id, "secret", Some("users:read")).unwrap();
+        assert_eq!(narrowed.scopes, vec![Permission::ReadUsers]);
+    }
+
+    #[test]
+    fn test_bad_credentials_are_invalid_client() {
+        let clients = repository(None);
+        let client_id = clients.client.client_id().to_string();
+
+        for (client_id, secret) in [
+            (client_id.as_str(), "wrong"),
+            ("not-a-uuid", "secret"),
+            (&Uuid::new_v4().to_string(), "secret"),
+        ] {
+            let result = grant(&clients, client_id.to_string(), secret, None);
+            assert!(matches!(result, Err(AuthError::InvalidClient)));
+        }
+
+        let revoked = repository(Some(Utc::now()));
+        let result = grant(
+            &revoked,
+            revoked.client.client_id().to_string(),
+            "secret",
+            None,
+        );
+        assert!(matches!(result, Err(AuthError::InvalidClient)));
+    }
+
+    #[test]
+    fn test_unregistered_scope_rejected() {
+        let clients = repository(None);
+        let client_id = clients.client.client_id().to_string();
+
+        let result = grant(&clients, client_id, "secret", Some("users:manage"));
+
+        assert!(matches!(result, Err(AuthError::InvalidScope)));
+    }
+}
@@ -0,0 +1,686 @@
+//! Complete federated login use case
+//!
+//! The identity provider sends the user back to the callback page with a
+//! code, which is redeemed for a verified ID token. Known upstream accounts
+//! sign in their linked user. Unknown ones are linked to the user with the
+//! same email address, or a new user without a password is created.
+
+use std::net::IpAddr;
+
+use chrono::{Duration, Utc};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use super::login_user::{complete_login, login_audit_event, LoginOutcome};
+use crate::domain::audit::{AsyncAuditLog, AuditAction};
+use crate::domain::auth::{AsyncUserRepository, OpaqueTokenGenerator, TokenService};
+use crate::domain::error::AuthError;
+use crate::domain::federation::{
+    find_provider, AsyncFederatedLoginRequestRepository, AsyncIdentityProviderClient,
+    AsyncUserIdentityRepository, ExternalIdentity, IdentityProvider, UserIdentity,
+};
+use crate::domain::mfa::{AsyncMfaChallengeRepository, AsyncMfaRepository};
+use crate::domain::refresh_token::AsyncRefreshTokenRepository;
+use crate::domain::session::{AsyncSessionRepository, SessionClient};
+use crate::domain::user::{normalize_display_name, Email, User};
+
+/// Input for completing a federated login
+#[derive(Debug)]
+pub struct CompleteFederatedLoginCommand {
+    /// State returned by the identity provider
+    pub state: String,
+    /// Authorization code returned by the identity provider
+    pub code: String,
+    /// Source address of the request, when known
+    pub client_ip: Option<IpAddr>,
+    /// `User-Agent` of the client, recorded with the session
+    pub user_agent: Option<String>,
+}
+
+/// Use case for completing a federated login
+pub struct CompleteFederatedLoginUseCase<
+    'a,
+    Q: ?Sized,
+    P: ?Sized,
+    R: ?Sized,
+    I: ?Sized,
+    T: ?Sized,
+    S: ?Sized,
+    D: ?Sized,
+    G: ?Sized,
+    M: ?Sized,
+    C: ?Sized,
+    A: ?Sized,
+> {
+    providers: &'a [IdentityProvider],
+    request_repository: &'a Q,
+    provider_client: &'a P,
+    user_repository: &'a R,
+    identity_repository: &'a I,
+    token_service: &'a T,
+    refresh_token_repository: &'a S,
+    session_repository: &'a D,
+    token_generator: &'a G,
+    mfa_repository: &'a M,
+    mfa_challenge_repository: &'a C,
+    redirect_uri: &'a str,
+    refresh_token_ttl: Duration,
+    mfa_challenge_ttl: Duration,
+    require_verified_email: bool,
+    audit_log: &'a A,
+}
+
+impl<'a, Q, P, R, I, T, S, D, G, M, C, A>
+    CompleteFederatedLoginUseCase<'a, Q, P, R, I, T, S, D, G, M, C, A>
+where
+    Q: AsyncFederatedLoginRequestRepository + ?Sized,
+    P: AsyncIdentityProviderClient + ?Sized,
+    R: AsyncUserRepository + ?Sized,
+    I: AsyncUserIdentityRepository + ?Sized,
+    T: TokenService + ?Sized,
+    S: AsyncRefreshTokenRepository + ?Sized,
+    D: AsyncSessionRepository + ?Sized,
+    G: OpaqueTokenGenerator + ?Sized,
+    M: AsyncMfaRepository + ?Sized,
+    C: AsyncMfaChallengeRepository + ?Sized,
+    A: AsyncAuditLog + ?Sized,
+{
+    /// Create a new use case instance
+    ///
+    /// `redirect_uri` must be the callback page the login was started with.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        providers: &'a [IdentityProvider],
+        request_repository: &'a Q,
+        provider_client: &'a P,
+        user_repository: &'a R,
+        identity_repository: &'a I,
+        token_service: &'a T,
+        refresh_token_repository: &'a S,
+        session_repository: &'a D,
+        token_generator: &'a G,
+        mfa_repository: &'a M,
+        mfa_challenge_repository: &'a C,
+        redirect_uri: &'a str,
+        refresh_token_ttl: Duration,
+        mfa_challenge_ttl: Duration,
+        require_verified_email: bool,
+        audit_log: &'a A,
+    ) -> Self {
+        Self {
+            providers,
+            request_repository,
+            provider_client,
+            user_repository,
+            identity_repository,
+            token_service,
+            refresh_token_repository,
+            session_repository,
+            token_generator,
+            mfa_repository,
+            mfa_challenge_repository,
+            redirect_uri,
+            refresh_token_ttl,
+            mfa_challenge_ttl,
+            require_verified_email,
+            audit_log,
+        }
+    }
+
+    /// Execute the login
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidGrant` if the state is unknown, expired or already used
+    /// - `AuthError::IdentityProviderNotFound` if the provider is no longer configured
+    /// - `AuthError::IdentityProvider` if the code or the ID token is rejected
+    /// - `AuthError::EmailNotVerified` if an unknown upstream account comes
+    ///   without a verified email address, or would be linked to a user who
+    ///   has not verified theirs
+    /// - `AuthError::AccountInactive` if the user is deactivated
+    /// - `AuthError::Internal` on infrastructure failures
+    pub async fn execute(
+        &self,
+        command: CompleteFederatedLoginCommand,
+    ) -> Result<LoginOutcome, AuthError> {
+        let mut user_id = None;
+        let result = self.complete(command, &mut user_id).await;
+        self.audit_log
+            .record(login_audit_event(
+                AuditAction::FederatedLogin,
+                &result,
+                user_id,
+            ))
+            .await;
+        result
+    }
+
+    /// Run the login, leaving the id of the signed-in user in `user_id`
+    async fn complete(
+        &self,
+        command: CompleteFederatedLoginCommand,
+        user_id: &mut Option<Uuid>,
+    ) -> Result<LoginOutcome, AuthError> {
+        let now = Utc::now();
+
+        // The state works once, so a leaked callback URL cannot be replayed
+        let request = self
+            .request_repository
+            .take(&self.token_generator.hash(&command.state))
+            .await?
+            .filter(|request| !request.is_expired(now))
+            .ok_or(AuthError::InvalidGrant)?;
+        let provider = find_provider(self.providers, request.provider())?;
+
+        let external = self
+            .provider_client
+            .redeem_code(
+                provider,
+                &command.code,
+                self.redirect_uri,
+                request.code_verifier(),
+                request.nonce(),
+            )
+            .await?;
+
+        let user = match self
+            .identity_repository
+            .find(&provider.id, &external.subject)
+            .await?
+        {
+            Some(identity) => {
+                self.identity_repository
+                    .record_login(&provider.id, &external.subject, now)
+                    .await?;
+                self.user_repository.find_by_id(identity.user_id()).await?
+            }
+            None => self.link_or_create(provider, &external).await?,
+        };
+        *user_id = Some(user.id().as_uuid());
+
+        if !user.is_active() {
+            return Err(AuthError::AccountInactive);
+        }
+        if self.require_verified_email && !user.is_email_verified() {
+            return Err(AuthError::EmailNotVerified);
+        }
+
+        let client = SessionClient {
+            user_agent: command.user_agent,
+            ip_address: command.client_ip,
+        };
+        complete_login(
+            &user,
+            &client,
+            self.token_service,
+            self.refresh_token_repository,
+            self.session_repository,
+            self.token_generator,
+            self.mfa_repository,
+            self.mfa_challenge_repository,
+            self.refresh_token_ttl,
+            self.mfa_challenge_ttl,
+        )
+        .await
+    }
+
+    /// Link an unknown upstream account by email address, creating the user
+    /// if there is none
+    async fn link_or_create(
+        &self,
+        provider: &IdentityProvider,
+        external: &ExternalIdentity,
+    ) -> Result<User, AuthError> {
+        let email = Email::new(
+            external
+                .verified_email()
+                .ok_or(AuthError::EmailNotVerified)?,
+        )?;
+
+        match self.user_repository.find_by_email(email.as_str()).await {
+            Ok(user) => {
+                // Whoever registered an unverified address may not own it,
+                // and must not gain access to the upstream account's owner
+                if !user.is_email_verified() {
+                    warn!(
+                        user_id = %user.id().as_uuid(),
+                        provider = %provider.id,
+                        "Refused to link identity to an unverified account"
+                    );
+                    return Err(AuthError::EmailNotVerified);
+                }
+                if !user.is_active() {
+                    return Err(AuthError::AccountInactive);
+                }
+
+                self.identity_repository
+                    .create(&UserIdentity::link(&provider.id, external, &user))
+                    .await?;
+                info!(
+                    user_id = %user.id().as_uuid(),
+                    provider = %provider.id,
+                    "Identity linked to existing user"
+                );
+                Ok(user)
+            }
+            Err(AuthError::UserNotFound) => {
+                // A name the provider allows may still be unacceptable here
+                let display_name =
+                    normalize_display_name(external.name.as_deref()).unwrap_or_default();
+                let user = User::new_federated(email, display_name);
+                let identity = UserIdentity::link(&provider.id, external, &user);
+                self.identity_repository
+                    .create_with_user(&user, &identity)
+                    .await?;
+                info!(
+                    user_id = %user.id().as_uuid(),
+                    provider = %provider.id,
+                    "User created from identity provider"
+                );
+                self.user_repository.find_by_id(user.id().as_uuid()).await
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    use chrono::DateTime;
+
+    use super::*;
+    use crate::application::commands::test_support::{
+        MockAuditLog, MockMfaChallengeRepository, MockMfaRepository, MockRefreshTokenRepository,
+        MockSessionRepository,
+    };
+    use crate::domain::auth::{BoxFuture, TokenData};
+    use crate::domain::federation::FederatedLoginRequest;
+    use crate::domain::user::HashedPassword;
+
+    // Request store removing requests as they are taken
+    #[derive(Default)]
+    struct MockFederatedLoginRequestRepository {
+        requests: Mutex<Vec<FederatedLoginRequest>>,
+    }
+
+    impl AsyncFederatedLoginRequestRepository for MockFederatedLoginRequestRepository {
+        fn create<'a>(
+            &'a self,
+            request: &'a FederatedLoginRequest,
+        ) -> BoxFuture<'a, Result<(), AuthError>> {
+            self.requests.lock().unwrap().push(request.clone());
+            Box::pin(async { Ok(()) })
+        }
+
+        fn take<'a>(
+            &'a self,
+            state_hash: &'a str,
+        ) -> BoxFuture<'a, Result<Option<FederatedLoginRequest>, AuthError>> {
+            let mut requests = self.requests.lock().unwrap();
+            let index = requests.iter().position(|r| r.state_hash() == state_hash);
+            let request = index.map(|index| requests.remove(index));
+            Box::pin(async { Ok(request) })
+        }
+    }
+
+    // Provider client returning a fixed identity for the right nonce and verifier
+    struct MockIdentityProviderClient {
+        identity: ExternalIdentity,
+    }
+
+    impl AsyncIdentityProviderClient for MockIdentityProviderClient {
+        fn authorization_url<'a>(
+            &'a self,
+            _provider: &'a IdentityProvider,
+            _redirect_uri: &'a str,
+            _state: &'a str,
+            _nonce: &'a str,
+            _code_challenge: &'a str,
+        ) -> BoxFuture<'a, Result<String, AuthError>> {
+            Box::pin(async { Ok("https://sso.example.com/authorize".to_string()) })
+        }
+
+        fn redeem_code<'a>(
+            &'a self,
+            _provider: &'a IdentityProvider,
+            code: &'a str,
+            _redirect_uri: &'a str,
+            code_verifier: &'a str,
+            nonce: &'a str,
+        ) -> BoxFuture<'a, Result<ExternalIdentity, AuthError>> {
+            Box::pin(async move {
+                if code == "upstream-code" && code_verifier == "verifier" && nonce == "nonce" {
+                    Ok(self.identity.clone())
+                } else {
+                    Err(AuthError::IdentityProvider("invalid_grant".to_string()))
+                }
+            })
+        }
+    }
+
+    // User store keyed by email address
+    #[derive(Default)]
+    struct MockUserRepository {
+        users: Mutex<Vec<User>>,
+    }
+
+    impl MockUserRepository {
+        fn find(&self, matches: impl Fn(&User) -> bool) -> Result<User, AuthError> {
+            self.users
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|u| matches(u))
+                .cloned()
+                .ok_or(AuthError::UserNotFound)
+        }
+    }
+
+    impl AsyncUserRepository for MockUserRepository {
+        fn find_by_id(&self, id: Uuid) -> BoxFuture<'_, Result<User, AuthError>> {
+            let user = self.find(|u| u.id().as_uuid() == id);
+            Box::pin(async { user })
+        }
+
+        fn find_by_email<'a>(&'a self, email: &'a str) -> BoxFuture<'a, Result<User, AuthError>> {
+            let user = self.find(|u| u.email().as_str() == email);
+            Box::pin(async { user })
+        }
+
+        fn exists_by_email<'a>(&'a self, email: &'a str) -> BoxFuture<'a, Result<bool, AuthError>> {
+            let exists = self.find(|u| u.email().as_str() == email).is_ok();
+            Box::pin(async move { Ok(exists) })
+        }
+
+        fn create<'a>(&'a self, user: &'a User) -> BoxFuture<'a, Result<User, AuthError>> {
+            self.users.lock().unwrap().push(user.clone());
+            Box::pin(async move { Ok(user.clone()) })
+        }
+
+        fn update<'a>(&'a self, user: &'a User) -> BoxFuture<'a, Result<User, AuthError>> {
+            Box::pin(async move { Ok(user.clone()) })
+        }
+    }
+
+    // Identity store writing new users to the user store
+    struct MockUserIdentityRepository<'a> {
+        users: &'a MockUserRepository,
+        identities: Mutex<Vec<UserIdentity>>,
+        logins: AtomicUsize,
+    }
+
+    impl<'u> MockUserIdentityRepository<'u> {
+        fn new(users: &'u MockUserRepository) -> Self {
+            Self {
+                users,
+                identities: Mutex::default(),
+                logins: AtomicUsize::new(0),
+            }
+        }
+    }
+
+    impl AsyncUserIdentityRepository for MockUserIdentityRepository<'_> {
+        fn find<'a>(
+            &'a self,
+            provider: &'a str,
+            subject: &'a str,
+        ) -> BoxFuture<'a, Result<Option<UserIdentity>, AuthError>> {
+            let identity = self
+                .identities
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|i| i.provider() == provider && i.subject() == subject)
+                .cloned();
+            Box::pin(async { Ok(identity) })
+        }
+
+        fn create<'a>(
+            &'a self,
+            identity: &'a UserIdentity,
+        ) -> BoxFuture<'a, Result<(), AuthError>> {
+            self.identities.lock().unwrap().push(identity.clone());
+            Box::pin(async { Ok(()) })
+        }
+
+        fn create_with_user<'a>(
+            &'a self,
+            user: &'a User,
+            identity: &'a UserIdentity,
+        ) -> BoxFuture<'a, Result<(), AuthError>> {
+            Box::pin(async move {
+                self.users.create(user).await?;
+                self.create(identity).await
+            })
+        }
+
+        fn record_login<'a>(
+            &'a self,
+            _provider: &'a str,
+            _subject: &'a str,
+            _at: DateTime<Utc>,
+        ) -> BoxFuture<'a, Result<(), AuthError>> {
+            self.logins.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    // Token service naming the user in its tokens
+    struct MockTokenService;
+
+    impl TokenService for MockTokenService {
+        fn create_token(
+            &self,
+            user: &User,
+            _session_id: Option<Uuid>,
+        ) -> Result<String, AuthError> {
+            Ok(format!("access:{}", user.id().as_uuid()))
+        }
+
+        fn validate_token(&self, _token: &str) -> Result<TokenData, AuthError> {
+            Err(AuthError::InvalidToken)
+        }
+
+        fn revoke_token(&self, _token: &TokenData) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn is_revoked(&self, _token: &TokenData) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+    }
+
+    // Generator with a reversible "hash"
+    struct MockTokenGenerator;
+
+    impl OpaqueTokenGenerator for MockTokenGenerator {
+        fn generate(&self) -> String {
+            "refresh".to_string()
+        }
+
+        fn hash(&self, token: &str) -> String {
+            format!("hashed:{}", token)
+        }
+    }
+
+    fn providers() -> Vec<IdentityProvider> {
+        vec![IdentityProvider {
+            id: "corp".to_string(),
+            name: "Corp SSO".to_string(),
+            issuer: "https://sso.example.com".to_string(),
+            client_id: "ticketing".to_string(),
+            client_secret: None,
+        }]
+    }
+
+    fn upstream(email_verified: bool) -> ExternalIdentity {
+        ExternalIdentity {
+            subject: "248289761001".to_string(),
+            email: Some("jane@example.com".to_string()),
+            email_verified,
+            name: Some("Jane Doe".to_string()),
+        }
+    }
+
+    fn pending_requests(ttl: Duration) -> MockFederatedLoginRequestRepository {
+        let requests = MockFederatedLoginRequestRepository::default();
+        let request = FederatedLoginRequest::issue(
+            "hashed:state".to_string(),
+            "corp".to_string(),
+            "nonce".to_string(),
+            "verifier".to_string(),
+            ttl,
+        );
+        requests.requests.lock().unwrap().push(request);
+        requests
+    }
+
+    async fn complete(
+        requests: &MockFederatedLoginRequestRepository,
+        client: &MockIdentityProviderClient,
+        identities: &MockUserIdentityRepository<'_>,
+    ) -> Result<LoginOutcome, AuthError> {
+        let providers = providers();
+        CompleteFederatedLoginUseCase::new(
+            &providers,
+            requests,
+            client,
+            identities.users,
+            identities,
+            &MockTokenService,
+            &MockRefreshTokenRepository::default(),
+            &MockSessionRepository,
+            &MockTokenGenerator,
+            &MockMfaRepository::default(),
+            &MockMfaChallengeRepository::default(),
+            "https://tickets.example.com/sso/callback",
+            Duration::days(30),
+            Duration::minutes(5),
+            false,
+            &MockAuditLog::default(),
+        )
+        .execute(CompleteFederatedLoginCommand {
+            state: "state".to_string(),
+            code: "upstream-code".to_string(),
+            client_ip: None,
+            user_agent: None,
+        })
+        .await
+    }
+
+    fn signed_in_user(outcome: LoginOutcome) -> Uuid {
+        match outcome {
+            LoginOutcome::Authenticated(result) => result.user_id,
+            LoginOutcome::MfaRequired { .. } => panic!("unexpected MFA challenge"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_first_login_creates_user_without_password() {
+        let users = MockUserRepository::default();
+        let identities = MockUserIdentityRepository::new(&users);
+        let client = MockIdentityProviderClient {
+            identity: upstream(true),
+        };
+
+        let user_id = signed_in_user(
+            complete(
+                &pending_requests(Duration::minutes(10)),
+                &client,
+                &identities,
+            )
+            .await
+            .unwrap(),
+        );
+
+        let user = users.find_by_id(user_id).await.unwrap();
+        assert_eq!(user.email().as_str(), "jane@example.com");
+        assert_eq!(user.display_name(), Some("Jane Doe"));
+        assert!(!user.has_password());
+        assert!(user.is_email_verified());
+        assert_eq!(identities.identities.lock().unwrap()[0].user_id(), user_id);
+
+        // The next login finds the link, whatever the email address says now
+        let client = MockIdentityProviderClient {
+            identity: ExternalIdentity {
+                email: None,
+                ..upstream(false)
+            },
+        };
+        let again = complete(
+            &pending_requests(Duration::minutes(10)),
+            &client,
+            &identities,
+        )
+        .await;
+        assert_eq!(signed_in_user(again.unwrap()), user_id);
+        assert_eq!(identities.logins.load(Ordering::SeqCst), 1);
+        assert_eq!(users.users.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_links_existing_user_by_verified_email() {
+        let email = Email::new("jane@example.com").unwrap();
+        let mut existing = User::new(email, HashedPassword::from_hash("hashed".to_string()), None);
+        let users = MockUserRepository::default();
+        users.create(&existing).await.unwrap();
+        let identities = MockUserIdentityRepository::new(&users);
+
+        // Neither side may be unverified
+        let unverified_upstream = MockIdentityProviderClient {
+            identity: upstream(false),
+        };
+        let result = complete(
+            &pending_requests(Duration::minutes(10)),
+            &unverified_upstream,
+            &identities,
+        )
+        .await;
+        assert!(matches!(result, Err(AuthError::EmailNotVerified)));
+
+        let client = MockIdentityProviderClient {
+            identity: upstream(true),
+        };
+        let result = complete(
+            &pending_requests(Duration::minutes(10)),
+            &client,
+            &identities,
+        )
+        .await;
+        assert!(matches!(result, Err(AuthError::EmailNotVerified)));
+        assert!(identities.identities.lock().unwrap().is_empty());
+
+        existing.verify_email();
+        *users.users.lock().unwrap() = vec![existing.clone()];
+        let result = complete(
+            &pending_requests(Duration::minutes(10)),
+            &client,
+            &identities,
+        )
+        .await;
+        assert_eq!(signed_in_user(result.unwrap()), existing.id().as_uuid());
+        assert_eq!(identities.identities.lock().unwrap().len(), 1);
+        assert_eq!(users.users.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_state_is_single_use_and_expires() {
+        let users = MockUserRepository::default();
+        let identities = MockUserIdentityRepository::new(&users);
+        let client = MockIdentityProviderClient {
+            identity: upstream(true),
+        };
+
+        let requests = pending_requests(Duration::minutes(10));
+        assert!(complete(&requests, &client, &identities).await.is_ok());
+        let replay = complete(&requests, &client, &identities).await;
+        assert!(matches!(replay, Err(AuthError::InvalidGrant)));
+
+        let expired = pending_requests(Duration::seconds(-1));
+        let result = complete(&expired, &client, &identities).await;
+        assert!(matches!(result, Err(AuthError::InvalidGrant)));
+        assert!(expired.requests.lock().unwrap().is_empty());
+    }
+}
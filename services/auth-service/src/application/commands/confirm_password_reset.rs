@@ -0,0 +1,350 @@
+//! Confirm password reset use case
+//!
+//! Sets a new password using a mailed reset token. The token is consumed,
+//! together with any other outstanding reset token for the account, and
+//! every refresh token of the user is revoked so existing sessions end.
+
+use chrono::Utc;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::domain::audit::{AuditAction, AuditEvent, AuditLog};
+use crate::domain::auth::{OpaqueTokenGenerator, PasswordHasher, UserRepository};
+use crate::domain::error::AuthError;
+use crate::domain::password_policy::PasswordPolicy;
+use crate::domain::password_reset::PasswordResetTokenRepository;
+use crate::domain::refresh_token::RefreshTokenRepository;
+
+/// Input for completing a password reset
+#[derive(Debug)]
+pub struct ConfirmPasswordResetCommand {
+    pub token: String,
+    pub new_password: String,
+}
+
+/// Use case for completing a password reset
+pub struct ConfirmPasswordResetUseCase<'a, R: ?Sized, P: ?Sized, S: ?Sized, H: ?Sized, G: ?Sized, L: ?Sized> {
+    user_repository: &'a R,
+    reset_token_repository: &'a P,
+    refresh_token_repository: &'a S,
+    password_hasher: &'a H,
+    password_policy: &'a PasswordPolicy,
+    token_generator: &'a G,
+    audit_log: &'a L,
+}
+
+impl<'a, R, P, S, H, G, L> ConfirmPasswordResetUseCase<'a, R, P, S, H, G, L>
+where
+    R: UserRepository + ?Sized,
+    P: PasswordResetTokenRepository + ?Sized,
+    S: RefreshTokenRepository + ?Sized,
+    H: PasswordHasher + ?Sized,
+    G: OpaqueTokenGenerator + ?Sized,
+    L: AuditLog + ?Sized,
+{
+    /// Create a new use case instance
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        user_repository: &'a R,
+        reset_token_repository: &'a P,
+        refresh_token_repository: &'a S,
+        password_hasher: &'a H,
+        password_policy: &'a PasswordPolicy,
+        token_generator: &'a G,
+        audit_log: &'a L,
+    ) -> Self {
+        Self {
+            user_repository,
+            reset_token_repository,
+            refresh_token_repository,
+            password_hasher,
+            password_policy,
+            token_generator,
+            audit_log,
+        }
+    }
+
+    /// Execute the reset
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidToken` if the token is unknown or already used
+    /// - `AuthError::TokenExpired` if the token has expired
+    /// - `AuthError::WeakPassword` if the new password breaks the password policy
+    /// - `AuthError::AccountInactive` if user account is deactivated
+    /// - `AuthError::Internal` on infrastructure failures
+    pub fn execute(&self, command: ConfirmPasswordResetCommand) -> Result<(), AuthError> {
+        let mut user_id = None;
+        let result = self.reset(command, &mut user_id);
+
+        let mut event = AuditEvent::new(AuditAction::PasswordReset).with_result(&result);
+        if let Some(user_id) = user_id {
+            event = event.with_target(user_id);
+            if result.is_ok() {
+                event = event.with_actor(user_id);
+            }
+        }
+        self.audit_log.record(event);
+        result
+    }
+
+    /// Run the reset, leaving the id of the token's account in `user_id`
+    fn reset(
+        &self,
+        command: ConfirmPasswordResetCommand,
+        user_id: &mut Option<Uuid>,
+    ) -> Result<(), AuthError> {
+        let reset_token = self
+            .reset_token_repository
+            .find_by_hash(&self.token_generator.hash(&command.token))?;
+        *user_id = Some(reset_token.user_id());
+
+        if reset_token.is_used() {
+            return Err(AuthError::InvalidToken);
+        }
+
+        if reset_token.is_expired(Utc::now()) {
+            return Err(AuthError::TokenExpired);
+        }
+
+        let mut user = self
+            .user_repository
+            .find_by_id(reset_token.user_id())
+            .map_err(|e| match e {
+                AuthError::UserNotFound => AuthError::InvalidToken,
+                other => other,
+            })?;
+
+        if !user.is_active() {
+            return Err(AuthError::AccountInactive);
+        }
+
+        self.password_policy
+            .validate(&command.new_password, user.email().as_str())?;
+
+        let hashed_password = self.password_hasher.hash(&command.new_password)?;
+
+        if !self.reset_token_repository.consume(&reset_token)? {
+            // Another request used this token first
+            return Err(AuthError::InvalidToken);
+        }
+
+        user.change_password(hashed_password);
+        self.user_repository.update(&user)?;
+
+        // Sign the user out everywhere
+        self.refresh_token_repository
+            .revoke_all_for_user(reset_token.user_id())?;
+
+        info!(user_id = %reset_token.user_id(), "Password reset completed");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use chrono::{DateTime, Duration};
+
+    use super::*;
+    use crate::application::commands::test_support::{MockAuditLog, MockPasswordHasher, MockUserRepository};
+    use crate::domain::audit::AuditOutcome;
+    use crate::domain::password_reset::PasswordResetToken;
+    use crate::domain::refresh_token::RefreshToken;
+    use crate::domain::user::{Email, HashedPassword, User};
+
+    // Reset token store holding a single token
+    struct MockResetTokenRepository {
+        token: RefCell<PasswordResetToken>,
+    }
+
+    impl PasswordResetTokenRepository for MockResetTokenRepository {
+        fn create(&self, _token: &PasswordResetToken) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn find_by_hash(&self, token_hash: &str) -> Result<PasswordResetToken, AuthError> {
+            let token = self.token.borrow();
+            if token.token_hash() == token_hash {
+                Ok(token.clone())
+            } else {
+                Err(AuthError::InvalidToken)
+            }
+        }
+
+        fn consume(&self, _token: &PasswordResetToken) -> Result<bool, AuthError> {
+            let current = self.token.borrow().clone();
+            if current.is_used() {
+                return Ok(false);
+            }
+            *self.token.borrow_mut() = PasswordResetToken::from_persistence(
+                current.id(),
+                current.user_id(),
+                current.token_hash().to_string(),
+                current.expires_at(),
+                current.created_at(),
+                Some(Utc::now()),
+            );
+            Ok(true)
+        }
+
+        fn purge_expired(&self, _now: DateTime<Utc>) -> Result<usize, AuthError> {
+            Ok(0)
+        }
+    }
+
+    // Refresh token store recording which users were signed out
+    #[derive(Default)]
+    struct MockRefreshTokenRepository {
+        revoked_users: RefCell<Vec<Uuid>>,
+    }
+
+    impl RefreshTokenRepository for MockRefreshTokenRepository {
+        fn create(&self, _token: &RefreshToken) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn find_by_hash(&self, _token_hash: &str) -> Result<RefreshToken, AuthError> {
+            Err(AuthError::InvalidToken)
+        }
+
+        fn rotate(&self, _current_id: Uuid, _next: &RefreshToken) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+
+        fn revoke_family(&self, _family_id: Uuid) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn revoke_all_for_user(&self, user_id: Uuid) -> Result<(), AuthError> {
+            self.revoked_users.borrow_mut().push(user_id);
+            Ok(())
+        }
+    }
+
+    // Mock generator hashing by prefix
+    struct MockTokenGenerator;
+
+    impl OpaqueTokenGenerator for MockTokenGenerator {
+        fn generate(&self) -> String {
+            "reset_secret".to_string()
+        }
+
+        fn hash(&self, token: &str) -> String {
+            format!("hashed_{}", token)
+        }
+    }
+
+    struct Fixture {
+        users: MockUserRepository,
+        reset_tokens: MockResetTokenRepository,
+        refresh_tokens: MockRefreshTokenRepository,
+        audit_log: MockAuditLog,
+    }
+
+    impl Fixture {
+        fn new(ttl: Duration) -> Self {
+            let email = Email::new("test@example.com").unwrap();
+            let user = User::new(
+                email,
+                HashedPassword::from_hash("hashed_old".to_string()),
+                None,
+            );
+            let token = PasswordResetToken::issue(
+                user.id().as_uuid(),
+                "hashed_reset_secret".to_string(),
+                ttl,
+            );
+            Self {
+                users: MockUserRepository::new(user),
+                reset_tokens: MockResetTokenRepository {
+                    token: RefCell::new(token),
+                },
+                refresh_tokens: MockRefreshTokenRepository::default(),
+                audit_log: MockAuditLog::default(),
+            }
+        }
+
+        fn confirm(&self, token: &str, new_password: &str) -> Result<(), AuthError> {
+            ConfirmPasswordResetUseCase::new(
+                &self.users,
+                &self.reset_tokens,
+                &self.refresh_tokens,
+                &MockPasswordHasher,
+                &PasswordPolicy::default(),
+                &MockTokenGenerator,
+                &self.audit_log,
+            )
+            .execute(ConfirmPasswordResetCommand {
+                token: token.to_string(),
+                new_password: new_password.to_string(),
+            })
+        }
+    }
+
+    #[test]
+    fn test_reset_changes_password_and_ends_sessions() {
+        let fixture = Fixture::new(Duration::hours(1));
+
+        fixture.confirm("reset_secret", "new_password").unwrap();
+
+        let user = fixture.users.user.lock().unwrap();
+        assert_eq!(user.hashed_password().unwrap().as_str(), "hashed_new_password");
+        assert!(fixture.reset_tokens.token.borrow().is_used());
+        assert_eq!(
+            *fixture.refresh_tokens.revoked_users.borrow(),
+            vec![user.id().as_uuid()]
+        );
+    }
+
+    #[test]
+    fn test_token_is_single_use() {
+        let fixture = Fixture::new(Duration::hours(1));
+
+        fixture.confirm("reset_secret", "new_password").unwrap();
+        let result = fixture.confirm("reset_secret", "another_password");
+
+        assert!(matches!(result, Err(AuthError::InvalidToken)));
+        assert_eq!(
+            fixture.users.user.lock().unwrap().hashed_password().unwrap().as_str(),
+            "hashed_new_password"
+        );
+
+        let user_id = fixture.users.user_id();
+        let events = fixture.audit_log.events.lock().unwrap();
+        assert_eq!(events[0].action(), AuditAction::PasswordReset);
+        assert_eq!(events[0].actor_id(), Some(user_id));
+        assert_eq!(events[1].outcome(), AuditOutcome::Failure);
+        assert_eq!(events[1].actor_id(), None);
+        assert_eq!(events[1].target_id(), Some(user_id));
+    }
+
+    #[test]
+    fn test_expired_token() {
+        let fixture = Fixture::new(Duration::seconds(-1));
+
+        let result = fixture.confirm("reset_secret", "new_password");
+
+        assert!(matches!(result, Err(AuthError::TokenExpired)));
+        assert!(!fixture.reset_tokens.token.borrow().is_used());
+    }
+
+    #[test]
+    fn test_weak_password_keeps_token() {
+        let fixture = Fixture::new(Duration::hours(1));
+
+        let result = fixture.confirm("reset_secret", "short");
+
+        assert!(matches!(result, Err(AuthError::WeakPassword(_))));
+        assert!(!fixture.reset_tokens.token.borrow().is_used());
+    }
+
+    #[test]
+    fn test_unknown_token() {
+        let fixture = Fixture::new(Duration::hours(1));
+
+        let result = fixture.confirm("wrong_secret", "new_password");
+
+        assert!(matches!(result, Err(AuthError::InvalidToken)));
+    }
+}
@@ -0,0 +1,297 @@
+//! Confirm TOTP enrollment use case
+//!
+//! Turns on two-factor authentication once the user proves their
+//! authenticator app produces valid codes, and hands out the recovery codes
+//! that can stand in for it. Recovery codes are shown exactly once; only
+//! their hashes are stored.
+
+use chrono::Utc;
+use tracing::info;
+
+use crate::domain::auth::{OpaqueTokenGenerator, TokenService};
+use crate::domain::error::AuthError;
+use crate::domain::mfa::{
+    normalize_recovery_code, MfaRepository, TotpService, RECOVERY_CODE_COUNT,
+};
+
+/// Input for confirming TOTP enrollment
+#[derive(Debug)]
+pub struct ConfirmTotpEnrollmentCommand {
+    /// Access token of the enrolling user
+    pub token: String,
+    /// Current code from the authenticator app
+    pub code: String,
+}
+
+/// Output after two-factor authentication is enabled
+#[derive(Debug)]
+pub struct ConfirmTotpEnrollmentResult {
+    /// Single-use codes for logging in without the authenticator
+    pub recovery_codes: Vec<String>,
+}
+
+/// Use case for confirming TOTP enrollment
+pub struct ConfirmTotpEnrollmentUseCase<'a, M: ?Sized, P: ?Sized, T: ?Sized, G: ?Sized> {
+    mfa_repository: &'a M,
+    totp_service: &'a P,
+    token_service: &'a T,
+    token_generator: &'a G,
+}
+
+impl<'a, M, P, T, G> ConfirmTotpEnrollmentUseCase<'a, M, P, T, G>
+where
+    M: MfaRepository + ?Sized,
+    P: TotpService + ?Sized,
+    T: TokenService + ?Sized,
+    G: OpaqueTokenGenerator + ?Sized,
+{
+    /// Create a new use case instance
+    pub fn new(
+        mfa_repository: &'a M,
+        totp_service: &'a P,
+        token_service: &'a T,
+        token_generator: &'a G,
+    ) -> Self {
+        Self {
+            mfa_repository,
+            totp_service,
+            token_service,
+            token_generator,
+        }
+    }
+
+    /// Execute the confirmation
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidToken` / `AuthError::TokenExpired` if the token is invalid
+    /// - `AuthError::Forbidden` for impersonation tokens
+    /// - `AuthError::MfaNotEnrolled` if enrollment was not started
+    /// - `AuthError::MfaAlreadyEnabled` if enrollment was already confirmed
+    /// - `AuthError::InvalidMfaCode` if the code is wrong
+    /// - `AuthError::Internal` on infrastructure failures
+    pub fn execute(
+        &self,
+        command: ConfirmTotpEnrollmentCommand,
+    ) -> Result<ConfirmTotpEnrollmentResult, AuthError> {
+        let token_data = self.token_service.validate_token(&command.token)?;
+        // Acting as a user does not extend to their credentials
+        if token_data.is_impersonated() {
+            return Err(AuthError::Forbidden);
+        }
+
+        let credential = self
+            .mfa_repository
+            .find_totp(token_data.user_id)?
+            .ok_or(AuthError::MfaNotEnrolled)?;
+
+        if credential.is_confirmed() {
+            return Err(AuthError::MfaAlreadyEnabled);
+        }
+
+        let step = self
+            .totp_service
+            .verify(credential.secret(), command.code.trim(), Utc::now())?
+            .ok_or(AuthError::InvalidMfaCode)?;
+
+        let recovery_codes: Vec<String> = (0..RECOVERY_CODE_COUNT)
+            .map(|_| self.totp_service.generate_recovery_code())
+            .collect();
+        let recovery_code_hashes: Vec<String> = recovery_codes
+            .iter()
+            .map(|code| self.token_generator.hash(&normalize_recovery_code(code)))
+            .collect();
+
+        if !self
+            .mfa_repository
+            .activate_totp(token_data.user_id, step, &recovery_code_hashes)?
+        {
+            // A concurrent confirmation won the race
+            return Err(AuthError::MfaAlreadyEnabled);
+        }
+
+        info!(user_id = %token_data.user_id, "TOTP two-factor authentication enabled");
+        Ok(ConfirmTotpEnrollmentResult { recovery_codes })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use chrono::DateTime;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::application::commands::test_support::token_data;
+    use crate::domain::auth::TokenData;
+    use crate::domain::mfa::TotpCredential;
+    use crate::domain::user::User;
+
+    // MFA repository recording activations
+    struct MockMfaRepository {
+        credential: RefCell<Option<TotpCredential>>,
+        recovery_code_hashes: RefCell<Vec<String>>,
+    }
+
+    impl MfaRepository for MockMfaRepository {
+        fn find_totp(&self, _user_id: Uuid) -> Result<Option<TotpCredential>, AuthError> {
+            Ok(self.credential.borrow().clone())
+        }
+
+        fn save_pending_totp(&self, _credential: &TotpCredential) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn activate_totp(
+            &self,
+            _user_id: Uuid,
+            step: i64,
+            recovery_code_hashes: &[String],
+        ) -> Result<bool, AuthError> {
+            let current = self.credential.borrow().clone();
+            match current {
+                Some(credential) if !credential.is_confirmed() => {
+                    *self.credential.borrow_mut() = Some(TotpCredential::from_persistence(
+                        credential.user_id(),
+                        credential.secret().to_string(),
+                        Some(Utc::now()),
+                        Some(step),
+                        credential.created_at(),
+                    ));
+                    *self.recovery_code_hashes.borrow_mut() = recovery_code_hashes.to_vec();
+                    Ok(true)
+                }
+                _ => Ok(false),
+            }
+        }
+    }
+
+    // TOTP service accepting "123456" in time step 42
+    struct MockTotpService;
+
+    impl TotpService for MockTotpService {
+        fn generate_secret(&self) -> String {
+            "JBSWY3DPEHPK3PXP".to_string()
+        }
+
+        fn provisioning_uri(&self, secret: &str, _account_name: &str) -> Result<String, AuthError> {
+            Ok(format!("otpauth://totp/Test?secret={}", secret))
+        }
+
+        fn verify(
+            &self,
+            _secret: &str,
+            code: &str,
+            _now: DateTime<Utc>,
+        ) -> Result<Option<i64>, AuthError> {
+            Ok((code == "123456").then_some(42))
+        }
+
+        fn generate_recovery_code(&self) -> String {
+            "abcde-fghij".to_string()
+        }
+    }
+
+    // Token service accepting any token
+    struct MockTokenService;
+
+    impl TokenService for MockTokenService {
+        fn create_token(
+            &self,
+            _user: &User,
+            _session_id: Option<Uuid>,
+        ) -> Result<String, AuthError> {
+            Ok("token".to_string())
+        }
+
+        fn validate_token(&self, _token: &str) -> Result<TokenData, AuthError> {
+            Ok(token_data(Uuid::nil()))
+        }
+
+        fn revoke_token(&self, _token: &TokenData) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn is_revoked(&self, _token: &TokenData) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+    }
+
+    // Mock generator hashing by prefix
+    struct MockTokenGenerator;
+
+    impl OpaqueTokenGenerator for MockTokenGenerator {
+        fn generate(&self) -> String {
+            "secret".to_string()
+        }
+
+        fn hash(&self, token: &str) -> String {
+            format!("hashed_{}", token)
+        }
+    }
+
+    fn pending_repo() -> MockMfaRepository {
+        MockMfaRepository {
+            credential: RefCell::new(Some(TotpCredential::new(
+                Uuid::nil(),
+                "JBSWY3DPEHPK3PXP".to_string(),
+            ))),
+            recovery_code_hashes: RefCell::new(vec![]),
+        }
+    }
+
+    fn confirm(
+        mfa: &MockMfaRepository,
+        code: &str,
+    ) -> Result<ConfirmTotpEnrollmentResult, AuthError> {
+        ConfirmTotpEnrollmentUseCase::new(
+            mfa,
+            &MockTotpService,
+            &MockTokenService,
+            &MockTokenGenerator,
+        )
+        .execute(ConfirmTotpEnrollmentCommand {
+            token: "token".to_string(),
+            code: code.to_string(),
+        })
+    }
+
+    #[test]
+    fn test_confirm_enables_mfa() {
+        let mfa = pending_repo();
+
+        let result = confirm(&mfa, " 123456 ").unwrap();
+
+        assert_eq!(result.recovery_codes.len(), RECOVERY_CODE_COUNT);
+        let credential = mfa.credential.borrow().clone().unwrap();
+        assert!(credential.is_confirmed());
+        // The confirming code cannot be replayed at login
+        assert_eq!(credential.last_used_step(), Some(42));
+        // Recovery codes are stored hashed, in canonical form
+        let hashes = mfa.recovery_code_hashes.borrow();
+        assert_eq!(hashes.len(), RECOVERY_CODE_COUNT);
+        assert_eq!(hashes[0], "hashed_ABCDEFGHIJ");
+    }
+
+    #[test]
+    fn test_wrong_code() {
+        let mfa = pending_repo();
+
+        let result = confirm(&mfa, "654321");
+
+        assert!(matches!(result, Err(AuthError::InvalidMfaCode)));
+        assert!(!mfa.credential.borrow().as_ref().unwrap().is_confirmed());
+    }
+
+    #[test]
+    fn test_not_enrolled() {
+        let mfa = MockMfaRepository {
+            credential: RefCell::new(None),
+            recovery_code_hashes: RefCell::new(vec![]),
+        };
+
+        let result = confirm(&mfa, "123456");
+
+        assert!(matches!(result, Err(AuthError::MfaNotEnrolled)));
+    }
+}
@@ -0,0 +1,390 @@
+//! Consume magic link use case
+//!
+//! Exchanges the secret from a magic link for the same tokens a password
+//! login issues. The link stands in for the password only: accounts with
+//! two-factor authentication still get an MFA challenge.
+
+use std::net::IpAddr;
+
+use chrono::{Duration, Utc};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use super::login_user::{complete_login, login_audit_event, LoginOutcome};
+use crate::domain::audit::{AsyncAuditLog, AuditAction};
+use crate::domain::auth::{AsyncUserRepository, OpaqueTokenGenerator, TokenService};
+use crate::domain::error::AuthError;
+use crate::domain::magic_link::AsyncMagicLinkTokenRepository;
+use crate::domain::mfa::{AsyncMfaChallengeRepository, AsyncMfaRepository};
+use crate::domain::refresh_token::AsyncRefreshTokenRepository;
+use crate::domain::session::{AsyncSessionRepository, SessionClient};
+
+/// Input for consuming a magic link
+#[derive(Debug)]
+pub struct ConsumeMagicLinkCommand {
+    /// Secret from the link
+    pub token: String,
+    /// Source address of the request, when known
+    pub client_ip: Option<IpAddr>,
+    /// `User-Agent` of the client, recorded with the session
+    pub user_agent: Option<String>,
+}
+
+/// Use case for consuming a magic link
+pub struct ConsumeMagicLinkUseCase<
+    'a,
+    R: ?Sized,
+    L: ?Sized,
+    T: ?Sized,
+    S: ?Sized,
+    D: ?Sized,
+    G: ?Sized,
+    M: ?Sized,
+    C: ?Sized,
+    A: ?Sized,
+> {
+    user_repository: &'a R,
+    link_repository: &'a L,
+    token_service: &'a T,
+    refresh_token_repository: &'a S,
+    session_repository: &'a D,
+    token_generator: &'a G,
+    mfa_repository: &'a M,
+    mfa_challenge_repository: &'a C,
+    refresh_token_ttl: Duration,
+    mfa_challenge_ttl: Duration,
+    require_verified_email: bool,
+    audit_log: &'a A,
+}
+
+impl<'a, R, L, T, S, D, G, M, C, A> ConsumeMagicLinkUseCase<'a, R, L, T, S, D, G, M, C, A>
+where
+    R: AsyncUserRepository + ?Sized,
+    L: AsyncMagicLinkTokenRepository + ?Sized,
+    T: TokenService + ?Sized,
+    S: AsyncRefreshTokenRepository + ?Sized,
+    D: AsyncSessionRepository + ?Sized,
+    G: OpaqueTokenGenerator + ?Sized,
+    M: AsyncMfaRepository + ?Sized,
+    C: AsyncMfaChallengeRepository + ?Sized,
+    A: AsyncAuditLog + ?Sized,
+{
+    /// Create a new use case instance
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        user_repository: &'a R,
+        link_repository: &'a L,
+        token_service: &'a T,
+        refresh_token_repository: &'a S,
+        session_repository: &'a D,
+        token_generator: &'a G,
+        mfa_repository: &'a M,
+        mfa_challenge_repository: &'a C,
+        refresh_token_ttl: Duration,
+        mfa_challenge_ttl: Duration,
+        require_verified_email: bool,
+        audit_log: &'a A,
+    ) -> Self {
+        Self {
+            user_repository,
+            link_repository,
+            token_service,
+            refresh_token_repository,
+            session_repository,
+            token_generator,
+            mfa_repository,
+            mfa_challenge_repository,
+            refresh_token_ttl,
+            mfa_challenge_ttl,
+            require_verified_email,
+            audit_log,
+        }
+    }
+
+    /// Execute the login
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidToken` if the link is unknown or already used
+    /// - `AuthError::TokenExpired` if the link has expired
+    /// - `AuthError::AccountInactive` if the user is deactivated
+    /// - `AuthError::EmailNotVerified` if verification is required and missing
+    /// - `AuthError::Internal` on infrastructure failures
+    pub async fn execute(
+        &self,
+        command: ConsumeMagicLinkCommand,
+    ) -> Result<LoginOutcome, AuthError> {
+        let mut user_id = None;
+        let result = self.consume(command, &mut user_id).await;
+        self.audit_log
+            .record(login_audit_event(
+                AuditAction::MagicLinkLogin,
+                &result,
+                user_id,
+            ))
+            .await;
+        result
+    }
+
+    /// Run the login, leaving the id of the link's account in `user_id`
+    async fn consume(
+        &self,
+        command: ConsumeMagicLinkCommand,
+        user_id: &mut Option<Uuid>,
+    ) -> Result<LoginOutcome, AuthError> {
+        let link = self
+            .link_repository
+            .find_by_hash(&self.token_generator.hash(&command.token))
+            .await?;
+        *user_id = Some(link.user_id());
+
+        if link.is_used() {
+            warn!(user_id = %link.user_id(), "Used magic link presented again");
+            return Err(AuthError::InvalidToken);
+        }
+
+        if link.is_expired(Utc::now()) {
+            return Err(AuthError::TokenExpired);
+        }
+
+        let user = self
+            .user_repository
+            .find_by_id(link.user_id())
+            .await
+            .map_err(|e| match e {
+                AuthError::UserNotFound => AuthError::InvalidToken,
+                other => other,
+            })?;
+
+        if !user.is_active() {
+            return Err(AuthError::AccountInactive);
+        }
+        if self.require_verified_email && !user.is_email_verified() {
+            return Err(AuthError::EmailNotVerified);
+        }
+
+        if !self.link_repository.consume(&link).await? {
+            // Another request used this link first
+            warn!(user_id = %link.user_id(), "Used magic link presented again");
+            return Err(AuthError::InvalidToken);
+        }
+
+        info!(user_id = %link.user_id(), "Magic link consumed");
+        let client = SessionClient {
+            user_agent: command.user_agent,
+            ip_address: command.client_ip,
+        };
+        complete_login(
+            &user,
+            &client,
+            self.token_service,
+            self.refresh_token_repository,
+            self.session_repository,
+            self.token_generator,
+            self.mfa_repository,
+            self.mfa_challenge_repository,
+            self.refresh_token_ttl,
+            self.mfa_challenge_ttl,
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::application::commands::test_support::{
+        MockAuditLog, MockMfaChallengeRepository, MockMfaRepository, MockRefreshTokenRepository,
+        MockSessionRepository, MockUserRepository,
+    };
+    use crate::domain::audit::AuditOutcome;
+    use crate::domain::auth::{BoxFuture, TokenData};
+    use crate::domain::magic_link::MagicLinkToken;
+    use crate::domain::user::{Email, HashedPassword, User};
+
+    // Link store marking links used on consumption
+    struct MockLinkRepository {
+        token: Mutex<MagicLinkToken>,
+    }
+
+    impl MockLinkRepository {
+        fn token(&self) -> MagicLinkToken {
+            self.token.lock().unwrap().clone()
+        }
+    }
+
+    impl AsyncMagicLinkTokenRepository for MockLinkRepository {
+        fn find_by_hash<'a>(
+            &'a self,
+            token_hash: &'a str,
+        ) -> BoxFuture<'a, Result<MagicLinkToken, AuthError>> {
+            let token = self.token();
+            Box::pin(async move {
+                if token.token_hash() == token_hash {
+                    Ok(token)
+                } else {
+                    Err(AuthError::InvalidToken)
+                }
+            })
+        }
+
+        fn consume<'a>(
+            &'a self,
+            _token: &'a MagicLinkToken,
+        ) -> BoxFuture<'a, Result<bool, AuthError>> {
+            let mut token = self.token.lock().unwrap();
+            let consumed = !token.is_used();
+            if consumed {
+                *token = MagicLinkToken::from_persistence(
+                    token.id(),
+                    token.user_id(),
+                    token.token_hash().to_string(),
+                    token.expires_at(),
+                    token.created_at(),
+                    Some(Utc::now()),
+                );
+            }
+            Box::pin(async move { Ok(consumed) })
+        }
+    }
+
+    // Token service naming the user in its tokens
+    struct MockTokenService;
+
+    impl TokenService for MockTokenService {
+        fn create_token(
+            &self,
+            user: &User,
+            _session_id: Option<Uuid>,
+        ) -> Result<String, AuthError> {
+            Ok(format!("access:{}", user.id().as_uuid()))
+        }
+
+        fn validate_token(&self, _token: &str) -> Result<TokenData, AuthError> {
+            Err(AuthError::InvalidToken)
+        }
+
+        fn revoke_token(&self, _token: &TokenData) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn is_revoked(&self, _token: &TokenData) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+    }
+
+    // Generator with a reversible "hash"
+    struct MockTokenGenerator;
+
+    impl OpaqueTokenGenerator for MockTokenGenerator {
+        fn generate(&self) -> String {
+            "refresh".to_string()
+        }
+
+        fn hash(&self, token: &str) -> String {
+            format!("hashed:{}", token)
+        }
+    }
+
+    fn create_user(active: bool) -> User {
+        let email = Email::new("test@example.com").unwrap();
+        let mut user = User::new(email, HashedPassword::from_hash("hashed".to_string()), None);
+        if !active {
+            user.deactivate();
+        }
+        user
+    }
+
+    fn create_links(user: &User, ttl: Duration) -> MockLinkRepository {
+        MockLinkRepository {
+            token: Mutex::new(MagicLinkToken::issue(
+                user.id().as_uuid(),
+                "hashed:link_secret".to_string(),
+                ttl,
+            )),
+        }
+    }
+
+    async fn consume(
+        repo: &MockUserRepository,
+        links: &MockLinkRepository,
+        audit_log: &MockAuditLog,
+    ) -> Result<LoginOutcome, AuthError> {
+        ConsumeMagicLinkUseCase::new(
+            repo,
+            links,
+            &MockTokenService,
+            &MockRefreshTokenRepository::default(),
+            &MockSessionRepository,
+            &MockTokenGenerator,
+            &MockMfaRepository::default(),
+            &MockMfaChallengeRepository::default(),
+            Duration::days(30),
+            Duration::minutes(5),
+            false,
+            audit_log,
+        )
+        .execute(ConsumeMagicLinkCommand {
+            token: "link_secret".to_string(),
+            client_ip: None,
+            user_agent: None,
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_link_signs_in_once() {
+        let repo = MockUserRepository::new(create_user(true));
+        let links = create_links(&repo.user.lock().unwrap(), Duration::minutes(15));
+        let audit_log = MockAuditLog::default();
+
+        match consume(&repo, &links, &audit_log).await.unwrap() {
+            LoginOutcome::Authenticated(result) => {
+                assert_eq!(result.user_id, repo.user_id());
+                assert_eq!(result.token, format!("access:{}", result.user_id));
+            }
+            LoginOutcome::MfaRequired { .. } => panic!("expected tokens"),
+        }
+
+        // Replaying the link fails
+        assert!(matches!(
+            consume(&repo, &links, &audit_log).await,
+            Err(AuthError::InvalidToken)
+        ));
+
+        let user_id = repo.user_id();
+        let events = audit_log.events.lock().unwrap();
+        assert_eq!(events[0].action(), AuditAction::MagicLinkLogin);
+        assert_eq!(events[0].outcome(), AuditOutcome::Success);
+        assert_eq!(events[0].actor_id(), Some(user_id));
+        assert_eq!(events[1].outcome(), AuditOutcome::Failure);
+        assert_eq!(events[1].actor_id(), None);
+        assert_eq!(events[1].target_id(), Some(user_id));
+    }
+
+    #[tokio::test]
+    async fn test_expired_link_is_rejected() {
+        let repo = MockUserRepository::new(create_user(true));
+        let links = create_links(&repo.user.lock().unwrap(), Duration::minutes(-1));
+        let audit_log = MockAuditLog::default();
+
+        assert!(matches!(
+            consume(&repo, &links, &audit_log).await,
+            Err(AuthError::TokenExpired)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_inactive_user_is_rejected() {
+        let repo = MockUserRepository::new(create_user(false));
+        let links = create_links(&repo.user.lock().unwrap(), Duration::minutes(15));
+        let audit_log = MockAuditLog::default();
+
+        assert!(matches!(
+            consume(&repo, &links, &audit_log).await,
+            Err(AuthError::AccountInactive)
+        ));
+        assert!(!links.token().is_used());
+    }
+}
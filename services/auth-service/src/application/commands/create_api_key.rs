@@ -0,0 +1,283 @@
+//! Create API key use case
+//!
+//! Lets a signed-in user issue a long-lived key for a machine client,
+//! limited to some of the permissions they hold. The key is returned once;
+//! only its hash is stored.
+
+use chrono::{Duration, Utc};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::domain::api_key::{key_prefix, normalize_key_name, ApiKey, ApiKeyRepository};
+use crate::domain::auth::{OpaqueTokenGenerator, TokenService, UserRepository};
+use crate::domain::error::AuthError;
+use crate::domain::role::{permissions_for, Permission};
+
+/// Input for creating an API key
+#[derive(Debug)]
+pub struct CreateApiKeyCommand {
+    /// Access token of the owner
+    pub token: String,
+    /// Name telling the owner's keys apart
+    pub name: String,
+    /// Permissions the key is limited to, e.g. `tickets:purchase`
+    pub scopes: Vec<String>,
+    /// Days until the key expires; 0 for a key that never expires
+    pub expires_in_days: u32,
+}
+
+/// A newly created key
+#[derive(Debug)]
+pub struct CreatedApiKey {
+    /// The key itself, shown to the owner only this once
+    pub key: String,
+    pub api_key: ApiKey,
+}
+
+/// Use case for creating an API key
+pub struct CreateApiKeyUseCase<'a, R: ?Sized, K: ?Sized, G: ?Sized, T: ?Sized> {
+    user_repository: &'a R,
+    api_key_repository: &'a K,
+    token_generator: &'a G,
+    token_service: &'a T,
+}
+
+impl<'a, R, K, G, T> CreateApiKeyUseCase<'a, R, K, G, T>
+where
+    R: UserRepository + ?Sized,
+    K: ApiKeyRepository + ?Sized,
+    G: OpaqueTokenGenerator + ?Sized,
+    T: TokenService + ?Sized,
+{
+    /// Create a new use case instance
+    pub fn new(
+        user_repository: &'a R,
+        api_key_repository: &'a K,
+        token_generator: &'a G,
+        token_service: &'a T,
+    ) -> Self {
+        Self {
+            user_repository,
+            api_key_repository,
+            token_generator,
+            token_service,
+        }
+    }
+
+    /// Execute the creation
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidToken` / `AuthError::TokenExpired` if the token is invalid
+    /// - `AuthError::Forbidden` if the caller authenticated with an API key or as a service
+    /// - `AuthError::AccountInactive` if user account is deactivated
+    /// - `AuthError::InvalidApiKeyName` if the name is empty or malformed
+    /// - `AuthError::InvalidScope` if no scope is given, or one is unknown or not held
+    /// - `AuthError::Internal` on infrastructure failures
+    pub fn execute(&self, command: CreateApiKeyCommand) -> Result<CreatedApiKey, AuthError> {
+        let token_data = self.token_service.validate_token(&command.token)?;
+        // Neither keys nor services may mint keys
+        if !token_data.is_session() {
+            return Err(AuthError::Forbidden);
+        }
+
+        let user = self.user_repository.find_by_id(token_data.user_id)?;
+        if !user.is_active() {
+            return Err(AuthError::AccountInactive);
+        }
+
+        let name = normalize_key_name(&command.name)?;
+        let held = permissions_for(user.roles());
+        let scopes = command
+            .scopes
+            .iter()
+            .map(|scope| scope.parse::<Permission>())
+            .collect::<Result<Vec<_>, _>>()?;
+        if scopes.is_empty() || scopes.iter().any(|scope| !held.contains(scope)) {
+            return Err(AuthError::InvalidScope);
+        }
+
+        let expires_at = match command.expires_in_days {
+            0 => None,
+            days => Some(Utc::now() + Duration::days(i64::from(days))),
+        };
+
+        let id = Uuid::new_v4();
+        let key = format!("{}_{}", key_prefix(id), self.token_generator.generate());
+        let api_key = ApiKey::issue(
+            id,
+            token_data.user_id,
+            name,
+            self.token_generator.hash(&key),
+            scopes,
+            expires_at,
+        );
+        self.api_key_repository.create(&api_key)?;
+
+        info!(user_id = %token_data.user_id, key_id = %id, "API key created");
+        Ok(CreatedApiKey { key, api_key })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use chrono::DateTime;
+
+    use super::*;
+    use crate::application::commands::test_support::{token_data, MockUserRepository};
+    use crate::domain::api_key::is_api_key;
+    use crate::domain::auth::TokenData;
+    use crate::domain::role::Role;
+    use crate::domain::user::{Email, HashedPassword, User};
+
+    // Key store recording created keys
+    #[derive(Default)]
+    struct MockApiKeyRepository {
+        keys: RefCell<Vec<ApiKey>>,
+    }
+
+    impl ApiKeyRepository for MockApiKeyRepository {
+        fn create(&self, key: &ApiKey) -> Result<(), AuthError> {
+            self.keys.borrow_mut().push(key.clone());
+            Ok(())
+        }
+
+        fn find_by_hash(&self, _key_hash: &str) -> Result<ApiKey, AuthError> {
+            Err(AuthError::InvalidToken)
+        }
+
+        fn find_by_id(&self, _id: Uuid) -> Result<ApiKey, AuthError> {
+            Err(AuthError::ApiKeyNotFound)
+        }
+
+        fn list_for_user(&self, _user_id: Uuid) -> Result<Vec<ApiKey>, AuthError> {
+            Ok(self.keys.borrow().clone())
+        }
+
+        fn revoke(&self, _id: Uuid) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+
+        fn record_use(&self, _id: Uuid, _at: DateTime<Utc>) -> Result<(), AuthError> {
+            Ok(())
+        }
+    }
+
+    // Generator with a fixed secret and a reversible "hash"
+    struct MockTokenGenerator;
+
+    impl OpaqueTokenGenerator for MockTokenGenerator {
+        fn generate(&self) -> String {
+            "secret".to_string()
+        }
+
+        fn hash(&self, token: &str) -> String {
+            format!("hashed:{}", token)
+        }
+    }
+
+    // Token service accepting "session" and "key" tokens for the stored user
+    struct MockTokenService {
+        user_id: Uuid,
+    }
+
+    impl TokenService for MockTokenService {
+        fn create_token(
+            &self,
+            _user: &User,
+            _session_id: Option<Uuid>,
+        ) -> Result<String, AuthError> {
+            Ok("token".to_string())
+        }
+
+        fn validate_token(&self, token: &str) -> Result<TokenData, AuthError> {
+            let scopes = match token {
+                "session" => None,
+                "key" => Some(vec![Permission::PurchaseTickets]),
+                _ => return Err(AuthError::InvalidToken),
+            };
+            Ok(TokenData {
+                roles: vec![Role::Organizer],
+                scopes,
+                ..token_data(self.user_id)
+            })
+        }
+
+        fn revoke_token(&self, _token: &TokenData) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn is_revoked(&self, _token: &TokenData) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+    }
+
+    fn setup() -> (MockUserRepository, MockTokenService) {
+        let email = Email::new("organizer@example.com").unwrap();
+        let mut user = User::new(email, HashedPassword::from_hash("hashed".to_string()), None);
+        user.assign_role(Role::Organizer);
+        let token_service = MockTokenService {
+            user_id: user.id().as_uuid(),
+        };
+        (MockUserRepository::new(user), token_service)
+    }
+
+    fn command(token: &str, scopes: &[&str]) -> CreateApiKeyCommand {
+        CreateApiKeyCommand {
+            token: token.to_string(),
+            name: " Box office ".to_string(),
+            scopes: scopes.iter().map(|scope| scope.to_string()).collect(),
+            expires_in_days: 30,
+        }
+    }
+
+    #[test]
+    fn test_create_api_key() {
+        let (users, token_service) = setup();
+        let keys = MockApiKeyRepository::default();
+        let use_case = CreateApiKeyUseCase::new(&users, &keys, &MockTokenGenerator, &token_service);
+
+        let created = use_case
+            .execute(command("session", &["events:manage", "tickets:purchase"]))
+            .unwrap();
+
+        assert!(is_api_key(&created.key));
+        assert!(created.key.starts_with(created.api_key.prefix()));
+        assert_eq!(created.api_key.name(), "Box office");
+        assert_eq!(
+            created.api_key.scopes(),
+            &[Permission::PurchaseTickets, Permission::ManageEvents]
+        );
+        assert_eq!(
+            created.api_key.key_hash(),
+            format!("hashed:{}", created.key)
+        );
+        assert!(created.api_key.expires_at().is_some());
+        assert_eq!(keys.keys.borrow().len(), 1);
+    }
+
+    #[test]
+    fn test_scopes_must_be_held_by_owner() {
+        let (users, token_service) = setup();
+        let keys = MockApiKeyRepository::default();
+        let use_case = CreateApiKeyUseCase::new(&users, &keys, &MockTokenGenerator, &token_service);
+
+        for scopes in [&[][..], &["users:manage"], &["tickets:refund"]] {
+            let result = use_case.execute(command("session", scopes));
+            assert!(matches!(result, Err(AuthError::InvalidScope)));
+        }
+        assert!(keys.keys.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_api_key_cannot_create_keys() {
+        let (users, token_service) = setup();
+        let keys = MockApiKeyRepository::default();
+        let use_case = CreateApiKeyUseCase::new(&users, &keys, &MockTokenGenerator, &token_service);
+
+        let result = use_case.execute(command("key", &["tickets:purchase"]));
+
+        assert!(matches!(result, Err(AuthError::Forbidden)));
+    }
+}
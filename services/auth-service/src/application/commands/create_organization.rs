@@ -0,0 +1,244 @@
+//! Create organization use case
+//!
+//! Lets a signed-in user found an organization. The founder becomes its
+//! owner and the only member until they invite others.
+
+use tracing::info;
+
+use crate::domain::audit::{AuditAction, AuditEvent, AuditLog};
+use crate::domain::auth::{TokenData, TokenService, UserRepository};
+use crate::domain::error::AuthError;
+use crate::domain::organization::{
+    normalize_organization_name, Membership, OrgRole, Organization, OrganizationRepository,
+};
+
+/// Input for creating an organization
+#[derive(Debug)]
+pub struct CreateOrganizationCommand {
+    /// Access token of the founder
+    pub token: String,
+    pub name: String,
+}
+
+/// Use case for creating an organization
+pub struct CreateOrganizationUseCase<'a, R: ?Sized, O: ?Sized, T: ?Sized, L: ?Sized> {
+    user_repository: &'a R,
+    organization_repository: &'a O,
+    token_service: &'a T,
+    audit_log: &'a L,
+}
+
+impl<'a, R, O, T, L> CreateOrganizationUseCase<'a, R, O, T, L>
+where
+    R: UserRepository + ?Sized,
+    O: OrganizationRepository + ?Sized,
+    T: TokenService + ?Sized,
+    L: AuditLog + ?Sized,
+{
+    /// Create a new use case instance
+    pub fn new(
+        user_repository: &'a R,
+        organization_repository: &'a O,
+        token_service: &'a T,
+        audit_log: &'a L,
+    ) -> Self {
+        Self {
+            user_repository,
+            organization_repository,
+            token_service,
+            audit_log,
+        }
+    }
+
+    /// Execute the creation, returning the organization and the founder's
+    /// membership
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidToken` / `AuthError::TokenExpired` if the token is invalid
+    /// - `AuthError::Forbidden` if the caller is not signed in as themselves
+    /// - `AuthError::AccountInactive` if user account is deactivated
+    /// - `AuthError::InvalidOrganizationName` if the name is empty or malformed
+    /// - `AuthError::Internal` on infrastructure failures
+    pub fn execute(
+        &self,
+        command: CreateOrganizationCommand,
+    ) -> Result<(Organization, Membership), AuthError> {
+        let token_data = self.token_service.validate_token(&command.token)?;
+
+        let result = self.create(&token_data, &command.name);
+        let mut event = AuditEvent::new(AuditAction::OrganizationCreation)
+            .with_actor(token_data.audit_actor())
+            .with_result(&result);
+        if let Ok((organization, _)) = &result {
+            event = event.with_detail(organization.id().to_string());
+        }
+        self.audit_log.record(event);
+        result
+    }
+
+    fn create(
+        &self,
+        token_data: &TokenData,
+        name: &str,
+    ) -> Result<(Organization, Membership), AuthError> {
+        // Keys, services and impersonations act for an existing user only
+        if !token_data.is_session() {
+            return Err(AuthError::Forbidden);
+        }
+
+        let user = self.user_repository.find_by_id(token_data.user_id)?;
+        if !user.is_active() {
+            return Err(AuthError::AccountInactive);
+        }
+
+        let organization = Organization::new(normalize_organization_name(name)?);
+        let owner = Membership::new(organization.id(), token_data.user_id, OrgRole::Owner);
+        self.organization_repository.create(&organization, &owner)?;
+
+        info!(
+            user_id = %token_data.user_id,
+            organization_id = %organization.id(),
+            "Organization created"
+        );
+        Ok((organization, owner))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::application::commands::test_support::{token_data, MockAuditLog, MockUserRepository};
+    use crate::domain::audit::AuditOutcome;
+    use crate::domain::user::{Email, HashedPassword, User};
+
+    // Organization store recording created organizations and owners
+    #[derive(Default)]
+    struct MockOrganizationRepository {
+        created: RefCell<Vec<(Organization, Membership)>>,
+    }
+
+    impl OrganizationRepository for MockOrganizationRepository {
+        fn create(&self, organization: &Organization, owner: &Membership) -> Result<(), AuthError> {
+            self.created
+                .borrow_mut()
+                .push((organization.clone(), owner.clone()));
+            Ok(())
+        }
+
+        fn find_by_id(&self, _id: Uuid) -> Result<Organization, AuthError> {
+            Err(AuthError::OrganizationNotFound)
+        }
+
+        fn find_membership(
+            &self,
+            _organization_id: Uuid,
+            _user_id: Uuid,
+        ) -> Result<Option<Membership>, AuthError> {
+            Ok(None)
+        }
+
+        fn list_for_user(
+            &self,
+            _user_id: Uuid,
+        ) -> Result<Vec<(Organization, Membership)>, AuthError> {
+            Ok(Vec::new())
+        }
+    }
+
+    // Token service accepting "session" and "key" tokens for one user
+    struct MockTokenService {
+        user_id: Uuid,
+    }
+
+    impl TokenService for MockTokenService {
+        fn create_token(
+            &self,
+            _user: &User,
+            _session_id: Option<Uuid>,
+        ) -> Result<String, AuthError> {
+            Ok("token".to_string())
+        }
+
+        fn validate_token(&self, token: &str) -> Result<TokenData, AuthError> {
+            let scopes = match token {
+                "session" => None,
+                "key" => Some(vec![]),
+                _ => return Err(AuthError::InvalidToken),
+            };
+            Ok(TokenData {
+                scopes,
+                ..token_data(self.user_id)
+            })
+        }
+
+        fn revoke_token(&self, _token: &TokenData) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn is_revoked(&self, _token: &TokenData) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+    }
+
+    fn founder() -> User {
+        let email = Email::new("founder@example.com").unwrap();
+        User::new(email, HashedPassword::from_hash("hashed".to_string()), None)
+    }
+
+    #[test]
+    fn test_founder_becomes_owner() {
+        let user = founder();
+        let user_id = user.id().as_uuid();
+        let users = MockUserRepository::new(user);
+        let organizations = MockOrganizationRepository::default();
+        let tokens = MockTokenService { user_id };
+        let audit_log = MockAuditLog::default();
+        let use_case = CreateOrganizationUseCase::new(&users, &organizations, &tokens, &audit_log);
+
+        let (organization, owner) = use_case
+            .execute(CreateOrganizationCommand {
+                token: "session".to_string(),
+                name: "  Riverside Concerts ".to_string(),
+            })
+            .unwrap();
+
+        assert_eq!(organization.name(), "Riverside Concerts");
+        assert_eq!(owner.role(), OrgRole::Owner);
+        let created = organizations.created.borrow();
+        assert_eq!(created.len(), 1);
+        assert_eq!(created[0].1.user_id(), user_id);
+        assert_eq!(created[0].1.role(), OrgRole::Owner);
+        assert_eq!(created[0].1.organization_id(), organization.id());
+
+        let events = audit_log.events.lock().unwrap();
+        assert_eq!(events[0].action(), AuditAction::OrganizationCreation);
+        assert_eq!(events[0].outcome(), AuditOutcome::Success);
+        assert_eq!(
+            events[0].detail(),
+            Some(organization.id().to_string().as_str())
+        );
+    }
+
+    #[test]
+    fn test_api_key_cannot_create_organization() {
+        let user = founder();
+        let user_id = user.id().as_uuid();
+        let users = MockUserRepository::new(user);
+        let organizations = MockOrganizationRepository::default();
+        let tokens = MockTokenService { user_id };
+        let audit_log = MockAuditLog::default();
+        let use_case = CreateOrganizationUseCase::new(&users, &organizations, &tokens, &audit_log);
+
+        let result = use_case.execute(CreateOrganizationCommand {
+            token: "key".to_string(),
+            name: "Riverside Concerts".to_string(),
+        });
+
+        assert!(matches!(result, Err(AuthError::Forbidden)));
+        assert!(organizations.created.borrow().is_empty());
+    }
+}
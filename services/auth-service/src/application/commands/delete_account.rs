@@ -0,0 +1,544 @@
+//! Delete account use case
+//!
+//! Erases a user's personal data. The `users` row is anonymised rather than
+//! deleted so ids held by other services stay valid, every credential is
+//! removed and a `user.erased` event, stored in the same transaction, tells
+//! other services to erase their copies. Users delete their own account by confirming their password, or
+//! with a recent login if they have none; deleting anyone else's requires
+//! the `users:manage` permission.
+
+use tracing::info;
+use uuid::Uuid;
+
+use super::change_password::reauthenticate;
+use crate::domain::audit::{AuditAction, AuditEvent, AuditLog};
+use crate::domain::auth::{PasswordHasher, TokenData, TokenService, UserRepository};
+use crate::domain::error::AuthError;
+use crate::domain::lockout::{LockoutPolicy, LoginThrottleRepository};
+use crate::domain::personal_data::PersonalDataRepository;
+use crate::domain::role::{authorize, Permission};
+use crate::domain::session::SessionRepository;
+
+/// Input for deleting an account
+#[derive(Debug)]
+pub struct DeleteAccountCommand {
+    /// Access token of the caller
+    pub token: String,
+    /// Account to delete; `None` for the caller's own
+    pub user_id: Option<Uuid>,
+    /// Password of the caller, required to delete their own account if
+    /// they have one
+    pub current_password: Option<String>,
+}
+
+/// Use case for deleting an account
+pub struct DeleteAccountUseCase<
+    'a,
+    R: ?Sized,
+    P: ?Sized,
+    H: ?Sized,
+    T: ?Sized,
+    S: ?Sized,
+    L: ?Sized,
+    A: ?Sized,
+> {
+    user_repository: &'a R,
+    personal_data_repository: &'a P,
+    password_hasher: &'a H,
+    token_service: &'a T,
+    session_repository: &'a S,
+    throttle_repository: &'a L,
+    lockout_policy: LockoutPolicy,
+    audit_log: &'a A,
+}
+
+impl<'a, R, P, H, T, S, L, A> DeleteAccountUseCase<'a, R, P, H, T, S, L, A>
+where
+    R: UserRepository + ?Sized,
+    P: PersonalDataRepository + ?Sized,
+    H: PasswordHasher + ?Sized,
+    T: TokenService + ?Sized,
+    S: SessionRepository + ?Sized,
+    L: LoginThrottleRepository + ?Sized,
+    A: AuditLog + ?Sized,
+{
+    /// Create a new use case instance
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        user_repository: &'a R,
+        personal_data_repository: &'a P,
+        password_hasher: &'a H,
+        token_service: &'a T,
+        session_repository: &'a S,
+        throttle_repository: &'a L,
+        lockout_policy: LockoutPolicy,
+        audit_log: &'a A,
+    ) -> Self {
+        Self {
+            user_repository,
+            personal_data_repository,
+            password_hasher,
+            token_service,
+            session_repository,
+            throttle_repository,
+            lockout_policy,
+            audit_log,
+        }
+    }
+
+    /// Execute the deletion
+    ///
+    /// Deleting an account that was already erased changes nothing.
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidToken` / `AuthError::TokenExpired` if the token is invalid
+    /// - `AuthError::Forbidden` if the caller may not delete another user's account,
+    ///   or acts as the user through an impersonation token
+    /// - `AuthError::UserNotFound` if the user does not exist
+    /// - `AuthError::AccountInactive` if the caller's own account is deactivated
+    /// - `AuthError::AccountLocked` after too many failed attempts
+    /// - `AuthError::InvalidCredentials` if the password is missing or wrong
+    /// - `AuthError::RecentLoginRequired` if the caller has no password and
+    ///   signed in too long ago
+    /// - `AuthError::Internal` on infrastructure failures
+    pub fn execute(&self, command: DeleteAccountCommand) -> Result<(), AuthError> {
+        let actor = self.token_service.validate_token(&command.token)?;
+        let user_id = command.user_id.unwrap_or(actor.user_id);
+        let self_service = user_id == actor.user_id;
+
+        let result = self.delete(&actor, user_id, command.current_password.as_deref());
+        let action = if self_service {
+            AuditAction::AccountDeletion
+        } else {
+            AuditAction::UserErasure
+        };
+        self.audit_log.record(
+            AuditEvent::new(action)
+                .with_actor(actor.audit_actor())
+                .with_target(user_id)
+                .with_result(&result),
+        );
+        result
+    }
+
+    fn delete(
+        &self,
+        actor: &TokenData,
+        user_id: Uuid,
+        current_password: Option<&str>,
+    ) -> Result<(), AuthError> {
+        let self_service = user_id == actor.user_id;
+        if !self_service {
+            authorize(actor, Permission::ManageUsers)?;
+        } else if actor.is_impersonated() {
+            return Err(AuthError::Forbidden);
+        }
+
+        let mut user = self.user_repository.find_by_id(user_id)?;
+
+        if !user.is_erased() {
+            if self_service {
+                reauthenticate(
+                    &user,
+                    actor,
+                    current_password,
+                    self.password_hasher,
+                    self.session_repository,
+                    self.throttle_repository,
+                    &self.lockout_policy,
+                )?;
+            }
+
+            let previous_email = user.email().as_str().to_string();
+            user.erase();
+            self.personal_data_repository
+                .erase(&user, &previous_email)?;
+
+            info!(user_id = %user_id, actor_id = %actor.user_id, "Account erased");
+        }
+
+        // The caller's own access token is no use any more
+        if self_service {
+            self.token_service.revoke_token(actor)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use chrono::{DateTime, Duration, Utc};
+
+    use super::*;
+    use crate::application::commands::test_support::{
+        token_data, MockAuditLog, MockPasswordHasher, MockUserRepository,
+    };
+    use crate::domain::audit::AuditOutcome;
+    use crate::domain::events::AuthEvent;
+    use crate::domain::federation::UserIdentity;
+    use crate::domain::lockout::{LoginThrottle, ThrottleKey};
+    use crate::domain::organization::{Membership, Organization};
+    use crate::domain::personal_data::SessionRecord;
+    use crate::domain::role::Role;
+    use crate::domain::session::Session;
+    use crate::domain::user::{Email, HashedPassword, User};
+
+    // Personal data store writing erased users back to the user repository,
+    // storing their new events and recording the addresses whose throttles
+    // were dropped
+    struct MockPersonalDataRepository<'a> {
+        users: &'a MockUserRepository,
+        outbox: &'a MockOutbox,
+        erased_emails: RefCell<Vec<String>>,
+    }
+
+    impl PersonalDataRepository for MockPersonalDataRepository<'_> {
+        fn find_sessions(&self, _user_id: Uuid) -> Result<Vec<SessionRecord>, AuthError> {
+            Ok(vec![])
+        }
+
+        fn find_identities(&self, _user_id: Uuid) -> Result<Vec<UserIdentity>, AuthError> {
+            Ok(vec![])
+        }
+
+        fn find_organizations(
+            &self,
+            _user_id: Uuid,
+        ) -> Result<Vec<(Organization, Membership)>, AuthError> {
+            Ok(vec![])
+        }
+
+        fn find_audit_events(&self, _user_id: Uuid) -> Result<Vec<AuditEvent>, AuthError> {
+            Ok(vec![])
+        }
+
+        fn erase(&self, user: &User, previous_email: &str) -> Result<(), AuthError> {
+            // Events raised since the user was loaded
+            let loaded = self.users.user.lock().unwrap().pending_events().len();
+            self.outbox
+                .events
+                .borrow_mut()
+                .extend_from_slice(&user.pending_events()[loaded..]);
+            self.users.update(user)?;
+            self.erased_emails
+                .borrow_mut()
+                .push(previous_email.to_string());
+            Ok(())
+        }
+    }
+
+    // Token service mapping "admin" and "support" tokens to roles; the
+    // "self" token belongs to the stored user. Revoked tokens are recorded.
+    struct MockTokenService {
+        self_id: Uuid,
+        revoked: RefCell<Vec<Uuid>>,
+    }
+
+    impl TokenService for MockTokenService {
+        fn create_token(
+            &self,
+            _user: &User,
+            _session_id: Option<Uuid>,
+        ) -> Result<String, AuthError> {
+            Ok("token".to_string())
+        }
+
+        fn validate_token(&self, token: &str) -> Result<TokenData, AuthError> {
+            let (user_id, roles) = match token {
+                "admin" => (Uuid::new_v4(), vec![Role::Admin]),
+                "support" => (Uuid::new_v4(), vec![Role::SupportAgent]),
+                "self" => (self.self_id, vec![Role::Customer]),
+                _ => return Err(AuthError::InvalidToken),
+            };
+            Ok(TokenData {
+                session_id: Some(Uuid::new_v4()),
+                roles,
+                ..token_data(user_id)
+            })
+        }
+
+        fn revoke_token(&self, token: &TokenData) -> Result<(), AuthError> {
+            self.revoked.borrow_mut().push(token.user_id);
+            Ok(())
+        }
+
+        fn is_revoked(&self, _token: &TokenData) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+    }
+
+    // Session store where every session started a minute ago
+    struct MockSessionRepository;
+
+    impl SessionRepository for MockSessionRepository {
+        fn create(&self, _session: &Session) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn find_by_id(&self, id: Uuid) -> Result<Session, AuthError> {
+            let started_at = Utc::now() - Duration::minutes(1);
+            Ok(Session::from_persistence(
+                id,
+                Uuid::new_v4(),
+                None,
+                None,
+                started_at,
+                started_at,
+                started_at + Duration::days(30),
+                None,
+                None,
+            ))
+        }
+
+        fn find_active_for_user(
+            &self,
+            _user_id: Uuid,
+            _now: DateTime<Utc>,
+        ) -> Result<Vec<Session>, AuthError> {
+            Ok(Vec::new())
+        }
+
+        fn touch(
+            &self,
+            _id: Uuid,
+            _now: DateTime<Utc>,
+            _expires_at: DateTime<Utc>,
+        ) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn set_organization(
+            &self,
+            _id: Uuid,
+            _organization_id: Option<Uuid>,
+        ) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn is_revoked(&self, _id: Uuid) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+    }
+
+    // Failure counters that never lock
+    struct MockLoginThrottleRepository;
+
+    impl LoginThrottleRepository for MockLoginThrottleRepository {
+        fn find(&self, _key: &ThrottleKey) -> Result<Option<LoginThrottle>, AuthError> {
+            Ok(None)
+        }
+
+        fn record_failure(
+            &self,
+            key: &ThrottleKey,
+            _policy: &LockoutPolicy,
+            now: DateTime<Utc>,
+        ) -> Result<LoginThrottle, AuthError> {
+            Ok(LoginThrottle::new(key.clone(), now))
+        }
+
+        fn clear(&self, _key: &ThrottleKey) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+
+        fn purge_stale(&self, _cutoff: DateTime<Utc>) -> Result<usize, AuthError> {
+            Ok(0)
+        }
+    }
+
+    // Outbox receiving the events stored with an erasure
+    #[derive(Default)]
+    struct MockOutbox {
+        events: RefCell<Vec<AuthEvent>>,
+    }
+
+    fn setup() -> (MockUserRepository, MockTokenService) {
+        let email = Email::new("test@example.com").unwrap();
+        let user = User::new(
+            email,
+            HashedPassword::from_hash("hashed_password".to_string()),
+            Some("Test User".to_string()),
+        );
+        let token_service = MockTokenService {
+            self_id: user.id().as_uuid(),
+            revoked: RefCell::new(vec![]),
+        };
+        (
+            MockUserRepository::new(user),
+            token_service,
+        )
+    }
+
+    fn delete(
+        users: &MockUserRepository,
+        token_service: &MockTokenService,
+        events: &MockOutbox,
+        audit_log: &MockAuditLog,
+        command: DeleteAccountCommand,
+    ) -> Result<Vec<String>, AuthError> {
+        let personal_data = MockPersonalDataRepository {
+            users,
+            outbox: events,
+            erased_emails: RefCell::new(vec![]),
+        };
+        DeleteAccountUseCase::new(
+            users,
+            &personal_data,
+            &MockPasswordHasher,
+            token_service,
+            &MockSessionRepository,
+            &MockLoginThrottleRepository,
+            LockoutPolicy {
+                email_threshold: 5,
+                ip_threshold: 20,
+                base_lockout: Duration::seconds(30),
+                max_lockout: Duration::minutes(10),
+                reset_after: Duration::minutes(15),
+            },
+            audit_log,
+        )
+        .execute(command)?;
+        Ok(personal_data.erased_emails.into_inner())
+    }
+
+    #[test]
+    fn test_user_deletes_own_account() {
+        let (users, token_service) = setup();
+        let events = MockOutbox::default();
+        let audit_log = MockAuditLog::default();
+        let user_id = users.user_id();
+
+        let erased_emails = delete(
+            &users,
+            &token_service,
+            &events,
+            &audit_log,
+            DeleteAccountCommand {
+                token: "self".to_string(),
+                user_id: None,
+                current_password: Some("password".to_string()),
+            },
+        )
+        .unwrap();
+
+        let user = users.user.lock().unwrap();
+        assert!(user.is_erased());
+        assert_eq!(user.id().as_uuid(), user_id);
+        assert_eq!(user.display_name(), None);
+        assert_eq!(erased_emails, vec!["test@example.com".to_string()]);
+        assert_eq!(
+            *events.events.borrow(),
+            vec![AuthEvent::UserErased {
+                user_id,
+                erased_at: user.updated_at(),
+            }]
+        );
+        assert_eq!(*token_service.revoked.borrow(), vec![user_id]);
+    }
+
+    #[test]
+    fn test_self_service_requires_password() {
+        let (users, token_service) = setup();
+        let events = MockOutbox::default();
+        let audit_log = MockAuditLog::default();
+
+        for password in [None, Some("wrong".to_string())] {
+            let result = delete(
+                &users,
+                &token_service,
+                &events,
+                &audit_log,
+                DeleteAccountCommand {
+                    token: "self".to_string(),
+                    user_id: None,
+                    current_password: password,
+                },
+            );
+            assert!(matches!(result, Err(AuthError::InvalidCredentials)));
+        }
+
+        assert!(!users.user.lock().unwrap().is_erased());
+        assert!(events.events.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_user_without_password_deletes_after_recent_login() {
+        let email = Email::new("test@example.com").unwrap();
+        let user = User::new_federated(email, None);
+        let token_service = MockTokenService {
+            self_id: user.id().as_uuid(),
+            revoked: RefCell::new(vec![]),
+        };
+        let users = MockUserRepository::new(user);
+
+        delete(
+            &users,
+            &token_service,
+            &MockOutbox::default(),
+            &MockAuditLog::default(),
+            DeleteAccountCommand {
+                token: "self".to_string(),
+                user_id: None,
+                current_password: None,
+            },
+        )
+        .unwrap();
+
+        assert!(users.user.lock().unwrap().is_erased());
+    }
+
+    #[test]
+    fn test_admin_deletes_account_without_password() {
+        let (users, token_service) = setup();
+        let events = MockOutbox::default();
+        let audit_log = MockAuditLog::default();
+        let user_id = Some(users.user_id());
+
+        let result = delete(
+            &users,
+            &token_service,
+            &events,
+            &audit_log,
+            DeleteAccountCommand {
+                token: "support".to_string(),
+                user_id,
+                current_password: None,
+            },
+        );
+        assert!(matches!(result, Err(AuthError::Forbidden)));
+        assert!(!users.user.lock().unwrap().is_erased());
+
+        for _ in 0..2 {
+            delete(
+                &users,
+                &token_service,
+                &events,
+                &audit_log,
+                DeleteAccountCommand {
+                    token: "admin".to_string(),
+                    user_id,
+                    current_password: None,
+                },
+            )
+            .unwrap();
+        }
+
+        // Repeating the deletion changes nothing
+        assert!(users.user.lock().unwrap().is_erased());
+        let events = events.events.borrow();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].name(), "user.erased");
+        assert!(token_service.revoked.borrow().is_empty());
+
+        let audited = audit_log.events.lock().unwrap();
+        assert_eq!(audited.len(), 3);
+        assert!(audited
+            .iter()
+            .all(|e| e.action() == AuditAction::UserErasure && e.target_id() == user_id));
+        assert_eq!(audited[0].outcome(), AuditOutcome::Failure);
+        assert_eq!(audited[1].outcome(), AuditOutcome::Success);
+    }
+}
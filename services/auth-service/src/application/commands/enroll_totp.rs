@@ -0,0 +1,262 @@
+//! Enroll TOTP use case
+//!
+//! Starts two-factor enrollment for the calling user by generating a new
+//! authenticator secret. The enrollment stays pending, and logins are
+//! unaffected, until it is confirmed with a first code.
+
+use tracing::info;
+
+use crate::domain::auth::{TokenService, UserRepository};
+use crate::domain::error::AuthError;
+use crate::domain::mfa::{MfaRepository, TotpCredential, TotpService};
+
+/// Input for starting TOTP enrollment
+#[derive(Debug)]
+pub struct EnrollTotpCommand {
+    /// Access token of the enrolling user
+    pub token: String,
+}
+
+/// Output after starting enrollment
+#[derive(Debug)]
+pub struct EnrollTotpResult {
+    /// Base32 secret, for entering into an authenticator app by hand
+    pub secret: String,
+    /// `otpauth://` URI, usually rendered as a QR code
+    pub otpauth_uri: String,
+}
+
+/// Use case for starting TOTP enrollment
+pub struct EnrollTotpUseCase<'a, R: ?Sized, M: ?Sized, P: ?Sized, T: ?Sized> {
+    user_repository: &'a R,
+    mfa_repository: &'a M,
+    totp_service: &'a P,
+    token_service: &'a T,
+}
+
+impl<'a, R, M, P, T> EnrollTotpUseCase<'a, R, M, P, T>
+where
+    R: UserRepository + ?Sized,
+    M: MfaRepository + ?Sized,
+    P: TotpService + ?Sized,
+    T: TokenService + ?Sized,
+{
+    /// Create a new use case instance
+    pub fn new(
+        user_repository: &'a R,
+        mfa_repository: &'a M,
+        totp_service: &'a P,
+        token_service: &'a T,
+    ) -> Self {
+        Self {
+            user_repository,
+            mfa_repository,
+            totp_service,
+            token_service,
+        }
+    }
+
+    /// Execute the enrollment
+    ///
+    /// Starting over replaces an earlier, unconfirmed secret.
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidToken` / `AuthError::TokenExpired` if the token is invalid
+    /// - `AuthError::Forbidden` for impersonation tokens
+    /// - `AuthError::AccountInactive` if user account is deactivated
+    /// - `AuthError::MfaAlreadyEnabled` if the user already confirmed an authenticator
+    /// - `AuthError::Internal` on infrastructure failures
+    pub fn execute(&self, command: EnrollTotpCommand) -> Result<EnrollTotpResult, AuthError> {
+        let token_data = self.token_service.validate_token(&command.token)?;
+        // Acting as a user does not extend to their credentials
+        if token_data.is_impersonated() {
+            return Err(AuthError::Forbidden);
+        }
+        let user = self.user_repository.find_by_id(token_data.user_id)?;
+
+        if !user.is_active() {
+            return Err(AuthError::AccountInactive);
+        }
+
+        if self
+            .mfa_repository
+            .find_totp(token_data.user_id)?
+            .is_some_and(|credential| credential.is_confirmed())
+        {
+            return Err(AuthError::MfaAlreadyEnabled);
+        }
+
+        let secret = self.totp_service.generate_secret();
+        let otpauth_uri = self
+            .totp_service
+            .provisioning_uri(&secret, user.email().as_str())?;
+
+        self.mfa_repository
+            .save_pending_totp(&TotpCredential::new(token_data.user_id, secret.clone()))?;
+
+        info!(user_id = %token_data.user_id, "TOTP enrollment started");
+        Ok(EnrollTotpResult {
+            secret,
+            otpauth_uri,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use chrono::{DateTime, Utc};
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::application::commands::test_support::{token_data, MockUserRepository};
+    use crate::domain::auth::TokenData;
+    use crate::domain::user::{Email, HashedPassword, User};
+
+    // MFA repository holding at most one credential
+    #[derive(Default)]
+    struct MockMfaRepository {
+        credential: RefCell<Option<TotpCredential>>,
+    }
+
+    impl MfaRepository for MockMfaRepository {
+        fn find_totp(&self, _user_id: Uuid) -> Result<Option<TotpCredential>, AuthError> {
+            Ok(self.credential.borrow().clone())
+        }
+
+        fn save_pending_totp(&self, credential: &TotpCredential) -> Result<(), AuthError> {
+            *self.credential.borrow_mut() = Some(credential.clone());
+            Ok(())
+        }
+
+        fn activate_totp(
+            &self,
+            _user_id: Uuid,
+            _step: i64,
+            _recovery_code_hashes: &[String],
+        ) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+    }
+
+    // TOTP service with a fixed secret
+    struct MockTotpService;
+
+    impl TotpService for MockTotpService {
+        fn generate_secret(&self) -> String {
+            "JBSWY3DPEHPK3PXP".to_string()
+        }
+
+        fn provisioning_uri(&self, secret: &str, account_name: &str) -> Result<String, AuthError> {
+            Ok(format!(
+                "otpauth://totp/Test:{}?secret={}",
+                account_name, secret
+            ))
+        }
+
+        fn verify(
+            &self,
+            _secret: &str,
+            _code: &str,
+            _now: DateTime<Utc>,
+        ) -> Result<Option<i64>, AuthError> {
+            Ok(None)
+        }
+
+        fn generate_recovery_code(&self) -> String {
+            "AAAAA-BBBBB".to_string()
+        }
+    }
+
+    // Token service accepting any token for the configured user, issued to
+    // `actor` when impersonating
+    struct MockTokenService {
+        user_id: Uuid,
+        actor: Option<Uuid>,
+    }
+
+    impl TokenService for MockTokenService {
+        fn create_token(
+            &self,
+            _user: &User,
+            _session_id: Option<Uuid>,
+        ) -> Result<String, AuthError> {
+            Ok("token".to_string())
+        }
+
+        fn validate_token(&self, _token: &str) -> Result<TokenData, AuthError> {
+            Ok(TokenData {
+                actor: self.actor,
+                ..token_data(self.user_id)
+            })
+        }
+
+        fn revoke_token(&self, _token: &TokenData) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn is_revoked(&self, _token: &TokenData) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+    }
+
+    fn enroll(mfa: &MockMfaRepository, actor: Option<Uuid>) -> Result<EnrollTotpResult, AuthError> {
+        let email = Email::new("test@example.com").unwrap();
+        let user = User::new(email, HashedPassword::from_hash("hashed".to_string()), None);
+        let token_service = MockTokenService {
+            user_id: user.id().as_uuid(),
+            actor,
+        };
+        let repo = MockUserRepository::new(user);
+
+        EnrollTotpUseCase::new(&repo, mfa, &MockTotpService, &token_service).execute(
+            EnrollTotpCommand {
+                token: "token".to_string(),
+            },
+        )
+    }
+
+    #[test]
+    fn test_enrollment_is_pending() {
+        let mfa = MockMfaRepository::default();
+
+        let result = enroll(&mfa, None).unwrap();
+
+        assert_eq!(result.secret, "JBSWY3DPEHPK3PXP");
+        assert!(result.otpauth_uri.contains("test@example.com"));
+        let credential = mfa.credential.borrow().clone().unwrap();
+        assert_eq!(credential.secret(), "JBSWY3DPEHPK3PXP");
+        assert!(!credential.is_confirmed());
+    }
+
+    #[test]
+    fn test_already_enabled() {
+        let mfa = MockMfaRepository::default();
+        *mfa.credential.borrow_mut() = Some(TotpCredential::from_persistence(
+            Uuid::new_v4(),
+            "OLDSECRET".to_string(),
+            Some(Utc::now()),
+            None,
+            Utc::now(),
+        ));
+
+        let result = enroll(&mfa, None);
+
+        assert!(matches!(result, Err(AuthError::MfaAlreadyEnabled)));
+        assert_eq!(
+            mfa.credential.borrow().as_ref().unwrap().secret(),
+            "OLDSECRET"
+        );
+    }
+
+    #[test]
+    fn test_impersonation_cannot_enroll() {
+        let mfa = MockMfaRepository::default();
+
+        let result = enroll(&mfa, Some(Uuid::new_v4()));
+
+        assert!(matches!(result, Err(AuthError::Forbidden)));
+        assert!(mfa.credential.borrow().is_none());
+    }
+}
@@ -0,0 +1,385 @@
+//! Exchange authorization code use case
+//!
+//! Last step of the OpenID Connect authorization-code flow: the web app
+//! redeems the code with its PKCE verifier and receives an access token for
+//! the user and an ID token describing them. Codes work once only, and the
+//! access token belongs to the session that authorized the code, so it stops
+//! working when that session ends. The access token carries the granted
+//! scopes, which decide the claims userinfo returns.
+
+use chrono::Utc;
+use tracing::{info, warn};
+
+use crate::domain::auth::{OpaqueTokenGenerator, UserRepository};
+use crate::domain::error::AuthError;
+use crate::domain::oidc::{AuthorizationCodeRepository, IdTokenIssuer, OidcClientRepository};
+use crate::domain::session::SessionRepository;
+
+/// Input for the authorization_code grant
+#[derive(Debug)]
+pub struct ExchangeAuthorizationCodeCommand {
+    pub client_id: String,
+    pub code: String,
+    /// Must repeat the redirect URI of the authorization request
+    pub redirect_uri: String,
+    /// PKCE verifier matching the challenge of the authorization request
+    pub code_verifier: String,
+}
+
+/// Tokens issued for a redeemed code
+#[derive(Debug)]
+pub struct ExchangeAuthorizationCodeResult {
+    pub access_token: String,
+    pub id_token: String,
+    /// Granted OpenID Connect scopes
+    pub scopes: Vec<String>,
+}
+
+/// Use case for the authorization_code grant
+pub struct ExchangeAuthorizationCodeUseCase<
+    'a,
+    C: ?Sized,
+    A: ?Sized,
+    U: ?Sized,
+    S: ?Sized,
+    I: ?Sized,
+    G: ?Sized,
+> {
+    client_repository: &'a C,
+    code_repository: &'a A,
+    user_repository: &'a U,
+    session_repository: &'a S,
+    id_token_issuer: &'a I,
+    token_generator: &'a G,
+}
+
+impl<'a, C, A, U, S, I, G> ExchangeAuthorizationCodeUseCase<'a, C, A, U, S, I, G>
+where
+    C: OidcClientRepository + ?Sized,
+    A: AuthorizationCodeRepository + ?Sized,
+    U: UserRepository + ?Sized,
+    S: SessionRepository + ?Sized,
+    I: IdTokenIssuer + ?Sized,
+    G: OpaqueTokenGenerator + ?Sized,
+{
+    /// Create a new use case instance
+    pub fn new(
+        client_repository: &'a C,
+        code_repository: &'a A,
+        user_repository: &'a U,
+        session_repository: &'a S,
+        id_token_issuer: &'a I,
+        token_generator: &'a G,
+    ) -> Self {
+        Self {
+            client_repository,
+            code_repository,
+            user_repository,
+            session_repository,
+            id_token_issuer,
+            token_generator,
+        }
+    }
+
+    /// Execute the grant
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidClient` if the client is unknown
+    /// - `AuthError::InvalidGrant` if the code is unknown, expired or already
+    ///   used, doesn't belong to the client or redirect URI, the verifier is
+    ///   wrong, the authorizing session has ended, or the user can no longer
+    ///   sign in
+    /// - `AuthError::Internal` on infrastructure failures
+    pub fn execute(
+        &self,
+        command: ExchangeAuthorizationCodeCommand,
+    ) -> Result<ExchangeAuthorizationCodeResult, AuthError> {
+        let client = self.client_repository.find_by_id(&command.client_id)?;
+
+        // The code is consumed even if the request turns out to be wrong, so
+        // an intercepted code cannot be retried
+        let code = self
+            .code_repository
+            .take(&self.token_generator.hash(&command.code))?
+            .ok_or(AuthError::InvalidGrant)?;
+        if let Err(e) = code.redeem(
+            client.client_id(),
+            &command.redirect_uri,
+            &command.code_verifier,
+            Utc::now(),
+        ) {
+            warn!(client_id = %client.client_id(), "Authorization code rejected");
+            return Err(e);
+        }
+
+        let user = match self.user_repository.find_by_id(code.user_id()) {
+            Ok(user) if user.is_active() => user,
+            Ok(_) | Err(AuthError::UserNotFound) => return Err(AuthError::InvalidGrant),
+            Err(e) => return Err(e),
+        };
+        if self.session_repository.is_revoked(code.session_id())? {
+            warn!(user_id = %code.user_id(), "Authorization code from an ended session");
+            return Err(AuthError::InvalidGrant);
+        }
+        let access_token = self.id_token_issuer.create_access_token(&user, &code)?;
+        let id_token = self.id_token_issuer.create_id_token(&user, &code)?;
+
+        info!(
+            user_id = %code.user_id(),
+            client_id = %client.client_id(),
+            "Authorization code redeemed"
+        );
+        Ok(ExchangeAuthorizationCodeResult {
+            access_token,
+            id_token,
+            scopes: code.scopes().to_vec(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::{Cell, RefCell};
+
+    use chrono::{DateTime, Duration};
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::application::commands::test_support::MockUserRepository;
+    use crate::domain::oidc::{pkce_challenge, AuthorizationCode, OidcClient};
+    use crate::domain::session::Session;
+    use crate::domain::user::{Email, HashedPassword, User};
+
+    const VERIFIER: &str = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+    const REDIRECT_URI: &str = "https://app.example.com/callback";
+    const SESSION_ID: Uuid = Uuid::from_u128(7);
+
+    // Mock repository holding a single client
+    struct MockOidcClientRepository;
+
+    impl OidcClientRepository for MockOidcClientRepository {
+        fn save(&self, client: &OidcClient) -> Result<OidcClient, AuthError> {
+            Ok(client.clone())
+        }
+
+        fn find_by_id(&self, client_id: &str) -> Result<OidcClient, AuthError> {
+            match client_id {
+                "web" | "other" => Ok(OidcClient::register(
+                    client_id.to_string(),
+                    "App".to_string(),
+                    vec![REDIRECT_URI.to_string()],
+                )),
+                _ => Err(AuthError::InvalidClient),
+            }
+        }
+    }
+
+    // Code store removing codes as they are taken
+    #[derive(Default)]
+    struct MockAuthorizationCodeRepository {
+        codes: RefCell<Vec<AuthorizationCode>>,
+    }
+
+    impl AuthorizationCodeRepository for MockAuthorizationCodeRepository {
+        fn create(&self, code: &AuthorizationCode) -> Result<(), AuthError> {
+            self.codes.borrow_mut().push(code.clone());
+            Ok(())
+        }
+
+        fn take(&self, code_hash: &str) -> Result<Option<AuthorizationCode>, AuthError> {
+            let mut codes = self.codes.borrow_mut();
+            let index = codes.iter().position(|c| c.code_hash() == code_hash);
+            Ok(index.map(|index| codes.remove(index)))
+        }
+
+        fn purge_expired(&self, _now: DateTime<Utc>) -> Result<usize, AuthError> {
+            Ok(0)
+        }
+    }
+
+    // Session store whose only session can be ended
+    #[derive(Default)]
+    struct MockSessionRepository {
+        revoked: Cell<bool>,
+    }
+
+    impl SessionRepository for MockSessionRepository {
+        fn create(&self, _session: &Session) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn find_by_id(&self, _id: Uuid) -> Result<Session, AuthError> {
+            Err(AuthError::SessionNotFound)
+        }
+
+        fn find_active_for_user(
+            &self,
+            _user_id: Uuid,
+            _now: DateTime<Utc>,
+        ) -> Result<Vec<Session>, AuthError> {
+            Ok(Vec::new())
+        }
+
+        fn touch(
+            &self,
+            _id: Uuid,
+            _now: DateTime<Utc>,
+            _expires_at: DateTime<Utc>,
+        ) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn set_organization(
+            &self,
+            _id: Uuid,
+            _organization_id: Option<Uuid>,
+        ) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn is_revoked(&self, id: Uuid) -> Result<bool, AuthError> {
+            Ok(id != SESSION_ID || self.revoked.get())
+        }
+    }
+
+    // Issuer naming the session and audience in its tokens
+    struct MockIdTokenIssuer;
+
+    impl IdTokenIssuer for MockIdTokenIssuer {
+        fn create_access_token(
+            &self,
+            user: &User,
+            code: &AuthorizationCode,
+        ) -> Result<String, AuthError> {
+            Ok(format!("access:{}:{}", user.id().as_uuid(), code.session_id()))
+        }
+
+        fn create_id_token(
+            &self,
+            user: &User,
+            code: &AuthorizationCode,
+        ) -> Result<String, AuthError> {
+            Ok(format!("id:{}:{}", user.id().as_uuid(), code.client_id()))
+        }
+    }
+
+    // Generator with a reversible "hash"
+    struct MockTokenGenerator;
+
+    impl OpaqueTokenGenerator for MockTokenGenerator {
+        fn generate(&self) -> String {
+            "code".to_string()
+        }
+
+        fn hash(&self, token: &str) -> String {
+            format!("hashed:{}", token)
+        }
+    }
+
+    fn setup() -> (MockAuthorizationCodeRepository, MockUserRepository) {
+        let email = Email::new("user@example.com").unwrap();
+        let user = User::new(email, HashedPassword::from_hash("hashed".to_string()), None);
+        let codes = MockAuthorizationCodeRepository::default();
+        codes
+            .create(&AuthorizationCode::issue(
+                "hashed:code".to_string(),
+                "web".to_string(),
+                user.id().as_uuid(),
+                SESSION_ID,
+                REDIRECT_URI.to_string(),
+                vec!["openid".to_string()],
+                pkce_challenge(VERIFIER),
+                None,
+                Duration::seconds(60),
+            ))
+            .unwrap();
+        (codes, MockUserRepository::new(user))
+    }
+
+    fn exchange(
+        codes: &MockAuthorizationCodeRepository,
+        users: &MockUserRepository,
+        sessions: &MockSessionRepository,
+        client_id: &str,
+        verifier: &str,
+    ) -> Result<ExchangeAuthorizationCodeResult, AuthError> {
+        ExchangeAuthorizationCodeUseCase::new(
+            &MockOidcClientRepository,
+            codes,
+            users,
+            sessions,
+            &MockIdTokenIssuer,
+            &MockTokenGenerator,
+        )
+        .execute(ExchangeAuthorizationCodeCommand {
+            client_id: client_id.to_string(),
+            code: "code".to_string(),
+            redirect_uri: REDIRECT_URI.to_string(),
+            code_verifier: verifier.to_string(),
+        })
+    }
+
+    #[test]
+    fn test_code_redeemed_once() {
+        let (codes, users) = setup();
+        let user_id = users.user_id();
+
+        let sessions = MockSessionRepository::default();
+
+        let result = exchange(&codes, &users, &sessions, "web", VERIFIER).unwrap();
+        assert_eq!(
+            result.access_token,
+            format!("access:{}:{}", user_id, SESSION_ID)
+        );
+        assert_eq!(result.id_token, format!("id:{}:web", user_id));
+        assert_eq!(result.scopes, vec!["openid".to_string()]);
+
+        let replay = exchange(&codes, &users, &sessions, "web", VERIFIER);
+        assert!(matches!(replay, Err(AuthError::InvalidGrant)));
+    }
+
+    #[test]
+    fn test_failed_redemption_burns_code() {
+        for client_id in ["other", "web"] {
+            let (codes, users) = setup();
+            let verifier = if client_id == "web" {
+                "wrong"
+            } else {
+                VERIFIER
+            };
+
+            let result = exchange(
+                &codes,
+                &users,
+                &MockSessionRepository::default(),
+                client_id,
+                verifier,
+            );
+            assert!(matches!(result, Err(AuthError::InvalidGrant)));
+            assert!(codes.codes.borrow().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_deactivated_user_gets_no_tokens() {
+        let (codes, users) = setup();
+        users.user.lock().unwrap().deactivate();
+        let sessions = MockSessionRepository::default();
+
+        let result = exchange(&codes, &users, &sessions, "web", VERIFIER);
+
+        assert!(matches!(result, Err(AuthError::InvalidGrant)));
+    }
+
+    #[test]
+    fn test_revoked_session_gets_no_tokens() {
+        let (codes, users) = setup();
+        let sessions = MockSessionRepository::default();
+        sessions.revoked.set(true);
+
+        let result = exchange(&codes, &users, &sessions, "web", VERIFIER);
+
+        assert!(matches!(result, Err(AuthError::InvalidGrant)));
+        assert!(codes.codes.borrow().is_empty());
+    }
+}
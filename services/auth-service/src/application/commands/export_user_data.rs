@@ -0,0 +1,361 @@
+//! Export user data use case
+//!
+//! Gathers everything the service stores about a user so it can be handed
+//! to them on request. Users export their own data; exporting anyone else's
+//! requires the `users:manage` permission.
+
+use chrono::Utc;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::domain::api_key::ApiKeyRepository;
+use crate::domain::audit::{AuditAction, AuditEvent, AuditLog};
+use crate::domain::auth::{TokenData, TokenService, UserRepository};
+use crate::domain::error::AuthError;
+use crate::domain::mfa::MfaRepository;
+use crate::domain::personal_data::{PersonalDataExport, PersonalDataRepository};
+use crate::domain::role::{authorize, Permission};
+
+/// Input for exporting a user's data
+#[derive(Debug)]
+pub struct ExportUserDataCommand {
+    /// Access token of the caller
+    pub token: String,
+    /// User to export; `None` for the caller
+    pub user_id: Option<Uuid>,
+}
+
+/// Use case for exporting a user's data
+pub struct ExportUserDataUseCase<
+    'a,
+    R: ?Sized,
+    P: ?Sized,
+    M: ?Sized,
+    K: ?Sized,
+    T: ?Sized,
+    L: ?Sized,
+> {
+    user_repository: &'a R,
+    personal_data_repository: &'a P,
+    mfa_repository: &'a M,
+    api_key_repository: &'a K,
+    token_service: &'a T,
+    audit_log: &'a L,
+}
+
+impl<'a, R, P, M, K, T, L> ExportUserDataUseCase<'a, R, P, M, K, T, L>
+where
+    R: UserRepository + ?Sized,
+    P: PersonalDataRepository + ?Sized,
+    M: MfaRepository + ?Sized,
+    K: ApiKeyRepository + ?Sized,
+    T: TokenService + ?Sized,
+    L: AuditLog + ?Sized,
+{
+    /// Create a new use case instance
+    pub fn new(
+        user_repository: &'a R,
+        personal_data_repository: &'a P,
+        mfa_repository: &'a M,
+        api_key_repository: &'a K,
+        token_service: &'a T,
+        audit_log: &'a L,
+    ) -> Self {
+        Self {
+            user_repository,
+            personal_data_repository,
+            mfa_repository,
+            api_key_repository,
+            token_service,
+            audit_log,
+        }
+    }
+
+    /// Execute the export
+    ///
+    /// Secrets such as password hashes and two-factor seeds are never
+    /// included. Exports of another user's data are audited.
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidToken` / `AuthError::TokenExpired` if the token is invalid
+    /// - `AuthError::Forbidden` if the caller may not export another user's data,
+    ///   or acts as the user through an impersonation token; administrators
+    ///   export through their own account, which is audited
+    /// - `AuthError::UserNotFound` if the user does not exist or was erased
+    /// - `AuthError::Internal` on infrastructure failures
+    pub fn execute(&self, command: ExportUserDataCommand) -> Result<PersonalDataExport, AuthError> {
+        let actor = self.token_service.validate_token(&command.token)?;
+        let user_id = command.user_id.unwrap_or(actor.user_id);
+        if user_id == actor.user_id {
+            if actor.is_impersonated() {
+                return Err(AuthError::Forbidden);
+            }
+            return self.export(&actor, user_id);
+        }
+
+        let result =
+            authorize(&actor, Permission::ManageUsers).and_then(|_| self.export(&actor, user_id));
+        self.audit_log.record(
+            AuditEvent::new(AuditAction::UserDataExport)
+                .with_actor(actor.audit_actor())
+                .with_target(user_id)
+                .with_result(&result),
+        );
+        result
+    }
+
+    fn export(&self, actor: &TokenData, user_id: Uuid) -> Result<PersonalDataExport, AuthError> {
+        let user = self.user_repository.find_by_id(user_id)?;
+        if user.is_erased() {
+            return Err(AuthError::UserNotFound);
+        }
+
+        let totp_enabled_at = self
+            .mfa_repository
+            .find_totp(user_id)?
+            .and_then(|credential| credential.confirmed_at());
+        let sessions = self.personal_data_repository.find_sessions(user_id)?;
+        let api_keys = self.api_key_repository.list_for_user(user_id)?;
+        let identities = self.personal_data_repository.find_identities(user_id)?;
+        let organizations = self.personal_data_repository.find_organizations(user_id)?;
+        let audit_events = self.personal_data_repository.find_audit_events(user_id)?;
+
+        info!(user_id = %user_id, actor_id = %actor.user_id, "User data exported");
+        Ok(PersonalDataExport {
+            user,
+            totp_enabled_at,
+            sessions,
+            api_keys,
+            identities,
+            organizations,
+            audit_events,
+            exported_at: Utc::now(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Duration};
+
+    use super::*;
+    use crate::application::commands::test_support::{token_data, MockAuditLog, MockUserRepository};
+    use crate::domain::api_key::ApiKey;
+    use crate::domain::federation::UserIdentity;
+    use crate::domain::mfa::TotpCredential;
+    use crate::domain::organization::{Membership, Organization};
+    use crate::domain::personal_data::SessionRecord;
+    use crate::domain::role::Role;
+    use crate::domain::user::{Email, HashedPassword, User};
+
+    // Personal data store with one session and one login per user
+    struct MockPersonalDataRepository;
+
+    impl PersonalDataRepository for MockPersonalDataRepository {
+        fn find_sessions(&self, _user_id: Uuid) -> Result<Vec<SessionRecord>, AuthError> {
+            let now = Utc::now();
+            Ok(vec![SessionRecord {
+                family_id: Uuid::new_v4(),
+                started_at: now - Duration::hours(2),
+                last_used_at: now,
+                expires_at: now + Duration::days(7),
+                ended_at: None,
+                user_agent: None,
+                ip_address: None,
+            }])
+        }
+
+        fn find_identities(&self, _user_id: Uuid) -> Result<Vec<UserIdentity>, AuthError> {
+            Ok(vec![])
+        }
+
+        fn find_organizations(
+            &self,
+            _user_id: Uuid,
+        ) -> Result<Vec<(Organization, Membership)>, AuthError> {
+            Ok(vec![])
+        }
+
+        fn find_audit_events(&self, user_id: Uuid) -> Result<Vec<AuditEvent>, AuthError> {
+            Ok(vec![AuditEvent::new(AuditAction::PasswordLogin).with_actor(user_id)])
+        }
+
+        fn erase(&self, _user: &User, _previous_email: &str) -> Result<(), AuthError> {
+            Ok(())
+        }
+    }
+
+    // MFA store where every user has confirmed TOTP
+    struct MockMfaRepository;
+
+    impl MfaRepository for MockMfaRepository {
+        fn find_totp(&self, user_id: Uuid) -> Result<Option<TotpCredential>, AuthError> {
+            let now = Utc::now();
+            Ok(Some(TotpCredential::from_persistence(
+                user_id,
+                "SECRET".to_string(),
+                Some(now),
+                None,
+                now,
+            )))
+        }
+
+        fn save_pending_totp(&self, _credential: &TotpCredential) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn activate_totp(
+            &self,
+            _user_id: Uuid,
+            _step: i64,
+            _recovery_code_hashes: &[String],
+        ) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+    }
+
+    // Key store where every user has no API keys
+    struct MockApiKeyRepository;
+
+    impl ApiKeyRepository for MockApiKeyRepository {
+        fn create(&self, _key: &ApiKey) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn find_by_hash(&self, _key_hash: &str) -> Result<ApiKey, AuthError> {
+            Err(AuthError::InvalidToken)
+        }
+
+        fn find_by_id(&self, _id: Uuid) -> Result<ApiKey, AuthError> {
+            Err(AuthError::ApiKeyNotFound)
+        }
+
+        fn list_for_user(&self, _user_id: Uuid) -> Result<Vec<ApiKey>, AuthError> {
+            Ok(vec![])
+        }
+
+        fn revoke(&self, _id: Uuid) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+
+        fn record_use(&self, _id: Uuid, _at: DateTime<Utc>) -> Result<(), AuthError> {
+            Ok(())
+        }
+    }
+
+    // Token service mapping "admin" and "support" tokens to roles; the
+    // "self" token belongs to the stored user
+    struct MockTokenService {
+        self_id: Uuid,
+    }
+
+    impl TokenService for MockTokenService {
+        fn create_token(
+            &self,
+            _user: &User,
+            _session_id: Option<Uuid>,
+        ) -> Result<String, AuthError> {
+            Ok("token".to_string())
+        }
+
+        fn validate_token(&self, token: &str) -> Result<TokenData, AuthError> {
+            let (user_id, roles) = match token {
+                "admin" => (Uuid::new_v4(), vec![Role::Admin]),
+                "support" => (Uuid::new_v4(), vec![Role::SupportAgent]),
+                "self" => (self.self_id, vec![Role::Customer]),
+                _ => return Err(AuthError::InvalidToken),
+            };
+            Ok(TokenData {
+                roles,
+                ..token_data(user_id)
+            })
+        }
+
+        fn revoke_token(&self, _token: &TokenData) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn is_revoked(&self, _token: &TokenData) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+    }
+
+    fn setup() -> (MockUserRepository, MockTokenService) {
+        let email = Email::new("test@example.com").unwrap();
+        let user = User::new(email, HashedPassword::from_hash("hashed".to_string()), None);
+        let token_service = MockTokenService {
+            self_id: user.id().as_uuid(),
+        };
+        (
+            MockUserRepository::new(user),
+            token_service,
+        )
+    }
+
+    fn export(
+        users: &MockUserRepository,
+        token_service: &MockTokenService,
+        token: &str,
+        user_id: Option<Uuid>,
+    ) -> Result<PersonalDataExport, AuthError> {
+        ExportUserDataUseCase::new(
+            users,
+            &MockPersonalDataRepository,
+            &MockMfaRepository,
+            &MockApiKeyRepository,
+            token_service,
+            &MockAuditLog::default(),
+        )
+        .execute(ExportUserDataCommand {
+            token: token.to_string(),
+            user_id,
+        })
+    }
+
+    #[test]
+    fn test_user_exports_own_data() {
+        let (users, token_service) = setup();
+
+        let export = export(&users, &token_service, "self", None).unwrap();
+
+        assert_eq!(export.user.email().as_str(), "test@example.com");
+        assert!(export.totp_enabled_at.is_some());
+        assert_eq!(export.sessions.len(), 1);
+        assert_eq!(export.login_history(), vec![export.sessions[0].started_at]);
+    }
+
+    #[test]
+    fn test_export_includes_audit_trail() {
+        let (users, token_service) = setup();
+        let user_id = users.user_id();
+
+        let export = export(&users, &token_service, "self", None).unwrap();
+
+        assert_eq!(export.audit_events.len(), 1);
+        assert_eq!(export.audit_events[0].action(), AuditAction::PasswordLogin);
+        assert_eq!(export.audit_events[0].actor_id(), Some(user_id));
+    }
+
+    #[test]
+    fn test_exporting_another_user_requires_manage_users() {
+        let (users, token_service) = setup();
+        let user_id = Some(users.user_id());
+
+        assert!(export(&users, &token_service, "admin", user_id).is_ok());
+        assert!(matches!(
+            export(&users, &token_service, "support", user_id),
+            Err(AuthError::Forbidden)
+        ));
+    }
+
+    #[test]
+    fn test_erased_user_not_exported() {
+        let (users, token_service) = setup();
+        users.user.lock().unwrap().erase();
+        let user_id = Some(users.user_id());
+
+        let result = export(&users, &token_service, "admin", user_id);
+
+        assert!(matches!(result, Err(AuthError::UserNotFound)));
+    }
+}
@@ -0,0 +1,33 @@
+//! Get current user use case
+//!
+//! Loads the account a validated token belongs to, for `/auth/me`. The
+//! user is awaited rather than fetched on the blocking thread pool; the
+//! caller validates the token beforehand with an `AsyncTokenValidator`.
+
+use crate::domain::auth::{AsyncUserRepository, TokenData};
+use crate::domain::error::AuthError;
+use crate::domain::user::User;
+
+/// Use case for getting the user a token belongs to
+pub struct GetCurrentUserUseCase<'a, R: ?Sized> {
+    user_repository: &'a R,
+}
+
+impl<'a, R> GetCurrentUserUseCase<'a, R>
+where
+    R: AsyncUserRepository + ?Sized,
+{
+    /// Create a new use case instance
+    pub fn new(user_repository: &'a R) -> Self {
+        Self { user_repository }
+    }
+
+    /// Execute the lookup for an already validated token
+    ///
+    /// # Errors
+    /// - `AuthError::UserNotFound` if the account no longer exists
+    /// - `AuthError::Internal` on infrastructure failures
+    pub async fn execute(&self, token_data: &TokenData) -> Result<User, AuthError> {
+        self.user_repository.find_by_id(token_data.user_id).await
+    }
+}
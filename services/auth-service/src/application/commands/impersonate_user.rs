@@ -0,0 +1,291 @@
+//! Impersonate user use case
+//!
+//! Lets an administrator see the platform as a customer or organizer does,
+//! without knowing their password. The token issued names the administrator
+//! in its `act` claim and belongs to the administrator's session, so logging
+//! out or ending that session revokes it. It cannot be refreshed, and it is
+//! refused wherever a user's own session is required, such as API key and
+//! session management.
+
+use chrono::Duration;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::domain::audit::{AuditAction, AuditEvent, AuditLog};
+use crate::domain::auth::{TokenData, TokenService, UserRepository};
+use crate::domain::error::AuthError;
+use crate::domain::impersonation::{check_impersonation_target, ImpersonationTokenIssuer};
+use crate::domain::role::{authorize, Permission};
+
+/// Input for impersonating a user
+#[derive(Debug)]
+pub struct ImpersonateUserCommand {
+    /// Access token of the acting administrator
+    pub token: String,
+    pub user_id: Uuid,
+}
+
+/// Token for acting as the user
+#[derive(Debug)]
+pub struct ImpersonateUserResult {
+    pub token: String,
+    /// Seconds until the token expires
+    pub expires_in: i64,
+    pub user_id: Uuid,
+    pub email: String,
+}
+
+/// Use case for impersonating a user
+pub struct ImpersonateUserUseCase<'a, R: ?Sized, T: ?Sized, I: ?Sized, L: ?Sized> {
+    user_repository: &'a R,
+    token_service: &'a T,
+    token_issuer: &'a I,
+    token_ttl: Duration,
+    audit_log: &'a L,
+}
+
+impl<'a, R, T, I, L> ImpersonateUserUseCase<'a, R, T, I, L>
+where
+    R: UserRepository + ?Sized,
+    T: TokenService + ?Sized,
+    I: ImpersonationTokenIssuer + ?Sized,
+    L: AuditLog + ?Sized,
+{
+    /// Create a new use case instance
+    ///
+    /// # Arguments
+    /// * `token_ttl` - Lifetime of impersonation tokens
+    pub fn new(
+        user_repository: &'a R,
+        token_service: &'a T,
+        token_issuer: &'a I,
+        token_ttl: Duration,
+        audit_log: &'a L,
+    ) -> Self {
+        Self {
+            user_repository,
+            token_service,
+            token_issuer,
+            token_ttl,
+            audit_log,
+        }
+    }
+
+    /// Execute impersonation, returning a token for the target user
+    ///
+    /// Only an administrator's own session may impersonate; API keys,
+    /// impersonation tokens and tokens issued before sessions were tracked
+    /// cannot.
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidToken` / `AuthError::TokenExpired` if the token is invalid
+    /// - `AuthError::Forbidden` if the caller may not impersonate users
+    /// - `AuthError::UserNotFound` if the target user does not exist or was erased
+    /// - `AuthError::CannotImpersonate` for the caller's own account and staff accounts
+    /// - `AuthError::AccountInactive` if the target account is deactivated
+    /// - `AuthError::Internal` on infrastructure failures
+    pub fn execute(
+        &self,
+        command: ImpersonateUserCommand,
+    ) -> Result<ImpersonateUserResult, AuthError> {
+        let actor = self.token_service.validate_token(&command.token)?;
+        let user_id = command.user_id;
+
+        let result = self.impersonate(&actor, user_id);
+        self.audit_log.record(
+            AuditEvent::new(AuditAction::Impersonation)
+                .with_actor(actor.audit_actor())
+                .with_target(user_id)
+                .with_result(&result),
+        );
+        result
+    }
+
+    fn impersonate(
+        &self,
+        actor: &TokenData,
+        user_id: Uuid,
+    ) -> Result<ImpersonateUserResult, AuthError> {
+        authorize(actor, Permission::ImpersonateUsers)?;
+        let session_id = match actor.session_id {
+            Some(session_id) if actor.is_session() => session_id,
+            _ => return Err(AuthError::Forbidden),
+        };
+
+        let user = self.user_repository.find_by_id(user_id)?;
+        // Erased accounts are gone for good
+        if user.is_erased() {
+            return Err(AuthError::UserNotFound);
+        }
+        check_impersonation_target(actor.user_id, &user)?;
+
+        let token = self.token_issuer.create_impersonation_token(
+            &user,
+            actor.user_id,
+            session_id,
+            self.token_ttl,
+        )?;
+
+        info!(
+            user_id = %user_id,
+            actor_id = %actor.user_id,
+            session_id = %session_id,
+            "Impersonation token issued"
+        );
+        Ok(ImpersonateUserResult {
+            token: token.access_token,
+            expires_in: self.token_ttl.num_seconds(),
+            user_id,
+            email: user.email().as_str().to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+    use crate::application::commands::test_support::{token_data, MockAuditLog, MockUserRepository};
+    use crate::domain::audit::AuditOutcome;
+    use crate::domain::impersonation::ImpersonationToken;
+    use crate::domain::role::Role;
+    use crate::domain::user::{Email, HashedPassword, User};
+
+    // Token service mapping "admin", "support", "key" and "legacy" tokens to
+    // callers; "key" is an administrator's API key and "legacy" an
+    // administrator's token from before sessions were tracked
+    struct MockTokenService {
+        admin_id: Uuid,
+        session_id: Uuid,
+    }
+
+    impl TokenService for MockTokenService {
+        fn create_token(
+            &self,
+            _user: &User,
+            _session_id: Option<Uuid>,
+        ) -> Result<String, AuthError> {
+            Ok("token".to_string())
+        }
+
+        fn validate_token(&self, token: &str) -> Result<TokenData, AuthError> {
+            let (roles, scopes, session_id) = match token {
+                "admin" => (vec![Role::Admin], None, Some(self.session_id)),
+                "support" => (vec![Role::SupportAgent], None, Some(self.session_id)),
+                "key" => (vec![Role::Admin], Some(vec![Permission::ImpersonateUsers]), None),
+                "legacy" => (vec![Role::Admin], None, None),
+                _ => return Err(AuthError::InvalidToken),
+            };
+            Ok(TokenData {
+                roles,
+                scopes,
+                session_id,
+                ..token_data(self.admin_id)
+            })
+        }
+
+        fn revoke_token(&self, _token: &TokenData) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn is_revoked(&self, _token: &TokenData) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+    }
+
+    // Issuer encoding the user, actor and session into the token
+    struct MockTokenIssuer;
+
+    impl ImpersonationTokenIssuer for MockTokenIssuer {
+        fn create_impersonation_token(
+            &self,
+            user: &User,
+            actor_id: Uuid,
+            session_id: Uuid,
+            ttl: Duration,
+        ) -> Result<ImpersonationToken, AuthError> {
+            Ok(ImpersonationToken {
+                access_token: format!("{}:{}:{}", user.id().as_uuid(), actor_id, session_id),
+                expires_at: Utc::now() + ttl,
+            })
+        }
+    }
+
+    fn customer() -> User {
+        let email = Email::new("customer@example.com").unwrap();
+        User::new(email, HashedPassword::from_hash("hashed".to_string()), None)
+    }
+
+    const SESSION_ID: Uuid = Uuid::from_u128(7);
+
+    fn impersonate(
+        user: User,
+        token: &str,
+        audit_log: &MockAuditLog,
+    ) -> Result<ImpersonateUserResult, AuthError> {
+        let user_id = user.id().as_uuid();
+        let users = MockUserRepository::new(user);
+        let token_service = MockTokenService {
+            admin_id: Uuid::new_v4(),
+            session_id: SESSION_ID,
+        };
+        ImpersonateUserUseCase::new(
+            &users,
+            &token_service,
+            &MockTokenIssuer,
+            Duration::minutes(15),
+            audit_log,
+        )
+        .execute(ImpersonateUserCommand {
+            token: token.to_string(),
+            user_id,
+        })
+    }
+
+    #[test]
+    fn test_admin_impersonates_customer() {
+        let user = customer();
+        let user_id = user.id().as_uuid();
+        let audit_log = MockAuditLog::default();
+
+        let result = impersonate(user, "admin", &audit_log).unwrap();
+
+        assert_eq!(result.user_id, user_id);
+        assert_eq!(result.email, "customer@example.com");
+        assert_eq!(result.expires_in, 900);
+        assert!(result.token.starts_with(&user_id.to_string()));
+        // Bound to the administrator's session
+        assert!(result.token.ends_with(&SESSION_ID.to_string()));
+
+        let events = audit_log.events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].action(), AuditAction::Impersonation);
+        assert_eq!(events[0].outcome(), AuditOutcome::Success);
+        assert_eq!(events[0].target_id(), Some(user_id));
+    }
+
+    #[test]
+    fn test_support_agent_api_key_and_sessionless_token_forbidden() {
+        let audit_log = MockAuditLog::default();
+
+        for token in ["support", "key", "legacy"] {
+            let result = impersonate(customer(), token, &audit_log);
+            assert!(matches!(result, Err(AuthError::Forbidden)));
+        }
+
+        let events = audit_log.events.lock().unwrap();
+        assert_eq!(events.len(), 3);
+        assert!(events.iter().all(|e| e.outcome() == AuditOutcome::Failure));
+    }
+
+    #[test]
+    fn test_staff_cannot_be_impersonated() {
+        let mut user = customer();
+        user.assign_role(Role::SupportAgent);
+
+        let result = impersonate(user, "admin", &MockAuditLog::default());
+
+        assert!(matches!(result, Err(AuthError::CannotImpersonate)));
+    }
+}
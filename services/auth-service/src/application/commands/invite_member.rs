@@ -0,0 +1,417 @@
+//! Invite member use case
+//!
+//! Lets owners and admins of an organization invite someone by email. The
+//! invitation link is mailed to the address and only the account with that
+//! address can accept it, so invitations can go out before the invitee has
+//! signed up.
+
+use chrono::{DateTime, Duration, Utc};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::domain::audit::{AuditAction, AuditEvent, AuditLog};
+use crate::domain::auth::{OpaqueTokenGenerator, TokenData, TokenService};
+use crate::domain::error::AuthError;
+use crate::domain::mailer::{EmailMessage, Mailer};
+use crate::domain::organization::{
+    Invitation, InvitationRepository, OrgRole, OrganizationRepository,
+};
+use crate::domain::user::Email;
+
+/// Input for inviting a member
+#[derive(Debug)]
+pub struct InviteMemberCommand {
+    /// Access token of the inviting member
+    pub token: String,
+    pub organization_id: Uuid,
+    pub email: String,
+    /// `member` or `admin`
+    pub role: String,
+}
+
+/// A sent invitation
+#[derive(Debug)]
+pub struct InviteMemberResult {
+    pub invitation_id: Uuid,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Use case for inviting a member
+pub struct InviteMemberUseCase<'a, O: ?Sized, I: ?Sized, T: ?Sized, G: ?Sized, M: ?Sized, L: ?Sized>
+{
+    organization_repository: &'a O,
+    invitation_repository: &'a I,
+    token_service: &'a T,
+    token_generator: &'a G,
+    mailer: &'a M,
+    invitation_ttl: Duration,
+    invitation_url: &'a str,
+    audit_log: &'a L,
+}
+
+impl<'a, O, I, T, G, M, L> InviteMemberUseCase<'a, O, I, T, G, M, L>
+where
+    O: OrganizationRepository + ?Sized,
+    I: InvitationRepository + ?Sized,
+    T: TokenService + ?Sized,
+    G: OpaqueTokenGenerator + ?Sized,
+    M: Mailer + ?Sized,
+    L: AuditLog + ?Sized,
+{
+    /// Create a new use case instance
+    ///
+    /// `invitation_url` is the page that accepts the invitation; the secret
+    /// is appended as a `token` query parameter.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        organization_repository: &'a O,
+        invitation_repository: &'a I,
+        token_service: &'a T,
+        token_generator: &'a G,
+        mailer: &'a M,
+        invitation_ttl: Duration,
+        invitation_url: &'a str,
+        audit_log: &'a L,
+    ) -> Self {
+        Self {
+            organization_repository,
+            invitation_repository,
+            token_service,
+            token_generator,
+            mailer,
+            invitation_ttl,
+            invitation_url,
+            audit_log,
+        }
+    }
+
+    /// Execute the invitation
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidToken` / `AuthError::TokenExpired` if the token is invalid
+    /// - `AuthError::Forbidden` if the caller is not signed in as themselves,
+    ///   or is a member who may not invite
+    /// - `AuthError::OrganizationNotFound` if the organization does not exist
+    ///   or the caller is not a member
+    /// - `AuthError::InvalidEmail` if email format is invalid
+    /// - `AuthError::InvalidRole` unless the role is `member` or `admin`
+    /// - `AuthError::Internal` on infrastructure failures
+    pub fn execute(&self, command: InviteMemberCommand) -> Result<InviteMemberResult, AuthError> {
+        let token_data = self.token_service.validate_token(&command.token)?;
+        let detail = format!("{} as {}", command.organization_id, command.role.trim());
+
+        let result = self.invite(&token_data, command);
+        self.audit_log.record(
+            AuditEvent::new(AuditAction::MemberInvitation)
+                .with_actor(token_data.audit_actor())
+                .with_detail(detail)
+                .with_result(&result),
+        );
+        result
+    }
+
+    fn invite(
+        &self,
+        token_data: &TokenData,
+        command: InviteMemberCommand,
+    ) -> Result<InviteMemberResult, AuthError> {
+        if !token_data.is_session() {
+            return Err(AuthError::Forbidden);
+        }
+
+        let organization_id = command.organization_id;
+        let inviter = self
+            .organization_repository
+            .find_membership(organization_id, token_data.user_id)?
+            .ok_or(AuthError::OrganizationNotFound)?;
+        if !inviter.role().can_invite() {
+            return Err(AuthError::Forbidden);
+        }
+        let organization = self.organization_repository.find_by_id(organization_id)?;
+
+        let email = Email::new(&command.email)?;
+        let role = command.role.parse::<OrgRole>()?;
+        // Ownership is never handed out by email
+        if role == OrgRole::Owner {
+            return Err(AuthError::InvalidRole);
+        }
+
+        let secret = self.token_generator.generate();
+        let invitation = Invitation::issue(
+            organization_id,
+            email.as_str().to_string(),
+            role,
+            self.token_generator.hash(&secret),
+            token_data.user_id,
+            self.invitation_ttl,
+        );
+        self.invitation_repository.create(&invitation)?;
+
+        self.mailer.send(&EmailMessage {
+            to: email.as_str().to_string(),
+            subject: format!("You are invited to join {}", organization.name()),
+            body: format!(
+                "You have been invited to join {} as {}.\n\n\
+                 Sign in or create an account with this email address, then follow this link \
+                 to accept:\n\n\
+                 {}?token={}\n\n\
+                 The link works once, until {}. If you were not expecting this invitation, \
+                 you can ignore this email.\n",
+                organization.name(),
+                role,
+                self.invitation_url,
+                secret,
+                invitation.expires_at().format("%Y-%m-%d %H:%M UTC")
+            ),
+        })?;
+
+        info!(
+            user_id = %token_data.user_id,
+            organization_id = %organization_id,
+            invitation_id = %invitation.id(),
+            "Organization invitation sent"
+        );
+        Ok(InviteMemberResult {
+            invitation_id: invitation.id(),
+            expires_at: invitation.expires_at(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+    use crate::application::commands::test_support::{token_data, MockAuditLog};
+    use crate::domain::audit::AuditOutcome;
+    use crate::domain::organization::{Membership, Organization};
+    use crate::domain::user::User;
+
+    // Organization store with one organization and fixed memberships
+    struct MockOrganizationRepository {
+        organization: Organization,
+        members: Vec<Membership>,
+    }
+
+    impl OrganizationRepository for MockOrganizationRepository {
+        fn create(
+            &self,
+            _organization: &Organization,
+            _owner: &Membership,
+        ) -> Result<(), AuthError> {
+            Err(AuthError::Internal("Not implemented".to_string()))
+        }
+
+        fn find_by_id(&self, id: Uuid) -> Result<Organization, AuthError> {
+            if self.organization.id() == id {
+                Ok(self.organization.clone())
+            } else {
+                Err(AuthError::OrganizationNotFound)
+            }
+        }
+
+        fn find_membership(
+            &self,
+            organization_id: Uuid,
+            user_id: Uuid,
+        ) -> Result<Option<Membership>, AuthError> {
+            Ok(self
+                .members
+                .iter()
+                .find(|m| m.organization_id() == organization_id && m.user_id() == user_id)
+                .cloned())
+        }
+
+        fn list_for_user(
+            &self,
+            _user_id: Uuid,
+        ) -> Result<Vec<(Organization, Membership)>, AuthError> {
+            Ok(Vec::new())
+        }
+    }
+
+    // Invitation store recording issued invitations
+    #[derive(Default)]
+    struct MockInvitationRepository {
+        created: RefCell<Vec<Invitation>>,
+    }
+
+    impl InvitationRepository for MockInvitationRepository {
+        fn create(&self, invitation: &Invitation) -> Result<(), AuthError> {
+            self.created.borrow_mut().push(invitation.clone());
+            Ok(())
+        }
+
+        fn find_by_hash(&self, _token_hash: &str) -> Result<Invitation, AuthError> {
+            Err(AuthError::InvalidInvitation)
+        }
+
+        fn accept(
+            &self,
+            _invitation: &Invitation,
+            _membership: &Membership,
+        ) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+
+        fn purge_expired(&self, _cutoff: DateTime<Utc>) -> Result<usize, AuthError> {
+            Ok(0)
+        }
+    }
+
+    // Token service whose tokens are the caller's user ID
+    struct MockTokenService;
+
+    impl TokenService for MockTokenService {
+        fn create_token(
+            &self,
+            _user: &User,
+            _session_id: Option<Uuid>,
+        ) -> Result<String, AuthError> {
+            Ok("token".to_string())
+        }
+
+        fn validate_token(&self, token: &str) -> Result<TokenData, AuthError> {
+            Ok(token_data(Uuid::parse_str(token).map_err(|_| AuthError::InvalidToken)?))
+        }
+
+        fn revoke_token(&self, _token: &TokenData) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn is_revoked(&self, _token: &TokenData) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+    }
+
+    // Mock generator with a fixed secret
+    struct MockTokenGenerator;
+
+    impl OpaqueTokenGenerator for MockTokenGenerator {
+        fn generate(&self) -> String {
+            "secret".to_string()
+        }
+
+        fn hash(&self, token: &str) -> String {
+            format!("hashed:{}", token)
+        }
+    }
+
+    // Mailer recording outgoing messages
+    #[derive(Default)]
+    struct MockMailer {
+        sent: RefCell<Vec<EmailMessage>>,
+    }
+
+    impl Mailer for MockMailer {
+        fn send(&self, message: &EmailMessage) -> Result<(), AuthError> {
+            self.sent.borrow_mut().push(message.clone());
+            Ok(())
+        }
+    }
+
+    struct Fixture {
+        organizations: MockOrganizationRepository,
+        invitations: MockInvitationRepository,
+        mailer: MockMailer,
+        audit_log: MockAuditLog,
+        owner_id: Uuid,
+        member_id: Uuid,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            let organization = Organization::new("Riverside Concerts".to_string());
+            let owner_id = Uuid::new_v4();
+            let member_id = Uuid::new_v4();
+            let members = vec![
+                Membership::new(organization.id(), owner_id, OrgRole::Owner),
+                Membership::new(organization.id(), member_id, OrgRole::Member),
+            ];
+            Self {
+                organizations: MockOrganizationRepository {
+                    organization,
+                    members,
+                },
+                invitations: MockInvitationRepository::default(),
+                mailer: MockMailer::default(),
+                audit_log: MockAuditLog::default(),
+                owner_id,
+                member_id,
+            }
+        }
+
+        fn invite(&self, caller: Uuid, role: &str) -> Result<InviteMemberResult, AuthError> {
+            InviteMemberUseCase::new(
+                &self.organizations,
+                &self.invitations,
+                &MockTokenService,
+                &MockTokenGenerator,
+                &self.mailer,
+                Duration::days(7),
+                "https://tickets.example.com/invitations/accept",
+                &self.audit_log,
+            )
+            .execute(InviteMemberCommand {
+                token: caller.to_string(),
+                organization_id: self.organizations.organization.id(),
+                email: "New.Member@Example.com".to_string(),
+                role: role.to_string(),
+            })
+        }
+    }
+
+    #[test]
+    fn test_owner_invites_by_email() {
+        let fixture = Fixture::new();
+
+        let result = fixture.invite(fixture.owner_id, "admin").unwrap();
+
+        let created = fixture.invitations.created.borrow();
+        assert_eq!(created.len(), 1);
+        assert_eq!(created[0].id(), result.invitation_id);
+        assert_eq!(created[0].email(), "new.member@example.com");
+        assert_eq!(created[0].role(), OrgRole::Admin);
+        assert_eq!(created[0].token_hash(), "hashed:secret");
+        assert_eq!(created[0].invited_by(), fixture.owner_id);
+
+        let sent = fixture.mailer.sent.borrow();
+        assert_eq!(sent[0].to, "new.member@example.com");
+        assert!(sent[0].subject.contains("Riverside Concerts"));
+        assert!(sent[0]
+            .body
+            .contains("https://tickets.example.com/invitations/accept?token=secret"));
+
+        let events = fixture.audit_log.events.lock().unwrap();
+        assert_eq!(events[0].action(), AuditAction::MemberInvitation);
+        assert_eq!(events[0].outcome(), AuditOutcome::Success);
+    }
+
+    #[test]
+    fn test_plain_members_and_outsiders_cannot_invite() {
+        let fixture = Fixture::new();
+
+        assert!(matches!(
+            fixture.invite(fixture.member_id, "member"),
+            Err(AuthError::Forbidden)
+        ));
+        assert!(matches!(
+            fixture.invite(Uuid::new_v4(), "member"),
+            Err(AuthError::OrganizationNotFound)
+        ));
+        assert!(fixture.invitations.created.borrow().is_empty());
+        assert!(fixture.mailer.sent.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_ownership_cannot_be_granted_by_invitation() {
+        let fixture = Fixture::new();
+
+        let result = fixture.invite(fixture.owner_id, "owner");
+
+        assert!(matches!(result, Err(AuthError::InvalidRole)));
+        let events = fixture.audit_log.events.lock().unwrap();
+        assert_eq!(events[0].outcome(), AuditOutcome::Failure);
+    }
+}
@@ -0,0 +1,43 @@
+//! List API keys use case
+//!
+//! Shows a signed-in user the keys they have issued. Keys themselves are
+//! never returned, only their name, prefix and usage.
+
+use crate::domain::api_key::{ApiKey, ApiKeyRepository};
+use crate::domain::auth::TokenService;
+use crate::domain::error::AuthError;
+
+/// Use case for listing the caller's API keys
+pub struct ListApiKeysUseCase<'a, K: ?Sized, T: ?Sized> {
+    api_key_repository: &'a K,
+    token_service: &'a T,
+}
+
+impl<'a, K, T> ListApiKeysUseCase<'a, K, T>
+where
+    K: ApiKeyRepository + ?Sized,
+    T: TokenService + ?Sized,
+{
+    /// Create a new use case instance
+    pub fn new(api_key_repository: &'a K, token_service: &'a T) -> Self {
+        Self {
+            api_key_repository,
+            token_service,
+        }
+    }
+
+    /// Execute the listing, newest keys first; revoked keys are left out
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidToken` / `AuthError::TokenExpired` if the token is invalid
+    /// - `AuthError::Forbidden` if the caller authenticated with an API key or as a service
+    /// - `AuthError::Internal` on infrastructure failures
+    pub fn execute(&self, token: &str) -> Result<Vec<ApiKey>, AuthError> {
+        let token_data = self.token_service.validate_token(token)?;
+        if !token_data.is_session() {
+            return Err(AuthError::Forbidden);
+        }
+
+        self.api_key_repository.list_for_user(token_data.user_id)
+    }
+}
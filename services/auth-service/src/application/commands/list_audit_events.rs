@@ -0,0 +1,213 @@
+//! List audit events use case
+//!
+//! Lets administrators review the security audit log. Only callers whose
+//! token carries the `audit:read` permission may read it.
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::domain::audit::{
+    AuditAction, AuditCursor, AuditEventRepository, AuditFilter, AuditOutcome, AuditPage,
+};
+use crate::domain::auth::TokenService;
+use crate::domain::error::AuthError;
+use crate::domain::role::{authorize, Permission};
+use crate::domain::user_search::page_size;
+
+/// Input for listing audit events
+#[derive(Debug, Default)]
+pub struct ListAuditEventsCommand {
+    /// Access token of the acting administrator
+    pub token: String,
+    /// Action identifier, e.g. `login.password`
+    pub action: Option<String>,
+    pub outcome: Option<AuditOutcome>,
+    pub actor_id: Option<Uuid>,
+    pub target_id: Option<Uuid>,
+    /// Occurred at or after this instant
+    pub occurred_after: Option<DateTime<Utc>>,
+    /// Occurred strictly before this instant
+    pub occurred_before: Option<DateTime<Utc>>,
+    /// Cursor returned with the previous page
+    pub cursor: Option<String>,
+    /// Page size; 0 for the default, capped at the maximum
+    pub limit: usize,
+}
+
+/// Use case for listing audit events
+pub struct ListAuditEventsUseCase<'a, A: ?Sized, T: ?Sized> {
+    audit_repository: &'a A,
+    token_service: &'a T,
+}
+
+impl<'a, A, T> ListAuditEventsUseCase<'a, A, T>
+where
+    A: AuditEventRepository + ?Sized,
+    T: TokenService + ?Sized,
+{
+    /// Create a new use case instance
+    pub fn new(audit_repository: &'a A, token_service: &'a T) -> Self {
+        Self {
+            audit_repository,
+            token_service,
+        }
+    }
+
+    /// Execute the listing, newest events first
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidToken` / `AuthError::TokenExpired` if the token is invalid
+    /// - `AuthError::Forbidden` if the caller may not read the audit log
+    /// - `AuthError::InvalidAuditAction` if the action filter is unknown
+    /// - `AuthError::InvalidCursor` if the cursor is malformed
+    /// - `AuthError::Internal` on infrastructure failures
+    pub fn execute(&self, command: ListAuditEventsCommand) -> Result<AuditPage, AuthError> {
+        let actor = self.token_service.validate_token(&command.token)?;
+        authorize(&actor, Permission::ReadAuditLog)?;
+
+        let filter = AuditFilter {
+            action: command
+                .action
+                .as_deref()
+                .map(str::parse::<AuditAction>)
+                .transpose()?,
+            outcome: command.outcome,
+            actor_id: command.actor_id,
+            target_id: command.target_id,
+            occurred_after: command.occurred_after,
+            occurred_before: command.occurred_before,
+        };
+        let cursor = command
+            .cursor
+            .as_deref()
+            .map(AuditCursor::decode)
+            .transpose()?;
+
+        self.audit_repository
+            .search(&filter, cursor.as_ref(), page_size(command.limit))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+    use crate::application::commands::test_support::token_data;
+    use crate::domain::auth::TokenData;
+    use crate::domain::role::Role;
+    use crate::domain::user::User;
+
+    // Audit repository recording the queries it receives
+    #[derive(Default)]
+    struct MockAuditEventRepository {
+        queries: RefCell<Vec<(AuditFilter, Option<AuditCursor>, usize)>>,
+    }
+
+    impl AuditEventRepository for MockAuditEventRepository {
+        fn search(
+            &self,
+            filter: &AuditFilter,
+            cursor: Option<&AuditCursor>,
+            limit: usize,
+        ) -> Result<AuditPage, AuthError> {
+            self.queries
+                .borrow_mut()
+                .push((filter.clone(), cursor.copied(), limit));
+            Ok(AuditPage {
+                events: vec![],
+                next_cursor: None,
+            })
+        }
+
+        fn purge_older_than(&self, _cutoff: DateTime<Utc>) -> Result<usize, AuthError> {
+            Ok(0)
+        }
+    }
+
+    // Token service mapping "admin" and "support" tokens to roles
+    struct MockTokenService;
+
+    impl TokenService for MockTokenService {
+        fn create_token(
+            &self,
+            _user: &User,
+            _session_id: Option<Uuid>,
+        ) -> Result<String, AuthError> {
+            Ok("token".to_string())
+        }
+
+        fn validate_token(&self, token: &str) -> Result<TokenData, AuthError> {
+            let roles = match token {
+                "admin" => vec![Role::Admin],
+                "support" => vec![Role::SupportAgent],
+                _ => return Err(AuthError::InvalidToken),
+            };
+            Ok(TokenData {
+                roles,
+                ..token_data(Uuid::new_v4())
+            })
+        }
+
+        fn revoke_token(&self, _token: &TokenData) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn is_revoked(&self, _token: &TokenData) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+    }
+
+    #[test]
+    fn test_admin_lists_events() {
+        let repo = MockAuditEventRepository::default();
+        let target_id = Uuid::new_v4();
+
+        ListAuditEventsUseCase::new(&repo, &MockTokenService)
+            .execute(ListAuditEventsCommand {
+                token: "admin".to_string(),
+                action: Some("login.password".to_string()),
+                outcome: Some(AuditOutcome::Failure),
+                target_id: Some(target_id),
+                limit: 500,
+                ..ListAuditEventsCommand::default()
+            })
+            .unwrap();
+
+        let queries = repo.queries.borrow();
+        let (filter, cursor, limit) = &queries[0];
+        assert_eq!(filter.action, Some(AuditAction::PasswordLogin));
+        assert_eq!(filter.outcome, Some(AuditOutcome::Failure));
+        assert_eq!(filter.target_id, Some(target_id));
+        assert_eq!(*cursor, None);
+        assert_eq!(*limit, 100);
+    }
+
+    #[test]
+    fn test_support_agent_forbidden() {
+        let repo = MockAuditEventRepository::default();
+
+        let result =
+            ListAuditEventsUseCase::new(&repo, &MockTokenService).execute(ListAuditEventsCommand {
+                token: "support".to_string(),
+                ..ListAuditEventsCommand::default()
+            });
+
+        assert!(matches!(result, Err(AuthError::Forbidden)));
+        assert!(repo.queries.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_unknown_action_rejected() {
+        let repo = MockAuditEventRepository::default();
+
+        let result =
+            ListAuditEventsUseCase::new(&repo, &MockTokenService).execute(ListAuditEventsCommand {
+                token: "admin".to_string(),
+                action: Some("login.teleport".to_string()),
+                ..ListAuditEventsCommand::default()
+            });
+
+        assert!(matches!(result, Err(AuthError::InvalidAuditAction)));
+    }
+}
@@ -0,0 +1,42 @@
+//! List OAuth clients use case
+//!
+//! Shows administrators the registered clients. Secrets are never
+//! returned, only what each client is for and may request.
+
+use crate::domain::auth::TokenService;
+use crate::domain::error::AuthError;
+use crate::domain::oauth_client::{OAuthClient, OAuthClientRepository};
+use crate::domain::role::{authorize, Permission};
+
+/// Use case for listing OAuth clients
+pub struct ListOAuthClientsUseCase<'a, C: ?Sized, T: ?Sized> {
+    client_repository: &'a C,
+    token_service: &'a T,
+}
+
+impl<'a, C, T> ListOAuthClientsUseCase<'a, C, T>
+where
+    C: OAuthClientRepository + ?Sized,
+    T: TokenService + ?Sized,
+{
+    /// Create a new use case instance
+    pub fn new(client_repository: &'a C, token_service: &'a T) -> Self {
+        Self {
+            client_repository,
+            token_service,
+        }
+    }
+
+    /// Execute the listing, newest clients first; revoked clients are left out
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidToken` / `AuthError::TokenExpired` if the token is invalid
+    /// - `AuthError::Forbidden` if the caller may not manage clients
+    /// - `AuthError::Internal` on infrastructure failures
+    pub fn execute(&self, token: &str) -> Result<Vec<OAuthClient>, AuthError> {
+        let actor = self.token_service.validate_token(token)?;
+        authorize(&actor, Permission::ManageClients)?;
+
+        self.client_repository.list()
+    }
+}
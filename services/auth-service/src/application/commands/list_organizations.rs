@@ -0,0 +1,44 @@
+//! List organizations use case
+//!
+//! Shows a user the organizations they belong to and their role in each,
+//! so clients can offer to switch between them.
+
+use crate::domain::auth::TokenService;
+use crate::domain::error::AuthError;
+use crate::domain::organization::{Membership, Organization, OrganizationRepository};
+
+/// Use case for listing the caller's organizations
+pub struct ListOrganizationsUseCase<'a, O: ?Sized, T: ?Sized> {
+    organization_repository: &'a O,
+    token_service: &'a T,
+}
+
+impl<'a, O, T> ListOrganizationsUseCase<'a, O, T>
+where
+    O: OrganizationRepository + ?Sized,
+    T: TokenService + ?Sized,
+{
+    /// Create a new use case instance
+    pub fn new(organization_repository: &'a O, token_service: &'a T) -> Self {
+        Self {
+            organization_repository,
+            token_service,
+        }
+    }
+
+    /// Execute the listing, oldest membership first
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidToken` / `AuthError::TokenExpired` if the token is invalid
+    /// - `AuthError::Forbidden` if the caller authenticated as a service
+    /// - `AuthError::Internal` on infrastructure failures
+    pub fn execute(&self, token: &str) -> Result<Vec<(Organization, Membership)>, AuthError> {
+        let token_data = self.token_service.validate_token(token)?;
+        if token_data.is_service() {
+            return Err(AuthError::Forbidden);
+        }
+
+        self.organization_repository
+            .list_for_user(token_data.user_id)
+    }
+}
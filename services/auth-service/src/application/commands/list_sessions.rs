@@ -0,0 +1,60 @@
+//! List sessions use case
+//!
+//! Shows a signed-in user the devices they are logged in on, so they can
+//! spot and end sessions they do not recognise.
+
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::domain::auth::TokenService;
+use crate::domain::error::AuthError;
+use crate::domain::session::{Session, SessionRepository};
+
+/// Output of listing the caller's sessions
+#[derive(Debug)]
+pub struct ListSessionsResult {
+    /// Active sessions, most recently seen first
+    pub sessions: Vec<Session>,
+    /// Session the caller's token belongs to, if it is one of them
+    pub current_session_id: Option<Uuid>,
+}
+
+/// Use case for listing the caller's sessions
+pub struct ListSessionsUseCase<'a, D: ?Sized, T: ?Sized> {
+    session_repository: &'a D,
+    token_service: &'a T,
+}
+
+impl<'a, D, T> ListSessionsUseCase<'a, D, T>
+where
+    D: SessionRepository + ?Sized,
+    T: TokenService + ?Sized,
+{
+    /// Create a new use case instance
+    pub fn new(session_repository: &'a D, token_service: &'a T) -> Self {
+        Self {
+            session_repository,
+            token_service,
+        }
+    }
+
+    /// Execute the listing; ended and expired sessions are left out
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidToken` / `AuthError::TokenExpired` if the token is invalid
+    /// - `AuthError::Forbidden` if the caller authenticated with an API key or as a service
+    /// - `AuthError::Internal` on infrastructure failures
+    pub fn execute(&self, token: &str) -> Result<ListSessionsResult, AuthError> {
+        let token_data = self.token_service.validate_token(token)?;
+        if !token_data.is_session() {
+            return Err(AuthError::Forbidden);
+        }
+
+        Ok(ListSessionsResult {
+            sessions: self
+                .session_repository
+                .find_active_for_user(token_data.user_id, Utc::now())?,
+            current_session_id: token_data.session_id,
+        })
+    }
+}
@@ -0,0 +1,215 @@
+//! List users use case
+//!
+//! Lets back-office staff find accounts without database access. Only
+//! callers whose token carries the `users:read` permission may list users.
+
+use chrono::{DateTime, Utc};
+
+use crate::domain::auth::TokenService;
+use crate::domain::error::AuthError;
+use crate::domain::role::{authorize, Permission, Role};
+use crate::domain::user_search::{
+    page_size, EmailMatch, UserCursor, UserFilter, UserPage, UserSearchRepository,
+};
+
+/// Input for listing users
+#[derive(Debug, Default)]
+pub struct ListUsersCommand {
+    /// Access token of the acting staff member
+    pub token: String,
+    pub is_active: Option<bool>,
+    pub role: Option<String>,
+    /// Created at or after this instant
+    pub created_after: Option<DateTime<Utc>>,
+    /// Created strictly before this instant
+    pub created_before: Option<DateTime<Utc>>,
+    pub email: Option<EmailMatch>,
+    /// Cursor returned with the previous page
+    pub cursor: Option<String>,
+    /// Page size; 0 for the default, capped at the maximum
+    pub limit: usize,
+}
+
+/// Use case for listing users
+pub struct ListUsersUseCase<'a, R: ?Sized, T: ?Sized> {
+    search_repository: &'a R,
+    token_service: &'a T,
+}
+
+impl<'a, R, T> ListUsersUseCase<'a, R, T>
+where
+    R: UserSearchRepository + ?Sized,
+    T: TokenService + ?Sized,
+{
+    /// Create a new use case instance
+    pub fn new(search_repository: &'a R, token_service: &'a T) -> Self {
+        Self {
+            search_repository,
+            token_service,
+        }
+    }
+
+    /// Execute the listing, newest users first
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidToken` / `AuthError::TokenExpired` if the token is invalid
+    /// - `AuthError::Forbidden` if the caller may not read users
+    /// - `AuthError::InvalidRole` if the role filter is unknown
+    /// - `AuthError::InvalidCursor` if the cursor is malformed
+    /// - `AuthError::Internal` on infrastructure failures
+    pub fn execute(&self, command: ListUsersCommand) -> Result<UserPage, AuthError> {
+        let actor = self.token_service.validate_token(&command.token)?;
+        authorize(&actor, Permission::ReadUsers)?;
+
+        let filter = UserFilter {
+            is_active: command.is_active,
+            role: command
+                .role
+                .as_deref()
+                .map(str::parse::<Role>)
+                .transpose()?,
+            created_after: command.created_after,
+            created_before: command.created_before,
+            // An empty search term matches everyone
+            email: command.email.filter(|email| !email.term().is_empty()),
+        };
+        let cursor = command
+            .cursor
+            .as_deref()
+            .map(UserCursor::decode)
+            .transpose()?;
+
+        self.search_repository
+            .search(&filter, cursor.as_ref(), page_size(command.limit))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::application::commands::test_support::token_data;
+    use crate::domain::auth::TokenData;
+    use crate::domain::user::User;
+
+    // Search repository recording the queries it receives
+    #[derive(Default)]
+    struct MockUserSearchRepository {
+        queries: RefCell<Vec<(UserFilter, Option<UserCursor>, usize)>>,
+    }
+
+    impl UserSearchRepository for MockUserSearchRepository {
+        fn search(
+            &self,
+            filter: &UserFilter,
+            cursor: Option<&UserCursor>,
+            limit: usize,
+        ) -> Result<UserPage, AuthError> {
+            self.queries
+                .borrow_mut()
+                .push((filter.clone(), cursor.copied(), limit));
+            Ok(UserPage {
+                users: vec![],
+                next_cursor: None,
+            })
+        }
+    }
+
+    // Token service mapping "support" and "customer" tokens to roles
+    struct MockTokenService;
+
+    impl TokenService for MockTokenService {
+        fn create_token(
+            &self,
+            _user: &User,
+            _session_id: Option<Uuid>,
+        ) -> Result<String, AuthError> {
+            Ok("token".to_string())
+        }
+
+        fn validate_token(&self, token: &str) -> Result<TokenData, AuthError> {
+            let roles = match token {
+                "support" => vec![Role::SupportAgent],
+                "customer" => vec![Role::Customer],
+                _ => return Err(AuthError::InvalidToken),
+            };
+            Ok(TokenData {
+                roles,
+                ..token_data(Uuid::new_v4())
+            })
+        }
+
+        fn revoke_token(&self, _token: &TokenData) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn is_revoked(&self, _token: &TokenData) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+    }
+
+    #[test]
+    fn test_support_agent_lists_users() {
+        let repo = MockUserSearchRepository::default();
+        let cursor = UserCursor {
+            created_at: DateTime::from_timestamp_micros(1_700_000_000_000_000).unwrap(),
+            id: Uuid::new_v4(),
+        };
+
+        ListUsersUseCase::new(&repo, &MockTokenService)
+            .execute(ListUsersCommand {
+                token: "support".to_string(),
+                is_active: Some(false),
+                role: Some("Organizer".to_string()),
+                email: Some(EmailMatch::Prefix("  ".to_string())),
+                cursor: Some(cursor.encode()),
+                limit: 500,
+                ..ListUsersCommand::default()
+            })
+            .unwrap();
+
+        let queries = repo.queries.borrow();
+        let (filter, used_cursor, limit) = &queries[0];
+        assert_eq!(filter.is_active, Some(false));
+        assert_eq!(filter.role, Some(Role::Organizer));
+        assert_eq!(filter.email, None);
+        assert_eq!(*used_cursor, Some(cursor));
+        assert_eq!(*limit, 100);
+    }
+
+    #[test]
+    fn test_customer_forbidden() {
+        let repo = MockUserSearchRepository::default();
+
+        let result = ListUsersUseCase::new(&repo, &MockTokenService).execute(ListUsersCommand {
+            token: "customer".to_string(),
+            ..ListUsersCommand::default()
+        });
+
+        assert!(matches!(result, Err(AuthError::Forbidden)));
+        assert!(repo.queries.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_filters_rejected() {
+        let repo = MockUserSearchRepository::default();
+        let use_case = ListUsersUseCase::new(&repo, &MockTokenService);
+
+        let result = use_case.execute(ListUsersCommand {
+            token: "support".to_string(),
+            role: Some("superuser".to_string()),
+            ..ListUsersCommand::default()
+        });
+        assert!(matches!(result, Err(AuthError::InvalidRole)));
+
+        let result = use_case.execute(ListUsersCommand {
+            token: "support".to_string(),
+            cursor: Some("garbage".to_string()),
+            ..ListUsersCommand::default()
+        });
+        assert!(matches!(result, Err(AuthError::InvalidCursor)));
+    }
+}
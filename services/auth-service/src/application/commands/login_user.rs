@@ -1,81 +1,467 @@
 //! Login user use case
 
-use crate::domain::auth::{PasswordHasher, TokenService, UserRepository};
+use std::net::IpAddr;
+
+use chrono::{DateTime, Duration, Utc};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::domain::audit::{AsyncAuditLog, AuditAction, AuditEvent};
+use crate::domain::auth::{
+    AsyncPasswordHasher, AsyncUserRepository, OpaqueTokenGenerator, TokenService,
+};
 use crate::domain::error::AuthError;
+use crate::domain::lockout::{
+    AsyncLoginThrottleRepository, LockoutPolicy, LoginThrottle, ThrottleKey,
+};
+use crate::domain::mfa::{AsyncMfaChallengeRepository, AsyncMfaRepository, MfaChallenge};
+use crate::domain::refresh_token::{AsyncRefreshTokenRepository, RefreshToken};
+use crate::domain::session::{AsyncSessionRepository, Session, SessionClient};
+use crate::domain::user::User;
 
 /// Input for user login
 #[derive(Debug)]
 pub struct LoginUserCommand {
     pub email: String,
     pub password: String,
+    /// Source address of the request, when known
+    pub client_ip: Option<IpAddr>,
+    /// `User-Agent` of the client, recorded with the session
+    pub user_agent: Option<String>,
 }
 
 /// Output after successful login
 #[derive(Debug)]
 pub struct LoginUserResult {
     pub token: String,
+    pub refresh_token: String,
     pub user_id: uuid::Uuid,
     pub email: String,
     pub display_name: Option<String>,
 }
 
+/// Outcome of a successful first factor: a correct password, a federated
+/// sign-in or a magic link
+#[derive(Debug)]
+pub enum LoginOutcome {
+    /// The user is signed in
+    Authenticated(LoginUserResult),
+    /// The user has two-factor authentication enabled and must complete
+    /// the login with `VerifyMfaUseCase`
+    MfaRequired {
+        /// Opaque secret identifying the pending login
+        challenge_token: String,
+        /// Seconds until the challenge expires
+        expires_in: i64,
+    },
+}
+
 /// Use case for user login
-pub struct LoginUserUseCase<'a, R: ?Sized, H: ?Sized, T: ?Sized> {
+///
+/// A successful login starts a new session. Failed attempts
+/// are counted per email address and source IP, and either can be locked out.
+pub struct LoginUserUseCase<
+    'a,
+    R: ?Sized,
+    H: ?Sized,
+    T: ?Sized,
+    S: ?Sized,
+    G: ?Sized,
+    M: ?Sized,
+    C: ?Sized,
+    L: ?Sized,
+    D: ?Sized,
+    A: ?Sized,
+> {
     user_repository: &'a R,
     password_hasher: &'a H,
     token_service: &'a T,
+    refresh_token_repository: &'a S,
+    session_repository: &'a D,
+    token_generator: &'a G,
+    mfa_repository: &'a M,
+    mfa_challenge_repository: &'a C,
+    throttle_repository: &'a L,
+    lockout_policy: LockoutPolicy,
+    refresh_token_ttl: Duration,
+    mfa_challenge_ttl: Duration,
+    require_verified_email: bool,
+    audit_log: &'a A,
 }
 
-impl<'a, R, H, T> LoginUserUseCase<'a, R, H, T>
+impl<'a, R, H, T, S, G, M, C, L, D, A> LoginUserUseCase<'a, R, H, T, S, G, M, C, L, D, A>
 where
-    R: UserRepository + ?Sized,
-    H: PasswordHasher + ?Sized,
+    R: AsyncUserRepository + ?Sized,
+    H: AsyncPasswordHasher + ?Sized,
     T: TokenService + ?Sized,
+    S: AsyncRefreshTokenRepository + ?Sized,
+    G: OpaqueTokenGenerator + ?Sized,
+    M: AsyncMfaRepository + ?Sized,
+    C: AsyncMfaChallengeRepository + ?Sized,
+    L: AsyncLoginThrottleRepository + ?Sized,
+    D: AsyncSessionRepository + ?Sized,
+    A: AsyncAuditLog + ?Sized,
 {
     /// Create a new use case instance
-    pub fn new(user_repository: &'a R, password_hasher: &'a H, token_service: &'a T) -> Self {
+    ///
+    /// With `require_verified_email`, users who have not confirmed their
+    /// address cannot log in.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        user_repository: &'a R,
+        password_hasher: &'a H,
+        token_service: &'a T,
+        refresh_token_repository: &'a S,
+        session_repository: &'a D,
+        token_generator: &'a G,
+        mfa_repository: &'a M,
+        mfa_challenge_repository: &'a C,
+        throttle_repository: &'a L,
+        lockout_policy: LockoutPolicy,
+        refresh_token_ttl: Duration,
+        mfa_challenge_ttl: Duration,
+        require_verified_email: bool,
+        audit_log: &'a A,
+    ) -> Self {
         Self {
             user_repository,
             password_hasher,
             token_service,
+            refresh_token_repository,
+            session_repository,
+            token_generator,
+            mfa_repository,
+            mfa_challenge_repository,
+            throttle_repository,
+            lockout_policy,
+            refresh_token_ttl,
+            mfa_challenge_ttl,
+            require_verified_email,
+            audit_log,
         }
     }
 
     /// Execute the login
     ///
     /// # Errors
+    /// - `AuthError::AccountLocked` if the email address or source IP is locked out
     /// - `AuthError::InvalidCredentials` if email/password is wrong
     /// - `AuthError::AccountInactive` if user account is deactivated
+    /// - `AuthError::EmailNotVerified` if verification is required and missing
     /// - `AuthError::Internal` on infrastructure failures
-    pub fn execute(&self, command: LoginUserCommand) -> Result<LoginUserResult, AuthError> {
-        // Find user by email
-        let user = self
-            .user_repository
-            .find_by_email(&command.email)
-            .map_err(|e| match e {
-                AuthError::UserNotFound => AuthError::InvalidCredentials,
-                other => other,
-            })?;
+    pub async fn execute(&self, command: LoginUserCommand) -> Result<LoginOutcome, AuthError> {
+        let mut user_id = None;
+        let result = self.login(command, &mut user_id).await;
+
+        // Attempts on unknown addresses have no target; the address tried is
+        // personal data and stays out of the audit trail
+        let event = login_audit_event(AuditAction::PasswordLogin, &result, user_id);
+        self.audit_log.record(event).await;
+        result
+    }
+
+    /// Run the login, leaving the id of the account tried in `user_id`
+    async fn login(
+        &self,
+        command: LoginUserCommand,
+        user_id: &mut Option<Uuid>,
+    ) -> Result<LoginOutcome, AuthError> {
+        let now = Utc::now();
+        let mut throttle_keys = vec![ThrottleKey::email(&command.email)];
+        throttle_keys.extend(command.client_ip.map(ThrottleKey::ip));
+
+        // Locked subjects are turned away before the password is looked at
+        ensure_not_locked(self.throttle_repository, &throttle_keys, now).await?;
+
+        // Find user by email; unknown addresses count as failures too
+        let user = match self.user_repository.find_by_email(&command.email).await {
+            Ok(user) => user,
+            Err(AuthError::UserNotFound) => {
+                record_failed_login(
+                    self.throttle_repository,
+                    &self.lockout_policy,
+                    &throttle_keys,
+                    now,
+                )
+                .await?;
+                return Err(AuthError::InvalidCredentials);
+            }
+            Err(e) => return Err(e),
+        };
+        *user_id = Some(user.id().as_uuid());
 
         // Check if account is active
         if !user.is_active() {
             return Err(AuthError::AccountInactive);
         }
 
-        // Verify password
-        let is_valid = self
-            .password_hasher
-            .verify(&command.password, user.hashed_password())?;
+        // Verify password; users of external identity providers have none
+        let is_valid = match user.hashed_password() {
+            Some(hashed) => {
+                self.password_hasher
+                    .verify(&command.password, hashed)
+                    .await?
+            }
+            None => false,
+        };
 
         if !is_valid {
+            record_failed_login(
+                self.throttle_repository,
+                &self.lockout_policy,
+                &throttle_keys,
+                now,
+            )
+            .await?;
             return Err(AuthError::InvalidCredentials);
         }
 
-        // Generate JWT token
-        let token = self.token_service.create_token(&user)?;
+        // Only reveal the verification state to someone who knows the password
+        if self.require_verified_email && !user.is_email_verified() {
+            return Err(AuthError::EmailNotVerified);
+        }
+
+        let client = SessionClient {
+            user_agent: command.user_agent,
+            ip_address: command.client_ip,
+        };
+        let outcome = complete_login(
+            &user,
+            &client,
+            self.token_service,
+            self.refresh_token_repository,
+            self.session_repository,
+            self.token_generator,
+            self.mfa_repository,
+            self.mfa_challenge_repository,
+            self.refresh_token_ttl,
+            self.mfa_challenge_ttl,
+        )
+        .await?;
+
+        // With MFA the counter is only cleared once the second factor passes
+        if let LoginOutcome::Authenticated(_) = outcome {
+            self.throttle_repository.clear(&throttle_keys[0]).await?;
+        }
+
+        Ok(outcome)
+    }
+}
+
+/// Audit entry for a login attempt on the account `user_id`, when known
+pub(crate) fn login_audit_event(
+    action: AuditAction,
+    result: &Result<LoginOutcome, AuthError>,
+    user_id: Option<Uuid>,
+) -> AuditEvent {
+    let mut event = AuditEvent::new(action).with_result(result);
+    if let Some(user_id) = user_id {
+        event = event.with_target(user_id);
+        match result {
+            Ok(LoginOutcome::Authenticated(_)) => event = event.with_actor(user_id),
+            Ok(LoginOutcome::MfaRequired { .. }) => {
+                event = event
+                    .with_actor(user_id)
+                    .with_detail("Second factor required");
+            }
+            Err(_) => {}
+        }
+    }
+    event
+}
+
+/// Sign in a user whose first factor has been checked
+///
+/// Users with two-factor authentication get a challenge instead of tokens;
+/// the session from `client` is only started once they pass it.
+///
+/// # Errors
+/// Returns `AuthError::Internal` on infrastructure failures
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn complete_login<T, S, D, G, M, C>(
+    user: &User,
+    client: &SessionClient,
+    token_service: &T,
+    refresh_token_repository: &S,
+    session_repository: &D,
+    token_generator: &G,
+    mfa_repository: &M,
+    mfa_challenge_repository: &C,
+    refresh_token_ttl: Duration,
+    mfa_challenge_ttl: Duration,
+) -> Result<LoginOutcome, AuthError>
+where
+    T: TokenService + ?Sized,
+    S: AsyncRefreshTokenRepository + ?Sized,
+    D: AsyncSessionRepository + ?Sized,
+    G: OpaqueTokenGenerator + ?Sized,
+    M: AsyncMfaRepository + ?Sized,
+    C: AsyncMfaChallengeRepository + ?Sized,
+{
+    // Hold back the tokens until the second factor is checked
+    let mfa_enabled = mfa_repository
+        .find_totp(user.id().as_uuid())
+        .await?
+        .is_some_and(|credential| credential.is_confirmed());
+
+    if mfa_enabled {
+        let (challenge, outcome) = issue_mfa_challenge(user, token_generator, mfa_challenge_ttl);
+        mfa_challenge_repository.create(&challenge).await?;
+        return Ok(outcome);
+    }
+
+    start_session(
+        user,
+        client,
+        token_service,
+        refresh_token_repository,
+        session_repository,
+        token_generator,
+        refresh_token_ttl,
+    )
+    .await
+    .map(LoginOutcome::Authenticated)
+}
+
+/// Reject the attempt if any of `keys` is locked out
+///
+/// # Errors
+/// - `AuthError::AccountLocked` with the longest remaining lockout
+/// - `AuthError::Internal` on infrastructure failures
+pub(crate) async fn ensure_not_locked<L>(
+    throttle_repository: &L,
+    keys: &[ThrottleKey],
+    now: DateTime<Utc>,
+) -> Result<(), AuthError>
+where
+    L: AsyncLoginThrottleRepository + ?Sized,
+{
+    let mut retry_after = None;
+    for key in keys {
+        if let Some(throttle) = throttle_repository.find(key).await? {
+            retry_after = retry_after.max(throttle.retry_after(now));
+        }
+    }
+    lockout(retry_after)
+}
+
+/// Refuse an attempt for `retry_after`, the longest lockout of its keys
+pub(crate) fn lockout(retry_after: Option<Duration>) -> Result<(), AuthError> {
+    match retry_after {
+        Some(retry_after) => Err(AuthError::AccountLocked {
+            // Round up so clients never retry a moment too early
+            retry_after_secs: (retry_after.num_milliseconds() + 999) / 1000,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Count a failed attempt against each of `keys`
+///
+/// # Errors
+/// Returns `AuthError::Internal` if a counter cannot be updated
+pub(crate) async fn record_failed_login<L>(
+    throttle_repository: &L,
+    policy: &LockoutPolicy,
+    keys: &[ThrottleKey],
+    now: DateTime<Utc>,
+) -> Result<(), AuthError>
+where
+    L: AsyncLoginThrottleRepository + ?Sized,
+{
+    for key in keys {
+        let throttle = throttle_repository.record_failure(key, policy, now).await?;
+        warn_on_lockout(key, &throttle, now);
+    }
+    Ok(())
+}
+
+/// Log the lockout a failed attempt against `key` started, if any
+pub(crate) fn warn_on_lockout(key: &ThrottleKey, throttle: &LoginThrottle, now: DateTime<Utc>) {
+    if let Some(lockout) = throttle.retry_after(now) {
+        warn!(
+            scope = %key.scope(),
+            failed_attempts = throttle.failed_attempts(),
+            lockout_secs = lockout.num_seconds(),
+            "Login locked out after repeated failures"
+        );
+    }
+}
+
+/// Challenge a user with two-factor authentication must answer, and the
+/// outcome telling the client so
+fn issue_mfa_challenge<G>(
+    user: &User,
+    token_generator: &G,
+    mfa_challenge_ttl: Duration,
+) -> (MfaChallenge, LoginOutcome)
+where
+    G: OpaqueTokenGenerator + ?Sized,
+{
+    let challenge_token = token_generator.generate();
+    let challenge = MfaChallenge::issue(
+        user.id().as_uuid(),
+        token_generator.hash(&challenge_token),
+        mfa_challenge_ttl,
+    );
+    let outcome = LoginOutcome::MfaRequired {
+        challenge_token,
+        expires_in: mfa_challenge_ttl.num_seconds(),
+    };
+    (challenge, outcome)
+}
+
+/// Session and refresh token of a new login, to be stored before the
+/// access token is handed out
+struct NewLogin {
+    session: Session,
+    refresh_token: RefreshToken,
+    /// Secret of `refresh_token`, for the client
+    secret: String,
+}
+
+impl NewLogin {
+    /// Issue the session and refresh token family, which share an ID
+    fn issue<G>(
+        user: &User,
+        client: &SessionClient,
+        token_generator: &G,
+        refresh_token_ttl: Duration,
+    ) -> Self
+    where
+        G: OpaqueTokenGenerator + ?Sized,
+    {
+        let session_id = Uuid::new_v4();
+        let secret = token_generator.generate();
+        let refresh_token = RefreshToken::issue(
+            user.id().as_uuid(),
+            session_id,
+            token_generator.hash(&secret),
+            refresh_token_ttl,
+        );
+        let session = Session::start(
+            session_id,
+            user.id().as_uuid(),
+            client,
+            refresh_token.expires_at(),
+        );
+        Self {
+            session,
+            refresh_token,
+            secret,
+        }
+    }
+
+    /// Create the access token bound to the stored session
+    fn finish<T>(self, user: &User, token_service: &T) -> Result<LoginUserResult, AuthError>
+    where
+        T: TokenService + ?Sized,
+    {
+        let token = token_service.create_token(user, Some(self.session.id()))?;
 
         Ok(LoginUserResult {
             token,
+            refresh_token: self.secret,
             user_id: user.id().as_uuid(),
             email: user.email().as_str().to_string(),
             display_name: user.display_name().map(String::from),
@@ -83,52 +469,98 @@ where
     }
 }
 
+/// Start a new session for the user: a refresh token family, and an
+/// access token bound to it
+///
+/// # Errors
+/// Returns `AuthError::Internal` if the tokens cannot be created or stored
+pub(crate) async fn start_session<T, S, D, G>(
+    user: &User,
+    client: &SessionClient,
+    token_service: &T,
+    refresh_token_repository: &S,
+    session_repository: &D,
+    token_generator: &G,
+    refresh_token_ttl: Duration,
+) -> Result<LoginUserResult, AuthError>
+where
+    T: TokenService + ?Sized,
+    S: AsyncRefreshTokenRepository + ?Sized,
+    D: AsyncSessionRepository + ?Sized,
+    G: OpaqueTokenGenerator + ?Sized,
+{
+    // The session is the refresh token family, and shares its ID
+    let login = NewLogin::issue(user, client, token_generator, refresh_token_ttl);
+    session_repository.create(&login.session).await?;
+    refresh_token_repository.create(&login.refresh_token).await?;
+    login.finish(user, token_service)
+}
+
 #[cfg(test)]
 mod tests {
+    use std::sync::Mutex;
+
     use super::*;
-    use crate::domain::auth::TokenData;
-    use crate::domain::user::{HashedPassword, User};
+    use crate::application::commands::test_support::{
+        MockAuditLog, MockMfaChallengeRepository, MockMfaRepository,
+    };
+    use crate::domain::audit::AuditOutcome;
+    use crate::domain::auth::{BoxFuture, TokenData};
+    use crate::domain::lockout::LoginThrottleRepository;
+    use crate::domain::mfa::TotpCredential;
+    use crate::domain::user::HashedPassword;
+    use crate::infrastructure::memory::in_memory_login_throttle_repository::InMemoryLoginThrottleRepository;
 
     // Mock repository
     struct MockUserRepository {
         user: Option<User>,
     }
 
-    impl UserRepository for MockUserRepository {
-        fn find_by_id(&self, _id: uuid::Uuid) -> Result<User, AuthError> {
-            self.user.clone().ok_or(AuthError::UserNotFound)
+    impl AsyncUserRepository for MockUserRepository {
+        fn find_by_id(&self, _id: uuid::Uuid) -> BoxFuture<'_, Result<User, AuthError>> {
+            let user = self.user.clone().ok_or(AuthError::UserNotFound);
+            Box::pin(async { user })
         }
 
-        fn find_by_email(&self, email: &str) -> Result<User, AuthError> {
-            match &self.user {
+        fn find_by_email<'a>(&'a self, email: &'a str) -> BoxFuture<'a, Result<User, AuthError>> {
+            let user = match &self.user {
                 Some(u) if u.email().as_str() == email => Ok(u.clone()),
                 _ => Err(AuthError::UserNotFound),
-            }
+            };
+            Box::pin(async { user })
         }
 
-        fn exists_by_email(&self, email: &str) -> Result<bool, AuthError> {
-            Ok(self.user.as_ref().map_or(false, |u| u.email().as_str() == email))
+        fn exists_by_email<'a>(&'a self, email: &'a str) -> BoxFuture<'a, Result<bool, AuthError>> {
+            let exists = self
+                .user
+                .as_ref()
+                .is_some_and(|u| u.email().as_str() == email);
+            Box::pin(async move { Ok(exists) })
         }
 
-        fn create(&self, _user: &User) -> Result<User, AuthError> {
-            Err(AuthError::Internal("Not implemented".to_string()))
+        fn create<'a>(&'a self, _user: &'a User) -> BoxFuture<'a, Result<User, AuthError>> {
+            Box::pin(async { Err(AuthError::Internal("Not implemented".to_string())) })
         }
 
-        fn update(&self, _user: &User) -> Result<User, AuthError> {
-            Err(AuthError::Internal("Not implemented".to_string()))
+        fn update<'a>(&'a self, _user: &'a User) -> BoxFuture<'a, Result<User, AuthError>> {
+            Box::pin(async { Err(AuthError::Internal("Not implemented".to_string())) })
         }
     }
 
     // Mock password hasher that expects "correct_password"
     struct MockPasswordHasher;
 
-    impl PasswordHasher for MockPasswordHasher {
-        fn hash(&self, plain: &str) -> Result<HashedPassword, AuthError> {
-            Ok(HashedPassword::from_hash(format!("hashed_{}", plain)))
+    impl AsyncPasswordHasher for MockPasswordHasher {
+        fn hash<'a>(&'a self, plain: &'a str) -> BoxFuture<'a, Result<HashedPassword, AuthError>> {
+            Box::pin(async move { Ok(HashedPassword::from_hash(format!("hashed_{}", plain))) })
         }
 
-        fn verify(&self, plain: &str, _hashed: &HashedPassword) -> Result<bool, AuthError> {
-            Ok(plain == "correct_password")
+        fn verify<'a>(
+            &'a self,
+            plain: &'a str,
+            _hashed: &'a HashedPassword,
+        ) -> BoxFuture<'a, Result<bool, AuthError>> {
+            Box::pin(async move { Ok(plain == "correct_password") })
         }
     }
 
@@ -136,13 +568,131 @@ mod tests {
     struct MockTokenService;
 
     impl TokenService for MockTokenService {
-        fn create_token(&self, _user: &User) -> Result<String, AuthError> {
+        fn create_token(
+            &self,
+            _user: &User,
+            _session_id: Option<Uuid>,
+        ) -> Result<String, AuthError> {
             Ok("mock_token".to_string())
         }
 
         fn validate_token(&self, _token: &str) -> Result<TokenData, AuthError> {
             Err(AuthError::InvalidToken)
         }
+
+        fn revoke_token(&self, _token: &TokenData) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn is_revoked(&self, _token: &TokenData) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+    }
+
+    // Mock refresh token repository that records issued tokens
+    #[derive(Default)]
+    struct MockRefreshTokenRepository {
+        created: Mutex<Vec<RefreshToken>>,
+    }
+
+    impl AsyncRefreshTokenRepository for MockRefreshTokenRepository {
+        fn create<'a>(&'a self, token: &'a RefreshToken) -> BoxFuture<'a, Result<(), AuthError>> {
+            self.created.lock().unwrap().push(token.clone());
+            Box::pin(async { Ok(()) })
+        }
+
+        fn find_by_hash<'a>(
+            &'a self,
+            _token_hash: &'a str,
+        ) -> BoxFuture<'a, Result<RefreshToken, AuthError>> {
+            Box::pin(async { Err(AuthError::InvalidToken) })
+        }
+
+        fn rotate<'a>(
+            &'a self,
+            _current_id: Uuid,
+            _next: &'a RefreshToken,
+        ) -> BoxFuture<'a, Result<bool, AuthError>> {
+            Box::pin(async { Ok(false) })
+        }
+
+        fn revoke_family(&self, _family_id: Uuid) -> BoxFuture<'_, Result<(), AuthError>> {
+            Box::pin(async { Ok(()) })
+        }
+
+        fn revoke_all_for_user(&self, _user_id: Uuid) -> BoxFuture<'_, Result<(), AuthError>> {
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    // Session store recording started sessions
+    #[derive(Default)]
+    struct MockSessionRepository {
+        created: Mutex<Vec<Session>>,
+    }
+
+    impl AsyncSessionRepository for MockSessionRepository {
+        fn create<'a>(&'a self, session: &'a Session) -> BoxFuture<'a, Result<(), AuthError>> {
+            self.created.lock().unwrap().push(session.clone());
+            Box::pin(async { Ok(()) })
+        }
+
+        fn find_by_id(&self, _id: Uuid) -> BoxFuture<'_, Result<Session, AuthError>> {
+            Box::pin(async { Err(AuthError::SessionNotFound) })
+        }
+
+        fn find_active_for_user(
+            &self,
+            _user_id: Uuid,
+            _now: DateTime<Utc>,
+        ) -> BoxFuture<'_, Result<Vec<Session>, AuthError>> {
+            let created = self.created.lock().unwrap().clone();
+            Box::pin(async { Ok(created) })
+        }
+
+        fn touch(
+            &self,
+            _id: Uuid,
+            _now: DateTime<Utc>,
+            _expires_at: DateTime<Utc>,
+        ) -> BoxFuture<'_, Result<(), AuthError>> {
+            Box::pin(async { Ok(()) })
+        }
+
+        fn set_organization(
+            &self,
+            _id: Uuid,
+            _organization_id: Option<Uuid>,
+        ) -> BoxFuture<'_, Result<(), AuthError>> {
+            Box::pin(async { Ok(()) })
+        }
+
+        fn is_revoked(&self, _id: Uuid) -> BoxFuture<'_, Result<bool, AuthError>> {
+            Box::pin(async { Ok(false) })
+        }
+    }
+
+    // Mock generator with a fixed secret
+    struct MockTokenGenerator;
+
+    impl OpaqueTokenGenerator for MockTokenGenerator {
+        fn generate(&self) -> String {
+            "refresh_secret".to_string()
+        }
+
+        fn hash(&self, token: &str) -> String {
+            format!("hash_{}", token)
+        }
+    }
+
+    fn lockout_policy() -> LockoutPolicy {
+        LockoutPolicy {
+            email_threshold: 2,
+            ip_threshold: 3,
+            base_lockout: Duration::seconds(30),
+            max_lockout: Duration::minutes(10),
+            reset_after: Duration::minutes(15),
+        }
     }
 
     fn create_test_user() -> User {
@@ -152,56 +702,413 @@ mod tests {
         User::new(email, password, Some("Test".to_string()))
     }
 
-    #[test]
-    fn test_login_success() {
+    #[tokio::test]
+    async fn test_login_success() {
         let repo = MockUserRepository {
             user: Some(create_test_user()),
         };
         let hasher = MockPasswordHasher;
         let token_service = MockTokenService;
-        let use_case = LoginUserUseCase::new(&repo, &hasher, &token_service);
+        let refresh_tokens = MockRefreshTokenRepository::default();
+        let sessions = MockSessionRepository::default();
+        let mfa = MockMfaRepository::default();
+        let challenges = MockMfaChallengeRepository::default();
+        let throttles = InMemoryLoginThrottleRepository::new();
+        let audit_log = MockAuditLog::default();
+        let use_case = LoginUserUseCase::new(
+            &repo,
+            &hasher,
+            &token_service,
+            &refresh_tokens,
+            &sessions,
+            &MockTokenGenerator,
+            &mfa,
+            &challenges,
+            &throttles,
+            lockout_policy(),
+            Duration::days(30),
+            Duration::minutes(5),
+            false,
+            &audit_log,
+        );
 
         let command = LoginUserCommand {
             email: "test@example.com".to_string(),
             password: "correct_password".to_string(),
+            client_ip: Some("203.0.113.7".parse().unwrap()),
+            user_agent: Some("Mozilla/5.0".to_string()),
         };
 
-        let result = use_case.execute(command).unwrap();
+        let LoginOutcome::Authenticated(result) = use_case.execute(command).await.unwrap() else {
+            panic!("expected tokens");
+        };
         assert_eq!(result.token, "mock_token");
+        assert_eq!(result.refresh_token, "refresh_secret");
         assert_eq!(result.email, "test@example.com");
+
+        // Only the hash of the refresh token is stored
+        let created = refresh_tokens.created.lock().unwrap();
+        assert_eq!(created.len(), 1);
+        assert_eq!(created[0].token_hash(), "hash_refresh_secret");
+
+        // The session is the token family, recorded with the device
+        let started = sessions.created.lock().unwrap();
+        assert_eq!(started.len(), 1);
+        assert_eq!(started[0].id(), created[0].family_id());
+        assert_eq!(started[0].user_agent(), Some("Mozilla/5.0"));
+        assert_eq!(
+            started[0].ip_address(),
+            Some("203.0.113.7".parse().unwrap())
+        );
+
+        let events = audit_log.events.lock().unwrap();
+        assert_eq!(events[0].action(), AuditAction::PasswordLogin);
+        assert_eq!(events[0].outcome(), AuditOutcome::Success);
+        assert_eq!(events[0].actor_id(), Some(result.user_id));
+        assert_eq!(events[0].target_id(), Some(result.user_id));
     }
 
-    #[test]
-    fn test_login_wrong_password() {
+    #[tokio::test]
+    async fn test_login_wrong_password() {
         let repo = MockUserRepository {
             user: Some(create_test_user()),
         };
         let hasher = MockPasswordHasher;
         let token_service = MockTokenService;
-        let use_case = LoginUserUseCase::new(&repo, &hasher, &token_service);
+        let refresh_tokens = MockRefreshTokenRepository::default();
+        let sessions = MockSessionRepository::default();
+        let mfa = MockMfaRepository::default();
+        let challenges = MockMfaChallengeRepository::default();
+        let throttles = InMemoryLoginThrottleRepository::new();
+        let audit_log = MockAuditLog::default();
+        let use_case = LoginUserUseCase::new(
+            &repo,
+            &hasher,
+            &token_service,
+            &refresh_tokens,
+            &sessions,
+            &MockTokenGenerator,
+            &mfa,
+            &challenges,
+            &throttles,
+            lockout_policy(),
+            Duration::days(30),
+            Duration::minutes(5),
+            false,
+            &audit_log,
+        );
 
         let command = LoginUserCommand {
             email: "test@example.com".to_string(),
             password: "wrong_password".to_string(),
+            client_ip: None,
+            user_agent: None,
         };
 
-        let result = use_case.execute(command);
+        let result = use_case.execute(command).await;
         assert!(matches!(result, Err(AuthError::InvalidCredentials)));
+        assert!(refresh_tokens.created.lock().unwrap().is_empty());
     }
 
-    #[test]
-    fn test_login_user_not_found() {
+    #[tokio::test]
+    async fn test_login_user_not_found() {
         let repo = MockUserRepository { user: None };
         let hasher = MockPasswordHasher;
         let token_service = MockTokenService;
-        let use_case = LoginUserUseCase::new(&repo, &hasher, &token_service);
+        let refresh_tokens = MockRefreshTokenRepository::default();
+        let sessions = MockSessionRepository::default();
+        let mfa = MockMfaRepository::default();
+        let challenges = MockMfaChallengeRepository::default();
+        let throttles = InMemoryLoginThrottleRepository::new();
+        let audit_log = MockAuditLog::default();
+        let use_case = LoginUserUseCase::new(
+            &repo,
+            &hasher,
+            &token_service,
+            &refresh_tokens,
+            &sessions,
+            &MockTokenGenerator,
+            &mfa,
+            &challenges,
+            &throttles,
+            lockout_policy(),
+            Duration::days(30),
+            Duration::minutes(5),
+            false,
+            &audit_log,
+        );
 
         let command = LoginUserCommand {
             email: "nonexistent@example.com".to_string(),
             password: "password".to_string(),
+            client_ip: None,
+            user_agent: None,
         };
 
-        let result = use_case.execute(command);
+        let result = use_case.execute(command).await;
         assert!(matches!(result, Err(AuthError::InvalidCredentials)));
+
+        // The attempt is kept without the address tried
+        let events = audit_log.events.lock().unwrap();
+        assert_eq!(events[0].outcome(), AuditOutcome::Failure);
+        assert_eq!(events[0].target_id(), None);
+        assert_eq!(events[0].detail(), Some("Invalid email or password"));
+    }
+
+    #[tokio::test]
+    async fn test_login_requires_verified_email() {
+        let repo = MockUserRepository {
+            user: Some(create_test_user()),
+        };
+        let refresh_tokens = MockRefreshTokenRepository::default();
+        let sessions = MockSessionRepository::default();
+        let mfa = MockMfaRepository::default();
+        let challenges = MockMfaChallengeRepository::default();
+        let throttles = InMemoryLoginThrottleRepository::new();
+        let audit_log = MockAuditLog::default();
+        let use_case = LoginUserUseCase::new(
+            &repo,
+            &MockPasswordHasher,
+            &MockTokenService,
+            &refresh_tokens,
+            &sessions,
+            &MockTokenGenerator,
+            &mfa,
+            &challenges,
+            &throttles,
+            lockout_policy(),
+            Duration::days(30),
+            Duration::minutes(5),
+            true,
+            &audit_log,
+        );
+
+        let command = LoginUserCommand {
+            email: "test@example.com".to_string(),
+            password: "correct_password".to_string(),
+            client_ip: None,
+            user_agent: None,
+        };
+
+        let result = use_case.execute(command).await;
+        assert!(matches!(result, Err(AuthError::EmailNotVerified)));
+        assert!(refresh_tokens.created.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_login_with_mfa_returns_challenge() {
+        let user = create_test_user();
+        let repo = MockUserRepository {
+            user: Some(user.clone()),
+        };
+        let refresh_tokens = MockRefreshTokenRepository::default();
+        let sessions = MockSessionRepository::default();
+        let mfa = MockMfaRepository {
+            credential: Some(TotpCredential::from_persistence(
+                user.id().as_uuid(),
+                "SECRET".to_string(),
+                Some(Utc::now()),
+                None,
+                Utc::now(),
+            )),
+        };
+        let challenges = MockMfaChallengeRepository::default();
+        let throttles = InMemoryLoginThrottleRepository::new();
+        let audit_log = MockAuditLog::default();
+        let use_case = LoginUserUseCase::new(
+            &repo,
+            &MockPasswordHasher,
+            &MockTokenService,
+            &refresh_tokens,
+            &sessions,
+            &MockTokenGenerator,
+            &mfa,
+            &challenges,
+            &throttles,
+            lockout_policy(),
+            Duration::days(30),
+            Duration::minutes(5),
+            false,
+            &audit_log,
+        );
+
+        let command = LoginUserCommand {
+            email: "test@example.com".to_string(),
+            password: "correct_password".to_string(),
+            client_ip: None,
+            user_agent: None,
+        };
+
+        let outcome = use_case.execute(command).await.unwrap();
+        assert!(matches!(
+            outcome,
+            LoginOutcome::MfaRequired {
+                expires_in: 300,
+                ..
+            }
+        ));
+        // No session is started before the second factor
+        assert!(refresh_tokens.created.lock().unwrap().is_empty());
+        let created = challenges.created.lock().unwrap();
+        assert_eq!(created.len(), 1);
+        assert_eq!(created[0].token_hash(), "hash_refresh_secret");
+        assert_eq!(
+            audit_log.events.lock().unwrap()[0].detail(),
+            Some("Second factor required")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_repeated_failures_lock_out() {
+        let repo = MockUserRepository {
+            user: Some(create_test_user()),
+        };
+        let refresh_tokens = MockRefreshTokenRepository::default();
+        let sessions = MockSessionRepository::default();
+        let mfa = MockMfaRepository::default();
+        let challenges = MockMfaChallengeRepository::default();
+        let throttles = InMemoryLoginThrottleRepository::new();
+        let audit_log = MockAuditLog::default();
+        let use_case = LoginUserUseCase::new(
+            &repo,
+            &MockPasswordHasher,
+            &MockTokenService,
+            &refresh_tokens,
+            &sessions,
+            &MockTokenGenerator,
+            &mfa,
+            &challenges,
+            &throttles,
+            lockout_policy(),
+            Duration::days(30),
+            Duration::minutes(5),
+            false,
+            &audit_log,
+        );
+        let client_ip: IpAddr = "203.0.113.7".parse().unwrap();
+        let attempt = |password: &str| {
+            use_case.execute(LoginUserCommand {
+                email: "test@example.com".to_string(),
+                password: password.to_string(),
+                client_ip: Some(client_ip),
+                user_agent: None,
+            })
+        };
+
+        assert!(matches!(
+            attempt("wrong_password").await,
+            Err(AuthError::InvalidCredentials)
+        ));
+        assert!(matches!(
+            attempt("wrong_password").await,
+            Err(AuthError::InvalidCredentials)
+        ));
+
+        // Even the right password is refused while locked
+        let result = attempt("correct_password").await;
+        assert!(matches!(
+            result,
+            Err(AuthError::AccountLocked {
+                retry_after_secs: 30
+            })
+        ));
+        assert!(refresh_tokens.created.lock().unwrap().is_empty());
+
+        // Both the address and the IP were counted
+        let ip_throttle = LoginThrottleRepository::find(&throttles, &ThrottleKey::ip(client_ip)).unwrap();
+        assert_eq!(ip_throttle.unwrap().failed_attempts(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_email_counts_as_failure() {
+        let repo = MockUserRepository { user: None };
+        let refresh_tokens = MockRefreshTokenRepository::default();
+        let sessions = MockSessionRepository::default();
+        let mfa = MockMfaRepository::default();
+        let challenges = MockMfaChallengeRepository::default();
+        let throttles = InMemoryLoginThrottleRepository::new();
+        let audit_log = MockAuditLog::default();
+        let use_case = LoginUserUseCase::new(
+            &repo,
+            &MockPasswordHasher,
+            &MockTokenService,
+            &refresh_tokens,
+            &sessions,
+            &MockTokenGenerator,
+            &mfa,
+            &challenges,
+            &throttles,
+            lockout_policy(),
+            Duration::days(30),
+            Duration::minutes(5),
+            false,
+            &audit_log,
+        );
+
+        let command = LoginUserCommand {
+            email: "nobody@example.com".to_string(),
+            password: "password".to_string(),
+            client_ip: None,
+            user_agent: None,
+        };
+
+        assert!(matches!(
+            use_case.execute(command).await,
+            Err(AuthError::InvalidCredentials)
+        ));
+        let throttle =
+            LoginThrottleRepository::find(&throttles, &ThrottleKey::email("nobody@example.com"))
+                .unwrap();
+        assert_eq!(throttle.unwrap().failed_attempts(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_success_clears_email_counter() {
+        let repo = MockUserRepository {
+            user: Some(create_test_user()),
+        };
+        let refresh_tokens = MockRefreshTokenRepository::default();
+        let sessions = MockSessionRepository::default();
+        let mfa = MockMfaRepository::default();
+        let challenges = MockMfaChallengeRepository::default();
+        let throttles = InMemoryLoginThrottleRepository::new();
+        LoginThrottleRepository::record_failure(
+            &throttles,
+            &ThrottleKey::email("test@example.com"),
+            &lockout_policy(),
+            Utc::now(),
+        )
+        .unwrap();
+        let audit_log = MockAuditLog::default();
+        let use_case = LoginUserUseCase::new(
+            &repo,
+            &MockPasswordHasher,
+            &MockTokenService,
+            &refresh_tokens,
+            &sessions,
+            &MockTokenGenerator,
+            &mfa,
+            &challenges,
+            &throttles,
+            lockout_policy(),
+            Duration::days(30),
+            Duration::minutes(5),
+            false,
+            &audit_log,
+        );
+
+        let command = LoginUserCommand {
+            email: "test@example.com".to_string(),
+            password: "correct_password".to_string(),
+            client_ip: None,
+            user_agent: None,
+        };
+
+        assert!(use_case.execute(command).await.is_ok());
+        assert!(
+            LoginThrottleRepository::find(&throttles, &ThrottleKey::email("test@example.com"))
+                .unwrap()
+                .is_none()
+        );
     }
 }
@@ -0,0 +1,289 @@
+//! Logout user use case
+//!
+//! Revokes the presented access token and ends the session it belongs to,
+//! along with the session of the refresh token, if one is presented.
+
+use uuid::Uuid;
+
+use crate::domain::auth::{OpaqueTokenGenerator, TokenService};
+use crate::domain::error::AuthError;
+use crate::domain::refresh_token::RefreshTokenRepository;
+
+/// Input for user logout
+#[derive(Debug)]
+pub struct LogoutUserCommand {
+    pub token: String,
+    pub refresh_token: Option<String>,
+}
+
+/// Use case for user logout
+pub struct LogoutUserUseCase<'a, T: ?Sized, S: ?Sized, G: ?Sized> {
+    token_service: &'a T,
+    refresh_token_repository: &'a S,
+    token_generator: &'a G,
+}
+
+impl<'a, T, S, G> LogoutUserUseCase<'a, T, S, G>
+where
+    T: TokenService + ?Sized,
+    S: RefreshTokenRepository + ?Sized,
+    G: OpaqueTokenGenerator + ?Sized,
+{
+    /// Create a new use case instance
+    pub fn new(
+        token_service: &'a T,
+        refresh_token_repository: &'a S,
+        token_generator: &'a G,
+    ) -> Self {
+        Self {
+            token_service,
+            refresh_token_repository,
+            token_generator,
+        }
+    }
+
+    /// Execute the logout
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidToken` if the access token is invalid, or the
+    ///   refresh token belongs to another user
+    /// - `AuthError::TokenExpired` if the access token has expired
+    /// - `AuthError::TokenRevoked` if the access token was already revoked
+    /// - `AuthError::Internal` on infrastructure failures
+    pub fn execute(&self, command: LogoutUserCommand) -> Result<(), AuthError> {
+        let token_data = self.token_service.validate_token(&command.token)?;
+
+        // Tokens issued before sessions were tracked belong to none, and an
+        // impersonation token's session is the administrator's, which
+        // logging out of the impersonation leaves alone
+        let mut families: Vec<Uuid> = token_data
+            .session_id
+            .filter(|_| !token_data.is_impersonated())
+            .into_iter()
+            .collect();
+
+        if let Some(refresh_token) = command.refresh_token {
+            let hash = self.token_generator.hash(&refresh_token);
+            match self.refresh_token_repository.find_by_hash(&hash) {
+                Ok(stored) if stored.user_id() == token_data.user_id => {
+                    if !families.contains(&stored.family_id()) {
+                        families.push(stored.family_id());
+                    }
+                }
+                Ok(_) => return Err(AuthError::InvalidToken),
+                // Unknown refresh tokens have nothing left to revoke
+                Err(AuthError::InvalidToken) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        for family_id in families {
+            self.refresh_token_repository.revoke_family(family_id)?;
+        }
+
+        self.token_service.revoke_token(&token_data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use chrono::Duration;
+
+    use super::*;
+    use crate::application::commands::test_support::token_data;
+    use crate::domain::auth::TokenData;
+    use crate::domain::refresh_token::RefreshToken;
+    use crate::domain::user::User;
+
+    // Token service accepting "valid" for a fixed user, session and actor
+    struct MockTokenService {
+        user_id: Uuid,
+        session_id: Option<Uuid>,
+        actor: Option<Uuid>,
+        revoked: RefCell<Vec<String>>,
+    }
+
+    impl TokenService for MockTokenService {
+        fn create_token(
+            &self,
+            _user: &User,
+            _session_id: Option<Uuid>,
+        ) -> Result<String, AuthError> {
+            Ok("token".to_string())
+        }
+
+        fn validate_token(&self, token: &str) -> Result<TokenData, AuthError> {
+            if token != "valid" {
+                return Err(AuthError::InvalidToken);
+            }
+            Ok(TokenData {
+                jti: "jti-1".to_string(),
+                session_id: self.session_id,
+                actor: self.actor,
+                ..token_data(self.user_id)
+            })
+        }
+
+        fn revoke_token(&self, token: &TokenData) -> Result<(), AuthError> {
+            self.revoked.borrow_mut().push(token.jti.clone());
+            Ok(())
+        }
+
+        fn is_revoked(&self, token: &TokenData) -> Result<bool, AuthError> {
+            Ok(self.revoked.borrow().contains(&token.jti))
+        }
+    }
+
+    // Refresh token store holding a single token
+    struct MockRefreshTokenRepository {
+        token: RefreshToken,
+        revoked_families: RefCell<Vec<Uuid>>,
+    }
+
+    impl RefreshTokenRepository for MockRefreshTokenRepository {
+        fn create(&self, _token: &RefreshToken) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn find_by_hash(&self, token_hash: &str) -> Result<RefreshToken, AuthError> {
+            if self.token.token_hash() == token_hash {
+                Ok(self.token.clone())
+            } else {
+                Err(AuthError::InvalidToken)
+            }
+        }
+
+        fn rotate(&self, _current_id: Uuid, _next: &RefreshToken) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+
+        fn revoke_family(&self, family_id: Uuid) -> Result<(), AuthError> {
+            self.revoked_families.borrow_mut().push(family_id);
+            Ok(())
+        }
+
+        fn revoke_all_for_user(&self, _user_id: Uuid) -> Result<(), AuthError> {
+            Ok(())
+        }
+    }
+
+    struct MockTokenGenerator;
+
+    impl OpaqueTokenGenerator for MockTokenGenerator {
+        fn generate(&self) -> String {
+            "secret".to_string()
+        }
+
+        fn hash(&self, token: &str) -> String {
+            format!("hash_{}", token)
+        }
+    }
+
+    /// Build an access token and a refresh token of the same session, or
+    /// a refresh token of someone else when `foreign` is set
+    fn fixtures(foreign: bool) -> (MockTokenService, MockRefreshTokenRepository) {
+        let user_id = Uuid::new_v4();
+        let session_id = Uuid::new_v4();
+        let owner = if foreign { Uuid::new_v4() } else { user_id };
+        let token_service = MockTokenService {
+            user_id,
+            session_id: Some(session_id),
+            actor: None,
+            revoked: RefCell::new(Vec::new()),
+        };
+        let refresh_tokens = MockRefreshTokenRepository {
+            token: RefreshToken::issue(
+                owner,
+                if foreign { Uuid::new_v4() } else { session_id },
+                "hash_refresh".to_string(),
+                Duration::days(1),
+            ),
+            revoked_families: RefCell::new(Vec::new()),
+        };
+        (token_service, refresh_tokens)
+    }
+
+    #[test]
+    fn test_logout_revokes_access_and_refresh_tokens() {
+        let (token_service, refresh_tokens) = fixtures(false);
+        let use_case = LogoutUserUseCase::new(&token_service, &refresh_tokens, &MockTokenGenerator);
+
+        use_case
+            .execute(LogoutUserCommand {
+                token: "valid".to_string(),
+                refresh_token: Some("refresh".to_string()),
+            })
+            .unwrap();
+
+        assert_eq!(*token_service.revoked.borrow(), vec!["jti-1".to_string()]);
+        assert_eq!(
+            *refresh_tokens.revoked_families.borrow(),
+            vec![refresh_tokens.token.family_id()]
+        );
+    }
+
+    #[test]
+    fn test_logout_without_refresh_token_ends_session() {
+        let (token_service, refresh_tokens) = fixtures(false);
+        let use_case = LogoutUserUseCase::new(&token_service, &refresh_tokens, &MockTokenGenerator);
+
+        use_case
+            .execute(LogoutUserCommand {
+                token: "valid".to_string(),
+                refresh_token: None,
+            })
+            .unwrap();
+
+        assert_eq!(
+            *refresh_tokens.revoked_families.borrow(),
+            vec![token_service.session_id.unwrap()]
+        );
+    }
+
+    #[test]
+    fn test_logout_of_impersonation_leaves_admin_session() {
+        let (mut token_service, refresh_tokens) = fixtures(false);
+        token_service.actor = Some(Uuid::new_v4());
+        let use_case = LogoutUserUseCase::new(&token_service, &refresh_tokens, &MockTokenGenerator);
+
+        use_case
+            .execute(LogoutUserCommand {
+                token: "valid".to_string(),
+                refresh_token: None,
+            })
+            .unwrap();
+
+        assert_eq!(*token_service.revoked.borrow(), vec!["jti-1".to_string()]);
+        assert!(refresh_tokens.revoked_families.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_logout_invalid_token() {
+        let (token_service, refresh_tokens) = fixtures(false);
+        let use_case = LogoutUserUseCase::new(&token_service, &refresh_tokens, &MockTokenGenerator);
+
+        let result = use_case.execute(LogoutUserCommand {
+            token: "invalid".to_string(),
+            refresh_token: None,
+        });
+
+        assert!(matches!(result, Err(AuthError::InvalidToken)));
+        assert!(token_service.revoked.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_logout_rejects_foreign_refresh_token() {
+        let (token_service, refresh_tokens) = fixtures(true);
+        let use_case = LogoutUserUseCase::new(&token_service, &refresh_tokens, &MockTokenGenerator);
+
+        let result = use_case.execute(LogoutUserCommand {
+            token: "valid".to_string(),
+            refresh_token: Some("refresh".to_string()),
+        });
+
+        assert!(matches!(result, Err(AuthError::InvalidToken)));
+        assert!(refresh_tokens.revoked_families.borrow().is_empty());
+    }
+}
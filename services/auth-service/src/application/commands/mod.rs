@@ -1,4 +1,53 @@
 //! Application commands (use cases)
 
+pub mod accept_invitation;
+pub mod assign_role;
+pub mod authorize;
+pub mod change_email;
+pub mod change_password;
+pub mod client_credentials;
+pub mod complete_federated_login;
+pub mod confirm_password_reset;
+pub mod confirm_totp_enrollment;
+pub mod consume_magic_link;
+pub mod create_api_key;
+pub mod create_organization;
+pub mod delete_account;
+pub mod enroll_totp;
+pub mod exchange_authorization_code;
+pub mod export_user_data;
+pub mod get_current_user;
+pub mod impersonate_user;
+pub mod invite_member;
+pub mod list_api_keys;
+pub mod list_audit_events;
+pub mod list_oauth_clients;
+pub mod list_organizations;
+pub mod list_sessions;
+pub mod list_users;
 pub mod login_user;
+pub mod logout_user;
+pub mod refresh_session;
+pub mod register_oauth_client;
+pub mod register_oidc_client;
 pub mod register_user;
+pub mod request_magic_link;
+pub mod request_password_reset;
+pub mod revoke_api_key;
+pub mod revoke_oauth_client;
+pub mod revoke_other_sessions;
+pub mod revoke_role;
+pub mod revoke_session;
+pub mod rotate_signing_key;
+pub mod send_email_verification;
+pub mod set_password;
+pub mod set_user_active;
+pub mod start_federated_login;
+pub mod switch_organization;
+pub mod unlock_account;
+pub mod update_profile;
+pub mod verify_email;
+pub mod verify_mfa;
+
+#[cfg(test)]
+pub(crate) mod test_support;
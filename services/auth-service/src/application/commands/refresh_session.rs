@@ -0,0 +1,684 @@
+//! Refresh session use case
+//!
+//! Exchanges a refresh token for a new access token and a rotated refresh
+//! token. Presenting a token that was already rotated is treated as theft:
+//! the whole token family is revoked. A refresh counts as activity of the
+//! session the family makes up. A session that switched to an organization
+//! gets an organization token again, as long as the user is still a member.
+
+use chrono::{Duration, Utc};
+use tracing::{info, warn};
+
+use crate::domain::auth::{AsyncUserRepository, OpaqueTokenGenerator, TokenService};
+use crate::domain::error::AuthError;
+use crate::domain::organization::{
+    ActiveOrganization, AsyncOrganizationRepository, OrganizationTokenIssuer,
+};
+use crate::domain::refresh_token::{AsyncRefreshTokenRepository, RefreshToken};
+use crate::domain::session::AsyncSessionRepository;
+use crate::domain::user::User;
+
+/// Input for refreshing a session
+#[derive(Debug)]
+pub struct RefreshSessionCommand {
+    pub refresh_token: String,
+}
+
+/// Output after a successful refresh
+#[derive(Debug)]
+pub struct RefreshSessionResult {
+    pub token: String,
+    pub refresh_token: String,
+    pub user_id: uuid::Uuid,
+    /// Organization the new access token acts for
+    pub organization: Option<ActiveOrganization>,
+}
+
+/// Use case for refreshing a session
+pub struct RefreshSessionUseCase<
+    'a,
+    R: ?Sized,
+    S: ?Sized,
+    D: ?Sized,
+    O: ?Sized,
+    T: ?Sized,
+    I: ?Sized,
+    G: ?Sized,
+> {
+    user_repository: &'a R,
+    refresh_token_repository: &'a S,
+    session_repository: &'a D,
+    organization_repository: &'a O,
+    token_service: &'a T,
+    token_issuer: &'a I,
+    token_generator: &'a G,
+    refresh_token_ttl: Duration,
+}
+
+impl<'a, R, S, D, O, T, I, G> RefreshSessionUseCase<'a, R, S, D, O, T, I, G>
+where
+    R: AsyncUserRepository + ?Sized,
+    S: AsyncRefreshTokenRepository + ?Sized,
+    D: AsyncSessionRepository + ?Sized,
+    O: AsyncOrganizationRepository + ?Sized,
+    T: TokenService + ?Sized,
+    I: OrganizationTokenIssuer + ?Sized,
+    G: OpaqueTokenGenerator + ?Sized,
+{
+    /// Create a new use case instance
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        user_repository: &'a R,
+        refresh_token_repository: &'a S,
+        session_repository: &'a D,
+        organization_repository: &'a O,
+        token_service: &'a T,
+        token_issuer: &'a I,
+        token_generator: &'a G,
+        refresh_token_ttl: Duration,
+    ) -> Self {
+        Self {
+            user_repository,
+            refresh_token_repository,
+            session_repository,
+            organization_repository,
+            token_service,
+            token_issuer,
+            token_generator,
+            refresh_token_ttl,
+        }
+    }
+
+    /// Execute the refresh
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidToken` if the token is unknown or revoked
+    /// - `AuthError::TokenExpired` if the token has expired
+    /// - `AuthError::TokenReused` if the token was already rotated (family revoked)
+    /// - `AuthError::AccountInactive` if user account is deactivated
+    /// - `AuthError::Internal` on infrastructure failures
+    pub async fn execute(
+        &self,
+        command: RefreshSessionCommand,
+    ) -> Result<RefreshSessionResult, AuthError> {
+        let current = self
+            .refresh_token_repository
+            .find_by_hash(&self.token_generator.hash(&command.refresh_token))
+            .await?;
+
+        if current.is_revoked() {
+            return Err(AuthError::InvalidToken);
+        }
+
+        if current.is_rotated() {
+            return Err(self.revoke_reused_family(&current).await);
+        }
+
+        if current.is_expired(Utc::now()) {
+            return Err(AuthError::TokenExpired);
+        }
+
+        let user = self
+            .user_repository
+            .find_by_id(current.user_id())
+            .await
+            .map_err(|e| match e {
+                AuthError::UserNotFound => AuthError::InvalidToken,
+                other => other,
+            })?;
+
+        if !user.is_active() {
+            self.refresh_token_repository
+                .revoke_family(current.family_id())
+                .await?;
+            return Err(AuthError::AccountInactive);
+        }
+
+        // Rotate within the same family
+        let refresh_token = self.token_generator.generate();
+        let next = RefreshToken::issue(
+            current.user_id(),
+            current.family_id(),
+            self.token_generator.hash(&refresh_token),
+            self.refresh_token_ttl,
+        );
+
+        if !self
+            .refresh_token_repository
+            .rotate(current.id(), &next)
+            .await?
+        {
+            // Another request exchanged this token first
+            return Err(self.revoke_reused_family(&current).await);
+        }
+
+        self.session_repository
+            .touch(current.family_id(), Utc::now(), next.expires_at())
+            .await?;
+
+        let organization = self
+            .active_organization(&user, current.family_id())
+            .await?;
+        let token = match organization {
+            Some(organization) => self.token_issuer.create_organization_token(
+                &user,
+                Some(current.family_id()),
+                organization,
+            )?,
+            None => self
+                .token_service
+                .create_token(&user, Some(current.family_id()))?,
+        };
+
+        Ok(RefreshSessionResult {
+            token,
+            refresh_token,
+            user_id: user.id().as_uuid(),
+            organization,
+        })
+    }
+
+    /// Organization the session acts for, with the user's current role in it
+    ///
+    /// A session whose user has left the organization returns to the
+    /// personal context.
+    async fn active_organization(
+        &self,
+        user: &User,
+        session_id: uuid::Uuid,
+    ) -> Result<Option<ActiveOrganization>, AuthError> {
+        let session = self.session_repository.find_by_id(session_id).await?;
+        let Some(organization_id) = session.organization_id() else {
+            return Ok(None);
+        };
+
+        let user_id = user.id().as_uuid();
+        match self
+            .organization_repository
+            .find_membership(organization_id, user_id)
+            .await?
+        {
+            Some(membership) => Ok(Some(ActiveOrganization {
+                id: organization_id,
+                role: membership.role(),
+            })),
+            None => {
+                info!(
+                    user_id = %user_id,
+                    organization_id = %organization_id,
+                    "Membership ended, refreshing into the personal context"
+                );
+                self.session_repository
+                    .set_organization(session_id, None)
+                    .await?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Revoke the family of a token that was presented after rotation
+    async fn revoke_reused_family(&self, token: &RefreshToken) -> AuthError {
+        warn!(
+            user_id = %token.user_id(),
+            family_id = %token.family_id(),
+            "Refresh token reuse detected, revoking token family"
+        );
+
+        match self
+            .refresh_token_repository
+            .revoke_family(token.family_id())
+            .await
+        {
+            Ok(()) => AuthError::TokenReused,
+            Err(e) => e,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::sync::Mutex;
+
+    use chrono::DateTime;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::application::commands::test_support::MockUserRepository;
+    use crate::domain::auth::{BoxFuture, TokenData};
+    use crate::domain::organization::{Membership, OrgRole};
+    use crate::domain::session::Session;
+    use crate::domain::user::{Email, HashedPassword};
+
+    // In-memory refresh token store mirroring the Diesel semantics
+    #[derive(Default)]
+    struct MockRefreshTokenRepository {
+        tokens: Mutex<Vec<RefreshToken>>,
+    }
+
+    impl MockRefreshTokenRepository {
+        fn get(&self, id: Uuid) -> RefreshToken {
+            self.tokens
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|t| t.id() == id)
+                .cloned()
+                .unwrap()
+        }
+
+        fn replace(&self, token: RefreshToken) {
+            let mut tokens = self.tokens.lock().unwrap();
+            let pos = tokens.iter().position(|t| t.id() == token.id()).unwrap();
+            tokens[pos] = token;
+        }
+
+        fn with_state(token: &RefreshToken, rotated: bool, revoked: bool) -> RefreshToken {
+            let now = Utc::now();
+            RefreshToken::from_persistence(
+                token.id(),
+                token.user_id(),
+                token.family_id(),
+                token.token_hash().to_string(),
+                token.expires_at(),
+                token.created_at(),
+                token.rotated_at().or(rotated.then_some(now)),
+                token.revoked_at().or(revoked.then_some(now)),
+            )
+        }
+
+        fn find(&self, token_hash: &str) -> Result<RefreshToken, AuthError> {
+            self.tokens
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|t| t.token_hash() == token_hash)
+                .cloned()
+                .ok_or(AuthError::InvalidToken)
+        }
+    }
+
+    impl AsyncRefreshTokenRepository for MockRefreshTokenRepository {
+        fn create<'a>(&'a self, token: &'a RefreshToken) -> BoxFuture<'a, Result<(), AuthError>> {
+            self.tokens.lock().unwrap().push(token.clone());
+            Box::pin(async { Ok(()) })
+        }
+
+        fn find_by_hash<'a>(
+            &'a self,
+            token_hash: &'a str,
+        ) -> BoxFuture<'a, Result<RefreshToken, AuthError>> {
+            let found = self.find(token_hash);
+            Box::pin(async { found })
+        }
+
+        fn rotate<'a>(
+            &'a self,
+            current_id: Uuid,
+            next: &'a RefreshToken,
+        ) -> BoxFuture<'a, Result<bool, AuthError>> {
+            let current = self.get(current_id);
+            let rotated = !current.is_rotated() && !current.is_revoked();
+            if rotated {
+                self.replace(Self::with_state(&current, true, false));
+                self.tokens.lock().unwrap().push(next.clone());
+            }
+            Box::pin(async move { Ok(rotated) })
+        }
+
+        fn revoke_family(&self, family_id: Uuid) -> BoxFuture<'_, Result<(), AuthError>> {
+            let family: Vec<RefreshToken> = self
+                .tokens
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|t| t.family_id() == family_id)
+                .cloned()
+                .collect();
+            for token in family {
+                self.replace(Self::with_state(&token, false, true));
+            }
+            Box::pin(async { Ok(()) })
+        }
+
+        fn revoke_all_for_user(&self, _user_id: Uuid) -> BoxFuture<'_, Result<(), AuthError>> {
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    // Session store recording refreshes, where every session acts for
+    // `organization_id`
+    #[derive(Default)]
+    struct MockSessionRepository {
+        touched: Mutex<Vec<Uuid>>,
+        organization_id: Mutex<Option<Uuid>>,
+    }
+
+    impl AsyncSessionRepository for MockSessionRepository {
+        fn create<'a>(&'a self, _session: &'a Session) -> BoxFuture<'a, Result<(), AuthError>> {
+            Box::pin(async { Ok(()) })
+        }
+
+        fn find_by_id(&self, id: Uuid) -> BoxFuture<'_, Result<Session, AuthError>> {
+            let now = Utc::now();
+            let session = Session::from_persistence(
+                id,
+                Uuid::new_v4(),
+                None,
+                None,
+                now,
+                now,
+                now + Duration::days(1),
+                None,
+                *self.organization_id.lock().unwrap(),
+            );
+            Box::pin(async { Ok(session) })
+        }
+
+        fn find_active_for_user(
+            &self,
+            _user_id: Uuid,
+            _now: DateTime<Utc>,
+        ) -> BoxFuture<'_, Result<Vec<Session>, AuthError>> {
+            Box::pin(async { Ok(Vec::new()) })
+        }
+
+        fn touch(
+            &self,
+            id: Uuid,
+            _now: DateTime<Utc>,
+            _expires_at: DateTime<Utc>,
+        ) -> BoxFuture<'_, Result<(), AuthError>> {
+            self.touched.lock().unwrap().push(id);
+            Box::pin(async { Ok(()) })
+        }
+
+        fn set_organization(
+            &self,
+            _id: Uuid,
+            organization_id: Option<Uuid>,
+        ) -> BoxFuture<'_, Result<(), AuthError>> {
+            *self.organization_id.lock().unwrap() = organization_id;
+            Box::pin(async { Ok(()) })
+        }
+
+        fn is_revoked(&self, _id: Uuid) -> BoxFuture<'_, Result<bool, AuthError>> {
+            Box::pin(async { Ok(false) })
+        }
+    }
+
+    // Organization store with at most one membership
+    #[derive(Default)]
+    struct MockOrganizationRepository {
+        membership: Option<Membership>,
+    }
+
+    impl AsyncOrganizationRepository for MockOrganizationRepository {
+        fn find_membership(
+            &self,
+            organization_id: Uuid,
+            user_id: Uuid,
+        ) -> BoxFuture<'_, Result<Option<Membership>, AuthError>> {
+            let membership = self
+                .membership
+                .clone()
+                .filter(|m| m.organization_id() == organization_id && m.user_id() == user_id);
+            Box::pin(async { Ok(membership) })
+        }
+    }
+
+    // Mock token service
+    struct MockTokenService;
+
+    impl TokenService for MockTokenService {
+        fn create_token(
+            &self,
+            _user: &User,
+            _session_id: Option<Uuid>,
+        ) -> Result<String, AuthError> {
+            Ok("access_token".to_string())
+        }
+
+        fn validate_token(&self, _token: &str) -> Result<TokenData, AuthError> {
+            Err(AuthError::InvalidToken)
+        }
+
+        fn revoke_token(&self, _token: &TokenData) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn is_revoked(&self, _token: &TokenData) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+    }
+
+    impl OrganizationTokenIssuer for MockTokenService {
+        fn create_organization_token(
+            &self,
+            _user: &User,
+            _session_id: Option<Uuid>,
+            organization: ActiveOrganization,
+        ) -> Result<String, AuthError> {
+            Ok(format!("org_token:{}", organization.role))
+        }
+    }
+
+    // Generator producing sequential secrets
+    #[derive(Default)]
+    struct MockTokenGenerator {
+        counter: RefCell<u32>,
+    }
+
+    impl OpaqueTokenGenerator for MockTokenGenerator {
+        fn generate(&self) -> String {
+            *self.counter.borrow_mut() += 1;
+            format!("secret_{}", self.counter.borrow())
+        }
+
+        fn hash(&self, token: &str) -> String {
+            format!("hash_{}", token)
+        }
+    }
+
+    fn create_test_user() -> User {
+        let email = Email::new("test@example.com").unwrap();
+        let password = HashedPassword::from_hash("hashed".to_string());
+        User::new(email, password, None)
+    }
+
+    /// Seed a token family for the user and return its first secret
+    fn seed_token(repo: &MockRefreshTokenRepository, user: &User, ttl: Duration) -> (String, Uuid) {
+        let family_id = Uuid::new_v4();
+        repo.tokens.lock().unwrap().push(RefreshToken::issue(
+            user.id().as_uuid(),
+            family_id,
+            "hash_secret_0".to_string(),
+            ttl,
+        ));
+        ("secret_0".to_string(), family_id)
+    }
+
+    async fn refresh(
+        users: &MockUserRepository,
+        tokens: &MockRefreshTokenRepository,
+        sessions: &MockSessionRepository,
+        generator: &MockTokenGenerator,
+        secret: &str,
+    ) -> Result<RefreshSessionResult, AuthError> {
+        refresh_with(
+            users,
+            tokens,
+            sessions,
+            &MockOrganizationRepository::default(),
+            generator,
+            secret,
+        )
+        .await
+    }
+
+    async fn refresh_with(
+        users: &MockUserRepository,
+        tokens: &MockRefreshTokenRepository,
+        sessions: &MockSessionRepository,
+        organizations: &MockOrganizationRepository,
+        generator: &MockTokenGenerator,
+        secret: &str,
+    ) -> Result<RefreshSessionResult, AuthError> {
+        RefreshSessionUseCase::new(
+            users,
+            tokens,
+            sessions,
+            organizations,
+            &MockTokenService,
+            &MockTokenService,
+            generator,
+            Duration::days(1),
+        )
+        .execute(RefreshSessionCommand {
+            refresh_token: secret.to_string(),
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_refresh_rotates_token() {
+        let users = MockUserRepository::new(create_test_user());
+        let tokens = MockRefreshTokenRepository::default();
+        let sessions = MockSessionRepository::default();
+        let generator = MockTokenGenerator::default();
+        let (secret, family_id) = seed_token(&tokens, &users.user.lock().unwrap(), Duration::days(1));
+
+        let result = refresh(&users, &tokens, &sessions, &generator, &secret).await.unwrap();
+
+        assert_eq!(result.token, "access_token");
+        assert_eq!(result.refresh_token, "secret_1");
+        let next = tokens.find("hash_secret_1").unwrap();
+        assert_eq!(next.family_id(), family_id);
+        assert!(tokens.find("hash_secret_0").unwrap().is_rotated());
+        assert_eq!(*sessions.touched.lock().unwrap(), vec![family_id]);
+
+        // The rotated secret can itself be refreshed
+        assert!(refresh(
+            &users,
+            &tokens,
+            &sessions,
+            &generator,
+            &result.refresh_token
+        )
+        .await
+        .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_reuse_revokes_family() {
+        let users = MockUserRepository::new(create_test_user());
+        let tokens = MockRefreshTokenRepository::default();
+        let sessions = MockSessionRepository::default();
+        let generator = MockTokenGenerator::default();
+        let (secret, _) = seed_token(&tokens, &users.user.lock().unwrap(), Duration::days(1));
+
+        let rotated = refresh(&users, &tokens, &sessions, &generator, &secret).await.unwrap();
+
+        // Replaying the original secret is detected...
+        let result = refresh(&users, &tokens, &sessions, &generator, &secret).await;
+        assert!(matches!(result, Err(AuthError::TokenReused)));
+
+        // ...and the legitimate successor is no longer usable either
+        let result = refresh(
+            &users,
+            &tokens,
+            &sessions,
+            &generator,
+            &rotated.refresh_token,
+        )
+        .await;
+        assert!(matches!(result, Err(AuthError::InvalidToken)));
+    }
+
+    #[tokio::test]
+    async fn test_expired_token() {
+        let users = MockUserRepository::new(create_test_user());
+        let tokens = MockRefreshTokenRepository::default();
+        let sessions = MockSessionRepository::default();
+        let generator = MockTokenGenerator::default();
+        let (secret, _) = seed_token(&tokens, &users.user.lock().unwrap(), Duration::seconds(-1));
+
+        let result = refresh(&users, &tokens, &sessions, &generator, &secret).await;
+        assert!(matches!(result, Err(AuthError::TokenExpired)));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_token() {
+        let users = MockUserRepository::new(create_test_user());
+        let tokens = MockRefreshTokenRepository::default();
+        let sessions = MockSessionRepository::default();
+        let generator = MockTokenGenerator::default();
+
+        let result = refresh(&users, &tokens, &sessions, &generator, "unknown").await;
+        assert!(matches!(result, Err(AuthError::InvalidToken)));
+    }
+
+    #[tokio::test]
+    async fn test_refresh_keeps_organization_of_member() {
+        let users = MockUserRepository::new(create_test_user());
+        let tokens = MockRefreshTokenRepository::default();
+        let sessions = MockSessionRepository::default();
+        let generator = MockTokenGenerator::default();
+        let (secret, _) = seed_token(&tokens, &users.user.lock().unwrap(), Duration::days(1));
+        let organization_id = Uuid::new_v4();
+        *sessions.organization_id.lock().unwrap() = Some(organization_id);
+        // The role changed since the switch; the new token carries the current one
+        let organizations = MockOrganizationRepository {
+            membership: Some(Membership::new(
+                organization_id,
+                users.user_id(),
+                OrgRole::Member,
+            )),
+        };
+
+        let result = refresh_with(
+            &users,
+            &tokens,
+            &sessions,
+            &organizations,
+            &generator,
+            &secret,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result.token, "org_token:member");
+        assert_eq!(
+            result.organization,
+            Some(ActiveOrganization {
+                id: organization_id,
+                role: OrgRole::Member,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_refresh_after_leaving_organization_returns_to_personal_context() {
+        let users = MockUserRepository::new(create_test_user());
+        let tokens = MockRefreshTokenRepository::default();
+        let sessions = MockSessionRepository::default();
+        let generator = MockTokenGenerator::default();
+        let (secret, _) = seed_token(&tokens, &users.user.lock().unwrap(), Duration::days(1));
+        *sessions.organization_id.lock().unwrap() = Some(Uuid::new_v4());
+
+        let result = refresh_with(
+            &users,
+            &tokens,
+            &sessions,
+            &MockOrganizationRepository::default(),
+            &generator,
+            &secret,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result.token, "access_token");
+        assert!(result.organization.is_none());
+        assert!(sessions.organization_id.lock().unwrap().is_none());
+    }
+}
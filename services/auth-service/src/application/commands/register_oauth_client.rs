@@ -0,0 +1,268 @@
+//! Register OAuth client use case
+//!
+//! Gives a backend service an identity for the client_credentials grant.
+//! Only callers whose token carries the `clients:manage` permission may do
+//! so. The secret is returned once; only its hash is stored.
+
+use tracing::info;
+use uuid::Uuid;
+
+use crate::domain::audit::{AuditAction, AuditEvent, AuditLog};
+use crate::domain::auth::{OpaqueTokenGenerator, TokenData, TokenService};
+use crate::domain::error::AuthError;
+use crate::domain::oauth_client::{normalize_client_name, OAuthClient, OAuthClientRepository};
+use crate::domain::role::{authorize, Permission};
+
+/// Input for registering an OAuth client
+#[derive(Debug)]
+pub struct RegisterOAuthClientCommand {
+    /// Access token of the acting administrator
+    pub token: String,
+    /// Name of the service the client is for
+    pub name: String,
+    /// Permissions the client may request, e.g. `users:read`
+    pub scopes: Vec<String>,
+}
+
+/// A newly registered client
+#[derive(Debug)]
+pub struct RegisteredOAuthClient {
+    /// The client secret, shown to the administrator only this once
+    pub client_secret: String,
+    pub client: OAuthClient,
+}
+
+/// Use case for registering an OAuth client
+pub struct RegisterOAuthClientUseCase<'a, C: ?Sized, G: ?Sized, T: ?Sized, L: ?Sized> {
+    client_repository: &'a C,
+    token_generator: &'a G,
+    token_service: &'a T,
+    audit_log: &'a L,
+}
+
+impl<'a, C, G, T, L> RegisterOAuthClientUseCase<'a, C, G, T, L>
+where
+    C: OAuthClientRepository + ?Sized,
+    G: OpaqueTokenGenerator + ?Sized,
+    T: TokenService + ?Sized,
+    L: AuditLog + ?Sized,
+{
+    /// Create a new use case instance
+    pub fn new(
+        client_repository: &'a C,
+        token_generator: &'a G,
+        token_service: &'a T,
+        audit_log: &'a L,
+    ) -> Self {
+        Self {
+            client_repository,
+            token_generator,
+            token_service,
+            audit_log,
+        }
+    }
+
+    /// Execute the registration
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidToken` / `AuthError::TokenExpired` if the token is invalid
+    /// - `AuthError::Forbidden` if the caller may not manage clients
+    /// - `AuthError::InvalidClientName` if the name is empty or malformed
+    /// - `AuthError::InvalidScope` if no scope is given, or one is unknown or
+    ///   not held by the caller
+    /// - `AuthError::Internal` on infrastructure failures
+    pub fn execute(
+        &self,
+        command: RegisterOAuthClientCommand,
+    ) -> Result<RegisteredOAuthClient, AuthError> {
+        let actor = self.token_service.validate_token(&command.token)?;
+
+        let result = self.register(&actor, command);
+        let mut event = AuditEvent::new(AuditAction::OAuthClientRegistration)
+            .with_actor(actor.audit_actor())
+            .with_result(&result);
+        if let Ok(registered) = &result {
+            event = event.with_detail(registered.client.client_id().to_string());
+        }
+        self.audit_log.record(event);
+        result
+    }
+
+    fn register(
+        &self,
+        actor: &TokenData,
+        command: RegisterOAuthClientCommand,
+    ) -> Result<RegisteredOAuthClient, AuthError> {
+        authorize(actor, Permission::ManageClients)?;
+
+        let name = normalize_client_name(&command.name)?;
+        // A client never gets more than the administrator registering it holds
+        let held = actor.permissions();
+        let scopes = command
+            .scopes
+            .iter()
+            .map(|scope| scope.parse::<Permission>())
+            .collect::<Result<Vec<_>, _>>()?;
+        if scopes.is_empty() || scopes.iter().any(|scope| !held.contains(scope)) {
+            return Err(AuthError::InvalidScope);
+        }
+
+        let client_secret = self.token_generator.generate();
+        let client = OAuthClient::register(
+            Uuid::new_v4(),
+            name,
+            self.token_generator.hash(&client_secret),
+            scopes,
+        );
+        self.client_repository.create(&client)?;
+
+        info!(
+            client_id = %client.client_id(),
+            actor_id = %actor.user_id,
+            "OAuth client registered"
+        );
+        Ok(RegisteredOAuthClient {
+            client_secret,
+            client,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+    use crate::application::commands::test_support::{token_data, MockAuditLog};
+    use crate::domain::role::Role;
+    use crate::domain::user::User;
+
+    // Client store recording registered clients
+    #[derive(Default)]
+    struct MockOAuthClientRepository {
+        clients: RefCell<Vec<OAuthClient>>,
+    }
+
+    impl OAuthClientRepository for MockOAuthClientRepository {
+        fn create(&self, client: &OAuthClient) -> Result<(), AuthError> {
+            self.clients.borrow_mut().push(client.clone());
+            Ok(())
+        }
+
+        fn find_by_id(&self, _client_id: Uuid) -> Result<OAuthClient, AuthError> {
+            Err(AuthError::OAuthClientNotFound)
+        }
+
+        fn list(&self) -> Result<Vec<OAuthClient>, AuthError> {
+            Ok(self.clients.borrow().clone())
+        }
+
+        fn revoke(&self, _client_id: Uuid) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+    }
+
+    // Generator with a fixed secret and a reversible "hash"
+    struct MockTokenGenerator;
+
+    impl OpaqueTokenGenerator for MockTokenGenerator {
+        fn generate(&self) -> String {
+            "secret".to_string()
+        }
+
+        fn hash(&self, token: &str) -> String {
+            format!("hashed:{}", token)
+        }
+    }
+
+    // Token service mapping "admin", "scoped" and "customer" tokens to callers
+    struct MockTokenService;
+
+    impl TokenService for MockTokenService {
+        fn create_token(
+            &self,
+            _user: &User,
+            _session_id: Option<Uuid>,
+        ) -> Result<String, AuthError> {
+            Ok("token".to_string())
+        }
+
+        fn validate_token(&self, token: &str) -> Result<TokenData, AuthError> {
+            let (roles, scopes) = match token {
+                "admin" => (vec![Role::Admin], None),
+                "scoped" => (
+                    vec![Role::Admin],
+                    Some(vec![Permission::ManageClients, Permission::ReadUsers]),
+                ),
+                "customer" => (vec![Role::Customer], None),
+                _ => return Err(AuthError::InvalidToken),
+            };
+            Ok(TokenData {
+                roles,
+                scopes,
+                ..token_data(Uuid::new_v4())
+            })
+        }
+
+        fn revoke_token(&self, _token: &TokenData) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn is_revoked(&self, _token: &TokenData) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+    }
+
+    fn register(
+        clients: &MockOAuthClientRepository,
+        token: &str,
+        scopes: &[&str],
+    ) -> Result<RegisteredOAuthClient, AuthError> {
+        RegisterOAuthClientUseCase::new(
+            clients,
+            &MockTokenGenerator,
+            &MockTokenService,
+            &MockAuditLog::default(),
+        )
+        .execute(RegisterOAuthClientCommand {
+            token: token.to_string(),
+            name: " orders-service ".to_string(),
+            scopes: scopes.iter().map(|scope| scope.to_string()).collect(),
+        })
+    }
+
+    #[test]
+    fn test_register_client() {
+        let clients = MockOAuthClientRepository::default();
+
+        let registered = register(&clients, "admin", &["users:read", "orders:read_all"]).unwrap();
+
+        assert_eq!(registered.client_secret, "secret");
+        assert_eq!(registered.client.name(), "orders-service");
+        assert_eq!(registered.client.secret_hash(), "hashed:secret");
+        assert_eq!(
+            registered.client.scopes(),
+            &[Permission::ReadAllOrders, Permission::ReadUsers]
+        );
+        assert_eq!(clients.clients.borrow().len(), 1);
+    }
+
+    #[test]
+    fn test_register_requires_permission_and_held_scopes() {
+        let clients = MockOAuthClientRepository::default();
+
+        assert!(matches!(
+            register(&clients, "customer", &["users:read"]),
+            Err(AuthError::Forbidden)
+        ));
+        assert!(matches!(
+            register(&clients, "scoped", &["users:manage"]),
+            Err(AuthError::InvalidScope)
+        ));
+        assert!(matches!(
+            register(&clients, "admin", &[]),
+            Err(AuthError::InvalidScope)
+        ));
+        assert!(clients.clients.borrow().is_empty());
+    }
+}
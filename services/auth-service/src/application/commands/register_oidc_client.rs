@@ -0,0 +1,107 @@
+//! Register OpenID Connect client use case
+//!
+//! Lets a first-party web app sign users in. Registering an existing client
+//! ID replaces its name and redirect URIs, so deployments can apply the same
+//! registration repeatedly.
+
+use tracing::info;
+
+use crate::domain::audit::{AuditAction, AuditEvent, AuditLog};
+use crate::domain::auth::{TokenData, TokenService};
+use crate::domain::error::AuthError;
+use crate::domain::oauth_client::normalize_client_name;
+use crate::domain::oidc::{
+    validate_client_id, validate_redirect_uri, OidcClient, OidcClientRepository,
+};
+use crate::domain::role::{authorize, Permission};
+
+/// Input for registering an OpenID Connect client
+#[derive(Debug)]
+pub struct RegisterOidcClientCommand {
+    /// Access token of the acting administrator
+    pub token: String,
+    /// Identifier the app presents, e.g. `web`
+    pub client_id: String,
+    /// Name of the app
+    pub name: String,
+    /// URIs authorization codes may be sent to
+    pub redirect_uris: Vec<String>,
+}
+
+/// Use case for registering an OpenID Connect client
+pub struct RegisterOidcClientUseCase<'a, C: ?Sized, T: ?Sized, L: ?Sized> {
+    client_repository: &'a C,
+    token_service: &'a T,
+    audit_log: &'a L,
+}
+
+impl<'a, C, T, L> RegisterOidcClientUseCase<'a, C, T, L>
+where
+    C: OidcClientRepository + ?Sized,
+    T: TokenService + ?Sized,
+    L: AuditLog + ?Sized,
+{
+    /// Create a new use case instance
+    pub fn new(client_repository: &'a C, token_service: &'a T, audit_log: &'a L) -> Self {
+        Self {
+            client_repository,
+            token_service,
+            audit_log,
+        }
+    }
+
+    /// Execute the registration
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidToken` / `AuthError::TokenExpired` if the token is invalid
+    /// - `AuthError::Forbidden` if the caller may not manage clients
+    /// - `AuthError::InvalidRequest` if the client ID or a redirect URI is
+    ///   malformed, or no redirect URI is given
+    /// - `AuthError::InvalidClientName` if the name is empty or malformed
+    /// - `AuthError::Internal` on infrastructure failures
+    pub fn execute(&self, command: RegisterOidcClientCommand) -> Result<OidcClient, AuthError> {
+        let actor = self.token_service.validate_token(&command.token)?;
+        let client_id = command.client_id.clone();
+
+        let result = self.register(&actor, command);
+        self.audit_log.record(
+            AuditEvent::new(AuditAction::OidcClientRegistration)
+                .with_actor(actor.audit_actor())
+                .with_detail(client_id)
+                .with_result(&result),
+        );
+        result
+    }
+
+    fn register(
+        &self,
+        actor: &TokenData,
+        command: RegisterOidcClientCommand,
+    ) -> Result<OidcClient, AuthError> {
+        authorize(actor, Permission::ManageClients)?;
+
+        validate_client_id(&command.client_id)?;
+        let name = normalize_client_name(&command.name)?;
+        if command.redirect_uris.is_empty() {
+            return Err(AuthError::InvalidRequest(
+                "at least one redirect_uri is required".to_string(),
+            ));
+        }
+        for redirect_uri in &command.redirect_uris {
+            validate_redirect_uri(redirect_uri)?;
+        }
+
+        let client = self.client_repository.save(&OidcClient::register(
+            command.client_id,
+            name,
+            command.redirect_uris,
+        ))?;
+
+        info!(
+            client_id = %client.client_id(),
+            actor_id = %actor.user_id,
+            "OIDC client registered"
+        );
+        Ok(client)
+    }
+}
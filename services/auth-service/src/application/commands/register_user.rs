@@ -1,8 +1,19 @@
 //! Register user use case
+//!
+//! New accounts start with an unverified email address; a verification
+//! link is mailed as part of registration.
 
-use crate::domain::auth::{PasswordHasher, UserRepository};
+use chrono::Duration;
+use tracing::info;
+
+use super::send_email_verification::verification_email;
+use crate::domain::audit::{AsyncAuditLog, AuditAction, AuditEvent};
+use crate::domain::auth::{AsyncPasswordHasher, AsyncUserRepository, OpaqueTokenGenerator};
+use crate::domain::email_verification::AsyncEmailVerificationTokenRepository;
 use crate::domain::error::AuthError;
-use crate::domain::user::{Email, User};
+use crate::domain::mailer::Mailer;
+use crate::domain::password_policy::PasswordPolicy;
+use crate::domain::user::{normalize_display_name, Email, User};
 
 /// Input for user registration
 #[derive(Debug)]
@@ -21,21 +32,54 @@ pub struct RegisterUserResult {
 }
 
 /// Use case for registering a new user
-pub struct RegisterUserUseCase<'a, R: ?Sized, H: ?Sized> {
+pub struct RegisterUserUseCase<'a, R: ?Sized, H: ?Sized, V: ?Sized, G: ?Sized, M: ?Sized, L: ?Sized>
+{
     user_repository: &'a R,
     password_hasher: &'a H,
+    password_policy: &'a PasswordPolicy,
+    verification_token_repository: &'a V,
+    token_generator: &'a G,
+    mailer: &'a M,
+    verification_token_ttl: Duration,
+    verification_url: &'a str,
+    audit_log: &'a L,
 }
 
-impl<'a, R, H> RegisterUserUseCase<'a, R, H>
+impl<'a, R, H, V, G, M, L> RegisterUserUseCase<'a, R, H, V, G, M, L>
 where
-    R: UserRepository + ?Sized,
-    H: PasswordHasher + ?Sized,
+    R: AsyncUserRepository + ?Sized,
+    H: AsyncPasswordHasher + ?Sized,
+    V: AsyncEmailVerificationTokenRepository + ?Sized,
+    G: OpaqueTokenGenerator + ?Sized,
+    M: Mailer + ?Sized,
+    L: AsyncAuditLog + ?Sized,
 {
     /// Create a new use case instance
-    pub fn new(user_repository: &'a R, password_hasher: &'a H) -> Self {
+    ///
+    /// `verification_url` is the page that completes email verification;
+    /// the secret is appended as a `token` query parameter.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        user_repository: &'a R,
+        password_hasher: &'a H,
+        password_policy: &'a PasswordPolicy,
+        verification_token_repository: &'a V,
+        token_generator: &'a G,
+        mailer: &'a M,
+        verification_token_ttl: Duration,
+        verification_url: &'a str,
+        audit_log: &'a L,
+    ) -> Self {
         Self {
             user_repository,
             password_hasher,
+            password_policy,
+            verification_token_repository,
+            token_generator,
+            mailer,
+            verification_token_ttl,
+            verification_url,
+            audit_log,
         }
     }
 
@@ -44,30 +88,60 @@ where
     /// # Errors
     /// - `AuthError::UserAlreadyExists` if email is taken
     /// - `AuthError::InvalidEmail` if email format is invalid
-    /// - `AuthError::WeakPassword` if password doesn't meet requirements
+    /// - `AuthError::InvalidDisplayName` if the display name is too long or malformed
+    /// - `AuthError::WeakPassword` if the password breaks the password policy
     /// - `AuthError::Internal` on infrastructure failures
-    pub fn execute(&self, command: RegisterUserCommand) -> Result<RegisterUserResult, AuthError> {
+    pub async fn execute(
+        &self,
+        command: RegisterUserCommand,
+    ) -> Result<RegisterUserResult, AuthError> {
+        let result = self.register(command).await;
+
+        let mut event = AuditEvent::new(AuditAction::Register).with_result(&result);
+        if let Ok(registered) = &result {
+            event = event
+                .with_actor(registered.user_id)
+                .with_target(registered.user_id);
+        }
+        self.audit_log.record(event).await;
+        result
+    }
+
+    async fn register(
+        &self,
+        command: RegisterUserCommand,
+    ) -> Result<RegisterUserResult, AuthError> {
         // Validate email format
         let email = Email::new(&command.email)?;
+        let display_name = normalize_display_name(command.display_name.as_deref())?;
 
         // Check if user already exists
-        if self.user_repository.exists_by_email(email.as_str())? {
+        if self.user_repository.exists_by_email(email.as_str()).await? {
             return Err(AuthError::UserAlreadyExists);
         }
 
-        // Validate password strength (basic check)
-        if command.password.len() < 8 {
-            return Err(AuthError::WeakPassword);
-        }
+        self.password_policy
+            .validate(&command.password, email.as_str())?;
 
         // Hash the password
-        let hashed_password = self.password_hasher.hash(&command.password)?;
+        let hashed_password = self.password_hasher.hash(&command.password).await?;
 
         // Create the user entity
-        let user = User::new(email, hashed_password, command.display_name);
+        let user = User::new(email, hashed_password, display_name);
 
         // Persist the user
-        let created_user = self.user_repository.create(&user)?;
+        let created_user = self.user_repository.create(&user).await?;
+
+        // Ask the user to confirm their address
+        let (token, message) = verification_email(
+            &created_user,
+            self.token_generator,
+            self.verification_token_ttl,
+            self.verification_url,
+        );
+        self.verification_token_repository.create(&token).await?;
+        self.mailer.send(&message)?;
+        info!(user_id = %created_user.id().as_uuid(), "Verification email sent");
 
         Ok(RegisterUserResult {
             user_id: created_user.id().as_uuid(),
@@ -79,76 +153,149 @@ where
 
 #[cfg(test)]
 mod tests {
+    use std::cell::RefCell;
+    use std::sync::Mutex;
+
     use super::*;
-    use crate::domain::user::HashedPassword;
+    use crate::application::commands::test_support::{MockAuditLog, MockPasswordHasher};
+    use crate::domain::audit::AuditOutcome;
+    use crate::domain::auth::BoxFuture;
+    use crate::domain::email_verification::EmailVerificationToken;
+    use crate::domain::mailer::EmailMessage;
+    use crate::domain::password_policy::PasswordRule;
 
     // Simple mock repository for testing
     struct MockUserRepository {
         existing_emails: Vec<String>,
     }
 
-    impl UserRepository for MockUserRepository {
-        fn find_by_id(&self, _id: uuid::Uuid) -> Result<User, AuthError> {
-            Err(AuthError::UserNotFound)
+    impl AsyncUserRepository for MockUserRepository {
+        fn find_by_id(&self, _id: uuid::Uuid) -> BoxFuture<'_, Result<User, AuthError>> {
+            Box::pin(async { Err(AuthError::UserNotFound) })
         }
 
-        fn find_by_email(&self, email: &str) -> Result<User, AuthError> {
-            if self.existing_emails.contains(&email.to_string()) {
+        fn find_by_email<'a>(&'a self, email: &'a str) -> BoxFuture<'a, Result<User, AuthError>> {
+            let user = if self.existing_emails.contains(&email.to_string()) {
                 Ok(User::from_persistence(
                     uuid::Uuid::new_v4(),
                     email.to_string(),
-                    "hash".to_string(),
+                    Some("hash".to_string()),
                     None,
                     true,
+                    None,
                     chrono::Utc::now(),
                     chrono::Utc::now(),
                 ))
             } else {
                 Err(AuthError::UserNotFound)
-            }
+            };
+            Box::pin(async { user })
         }
 
-        fn exists_by_email(&self, email: &str) -> Result<bool, AuthError> {
-            Ok(self.existing_emails.contains(&email.to_lowercase()))
+        fn exists_by_email<'a>(&'a self, email: &'a str) -> BoxFuture<'a, Result<bool, AuthError>> {
+            let exists = self.existing_emails.contains(&email.to_lowercase());
+            Box::pin(async move { Ok(exists) })
         }
 
-        fn create(&self, user: &User) -> Result<User, AuthError> {
-            Ok(User::from_persistence(
+        fn create<'a>(&'a self, user: &'a User) -> BoxFuture<'a, Result<User, AuthError>> {
+            let created = User::from_persistence(
                 user.id().as_uuid(),
                 user.email().as_str().to_string(),
-                user.hashed_password().as_str().to_string(),
+                user.hashed_password().map(|h| h.as_str().to_string()),
                 user.display_name().map(String::from),
                 user.is_active(),
+                user.email_verified_at(),
                 user.created_at(),
                 user.updated_at(),
-            ))
+            );
+            Box::pin(async { Ok(created) })
         }
 
-        fn update(&self, _user: &User) -> Result<User, AuthError> {
-            Err(AuthError::Internal("Not implemented".to_string()))
+        fn update<'a>(&'a self, _user: &'a User) -> BoxFuture<'a, Result<User, AuthError>> {
+            Box::pin(async { Err(AuthError::Internal("Not implemented".to_string())) })
         }
     }
 
-    // Simple mock hasher
-    struct MockPasswordHasher;
+    // Verification token store recording issued tokens
+    #[derive(Default)]
+    struct MockVerificationTokenRepository {
+        created: Mutex<Vec<EmailVerificationToken>>,
+    }
+
+    impl AsyncEmailVerificationTokenRepository for MockVerificationTokenRepository {
+        fn create<'a>(
+            &'a self,
+            token: &'a EmailVerificationToken,
+        ) -> BoxFuture<'a, Result<(), AuthError>> {
+            self.created.lock().unwrap().push(token.clone());
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    // Mock generator with a fixed secret
+    struct MockTokenGenerator;
 
-    impl PasswordHasher for MockPasswordHasher {
-        fn hash(&self, plain: &str) -> Result<HashedPassword, AuthError> {
-            Ok(HashedPassword::from_hash(format!("hashed_{}", plain)))
+    impl OpaqueTokenGenerator for MockTokenGenerator {
+        fn generate(&self) -> String {
+            "verify_secret".to_string()
         }
 
-        fn verify(&self, plain: &str, hashed: &HashedPassword) -> Result<bool, AuthError> {
-            Ok(hashed.as_str() == format!("hashed_{}", plain))
+        fn hash(&self, token: &str) -> String {
+            format!("hashed_{}", token)
         }
     }
 
-    #[test]
-    fn test_register_success() {
+    // Mailer collecting sent messages
+    #[derive(Default)]
+    struct MockMailer {
+        sent: RefCell<Vec<EmailMessage>>,
+    }
+
+    impl Mailer for MockMailer {
+        fn send(&self, message: &EmailMessage) -> Result<(), AuthError> {
+            self.sent.borrow_mut().push(message.clone());
+            Ok(())
+        }
+    }
+
+    async fn register(
+        repo: &MockUserRepository,
+        tokens: &MockVerificationTokenRepository,
+        mailer: &MockMailer,
+        command: RegisterUserCommand,
+    ) -> Result<RegisterUserResult, AuthError> {
+        register_audited(repo, tokens, mailer, &MockAuditLog::default(), command).await
+    }
+
+    async fn register_audited(
+        repo: &MockUserRepository,
+        tokens: &MockVerificationTokenRepository,
+        mailer: &MockMailer,
+        audit_log: &MockAuditLog,
+        command: RegisterUserCommand,
+    ) -> Result<RegisterUserResult, AuthError> {
+        RegisterUserUseCase::new(
+            repo,
+            &MockPasswordHasher,
+            &PasswordPolicy::default(),
+            tokens,
+            &MockTokenGenerator,
+            mailer,
+            Duration::hours(24),
+            "https://example.com/verify-email",
+            audit_log,
+        )
+        .execute(command)
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_register_success() {
         let repo = MockUserRepository {
             existing_emails: vec![],
         };
-        let hasher = MockPasswordHasher;
-        let use_case = RegisterUserUseCase::new(&repo, &hasher);
+        let tokens = MockVerificationTokenRepository::default();
+        let mailer = MockMailer::default();
 
         let command = RegisterUserCommand {
             email: "new@example.com".to_string(),
@@ -156,18 +303,25 @@ mod tests {
             display_name: Some("New User".to_string()),
         };
 
-        let result = use_case.execute(command).unwrap();
+        let result = register(&repo, &tokens, &mailer, command).await.unwrap();
         assert_eq!(result.email, "new@example.com");
         assert_eq!(result.display_name, Some("New User".to_string()));
+
+        // A verification link goes out for the new address
+        let created = tokens.created.lock().unwrap();
+        assert_eq!(created.len(), 1);
+        assert_eq!(created[0].user_id(), result.user_id);
+        assert_eq!(created[0].email(), "new@example.com");
+        assert_eq!(mailer.sent.borrow()[0].to, "new@example.com");
     }
 
-    #[test]
-    fn test_register_email_exists() {
+    #[tokio::test]
+    async fn test_register_email_exists() {
         let repo = MockUserRepository {
             existing_emails: vec!["existing@example.com".to_string()],
         };
-        let hasher = MockPasswordHasher;
-        let use_case = RegisterUserUseCase::new(&repo, &hasher);
+        let tokens = MockVerificationTokenRepository::default();
+        let mailer = MockMailer::default();
 
         let command = RegisterUserCommand {
             email: "existing@example.com".to_string(),
@@ -175,17 +329,17 @@ mod tests {
             display_name: None,
         };
 
-        let result = use_case.execute(command);
+        let result = register(&repo, &tokens, &mailer, command).await;
         assert!(matches!(result, Err(AuthError::UserAlreadyExists)));
     }
 
-    #[test]
-    fn test_register_weak_password() {
+    #[tokio::test]
+    async fn test_register_weak_password() {
         let repo = MockUserRepository {
             existing_emails: vec![],
         };
-        let hasher = MockPasswordHasher;
-        let use_case = RegisterUserUseCase::new(&repo, &hasher);
+        let tokens = MockVerificationTokenRepository::default();
+        let mailer = MockMailer::default();
 
         let command = RegisterUserCommand {
             email: "test@example.com".to_string(),
@@ -193,7 +347,79 @@ mod tests {
             display_name: None,
         };
 
-        let result = use_case.execute(command);
-        assert!(matches!(result, Err(AuthError::WeakPassword)));
+        let result = register(&repo, &tokens, &mailer, command).await;
+        assert_eq!(
+            result.unwrap_err(),
+            AuthError::WeakPassword(vec![PasswordRule::MinLength(8)])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_register_password_containing_email() {
+        let repo = MockUserRepository {
+            existing_emails: vec![],
+        };
+        let tokens = MockVerificationTokenRepository::default();
+        let mailer = MockMailer::default();
+
+        let command = RegisterUserCommand {
+            email: "Margaret@example.com".to_string(),
+            password: "margaret1984".to_string(),
+            display_name: None,
+        };
+
+        let result = register(&repo, &tokens, &mailer, command).await;
+        assert_eq!(
+            result.unwrap_err(),
+            AuthError::WeakPassword(vec![PasswordRule::NoEmail])
+        );
+        assert!(tokens.created.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_register_is_audited() {
+        let repo = MockUserRepository {
+            existing_emails: vec!["existing@example.com".to_string()],
+        };
+        let tokens = MockVerificationTokenRepository::default();
+        let mailer = MockMailer::default();
+        let audit_log = MockAuditLog::default();
+
+        let created = register_audited(
+            &repo,
+            &tokens,
+            &mailer,
+            &audit_log,
+            RegisterUserCommand {
+                email: "new@example.com".to_string(),
+                password: "ValidPass123".to_string(),
+                display_name: None,
+            },
+        )
+        .await
+        .unwrap();
+        let _ = register_audited(
+            &repo,
+            &tokens,
+            &mailer,
+            &audit_log,
+            RegisterUserCommand {
+                email: "existing@example.com".to_string(),
+                password: "ValidPass123".to_string(),
+                display_name: None,
+            },
+        )
+        .await;
+
+        let events = audit_log.events.lock().unwrap();
+        assert_eq!(events[0].action(), AuditAction::Register);
+        assert_eq!(events[0].outcome(), AuditOutcome::Success);
+        assert_eq!(events[0].target_id(), Some(created.user_id));
+        assert_eq!(events[1].outcome(), AuditOutcome::Failure);
+        assert_eq!(events[1].target_id(), None);
+        assert_eq!(
+            events[1].detail(),
+            Some("User with this email already exists")
+        );
     }
 }
@@ -0,0 +1,340 @@
+//! Request magic link use case
+//!
+//! Mails a single-use login link to the owner of an email address. As with
+//! password resets, the outcome is the same whether or not the address
+//! belongs to an account, and requests over the rate limit are dropped
+//! rather than refused, so the endpoint reveals nothing about accounts.
+//! Both requests and dropped requests are audited.
+
+use chrono::{Duration, Utc};
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::domain::audit::{AuditAction, AuditEvent, AuditLog};
+use crate::domain::auth::{OpaqueTokenGenerator, UserRepository};
+use crate::domain::error::AuthError;
+use crate::domain::magic_link::{MagicLinkRateLimit, MagicLinkToken, MagicLinkTokenRepository};
+use crate::domain::mailer::{EmailMessage, Mailer};
+use crate::domain::user::Email;
+
+/// Input for requesting a magic link
+#[derive(Debug)]
+pub struct RequestMagicLinkCommand {
+    pub email: String,
+}
+
+/// What became of a well-formed request
+enum Delivery {
+    /// Mailed to the account
+    Sent(Uuid),
+    /// No active account has the address
+    NoAccount,
+    /// Dropped because the account was mailed too many links recently
+    RateLimited(Uuid),
+}
+
+/// Use case for requesting a magic link
+pub struct RequestMagicLinkUseCase<'a, R: ?Sized, L: ?Sized, G: ?Sized, M: ?Sized, A: ?Sized> {
+    user_repository: &'a R,
+    link_repository: &'a L,
+    token_generator: &'a G,
+    mailer: &'a M,
+    audit_log: &'a A,
+    link_ttl: Duration,
+    login_url: &'a str,
+    rate_limit: MagicLinkRateLimit,
+}
+
+impl<'a, R, L, G, M, A> RequestMagicLinkUseCase<'a, R, L, G, M, A>
+where
+    R: UserRepository + ?Sized,
+    L: MagicLinkTokenRepository + ?Sized,
+    G: OpaqueTokenGenerator + ?Sized,
+    M: Mailer + ?Sized,
+    A: AuditLog + ?Sized,
+{
+    /// Create a new use case instance
+    ///
+    /// `login_url` is the page that completes the login; the secret is
+    /// appended as a `token` query parameter.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        user_repository: &'a R,
+        link_repository: &'a L,
+        token_generator: &'a G,
+        mailer: &'a M,
+        audit_log: &'a A,
+        link_ttl: Duration,
+        login_url: &'a str,
+        rate_limit: MagicLinkRateLimit,
+    ) -> Self {
+        Self {
+            user_repository,
+            link_repository,
+            token_generator,
+            mailer,
+            audit_log,
+            link_ttl,
+            login_url,
+            rate_limit,
+        }
+    }
+
+    /// Execute the request
+    ///
+    /// Succeeds without sending anything for unknown or inactive accounts,
+    /// and for accounts that were mailed too many links recently.
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidEmail` if email format is invalid
+    /// - `AuthError::Internal` on infrastructure failures
+    pub fn execute(&self, command: RequestMagicLinkCommand) -> Result<(), AuthError> {
+        let result = self.request(command);
+
+        let event = match &result {
+            Ok(Delivery::Sent(user_id)) => {
+                AuditEvent::new(AuditAction::MagicLinkRequest).with_target(*user_id)
+            }
+            Ok(Delivery::RateLimited(user_id)) => {
+                AuditEvent::new(AuditAction::MagicLinkRateLimit).with_target(*user_id)
+            }
+            Ok(Delivery::NoAccount) | Err(_) => {
+                AuditEvent::new(AuditAction::MagicLinkRequest).with_result(&result)
+            }
+        };
+        self.audit_log.record(event);
+        result.map(|_| ())
+    }
+
+    fn request(&self, command: RequestMagicLinkCommand) -> Result<Delivery, AuthError> {
+        let email = Email::new(&command.email)?;
+
+        let user = match self.user_repository.find_by_email(email.as_str()) {
+            Ok(user) if user.is_active() => user,
+            Ok(_) | Err(AuthError::UserNotFound) => {
+                debug!("Magic link requested for unknown or inactive account");
+                return Ok(Delivery::NoAccount);
+            }
+            Err(e) => return Err(e),
+        };
+        let user_id = user.id().as_uuid();
+
+        let recent = self
+            .link_repository
+            .count_issued_since(user_id, Utc::now() - self.rate_limit.window)?;
+        if recent >= self.rate_limit.max_links {
+            warn!(user_id = %user_id, recent, "Magic link rate limit reached, request dropped");
+            return Ok(Delivery::RateLimited(user_id));
+        }
+
+        let secret = self.token_generator.generate();
+        self.link_repository.create(&MagicLinkToken::issue(
+            user_id,
+            self.token_generator.hash(&secret),
+            self.link_ttl,
+        ))?;
+
+        self.mailer.send(&EmailMessage {
+            to: user.email().as_str().to_string(),
+            subject: "Your login link".to_string(),
+            body: format!(
+                "Follow this link within {} minutes to log in:\n\n\
+                 {}?token={}\n\n\
+                 The link works once. If you did not request it, you can ignore this email.\n",
+                self.link_ttl.num_minutes(),
+                self.login_url,
+                secret
+            ),
+        })?;
+
+        info!(user_id = %user_id, "Magic link requested");
+        Ok(Delivery::Sent(user_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use chrono::DateTime;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::application::commands::test_support::{MockAuditLog, MockUserRepository};
+    use crate::domain::user::{HashedPassword, User};
+
+    // Link store recording issued tokens
+    #[derive(Default)]
+    struct MockLinkRepository {
+        created: RefCell<Vec<MagicLinkToken>>,
+    }
+
+    impl MagicLinkTokenRepository for MockLinkRepository {
+        fn create(&self, token: &MagicLinkToken) -> Result<(), AuthError> {
+            self.created.borrow_mut().push(token.clone());
+            Ok(())
+        }
+
+        fn count_issued_since(
+            &self,
+            user_id: Uuid,
+            since: DateTime<Utc>,
+        ) -> Result<i64, AuthError> {
+            Ok(self
+                .created
+                .borrow()
+                .iter()
+                .filter(|token| token.user_id() == user_id && token.created_at() >= since)
+                .count() as i64)
+        }
+
+        fn purge_expired(&self, _cutoff: DateTime<Utc>) -> Result<usize, AuthError> {
+            Ok(0)
+        }
+    }
+
+    // Mock generator with a fixed secret
+    struct MockTokenGenerator;
+
+    impl OpaqueTokenGenerator for MockTokenGenerator {
+        fn generate(&self) -> String {
+            "link_secret".to_string()
+        }
+
+        fn hash(&self, token: &str) -> String {
+            format!("hashed_{}", token)
+        }
+    }
+
+    // Mailer collecting sent messages
+    #[derive(Default)]
+    struct MockMailer {
+        sent: RefCell<Vec<EmailMessage>>,
+    }
+
+    impl Mailer for MockMailer {
+        fn send(&self, message: &EmailMessage) -> Result<(), AuthError> {
+            self.sent.borrow_mut().push(message.clone());
+            Ok(())
+        }
+    }
+
+    fn create_repo(active: bool) -> MockUserRepository {
+        let email = Email::new("test@example.com").unwrap();
+        let mut user = User::new(email, HashedPassword::from_hash("hashed".to_string()), None);
+        if !active {
+            user.deactivate();
+        }
+        MockUserRepository::new(user)
+    }
+
+    fn request(
+        repo: &MockUserRepository,
+        links: &MockLinkRepository,
+        mailer: &MockMailer,
+        email: &str,
+    ) -> Result<(), AuthError> {
+        request_audited(repo, links, mailer, &MockAuditLog::default(), email)
+    }
+
+    fn request_audited(
+        repo: &MockUserRepository,
+        links: &MockLinkRepository,
+        mailer: &MockMailer,
+        audit_log: &MockAuditLog,
+        email: &str,
+    ) -> Result<(), AuthError> {
+        RequestMagicLinkUseCase::new(
+            repo,
+            links,
+            &MockTokenGenerator,
+            mailer,
+            audit_log,
+            Duration::minutes(15),
+            "https://example.com/magic-link",
+            MagicLinkRateLimit {
+                max_links: 2,
+                window: Duration::hours(1),
+            },
+        )
+        .execute(RequestMagicLinkCommand {
+            email: email.to_string(),
+        })
+    }
+
+    #[test]
+    fn test_mails_login_link() {
+        let repo = create_repo(true);
+        let links = MockLinkRepository::default();
+        let mailer = MockMailer::default();
+
+        request(&repo, &links, &mailer, "Test@Example.com").unwrap();
+
+        // Only the hash is stored, the secret goes out by mail
+        let created = links.created.borrow();
+        assert_eq!(created.len(), 1);
+        assert_eq!(created[0].token_hash(), "hashed_link_secret");
+        assert_eq!(created[0].user_id(), repo.user_id());
+
+        let sent = mailer.sent.borrow();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].to, "test@example.com");
+        assert!(sent[0]
+            .body
+            .contains("https://example.com/magic-link?token=link_secret"));
+    }
+
+    #[test]
+    fn test_rate_limit_drops_requests_silently() {
+        let repo = create_repo(true);
+        let links = MockLinkRepository::default();
+        let mailer = MockMailer::default();
+
+        for _ in 0..3 {
+            request(&repo, &links, &mailer, "test@example.com").unwrap();
+        }
+
+        assert_eq!(links.created.borrow().len(), 2);
+        assert_eq!(mailer.sent.borrow().len(), 2);
+    }
+
+    #[test]
+    fn test_requests_and_dropped_requests_are_audited() {
+        let repo = create_repo(true);
+        let links = MockLinkRepository::default();
+        let mailer = MockMailer::default();
+        let audit_log = MockAuditLog::default();
+
+        for _ in 0..3 {
+            request_audited(&repo, &links, &mailer, &audit_log, "test@example.com").unwrap();
+        }
+        request_audited(&repo, &links, &mailer, &audit_log, "other@example.com").unwrap();
+
+        let events = audit_log.events.lock().unwrap();
+        let actions: Vec<_> = events.iter().map(AuditEvent::action).collect();
+        assert_eq!(
+            actions,
+            vec![
+                AuditAction::MagicLinkRequest,
+                AuditAction::MagicLinkRequest,
+                AuditAction::MagicLinkRateLimit,
+                AuditAction::MagicLinkRequest,
+            ]
+        );
+        assert_eq!(events[2].target_id(), Some(repo.user_id()));
+        // Unknown addresses are audited without a target
+        assert_eq!(events[3].target_id(), None);
+    }
+
+    #[test]
+    fn test_unknown_or_inactive_account_is_silent() {
+        let links = MockLinkRepository::default();
+        let mailer = MockMailer::default();
+
+        request(&create_repo(true), &links, &mailer, "other@example.com").unwrap();
+        request(&create_repo(false), &links, &mailer, "test@example.com").unwrap();
+
+        assert!(links.created.borrow().is_empty());
+        assert!(mailer.sent.borrow().is_empty());
+    }
+}
@@ -0,0 +1,241 @@
+//! Request password reset use case
+//!
+//! Mails a single-use reset link to the owner of an email address. The
+//! outcome is the same whether or not the address belongs to an account, so
+//! the endpoint cannot be used to discover registered users.
+
+use chrono::Duration;
+use tracing::{debug, info};
+
+use crate::domain::auth::{OpaqueTokenGenerator, UserRepository};
+use crate::domain::error::AuthError;
+use crate::domain::mailer::{EmailMessage, Mailer};
+use crate::domain::password_reset::{PasswordResetToken, PasswordResetTokenRepository};
+use crate::domain::user::Email;
+
+/// Input for requesting a password reset
+#[derive(Debug)]
+pub struct RequestPasswordResetCommand {
+    pub email: String,
+}
+
+/// Use case for requesting a password reset
+pub struct RequestPasswordResetUseCase<'a, R: ?Sized, P: ?Sized, G: ?Sized, M: ?Sized> {
+    user_repository: &'a R,
+    reset_token_repository: &'a P,
+    token_generator: &'a G,
+    mailer: &'a M,
+    reset_token_ttl: Duration,
+    reset_url: &'a str,
+}
+
+impl<'a, R, P, G, M> RequestPasswordResetUseCase<'a, R, P, G, M>
+where
+    R: UserRepository + ?Sized,
+    P: PasswordResetTokenRepository + ?Sized,
+    G: OpaqueTokenGenerator + ?Sized,
+    M: Mailer + ?Sized,
+{
+    /// Create a new use case instance
+    ///
+    /// `reset_url` is the page that completes the reset; the secret is
+    /// appended as a `token` query parameter.
+    pub fn new(
+        user_repository: &'a R,
+        reset_token_repository: &'a P,
+        token_generator: &'a G,
+        mailer: &'a M,
+        reset_token_ttl: Duration,
+        reset_url: &'a str,
+    ) -> Self {
+        Self {
+            user_repository,
+            reset_token_repository,
+            token_generator,
+            mailer,
+            reset_token_ttl,
+            reset_url,
+        }
+    }
+
+    /// Execute the request
+    ///
+    /// Succeeds without sending anything for unknown or inactive accounts.
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidEmail` if email format is invalid
+    /// - `AuthError::Internal` on infrastructure failures
+    pub fn execute(&self, command: RequestPasswordResetCommand) -> Result<(), AuthError> {
+        let email = Email::new(&command.email)?;
+
+        let user = match self.user_repository.find_by_email(email.as_str()) {
+            Ok(user) if user.is_active() => user,
+            Ok(_) | Err(AuthError::UserNotFound) => {
+                debug!("Password reset requested for unknown or inactive account");
+                return Ok(());
+            }
+            Err(e) => return Err(e),
+        };
+
+        let secret = self.token_generator.generate();
+        self.reset_token_repository
+            .create(&PasswordResetToken::issue(
+                user.id().as_uuid(),
+                self.token_generator.hash(&secret),
+                self.reset_token_ttl,
+            ))?;
+
+        self.mailer.send(&EmailMessage {
+            to: user.email().as_str().to_string(),
+            subject: "Reset your password".to_string(),
+            body: format!(
+                "A password reset was requested for your account.\n\n\
+                 Follow this link within {} minutes to choose a new password:\n\n\
+                 {}?token={}\n\n\
+                 If you did not request this, you can ignore this email.\n",
+                self.reset_token_ttl.num_minutes(),
+                self.reset_url,
+                secret
+            ),
+        })?;
+
+        info!(user_id = %user.id().as_uuid(), "Password reset requested");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use chrono::{DateTime, Utc};
+
+    use super::*;
+    use crate::application::commands::test_support::MockUserRepository;
+    use crate::domain::user::{HashedPassword, User};
+
+    // Reset token store recording issued tokens
+    #[derive(Default)]
+    struct MockResetTokenRepository {
+        created: RefCell<Vec<PasswordResetToken>>,
+    }
+
+    impl PasswordResetTokenRepository for MockResetTokenRepository {
+        fn create(&self, token: &PasswordResetToken) -> Result<(), AuthError> {
+            self.created.borrow_mut().push(token.clone());
+            Ok(())
+        }
+
+        fn find_by_hash(&self, _token_hash: &str) -> Result<PasswordResetToken, AuthError> {
+            Err(AuthError::InvalidToken)
+        }
+
+        fn consume(&self, _token: &PasswordResetToken) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+
+        fn purge_expired(&self, _now: DateTime<Utc>) -> Result<usize, AuthError> {
+            Ok(0)
+        }
+    }
+
+    // Mock generator with a fixed secret
+    struct MockTokenGenerator;
+
+    impl OpaqueTokenGenerator for MockTokenGenerator {
+        fn generate(&self) -> String {
+            "reset_secret".to_string()
+        }
+
+        fn hash(&self, token: &str) -> String {
+            format!("hashed_{}", token)
+        }
+    }
+
+    // Mailer collecting sent messages
+    #[derive(Default)]
+    struct MockMailer {
+        sent: RefCell<Vec<EmailMessage>>,
+    }
+
+    impl Mailer for MockMailer {
+        fn send(&self, message: &EmailMessage) -> Result<(), AuthError> {
+            self.sent.borrow_mut().push(message.clone());
+            Ok(())
+        }
+    }
+
+    fn create_repo(active: bool) -> MockUserRepository {
+        let email = Email::new("test@example.com").unwrap();
+        let mut user = User::new(email, HashedPassword::from_hash("hashed".to_string()), None);
+        if !active {
+            user.deactivate();
+        }
+        MockUserRepository::new(user)
+    }
+
+    fn request(
+        repo: &MockUserRepository,
+        tokens: &MockResetTokenRepository,
+        mailer: &MockMailer,
+        email: &str,
+    ) -> Result<(), AuthError> {
+        RequestPasswordResetUseCase::new(
+            repo,
+            tokens,
+            &MockTokenGenerator,
+            mailer,
+            Duration::minutes(30),
+            "https://example.com/reset-password",
+        )
+        .execute(RequestPasswordResetCommand {
+            email: email.to_string(),
+        })
+    }
+
+    #[test]
+    fn test_mails_reset_link() {
+        let repo = create_repo(true);
+        let tokens = MockResetTokenRepository::default();
+        let mailer = MockMailer::default();
+
+        request(&repo, &tokens, &mailer, "Test@Example.com").unwrap();
+
+        // Only the hash is stored, the secret goes out by mail
+        let created = tokens.created.borrow();
+        assert_eq!(created.len(), 1);
+        assert_eq!(created[0].token_hash(), "hashed_reset_secret");
+        assert_eq!(created[0].user_id(), repo.user_id());
+
+        let sent = mailer.sent.borrow();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].to, "test@example.com");
+        assert!(sent[0]
+            .body
+            .contains("https://example.com/reset-password?token=reset_secret"));
+    }
+
+    #[test]
+    fn test_unknown_email_is_silent() {
+        let repo = create_repo(true);
+        let tokens = MockResetTokenRepository::default();
+        let mailer = MockMailer::default();
+
+        request(&repo, &tokens, &mailer, "other@example.com").unwrap();
+
+        assert!(tokens.created.borrow().is_empty());
+        assert!(mailer.sent.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_inactive_account_is_silent() {
+        let repo = create_repo(false);
+        let tokens = MockResetTokenRepository::default();
+        let mailer = MockMailer::default();
+
+        request(&repo, &tokens, &mailer, "test@example.com").unwrap();
+
+        assert!(tokens.created.borrow().is_empty());
+        assert!(mailer.sent.borrow().is_empty());
+    }
+}
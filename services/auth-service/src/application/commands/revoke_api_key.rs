@@ -0,0 +1,210 @@
+//! Revoke API key use case
+//!
+//! Owners revoke their own keys, e.g. when a kiosk is retired or a key
+//! leaks; staff with the `users:manage` permission may revoke anyone's.
+
+use tracing::info;
+use uuid::Uuid;
+
+use crate::domain::api_key::ApiKeyRepository;
+use crate::domain::auth::TokenService;
+use crate::domain::error::AuthError;
+use crate::domain::role::{authorize, Permission};
+
+/// Input for revoking an API key
+#[derive(Debug)]
+pub struct RevokeApiKeyCommand {
+    /// Access token of the caller
+    pub token: String,
+    pub key_id: Uuid,
+}
+
+/// Use case for revoking an API key
+pub struct RevokeApiKeyUseCase<'a, K: ?Sized, T: ?Sized> {
+    api_key_repository: &'a K,
+    token_service: &'a T,
+}
+
+impl<'a, K, T> RevokeApiKeyUseCase<'a, K, T>
+where
+    K: ApiKeyRepository + ?Sized,
+    T: TokenService + ?Sized,
+{
+    /// Create a new use case instance
+    pub fn new(api_key_repository: &'a K, token_service: &'a T) -> Self {
+        Self {
+            api_key_repository,
+            token_service,
+        }
+    }
+
+    /// Execute the revocation
+    ///
+    /// Revoking an already revoked key succeeds.
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidToken` / `AuthError::TokenExpired` if the token is invalid
+    /// - `AuthError::Forbidden` if the caller authenticated with an API key or as a service
+    /// - `AuthError::ApiKeyNotFound` if the key doesn't exist or belongs to
+    ///   someone else and the caller may not manage users
+    /// - `AuthError::Internal` on infrastructure failures
+    pub fn execute(&self, command: RevokeApiKeyCommand) -> Result<(), AuthError> {
+        let actor = self.token_service.validate_token(&command.token)?;
+        if !actor.is_session() {
+            return Err(AuthError::Forbidden);
+        }
+
+        let key = self.api_key_repository.find_by_id(command.key_id)?;
+        // Other users' keys are hidden rather than forbidden
+        if key.user_id() != actor.user_id && authorize(&actor, Permission::ManageUsers).is_err() {
+            return Err(AuthError::ApiKeyNotFound);
+        }
+
+        if self.api_key_repository.revoke(key.id())? {
+            info!(
+                key_id = %key.id(),
+                user_id = %key.user_id(),
+                actor_id = %actor.user_id,
+                "API key revoked"
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use chrono::{DateTime, Utc};
+
+    use super::*;
+    use crate::application::commands::test_support::token_data;
+    use crate::domain::api_key::ApiKey;
+    use crate::domain::auth::TokenData;
+    use crate::domain::role::Role;
+    use crate::domain::user::User;
+
+    // Key store holding one key and recording revocations
+    struct MockApiKeyRepository {
+        key: ApiKey,
+        revoked: RefCell<Vec<Uuid>>,
+    }
+
+    impl ApiKeyRepository for MockApiKeyRepository {
+        fn create(&self, _key: &ApiKey) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn find_by_hash(&self, _key_hash: &str) -> Result<ApiKey, AuthError> {
+            Err(AuthError::InvalidToken)
+        }
+
+        fn find_by_id(&self, id: Uuid) -> Result<ApiKey, AuthError> {
+            if self.key.id() == id {
+                Ok(self.key.clone())
+            } else {
+                Err(AuthError::ApiKeyNotFound)
+            }
+        }
+
+        fn list_for_user(&self, _user_id: Uuid) -> Result<Vec<ApiKey>, AuthError> {
+            Ok(vec![self.key.clone()])
+        }
+
+        fn revoke(&self, id: Uuid) -> Result<bool, AuthError> {
+            self.revoked.borrow_mut().push(id);
+            Ok(true)
+        }
+
+        fn record_use(&self, _id: Uuid, _at: DateTime<Utc>) -> Result<(), AuthError> {
+            Ok(())
+        }
+    }
+
+    // Token service mapping "owner", "admin" and "other" tokens to callers
+    struct MockTokenService {
+        owner_id: Uuid,
+    }
+
+    impl TokenService for MockTokenService {
+        fn create_token(
+            &self,
+            _user: &User,
+            _session_id: Option<Uuid>,
+        ) -> Result<String, AuthError> {
+            Ok("token".to_string())
+        }
+
+        fn validate_token(&self, token: &str) -> Result<TokenData, AuthError> {
+            let (user_id, roles) = match token {
+                "owner" => (self.owner_id, vec![Role::Customer]),
+                "admin" => (Uuid::new_v4(), vec![Role::Admin]),
+                "other" => (Uuid::new_v4(), vec![Role::Customer]),
+                _ => return Err(AuthError::InvalidToken),
+            };
+            Ok(TokenData {
+                roles,
+                ..token_data(user_id)
+            })
+        }
+
+        fn revoke_token(&self, _token: &TokenData) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn is_revoked(&self, _token: &TokenData) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+    }
+
+    fn setup() -> (MockApiKeyRepository, MockTokenService) {
+        let owner_id = Uuid::new_v4();
+        let key = ApiKey::issue(
+            Uuid::new_v4(),
+            owner_id,
+            "Kiosk".to_string(),
+            "hash".to_string(),
+            vec![Permission::PurchaseTickets],
+            None,
+        );
+        (
+            MockApiKeyRepository {
+                key,
+                revoked: RefCell::new(Vec::new()),
+            },
+            MockTokenService { owner_id },
+        )
+    }
+
+    fn revoke(
+        keys: &MockApiKeyRepository,
+        token_service: &MockTokenService,
+        token: &str,
+    ) -> Result<(), AuthError> {
+        RevokeApiKeyUseCase::new(keys, token_service).execute(RevokeApiKeyCommand {
+            token: token.to_string(),
+            key_id: keys.key.id(),
+        })
+    }
+
+    #[test]
+    fn test_owner_and_admin_revoke_key() {
+        let (keys, token_service) = setup();
+
+        revoke(&keys, &token_service, "owner").unwrap();
+        revoke(&keys, &token_service, "admin").unwrap();
+
+        assert_eq!(*keys.revoked.borrow(), vec![keys.key.id(); 2]);
+    }
+
+    #[test]
+    fn test_other_users_key_not_found() {
+        let (keys, token_service) = setup();
+
+        let result = revoke(&keys, &token_service, "other");
+
+        assert!(matches!(result, Err(AuthError::ApiKeyNotFound)));
+        assert!(keys.revoked.borrow().is_empty());
+    }
+}
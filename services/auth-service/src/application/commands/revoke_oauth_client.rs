@@ -0,0 +1,81 @@
+//! Revoke OAuth client use case
+//!
+//! Stops a client from obtaining new tokens, e.g. when a service is retired
+//! or its secret leaks. Tokens already issued stay valid until they expire.
+
+use tracing::info;
+use uuid::Uuid;
+
+use crate::domain::audit::{AuditAction, AuditEvent, AuditLog};
+use crate::domain::auth::{TokenData, TokenService};
+use crate::domain::error::AuthError;
+use crate::domain::oauth_client::OAuthClientRepository;
+use crate::domain::role::{authorize, Permission};
+
+/// Input for revoking an OAuth client
+#[derive(Debug)]
+pub struct RevokeOAuthClientCommand {
+    /// Access token of the acting administrator
+    pub token: String,
+    pub client_id: Uuid,
+}
+
+/// Use case for revoking an OAuth client
+pub struct RevokeOAuthClientUseCase<'a, C: ?Sized, T: ?Sized, L: ?Sized> {
+    client_repository: &'a C,
+    token_service: &'a T,
+    audit_log: &'a L,
+}
+
+impl<'a, C, T, L> RevokeOAuthClientUseCase<'a, C, T, L>
+where
+    C: OAuthClientRepository + ?Sized,
+    T: TokenService + ?Sized,
+    L: AuditLog + ?Sized,
+{
+    /// Create a new use case instance
+    pub fn new(client_repository: &'a C, token_service: &'a T, audit_log: &'a L) -> Self {
+        Self {
+            client_repository,
+            token_service,
+            audit_log,
+        }
+    }
+
+    /// Execute the revocation
+    ///
+    /// Revoking an already revoked client succeeds.
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidToken` / `AuthError::TokenExpired` if the token is invalid
+    /// - `AuthError::Forbidden` if the caller may not manage clients
+    /// - `AuthError::OAuthClientNotFound` if the client doesn't exist
+    /// - `AuthError::Internal` on infrastructure failures
+    pub fn execute(&self, command: RevokeOAuthClientCommand) -> Result<(), AuthError> {
+        let actor = self.token_service.validate_token(&command.token)?;
+        let client_id = command.client_id;
+
+        let result = self.revoke(&actor, client_id);
+        self.audit_log.record(
+            AuditEvent::new(AuditAction::OAuthClientRevocation)
+                .with_actor(actor.audit_actor())
+                .with_detail(client_id.to_string())
+                .with_result(&result),
+        );
+        result
+    }
+
+    fn revoke(&self, actor: &TokenData, client_id: Uuid) -> Result<(), AuthError> {
+        authorize(actor, Permission::ManageClients)?;
+
+        let client = self.client_repository.find_by_id(client_id)?;
+        if self.client_repository.revoke(client.client_id())? {
+            info!(
+                client_id = %client.client_id(),
+                actor_id = %actor.user_id,
+                "OAuth client revoked"
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,240 @@
+//! Revoke other sessions use case
+//!
+//! Logs a user out everywhere except on the device they are using, e.g.
+//! after a lost phone or a password scare.
+
+use chrono::Utc;
+use tracing::info;
+
+use crate::domain::auth::TokenService;
+use crate::domain::error::AuthError;
+use crate::domain::refresh_token::RefreshTokenRepository;
+use crate::domain::session::SessionRepository;
+
+/// Use case for revoking all of the caller's sessions but the current one
+pub struct RevokeOtherSessionsUseCase<'a, D: ?Sized, S: ?Sized, T: ?Sized> {
+    session_repository: &'a D,
+    refresh_token_repository: &'a S,
+    token_service: &'a T,
+}
+
+impl<'a, D, S, T> RevokeOtherSessionsUseCase<'a, D, S, T>
+where
+    D: SessionRepository + ?Sized,
+    S: RefreshTokenRepository + ?Sized,
+    T: TokenService + ?Sized,
+{
+    /// Create a new use case instance
+    pub fn new(
+        session_repository: &'a D,
+        refresh_token_repository: &'a S,
+        token_service: &'a T,
+    ) -> Self {
+        Self {
+            session_repository,
+            refresh_token_repository,
+            token_service,
+        }
+    }
+
+    /// Execute the revocation, returning the number of sessions ended
+    ///
+    /// A token issued before sessions were tracked belongs to none of them,
+    /// so every session is ended.
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidToken` / `AuthError::TokenExpired` if the token is invalid
+    /// - `AuthError::Forbidden` if the caller authenticated with an API key or as a service
+    /// - `AuthError::Internal` on infrastructure failures
+    pub fn execute(&self, token: &str) -> Result<usize, AuthError> {
+        let actor = self.token_service.validate_token(token)?;
+        if !actor.is_session() {
+            return Err(AuthError::Forbidden);
+        }
+
+        let others: Vec<_> = self
+            .session_repository
+            .find_active_for_user(actor.user_id, Utc::now())?
+            .into_iter()
+            .filter(|session| Some(session.id()) != actor.session_id)
+            .collect();
+
+        for session in &others {
+            self.refresh_token_repository.revoke_family(session.id())?;
+        }
+
+        info!(
+            user_id = %actor.user_id,
+            revoked = others.len(),
+            "Other sessions revoked"
+        );
+        Ok(others.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use chrono::{DateTime, Duration};
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::application::commands::test_support::token_data;
+    use crate::domain::auth::TokenData;
+    use crate::domain::refresh_token::RefreshToken;
+    use crate::domain::session::{Session, SessionClient};
+    use crate::domain::user::User;
+
+    // Session store holding a user's active sessions
+    struct MockSessionRepository {
+        sessions: Vec<Session>,
+    }
+
+    impl SessionRepository for MockSessionRepository {
+        fn create(&self, _session: &Session) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn find_by_id(&self, _id: Uuid) -> Result<Session, AuthError> {
+            Err(AuthError::SessionNotFound)
+        }
+
+        fn find_active_for_user(
+            &self,
+            _user_id: Uuid,
+            _now: DateTime<Utc>,
+        ) -> Result<Vec<Session>, AuthError> {
+            Ok(self.sessions.clone())
+        }
+
+        fn touch(
+            &self,
+            _id: Uuid,
+            _now: DateTime<Utc>,
+            _expires_at: DateTime<Utc>,
+        ) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn set_organization(
+            &self,
+            _id: Uuid,
+            _organization_id: Option<Uuid>,
+        ) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn is_revoked(&self, _id: Uuid) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+    }
+
+    // Refresh token store recording revoked families
+    #[derive(Default)]
+    struct MockRefreshTokenRepository {
+        revoked_families: RefCell<Vec<Uuid>>,
+    }
+
+    impl RefreshTokenRepository for MockRefreshTokenRepository {
+        fn create(&self, _token: &RefreshToken) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn find_by_hash(&self, _token_hash: &str) -> Result<RefreshToken, AuthError> {
+            Err(AuthError::InvalidToken)
+        }
+
+        fn rotate(&self, _current_id: Uuid, _next: &RefreshToken) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+
+        fn revoke_family(&self, family_id: Uuid) -> Result<(), AuthError> {
+            self.revoked_families.borrow_mut().push(family_id);
+            Ok(())
+        }
+
+        fn revoke_all_for_user(&self, _user_id: Uuid) -> Result<(), AuthError> {
+            Ok(())
+        }
+    }
+
+    // Token service whose token belongs to a fixed session
+    struct MockTokenService {
+        session_id: Option<Uuid>,
+    }
+
+    impl TokenService for MockTokenService {
+        fn create_token(
+            &self,
+            _user: &User,
+            _session_id: Option<Uuid>,
+        ) -> Result<String, AuthError> {
+            Ok("token".to_string())
+        }
+
+        fn validate_token(&self, _token: &str) -> Result<TokenData, AuthError> {
+            Ok(TokenData {
+                session_id: self.session_id,
+                ..token_data(Uuid::new_v4())
+            })
+        }
+
+        fn revoke_token(&self, _token: &TokenData) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn is_revoked(&self, _token: &TokenData) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+    }
+
+    fn create_repo() -> MockSessionRepository {
+        let user_id = Uuid::new_v4();
+        let expires_at = Utc::now() + Duration::days(30);
+        MockSessionRepository {
+            sessions: (0..3)
+                .map(|_| {
+                    Session::start(
+                        Uuid::new_v4(),
+                        user_id,
+                        &SessionClient::default(),
+                        expires_at,
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_keeps_current_session() {
+        let sessions = create_repo();
+        let tokens = MockRefreshTokenRepository::default();
+        let current = sessions.sessions[1].id();
+        let token_service = MockTokenService {
+            session_id: Some(current),
+        };
+
+        let revoked = RevokeOtherSessionsUseCase::new(&sessions, &tokens, &token_service)
+            .execute("token")
+            .unwrap();
+
+        assert_eq!(revoked, 2);
+        let revoked_families = tokens.revoked_families.borrow();
+        assert_eq!(revoked_families.len(), 2);
+        assert!(!revoked_families.contains(&current));
+    }
+
+    #[test]
+    fn test_token_without_session_revokes_all() {
+        let sessions = create_repo();
+        let tokens = MockRefreshTokenRepository::default();
+        let token_service = MockTokenService { session_id: None };
+
+        let revoked = RevokeOtherSessionsUseCase::new(&sessions, &tokens, &token_service)
+            .execute("token")
+            .unwrap();
+
+        assert_eq!(revoked, 3);
+    }
+}
@@ -0,0 +1,239 @@
+//! Revoke role use case
+//!
+//! Removes a role from a user. Only callers whose token carries the
+//! `roles:manage` permission may do so, and administrators cannot drop
+//! their own admin role.
+
+use uuid::Uuid;
+
+use crate::domain::audit::{AuditAction, AuditEvent, AuditLog};
+use crate::domain::auth::{TokenData, TokenService, UserRepository};
+use crate::domain::error::AuthError;
+use crate::domain::role::{authorize, Permission, Role};
+
+/// Input for revoking a role
+#[derive(Debug)]
+pub struct RevokeRoleCommand {
+    /// Access token of the acting administrator
+    pub token: String,
+    pub user_id: Uuid,
+    pub role: String,
+}
+
+/// Output after a successful revocation
+#[derive(Debug)]
+pub struct RevokeRoleResult {
+    pub user_id: Uuid,
+    pub roles: Vec<Role>,
+}
+
+/// Use case for revoking a role
+pub struct RevokeRoleUseCase<'a, R: ?Sized, T: ?Sized, L: ?Sized> {
+    user_repository: &'a R,
+    token_service: &'a T,
+    audit_log: &'a L,
+}
+
+impl<'a, R, T, L> RevokeRoleUseCase<'a, R, T, L>
+where
+    R: UserRepository + ?Sized,
+    T: TokenService + ?Sized,
+    L: AuditLog + ?Sized,
+{
+    /// Create a new use case instance
+    pub fn new(user_repository: &'a R, token_service: &'a T, audit_log: &'a L) -> Self {
+        Self {
+            user_repository,
+            token_service,
+            audit_log,
+        }
+    }
+
+    /// Execute the revocation
+    ///
+    /// Revoking a role the user does not hold is a no-op. Tokens issued
+    /// before the revocation keep their roles until they expire.
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidToken` / `AuthError::TokenExpired` if the token is invalid
+    /// - `AuthError::Forbidden` if the caller may not manage roles, or tries to
+    ///   revoke their own admin role
+    /// - `AuthError::InvalidRole` if the role is unknown
+    /// - `AuthError::UserNotFound` if the target user does not exist
+    /// - `AuthError::Internal` on infrastructure failures
+    pub fn execute(&self, command: RevokeRoleCommand) -> Result<RevokeRoleResult, AuthError> {
+        let actor = self.token_service.validate_token(&command.token)?;
+        let user_id = command.user_id;
+        let role = command.role.clone();
+
+        let result = self.revoke(&actor, command);
+        self.audit_log.record(
+            AuditEvent::new(AuditAction::RoleRevocation)
+                .with_actor(actor.audit_actor())
+                .with_target(user_id)
+                .with_detail(role)
+                .with_result(&result),
+        );
+        result
+    }
+
+    fn revoke(
+        &self,
+        actor: &TokenData,
+        command: RevokeRoleCommand,
+    ) -> Result<RevokeRoleResult, AuthError> {
+        authorize(actor, Permission::ManageRoles)?;
+
+        let role: Role = command.role.parse()?;
+
+        // Prevent administrators from locking themselves out
+        if role == Role::Admin && actor.user_id == command.user_id {
+            return Err(AuthError::Forbidden);
+        }
+
+        let mut user = self.user_repository.find_by_id(command.user_id)?;
+
+        if user.revoke_role(role) {
+            user = self.user_repository.update(&user)?;
+        }
+
+        Ok(RevokeRoleResult {
+            user_id: user.id().as_uuid(),
+            roles: user.roles().to_vec(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+    use crate::application::commands::test_support::{token_data, MockAuditLog};
+    use crate::domain::user::{Email, HashedPassword, User};
+
+    // Mock repository holding a single admin user
+    struct MockUserRepository {
+        user: RefCell<User>,
+    }
+
+    impl UserRepository for MockUserRepository {
+        fn find_by_id(&self, id: Uuid) -> Result<User, AuthError> {
+            let user = self.user.borrow();
+            if user.id().as_uuid() == id {
+                Ok(user.clone())
+            } else {
+                Err(AuthError::UserNotFound)
+            }
+        }
+
+        fn find_by_email(&self, _email: &str) -> Result<User, AuthError> {
+            Err(AuthError::UserNotFound)
+        }
+
+        fn exists_by_email(&self, _email: &str) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+
+        fn create(&self, _user: &User) -> Result<User, AuthError> {
+            Err(AuthError::Internal("Not implemented".to_string()))
+        }
+
+        fn update(&self, user: &User) -> Result<User, AuthError> {
+            *self.user.borrow_mut() = user.clone();
+            Ok(user.clone())
+        }
+    }
+
+    // Token service issuing "admin" tokens for a fixed actor
+    struct MockTokenService {
+        actor_id: Uuid,
+    }
+
+    impl TokenService for MockTokenService {
+        fn create_token(
+            &self,
+            _user: &User,
+            _session_id: Option<Uuid>,
+        ) -> Result<String, AuthError> {
+            Ok("token".to_string())
+        }
+
+        fn validate_token(&self, token: &str) -> Result<TokenData, AuthError> {
+            let roles = match token {
+                "admin" => vec![Role::Admin],
+                "customer" => vec![Role::Customer],
+                _ => return Err(AuthError::InvalidToken),
+            };
+            Ok(TokenData {
+                roles,
+                ..token_data(self.actor_id)
+            })
+        }
+
+        fn revoke_token(&self, _token: &TokenData) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn is_revoked(&self, _token: &TokenData) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+    }
+
+    fn create_repo() -> MockUserRepository {
+        let email = Email::new("test@example.com").unwrap();
+        let password = HashedPassword::from_hash("hashed".to_string());
+        let mut user = User::new(email, password, None);
+        user.assign_role(Role::Admin);
+        MockUserRepository {
+            user: RefCell::new(user),
+        }
+    }
+
+    fn revoke(
+        repo: &MockUserRepository,
+        actor_id: Uuid,
+        token: &str,
+        role: &str,
+    ) -> Result<RevokeRoleResult, AuthError> {
+        let user_id = repo.user.borrow().id().as_uuid();
+        RevokeRoleUseCase::new(repo, &MockTokenService { actor_id }, &MockAuditLog::default()).execute(
+            RevokeRoleCommand {
+                token: token.to_string(),
+                user_id,
+                role: role.to_string(),
+            },
+        )
+    }
+
+    #[test]
+    fn test_admin_revokes_role() {
+        let repo = create_repo();
+
+        let result = revoke(&repo, Uuid::new_v4(), "admin", "admin").unwrap();
+
+        assert_eq!(result.roles, vec![Role::Customer]);
+        assert!(!repo.user.borrow().has_role(Role::Admin));
+    }
+
+    #[test]
+    fn test_non_admin_forbidden() {
+        let repo = create_repo();
+
+        let result = revoke(&repo, Uuid::new_v4(), "customer", "admin");
+
+        assert!(matches!(result, Err(AuthError::Forbidden)));
+        assert!(repo.user.borrow().has_role(Role::Admin));
+    }
+
+    #[test]
+    fn test_cannot_revoke_own_admin_role() {
+        let repo = create_repo();
+        let own_id = repo.user.borrow().id().as_uuid();
+
+        let result = revoke(&repo, own_id, "admin", "admin");
+
+        assert!(matches!(result, Err(AuthError::Forbidden)));
+        assert!(repo.user.borrow().has_role(Role::Admin));
+    }
+}
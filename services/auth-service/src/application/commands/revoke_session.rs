@@ -0,0 +1,239 @@
+//! Revoke session use case
+//!
+//! Logs a user out of one of their devices: the session's refresh tokens
+//! stop working and its access tokens are rejected from then on.
+
+use tracing::info;
+use uuid::Uuid;
+
+use crate::domain::auth::TokenService;
+use crate::domain::error::AuthError;
+use crate::domain::refresh_token::RefreshTokenRepository;
+use crate::domain::session::SessionRepository;
+
+/// Input for revoking a session
+#[derive(Debug)]
+pub struct RevokeSessionCommand {
+    /// Access token of the caller
+    pub token: String,
+    pub session_id: Uuid,
+}
+
+/// Use case for revoking one of the caller's sessions
+pub struct RevokeSessionUseCase<'a, D: ?Sized, S: ?Sized, T: ?Sized> {
+    session_repository: &'a D,
+    refresh_token_repository: &'a S,
+    token_service: &'a T,
+}
+
+impl<'a, D, S, T> RevokeSessionUseCase<'a, D, S, T>
+where
+    D: SessionRepository + ?Sized,
+    S: RefreshTokenRepository + ?Sized,
+    T: TokenService + ?Sized,
+{
+    /// Create a new use case instance
+    pub fn new(
+        session_repository: &'a D,
+        refresh_token_repository: &'a S,
+        token_service: &'a T,
+    ) -> Self {
+        Self {
+            session_repository,
+            refresh_token_repository,
+            token_service,
+        }
+    }
+
+    /// Execute the revocation
+    ///
+    /// Revoking an already ended session succeeds. The caller may revoke
+    /// the session they are using, which logs them out.
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidToken` / `AuthError::TokenExpired` if the token is invalid
+    /// - `AuthError::Forbidden` if the caller authenticated with an API key or as a service
+    /// - `AuthError::SessionNotFound` if the session doesn't exist or belongs to someone else
+    /// - `AuthError::Internal` on infrastructure failures
+    pub fn execute(&self, command: RevokeSessionCommand) -> Result<(), AuthError> {
+        let actor = self.token_service.validate_token(&command.token)?;
+        if !actor.is_session() {
+            return Err(AuthError::Forbidden);
+        }
+
+        let session = self.session_repository.find_by_id(command.session_id)?;
+        // Other users' sessions are hidden rather than forbidden
+        if session.user_id() != actor.user_id {
+            return Err(AuthError::SessionNotFound);
+        }
+
+        self.refresh_token_repository.revoke_family(session.id())?;
+        info!(
+            session_id = %session.id(),
+            user_id = %session.user_id(),
+            "Session revoked"
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use chrono::{DateTime, Duration, Utc};
+
+    use super::*;
+    use crate::application::commands::test_support::token_data;
+    use crate::domain::auth::TokenData;
+    use crate::domain::refresh_token::RefreshToken;
+    use crate::domain::session::{Session, SessionClient};
+    use crate::domain::user::User;
+
+    // Session store holding one session
+    struct MockSessionRepository {
+        session: Session,
+    }
+
+    impl SessionRepository for MockSessionRepository {
+        fn create(&self, _session: &Session) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn find_by_id(&self, id: Uuid) -> Result<Session, AuthError> {
+            if self.session.id() == id {
+                Ok(self.session.clone())
+            } else {
+                Err(AuthError::SessionNotFound)
+            }
+        }
+
+        fn find_active_for_user(
+            &self,
+            _user_id: Uuid,
+            _now: DateTime<Utc>,
+        ) -> Result<Vec<Session>, AuthError> {
+            Ok(vec![self.session.clone()])
+        }
+
+        fn touch(
+            &self,
+            _id: Uuid,
+            _now: DateTime<Utc>,
+            _expires_at: DateTime<Utc>,
+        ) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn set_organization(
+            &self,
+            _id: Uuid,
+            _organization_id: Option<Uuid>,
+        ) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn is_revoked(&self, _id: Uuid) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+    }
+
+    // Refresh token store recording revoked families
+    #[derive(Default)]
+    struct MockRefreshTokenRepository {
+        revoked_families: RefCell<Vec<Uuid>>,
+    }
+
+    impl RefreshTokenRepository for MockRefreshTokenRepository {
+        fn create(&self, _token: &RefreshToken) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn find_by_hash(&self, _token_hash: &str) -> Result<RefreshToken, AuthError> {
+            Err(AuthError::InvalidToken)
+        }
+
+        fn rotate(&self, _current_id: Uuid, _next: &RefreshToken) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+
+        fn revoke_family(&self, family_id: Uuid) -> Result<(), AuthError> {
+            self.revoked_families.borrow_mut().push(family_id);
+            Ok(())
+        }
+
+        fn revoke_all_for_user(&self, _user_id: Uuid) -> Result<(), AuthError> {
+            Ok(())
+        }
+    }
+
+    // Token service mapping the token string to the caller's user ID
+    struct MockTokenService;
+
+    impl TokenService for MockTokenService {
+        fn create_token(
+            &self,
+            _user: &User,
+            _session_id: Option<Uuid>,
+        ) -> Result<String, AuthError> {
+            Ok("token".to_string())
+        }
+
+        fn validate_token(&self, token: &str) -> Result<TokenData, AuthError> {
+            Ok(token_data(Uuid::parse_str(token).map_err(|_| AuthError::InvalidToken)?))
+        }
+
+        fn revoke_token(&self, _token: &TokenData) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn is_revoked(&self, _token: &TokenData) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+    }
+
+    fn create_repo() -> MockSessionRepository {
+        MockSessionRepository {
+            session: Session::start(
+                Uuid::new_v4(),
+                Uuid::new_v4(),
+                &SessionClient::default(),
+                Utc::now() + Duration::days(30),
+            ),
+        }
+    }
+
+    #[test]
+    fn test_owner_revokes_session() {
+        let sessions = create_repo();
+        let tokens = MockRefreshTokenRepository::default();
+
+        RevokeSessionUseCase::new(&sessions, &tokens, &MockTokenService)
+            .execute(RevokeSessionCommand {
+                token: sessions.session.user_id().to_string(),
+                session_id: sessions.session.id(),
+            })
+            .unwrap();
+
+        assert_eq!(
+            *tokens.revoked_families.borrow(),
+            vec![sessions.session.id()]
+        );
+    }
+
+    #[test]
+    fn test_other_users_session_is_hidden() {
+        let sessions = create_repo();
+        let tokens = MockRefreshTokenRepository::default();
+
+        let result = RevokeSessionUseCase::new(&sessions, &tokens, &MockTokenService).execute(
+            RevokeSessionCommand {
+                token: Uuid::new_v4().to_string(),
+                session_id: sessions.session.id(),
+            },
+        );
+
+        assert!(matches!(result, Err(AuthError::SessionNotFound)));
+        assert!(tokens.revoked_families.borrow().is_empty());
+    }
+}
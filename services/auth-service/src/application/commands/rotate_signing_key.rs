@@ -0,0 +1,228 @@
+//! Rotate signing key use case
+//!
+//! Generates a new JWT signing key and retires the current one. Retired
+//! keys keep verifying tokens for a grace period covering the lifetime of
+//! the tokens they signed.
+
+use chrono::{Duration, Utc};
+use tracing::info;
+
+use crate::domain::error::AuthError;
+use crate::domain::signing_key::{SigningAlgorithm, SigningKeyGenerator, SigningKeyRepository};
+
+/// Input for rotating the signing key
+#[derive(Debug)]
+pub struct RotateSigningKeyCommand {
+    pub algorithm: SigningAlgorithm,
+    /// Rotate even if an active key for `algorithm` already exists
+    pub force: bool,
+}
+
+/// Output after a rotation check
+#[derive(Debug)]
+pub struct RotateSigningKeyResult {
+    /// Identifier of the key now signing tokens
+    pub kid: String,
+    /// Whether a new key was generated
+    pub rotated: bool,
+}
+
+/// Use case for rotating the signing key
+pub struct RotateSigningKeyUseCase<'a, R: ?Sized, G: ?Sized> {
+    signing_key_repository: &'a R,
+    signing_key_generator: &'a G,
+    verification_grace: Duration,
+}
+
+impl<'a, R, G> RotateSigningKeyUseCase<'a, R, G>
+where
+    R: SigningKeyRepository + ?Sized,
+    G: SigningKeyGenerator + ?Sized,
+{
+    /// Create a new use case instance
+    ///
+    /// `verification_grace` must cover the access token lifetime plus the
+    /// time other replicas may keep signing with the retired key.
+    pub fn new(
+        signing_key_repository: &'a R,
+        signing_key_generator: &'a G,
+        verification_grace: Duration,
+    ) -> Self {
+        Self {
+            signing_key_repository,
+            signing_key_generator,
+            verification_grace,
+        }
+    }
+
+    /// Execute the rotation
+    ///
+    /// Without `force`, a new key is only generated when no active key
+    /// exists for the requested algorithm, which makes this safe to run on
+    /// every startup.
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on key generation or storage failures
+    pub fn execute(
+        &self,
+        command: RotateSigningKeyCommand,
+    ) -> Result<RotateSigningKeyResult, AuthError> {
+        let now = Utc::now();
+
+        if !command.force {
+            let current = self
+                .signing_key_repository
+                .find_verifiable(now)?
+                .into_iter()
+                .find(|key| key.is_active() && key.algorithm() == command.algorithm);
+
+            if let Some(current) = current {
+                return Ok(RotateSigningKeyResult {
+                    kid: current.kid().to_string(),
+                    rotated: false,
+                });
+            }
+        }
+
+        let next = self.signing_key_generator.generate(command.algorithm)?;
+        self.signing_key_repository
+            .rotate(&next, now + self.verification_grace)?;
+
+        info!(
+            kid = next.kid(),
+            algorithm = %next.algorithm(),
+            "Rotated JWT signing key"
+        );
+
+        Ok(RotateSigningKeyResult {
+            kid: next.kid().to_string(),
+            rotated: true,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use chrono::DateTime;
+
+    use super::*;
+    use crate::domain::signing_key::SigningKey;
+
+    // In-memory key store mirroring the Diesel semantics
+    #[derive(Default)]
+    struct MockSigningKeyRepository {
+        keys: RefCell<Vec<SigningKey>>,
+    }
+
+    impl SigningKeyRepository for MockSigningKeyRepository {
+        fn find_verifiable(&self, now: DateTime<Utc>) -> Result<Vec<SigningKey>, AuthError> {
+            let mut keys: Vec<SigningKey> = self
+                .keys
+                .borrow()
+                .iter()
+                .filter(|k| k.is_verifiable(now))
+                .cloned()
+                .collect();
+            keys.sort_by_key(|k| std::cmp::Reverse(k.created_at()));
+            Ok(keys)
+        }
+
+        fn rotate(&self, next: &SigningKey, verify_until: DateTime<Utc>) -> Result<(), AuthError> {
+            let mut keys = self.keys.borrow_mut();
+            for key in keys.iter_mut().filter(|k| k.is_active()) {
+                *key = SigningKey::from_persistence(
+                    key.kid().to_string(),
+                    key.algorithm(),
+                    key.private_key_pem().to_string(),
+                    key.public_key_pem().to_string(),
+                    key.created_at(),
+                    Some(Utc::now()),
+                    Some(verify_until),
+                );
+            }
+            keys.push(next.clone());
+            Ok(())
+        }
+
+        fn purge_expired(&self, _now: DateTime<Utc>) -> Result<usize, AuthError> {
+            Ok(0)
+        }
+    }
+
+    // Generator producing sequential key IDs
+    #[derive(Default)]
+    struct MockSigningKeyGenerator {
+        counter: RefCell<u32>,
+    }
+
+    impl SigningKeyGenerator for MockSigningKeyGenerator {
+        fn generate(&self, algorithm: SigningAlgorithm) -> Result<SigningKey, AuthError> {
+            *self.counter.borrow_mut() += 1;
+            Ok(SigningKey::new(
+                format!("kid-{}", self.counter.borrow()),
+                algorithm,
+                "private".to_string(),
+                "public".to_string(),
+            ))
+        }
+    }
+
+    fn rotate(
+        repo: &MockSigningKeyRepository,
+        generator: &MockSigningKeyGenerator,
+        algorithm: SigningAlgorithm,
+        force: bool,
+    ) -> RotateSigningKeyResult {
+        RotateSigningKeyUseCase::new(repo, generator, Duration::hours(1))
+            .execute(RotateSigningKeyCommand { algorithm, force })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_creates_key_only_when_missing() {
+        let repo = MockSigningKeyRepository::default();
+        let generator = MockSigningKeyGenerator::default();
+
+        let first = rotate(&repo, &generator, SigningAlgorithm::EdDsa, false);
+        let second = rotate(&repo, &generator, SigningAlgorithm::EdDsa, false);
+
+        assert!(first.rotated);
+        assert!(!second.rotated);
+        assert_eq!(first.kid, second.kid);
+        assert_eq!(repo.keys.borrow().len(), 1);
+    }
+
+    #[test]
+    fn test_forced_rotation_keeps_old_key_verifiable() {
+        let repo = MockSigningKeyRepository::default();
+        let generator = MockSigningKeyGenerator::default();
+
+        let first = rotate(&repo, &generator, SigningAlgorithm::EdDsa, false);
+        let second = rotate(&repo, &generator, SigningAlgorithm::EdDsa, true);
+
+        assert_ne!(first.kid, second.kid);
+        let keys = repo.find_verifiable(Utc::now()).unwrap();
+        assert_eq!(keys.len(), 2);
+        assert_eq!(keys.iter().filter(|k| k.is_active()).count(), 1);
+
+        // The retired key drops out once the grace period is over
+        let later = repo
+            .find_verifiable(Utc::now() + Duration::hours(2))
+            .unwrap();
+        assert_eq!(later.len(), 1);
+        assert_eq!(later[0].kid(), second.kid);
+    }
+
+    #[test]
+    fn test_algorithm_change_rotates() {
+        let repo = MockSigningKeyRepository::default();
+        let generator = MockSigningKeyGenerator::default();
+
+        rotate(&repo, &generator, SigningAlgorithm::Rs256, false);
+        let result = rotate(&repo, &generator, SigningAlgorithm::EdDsa, false);
+
+        assert!(result.rotated);
+    }
+}
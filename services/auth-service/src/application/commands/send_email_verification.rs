@@ -0,0 +1,266 @@
+//! Send email verification use case
+//!
+//! Mails a fresh verification link to a user whose address is not yet
+//! verified, e.g. because the link sent at registration expired. Like the
+//! password reset request, the outcome does not reveal whether an account
+//! exists.
+
+use chrono::Duration;
+use tracing::{debug, info};
+
+use crate::domain::auth::{OpaqueTokenGenerator, UserRepository};
+use crate::domain::email_verification::{EmailVerificationToken, EmailVerificationTokenRepository};
+use crate::domain::error::AuthError;
+use crate::domain::mailer::{EmailMessage, Mailer};
+use crate::domain::user::{Email, User};
+
+/// Input for (re)sending a verification link
+#[derive(Debug)]
+pub struct SendEmailVerificationCommand {
+    pub email: String,
+}
+
+/// Use case for (re)sending a verification link
+pub struct SendEmailVerificationUseCase<'a, R: ?Sized, V: ?Sized, G: ?Sized, M: ?Sized> {
+    user_repository: &'a R,
+    verification_token_repository: &'a V,
+    token_generator: &'a G,
+    mailer: &'a M,
+    verification_token_ttl: Duration,
+    verification_url: &'a str,
+}
+
+impl<'a, R, V, G, M> SendEmailVerificationUseCase<'a, R, V, G, M>
+where
+    R: UserRepository + ?Sized,
+    V: EmailVerificationTokenRepository + ?Sized,
+    G: OpaqueTokenGenerator + ?Sized,
+    M: Mailer + ?Sized,
+{
+    /// Create a new use case instance
+    ///
+    /// `verification_url` is the page that completes verification; the
+    /// secret is appended as a `token` query parameter.
+    pub fn new(
+        user_repository: &'a R,
+        verification_token_repository: &'a V,
+        token_generator: &'a G,
+        mailer: &'a M,
+        verification_token_ttl: Duration,
+        verification_url: &'a str,
+    ) -> Self {
+        Self {
+            user_repository,
+            verification_token_repository,
+            token_generator,
+            mailer,
+            verification_token_ttl,
+            verification_url,
+        }
+    }
+
+    /// Execute the request
+    ///
+    /// Succeeds without sending anything for unknown, inactive or already
+    /// verified accounts.
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidEmail` if email format is invalid
+    /// - `AuthError::Internal` on infrastructure failures
+    pub fn execute(&self, command: SendEmailVerificationCommand) -> Result<(), AuthError> {
+        let email = Email::new(&command.email)?;
+
+        let user = match self.user_repository.find_by_email(email.as_str()) {
+            Ok(user) if user.is_active() && !user.is_email_verified() => user,
+            Ok(_) | Err(AuthError::UserNotFound) => {
+                debug!("Verification requested for unknown, inactive or verified account");
+                return Ok(());
+            }
+            Err(e) => return Err(e),
+        };
+
+        send_verification_email(
+            &user,
+            self.verification_token_repository,
+            self.token_generator,
+            self.mailer,
+            self.verification_token_ttl,
+            self.verification_url,
+        )
+    }
+}
+
+/// Issue a verification token for the user's current address and mail it
+///
+/// # Errors
+/// Returns `AuthError::Internal` if the token cannot be stored or the mail
+/// cannot be sent
+pub(crate) fn send_verification_email<V, G, M>(
+    user: &User,
+    verification_token_repository: &V,
+    token_generator: &G,
+    mailer: &M,
+    ttl: Duration,
+    verification_url: &str,
+) -> Result<(), AuthError>
+where
+    V: EmailVerificationTokenRepository + ?Sized,
+    G: OpaqueTokenGenerator + ?Sized,
+    M: Mailer + ?Sized,
+{
+    let (token, message) = verification_email(user, token_generator, ttl, verification_url);
+    verification_token_repository.create(&token)?;
+    mailer.send(&message)?;
+
+    info!(user_id = %user.id().as_uuid(), "Verification email sent");
+    Ok(())
+}
+
+/// Issue a verification token for the user's current address, with the
+/// email carrying its link
+pub(crate) fn verification_email<G>(
+    user: &User,
+    token_generator: &G,
+    ttl: Duration,
+    verification_url: &str,
+) -> (EmailVerificationToken, EmailMessage)
+where
+    G: OpaqueTokenGenerator + ?Sized,
+{
+    let secret = token_generator.generate();
+    let token = EmailVerificationToken::issue(
+        user.id().as_uuid(),
+        user.email().as_str().to_string(),
+        token_generator.hash(&secret),
+        ttl,
+    );
+
+    let message = EmailMessage {
+        to: user.email().as_str().to_string(),
+        subject: "Verify your email address".to_string(),
+        body: format!(
+            "Please confirm this is your email address by following this link \
+             within {} hours:\n\n\
+             {}?token={}\n\n\
+             If you did not create an account, you can ignore this email.\n",
+            ttl.num_hours(),
+            verification_url,
+            secret
+        ),
+    };
+    (token, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use chrono::{DateTime, Utc};
+
+    use super::*;
+    use crate::application::commands::test_support::MockUserRepository;
+    use crate::domain::user::HashedPassword;
+
+    // Verification token store recording issued tokens
+    #[derive(Default)]
+    struct MockVerificationTokenRepository {
+        created: RefCell<Vec<EmailVerificationToken>>,
+    }
+
+    impl EmailVerificationTokenRepository for MockVerificationTokenRepository {
+        fn create(&self, token: &EmailVerificationToken) -> Result<(), AuthError> {
+            self.created.borrow_mut().push(token.clone());
+            Ok(())
+        }
+
+        fn find_by_hash(&self, _token_hash: &str) -> Result<EmailVerificationToken, AuthError> {
+            Err(AuthError::InvalidToken)
+        }
+
+        fn consume(&self, _token: &EmailVerificationToken) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+
+        fn purge_expired(&self, _now: DateTime<Utc>) -> Result<usize, AuthError> {
+            Ok(0)
+        }
+    }
+
+    // Mock generator with a fixed secret
+    struct MockTokenGenerator;
+
+    impl OpaqueTokenGenerator for MockTokenGenerator {
+        fn generate(&self) -> String {
+            "verify_secret".to_string()
+        }
+
+        fn hash(&self, token: &str) -> String {
+            format!("hashed_{}", token)
+        }
+    }
+
+    // Mailer collecting sent messages
+    #[derive(Default)]
+    struct MockMailer {
+        sent: RefCell<Vec<EmailMessage>>,
+    }
+
+    impl Mailer for MockMailer {
+        fn send(&self, message: &EmailMessage) -> Result<(), AuthError> {
+            self.sent.borrow_mut().push(message.clone());
+            Ok(())
+        }
+    }
+
+    fn send(user: User, mailer: &MockMailer) -> MockVerificationTokenRepository {
+        let repo = MockUserRepository::new(user);
+        let tokens = MockVerificationTokenRepository::default();
+        SendEmailVerificationUseCase::new(
+            &repo,
+            &tokens,
+            &MockTokenGenerator,
+            mailer,
+            Duration::hours(24),
+            "https://example.com/verify-email",
+        )
+        .execute(SendEmailVerificationCommand {
+            email: "test@example.com".to_string(),
+        })
+        .unwrap();
+        tokens
+    }
+
+    fn create_user() -> User {
+        let email = Email::new("test@example.com").unwrap();
+        User::new(email, HashedPassword::from_hash("hashed".to_string()), None)
+    }
+
+    #[test]
+    fn test_mails_verification_link() {
+        let mailer = MockMailer::default();
+
+        let tokens = send(create_user(), &mailer);
+
+        let created = tokens.created.borrow();
+        assert_eq!(created.len(), 1);
+        assert_eq!(created[0].email(), "test@example.com");
+        assert_eq!(created[0].token_hash(), "hashed_verify_secret");
+        let sent = mailer.sent.borrow();
+        assert_eq!(sent.len(), 1);
+        assert!(sent[0]
+            .body
+            .contains("https://example.com/verify-email?token=verify_secret"));
+    }
+
+    #[test]
+    fn test_verified_account_is_silent() {
+        let mailer = MockMailer::default();
+        let mut user = create_user();
+        user.verify_email();
+
+        let tokens = send(user, &mailer);
+
+        assert!(tokens.created.borrow().is_empty());
+        assert!(mailer.sent.borrow().is_empty());
+    }
+}
@@ -0,0 +1,290 @@
+//! Set password use case
+//!
+//! Lets an administrator replace a user's password without knowing the
+//! current one, for example to hand over a support account. Only callers
+//! whose token carries the `users:manage` permission may do so. The new
+//! password must meet the password policy, and every session and refresh
+//! token of the user is revoked, signing them out everywhere straight away.
+
+use tracing::info;
+use uuid::Uuid;
+
+use crate::domain::audit::{AuditAction, AuditEvent, AuditLog};
+use crate::domain::auth::{PasswordHasher, TokenData, TokenService, UserRepository};
+use crate::domain::error::AuthError;
+use crate::domain::password_policy::PasswordPolicy;
+use crate::domain::refresh_token::RefreshTokenRepository;
+use crate::domain::role::{authorize, Permission};
+
+/// Input for setting another user's password
+#[derive(Debug)]
+pub struct SetPasswordCommand {
+    /// Access token of the acting administrator
+    pub token: String,
+    pub user_id: Uuid,
+    pub new_password: String,
+}
+
+/// Use case for setting another user's password
+pub struct SetPasswordUseCase<'a, R: ?Sized, H: ?Sized, S: ?Sized, T: ?Sized, L: ?Sized> {
+    user_repository: &'a R,
+    password_hasher: &'a H,
+    password_policy: &'a PasswordPolicy,
+    refresh_token_repository: &'a S,
+    token_service: &'a T,
+    audit_log: &'a L,
+}
+
+impl<'a, R, H, S, T, L> SetPasswordUseCase<'a, R, H, S, T, L>
+where
+    R: UserRepository + ?Sized,
+    H: PasswordHasher + ?Sized,
+    S: RefreshTokenRepository + ?Sized,
+    T: TokenService + ?Sized,
+    L: AuditLog + ?Sized,
+{
+    /// Create a new use case instance
+    pub fn new(
+        user_repository: &'a R,
+        password_hasher: &'a H,
+        password_policy: &'a PasswordPolicy,
+        refresh_token_repository: &'a S,
+        token_service: &'a T,
+        audit_log: &'a L,
+    ) -> Self {
+        Self {
+            user_repository,
+            password_hasher,
+            password_policy,
+            refresh_token_repository,
+            token_service,
+            audit_log,
+        }
+    }
+
+    /// Execute the change
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidToken` / `AuthError::TokenExpired` if the token is invalid
+    /// - `AuthError::Forbidden` if the caller may not manage users
+    /// - `AuthError::UserNotFound` if the target user does not exist or was erased
+    /// - `AuthError::WeakPassword` if the new password breaks the password policy
+    /// - `AuthError::Internal` on infrastructure failures
+    pub fn execute(&self, command: SetPasswordCommand) -> Result<(), AuthError> {
+        let actor = self.token_service.validate_token(&command.token)?;
+        let user_id = command.user_id;
+
+        let result = self.set(&actor, command);
+        self.audit_log.record(
+            AuditEvent::new(AuditAction::PasswordSet)
+                .with_actor(actor.audit_actor())
+                .with_target(user_id)
+                .with_result(&result),
+        );
+        result
+    }
+
+    fn set(&self, actor: &TokenData, command: SetPasswordCommand) -> Result<(), AuthError> {
+        authorize(actor, Permission::ManageUsers)?;
+
+        let mut user = self.user_repository.find_by_id(command.user_id)?;
+        // Erased accounts are gone for good
+        if user.is_erased() {
+            return Err(AuthError::UserNotFound);
+        }
+
+        self.password_policy
+            .validate(&command.new_password, user.email().as_str())?;
+
+        let hashed_password = self.password_hasher.hash(&command.new_password)?;
+        user.change_password(hashed_password);
+        self.user_repository.update(&user)?;
+
+        // Sign the user out everywhere
+        self.refresh_token_repository
+            .revoke_all_for_user(command.user_id)?;
+
+        info!(
+            user_id = %command.user_id,
+            actor_id = %actor.user_id,
+            "Password set by administrator"
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+    use crate::application::commands::test_support::{
+        token_data, MockAuditLog, MockPasswordHasher, MockUserRepository,
+    };
+    use crate::domain::audit::AuditOutcome;
+    use crate::domain::refresh_token::RefreshToken;
+    use crate::domain::role::Role;
+    use crate::domain::user::{Email, HashedPassword, User};
+
+    // Token service accepting any token for an actor with the given roles
+    struct MockTokenService {
+        roles: Vec<Role>,
+    }
+
+    impl TokenService for MockTokenService {
+        fn create_token(
+            &self,
+            _user: &User,
+            _session_id: Option<Uuid>,
+        ) -> Result<String, AuthError> {
+            Ok("token".to_string())
+        }
+
+        fn validate_token(&self, _token: &str) -> Result<TokenData, AuthError> {
+            Ok(TokenData {
+                roles: self.roles.clone(),
+                ..token_data(Uuid::new_v4())
+            })
+        }
+
+        fn revoke_token(&self, _token: &TokenData) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn is_revoked(&self, _token: &TokenData) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+    }
+
+    // Refresh token store recording which users were signed out
+    #[derive(Default)]
+    struct MockRefreshTokenRepository {
+        revoked_users: RefCell<Vec<Uuid>>,
+    }
+
+    impl RefreshTokenRepository for MockRefreshTokenRepository {
+        fn create(&self, _token: &RefreshToken) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn find_by_hash(&self, _token_hash: &str) -> Result<RefreshToken, AuthError> {
+            Err(AuthError::InvalidToken)
+        }
+
+        fn rotate(&self, _current_id: Uuid, _next: &RefreshToken) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+
+        fn revoke_family(&self, _family_id: Uuid) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn revoke_all_for_user(&self, user_id: Uuid) -> Result<(), AuthError> {
+            self.revoked_users.borrow_mut().push(user_id);
+            Ok(())
+        }
+    }
+
+    struct Fixture {
+        users: MockUserRepository,
+        refresh_tokens: MockRefreshTokenRepository,
+        audit_log: MockAuditLog,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            let user = User::new(
+                Email::new("test@example.com").unwrap(),
+                HashedPassword::from_hash("hashed_old_password".to_string()),
+                None,
+            );
+            Self {
+                users: MockUserRepository::new(user),
+                refresh_tokens: MockRefreshTokenRepository::default(),
+                audit_log: MockAuditLog::default(),
+            }
+        }
+
+        fn user_id(&self) -> Uuid {
+            self.users.user_id()
+        }
+
+        fn set(&self, roles: Vec<Role>, new_password: &str) -> Result<(), AuthError> {
+            SetPasswordUseCase::new(
+                &self.users,
+                &MockPasswordHasher,
+                &PasswordPolicy::default(),
+                &self.refresh_tokens,
+                &MockTokenService { roles },
+                &self.audit_log,
+            )
+            .execute(SetPasswordCommand {
+                token: "token".to_string(),
+                user_id: self.user_id(),
+                new_password: new_password.to_string(),
+            })
+        }
+    }
+
+    #[test]
+    fn test_admin_sets_password_and_signs_user_out() {
+        let fixture = Fixture::new();
+
+        fixture.set(vec![Role::Admin], "new_password_123").unwrap();
+
+        let user = fixture.users.user.lock().unwrap();
+        assert_eq!(
+            user.hashed_password().unwrap().as_str(),
+            "hashed_new_password_123"
+        );
+        assert_eq!(
+            *fixture.refresh_tokens.revoked_users.borrow(),
+            [user.id().as_uuid()]
+        );
+
+        let events = fixture.audit_log.events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].action(), AuditAction::PasswordSet);
+        assert_eq!(events[0].target_id(), Some(user.id().as_uuid()));
+        assert_eq!(events[0].outcome(), AuditOutcome::Success);
+    }
+
+    #[test]
+    fn test_requires_manage_users() {
+        let fixture = Fixture::new();
+
+        let result = fixture.set(vec![Role::SupportAgent], "new_password_123");
+
+        assert!(matches!(result, Err(AuthError::Forbidden)));
+        let user = fixture.users.user.lock().unwrap();
+        assert_eq!(
+            user.hashed_password().unwrap().as_str(),
+            "hashed_old_password"
+        );
+        assert!(fixture.refresh_tokens.revoked_users.borrow().is_empty());
+        assert_eq!(
+            fixture.audit_log.events.lock().unwrap()[0].outcome(),
+            AuditOutcome::Failure
+        );
+    }
+
+    #[test]
+    fn test_new_password_must_meet_policy() {
+        let fixture = Fixture::new();
+
+        let result = fixture.set(vec![Role::Admin], "short");
+
+        assert!(matches!(result, Err(AuthError::WeakPassword(_))));
+        assert!(fixture.refresh_tokens.revoked_users.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_erased_user_is_not_found() {
+        let fixture = Fixture::new();
+        fixture.users.user.lock().unwrap().erase();
+
+        let result = fixture.set(vec![Role::Admin], "new_password_123");
+
+        assert!(matches!(result, Err(AuthError::UserNotFound)));
+    }
+}
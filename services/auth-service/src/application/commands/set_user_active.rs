@@ -0,0 +1,274 @@
+//! Deactivate / reactivate user use case
+//!
+//! Suspends or restores an account. Only callers whose token carries the
+//! `users:manage` permission may do so. Deactivated users cannot log in or
+//! refresh; their sessions and refresh tokens are revoked straight away, so
+//! access tokens already issued to them stop working too.
+
+use tracing::info;
+use uuid::Uuid;
+
+use crate::domain::audit::{AuditAction, AuditEvent, AuditLog};
+use crate::domain::auth::{TokenData, TokenService, UserRepository};
+use crate::domain::error::AuthError;
+use crate::domain::refresh_token::RefreshTokenRepository;
+use crate::domain::role::{authorize, Permission};
+use crate::domain::user::User;
+
+/// Input for deactivating or reactivating a user
+#[derive(Debug)]
+pub struct SetUserActiveCommand {
+    /// Access token of the acting administrator
+    pub token: String,
+    pub user_id: Uuid,
+    /// `false` to deactivate, `true` to reactivate
+    pub active: bool,
+}
+
+/// Use case for deactivating or reactivating a user
+pub struct SetUserActiveUseCase<'a, R: ?Sized, S: ?Sized, T: ?Sized, L: ?Sized> {
+    user_repository: &'a R,
+    refresh_token_repository: &'a S,
+    token_service: &'a T,
+    audit_log: &'a L,
+}
+
+impl<'a, R, S, T, L> SetUserActiveUseCase<'a, R, S, T, L>
+where
+    R: UserRepository + ?Sized,
+    S: RefreshTokenRepository + ?Sized,
+    T: TokenService + ?Sized,
+    L: AuditLog + ?Sized,
+{
+    /// Create a new use case instance
+    pub fn new(
+        user_repository: &'a R,
+        refresh_token_repository: &'a S,
+        token_service: &'a T,
+        audit_log: &'a L,
+    ) -> Self {
+        Self {
+            user_repository,
+            refresh_token_repository,
+            token_service,
+            audit_log,
+        }
+    }
+
+    /// Execute the change, returning the updated user
+    ///
+    /// Setting the state the user already has is a no-op. Administrators
+    /// cannot deactivate themselves, so an account always remains to undo
+    /// a mistake.
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidToken` / `AuthError::TokenExpired` if the token is invalid
+    /// - `AuthError::Forbidden` if the caller may not manage users or targets themselves
+    /// - `AuthError::UserNotFound` if the target user does not exist or was erased
+    /// - `AuthError::Internal` on infrastructure failures
+    pub fn execute(&self, command: SetUserActiveCommand) -> Result<User, AuthError> {
+        let actor = self.token_service.validate_token(&command.token)?;
+        let user_id = command.user_id;
+        let action = if command.active {
+            AuditAction::UserReactivation
+        } else {
+            AuditAction::UserDeactivation
+        };
+
+        let result = self.set_active(&actor, command);
+        self.audit_log.record(
+            AuditEvent::new(action)
+                .with_actor(actor.audit_actor())
+                .with_target(user_id)
+                .with_result(&result),
+        );
+        result
+    }
+
+    fn set_active(
+        &self,
+        actor: &TokenData,
+        command: SetUserActiveCommand,
+    ) -> Result<User, AuthError> {
+        authorize(actor, Permission::ManageUsers)?;
+
+        if !command.active && actor.user_id == command.user_id {
+            return Err(AuthError::Forbidden);
+        }
+
+        let mut user = self.user_repository.find_by_id(command.user_id)?;
+        // Erased accounts are gone for good
+        if user.is_erased() {
+            return Err(AuthError::UserNotFound);
+        }
+        if user.is_active() == command.active {
+            return Ok(user);
+        }
+
+        if command.active {
+            user.activate();
+        } else {
+            user.deactivate();
+        }
+        let user = self.user_repository.update(&user)?;
+
+        if !command.active {
+            self.refresh_token_repository
+                .revoke_all_for_user(command.user_id)?;
+        }
+
+        info!(
+            user_id = %command.user_id,
+            actor_id = %actor.user_id,
+            active = command.active,
+            "User activation changed"
+        );
+        Ok(user)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+    use crate::application::commands::test_support::{token_data, MockAuditLog, MockUserRepository};
+    use crate::domain::refresh_token::RefreshToken;
+    use crate::domain::role::Role;
+    use crate::domain::user::{Email, HashedPassword};
+
+    // Refresh token store recording which users were signed out
+    #[derive(Default)]
+    struct MockRefreshTokenRepository {
+        revoked_users: RefCell<Vec<Uuid>>,
+    }
+
+    impl RefreshTokenRepository for MockRefreshTokenRepository {
+        fn create(&self, _token: &RefreshToken) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn find_by_hash(&self, _token_hash: &str) -> Result<RefreshToken, AuthError> {
+            Err(AuthError::InvalidToken)
+        }
+
+        fn rotate(&self, _current_id: Uuid, _next: &RefreshToken) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+
+        fn revoke_family(&self, _family_id: Uuid) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn revoke_all_for_user(&self, user_id: Uuid) -> Result<(), AuthError> {
+            self.revoked_users.borrow_mut().push(user_id);
+            Ok(())
+        }
+    }
+
+    // Token service mapping "admin" and "support" tokens to roles; the
+    // "self" token belongs to the administrator with `self_id`
+    struct MockTokenService {
+        self_id: Uuid,
+    }
+
+    impl TokenService for MockTokenService {
+        fn create_token(
+            &self,
+            _user: &User,
+            _session_id: Option<Uuid>,
+        ) -> Result<String, AuthError> {
+            Ok("token".to_string())
+        }
+
+        fn validate_token(&self, token: &str) -> Result<TokenData, AuthError> {
+            let (user_id, roles) = match token {
+                "admin" => (Uuid::new_v4(), vec![Role::Admin]),
+                "self" => (self.self_id, vec![Role::Admin]),
+                "support" => (Uuid::new_v4(), vec![Role::SupportAgent]),
+                _ => return Err(AuthError::InvalidToken),
+            };
+            Ok(TokenData {
+                roles,
+                ..token_data(user_id)
+            })
+        }
+
+        fn revoke_token(&self, _token: &TokenData) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn is_revoked(&self, _token: &TokenData) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+    }
+
+    fn setup() -> (MockUserRepository, MockTokenService) {
+        let email = Email::new("test@example.com").unwrap();
+        let user = User::new(email, HashedPassword::from_hash("hashed".to_string()), None);
+        let token_service = MockTokenService {
+            self_id: user.id().as_uuid(),
+        };
+        (
+            MockUserRepository::new(user),
+            token_service,
+        )
+    }
+
+    fn set_active(
+        users: &MockUserRepository,
+        refresh_tokens: &MockRefreshTokenRepository,
+        token_service: &MockTokenService,
+        token: &str,
+        active: bool,
+    ) -> Result<User, AuthError> {
+        let user_id = users.user_id();
+        SetUserActiveUseCase::new(users, refresh_tokens, token_service, &MockAuditLog::default()).execute(
+            SetUserActiveCommand {
+                token: token.to_string(),
+                user_id,
+                active,
+            },
+        )
+    }
+
+    #[test]
+    fn test_deactivate_and_reactivate() {
+        let (users, token_service) = setup();
+        let refresh_tokens = MockRefreshTokenRepository::default();
+
+        let user = set_active(&users, &refresh_tokens, &token_service, "admin", false).unwrap();
+        assert!(!user.is_active());
+        assert_eq!(
+            *refresh_tokens.revoked_users.borrow(),
+            vec![user.id().as_uuid()]
+        );
+
+        let user = set_active(&users, &refresh_tokens, &token_service, "admin", true).unwrap();
+        assert!(user.is_active());
+        assert!(users.user.lock().unwrap().is_active());
+        assert_eq!(refresh_tokens.revoked_users.borrow().len(), 1);
+    }
+
+    #[test]
+    fn test_support_agent_forbidden() {
+        let (users, token_service) = setup();
+        let refresh_tokens = MockRefreshTokenRepository::default();
+
+        let result = set_active(&users, &refresh_tokens, &token_service, "support", false);
+
+        assert!(matches!(result, Err(AuthError::Forbidden)));
+        assert!(users.user.lock().unwrap().is_active());
+    }
+
+    #[test]
+    fn test_admin_cannot_deactivate_themselves() {
+        let (users, token_service) = setup();
+        let refresh_tokens = MockRefreshTokenRepository::default();
+
+        let result = set_active(&users, &refresh_tokens, &token_service, "self", false);
+
+        assert!(matches!(result, Err(AuthError::Forbidden)));
+        assert!(users.user.lock().unwrap().is_active());
+    }
+}
@@ -0,0 +1,114 @@
+//! Start federated login use case
+//!
+//! First step of signing in through an external identity provider: the
+//! login page gets a URL to send the user to, and a `state` to compare with
+//! the one the provider sends back.
+
+use chrono::Duration;
+
+use crate::domain::auth::OpaqueTokenGenerator;
+use crate::domain::error::AuthError;
+use crate::domain::federation::{
+    find_provider, AsyncFederatedLoginRequestRepository, AsyncIdentityProviderClient,
+    FederatedLoginRequest, IdentityProvider,
+};
+use crate::domain::oidc::pkce_challenge;
+
+/// Input for starting a federated login
+#[derive(Debug)]
+pub struct StartFederatedLoginCommand {
+    /// ID of the identity provider
+    pub provider: String,
+}
+
+/// Where to send the user
+#[derive(Debug)]
+pub struct StartFederatedLoginResult {
+    /// Login page of the identity provider
+    pub authorization_url: String,
+    /// Opaque value the provider returns to the callback page
+    pub state: String,
+    /// Seconds the user has to complete the sign-in
+    pub expires_in: i64,
+}
+
+/// Use case for starting a federated login
+pub struct StartFederatedLoginUseCase<'a, R: ?Sized, C: ?Sized, G: ?Sized> {
+    providers: &'a [IdentityProvider],
+    request_repository: &'a R,
+    provider_client: &'a C,
+    token_generator: &'a G,
+    redirect_uri: &'a str,
+    request_ttl: Duration,
+}
+
+impl<'a, R, C, G> StartFederatedLoginUseCase<'a, R, C, G>
+where
+    R: AsyncFederatedLoginRequestRepository + ?Sized,
+    C: AsyncIdentityProviderClient + ?Sized,
+    G: OpaqueTokenGenerator + ?Sized,
+{
+    /// Create a new use case instance
+    ///
+    /// `redirect_uri` is the callback page registered with every provider.
+    pub fn new(
+        providers: &'a [IdentityProvider],
+        request_repository: &'a R,
+        provider_client: &'a C,
+        token_generator: &'a G,
+        redirect_uri: &'a str,
+        request_ttl: Duration,
+    ) -> Self {
+        Self {
+            providers,
+            request_repository,
+            provider_client,
+            token_generator,
+            redirect_uri,
+            request_ttl,
+        }
+    }
+
+    /// Execute the start
+    ///
+    /// # Errors
+    /// - `AuthError::IdentityProviderNotFound` if the provider is not configured
+    /// - `AuthError::IdentityProvider` if the provider cannot be reached
+    /// - `AuthError::Internal` on infrastructure failures
+    pub async fn execute(
+        &self,
+        command: StartFederatedLoginCommand,
+    ) -> Result<StartFederatedLoginResult, AuthError> {
+        let provider = find_provider(self.providers, &command.provider)?;
+
+        let state = self.token_generator.generate();
+        let nonce = self.token_generator.generate();
+        let code_verifier = self.token_generator.generate();
+        let authorization_url = self
+            .provider_client
+            .authorization_url(
+                provider,
+                self.redirect_uri,
+                &state,
+                &nonce,
+                &pkce_challenge(&code_verifier),
+            )
+            .await?;
+
+        self.request_repository
+            .create(&FederatedLoginRequest::issue(
+                self.token_generator.hash(&state),
+                provider.id.clone(),
+                nonce,
+                code_verifier,
+                self.request_ttl,
+            ))
+            .await?;
+
+        Ok(StartFederatedLoginResult {
+            authorization_url,
+            state,
+            expires_in: self.request_ttl.num_seconds(),
+        })
+    }
+}
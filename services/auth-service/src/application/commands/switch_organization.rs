@@ -0,0 +1,386 @@
+//! Switch organization use case
+//!
+//! Re-issues the caller's access token for another organization, or for
+//! none. The new token stays bound to the same session, so logging out or
+//! revoking the session still ends it. The session remembers the
+//! organization, so refreshing keeps acting for it.
+
+use tracing::info;
+use uuid::Uuid;
+
+use crate::domain::auth::{TokenService, UserRepository};
+use crate::domain::error::AuthError;
+use crate::domain::organization::{
+    ActiveOrganization, OrganizationRepository, OrganizationTokenIssuer,
+};
+use crate::domain::session::SessionRepository;
+
+/// Input for switching organization
+#[derive(Debug)]
+pub struct SwitchOrganizationCommand {
+    /// Current access token
+    pub token: String,
+    /// Organization to act for; `None` returns to the personal context
+    pub organization_id: Option<Uuid>,
+}
+
+/// Re-issued access token
+#[derive(Debug)]
+pub struct SwitchOrganizationResult {
+    pub token: String,
+    /// Organization the token acts for
+    pub organization: Option<ActiveOrganization>,
+}
+
+/// Use case for switching organization
+pub struct SwitchOrganizationUseCase<'a, R: ?Sized, O: ?Sized, D: ?Sized, T: ?Sized, I: ?Sized> {
+    user_repository: &'a R,
+    organization_repository: &'a O,
+    session_repository: &'a D,
+    token_service: &'a T,
+    token_issuer: &'a I,
+}
+
+impl<'a, R, O, D, T, I> SwitchOrganizationUseCase<'a, R, O, D, T, I>
+where
+    R: UserRepository + ?Sized,
+    O: OrganizationRepository + ?Sized,
+    D: SessionRepository + ?Sized,
+    T: TokenService + ?Sized,
+    I: OrganizationTokenIssuer + ?Sized,
+{
+    /// Create a new use case instance
+    pub fn new(
+        user_repository: &'a R,
+        organization_repository: &'a O,
+        session_repository: &'a D,
+        token_service: &'a T,
+        token_issuer: &'a I,
+    ) -> Self {
+        Self {
+            user_repository,
+            organization_repository,
+            session_repository,
+            token_service,
+            token_issuer,
+        }
+    }
+
+    /// Execute the switch
+    ///
+    /// The membership is read now; a role granted or withdrawn later shows up
+    /// in the next token only.
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidToken` / `AuthError::TokenExpired` if the token is invalid
+    /// - `AuthError::Forbidden` if the caller is not signed in as themselves
+    /// - `AuthError::AccountInactive` if user account is deactivated
+    /// - `AuthError::OrganizationNotFound` if the caller is not a member
+    /// - `AuthError::Internal` on infrastructure failures
+    pub fn execute(
+        &self,
+        command: SwitchOrganizationCommand,
+    ) -> Result<SwitchOrganizationResult, AuthError> {
+        let token_data = self.token_service.validate_token(&command.token)?;
+        if !token_data.is_session() {
+            return Err(AuthError::Forbidden);
+        }
+
+        let user = self.user_repository.find_by_id(token_data.user_id)?;
+        if !user.is_active() {
+            return Err(AuthError::AccountInactive);
+        }
+
+        let Some(organization_id) = command.organization_id else {
+            let token = self
+                .token_service
+                .create_token(&user, token_data.session_id)?;
+            if let Some(session_id) = token_data.session_id {
+                self.session_repository.set_organization(session_id, None)?;
+            }
+            return Ok(SwitchOrganizationResult {
+                token,
+                organization: None,
+            });
+        };
+
+        let membership = self
+            .organization_repository
+            .find_membership(organization_id, token_data.user_id)?
+            .ok_or(AuthError::OrganizationNotFound)?;
+        let organization = ActiveOrganization {
+            id: organization_id,
+            role: membership.role(),
+        };
+        let token = self.token_issuer.create_organization_token(
+            &user,
+            token_data.session_id,
+            organization,
+        )?;
+        if let Some(session_id) = token_data.session_id {
+            self.session_repository
+                .set_organization(session_id, Some(organization_id))?;
+        }
+
+        info!(
+            user_id = %token_data.user_id,
+            organization_id = %organization_id,
+            "Switched organization"
+        );
+        Ok(SwitchOrganizationResult {
+            token,
+            organization: Some(organization),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use chrono::{DateTime, Utc};
+
+    use super::*;
+    use crate::application::commands::test_support::{token_data, MockUserRepository};
+    use crate::domain::auth::TokenData;
+    use crate::domain::organization::{Membership, OrgRole, Organization};
+    use crate::domain::session::Session;
+    use crate::domain::user::{Email, HashedPassword, User};
+
+    // Organization store with a single membership
+    struct MockOrganizationRepository {
+        membership: Membership,
+    }
+
+    impl OrganizationRepository for MockOrganizationRepository {
+        fn create(
+            &self,
+            _organization: &Organization,
+            _owner: &Membership,
+        ) -> Result<(), AuthError> {
+            Err(AuthError::Internal("Not implemented".to_string()))
+        }
+
+        fn find_by_id(&self, _id: Uuid) -> Result<Organization, AuthError> {
+            Err(AuthError::OrganizationNotFound)
+        }
+
+        fn find_membership(
+            &self,
+            organization_id: Uuid,
+            user_id: Uuid,
+        ) -> Result<Option<Membership>, AuthError> {
+            let found = self.membership.organization_id() == organization_id
+                && self.membership.user_id() == user_id;
+            Ok(found.then(|| self.membership.clone()))
+        }
+
+        fn list_for_user(
+            &self,
+            _user_id: Uuid,
+        ) -> Result<Vec<(Organization, Membership)>, AuthError> {
+            Ok(Vec::new())
+        }
+    }
+
+    // Session store recording the organization each session switched to
+    #[derive(Default)]
+    struct MockSessionRepository {
+        organizations: RefCell<Vec<(Uuid, Option<Uuid>)>>,
+    }
+
+    impl SessionRepository for MockSessionRepository {
+        fn create(&self, _session: &Session) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn find_by_id(&self, _id: Uuid) -> Result<Session, AuthError> {
+            Err(AuthError::SessionNotFound)
+        }
+
+        fn find_active_for_user(
+            &self,
+            _user_id: Uuid,
+            _now: DateTime<Utc>,
+        ) -> Result<Vec<Session>, AuthError> {
+            Ok(Vec::new())
+        }
+
+        fn touch(
+            &self,
+            _id: Uuid,
+            _now: DateTime<Utc>,
+            _expires_at: DateTime<Utc>,
+        ) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn set_organization(
+            &self,
+            id: Uuid,
+            organization_id: Option<Uuid>,
+        ) -> Result<(), AuthError> {
+            self.organizations.borrow_mut().push((id, organization_id));
+            Ok(())
+        }
+
+        fn is_revoked(&self, _id: Uuid) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+    }
+
+    // Token service accepting "session" and "key" tokens for one user, and
+    // naming the issued token after its session and organization
+    struct MockTokenService {
+        user_id: Uuid,
+        session_id: Uuid,
+    }
+
+    impl TokenService for MockTokenService {
+        fn create_token(
+            &self,
+            _user: &User,
+            session_id: Option<Uuid>,
+        ) -> Result<String, AuthError> {
+            Ok(format!("personal:{}", session_id.unwrap()))
+        }
+
+        fn validate_token(&self, token: &str) -> Result<TokenData, AuthError> {
+            let scopes = match token {
+                "session" => None,
+                "key" => Some(vec![]),
+                _ => return Err(AuthError::InvalidToken),
+            };
+            Ok(TokenData {
+                session_id: Some(self.session_id),
+                scopes,
+                ..token_data(self.user_id)
+            })
+        }
+
+        fn revoke_token(&self, _token: &TokenData) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn is_revoked(&self, _token: &TokenData) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+    }
+
+    impl OrganizationTokenIssuer for MockTokenService {
+        fn create_organization_token(
+            &self,
+            _user: &User,
+            session_id: Option<Uuid>,
+            organization: ActiveOrganization,
+        ) -> Result<String, AuthError> {
+            Ok(format!(
+                "{}:{}:{}",
+                organization.id,
+                organization.role,
+                session_id.unwrap()
+            ))
+        }
+    }
+
+    struct Fixture {
+        users: MockUserRepository,
+        organizations: MockOrganizationRepository,
+        sessions: MockSessionRepository,
+        tokens: MockTokenService,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            let email = Email::new("member@example.com").unwrap();
+            let user = User::new(email, HashedPassword::from_hash("hashed".to_string()), None);
+            let user_id = user.id().as_uuid();
+            Self {
+                users: MockUserRepository::new(user),
+                organizations: MockOrganizationRepository {
+                    membership: Membership::new(Uuid::new_v4(), user_id, OrgRole::Admin),
+                },
+                sessions: MockSessionRepository::default(),
+                tokens: MockTokenService {
+                    user_id,
+                    session_id: Uuid::new_v4(),
+                },
+            }
+        }
+
+        fn switch(
+            &self,
+            token: &str,
+            organization_id: Option<Uuid>,
+        ) -> Result<SwitchOrganizationResult, AuthError> {
+            SwitchOrganizationUseCase::new(
+                &self.users,
+                &self.organizations,
+                &self.sessions,
+                &self.tokens,
+                &self.tokens,
+            )
+            .execute(SwitchOrganizationCommand {
+                token: token.to_string(),
+                organization_id,
+            })
+        }
+    }
+
+    #[test]
+    fn test_switch_into_member_organization_keeps_session() {
+        let fixture = Fixture::new();
+        let organization_id = fixture.organizations.membership.organization_id();
+
+        let result = fixture.switch("session", Some(organization_id)).unwrap();
+
+        assert_eq!(
+            result.token,
+            format!("{}:admin:{}", organization_id, fixture.tokens.session_id)
+        );
+        assert_eq!(
+            result.organization,
+            Some(ActiveOrganization {
+                id: organization_id,
+                role: OrgRole::Admin,
+            })
+        );
+        assert_eq!(
+            *fixture.sessions.organizations.borrow(),
+            [(fixture.tokens.session_id, Some(organization_id))]
+        );
+    }
+
+    #[test]
+    fn test_switch_back_to_personal_context() {
+        let fixture = Fixture::new();
+
+        let result = fixture.switch("session", None).unwrap();
+
+        assert_eq!(
+            result.token,
+            format!("personal:{}", fixture.tokens.session_id)
+        );
+        assert!(result.organization.is_none());
+        assert_eq!(
+            *fixture.sessions.organizations.borrow(),
+            [(fixture.tokens.session_id, None)]
+        );
+    }
+
+    #[test]
+    fn test_non_member_and_api_key_refused() {
+        let fixture = Fixture::new();
+        let organization_id = fixture.organizations.membership.organization_id();
+
+        assert!(matches!(
+            fixture.switch("session", Some(Uuid::new_v4())),
+            Err(AuthError::OrganizationNotFound)
+        ));
+        assert!(matches!(
+            fixture.switch("key", Some(organization_id)),
+            Err(AuthError::Forbidden)
+        ));
+        assert!(fixture.sessions.organizations.borrow().is_empty());
+    }
+}
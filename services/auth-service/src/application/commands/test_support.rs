@@ -0,0 +1,318 @@
+//! Test doubles shared by the use case tests
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+use crate::domain::audit::{AsyncAuditLog, AuditEvent, AuditLog};
+use crate::domain::auth::{
+    AsyncPasswordHasher, AsyncUserRepository, BoxFuture, PasswordHasher, Principal, TokenData,
+    UserRepository,
+};
+use crate::domain::error::AuthError;
+use crate::domain::mfa::{
+    AsyncMfaChallengeRepository, AsyncMfaRepository, MfaChallenge, TotpCredential,
+};
+use crate::domain::refresh_token::{AsyncRefreshTokenRepository, RefreshToken};
+use crate::domain::session::{AsyncSessionRepository, Session};
+use crate::domain::user::{HashedPassword, User};
+
+/// Repository holding a single user and counting writes
+pub(super) struct MockUserRepository {
+    pub user: Mutex<User>,
+    updates: AtomicUsize,
+}
+
+impl MockUserRepository {
+    pub fn new(user: User) -> Self {
+        Self {
+            user: Mutex::new(user),
+            updates: AtomicUsize::new(0),
+        }
+    }
+
+    /// Id of the stored user, without holding its lock
+    pub fn user_id(&self) -> Uuid {
+        self.user.lock().unwrap().id().as_uuid()
+    }
+
+    /// Number of updates written so far
+    pub fn updates(&self) -> usize {
+        self.updates.load(Ordering::SeqCst)
+    }
+}
+
+impl UserRepository for MockUserRepository {
+    fn find_by_id(&self, id: Uuid) -> Result<User, AuthError> {
+        let user = self.user.lock().unwrap();
+        if user.id().as_uuid() == id {
+            Ok(user.clone())
+        } else {
+            Err(AuthError::UserNotFound)
+        }
+    }
+
+    fn find_by_email(&self, email: &str) -> Result<User, AuthError> {
+        let user = self.user.lock().unwrap();
+        if user.email().as_str() == email {
+            Ok(user.clone())
+        } else {
+            Err(AuthError::UserNotFound)
+        }
+    }
+
+    fn exists_by_email(&self, email: &str) -> Result<bool, AuthError> {
+        Ok(self.user.lock().unwrap().email().as_str() == email)
+    }
+
+    fn create(&self, _user: &User) -> Result<User, AuthError> {
+        Err(AuthError::Internal("Not implemented".to_string()))
+    }
+
+    fn update(&self, user: &User) -> Result<User, AuthError> {
+        self.updates.fetch_add(1, Ordering::SeqCst);
+        *self.user.lock().unwrap() = user.clone();
+        Ok(user.clone())
+    }
+}
+
+impl AsyncUserRepository for MockUserRepository {
+    fn find_by_id(&self, id: Uuid) -> BoxFuture<'_, Result<User, AuthError>> {
+        Box::pin(async move { UserRepository::find_by_id(self, id) })
+    }
+
+    fn find_by_email<'a>(&'a self, email: &'a str) -> BoxFuture<'a, Result<User, AuthError>> {
+        Box::pin(async move { UserRepository::find_by_email(self, email) })
+    }
+
+    fn exists_by_email<'a>(&'a self, email: &'a str) -> BoxFuture<'a, Result<bool, AuthError>> {
+        Box::pin(async move { UserRepository::exists_by_email(self, email) })
+    }
+
+    fn create<'a>(&'a self, user: &'a User) -> BoxFuture<'a, Result<User, AuthError>> {
+        Box::pin(async move { UserRepository::create(self, user) })
+    }
+
+    fn update<'a>(&'a self, user: &'a User) -> BoxFuture<'a, Result<User, AuthError>> {
+        Box::pin(async move { UserRepository::update(self, user) })
+    }
+}
+
+/// Password hasher prefixing passwords with `hashed_`
+pub(super) struct MockPasswordHasher;
+
+impl PasswordHasher for MockPasswordHasher {
+    fn hash(&self, plain: &str) -> Result<HashedPassword, AuthError> {
+        Ok(HashedPassword::from_hash(format!("hashed_{}", plain)))
+    }
+
+    fn verify(&self, plain: &str, hashed: &HashedPassword) -> Result<bool, AuthError> {
+        Ok(hashed.as_str() == format!("hashed_{}", plain))
+    }
+}
+
+impl AsyncPasswordHasher for MockPasswordHasher {
+    fn hash<'a>(&'a self, plain: &'a str) -> BoxFuture<'a, Result<HashedPassword, AuthError>> {
+        Box::pin(async move { PasswordHasher::hash(self, plain) })
+    }
+
+    fn verify<'a>(
+        &'a self,
+        plain: &'a str,
+        hashed: &'a HashedPassword,
+    ) -> BoxFuture<'a, Result<bool, AuthError>> {
+        Box::pin(async move { PasswordHasher::verify(self, plain, hashed) })
+    }
+}
+
+/// Audit log keeping every entry
+#[derive(Default)]
+pub(super) struct MockAuditLog {
+    pub events: Mutex<Vec<AuditEvent>>,
+}
+
+impl AuditLog for MockAuditLog {
+    fn record(&self, event: AuditEvent) {
+        self.events.lock().unwrap().push(event);
+    }
+}
+
+impl AsyncAuditLog for MockAuditLog {
+    fn record(&self, event: AuditEvent) -> BoxFuture<'_, ()> {
+        Box::pin(async move { AuditLog::record(self, event) })
+    }
+}
+
+/// Refresh token store recording issued tokens
+#[derive(Default)]
+pub(super) struct MockRefreshTokenRepository {
+    pub created: Mutex<Vec<RefreshToken>>,
+}
+
+impl AsyncRefreshTokenRepository for MockRefreshTokenRepository {
+    fn create<'a>(&'a self, token: &'a RefreshToken) -> BoxFuture<'a, Result<(), AuthError>> {
+        self.created.lock().unwrap().push(token.clone());
+        Box::pin(async { Ok(()) })
+    }
+
+    fn find_by_hash<'a>(
+        &'a self,
+        _token_hash: &'a str,
+    ) -> BoxFuture<'a, Result<RefreshToken, AuthError>> {
+        Box::pin(async { Err(AuthError::InvalidToken) })
+    }
+
+    fn rotate<'a>(
+        &'a self,
+        _current_id: Uuid,
+        _next: &'a RefreshToken,
+    ) -> BoxFuture<'a, Result<bool, AuthError>> {
+        Box::pin(async { Ok(false) })
+    }
+
+    fn revoke_family(&self, _family_id: Uuid) -> BoxFuture<'_, Result<(), AuthError>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    fn revoke_all_for_user(&self, _user_id: Uuid) -> BoxFuture<'_, Result<(), AuthError>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// Session store accepting every session and finding none
+pub(super) struct MockSessionRepository;
+
+impl AsyncSessionRepository for MockSessionRepository {
+    fn create<'a>(&'a self, _session: &'a Session) -> BoxFuture<'a, Result<(), AuthError>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    fn find_by_id(&self, _id: Uuid) -> BoxFuture<'_, Result<Session, AuthError>> {
+        Box::pin(async { Err(AuthError::SessionNotFound) })
+    }
+
+    fn find_active_for_user(
+        &self,
+        _user_id: Uuid,
+        _now: DateTime<Utc>,
+    ) -> BoxFuture<'_, Result<Vec<Session>, AuthError>> {
+        Box::pin(async { Ok(Vec::new()) })
+    }
+
+    fn touch(
+        &self,
+        _id: Uuid,
+        _now: DateTime<Utc>,
+        _expires_at: DateTime<Utc>,
+    ) -> BoxFuture<'_, Result<(), AuthError>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    fn set_organization(
+        &self,
+        _id: Uuid,
+        _organization_id: Option<Uuid>,
+    ) -> BoxFuture<'_, Result<(), AuthError>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    fn is_revoked(&self, _id: Uuid) -> BoxFuture<'_, Result<bool, AuthError>> {
+        Box::pin(async { Ok(false) })
+    }
+}
+
+/// MFA store holding at most one credential, without recovery codes
+#[derive(Default)]
+pub(super) struct MockMfaRepository {
+    pub credential: Option<TotpCredential>,
+}
+
+impl AsyncMfaRepository for MockMfaRepository {
+    fn find_totp(
+        &self,
+        _user_id: Uuid,
+    ) -> BoxFuture<'_, Result<Option<TotpCredential>, AuthError>> {
+        let credential = self.credential.clone();
+        Box::pin(async { Ok(credential) })
+    }
+
+    fn record_totp_step(
+        &self,
+        _user_id: Uuid,
+        _step: i64,
+    ) -> BoxFuture<'_, Result<bool, AuthError>> {
+        Box::pin(async { Ok(false) })
+    }
+
+    fn has_recovery_code<'a>(
+        &'a self,
+        _user_id: Uuid,
+        _code_hash: &'a str,
+    ) -> BoxFuture<'a, Result<bool, AuthError>> {
+        Box::pin(async { Ok(false) })
+    }
+
+    fn consume_recovery_code<'a>(
+        &'a self,
+        _user_id: Uuid,
+        _code_hash: &'a str,
+    ) -> BoxFuture<'a, Result<bool, AuthError>> {
+        Box::pin(async { Ok(false) })
+    }
+}
+
+/// Challenge store recording issued challenges and finding none
+#[derive(Default)]
+pub(super) struct MockMfaChallengeRepository {
+    pub created: Mutex<Vec<MfaChallenge>>,
+}
+
+impl AsyncMfaChallengeRepository for MockMfaChallengeRepository {
+    fn create<'a>(&'a self, challenge: &'a MfaChallenge) -> BoxFuture<'a, Result<(), AuthError>> {
+        self.created.lock().unwrap().push(challenge.clone());
+        Box::pin(async { Ok(()) })
+    }
+
+    fn find_by_hash<'a>(
+        &'a self,
+        _token_hash: &'a str,
+    ) -> BoxFuture<'a, Result<MfaChallenge, AuthError>> {
+        Box::pin(async { Err(AuthError::InvalidToken) })
+    }
+
+    fn record_failed_attempt<'a>(
+        &'a self,
+        _challenge: &'a MfaChallenge,
+    ) -> BoxFuture<'a, Result<i32, AuthError>> {
+        Box::pin(async { Ok(0) })
+    }
+
+    fn consume<'a>(
+        &'a self,
+        _challenge: &'a MfaChallenge,
+    ) -> BoxFuture<'a, Result<bool, AuthError>> {
+        Box::pin(async { Ok(false) })
+    }
+}
+
+/// Token data for a signed-in user, valid for an hour, holding no roles,
+/// scopes or other claims
+///
+/// Tests override the claims they care about with struct update syntax.
+pub(crate) fn token_data(user_id: Uuid) -> TokenData {
+    TokenData {
+        user_id,
+        email: "user@example.com".to_string(),
+        jti: "jti".to_string(),
+        session_id: None,
+        expires_at: Utc::now() + Duration::hours(1),
+        roles: Vec::new(),
+        scopes: None,
+        principal: Principal::User,
+        actor: None,
+        organization: None,
+        oidc_scopes: None,
+    }
+}
@@ -0,0 +1,221 @@
+//! Unlock account use case
+//!
+//! Lifts a login lockout on a user's email address before it runs out, e.g.
+//! after support has confirmed the owner's identity. Only callers whose
+//! token carries the `users:manage` permission may do so. Locks on source IP
+//! addresses are left alone.
+
+use tracing::info;
+use uuid::Uuid;
+
+use crate::domain::audit::{AuditAction, AuditEvent, AuditLog};
+use crate::domain::auth::{TokenData, TokenService, UserRepository};
+use crate::domain::error::AuthError;
+use crate::domain::lockout::{LoginThrottleRepository, ThrottleKey};
+use crate::domain::role::{authorize, Permission};
+
+/// Input for unlocking an account
+#[derive(Debug)]
+pub struct UnlockAccountCommand {
+    /// Access token of the acting administrator
+    pub token: String,
+    pub user_id: Uuid,
+}
+
+/// Output after unlocking an account
+#[derive(Debug)]
+pub struct UnlockAccountResult {
+    pub user_id: Uuid,
+    /// Whether any failed logins were on record
+    pub had_failures: bool,
+}
+
+/// Use case for unlocking an account
+pub struct UnlockAccountUseCase<'a, R: ?Sized, L: ?Sized, T: ?Sized, A: ?Sized> {
+    user_repository: &'a R,
+    throttle_repository: &'a L,
+    token_service: &'a T,
+    audit_log: &'a A,
+}
+
+impl<'a, R, L, T, A> UnlockAccountUseCase<'a, R, L, T, A>
+where
+    R: UserRepository + ?Sized,
+    L: LoginThrottleRepository + ?Sized,
+    T: TokenService + ?Sized,
+    A: AuditLog + ?Sized,
+{
+    /// Create a new use case instance
+    pub fn new(
+        user_repository: &'a R,
+        throttle_repository: &'a L,
+        token_service: &'a T,
+        audit_log: &'a A,
+    ) -> Self {
+        Self {
+            user_repository,
+            throttle_repository,
+            token_service,
+            audit_log,
+        }
+    }
+
+    /// Execute the unlock
+    ///
+    /// Clears the failed login counter as well, so the user gets the full
+    /// number of attempts again.
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidToken` / `AuthError::TokenExpired` if the token is invalid
+    /// - `AuthError::Forbidden` if the caller may not manage users
+    /// - `AuthError::UserNotFound` if the target user does not exist
+    /// - `AuthError::Internal` on infrastructure failures
+    pub fn execute(&self, command: UnlockAccountCommand) -> Result<UnlockAccountResult, AuthError> {
+        let actor = self.token_service.validate_token(&command.token)?;
+        let user_id = command.user_id;
+
+        let result = self.unlock(&actor, command);
+        self.audit_log.record(
+            AuditEvent::new(AuditAction::AccountUnlock)
+                .with_actor(actor.audit_actor())
+                .with_target(user_id)
+                .with_result(&result),
+        );
+        result
+    }
+
+    fn unlock(
+        &self,
+        actor: &TokenData,
+        command: UnlockAccountCommand,
+    ) -> Result<UnlockAccountResult, AuthError> {
+        authorize(actor, Permission::ManageUsers)?;
+
+        let user = self.user_repository.find_by_id(command.user_id)?;
+        let had_failures = self
+            .throttle_repository
+            .clear(&ThrottleKey::email(user.email().as_str()))?;
+
+        info!(user_id = %command.user_id, actor_id = %actor.user_id, had_failures, "Account unlocked");
+        Ok(UnlockAccountResult {
+            user_id: command.user_id,
+            had_failures,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use chrono::{DateTime, Utc};
+
+    use super::*;
+    use crate::application::commands::test_support::{token_data, MockAuditLog, MockUserRepository};
+    use crate::domain::lockout::{LockoutPolicy, LoginThrottle};
+    use crate::domain::role::Role;
+    use crate::domain::user::{Email, HashedPassword, User};
+
+    // Counter store recording which subjects were cleared
+    #[derive(Default)]
+    struct MockLoginThrottleRepository {
+        cleared: RefCell<Vec<ThrottleKey>>,
+    }
+
+    impl LoginThrottleRepository for MockLoginThrottleRepository {
+        fn find(&self, _key: &ThrottleKey) -> Result<Option<LoginThrottle>, AuthError> {
+            Ok(None)
+        }
+
+        fn record_failure(
+            &self,
+            key: &ThrottleKey,
+            _policy: &LockoutPolicy,
+            now: DateTime<Utc>,
+        ) -> Result<LoginThrottle, AuthError> {
+            Ok(LoginThrottle::new(key.clone(), now))
+        }
+
+        fn clear(&self, key: &ThrottleKey) -> Result<bool, AuthError> {
+            self.cleared.borrow_mut().push(key.clone());
+            Ok(true)
+        }
+
+        fn purge_stale(&self, _cutoff: DateTime<Utc>) -> Result<usize, AuthError> {
+            Ok(0)
+        }
+    }
+
+    // Token service mapping "admin" and "support" tokens to roles
+    struct MockTokenService;
+
+    impl TokenService for MockTokenService {
+        fn create_token(
+            &self,
+            _user: &User,
+            _session_id: Option<Uuid>,
+        ) -> Result<String, AuthError> {
+            Ok("token".to_string())
+        }
+
+        fn validate_token(&self, token: &str) -> Result<TokenData, AuthError> {
+            let roles = match token {
+                "admin" => vec![Role::Admin],
+                "support" => vec![Role::SupportAgent],
+                _ => return Err(AuthError::InvalidToken),
+            };
+            Ok(TokenData {
+                roles,
+                ..token_data(Uuid::new_v4())
+            })
+        }
+
+        fn revoke_token(&self, _token: &TokenData) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn is_revoked(&self, _token: &TokenData) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+    }
+
+    fn create_repo() -> MockUserRepository {
+        let email = Email::new("test@example.com").unwrap();
+        let password = HashedPassword::from_hash("hashed".to_string());
+        MockUserRepository::new(User::new(email, password, None))
+    }
+
+    #[test]
+    fn test_admin_unlocks_account() {
+        let repo = create_repo();
+        let throttles = MockLoginThrottleRepository::default();
+
+        let result = UnlockAccountUseCase::new(&repo, &throttles, &MockTokenService, &MockAuditLog::default())
+            .execute(UnlockAccountCommand {
+                token: "admin".to_string(),
+                user_id: repo.user_id(),
+            })
+            .unwrap();
+
+        assert!(result.had_failures);
+        assert_eq!(
+            *throttles.cleared.borrow(),
+            vec![ThrottleKey::email("test@example.com")]
+        );
+    }
+
+    #[test]
+    fn test_support_agent_forbidden() {
+        let repo = create_repo();
+        let throttles = MockLoginThrottleRepository::default();
+
+        let result = UnlockAccountUseCase::new(&repo, &throttles, &MockTokenService, &MockAuditLog::default())
+            .execute(UnlockAccountCommand {
+                token: "support".to_string(),
+                user_id: repo.user_id(),
+            });
+
+        assert!(matches!(result, Err(AuthError::Forbidden)));
+        assert!(throttles.cleared.borrow().is_empty());
+    }
+}
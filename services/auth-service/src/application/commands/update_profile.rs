@@ -0,0 +1,233 @@
+//! Update profile use case
+//!
+//! Lets a signed-in user edit the non-sensitive parts of their account,
+//! currently the display name. Only the user's own session may do so; API
+//! keys and impersonation tokens are refused.
+
+use tracing::info;
+
+use crate::domain::audit::{AuditAction, AuditEvent, AuditLog};
+use crate::domain::auth::{TokenData, TokenService, UserRepository};
+use crate::domain::error::AuthError;
+use crate::domain::user::{normalize_display_name, User};
+
+/// Input for updating a profile
+#[derive(Debug)]
+pub struct UpdateProfileCommand {
+    /// Access token of the user
+    pub token: String,
+    /// New display name; `None` or an empty name clears it
+    pub display_name: Option<String>,
+}
+
+/// Use case for updating a profile
+pub struct UpdateProfileUseCase<'a, R: ?Sized, T: ?Sized, L: ?Sized> {
+    user_repository: &'a R,
+    token_service: &'a T,
+    audit_log: &'a L,
+}
+
+impl<'a, R, T, L> UpdateProfileUseCase<'a, R, T, L>
+where
+    R: UserRepository + ?Sized,
+    T: TokenService + ?Sized,
+    L: AuditLog + ?Sized,
+{
+    /// Create a new use case instance
+    pub fn new(user_repository: &'a R, token_service: &'a T, audit_log: &'a L) -> Self {
+        Self {
+            user_repository,
+            token_service,
+            audit_log,
+        }
+    }
+
+    /// Execute the update, returning the updated user
+    ///
+    /// The user is only written if something changed.
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidToken` / `AuthError::TokenExpired` if the token is invalid
+    /// - `AuthError::Forbidden` if the token is not the user's own session
+    /// - `AuthError::AccountInactive` if user account is deactivated
+    /// - `AuthError::InvalidDisplayName` if the display name is too long or malformed
+    /// - `AuthError::Internal` on infrastructure failures
+    pub fn execute(&self, command: UpdateProfileCommand) -> Result<User, AuthError> {
+        let token_data = self.token_service.validate_token(&command.token)?;
+
+        let result = self.update(&token_data, command);
+        self.audit_log.record(
+            AuditEvent::new(AuditAction::ProfileUpdate)
+                .with_actor(token_data.audit_actor())
+                .with_target(token_data.user_id)
+                .with_result(&result),
+        );
+        result
+    }
+
+    fn update(
+        &self,
+        token_data: &TokenData,
+        command: UpdateProfileCommand,
+    ) -> Result<User, AuthError> {
+        if !token_data.is_session() {
+            return Err(AuthError::Forbidden);
+        }
+        let mut user = self.user_repository.find_by_id(token_data.user_id)?;
+
+        if !user.is_active() {
+            return Err(AuthError::AccountInactive);
+        }
+
+        let display_name = normalize_display_name(command.display_name.as_deref())?;
+        if !user.set_display_name(display_name) {
+            return Ok(user);
+        }
+
+        let user = self.user_repository.update(&user)?;
+
+        info!(user_id = %token_data.user_id, "Profile updated");
+        Ok(user)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::application::commands::test_support::{token_data, MockAuditLog, MockUserRepository};
+    use crate::domain::audit::AuditOutcome;
+    use crate::domain::role::Permission;
+    use crate::domain::user::{Email, HashedPassword};
+
+    // Token service accepting any token for one user
+    struct MockTokenService {
+        user_id: Uuid,
+        scopes: Option<Vec<Permission>>,
+        actor: Option<Uuid>,
+    }
+
+    impl TokenService for MockTokenService {
+        fn create_token(
+            &self,
+            _user: &User,
+            _session_id: Option<Uuid>,
+        ) -> Result<String, AuthError> {
+            Ok("token".to_string())
+        }
+
+        fn validate_token(&self, _token: &str) -> Result<TokenData, AuthError> {
+            Ok(TokenData {
+                scopes: self.scopes.clone(),
+                actor: self.actor,
+                ..token_data(self.user_id)
+            })
+        }
+
+        fn revoke_token(&self, _token: &TokenData) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn is_revoked(&self, _token: &TokenData) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+    }
+
+    fn setup() -> (MockUserRepository, MockTokenService) {
+        let email = Email::new("test@example.com").unwrap();
+        let user = User::new(
+            email,
+            HashedPassword::from_hash("hashed".to_string()),
+            Some("Old Name".to_string()),
+        );
+        let token_service = MockTokenService {
+            user_id: user.id().as_uuid(),
+            scopes: None,
+            actor: None,
+        };
+        let users = MockUserRepository::new(user);
+        (users, token_service)
+    }
+
+    fn update(
+        users: &MockUserRepository,
+        token_service: &MockTokenService,
+        audit_log: &MockAuditLog,
+        display_name: Option<&str>,
+    ) -> Result<User, AuthError> {
+        UpdateProfileUseCase::new(users, token_service, audit_log).execute(UpdateProfileCommand {
+            token: "token".to_string(),
+            display_name: display_name.map(str::to_string),
+        })
+    }
+
+    #[test]
+    fn test_update_display_name() {
+        let (users, token_service) = setup();
+        let audit_log = MockAuditLog::default();
+
+        let user = update(&users, &token_service, &audit_log, Some("  New Name ")).unwrap();
+        assert_eq!(user.display_name(), Some("New Name"));
+        assert_eq!(users.updates(), 1);
+
+        // Unchanged names are not written again
+        update(&users, &token_service, &audit_log, Some("New Name")).unwrap();
+        assert_eq!(users.updates(), 1);
+
+        let user = update(&users, &token_service, &audit_log, Some("")).unwrap();
+        assert_eq!(user.display_name(), None);
+        assert_eq!(users.updates(), 2);
+    }
+
+    #[test]
+    fn test_invalid_display_name_rejected() {
+        let (users, token_service) = setup();
+        let audit_log = MockAuditLog::default();
+
+        let result = update(&users, &token_service, &audit_log, Some(&"x".repeat(101)));
+
+        assert!(matches!(result, Err(AuthError::InvalidDisplayName)));
+        assert_eq!(users.user.lock().unwrap().display_name(), Some("Old Name"));
+
+        let events = audit_log.events.lock().unwrap();
+        assert_eq!(events[0].action(), AuditAction::ProfileUpdate);
+        assert_eq!(events[0].outcome(), AuditOutcome::Failure);
+        assert_eq!(
+            events[0].target_id(),
+            Some(users.user_id())
+        );
+    }
+
+    #[test]
+    fn test_api_key_forbidden() {
+        let (users, mut token_service) = setup();
+        token_service.scopes = Some(vec![Permission::ReadUsers]);
+
+        let result = update(
+            &users,
+            &token_service,
+            &MockAuditLog::default(),
+            Some("New Name"),
+        );
+
+        assert!(matches!(result, Err(AuthError::Forbidden)));
+        assert_eq!(users.updates(), 0);
+    }
+
+    #[test]
+    fn test_impersonation_forbidden_and_audited_as_administrator() {
+        let (users, mut token_service) = setup();
+        let admin_id = Uuid::new_v4();
+        token_service.actor = Some(admin_id);
+        let audit_log = MockAuditLog::default();
+
+        let result = update(&users, &token_service, &audit_log, Some("New Name"));
+
+        assert!(matches!(result, Err(AuthError::Forbidden)));
+        assert_eq!(users.updates(), 0);
+        let events = audit_log.events.lock().unwrap();
+        assert_eq!(events[0].actor_id(), Some(admin_id));
+        assert_eq!(events[0].target_id(), Some(token_service.user_id));
+    }
+}
@@ -0,0 +1,229 @@
+//! Verify email use case
+//!
+//! Confirms ownership of an email address using a mailed verification
+//! token.
+
+use chrono::Utc;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::domain::auth::{OpaqueTokenGenerator, UserRepository};
+use crate::domain::email_verification::EmailVerificationTokenRepository;
+use crate::domain::error::AuthError;
+
+/// Input for verifying an email address
+#[derive(Debug)]
+pub struct VerifyEmailCommand {
+    pub token: String,
+}
+
+/// Output after a successful verification
+#[derive(Debug)]
+pub struct VerifyEmailResult {
+    pub user_id: Uuid,
+    pub email: String,
+}
+
+/// Use case for verifying an email address
+pub struct VerifyEmailUseCase<'a, R: ?Sized, V: ?Sized, G: ?Sized> {
+    user_repository: &'a R,
+    verification_token_repository: &'a V,
+    token_generator: &'a G,
+}
+
+impl<'a, R, V, G> VerifyEmailUseCase<'a, R, V, G>
+where
+    R: UserRepository + ?Sized,
+    V: EmailVerificationTokenRepository + ?Sized,
+    G: OpaqueTokenGenerator + ?Sized,
+{
+    /// Create a new use case instance
+    pub fn new(
+        user_repository: &'a R,
+        verification_token_repository: &'a V,
+        token_generator: &'a G,
+    ) -> Self {
+        Self {
+            user_repository,
+            verification_token_repository,
+            token_generator,
+        }
+    }
+
+    /// Execute the verification
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidToken` if the token is unknown, already used, or
+    ///   was sent to an address the user no longer has
+    /// - `AuthError::TokenExpired` if the token has expired
+    /// - `AuthError::Internal` on infrastructure failures
+    pub fn execute(&self, command: VerifyEmailCommand) -> Result<VerifyEmailResult, AuthError> {
+        let token = self
+            .verification_token_repository
+            .find_by_hash(&self.token_generator.hash(&command.token))?;
+
+        if token.is_used() {
+            return Err(AuthError::InvalidToken);
+        }
+
+        if token.is_expired(Utc::now()) {
+            return Err(AuthError::TokenExpired);
+        }
+
+        let mut user = self
+            .user_repository
+            .find_by_id(token.user_id())
+            .map_err(|e| match e {
+                AuthError::UserNotFound => AuthError::InvalidToken,
+                other => other,
+            })?;
+
+        if user.email().as_str() != token.email() {
+            return Err(AuthError::InvalidToken);
+        }
+
+        if !self.verification_token_repository.consume(&token)? {
+            return Err(AuthError::InvalidToken);
+        }
+
+        if user.verify_email() {
+            user = self.user_repository.update(&user)?;
+            info!(user_id = %user.id().as_uuid(), "Email address verified");
+        }
+
+        Ok(VerifyEmailResult {
+            user_id: user.id().as_uuid(),
+            email: user.email().as_str().to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use chrono::{DateTime, Duration};
+
+    use super::*;
+    use crate::application::commands::test_support::MockUserRepository;
+    use crate::domain::email_verification::EmailVerificationToken;
+    use crate::domain::user::{Email, HashedPassword, User};
+
+    // Verification token store holding a single token
+    struct MockVerificationTokenRepository {
+        token: RefCell<EmailVerificationToken>,
+    }
+
+    impl EmailVerificationTokenRepository for MockVerificationTokenRepository {
+        fn create(&self, _token: &EmailVerificationToken) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn find_by_hash(&self, token_hash: &str) -> Result<EmailVerificationToken, AuthError> {
+            let token = self.token.borrow();
+            if token.token_hash() == token_hash {
+                Ok(token.clone())
+            } else {
+                Err(AuthError::InvalidToken)
+            }
+        }
+
+        fn consume(&self, _token: &EmailVerificationToken) -> Result<bool, AuthError> {
+            let current = self.token.borrow().clone();
+            if current.is_used() {
+                return Ok(false);
+            }
+            *self.token.borrow_mut() = EmailVerificationToken::from_persistence(
+                current.id(),
+                current.user_id(),
+                current.email().to_string(),
+                current.token_hash().to_string(),
+                current.expires_at(),
+                current.created_at(),
+                Some(Utc::now()),
+            );
+            Ok(true)
+        }
+
+        fn purge_expired(&self, _now: DateTime<Utc>) -> Result<usize, AuthError> {
+            Ok(0)
+        }
+    }
+
+    // Mock generator hashing by prefix
+    struct MockTokenGenerator;
+
+    impl OpaqueTokenGenerator for MockTokenGenerator {
+        fn generate(&self) -> String {
+            "verify_secret".to_string()
+        }
+
+        fn hash(&self, token: &str) -> String {
+            format!("hashed_{}", token)
+        }
+    }
+
+    fn setup(
+        sent_to: &str,
+        ttl: Duration,
+    ) -> (MockUserRepository, MockVerificationTokenRepository) {
+        let email = Email::new("test@example.com").unwrap();
+        let user = User::new(email, HashedPassword::from_hash("hashed".to_string()), None);
+        let token = EmailVerificationToken::issue(
+            user.id().as_uuid(),
+            sent_to.to_string(),
+            "hashed_verify_secret".to_string(),
+            ttl,
+        );
+        (
+            MockUserRepository::new(user),
+            MockVerificationTokenRepository {
+                token: RefCell::new(token),
+            },
+        )
+    }
+
+    fn verify(
+        users: &MockUserRepository,
+        tokens: &MockVerificationTokenRepository,
+        token: &str,
+    ) -> Result<VerifyEmailResult, AuthError> {
+        VerifyEmailUseCase::new(users, tokens, &MockTokenGenerator).execute(VerifyEmailCommand {
+            token: token.to_string(),
+        })
+    }
+
+    #[test]
+    fn test_verifies_email_once() {
+        let (users, tokens) = setup("test@example.com", Duration::hours(24));
+
+        let result = verify(&users, &tokens, "verify_secret").unwrap();
+
+        assert_eq!(result.email, "test@example.com");
+        assert!(users.user.lock().unwrap().is_email_verified());
+        assert!(matches!(
+            verify(&users, &tokens, "verify_secret"),
+            Err(AuthError::InvalidToken)
+        ));
+    }
+
+    #[test]
+    fn test_expired_token() {
+        let (users, tokens) = setup("test@example.com", Duration::seconds(-1));
+
+        let result = verify(&users, &tokens, "verify_secret");
+
+        assert!(matches!(result, Err(AuthError::TokenExpired)));
+        assert!(!users.user.lock().unwrap().is_email_verified());
+    }
+
+    #[test]
+    fn test_token_for_previous_address() {
+        let (users, tokens) = setup("old@example.com", Duration::hours(24));
+
+        let result = verify(&users, &tokens, "verify_secret");
+
+        assert!(matches!(result, Err(AuthError::InvalidToken)));
+        assert!(!users.user.lock().unwrap().is_email_verified());
+    }
+}
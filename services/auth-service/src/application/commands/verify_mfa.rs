@@ -0,0 +1,673 @@
+//! Verify MFA use case
+//!
+//! Second step of logging in to an account with two-factor authentication:
+//! exchanges the challenge handed out for a correct password, together with
+//! a TOTP or recovery code, for an access token and refresh token. Each TOTP
+//! code and each recovery code is accepted only once, and a challenge is
+//! burned after too many wrong codes. Wrong codes also count towards the
+//! account's login lockout.
+
+use std::net::IpAddr;
+
+use chrono::{Duration, Utc};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use super::login_user::{ensure_not_locked, record_failed_login, start_session, LoginUserResult};
+use crate::domain::audit::{AsyncAuditLog, AuditAction, AuditEvent};
+use crate::domain::auth::{AsyncUserRepository, OpaqueTokenGenerator, TokenService};
+use crate::domain::error::AuthError;
+use crate::domain::lockout::{AsyncLoginThrottleRepository, LockoutPolicy, ThrottleKey};
+use crate::domain::mfa::{
+    is_totp_code, normalize_recovery_code, AsyncMfaChallengeRepository, AsyncMfaRepository,
+    TotpCredential, TotpService,
+};
+use crate::domain::refresh_token::AsyncRefreshTokenRepository;
+use crate::domain::session::{AsyncSessionRepository, SessionClient};
+
+/// Input for completing a two-factor login
+#[derive(Debug)]
+pub struct VerifyMfaCommand {
+    /// Challenge returned by the password step
+    pub challenge_token: String,
+    /// TOTP code or recovery code
+    pub code: String,
+    /// Source address of the request, when known
+    pub client_ip: Option<IpAddr>,
+    /// `User-Agent` of the client, recorded with the session
+    pub user_agent: Option<String>,
+}
+
+/// Use case for completing a two-factor login
+pub struct VerifyMfaUseCase<
+    'a,
+    R: ?Sized,
+    C: ?Sized,
+    M: ?Sized,
+    P: ?Sized,
+    T: ?Sized,
+    S: ?Sized,
+    D: ?Sized,
+    G: ?Sized,
+    L: ?Sized,
+    A: ?Sized,
+> {
+    user_repository: &'a R,
+    mfa_challenge_repository: &'a C,
+    mfa_repository: &'a M,
+    totp_service: &'a P,
+    token_service: &'a T,
+    refresh_token_repository: &'a S,
+    session_repository: &'a D,
+    token_generator: &'a G,
+    throttle_repository: &'a L,
+    lockout_policy: LockoutPolicy,
+    refresh_token_ttl: Duration,
+    audit_log: &'a A,
+}
+
+impl<'a, R, C, M, P, T, S, D, G, L, A> VerifyMfaUseCase<'a, R, C, M, P, T, S, D, G, L, A>
+where
+    R: AsyncUserRepository + ?Sized,
+    C: AsyncMfaChallengeRepository + ?Sized,
+    M: AsyncMfaRepository + ?Sized,
+    P: TotpService + ?Sized,
+    T: TokenService + ?Sized,
+    S: AsyncRefreshTokenRepository + ?Sized,
+    D: AsyncSessionRepository + ?Sized,
+    G: OpaqueTokenGenerator + ?Sized,
+    L: AsyncLoginThrottleRepository + ?Sized,
+    A: AsyncAuditLog + ?Sized,
+{
+    /// Create a new use case instance
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        user_repository: &'a R,
+        mfa_challenge_repository: &'a C,
+        mfa_repository: &'a M,
+        totp_service: &'a P,
+        token_service: &'a T,
+        refresh_token_repository: &'a S,
+        session_repository: &'a D,
+        token_generator: &'a G,
+        throttle_repository: &'a L,
+        lockout_policy: LockoutPolicy,
+        refresh_token_ttl: Duration,
+        audit_log: &'a A,
+    ) -> Self {
+        Self {
+            user_repository,
+            mfa_challenge_repository,
+            mfa_repository,
+            totp_service,
+            token_service,
+            refresh_token_repository,
+            session_repository,
+            token_generator,
+            throttle_repository,
+            lockout_policy,
+            refresh_token_ttl,
+            audit_log,
+        }
+    }
+
+    /// Execute the verification
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidToken` if the challenge is unknown, used or burned
+    /// - `AuthError::TokenExpired` if the challenge has expired
+    /// - `AuthError::AccountInactive` if user account is deactivated
+    /// - `AuthError::AccountLocked` if the account is locked out
+    /// - `AuthError::InvalidMfaCode` if the code is wrong or was already used
+    /// - `AuthError::Internal` on infrastructure failures
+    pub async fn execute(&self, command: VerifyMfaCommand) -> Result<LoginUserResult, AuthError> {
+        let mut user_id = None;
+        let result = self.verify(command, &mut user_id).await;
+
+        let mut event = AuditEvent::new(AuditAction::MfaLogin).with_result(&result);
+        if let Some(user_id) = user_id {
+            event = event.with_target(user_id);
+            if result.is_ok() {
+                event = event.with_actor(user_id);
+            }
+        }
+        self.audit_log.record(event).await;
+        result
+    }
+
+    /// Run the verification, leaving the id of the account in `user_id`
+    async fn verify(
+        &self,
+        command: VerifyMfaCommand,
+        user_id: &mut Option<Uuid>,
+    ) -> Result<LoginUserResult, AuthError> {
+        let challenge = self
+            .mfa_challenge_repository
+            .find_by_hash(&self.token_generator.hash(&command.challenge_token))
+            .await?;
+        *user_id = Some(challenge.user_id());
+
+        if challenge.is_used() {
+            return Err(AuthError::InvalidToken);
+        }
+
+        let now = Utc::now();
+        if challenge.is_expired(now) {
+            return Err(AuthError::TokenExpired);
+        }
+
+        let user = self
+            .user_repository
+            .find_by_id(challenge.user_id())
+            .await
+            .map_err(|e| match e {
+                AuthError::UserNotFound => AuthError::InvalidToken,
+                other => other,
+            })?;
+
+        if !user.is_active() {
+            return Err(AuthError::AccountInactive);
+        }
+
+        let throttle_keys = [ThrottleKey::email(user.email().as_str())];
+        ensure_not_locked(self.throttle_repository, &throttle_keys, now).await?;
+
+        let credential = self
+            .mfa_repository
+            .find_totp(challenge.user_id())
+            .await?
+            .filter(TotpCredential::is_confirmed)
+            .ok_or(AuthError::InvalidToken)?;
+
+        let Some(code) = self.check_code(&credential, &command.code).await? else {
+            let attempts = self
+                .mfa_challenge_repository
+                .record_failed_attempt(&challenge)
+                .await?;
+            warn!(user_id = %challenge.user_id(), attempts, "Wrong two-factor code");
+            record_failed_login(
+                self.throttle_repository,
+                &self.lockout_policy,
+                &throttle_keys,
+                now,
+            )
+            .await?;
+            return Err(AuthError::InvalidMfaCode);
+        };
+
+        if !self.mfa_challenge_repository.consume(&challenge).await? {
+            // Another request completed this login first
+            return Err(AuthError::InvalidToken);
+        }
+
+        // A recovery code is only spent once the challenge is ours
+        if let AcceptedCode::Recovery(code_hash) = code {
+            if !self
+                .mfa_repository
+                .consume_recovery_code(challenge.user_id(), &code_hash)
+                .await?
+            {
+                // Another login used the same code in the meantime
+                return Err(AuthError::InvalidMfaCode);
+            }
+            info!(user_id = %challenge.user_id(), "Recovery code used");
+        }
+
+        self.throttle_repository.clear(&throttle_keys[0]).await?;
+
+        let client = SessionClient {
+            user_agent: command.user_agent,
+            ip_address: command.client_ip,
+        };
+        start_session(
+            &user,
+            &client,
+            self.token_service,
+            self.refresh_token_repository,
+            self.session_repository,
+            self.token_generator,
+            self.refresh_token_ttl,
+        )
+        .await
+    }
+
+    /// Check a TOTP or recovery code
+    ///
+    /// A valid TOTP code is recorded right away, so it cannot be replayed.
+    /// A recovery code is left unused for the caller to consume.
+    async fn check_code(
+        &self,
+        credential: &TotpCredential,
+        code: &str,
+    ) -> Result<Option<AcceptedCode>, AuthError> {
+        let code: String = code.chars().filter(|c| !c.is_whitespace()).collect();
+        let user_id = credential.user_id();
+
+        if is_totp_code(&code) {
+            let accepted = match self
+                .totp_service
+                .verify(credential.secret(), &code, Utc::now())?
+            {
+                // Rejects codes that were already accepted once
+                Some(step) => self.mfa_repository.record_totp_step(user_id, step).await?,
+                None => false,
+            };
+            return Ok(accepted.then_some(AcceptedCode::Totp));
+        }
+
+        let normalized = normalize_recovery_code(&code);
+        if normalized.is_empty() {
+            return Ok(None);
+        }
+
+        let code_hash = self.token_generator.hash(&normalized);
+        let found = self
+            .mfa_repository
+            .has_recovery_code(user_id, &code_hash)
+            .await?;
+        Ok(found.then_some(AcceptedCode::Recovery(code_hash)))
+    }
+}
+
+/// A second factor that passed verification
+enum AcceptedCode {
+    /// TOTP code, already recorded against replays
+    Totp,
+    /// Hash of an unused recovery code
+    Recovery(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Mutex;
+
+    use chrono::DateTime;
+
+    use super::*;
+    use crate::application::commands::test_support::{
+        MockAuditLog, MockRefreshTokenRepository, MockSessionRepository, MockUserRepository,
+    };
+    use crate::domain::audit::AuditOutcome;
+    use crate::domain::auth::{BoxFuture, TokenData};
+    use crate::domain::mfa::{MfaChallenge, MAX_MFA_ATTEMPTS};
+    use crate::domain::user::{Email, HashedPassword, User};
+    use crate::infrastructure::memory::in_memory_login_throttle_repository::InMemoryLoginThrottleRepository;
+
+    // Challenge store holding a single challenge
+    struct MockMfaChallengeRepository {
+        challenge: Mutex<MfaChallenge>,
+        // Simulates a concurrent request consuming the challenge first
+        consumed_elsewhere: AtomicBool,
+    }
+
+    impl MockMfaChallengeRepository {
+        fn challenge(&self) -> MfaChallenge {
+            self.challenge.lock().unwrap().clone()
+        }
+
+        fn replace(&self, failed_attempts: i32, used: bool) {
+            let mut challenge = self.challenge.lock().unwrap();
+            *challenge = MfaChallenge::from_persistence(
+                challenge.id(),
+                challenge.user_id(),
+                challenge.token_hash().to_string(),
+                failed_attempts,
+                challenge.expires_at(),
+                challenge.created_at(),
+                used.then(Utc::now),
+            );
+        }
+    }
+
+    impl AsyncMfaChallengeRepository for MockMfaChallengeRepository {
+        fn create<'a>(
+            &'a self,
+            _challenge: &'a MfaChallenge,
+        ) -> BoxFuture<'a, Result<(), AuthError>> {
+            Box::pin(async { Ok(()) })
+        }
+
+        fn find_by_hash<'a>(
+            &'a self,
+            token_hash: &'a str,
+        ) -> BoxFuture<'a, Result<MfaChallenge, AuthError>> {
+            let challenge = self.challenge();
+            Box::pin(async move {
+                if challenge.token_hash() == token_hash {
+                    Ok(challenge)
+                } else {
+                    Err(AuthError::InvalidToken)
+                }
+            })
+        }
+
+        fn record_failed_attempt<'a>(
+            &'a self,
+            _challenge: &'a MfaChallenge,
+        ) -> BoxFuture<'a, Result<i32, AuthError>> {
+            let attempts = self.challenge().failed_attempts() + 1;
+            self.replace(attempts, attempts >= MAX_MFA_ATTEMPTS);
+            Box::pin(async move { Ok(attempts) })
+        }
+
+        fn consume<'a>(
+            &'a self,
+            _challenge: &'a MfaChallenge,
+        ) -> BoxFuture<'a, Result<bool, AuthError>> {
+            let current = self.challenge();
+            let consumed =
+                !current.is_used() && !self.consumed_elsewhere.load(Ordering::SeqCst);
+            if consumed {
+                self.replace(current.failed_attempts(), true);
+            }
+            Box::pin(async move { Ok(consumed) })
+        }
+    }
+
+    // MFA repository with a confirmed credential and one recovery code
+    struct MockMfaRepository {
+        credential: TotpCredential,
+        last_used_step: Mutex<Option<i64>>,
+        recovery_code_hashes: Mutex<Vec<String>>,
+    }
+
+    impl AsyncMfaRepository for MockMfaRepository {
+        fn find_totp(
+            &self,
+            _user_id: Uuid,
+        ) -> BoxFuture<'_, Result<Option<TotpCredential>, AuthError>> {
+            let credential = self.credential.clone();
+            Box::pin(async { Ok(Some(credential)) })
+        }
+
+        fn record_totp_step(
+            &self,
+            _user_id: Uuid,
+            step: i64,
+        ) -> BoxFuture<'_, Result<bool, AuthError>> {
+            let mut last = self.last_used_step.lock().unwrap();
+            let accepted = !last.is_some_and(|last| last >= step);
+            if accepted {
+                *last = Some(step);
+            }
+            Box::pin(async move { Ok(accepted) })
+        }
+
+        fn has_recovery_code<'a>(
+            &'a self,
+            _user_id: Uuid,
+            code_hash: &'a str,
+        ) -> BoxFuture<'a, Result<bool, AuthError>> {
+            let found = self
+                .recovery_code_hashes
+                .lock()
+                .unwrap()
+                .iter()
+                .any(|hash| hash == code_hash);
+            Box::pin(async move { Ok(found) })
+        }
+
+        fn consume_recovery_code<'a>(
+            &'a self,
+            _user_id: Uuid,
+            code_hash: &'a str,
+        ) -> BoxFuture<'a, Result<bool, AuthError>> {
+            let mut hashes = self.recovery_code_hashes.lock().unwrap();
+            let before = hashes.len();
+            hashes.retain(|hash| hash != code_hash);
+            let consumed = hashes.len() < before;
+            Box::pin(async move { Ok(consumed) })
+        }
+    }
+
+    // TOTP service accepting "123456" in time step 42
+    struct MockTotpService;
+
+    impl TotpService for MockTotpService {
+        fn generate_secret(&self) -> String {
+            "JBSWY3DPEHPK3PXP".to_string()
+        }
+
+        fn provisioning_uri(&self, secret: &str, _account_name: &str) -> Result<String, AuthError> {
+            Ok(format!("otpauth://totp/Test?secret={}", secret))
+        }
+
+        fn verify(
+            &self,
+            _secret: &str,
+            code: &str,
+            _now: DateTime<Utc>,
+        ) -> Result<Option<i64>, AuthError> {
+            Ok((code == "123456").then_some(42))
+        }
+
+        fn generate_recovery_code(&self) -> String {
+            "AAAAA-BBBBB".to_string()
+        }
+    }
+
+    // Mock token service
+    struct MockTokenService;
+
+    impl TokenService for MockTokenService {
+        fn create_token(
+            &self,
+            _user: &User,
+            _session_id: Option<Uuid>,
+        ) -> Result<String, AuthError> {
+            Ok("mock_token".to_string())
+        }
+
+        fn validate_token(&self, _token: &str) -> Result<TokenData, AuthError> {
+            Err(AuthError::InvalidToken)
+        }
+
+        fn revoke_token(&self, _token: &TokenData) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn is_revoked(&self, _token: &TokenData) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+    }
+
+    // Mock generator hashing by prefix
+    struct MockTokenGenerator;
+
+    impl OpaqueTokenGenerator for MockTokenGenerator {
+        fn generate(&self) -> String {
+            "refresh_secret".to_string()
+        }
+
+        fn hash(&self, token: &str) -> String {
+            format!("hashed_{}", token)
+        }
+    }
+
+    fn lockout_policy() -> LockoutPolicy {
+        LockoutPolicy {
+            email_threshold: 10,
+            ip_threshold: 20,
+            base_lockout: Duration::seconds(30),
+            max_lockout: Duration::minutes(10),
+            reset_after: Duration::minutes(15),
+        }
+    }
+
+    struct Fixture {
+        users: MockUserRepository,
+        challenges: MockMfaChallengeRepository,
+        mfa: MockMfaRepository,
+        refresh_tokens: MockRefreshTokenRepository,
+        throttles: InMemoryLoginThrottleRepository,
+        audit_log: MockAuditLog,
+    }
+
+    impl Fixture {
+        fn new(challenge_ttl: Duration) -> Self {
+            let email = Email::new("test@example.com").unwrap();
+            let user = User::new(email, HashedPassword::from_hash("hashed".to_string()), None);
+            let user_id = user.id().as_uuid();
+            Self {
+                users: MockUserRepository::new(user),
+                challenges: MockMfaChallengeRepository {
+                    challenge: Mutex::new(MfaChallenge::issue(
+                        user_id,
+                        "hashed_challenge".to_string(),
+                        challenge_ttl,
+                    )),
+                    consumed_elsewhere: AtomicBool::new(false),
+                },
+                mfa: MockMfaRepository {
+                    credential: TotpCredential::from_persistence(
+                        user_id,
+                        "JBSWY3DPEHPK3PXP".to_string(),
+                        Some(Utc::now()),
+                        Some(41),
+                        Utc::now(),
+                    ),
+                    last_used_step: Mutex::new(Some(41)),
+                    recovery_code_hashes: Mutex::new(vec!["hashed_AAAAABBBBB".to_string()]),
+                },
+                refresh_tokens: MockRefreshTokenRepository::default(),
+                throttles: InMemoryLoginThrottleRepository::new(),
+                audit_log: MockAuditLog::default(),
+            }
+        }
+
+        async fn verify(&self, code: &str) -> Result<LoginUserResult, AuthError> {
+            VerifyMfaUseCase::new(
+                &self.users,
+                &self.challenges,
+                &self.mfa,
+                &MockTotpService,
+                &MockTokenService,
+                &self.refresh_tokens,
+                &MockSessionRepository,
+                &MockTokenGenerator,
+                &self.throttles,
+                lockout_policy(),
+                Duration::days(30),
+                &self.audit_log,
+            )
+            .execute(VerifyMfaCommand {
+                challenge_token: "challenge".to_string(),
+                code: code.to_string(),
+                client_ip: None,
+                user_agent: None,
+            })
+            .await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_totp_code_completes_login() {
+        let fixture = Fixture::new(Duration::minutes(5));
+
+        let result = fixture.verify("123 456").await.unwrap();
+
+        assert_eq!(result.token, "mock_token");
+        assert_eq!(result.refresh_token, "refresh_secret");
+        assert_eq!(fixture.refresh_tokens.created.lock().unwrap().len(), 1);
+        assert!(fixture.challenges.challenge().is_used());
+        assert_eq!(*fixture.mfa.last_used_step.lock().unwrap(), Some(42));
+    }
+
+    #[tokio::test]
+    async fn test_totp_code_cannot_be_replayed() {
+        let fixture = Fixture::new(Duration::minutes(5));
+        *fixture.mfa.last_used_step.lock().unwrap() = Some(42);
+
+        let result = fixture.verify("123456").await;
+
+        assert!(matches!(result, Err(AuthError::InvalidMfaCode)));
+        assert_eq!(fixture.challenges.challenge().failed_attempts(), 1);
+        assert!(fixture.refresh_tokens.created.lock().unwrap().is_empty());
+
+        let events = fixture.audit_log.events.lock().unwrap();
+        assert_eq!(events[0].action(), AuditAction::MfaLogin);
+        assert_eq!(events[0].outcome(), AuditOutcome::Failure);
+        assert_eq!(events[0].actor_id(), None);
+        assert_eq!(
+            events[0].target_id(),
+            Some(fixture.users.user_id())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_recovery_code_is_single_use() {
+        let fixture = Fixture::new(Duration::minutes(5));
+
+        fixture.verify("aaaaa-bbbbb").await.unwrap();
+        // Same code with a fresh challenge
+        fixture.challenges.replace(0, false);
+        let result = fixture.verify("AAAAA-BBBBB").await;
+
+        assert!(matches!(result, Err(AuthError::InvalidMfaCode)));
+    }
+
+    #[tokio::test]
+    async fn test_recovery_code_survives_losing_the_challenge() {
+        let fixture = Fixture::new(Duration::minutes(5));
+        fixture.challenges.consumed_elsewhere.store(true, Ordering::SeqCst);
+
+        let result = fixture.verify("AAAAA-BBBBB").await;
+
+        assert!(matches!(result, Err(AuthError::InvalidToken)));
+        assert_eq!(
+            *fixture.mfa.recovery_code_hashes.lock().unwrap(),
+            ["hashed_AAAAABBBBB"]
+        );
+        assert!(fixture.refresh_tokens.created.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_challenge_burned_after_too_many_attempts() {
+        let fixture = Fixture::new(Duration::minutes(5));
+
+        for _ in 0..MAX_MFA_ATTEMPTS {
+            let result = fixture.verify("000000").await;
+            assert!(matches!(result, Err(AuthError::InvalidMfaCode)));
+        }
+        let result = fixture.verify("123456").await;
+
+        assert!(matches!(result, Err(AuthError::InvalidToken)));
+        // Every wrong code counted towards the account lockout
+        let throttle = fixture
+            .throttles
+            .find(&ThrottleKey::email("test@example.com"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(throttle.failed_attempts(), MAX_MFA_ATTEMPTS);
+    }
+
+    #[tokio::test]
+    async fn test_locked_account_cannot_complete_login() {
+        let fixture = Fixture::new(Duration::minutes(5));
+        let policy = LockoutPolicy {
+            email_threshold: 1,
+            ..lockout_policy()
+        };
+        fixture
+            .throttles
+            .record_failure(&ThrottleKey::email("test@example.com"), &policy, Utc::now())
+            .await
+            .unwrap();
+
+        let result = fixture.verify("123456").await;
+
+        assert!(matches!(result, Err(AuthError::AccountLocked { .. })));
+        assert!(fixture.refresh_tokens.created.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_expired_challenge() {
+        let fixture = Fixture::new(Duration::seconds(-1));
+
+        let result = fixture.verify("123456").await;
+
+        assert!(matches!(result, Err(AuthError::TokenExpired)));
+    }
+}
@@ -0,0 +1,465 @@
+//! auth-admin - operator CLI for the Auth Service
+//!
+//! Runs migrations and one-off account operations against the service's
+//! database, with the same configuration as the service. Account changes
+//! go through the application use cases, authenticated as the operator
+//! (see `OperatorTokenService`), so they are checked and audited like the
+//! admin API.
+
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufRead, BufWriter, IsTerminal, Write};
+
+use clap::{Parser, Subcommand};
+use diesel_migrations::MigrationHarness;
+use serde_json::json;
+use uuid::Uuid;
+
+use auth_service::application::commands::{
+    assign_role::{AssignRoleCommand, AssignRoleUseCase},
+    list_users::{ListUsersCommand, ListUsersUseCase},
+    register_user::{RegisterUserCommand, RegisterUserUseCase},
+    rotate_signing_key::{RotateSigningKeyCommand, RotateSigningKeyUseCase},
+    set_password::{SetPasswordCommand, SetPasswordUseCase},
+    set_user_active::{SetUserActiveCommand, SetUserActiveUseCase},
+};
+use auth_service::domain::auth::UserRepository;
+use auth_service::domain::mailer::Mailer;
+use auth_service::domain::password_policy::PasswordPolicy;
+use auth_service::domain::role::Permission;
+use auth_service::domain::signing_key::SigningAlgorithm;
+use auth_service::domain::user::{Email, User};
+use auth_service::domain::user_search::MAX_PAGE_SIZE;
+use auth_service::infrastructure::{
+    config::Config,
+    db::async_audit_log_diesel::AsyncDieselAuditLog,
+    db::async_email_verification_repository_diesel::AsyncDieselEmailVerificationTokenRepository,
+    db::async_user_repository_diesel::AsyncDieselUserRepository,
+    db::audit_event_repository_diesel::DieselAuditEventRepository,
+    db::connection::{
+        create_async_connection_pool, create_connection_pool, DbPool, MIGRATIONS,
+    },
+    db::refresh_token_repository_diesel::DieselRefreshTokenRepository,
+    db::signing_key_repository_diesel::DieselSigningKeyRepository,
+    db::user_repository_diesel::DieselUserRepository,
+    mail::{file_mailer::FileMailer, log_mailer::LogMailer},
+    security::{
+        argon2_password_hasher::Argon2PasswordHasher, breached_password_file::BreachedPasswordFile,
+        opaque_token_generator::RandomOpaqueTokenGenerator,
+        operator_token_service::OperatorTokenService,
+        signing_key_generator::RandomSigningKeyGenerator,
+    },
+};
+
+/// Connections held by the CLI; commands run one query at a time
+const POOL_SIZE: u32 = 2;
+
+#[derive(Parser)]
+#[command(
+    name = "auth-admin",
+    about = "Operate the Auth Service database and accounts"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Apply, revert or list database migrations
+    Migrate {
+        #[command(subcommand)]
+        action: MigrateAction,
+    },
+    /// Register the initial administrator
+    Seed {
+        #[arg(long, default_value = "admin@example.com")]
+        email: String,
+        #[arg(long, default_value = "System Administrator")]
+        display_name: String,
+        /// Read from standard input when omitted
+        #[arg(long, env = "AUTH_ADMIN_PASSWORD", hide_env_values = true)]
+        password: Option<String>,
+        /// Grant the admin role if the account already exists, instead of failing
+        #[arg(long)]
+        grant_existing: bool,
+    },
+    /// Register a user, optionally granting roles
+    CreateUser {
+        #[arg(long)]
+        email: String,
+        #[arg(long)]
+        display_name: Option<String>,
+        /// Role to grant on top of customer; repeatable
+        #[arg(long = "role")]
+        roles: Vec<String>,
+        /// Read from standard input when omitted
+        #[arg(long, env = "AUTH_ADMIN_PASSWORD", hide_env_values = true)]
+        password: Option<String>,
+    },
+    /// Replace a user's password and end their sessions
+    SetPassword {
+        /// Email address or ID of the user
+        user: String,
+        /// Read from standard input when omitted
+        #[arg(long, env = "AUTH_ADMIN_PASSWORD", hide_env_values = true)]
+        password: Option<String>,
+    },
+    /// Suspend a user and revoke their refresh tokens
+    Deactivate {
+        /// Email address or ID of the user
+        user: String,
+    },
+    /// Grant a user a role
+    AssignRole {
+        /// Email address or ID of the user
+        user: String,
+        /// customer, organizer, support_agent or admin
+        role: String,
+    },
+    /// Generate a new JWT signing key, retiring the current one
+    RotateSigningKey {
+        /// Defaults to AUTH_JWT_ALGORITHM
+        #[arg(long)]
+        algorithm: Option<String>,
+    },
+    /// Write every user as one JSON object per line
+    ExportUsers {
+        /// Defaults to standard output
+        #[arg(long)]
+        output: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum MigrateAction {
+    /// Apply pending migrations
+    Up,
+    /// Revert the latest migration
+    Down,
+    /// List applied and pending migrations
+    Status,
+}
+
+fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let cli = Cli::parse();
+
+    dotenvy::dotenv().ok();
+    let config = Config::from_env()?;
+
+    // Logs go to stderr, keeping stdout for command output
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::from_default_env()
+                .add_directive("auth_service=info".parse().unwrap()),
+        )
+        .with_writer(io::stderr)
+        .init();
+
+    let pool = create_connection_pool(&config.database_url, POOL_SIZE)?;
+
+    match cli.command {
+        Command::Migrate { action } => migrate(&pool, action),
+        Command::Seed {
+            email,
+            display_name,
+            password,
+            grant_existing,
+        } => seed(
+            &pool,
+            &config,
+            email,
+            display_name,
+            password,
+            grant_existing,
+        ),
+        Command::CreateUser {
+            email,
+            display_name,
+            roles,
+            password,
+        } => create_user(&pool, &config, email, display_name, roles, password),
+        Command::SetPassword { user, password } => {
+            let users = DieselUserRepository::new(pool.clone());
+            let operator = OperatorTokenService::new(vec![Permission::ManageUsers]);
+            let user_id = find_user(&users, &user)?.id().as_uuid();
+
+            SetPasswordUseCase::new(
+                &users,
+                &Argon2PasswordHasher::new(),
+                &password_policy(&config)?,
+                &DieselRefreshTokenRepository::new(pool.clone()),
+                &operator,
+                &DieselAuditEventRepository::new(pool.clone()),
+            )
+            .execute(SetPasswordCommand {
+                token: operator.token().to_string(),
+                user_id,
+                new_password: read_password(password)?,
+            })?;
+            println!("Password set for {}", user_id);
+            Ok(())
+        }
+        Command::Deactivate { user } => {
+            let users = DieselUserRepository::new(pool.clone());
+            let operator = OperatorTokenService::new(vec![Permission::ManageUsers]);
+            let user_id = find_user(&users, &user)?.id().as_uuid();
+
+            SetUserActiveUseCase::new(
+                &users,
+                &DieselRefreshTokenRepository::new(pool.clone()),
+                &operator,
+                &DieselAuditEventRepository::new(pool.clone()),
+            )
+            .execute(SetUserActiveCommand {
+                token: operator.token().to_string(),
+                user_id,
+                active: false,
+            })?;
+            println!("Deactivated {}", user_id);
+            Ok(())
+        }
+        Command::AssignRole { user, role } => {
+            let users = DieselUserRepository::new(pool.clone());
+            let user_id = find_user(&users, &user)?.id().as_uuid();
+            assign_roles(&pool, user_id, &[role])
+        }
+        Command::RotateSigningKey { algorithm } => {
+            let algorithm: SigningAlgorithm = algorithm
+                .as_deref()
+                .unwrap_or(&config.jwt_algorithm)
+                .parse()?;
+            let result = RotateSigningKeyUseCase::new(
+                &DieselSigningKeyRepository::new(pool.clone()),
+                &RandomSigningKeyGenerator::new(),
+                config.signing_key_grace(),
+            )
+            .execute(RotateSigningKeyCommand {
+                algorithm,
+                force: true,
+            })?;
+            println!("Signing key {} ({}) is active", result.kid, algorithm);
+            Ok(())
+        }
+        Command::ExportUsers { output } => {
+            let out: Box<dyn Write> = match output {
+                Some(path) => Box::new(File::create(path)?),
+                None => Box::new(io::stdout().lock()),
+            };
+            export_users(&pool, BufWriter::new(out))
+        }
+    }
+}
+
+/// Apply, revert or list the embedded migrations
+fn migrate(pool: &DbPool, action: MigrateAction) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let mut conn = pool.get()?;
+
+    match action {
+        MigrateAction::Up => {
+            let applied = conn.run_pending_migrations(MIGRATIONS)?;
+            if applied.is_empty() {
+                println!("Database is up to date");
+            }
+            for version in applied {
+                println!("Applied {}", version);
+            }
+        }
+        MigrateAction::Down => {
+            let reverted = conn.revert_last_migration(MIGRATIONS)?;
+            println!("Reverted {}", reverted);
+        }
+        MigrateAction::Status => {
+            for version in conn.applied_migrations()? {
+                println!("applied  {}", version);
+            }
+            for migration in conn.pending_migrations(MIGRATIONS)? {
+                println!("pending  {}", migration.name());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Register the administrator like `create-user --role admin`
+///
+/// An existing account is only made an administrator when `grant_existing`
+/// is set, and then through the role assignment use case.
+fn seed(
+    pool: &DbPool,
+    config: &Config,
+    email: String,
+    display_name: String,
+    password: Option<String>,
+    grant_existing: bool,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let admin = vec!["admin".to_string()];
+    let users = DieselUserRepository::new(pool.clone());
+    let normalized = Email::new(&email)?;
+    if !users.exists_by_email(normalized.as_str())? {
+        return create_user(pool, config, email, Some(display_name), admin, password);
+    }
+
+    if !grant_existing {
+        return Err(format!(
+            "{} already exists; pass --grant-existing to make it an administrator",
+            normalized.as_str()
+        )
+        .into());
+    }
+    let user_id = users.find_by_email(normalized.as_str())?.id().as_uuid();
+    assign_roles(pool, user_id, &admin)
+}
+
+/// Register a user through the registration use case, then grant `roles`
+fn create_user(
+    pool: &DbPool,
+    config: &Config,
+    email: String,
+    display_name: Option<String>,
+    roles: Vec<String>,
+    password: Option<String>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    // Without SMTP configured, mail is written to disk or the log
+    let mailer: Box<dyn Mailer> = match &config.mail_dir {
+        Some(dir) => Box::new(FileMailer::new(dir)?),
+        None => Box::new(LogMailer::new()),
+    };
+
+    // Registration runs on the async stores, like the service's
+    let async_pool = create_async_connection_pool(&config.database_url, 1)?;
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    let password = read_password(password)?;
+    let password_policy = password_policy(config)?;
+    let result = runtime.block_on(
+        RegisterUserUseCase::new(
+            &AsyncDieselUserRepository::new(async_pool.clone()),
+            &Argon2PasswordHasher::new(),
+            &password_policy,
+            &AsyncDieselEmailVerificationTokenRepository::new(async_pool.clone()),
+            &RandomOpaqueTokenGenerator::new(),
+            mailer.as_ref(),
+            chrono::Duration::seconds(config.email_verification_token_expiration_secs),
+            &config.email_verification_url,
+            &AsyncDieselAuditLog::new(async_pool),
+        )
+        .execute(RegisterUserCommand {
+            email,
+            password,
+            display_name,
+        }),
+    )?;
+    println!("Created {} ({})", result.email, result.user_id);
+
+    assign_roles(pool, result.user_id, &roles)
+}
+
+/// Grant each of `roles` through the role assignment use case
+fn assign_roles(
+    pool: &DbPool,
+    user_id: Uuid,
+    roles: &[String],
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let users = DieselUserRepository::new(pool.clone());
+    let audit_log = DieselAuditEventRepository::new(pool.clone());
+    let operator = OperatorTokenService::new(vec![Permission::ManageRoles]);
+    let use_case = AssignRoleUseCase::new(&users, &operator, &audit_log);
+
+    for role in roles {
+        let result = use_case.execute(AssignRoleCommand {
+            token: operator.token().to_string(),
+            user_id,
+            role: role.clone(),
+        })?;
+        let roles: Vec<&str> = result.roles.iter().map(|role| role.as_str()).collect();
+        println!("{} now holds {}", user_id, roles.join(", "));
+    }
+    Ok(())
+}
+
+/// Page through every user with the user listing use case
+fn export_users(pool: &DbPool, mut out: impl Write) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let users = DieselUserRepository::new(pool.clone());
+    let operator = OperatorTokenService::new(vec![Permission::ReadUsers]);
+    let use_case = ListUsersUseCase::new(&users, &operator);
+
+    let mut cursor = None;
+    loop {
+        let page = use_case.execute(ListUsersCommand {
+            token: operator.token().to_string(),
+            is_active: None,
+            role: None,
+            created_after: None,
+            created_before: None,
+            email: None,
+            cursor,
+            limit: MAX_PAGE_SIZE,
+        })?;
+
+        for user in &page.users {
+            let roles: Vec<&str> = user.roles().iter().map(|role| role.as_str()).collect();
+            let line = json!({
+                "user_id": user.id().as_uuid(),
+                "email": user.email().as_str(),
+                "display_name": user.display_name(),
+                "is_active": user.is_active(),
+                "email_verified_at": user.email_verified_at(),
+                "roles": roles,
+                "created_at": user.created_at(),
+                "updated_at": user.updated_at(),
+            });
+            writeln!(out, "{}", line)?;
+        }
+
+        match page.next_cursor {
+            Some(next) => cursor = Some(next.encode()),
+            None => break,
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// Find a user by email address or ID
+fn find_user(
+    users: &DieselUserRepository,
+    user: &str,
+) -> Result<User, Box<dyn Error + Send + Sync>> {
+    let user = match Uuid::parse_str(user) {
+        Ok(id) => users.find_by_id(id)?,
+        Err(_) => users.find_by_email(user)?,
+    };
+    Ok(user)
+}
+
+/// Password rules of the service, including the breached password list
+fn password_policy(config: &Config) -> Result<PasswordPolicy, Box<dyn Error + Send + Sync>> {
+    let mut policy = PasswordPolicy::new(config.password_rules());
+    if let Some(path) = &config.breached_passwords_file {
+        policy = policy.with_breached_list(std::sync::Arc::new(BreachedPasswordFile::open(path)?));
+    }
+    Ok(policy)
+}
+
+/// The password given as an option, or else the first line of standard input
+///
+/// Standard input keeps the password out of the process list and shell history.
+fn read_password(password: Option<String>) -> Result<String, Box<dyn Error + Send + Sync>> {
+    if let Some(password) = password {
+        return Ok(password);
+    }
+
+    let stdin = io::stdin();
+    if stdin.is_terminal() {
+        eprint!("Password: ");
+    }
+    let mut line = String::new();
+    stdin.lock().read_line(&mut line)?;
+    let password = line.trim_end_matches(['\r', '\n']).to_string();
+    if password.is_empty() {
+        return Err("No password given".into());
+    }
+    Ok(password)
+}
@@ -0,0 +1,420 @@
+//! API keys for machine clients
+//!
+//! Integrations such as box-office kiosks and partner resellers authenticate
+//! with long-lived, named keys instead of a person's password. A key belongs
+//! to a user and is limited to a subset of that user's permissions; it may
+//! expire and can be revoked at any time. Only a hash of the key is stored,
+//! so the key itself is shown once, when it is created.
+
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+use super::auth::{BoxFuture, Principal, TokenData};
+use super::error::AuthError;
+use super::role::{permissions_for, Permission};
+use super::user::User;
+
+/// Prefix of every API key, so leaked keys are easy to recognise
+pub const API_KEY_PREFIX: &str = "tsk_";
+
+/// Longest accepted key name, in characters
+pub const MAX_API_KEY_NAME_LEN: usize = 100;
+
+/// Last use is recorded at most this often, sparing a write per request
+const LAST_USED_RESOLUTION_SECS: i64 = 60;
+
+/// Check whether a presented credential is an API key rather than a JWT
+#[must_use]
+pub fn is_api_key(token: &str) -> bool {
+    token.starts_with(API_KEY_PREFIX)
+}
+
+/// Public part of a key, shown in listings to tell keys apart
+///
+/// Derived from the key id, so it is unique and reveals nothing secret.
+#[must_use]
+pub fn key_prefix(id: Uuid) -> String {
+    let id = id.simple().to_string();
+    format!("{}{}", API_KEY_PREFIX, &id[..8])
+}
+
+/// Validate and normalize a key name
+///
+/// # Errors
+/// Returns `AuthError::InvalidApiKeyName` if the name is empty, too long or
+/// contains control characters
+pub fn normalize_key_name(value: &str) -> Result<String, AuthError> {
+    let trimmed = value.trim();
+    if trimmed.is_empty()
+        || trimmed.chars().count() > MAX_API_KEY_NAME_LEN
+        || trimmed.chars().any(char::is_control)
+    {
+        return Err(AuthError::InvalidApiKeyName);
+    }
+    Ok(trimmed.to_string())
+}
+
+/// API key entity
+#[derive(Debug, Clone)]
+pub struct ApiKey {
+    id: Uuid,
+    user_id: Uuid,
+    name: String,
+    prefix: String,
+    key_hash: String,
+    scopes: Vec<Permission>,
+    expires_at: Option<DateTime<Utc>>,
+    last_used_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+    revoked_at: Option<DateTime<Utc>>,
+}
+
+impl ApiKey {
+    /// Issue a new key
+    ///
+    /// # Arguments
+    /// * `id` - Key id; the prefix of the key is derived from it
+    /// * `user_id` - Owner of the key
+    /// * `name` - Already normalized name
+    /// * `key_hash` - Hash of the full key given to the client
+    /// * `scopes` - Permissions the key is limited to
+    /// * `expires_at` - End of validity, `None` for a key that never expires
+    #[must_use]
+    pub fn issue(
+        id: Uuid,
+        user_id: Uuid,
+        name: String,
+        key_hash: String,
+        scopes: Vec<Permission>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        let mut scopes = scopes;
+        scopes.sort();
+        scopes.dedup();
+        Self {
+            id,
+            user_id,
+            name,
+            prefix: key_prefix(id),
+            key_hash,
+            scopes,
+            expires_at,
+            last_used_at: None,
+            created_at: Utc::now(),
+            revoked_at: None,
+        }
+    }
+
+    /// Reconstruct a key from persistence
+    #[must_use]
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_persistence(
+        id: Uuid,
+        user_id: Uuid,
+        name: String,
+        prefix: String,
+        key_hash: String,
+        scopes: Vec<Permission>,
+        expires_at: Option<DateTime<Utc>>,
+        last_used_at: Option<DateTime<Utc>>,
+        created_at: DateTime<Utc>,
+        revoked_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        Self {
+            id,
+            user_id,
+            name,
+            prefix,
+            key_hash,
+            scopes,
+            expires_at,
+            last_used_at,
+            created_at,
+            revoked_at,
+        }
+    }
+
+    /// Get the key ID
+    #[must_use]
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// Get the owning user's ID
+    #[must_use]
+    pub fn user_id(&self) -> Uuid {
+        self.user_id
+    }
+
+    /// Get the name given by the owner
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the public prefix of the key
+    #[must_use]
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Get the stored hash of the key
+    #[must_use]
+    pub fn key_hash(&self) -> &str {
+        &self.key_hash
+    }
+
+    /// Get the permissions the key is limited to
+    #[must_use]
+    pub fn scopes(&self) -> &[Permission] {
+        &self.scopes
+    }
+
+    /// Get the expiration timestamp, `None` if the key never expires
+    #[must_use]
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expires_at
+    }
+
+    /// Get the time the key was last used, if ever
+    #[must_use]
+    pub fn last_used_at(&self) -> Option<DateTime<Utc>> {
+        self.last_used_at
+    }
+
+    /// Get the creation timestamp
+    #[must_use]
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    /// Get the revocation timestamp
+    #[must_use]
+    pub fn revoked_at(&self) -> Option<DateTime<Utc>> {
+        self.revoked_at
+    }
+
+    /// Check if the key has been revoked
+    #[must_use]
+    pub fn is_revoked(&self) -> bool {
+        self.revoked_at.is_some()
+    }
+
+    /// Check if the key has expired at `now`
+    #[must_use]
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    /// Check if the last use recorded is too old to leave as it is
+    #[must_use]
+    pub fn needs_last_used_update(&self, now: DateTime<Utc>) -> bool {
+        self.last_used_at
+            .is_none_or(|at| now - at >= Duration::seconds(LAST_USED_RESOLUTION_SECS))
+    }
+
+    /// Authenticate a request made with this key on behalf of `owner`
+    ///
+    /// The key never grants more than its owner currently holds: scopes
+    /// whose permission the owner has since lost are dropped.
+    ///
+    /// # Errors
+    /// - `AuthError::TokenRevoked` if the key was revoked
+    /// - `AuthError::TokenExpired` if the key has expired
+    /// - `AuthError::AccountInactive` if the owner is deactivated
+    pub fn authenticate(&self, owner: &User, now: DateTime<Utc>) -> Result<TokenData, AuthError> {
+        if self.is_revoked() {
+            return Err(AuthError::TokenRevoked);
+        }
+        if self.is_expired(now) {
+            return Err(AuthError::TokenExpired);
+        }
+        if !owner.is_active() {
+            return Err(AuthError::AccountInactive);
+        }
+
+        let held = permissions_for(owner.roles());
+        let scopes = self
+            .scopes
+            .iter()
+            .copied()
+            .filter(|scope| held.contains(scope))
+            .collect();
+
+        Ok(TokenData {
+            user_id: self.user_id,
+            email: owner.email().as_str().to_string(),
+            jti: self.id.to_string(),
+            session_id: None,
+            expires_at: self.expires_at.unwrap_or(DateTime::<Utc>::MAX_UTC),
+            roles: owner.roles().to_vec(),
+            scopes: Some(scopes),
+            principal: Principal::User,
+            actor: None,
+            organization: None,
+            oidc_scopes: None,
+        })
+    }
+}
+
+/// Repository interface for API key persistence
+pub trait ApiKeyRepository {
+    /// Persist a newly issued key
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn create(&self, key: &ApiKey) -> Result<(), AuthError>;
+
+    /// Find a key by the hash of its secret
+    ///
+    /// # Errors
+    /// Returns `AuthError::InvalidToken` if no key has this hash
+    /// Returns `AuthError::Internal` on database errors
+    fn find_by_hash(&self, key_hash: &str) -> Result<ApiKey, AuthError>;
+
+    /// Find a key by its ID
+    ///
+    /// # Errors
+    /// Returns `AuthError::ApiKeyNotFound` if the key doesn't exist
+    /// Returns `AuthError::Internal` on database errors
+    fn find_by_id(&self, id: Uuid) -> Result<ApiKey, AuthError>;
+
+    /// List the unrevoked keys of a user, newest first
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn list_for_user(&self, user_id: Uuid) -> Result<Vec<ApiKey>, AuthError>;
+
+    /// Revoke a key, returning false if it was already revoked or doesn't exist
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn revoke(&self, id: Uuid) -> Result<bool, AuthError>;
+
+    /// Record that a key was used at `at`
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn record_use(&self, id: Uuid, at: DateTime<Utc>) -> Result<(), AuthError>;
+}
+
+/// Async variant of the [`ApiKeyRepository`] methods token validation uses
+///
+/// Errors are those of the matching [`ApiKeyRepository`] method.
+pub trait AsyncApiKeyRepository {
+    /// Find a key by the hash of its secret
+    fn find_by_hash<'a>(&'a self, key_hash: &'a str) -> BoxFuture<'a, Result<ApiKey, AuthError>>;
+
+    /// Find a key by its ID
+    fn find_by_id(&self, id: Uuid) -> BoxFuture<'_, Result<ApiKey, AuthError>>;
+
+    /// Record that a key was used at `at`
+    fn record_use(&self, id: Uuid, at: DateTime<Utc>) -> BoxFuture<'_, Result<(), AuthError>>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::role::Role;
+    use crate::domain::user::{Email, HashedPassword};
+
+    fn owner() -> User {
+        User::new(
+            Email::new("kiosk@example.com").unwrap(),
+            HashedPassword::from_hash("hashed".to_string()),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_key_prefix_is_recognisable() {
+        let prefix = key_prefix(Uuid::new_v4());
+
+        assert!(is_api_key(&prefix));
+        assert_eq!(prefix.len(), API_KEY_PREFIX.len() + 8);
+        assert!(!is_api_key("eyJhbGciOiJFZERTQSJ9.e30.sig"));
+    }
+
+    #[test]
+    fn test_normalize_key_name() {
+        assert_eq!(normalize_key_name("  Kiosk 1 "), Ok("Kiosk 1".to_string()));
+        assert_eq!(normalize_key_name(" "), Err(AuthError::InvalidApiKeyName));
+        assert_eq!(
+            normalize_key_name(&"k".repeat(MAX_API_KEY_NAME_LEN + 1)),
+            Err(AuthError::InvalidApiKeyName)
+        );
+    }
+
+    #[test]
+    fn test_authenticate_limits_scopes_to_owner_permissions() {
+        let owner = owner();
+        let key = ApiKey::issue(
+            Uuid::new_v4(),
+            owner.id().as_uuid(),
+            "Kiosk".to_string(),
+            "hash".to_string(),
+            vec![Permission::PurchaseTickets, Permission::ManageEvents],
+            None,
+        );
+
+        let token = key.authenticate(&owner, Utc::now()).unwrap();
+
+        assert_eq!(token.user_id, owner.id().as_uuid());
+        assert_eq!(token.roles, vec![Role::Customer]);
+        assert_eq!(token.scopes, Some(vec![Permission::PurchaseTickets]));
+    }
+
+    #[test]
+    fn test_authenticate_rejects_unusable_keys() {
+        let mut owner = owner();
+        let now = Utc::now();
+        let key = |expires_at, revoked_at| {
+            ApiKey::from_persistence(
+                Uuid::new_v4(),
+                owner.id().as_uuid(),
+                "Kiosk".to_string(),
+                "tsk_00000000".to_string(),
+                "hash".to_string(),
+                vec![Permission::PurchaseTickets],
+                expires_at,
+                None,
+                now,
+                revoked_at,
+            )
+        };
+
+        assert!(matches!(
+            key(Some(now), None).authenticate(&owner, now),
+            Err(AuthError::TokenExpired)
+        ));
+        assert!(matches!(
+            key(None, Some(now)).authenticate(&owner, now),
+            Err(AuthError::TokenRevoked)
+        ));
+
+        let valid = key(Some(now + Duration::days(1)), None);
+        owner.deactivate();
+        assert!(matches!(
+            valid.authenticate(&owner, now),
+            Err(AuthError::AccountInactive)
+        ));
+    }
+
+    #[test]
+    fn test_last_used_updates_are_coalesced() {
+        let now = Utc::now();
+        let mut key = ApiKey::issue(
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            "Kiosk".to_string(),
+            "hash".to_string(),
+            vec![],
+            None,
+        );
+        assert!(key.needs_last_used_update(now));
+
+        key.last_used_at = Some(now - Duration::seconds(10));
+        assert!(!key.needs_last_used_update(now));
+        assert!(key.needs_last_used_update(now + Duration::minutes(1)));
+    }
+}
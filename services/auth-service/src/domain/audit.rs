@@ -0,0 +1,574 @@
+//! Security audit log
+//!
+//! Records who did what to which account, from where and with what
+//! outcome: sign-ups, logins, rejected tokens, profile changes and
+//! administrative actions. Entries are append-only; only the retention
+//! purge removes them. Administrators page through them newest first with
+//! the same kind of keyset cursor as the user search.
+
+use std::fmt;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use super::auth::BoxFuture;
+use super::error::AuthError;
+use super::session::{SessionClient, MAX_USER_AGENT_LEN};
+
+/// Longest detail kept with an entry
+pub const MAX_DETAIL_LEN: usize = 512;
+
+/// Audited action
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AuditAction {
+    /// Account registration
+    Register,
+    /// Login with email and password
+    PasswordLogin,
+    /// Second factor of a two-step login
+    MfaLogin,
+    /// Login with a magic link
+    MagicLinkLogin,
+    /// Magic link requested for an email address
+    MagicLinkRequest,
+    /// Magic link request dropped by the rate limit
+    MagicLinkRateLimit,
+    /// Login through an external identity provider
+    FederatedLogin,
+    /// Rejected access token or API key
+    TokenValidation,
+    /// Display name change
+    ProfileUpdate,
+    /// Password change by the account owner
+    PasswordChange,
+    /// Email address change by the account owner
+    EmailChange,
+    /// Password set with a reset link
+    PasswordReset,
+    /// Erasure of one's own account
+    AccountDeletion,
+    /// Organization created
+    OrganizationCreation,
+    /// Invitation to join an organization sent
+    MemberInvitation,
+    /// Invitation to join an organization accepted
+    InvitationAcceptance,
+    /// Role granted by an administrator
+    RoleAssignment,
+    /// Role revoked by an administrator
+    RoleRevocation,
+    /// Account suspended by an administrator
+    UserDeactivation,
+    /// Account restored by an administrator
+    UserReactivation,
+    /// Password set by an administrator
+    PasswordSet,
+    /// Login lockout lifted by an administrator
+    AccountUnlock,
+    /// Personal data of another user exported by an administrator
+    UserDataExport,
+    /// Account of another user erased by an administrator
+    UserErasure,
+    /// Token issued to an administrator acting as another user
+    Impersonation,
+    /// OAuth client registered
+    OAuthClientRegistration,
+    /// OAuth client revoked
+    OAuthClientRevocation,
+    /// OpenID Connect client registered or updated
+    OidcClientRegistration,
+}
+
+impl AuditAction {
+    /// All actions
+    pub const ALL: [AuditAction; 28] = [
+        AuditAction::Register,
+        AuditAction::PasswordLogin,
+        AuditAction::MfaLogin,
+        AuditAction::MagicLinkLogin,
+        AuditAction::MagicLinkRequest,
+        AuditAction::MagicLinkRateLimit,
+        AuditAction::FederatedLogin,
+        AuditAction::TokenValidation,
+        AuditAction::ProfileUpdate,
+        AuditAction::PasswordChange,
+        AuditAction::EmailChange,
+        AuditAction::PasswordReset,
+        AuditAction::AccountDeletion,
+        AuditAction::OrganizationCreation,
+        AuditAction::MemberInvitation,
+        AuditAction::InvitationAcceptance,
+        AuditAction::RoleAssignment,
+        AuditAction::RoleRevocation,
+        AuditAction::UserDeactivation,
+        AuditAction::UserReactivation,
+        AuditAction::PasswordSet,
+        AuditAction::AccountUnlock,
+        AuditAction::UserDataExport,
+        AuditAction::UserErasure,
+        AuditAction::Impersonation,
+        AuditAction::OAuthClientRegistration,
+        AuditAction::OAuthClientRevocation,
+        AuditAction::OidcClientRegistration,
+    ];
+
+    /// Stable identifier used in storage and the admin API
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Register => "user.register",
+            Self::PasswordLogin => "login.password",
+            Self::MfaLogin => "login.mfa",
+            Self::MagicLinkLogin => "login.magic_link",
+            Self::MagicLinkRequest => "magic_link.request",
+            Self::MagicLinkRateLimit => "magic_link.rate_limit",
+            Self::FederatedLogin => "login.federated",
+            Self::TokenValidation => "token.validate",
+            Self::ProfileUpdate => "profile.update",
+            Self::PasswordChange => "profile.password_change",
+            Self::EmailChange => "profile.email_change",
+            Self::PasswordReset => "profile.password_reset",
+            Self::AccountDeletion => "profile.delete",
+            Self::OrganizationCreation => "organization.create",
+            Self::MemberInvitation => "organization.invite",
+            Self::InvitationAcceptance => "organization.join",
+            Self::RoleAssignment => "admin.role_assign",
+            Self::RoleRevocation => "admin.role_revoke",
+            Self::UserDeactivation => "admin.user_deactivate",
+            Self::UserReactivation => "admin.user_reactivate",
+            Self::PasswordSet => "admin.password_set",
+            Self::AccountUnlock => "admin.account_unlock",
+            Self::UserDataExport => "admin.user_export",
+            Self::UserErasure => "admin.user_erase",
+            Self::Impersonation => "admin.impersonate",
+            Self::OAuthClientRegistration => "admin.oauth_client_register",
+            Self::OAuthClientRevocation => "admin.oauth_client_revoke",
+            Self::OidcClientRegistration => "admin.oidc_client_register",
+        }
+    }
+}
+
+impl fmt::Display for AuditAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AuditAction {
+    type Err = AuthError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|action| action.as_str() == value.trim())
+            .ok_or(AuthError::InvalidAuditAction)
+    }
+}
+
+/// Whether an audited action went through
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AuditOutcome {
+    Success,
+    Failure,
+}
+
+impl AuditOutcome {
+    /// Stable identifier used in storage
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::Failure => "failure",
+        }
+    }
+
+    /// Parse a stored identifier
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` for unknown values
+    pub fn parse(value: &str) -> Result<Self, AuthError> {
+        match value {
+            "success" => Ok(Self::Success),
+            "failure" => Ok(Self::Failure),
+            other => Err(AuthError::Internal(format!(
+                "Unknown audit outcome: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// One entry of the audit log
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditEvent {
+    id: Uuid,
+    occurred_at: DateTime<Utc>,
+    action: AuditAction,
+    outcome: AuditOutcome,
+    actor_id: Option<Uuid>,
+    target_id: Option<Uuid>,
+    ip_address: Option<IpAddr>,
+    user_agent: Option<String>,
+    detail: Option<String>,
+}
+
+impl AuditEvent {
+    /// Successful `action` happening now; fill in the rest with the
+    /// `with_*` methods
+    #[must_use]
+    pub fn new(action: AuditAction) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            occurred_at: Utc::now(),
+            action,
+            outcome: AuditOutcome::Success,
+            actor_id: None,
+            target_id: None,
+            ip_address: None,
+            user_agent: None,
+            detail: None,
+        }
+    }
+
+    /// Reconstitute an entry from persistence
+    #[allow(clippy::too_many_arguments)]
+    #[must_use]
+    pub fn from_persistence(
+        id: Uuid,
+        occurred_at: DateTime<Utc>,
+        action: AuditAction,
+        outcome: AuditOutcome,
+        actor_id: Option<Uuid>,
+        target_id: Option<Uuid>,
+        ip_address: Option<IpAddr>,
+        user_agent: Option<String>,
+        detail: Option<String>,
+    ) -> Self {
+        Self {
+            id,
+            occurred_at,
+            action,
+            outcome,
+            actor_id,
+            target_id,
+            ip_address,
+            user_agent,
+            detail,
+        }
+    }
+
+    /// Set the user who performed the action
+    #[must_use]
+    pub fn with_actor(mut self, actor_id: Uuid) -> Self {
+        self.actor_id = Some(actor_id);
+        self
+    }
+
+    /// Set the user the action was aimed at
+    #[must_use]
+    pub fn with_target(mut self, target_id: Uuid) -> Self {
+        self.target_id = Some(target_id);
+        self
+    }
+
+    /// Attach a short free-form detail, such as the role granted
+    #[must_use]
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(truncate(detail.into(), MAX_DETAIL_LEN));
+        self
+    }
+
+    /// Set the address and user agent of the client, unless already known
+    #[must_use]
+    pub fn with_client(mut self, client: &SessionClient) -> Self {
+        self.ip_address = self.ip_address.or(client.ip_address);
+        if self.user_agent.is_none() {
+            self.user_agent = client
+                .user_agent
+                .clone()
+                .map(|user_agent| truncate(user_agent, MAX_USER_AGENT_LEN));
+        }
+        self
+    }
+
+    /// Take the outcome from the result of the action; failures keep the
+    /// error as their detail
+    #[must_use]
+    pub fn with_result<T>(mut self, result: &Result<T, AuthError>) -> Self {
+        if let Err(error) = result {
+            self.outcome = AuditOutcome::Failure;
+            self.detail = Some(truncate(error.to_string(), MAX_DETAIL_LEN));
+        }
+        self
+    }
+
+    /// Get the entry ID
+    #[must_use]
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// Get the time of the action
+    #[must_use]
+    pub fn occurred_at(&self) -> DateTime<Utc> {
+        self.occurred_at
+    }
+
+    /// Get the audited action
+    #[must_use]
+    pub fn action(&self) -> AuditAction {
+        self.action
+    }
+
+    /// Get whether the action went through
+    #[must_use]
+    pub fn outcome(&self) -> AuditOutcome {
+        self.outcome
+    }
+
+    /// Get the user who performed the action, if known
+    #[must_use]
+    pub fn actor_id(&self) -> Option<Uuid> {
+        self.actor_id
+    }
+
+    /// Get the user the action was aimed at, if any
+    #[must_use]
+    pub fn target_id(&self) -> Option<Uuid> {
+        self.target_id
+    }
+
+    /// Get the address the request came from
+    #[must_use]
+    pub fn ip_address(&self) -> Option<IpAddr> {
+        self.ip_address
+    }
+
+    /// Get the user agent of the client
+    #[must_use]
+    pub fn user_agent(&self) -> Option<&str> {
+        self.user_agent.as_deref()
+    }
+
+    /// Get the detail, or the reason of a failure
+    #[must_use]
+    pub fn detail(&self) -> Option<&str> {
+        self.detail.as_deref()
+    }
+}
+
+/// Cut `value` to at most `max` characters
+fn truncate(value: String, max: usize) -> String {
+    value.chars().take(max).collect()
+}
+
+/// Criteria a listed entry must meet; unset fields match everything
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuditFilter {
+    pub action: Option<AuditAction>,
+    pub outcome: Option<AuditOutcome>,
+    pub actor_id: Option<Uuid>,
+    pub target_id: Option<Uuid>,
+    /// Occurred at or after this instant
+    pub occurred_after: Option<DateTime<Utc>>,
+    /// Occurred strictly before this instant
+    pub occurred_before: Option<DateTime<Utc>>,
+}
+
+/// Position after the last entry of a page
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuditCursor {
+    pub occurred_at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl AuditCursor {
+    /// Cursor pointing after `event`
+    #[must_use]
+    pub fn after(event: &AuditEvent) -> Self {
+        Self {
+            occurred_at: event.occurred_at(),
+            id: event.id(),
+        }
+    }
+
+    /// Encode for handing to clients
+    #[must_use]
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(format!(
+            "{}:{}",
+            self.occurred_at.timestamp_micros(),
+            self.id
+        ))
+    }
+
+    /// Decode a cursor produced by `encode`
+    ///
+    /// # Errors
+    /// Returns `AuthError::InvalidCursor` if `value` is not a valid cursor
+    pub fn decode(value: &str) -> Result<Self, AuthError> {
+        let bytes = URL_SAFE_NO_PAD
+            .decode(value)
+            .map_err(|_| AuthError::InvalidCursor)?;
+        let text = String::from_utf8(bytes).map_err(|_| AuthError::InvalidCursor)?;
+        let (micros, id) = text.split_once(':').ok_or(AuthError::InvalidCursor)?;
+
+        let occurred_at = micros
+            .parse()
+            .ok()
+            .and_then(DateTime::from_timestamp_micros)
+            .ok_or(AuthError::InvalidCursor)?;
+        let id = id.parse().map_err(|_| AuthError::InvalidCursor)?;
+
+        Ok(Self { occurred_at, id })
+    }
+}
+
+/// One page of audit entries
+#[derive(Debug, Clone)]
+pub struct AuditPage {
+    pub events: Vec<AuditEvent>,
+    /// Cursor for the following page, `None` on the last one
+    pub next_cursor: Option<AuditCursor>,
+}
+
+/// Service interface for writing the audit log
+///
+/// Recording is best effort: an entry that cannot be written is reported
+/// by the implementation and never fails the action being audited.
+pub trait AuditLog {
+    /// Append an entry
+    fn record(&self, event: AuditEvent);
+}
+
+/// Async variant of [`AuditLog`]
+///
+/// Like [`AuditLog::record`], writing an entry never fails the caller.
+pub trait AsyncAuditLog {
+    /// Record an entry
+    fn record(&self, event: AuditEvent) -> BoxFuture<'_, ()>;
+}
+
+/// Repository interface for reading and pruning the audit log
+pub trait AuditEventRepository {
+    /// List entries matching `filter`, newest first, starting after `cursor`
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn search(
+        &self,
+        filter: &AuditFilter,
+        cursor: Option<&AuditCursor>,
+        limit: usize,
+    ) -> Result<AuditPage, AuthError>;
+
+    /// Delete entries that occurred before `cutoff`, returning how many
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn purge_older_than(&self, cutoff: DateTime<Utc>) -> Result<usize, AuthError>;
+}
+
+/// Audit log stamping entries with the client of the current request
+pub struct ClientAuditLog<'a, L: ?Sized> {
+    log: &'a L,
+    client: SessionClient,
+}
+
+impl<'a, L: ?Sized> ClientAuditLog<'a, L> {
+    /// Wrap `log` for a request from `client`
+    pub fn new(log: &'a L, client: SessionClient) -> Self {
+        Self { log, client }
+    }
+}
+
+impl<L: AuditLog + ?Sized> AuditLog for ClientAuditLog<'_, L> {
+    fn record(&self, event: AuditEvent) {
+        self.log.record(event.with_client(&self.client));
+    }
+}
+
+impl<L: AsyncAuditLog + ?Sized> AsyncAuditLog for ClientAuditLog<'_, L> {
+    fn record(&self, event: AuditEvent) -> BoxFuture<'_, ()> {
+        self.log.record(event.with_client(&self.client))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct MockAuditLog {
+        events: RefCell<Vec<AuditEvent>>,
+    }
+
+    impl AuditLog for MockAuditLog {
+        fn record(&self, event: AuditEvent) {
+            self.events.borrow_mut().push(event);
+        }
+    }
+
+    #[test]
+    fn test_failed_result_is_recorded_with_reason() {
+        let result: Result<(), AuthError> = Err(AuthError::Forbidden);
+        let event = AuditEvent::new(AuditAction::RoleAssignment)
+            .with_detail("admin")
+            .with_result(&result);
+
+        assert_eq!(event.outcome(), AuditOutcome::Failure);
+        assert_eq!(event.detail(), Some("Insufficient permissions"));
+
+        let event = AuditEvent::new(AuditAction::RoleAssignment)
+            .with_detail("admin")
+            .with_result(&Ok::<(), AuthError>(()));
+        assert_eq!(event.outcome(), AuditOutcome::Success);
+        assert_eq!(event.detail(), Some("admin"));
+    }
+
+    #[test]
+    fn test_client_audit_log_adds_client() {
+        let log = MockAuditLog::default();
+        let client = SessionClient {
+            user_agent: Some("x".repeat(MAX_USER_AGENT_LEN + 10)),
+            ip_address: Some("203.0.113.7".parse().unwrap()),
+        };
+
+        ClientAuditLog::new(&log, client).record(AuditEvent::new(AuditAction::PasswordLogin));
+
+        let events = log.events.borrow();
+        assert_eq!(events[0].ip_address(), "203.0.113.7".parse().ok());
+        assert_eq!(events[0].user_agent().unwrap().len(), MAX_USER_AGENT_LEN);
+    }
+
+    #[test]
+    fn test_action_round_trip() {
+        for action in AuditAction::ALL {
+            assert_eq!(action.as_str().parse::<AuditAction>(), Ok(action));
+        }
+        assert_eq!(
+            "login.unknown".parse::<AuditAction>(),
+            Err(AuthError::InvalidAuditAction)
+        );
+    }
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let cursor = AuditCursor {
+            occurred_at: DateTime::from_timestamp_micros(1_700_000_000_123_456).unwrap(),
+            id: Uuid::new_v4(),
+        };
+
+        assert_eq!(AuditCursor::decode(&cursor.encode()), Ok(cursor));
+        assert_eq!(
+            AuditCursor::decode("not a cursor"),
+            Err(AuthError::InvalidCursor)
+        );
+    }
+}
@@ -3,9 +3,15 @@
 //! Defines traits for authentication-related services.
 //! Implementations are in the infrastructure layer.
 
+use std::future::Future;
+use std::pin::Pin;
+
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
 use super::error::AuthError;
+use super::organization::ActiveOrganization;
+use super::role::{permissions_for, Permission, Role};
 use super::user::{HashedPassword, User};
 
 /// Repository interface for user persistence
@@ -42,6 +48,32 @@ pub trait UserRepository {
     fn update(&self, user: &User) -> Result<User, AuthError>;
 }
 
+/// Future returned by async repository methods
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Async variant of [`UserRepository`]
+///
+/// Implemented over an async connection pool, so request handlers can
+/// await user queries directly instead of moving them to the blocking
+/// thread pool. Errors are those of the matching [`UserRepository`] method.
+/// Futures are boxed so the repository can be held as a trait object.
+pub trait AsyncUserRepository {
+    /// Find a user by their ID
+    fn find_by_id(&self, id: Uuid) -> BoxFuture<'_, Result<User, AuthError>>;
+
+    /// Find a user by their email address
+    fn find_by_email<'a>(&'a self, email: &'a str) -> BoxFuture<'a, Result<User, AuthError>>;
+
+    /// Check if a user with the given email exists
+    fn exists_by_email<'a>(&'a self, email: &'a str) -> BoxFuture<'a, Result<bool, AuthError>>;
+
+    /// Create a new user
+    fn create<'a>(&'a self, user: &'a User) -> BoxFuture<'a, Result<User, AuthError>>;
+
+    /// Update an existing user
+    fn update<'a>(&'a self, user: &'a User) -> BoxFuture<'a, Result<User, AuthError>>;
+}
+
 /// Service interface for password hashing
 pub trait PasswordHasher {
     /// Hash a plain text password
@@ -61,25 +93,177 @@ pub trait PasswordHasher {
     fn verify(&self, plain_password: &str, hashed: &HashedPassword) -> Result<bool, AuthError>;
 }
 
+/// Async variant of [`PasswordHasher`]
+///
+/// Hashing is deliberately slow, so implementations run it off the async
+/// worker threads. Errors are those of the matching [`PasswordHasher`] method.
+pub trait AsyncPasswordHasher {
+    /// Hash a plain text password
+    fn hash<'a>(&'a self, plain_password: &'a str) -> BoxFuture<'a, Result<HashedPassword, AuthError>>;
+
+    /// Verify a plain text password against a hash
+    fn verify<'a>(
+        &'a self,
+        plain_password: &'a str,
+        hashed: &'a HashedPassword,
+    ) -> BoxFuture<'a, Result<bool, AuthError>>;
+}
+
+/// Kind of caller a token authenticates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Principal {
+    /// A person, signed in or through one of their API keys
+    User,
+    /// A backend service authenticated as an OAuth client
+    Service,
+}
+
+impl Principal {
+    /// Stable identifier exposed to other services
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::User => "user",
+            Self::Service => "service",
+        }
+    }
+}
+
 /// Data extracted from a validated token
 #[derive(Debug, Clone)]
 pub struct TokenData {
+    /// Subject: the user's ID, or the client ID of a service
     pub user_id: Uuid,
+    /// The user's email; empty for services
     pub email: String,
+    /// Unique token identifier (`jti` claim), used as the revocation key
+    pub jti: String,
+    /// Session the token was issued for (`sid` claim); `None` for tokens
+    /// issued outside a login, such as OAuth access tokens and API keys
+    pub session_id: Option<Uuid>,
+    pub expires_at: DateTime<Utc>,
+    /// Roles held by the user when the token was issued
+    pub roles: Vec<Role>,
+    /// Permissions an API key or service is limited to; `None` for session tokens
+    pub scopes: Option<Vec<Permission>>,
+    pub principal: Principal,
+    /// Administrator acting as the user (`act` claim); `None` unless the
+    /// token was issued for an impersonation
+    pub actor: Option<Uuid>,
+    /// Organization the token acts for (`org_id` claim); `None` outside
+    /// any organization
+    pub organization: Option<ActiveOrganization>,
+    /// OpenID Connect scopes granted to the client the token was issued to;
+    /// `None` for tokens issued outside the authorization-code flow
+    pub oidc_scopes: Option<Vec<String>>,
+}
+
+impl TokenData {
+    /// Check if the token is an API key rather than a session token
+    #[must_use]
+    pub fn is_api_key(&self) -> bool {
+        self.principal == Principal::User && self.scopes.is_some()
+    }
+
+    /// Check if the token was issued to a service rather than a user
+    #[must_use]
+    pub fn is_service(&self) -> bool {
+        self.principal == Principal::Service
+    }
+
+    /// Check if the token was issued to an administrator acting as the user
+    #[must_use]
+    pub fn is_impersonated(&self) -> bool {
+        self.actor.is_some()
+    }
+
+    /// Who is accountable for what the token does: the administrator behind
+    /// an impersonation, otherwise the subject
+    #[must_use]
+    pub fn audit_actor(&self) -> Uuid {
+        self.actor.unwrap_or(self.user_id)
+    }
+
+    /// Check if the token is a user's own session, neither a key, a service
+    /// nor an impersonation
+    #[must_use]
+    pub fn is_session(&self) -> bool {
+        self.principal == Principal::User && self.scopes.is_none() && self.actor.is_none()
+    }
+
+    /// Permissions the token can actually exercise
+    #[must_use]
+    pub fn permissions(&self) -> Vec<Permission> {
+        match &self.scopes {
+            Some(scopes) => scopes.clone(),
+            None => permissions_for(&self.roles),
+        }
+    }
 }
 
 /// Service interface for JWT token operations
 pub trait TokenService {
     /// Create a new JWT token for a user
     ///
+    /// Tokens issued for a login carry its `session_id`, so ending the
+    /// session revokes them.
+    ///
     /// # Errors
     /// Returns `AuthError::Internal` if token creation fails
-    fn create_token(&self, user: &User) -> Result<String, AuthError>;
+    fn create_token(&self, user: &User, session_id: Option<Uuid>) -> Result<String, AuthError>;
 
     /// Validate a token and extract its data
     ///
     /// # Errors
     /// Returns `AuthError::InvalidToken` if token is malformed
     /// Returns `AuthError::TokenExpired` if token has expired
+    /// Returns `AuthError::TokenRevoked` if token has been revoked
     fn validate_token(&self, token: &str) -> Result<TokenData, AuthError>;
+
+    /// Revoke a validated token so it is rejected before it expires
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` if the revocation cannot be stored
+    fn revoke_token(&self, token: &TokenData) -> Result<(), AuthError>;
+
+    /// Check whether a validated token has since been revoked
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` if the revocation store is unavailable
+    fn is_revoked(&self, token: &TokenData) -> Result<bool, AuthError>;
+}
+
+/// Async variant of the validation half of [`TokenService`]
+///
+/// Revocation and session lookups are awaited, so validating a token holds
+/// no blocking thread. Errors are those of the matching [`TokenService`]
+/// method.
+pub trait AsyncTokenValidator {
+    /// Validate a token and extract its data
+    fn validate_token<'a>(&'a self, token: &'a str)
+        -> BoxFuture<'a, Result<TokenData, AuthError>>;
+
+    /// Check whether a validated token has since been revoked
+    fn is_revoked<'a>(&'a self, token: &'a TokenData) -> BoxFuture<'a, Result<bool, AuthError>>;
+}
+
+/// A [`TokenService`] that can also validate tokens asynchronously
+///
+/// What token service decorators wrap, so that both kinds of validation
+/// pass through them.
+pub trait AsyncTokenService: TokenService + AsyncTokenValidator {}
+
+impl<T: TokenService + AsyncTokenValidator + ?Sized> AsyncTokenService for T {}
+
+/// Service interface for opaque (non-JWT) secrets such as refresh tokens
+///
+/// Secrets are only ever shown to the client; the hash is what gets stored.
+pub trait OpaqueTokenGenerator {
+    /// Generate a new random, URL-safe secret
+    fn generate(&self) -> String;
+
+    /// Derive the value persisted for a secret
+    ///
+    /// Must be deterministic so a presented secret can be looked up.
+    fn hash(&self, token: &str) -> String;
 }
@@ -0,0 +1,187 @@
+//! Email verification token domain entity
+//!
+//! A verification token is an opaque secret mailed to a new address to
+//! prove the user can read it. Only a hash of the secret is persisted, and
+//! each token can be used at most once. A token is bound to the address it
+//! was sent to, so it stops working if the user changes their email.
+
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+use super::auth::BoxFuture;
+use super::error::AuthError;
+
+/// Email verification token entity
+#[derive(Debug, Clone)]
+pub struct EmailVerificationToken {
+    id: Uuid,
+    user_id: Uuid,
+    email: String,
+    token_hash: String,
+    expires_at: DateTime<Utc>,
+    created_at: DateTime<Utc>,
+    used_at: Option<DateTime<Utc>>,
+}
+
+impl EmailVerificationToken {
+    /// Issue a new verification token
+    ///
+    /// # Arguments
+    /// * `user_id` - User whose address is being verified
+    /// * `email` - Address the secret is mailed to
+    /// * `token_hash` - Hash of the opaque secret mailed to the user
+    /// * `ttl` - Lifetime of the token
+    #[must_use]
+    pub fn issue(user_id: Uuid, email: String, token_hash: String, ttl: Duration) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            user_id,
+            email,
+            token_hash,
+            expires_at: now + ttl,
+            created_at: now,
+            used_at: None,
+        }
+    }
+
+    /// Reconstruct a verification token from persistence
+    #[must_use]
+    pub fn from_persistence(
+        id: Uuid,
+        user_id: Uuid,
+        email: String,
+        token_hash: String,
+        expires_at: DateTime<Utc>,
+        created_at: DateTime<Utc>,
+        used_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        Self {
+            id,
+            user_id,
+            email,
+            token_hash,
+            expires_at,
+            created_at,
+            used_at,
+        }
+    }
+
+    /// Get the token ID
+    #[must_use]
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// Get the owning user's ID
+    #[must_use]
+    pub fn user_id(&self) -> Uuid {
+        self.user_id
+    }
+
+    /// Get the address the token was sent to
+    #[must_use]
+    pub fn email(&self) -> &str {
+        &self.email
+    }
+
+    /// Get the stored hash of the secret
+    #[must_use]
+    pub fn token_hash(&self) -> &str {
+        &self.token_hash
+    }
+
+    /// Get the expiration timestamp
+    #[must_use]
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        self.expires_at
+    }
+
+    /// Get the creation timestamp
+    #[must_use]
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    /// Get the timestamp at which the token was used
+    #[must_use]
+    pub fn used_at(&self) -> Option<DateTime<Utc>> {
+        self.used_at
+    }
+
+    /// Check whether the token has already been used
+    #[must_use]
+    pub fn is_used(&self) -> bool {
+        self.used_at.is_some()
+    }
+
+    /// Check whether the token is past its expiry at `now`
+    #[must_use]
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        now >= self.expires_at
+    }
+}
+
+/// Repository interface for email verification token persistence
+pub trait EmailVerificationTokenRepository {
+    /// Store a newly issued verification token
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn create(&self, token: &EmailVerificationToken) -> Result<(), AuthError>;
+
+    /// Find a verification token by the hash of its secret
+    ///
+    /// # Errors
+    /// Returns `AuthError::InvalidToken` if no token has this hash
+    /// Returns `AuthError::Internal` on database errors
+    fn find_by_hash(&self, token_hash: &str) -> Result<EmailVerificationToken, AuthError>;
+
+    /// Atomically mark a token as used, along with every other unused token
+    /// issued to the same user
+    ///
+    /// Returns `Ok(false)` if the token was already used.
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn consume(&self, token: &EmailVerificationToken) -> Result<bool, AuthError>;
+
+    /// Delete tokens that have expired by `now`
+    ///
+    /// Returns the number of tokens removed.
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn purge_expired(&self, now: DateTime<Utc>) -> Result<usize, AuthError>;
+}
+
+/// Async variant of the [`EmailVerificationTokenRepository`] write made on
+/// registration
+///
+/// Errors are those of [`EmailVerificationTokenRepository::create`].
+pub trait AsyncEmailVerificationTokenRepository {
+    /// Store a newly issued token
+    fn create<'a>(
+        &'a self,
+        token: &'a EmailVerificationToken,
+    ) -> BoxFuture<'a, Result<(), AuthError>>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_issue_is_usable() {
+        let token = EmailVerificationToken::issue(
+            Uuid::new_v4(),
+            "test@example.com".to_string(),
+            "h".to_string(),
+            Duration::days(1),
+        );
+
+        assert!(!token.is_used());
+        assert!(!token.is_expired(Utc::now()));
+        assert!(token.is_expired(Utc::now() + Duration::days(1)));
+    }
+}
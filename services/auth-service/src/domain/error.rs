@@ -5,6 +5,8 @@
 
 use std::fmt;
 
+use super::password_policy::PasswordRule;
+
 /// Domain-level authentication errors
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum AuthError {
@@ -23,15 +25,105 @@ pub enum AuthError {
     /// Token has expired
     TokenExpired,
 
+    /// Token was revoked before its expiry (e.g. by logout)
+    TokenRevoked,
+
+    /// Refresh token was already rotated and has been presented again
+    TokenReused,
+
     /// Email format is invalid
     InvalidEmail,
 
-    /// Password does not meet requirements
-    WeakPassword,
+    /// Display name is too long or contains control characters
+    InvalidDisplayName,
+
+    /// Password does not meet requirements; lists every rule it breaks
+    WeakPassword(Vec<PasswordRule>),
 
     /// User account is inactive
     AccountInactive,
 
+    /// Too many failed logins; retrying is pointless for `retry_after_secs` seconds
+    AccountLocked { retry_after_secs: i64 },
+
+    /// Login requires a verified email address and this one is not
+    EmailNotVerified,
+
+    /// User has no password to confirm a sensitive change with and did not
+    /// sign in recently enough
+    RecentLoginRequired,
+
+    /// Second-factor code is wrong or was already used
+    InvalidMfaCode,
+
+    /// Two-factor authentication is already enabled for the user
+    MfaAlreadyEnabled,
+
+    /// No pending two-factor enrollment to confirm
+    MfaNotEnrolled,
+
+    /// Caller lacks the permission required for the operation
+    Forbidden,
+
+    /// Target account may not be impersonated
+    CannotImpersonate,
+
+    /// Role name is not recognised
+    InvalidRole,
+
+    /// Pagination cursor is malformed
+    InvalidCursor,
+
+    /// Audit action filter is not a known action
+    InvalidAuditAction,
+
+    /// Permission name is not recognised, or not held by the key's owner
+    InvalidScope,
+
+    /// API key name is empty, too long or contains control characters
+    InvalidApiKeyName,
+
+    /// API key was not found
+    ApiKeyNotFound,
+
+    /// Session was not found
+    SessionNotFound,
+
+    /// Organization name is empty, too long or contains control characters
+    InvalidOrganizationName,
+
+    /// Organization does not exist or the caller is not a member
+    OrganizationNotFound,
+
+    /// Invitation is unknown, expired or already accepted
+    InvalidInvitation,
+
+    /// OAuth client is unknown or revoked, or its secret is wrong
+    InvalidClient,
+
+    /// Token request uses a grant type this service does not support
+    UnsupportedGrantType,
+
+    /// OAuth client name is empty, too long or contains control characters
+    InvalidClientName,
+
+    /// OAuth client was not found
+    OAuthClientNotFound,
+
+    /// Authorization code is unknown, expired or already used, or doesn't
+    /// match the token request; also a federated sign-in whose state is
+    /// unknown, expired or already used
+    InvalidGrant,
+
+    /// OAuth request is missing a parameter or has a malformed one
+    InvalidRequest(String),
+
+    /// No identity provider is configured under the given ID
+    IdentityProviderNotFound,
+
+    /// Identity provider failed or answered with something unacceptable
+    IdentityProvider(String),
+
     /// Internal error during operation
     Internal(String),
 }
@@ -44,9 +136,48 @@ impl fmt::Display for AuthError {
             Self::InvalidCredentials => write!(f, "Invalid email or password"),
             Self::InvalidToken => write!(f, "Invalid or malformed token"),
             Self::TokenExpired => write!(f, "Token has expired"),
+            Self::TokenRevoked => write!(f, "Token has been revoked"),
+            Self::TokenReused => write!(f, "Token has already been used"),
             Self::InvalidEmail => write!(f, "Invalid email format"),
-            Self::WeakPassword => write!(f, "Password does not meet minimum requirements"),
+            Self::InvalidDisplayName => write!(f, "Invalid display name"),
+            Self::WeakPassword(rules) => {
+                write!(f, "Password does not meet requirements")?;
+                for (i, rule) in rules.iter().enumerate() {
+                    write!(f, "{}{}", if i == 0 { ": " } else { "; " }, rule)?;
+                }
+                Ok(())
+            }
             Self::AccountInactive => write!(f, "User account is inactive"),
+            Self::AccountLocked { retry_after_secs } => write!(
+                f,
+                "Too many failed login attempts, try again in {} seconds",
+                retry_after_secs
+            ),
+            Self::EmailNotVerified => write!(f, "Email address has not been verified"),
+            Self::RecentLoginRequired => write!(f, "Sign in again to make this change"),
+            Self::InvalidMfaCode => write!(f, "Invalid two-factor authentication code"),
+            Self::MfaAlreadyEnabled => write!(f, "Two-factor authentication is already enabled"),
+            Self::MfaNotEnrolled => write!(f, "No pending two-factor enrollment"),
+            Self::Forbidden => write!(f, "Insufficient permissions"),
+            Self::CannotImpersonate => write!(f, "This user cannot be impersonated"),
+            Self::InvalidRole => write!(f, "Unknown role"),
+            Self::InvalidCursor => write!(f, "Invalid pagination cursor"),
+            Self::InvalidAuditAction => write!(f, "Unknown audit action"),
+            Self::InvalidScope => write!(f, "Invalid or unavailable scope"),
+            Self::InvalidApiKeyName => write!(f, "Invalid API key name"),
+            Self::ApiKeyNotFound => write!(f, "API key not found"),
+            Self::SessionNotFound => write!(f, "Session not found"),
+            Self::InvalidOrganizationName => write!(f, "Invalid organization name"),
+            Self::OrganizationNotFound => write!(f, "Organization not found"),
+            Self::InvalidInvitation => write!(f, "Invalid or expired invitation"),
+            Self::InvalidClient => write!(f, "Client authentication failed"),
+            Self::UnsupportedGrantType => write!(f, "Unsupported grant type"),
+            Self::InvalidClientName => write!(f, "Invalid client name"),
+            Self::OAuthClientNotFound => write!(f, "OAuth client not found"),
+            Self::InvalidGrant => write!(f, "Invalid or expired authorization code"),
+            Self::InvalidRequest(msg) => write!(f, "Invalid request: {}", msg),
+            Self::IdentityProviderNotFound => write!(f, "Identity provider not found"),
+            Self::IdentityProvider(msg) => write!(f, "Identity provider error: {}", msg),
             Self::Internal(msg) => write!(f, "Internal error: {}", msg),
         }
     }
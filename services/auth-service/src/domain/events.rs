@@ -0,0 +1,94 @@
+//! Domain events published to other services
+//!
+//! Other services keep their own copies of some user data and react to
+//! changes announced here. Events about a user change are stored in the
+//! outbox together with the change; delivery is an infrastructure concern.
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// Event raised by the auth service
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuthEvent {
+    /// An account was created, by registration or a first federated login
+    UserRegistered {
+        user_id: Uuid,
+        email: String,
+        display_name: Option<String>,
+        occurred_at: DateTime<Utc>,
+    },
+    /// A user signed in and a session was started
+    UserLoggedIn {
+        user_id: Uuid,
+        session_id: Uuid,
+        occurred_at: DateTime<Utc>,
+    },
+    /// An active account was deactivated
+    UserDeactivated {
+        user_id: Uuid,
+        occurred_at: DateTime<Utc>,
+    },
+    /// A user's password was changed, reset or set for the first time
+    PasswordChanged {
+        user_id: Uuid,
+        occurred_at: DateTime<Utc>,
+    },
+    /// A user's personal data was erased; consumers must erase theirs too
+    UserErased {
+        user_id: Uuid,
+        erased_at: DateTime<Utc>,
+    },
+}
+
+impl AuthEvent {
+    /// Stable event name, used as the message type
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::UserRegistered { .. } => "user.registered",
+            Self::UserLoggedIn { .. } => "user.logged_in",
+            Self::UserDeactivated { .. } => "user.deactivated",
+            Self::PasswordChanged { .. } => "user.password_changed",
+            Self::UserErased { .. } => "user.erased",
+        }
+    }
+
+    /// Version of the event's payload schema
+    ///
+    /// Bumped whenever the payload of an event changes in a way existing
+    /// consumers cannot read; adding optional fields does not count.
+    #[must_use]
+    pub fn schema_version(&self) -> i32 {
+        match self {
+            Self::UserRegistered { .. }
+            | Self::UserLoggedIn { .. }
+            | Self::UserDeactivated { .. }
+            | Self::PasswordChanged { .. }
+            | Self::UserErased { .. } => 1,
+        }
+    }
+
+    /// User the event is about, used as the partition key
+    #[must_use]
+    pub fn user_id(&self) -> Uuid {
+        match self {
+            Self::UserRegistered { user_id, .. }
+            | Self::UserLoggedIn { user_id, .. }
+            | Self::UserDeactivated { user_id, .. }
+            | Self::PasswordChanged { user_id, .. }
+            | Self::UserErased { user_id, .. } => *user_id,
+        }
+    }
+
+    /// Time the announced change happened
+    #[must_use]
+    pub fn occurred_at(&self) -> DateTime<Utc> {
+        match self {
+            Self::UserRegistered { occurred_at, .. }
+            | Self::UserLoggedIn { occurred_at, .. }
+            | Self::UserDeactivated { occurred_at, .. }
+            | Self::PasswordChanged { occurred_at, .. } => *occurred_at,
+            Self::UserErased { erased_at, .. } => *erased_at,
+        }
+    }
+}
@@ -0,0 +1,438 @@
+//! Sign-in through external OpenID Connect providers
+//!
+//! The service is a relying party for configured upstream issuers, such as a
+//! social login or a company's SSO. Upstream accounts are linked to users by
+//! the issuer's subject identifier. The first sign-in links the user with the
+//! same verified email address, or creates a user without a password.
+
+use std::fmt;
+
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+use super::auth::BoxFuture;
+use super::error::AuthError;
+use super::user::User;
+
+/// Longest accepted identity provider ID, in characters
+pub const MAX_PROVIDER_ID_LEN: usize = 64;
+
+/// Scopes requested from identity providers
+pub const FEDERATED_SCOPES: [&str; 3] = ["openid", "email", "profile"];
+
+/// Check an identity provider ID: 1 to 64 lowercase letters, digits,
+/// dashes or underscores
+#[must_use]
+pub fn is_valid_provider_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_PROVIDER_ID_LEN
+        && id
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || matches!(b, b'-' | b'_'))
+}
+
+/// Upstream OpenID Connect provider users may sign in with
+#[derive(Clone)]
+pub struct IdentityProvider {
+    /// Short identifier used in requests and stored with linked identities
+    pub id: String,
+    /// Name shown on the login page
+    pub name: String,
+    /// Issuer identifier; endpoints are read from its discovery document
+    pub issuer: String,
+    /// Client ID registered with the provider, the `aud` of its ID tokens
+    pub client_id: String,
+    /// Secret for the token endpoint; `None` for providers relying on PKCE alone
+    pub client_secret: Option<String>,
+}
+
+impl fmt::Debug for IdentityProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The client secret stays out of logs
+        f.debug_struct("IdentityProvider")
+            .field("id", &self.id)
+            .field("name", &self.name)
+            .field("issuer", &self.issuer)
+            .field("client_id", &self.client_id)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Find a configured provider by ID
+///
+/// # Errors
+/// Returns `AuthError::IdentityProviderNotFound` if no provider has the ID
+pub fn find_provider<'a>(
+    providers: &'a [IdentityProvider],
+    id: &str,
+) -> Result<&'a IdentityProvider, AuthError> {
+    providers
+        .iter()
+        .find(|provider| provider.id == id)
+        .ok_or(AuthError::IdentityProviderNotFound)
+}
+
+/// Claims of a verified ID token from an identity provider
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalIdentity {
+    /// Subject identifier, unique and stable within the issuer
+    pub subject: String,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub name: Option<String>,
+}
+
+impl ExternalIdentity {
+    /// Get the email address, if the provider vouches for it
+    #[must_use]
+    pub fn verified_email(&self) -> Option<&str> {
+        self.email.as_deref().filter(|_| self.email_verified)
+    }
+}
+
+/// Link between a user and an account at an identity provider
+#[derive(Debug, Clone)]
+pub struct UserIdentity {
+    provider: String,
+    subject: String,
+    user_id: Uuid,
+    email: Option<String>,
+    created_at: DateTime<Utc>,
+    last_login_at: Option<DateTime<Utc>>,
+}
+
+impl UserIdentity {
+    /// Link an upstream account to a user on its first sign-in
+    #[must_use]
+    pub fn link(provider: &str, identity: &ExternalIdentity, user: &User) -> Self {
+        let now = Utc::now();
+        Self {
+            provider: provider.to_string(),
+            subject: identity.subject.clone(),
+            user_id: user.id().as_uuid(),
+            email: identity.email.clone(),
+            created_at: now,
+            last_login_at: Some(now),
+        }
+    }
+
+    /// Reconstruct a link from persistence
+    #[must_use]
+    pub fn from_persistence(
+        provider: String,
+        subject: String,
+        user_id: Uuid,
+        email: Option<String>,
+        created_at: DateTime<Utc>,
+        last_login_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        Self {
+            provider,
+            subject,
+            user_id,
+            email,
+            created_at,
+            last_login_at,
+        }
+    }
+
+    /// Get the identity provider ID
+    #[must_use]
+    pub fn provider(&self) -> &str {
+        &self.provider
+    }
+
+    /// Get the subject identifier at the provider
+    #[must_use]
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+
+    /// Get the linked user's ID
+    #[must_use]
+    pub fn user_id(&self) -> Uuid {
+        self.user_id
+    }
+
+    /// Get the email address the provider reported when the link was made
+    #[must_use]
+    pub fn email(&self) -> Option<&str> {
+        self.email.as_deref()
+    }
+
+    /// Get the time the link was made
+    #[must_use]
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    /// Get the time of the latest sign-in through the provider
+    #[must_use]
+    pub fn last_login_at(&self) -> Option<DateTime<Utc>> {
+        self.last_login_at
+    }
+}
+
+/// Sign-in started at an identity provider and not yet completed
+///
+/// Only a hash of the `state` handed to the browser is persisted. The nonce
+/// and PKCE verifier never leave the service.
+#[derive(Debug, Clone)]
+pub struct FederatedLoginRequest {
+    state_hash: String,
+    provider: String,
+    nonce: String,
+    code_verifier: String,
+    expires_at: DateTime<Utc>,
+    created_at: DateTime<Utc>,
+}
+
+impl FederatedLoginRequest {
+    /// Start a sign-in
+    ///
+    /// # Arguments
+    /// * `state_hash` - Hash of the opaque `state` sent through the browser
+    /// * `provider` - ID of the identity provider
+    /// * `nonce` - Value the provider must echo in its ID token
+    /// * `code_verifier` - PKCE verifier for the provider's code
+    /// * `ttl` - Time the user has to sign in at the provider
+    #[must_use]
+    pub fn issue(
+        state_hash: String,
+        provider: String,
+        nonce: String,
+        code_verifier: String,
+        ttl: Duration,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            state_hash,
+            provider,
+            nonce,
+            code_verifier,
+            expires_at: now + ttl,
+            created_at: now,
+        }
+    }
+
+    /// Reconstruct a request from persistence
+    #[must_use]
+    pub fn from_persistence(
+        state_hash: String,
+        provider: String,
+        nonce: String,
+        code_verifier: String,
+        expires_at: DateTime<Utc>,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            state_hash,
+            provider,
+            nonce,
+            code_verifier,
+            expires_at,
+            created_at,
+        }
+    }
+
+    /// Get the hash of the state
+    #[must_use]
+    pub fn state_hash(&self) -> &str {
+        &self.state_hash
+    }
+
+    /// Get the identity provider ID
+    #[must_use]
+    pub fn provider(&self) -> &str {
+        &self.provider
+    }
+
+    /// Get the nonce expected in the ID token
+    #[must_use]
+    pub fn nonce(&self) -> &str {
+        &self.nonce
+    }
+
+    /// Get the PKCE code verifier
+    #[must_use]
+    pub fn code_verifier(&self) -> &str {
+        &self.code_verifier
+    }
+
+    /// Get the expiry timestamp
+    #[must_use]
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        self.expires_at
+    }
+
+    /// Get the creation timestamp
+    #[must_use]
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    /// Check if the request has expired at `now`
+    #[must_use]
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at <= now
+    }
+}
+
+/// Async service interface for talking to identity providers
+pub trait AsyncIdentityProviderClient {
+    /// Build the URL that sends the user to the provider's login page
+    ///
+    /// # Errors
+    /// Returns `AuthError::IdentityProvider` if the provider's discovery
+    /// document cannot be loaded
+    fn authorization_url<'a>(
+        &'a self,
+        provider: &'a IdentityProvider,
+        redirect_uri: &'a str,
+        state: &'a str,
+        nonce: &'a str,
+        code_challenge: &'a str,
+    ) -> BoxFuture<'a, Result<String, AuthError>>;
+
+    /// Redeem an authorization code and verify the returned ID token
+    ///
+    /// The token must be signed by the provider, issued for its client ID,
+    /// unexpired and carry `nonce`.
+    ///
+    /// # Errors
+    /// Returns `AuthError::IdentityProvider` if the provider rejects the code
+    /// or returns an ID token that fails verification
+    fn redeem_code<'a>(
+        &'a self,
+        provider: &'a IdentityProvider,
+        code: &'a str,
+        redirect_uri: &'a str,
+        code_verifier: &'a str,
+        nonce: &'a str,
+    ) -> BoxFuture<'a, Result<ExternalIdentity, AuthError>>;
+}
+
+/// Async repository interface for links between users and upstream accounts
+pub trait AsyncUserIdentityRepository {
+    /// Find the link for an upstream account
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn find<'a>(
+        &'a self,
+        provider: &'a str,
+        subject: &'a str,
+    ) -> BoxFuture<'a, Result<Option<UserIdentity>, AuthError>>;
+
+    /// Link an upstream account to an existing user
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn create<'a>(&'a self, identity: &'a UserIdentity) -> BoxFuture<'a, Result<(), AuthError>>;
+
+    /// Create a user and link an upstream account to them in one transaction
+    ///
+    /// # Errors
+    /// - `AuthError::UserAlreadyExists` if the email address or the upstream
+    ///   account was taken concurrently
+    /// - `AuthError::Internal` on database errors
+    fn create_with_user<'a>(
+        &'a self,
+        user: &'a User,
+        identity: &'a UserIdentity,
+    ) -> BoxFuture<'a, Result<(), AuthError>>;
+
+    /// Record a sign-in through a linked upstream account
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn record_login<'a>(
+        &'a self,
+        provider: &'a str,
+        subject: &'a str,
+        at: DateTime<Utc>,
+    ) -> BoxFuture<'a, Result<(), AuthError>>;
+}
+
+/// Repository interface for sign-ins pending at identity providers
+pub trait FederatedLoginRequestRepository {
+    /// Delete requests that expired before `now`, returning how many
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn purge_expired(&self, now: DateTime<Utc>) -> Result<usize, AuthError>;
+}
+
+/// Async repository interface for sign-ins pending at identity providers
+pub trait AsyncFederatedLoginRequestRepository {
+    /// Store a new request
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn create<'a>(
+        &'a self,
+        request: &'a FederatedLoginRequest,
+    ) -> BoxFuture<'a, Result<(), AuthError>>;
+
+    /// Remove and return the request with the given state hash
+    ///
+    /// Expired requests are returned too; the caller decides.
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn take<'a>(
+        &'a self,
+        state_hash: &'a str,
+    ) -> BoxFuture<'a, Result<Option<FederatedLoginRequest>, AuthError>>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provider(id: &str) -> IdentityProvider {
+        IdentityProvider {
+            id: id.to_string(),
+            name: "Corp".to_string(),
+            issuer: "https://sso.example.com".to_string(),
+            client_id: "ticketing".to_string(),
+            client_secret: Some("s3cret".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_provider_lookup_and_debug() {
+        let providers = [provider("corp"), provider("google")];
+
+        assert_eq!(find_provider(&providers, "google").unwrap().id, "google");
+        assert!(matches!(
+            find_provider(&providers, "github"),
+            Err(AuthError::IdentityProviderNotFound)
+        ));
+        assert!(!format!("{:?}", providers[0]).contains("s3cret"));
+    }
+
+    #[test]
+    fn test_is_valid_provider_id() {
+        assert!(is_valid_provider_id("google"));
+        assert!(is_valid_provider_id("corp_sso-2"));
+        assert!(!is_valid_provider_id(""));
+        assert!(!is_valid_provider_id("Google"));
+        assert!(!is_valid_provider_id("corp/sso"));
+        assert!(!is_valid_provider_id(&"a".repeat(MAX_PROVIDER_ID_LEN + 1)));
+    }
+
+    #[test]
+    fn test_verified_email_requires_verification() {
+        let mut identity = ExternalIdentity {
+            subject: "248289761001".to_string(),
+            email: Some("jane@example.com".to_string()),
+            email_verified: false,
+            name: None,
+        };
+        assert_eq!(identity.verified_email(), None);
+
+        identity.email_verified = true;
+        assert_eq!(identity.verified_email(), Some("jane@example.com"));
+    }
+}
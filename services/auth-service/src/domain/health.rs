@@ -0,0 +1,7 @@
+//! Health of the service's dependencies
+
+/// Probe of a dependency the service cannot work without
+pub trait HealthCheck {
+    /// Check whether the dependency can be reached right now
+    fn is_healthy(&self) -> bool;
+}
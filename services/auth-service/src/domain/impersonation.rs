@@ -0,0 +1,115 @@
+//! Administrators acting as another user
+//!
+//! An impersonation token is an access token for the target user that also
+//! names the administrator behind it in an RFC 8693 `act` claim. It carries
+//! the administrator's session in its `sid` claim and comes without a
+//! refresh token, so it ends when it expires, is revoked or that session
+//! ends, and downstream services can refuse sensitive operations to it.
+
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+use super::error::AuthError;
+use super::role::Role;
+use super::user::User;
+
+/// Access token issued to an administrator acting as a user
+#[derive(Debug, Clone)]
+pub struct ImpersonationToken {
+    pub access_token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Check that `actor_id` may act as `target`
+///
+/// Staff accounts are never impersonated, so an impersonation cannot widen
+/// or disguise administrative access.
+///
+/// # Errors
+/// Returns `AuthError::CannotImpersonate` for the actor's own account and
+/// for support agents and administrators, and `AuthError::AccountInactive`
+/// for deactivated accounts
+pub fn check_impersonation_target(actor_id: Uuid, target: &User) -> Result<(), AuthError> {
+    if target.id().as_uuid() == actor_id
+        || target
+            .roles()
+            .iter()
+            .any(|role| matches!(role, Role::SupportAgent | Role::Admin))
+    {
+        return Err(AuthError::CannotImpersonate);
+    }
+    if !target.is_active() {
+        return Err(AuthError::AccountInactive);
+    }
+    Ok(())
+}
+
+/// Service interface for issuing impersonation tokens
+pub trait ImpersonationTokenIssuer {
+    /// Create a token for `user` naming `actor_id` as the acting
+    /// administrator, valid for `ttl` or until the administrator's session
+    /// `session_id` ends
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` if token creation fails
+    fn create_impersonation_token(
+        &self,
+        user: &User,
+        actor_id: Uuid,
+        session_id: Uuid,
+        ttl: Duration,
+    ) -> Result<ImpersonationToken, AuthError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::user::{Email, HashedPassword};
+
+    fn user_with(role: Role) -> User {
+        let mut user = User::new(
+            Email::new("target@example.com").unwrap(),
+            HashedPassword::from_hash("hash".to_string()),
+            None,
+        );
+        user.assign_role(role);
+        user
+    }
+
+    #[test]
+    fn test_customers_and_organizers_can_be_impersonated() {
+        let actor_id = Uuid::new_v4();
+
+        assert!(check_impersonation_target(actor_id, &user_with(Role::Customer)).is_ok());
+        assert!(check_impersonation_target(actor_id, &user_with(Role::Organizer)).is_ok());
+    }
+
+    #[test]
+    fn test_staff_and_self_cannot_be_impersonated() {
+        let staff = user_with(Role::SupportAgent);
+        let admin = user_with(Role::Admin);
+        let customer = user_with(Role::Customer);
+
+        for (actor_id, target) in [
+            (Uuid::new_v4(), &staff),
+            (Uuid::new_v4(), &admin),
+            (customer.id().as_uuid(), &customer),
+        ] {
+            assert!(matches!(
+                check_impersonation_target(actor_id, target),
+                Err(AuthError::CannotImpersonate)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_inactive_users_cannot_be_impersonated() {
+        let mut user = user_with(Role::Customer);
+        user.deactivate();
+
+        assert!(matches!(
+            check_impersonation_target(Uuid::new_v4(), &user),
+            Err(AuthError::AccountInactive)
+        ));
+    }
+}
@@ -0,0 +1,352 @@
+//! Brute-force protection for logins
+//!
+//! Failed login attempts are counted per email address and per source IP.
+//! Once a counter reaches its threshold the subject is locked out, and every
+//! further failure after the lock expires doubles the lockout, up to a cap.
+//! Counters are forgotten after a quiet period. They are persisted so that
+//! every replica enforces the same locks.
+
+use std::fmt;
+use std::net::IpAddr;
+
+use chrono::{DateTime, Duration, Utc};
+
+use super::auth::BoxFuture;
+use super::error::AuthError;
+
+/// What a failure counter is keyed by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThrottleScope {
+    /// Login email address, whether or not an account exists for it
+    Email,
+    /// Source IP address of the request
+    Ip,
+}
+
+impl ThrottleScope {
+    /// Stable identifier used in storage
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Email => "email",
+            Self::Ip => "ip",
+        }
+    }
+}
+
+impl fmt::Display for ThrottleScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Subject whose failed logins are counted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThrottleKey {
+    scope: ThrottleScope,
+    value: String,
+}
+
+impl ThrottleKey {
+    /// Key for a login email address
+    ///
+    /// The address is normalized the same way as `Email`, so differently
+    /// cased spellings share one counter.
+    #[must_use]
+    pub fn email(email: &str) -> Self {
+        Self {
+            scope: ThrottleScope::Email,
+            value: email.trim().to_lowercase(),
+        }
+    }
+
+    /// Key for a source IP address
+    #[must_use]
+    pub fn ip(ip: IpAddr) -> Self {
+        Self {
+            scope: ThrottleScope::Ip,
+            value: ip.to_string(),
+        }
+    }
+
+    /// Get the scope
+    #[must_use]
+    pub fn scope(&self) -> ThrottleScope {
+        self.scope
+    }
+
+    /// Get the normalized email address or IP address
+    #[must_use]
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+}
+
+/// Thresholds and durations for lockouts
+#[derive(Debug, Clone, Copy)]
+pub struct LockoutPolicy {
+    /// Failures per email address before it is locked
+    pub email_threshold: i32,
+    /// Failures per IP address before it is locked; higher than the email
+    /// threshold because many users can share an address
+    pub ip_threshold: i32,
+    /// Lockout after reaching a threshold
+    pub base_lockout: Duration,
+    /// Upper bound for the doubling lockout
+    pub max_lockout: Duration,
+    /// Quiet period after which failures are forgotten
+    pub reset_after: Duration,
+}
+
+impl LockoutPolicy {
+    /// Failure threshold for `scope`
+    #[must_use]
+    pub fn threshold(&self, scope: ThrottleScope) -> i32 {
+        match scope {
+            ThrottleScope::Email => self.email_threshold,
+            ThrottleScope::Ip => self.ip_threshold,
+        }
+    }
+
+    /// Lockout after `failures` consecutive failures, if any
+    #[must_use]
+    pub fn lockout_for(&self, scope: ThrottleScope, failures: i32) -> Option<Duration> {
+        let excess = failures.checked_sub(self.threshold(scope))?;
+        if excess < 0 {
+            return None;
+        }
+
+        // 2^excess overflows quickly; anything past 2^20 is capped anyway
+        let factor = 1i32 << excess.min(20);
+        Some(
+            self.base_lockout
+                .checked_mul(factor)
+                .map_or(self.max_lockout, |lockout| lockout.min(self.max_lockout)),
+        )
+    }
+}
+
+/// Failed login counter for one subject
+#[derive(Debug, Clone)]
+pub struct LoginThrottle {
+    key: ThrottleKey,
+    failed_attempts: i32,
+    last_failed_at: DateTime<Utc>,
+    locked_until: Option<DateTime<Utc>>,
+}
+
+impl LoginThrottle {
+    /// Counter for a subject's first failure, before it is recorded
+    #[must_use]
+    pub fn new(key: ThrottleKey, now: DateTime<Utc>) -> Self {
+        Self {
+            key,
+            failed_attempts: 0,
+            last_failed_at: now,
+            locked_until: None,
+        }
+    }
+
+    /// Reconstruct a counter from persistence
+    #[must_use]
+    pub fn from_persistence(
+        key: ThrottleKey,
+        failed_attempts: i32,
+        last_failed_at: DateTime<Utc>,
+        locked_until: Option<DateTime<Utc>>,
+    ) -> Self {
+        Self {
+            key,
+            failed_attempts,
+            last_failed_at,
+            locked_until,
+        }
+    }
+
+    /// Get the subject
+    #[must_use]
+    pub fn key(&self) -> &ThrottleKey {
+        &self.key
+    }
+
+    /// Get the number of failures since the counter was last reset
+    #[must_use]
+    pub fn failed_attempts(&self) -> i32 {
+        self.failed_attempts
+    }
+
+    /// Get the time of the latest failure
+    #[must_use]
+    pub fn last_failed_at(&self) -> DateTime<Utc> {
+        self.last_failed_at
+    }
+
+    /// Get the end of the current or most recent lockout
+    #[must_use]
+    pub fn locked_until(&self) -> Option<DateTime<Utc>> {
+        self.locked_until
+    }
+
+    /// Time left until the subject may try again, if it is locked
+    #[must_use]
+    pub fn retry_after(&self, now: DateTime<Utc>) -> Option<Duration> {
+        self.locked_until
+            .filter(|until| *until > now)
+            .map(|until| until - now)
+    }
+
+    /// Count a failure, locking the subject once its threshold is reached
+    ///
+    /// The quiet period runs from the later of the last failure and the end
+    /// of the last lockout, so long lockouts do not reset the back-off.
+    pub fn record_failure(&mut self, now: DateTime<Utc>, policy: &LockoutPolicy) {
+        let quiet_since = self
+            .locked_until
+            .map_or(self.last_failed_at, |until| until.max(self.last_failed_at));
+        if now - quiet_since > policy.reset_after {
+            self.failed_attempts = 0;
+            self.locked_until = None;
+        }
+
+        self.failed_attempts = self.failed_attempts.saturating_add(1);
+        self.last_failed_at = now;
+        if let Some(lockout) = policy.lockout_for(self.key.scope(), self.failed_attempts) {
+            self.locked_until = Some(now + lockout);
+        }
+    }
+}
+
+/// Persistence port for failed login counters
+pub trait LoginThrottleRepository {
+    /// Find the counter for a subject, if any failures are on record
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on storage failures
+    fn find(&self, key: &ThrottleKey) -> Result<Option<LoginThrottle>, AuthError>;
+
+    /// Record a failure for a subject and return the updated counter
+    ///
+    /// Implementations must apply `LoginThrottle::record_failure` atomically,
+    /// so concurrent failures on different replicas are all counted.
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on storage failures
+    fn record_failure(
+        &self,
+        key: &ThrottleKey,
+        policy: &LockoutPolicy,
+        now: DateTime<Utc>,
+    ) -> Result<LoginThrottle, AuthError>;
+
+    /// Forget all failures for a subject, lifting any lockout
+    ///
+    /// Returns `false` if nothing was on record.
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on storage failures
+    fn clear(&self, key: &ThrottleKey) -> Result<bool, AuthError>;
+
+    /// Delete counters whose latest failure and lockout both ended before `cutoff`
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on storage failures
+    fn purge_stale(&self, cutoff: DateTime<Utc>) -> Result<usize, AuthError>;
+}
+
+/// Async variant of [`LoginThrottleRepository`]
+///
+/// Errors are those of the matching [`LoginThrottleRepository`] method, and
+/// `record_failure` must be just as atomic.
+pub trait AsyncLoginThrottleRepository {
+    /// Find the counter for a subject, if any failures are on record
+    fn find<'a>(&'a self, key: &'a ThrottleKey)
+        -> BoxFuture<'a, Result<Option<LoginThrottle>, AuthError>>;
+
+    /// Record a failure for a subject and return the updated counter
+    fn record_failure<'a>(
+        &'a self,
+        key: &'a ThrottleKey,
+        policy: &'a LockoutPolicy,
+        now: DateTime<Utc>,
+    ) -> BoxFuture<'a, Result<LoginThrottle, AuthError>>;
+
+    /// Forget all failures for a subject, lifting any lockout
+    fn clear<'a>(&'a self, key: &'a ThrottleKey) -> BoxFuture<'a, Result<bool, AuthError>>;
+
+    /// Delete counters whose latest failure and lockout both ended before `cutoff`
+    fn purge_stale(&self, cutoff: DateTime<Utc>) -> BoxFuture<'_, Result<usize, AuthError>>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> LockoutPolicy {
+        LockoutPolicy {
+            email_threshold: 3,
+            ip_threshold: 10,
+            base_lockout: Duration::seconds(30),
+            max_lockout: Duration::seconds(100),
+            reset_after: Duration::minutes(15),
+        }
+    }
+
+    #[test]
+    fn test_lockout_doubles_up_to_cap() {
+        let policy = policy();
+
+        assert_eq!(policy.lockout_for(ThrottleScope::Email, 2), None);
+        assert_eq!(
+            policy.lockout_for(ThrottleScope::Email, 3),
+            Some(Duration::seconds(30))
+        );
+        assert_eq!(
+            policy.lockout_for(ThrottleScope::Email, 4),
+            Some(Duration::seconds(60))
+        );
+        assert_eq!(
+            policy.lockout_for(ThrottleScope::Email, 5),
+            Some(Duration::seconds(100))
+        );
+        assert_eq!(
+            policy.lockout_for(ThrottleScope::Email, i32::MAX),
+            Some(Duration::seconds(100))
+        );
+        assert_eq!(policy.lockout_for(ThrottleScope::Ip, 5), None);
+    }
+
+    #[test]
+    fn test_record_failure_locks_at_threshold() {
+        let policy = policy();
+        let now = Utc::now();
+        let mut throttle = LoginThrottle::new(ThrottleKey::email(" User@Example.com "), now);
+
+        throttle.record_failure(now, &policy);
+        throttle.record_failure(now, &policy);
+        assert_eq!(throttle.retry_after(now), None);
+
+        throttle.record_failure(now, &policy);
+        assert_eq!(throttle.retry_after(now), Some(Duration::seconds(30)));
+        assert_eq!(throttle.key().value(), "user@example.com");
+    }
+
+    #[test]
+    fn test_failures_are_forgotten_after_quiet_period() {
+        let policy = policy();
+        let start = Utc::now();
+        let mut throttle = LoginThrottle::new(ThrottleKey::email("user@example.com"), start);
+        for _ in 0..3 {
+            throttle.record_failure(start, &policy);
+        }
+
+        // Counted from the end of the lockout, not the last failure
+        let lock_end = start + Duration::seconds(30);
+        throttle.record_failure(lock_end + Duration::minutes(10), &policy);
+        assert_eq!(throttle.failed_attempts(), 4);
+
+        let later = lock_end + Duration::minutes(30);
+        throttle.record_failure(later, &policy);
+        assert_eq!(throttle.failed_attempts(), 1);
+        assert_eq!(throttle.retry_after(later), None);
+    }
+}
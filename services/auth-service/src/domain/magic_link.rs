@@ -0,0 +1,182 @@
+//! Magic link domain entity
+//!
+//! A magic link signs a user in without a password. Like reset tokens, the
+//! secret is mailed to the user and only its hash is persisted. Links are
+//! short-lived, work once, and only a few are mailed per address and window.
+
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+use super::auth::BoxFuture;
+use super::error::AuthError;
+
+/// How many magic links one account may be mailed
+#[derive(Debug, Clone, Copy)]
+pub struct MagicLinkRateLimit {
+    /// Links per window; further requests are dropped
+    pub max_links: i64,
+    /// Sliding window the links are counted in
+    pub window: Duration,
+}
+
+/// Magic link token entity
+#[derive(Debug, Clone)]
+pub struct MagicLinkToken {
+    id: Uuid,
+    user_id: Uuid,
+    token_hash: String,
+    expires_at: DateTime<Utc>,
+    created_at: DateTime<Utc>,
+    used_at: Option<DateTime<Utc>>,
+}
+
+impl MagicLinkToken {
+    /// Issue a new magic link token
+    ///
+    /// # Arguments
+    /// * `user_id` - User the link signs in
+    /// * `token_hash` - Hash of the opaque secret mailed to the user
+    /// * `ttl` - Lifetime of the link
+    #[must_use]
+    pub fn issue(user_id: Uuid, token_hash: String, ttl: Duration) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            user_id,
+            token_hash,
+            expires_at: now + ttl,
+            created_at: now,
+            used_at: None,
+        }
+    }
+
+    /// Reconstruct a magic link token from persistence
+    #[must_use]
+    pub fn from_persistence(
+        id: Uuid,
+        user_id: Uuid,
+        token_hash: String,
+        expires_at: DateTime<Utc>,
+        created_at: DateTime<Utc>,
+        used_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        Self {
+            id,
+            user_id,
+            token_hash,
+            expires_at,
+            created_at,
+            used_at,
+        }
+    }
+
+    /// Get the token ID
+    #[must_use]
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// Get the ID of the user the link signs in
+    #[must_use]
+    pub fn user_id(&self) -> Uuid {
+        self.user_id
+    }
+
+    /// Get the stored hash of the secret
+    #[must_use]
+    pub fn token_hash(&self) -> &str {
+        &self.token_hash
+    }
+
+    /// Get the expiration timestamp
+    #[must_use]
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        self.expires_at
+    }
+
+    /// Get the creation timestamp
+    #[must_use]
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    /// Get the timestamp at which the link was used
+    #[must_use]
+    pub fn used_at(&self) -> Option<DateTime<Utc>> {
+        self.used_at
+    }
+
+    /// Check whether the link has already been used
+    #[must_use]
+    pub fn is_used(&self) -> bool {
+        self.used_at.is_some()
+    }
+
+    /// Check whether the link is past its expiry at `now`
+    #[must_use]
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        now >= self.expires_at
+    }
+}
+
+/// Repository interface for magic link token persistence
+pub trait MagicLinkTokenRepository {
+    /// Store a newly issued token
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn create(&self, token: &MagicLinkToken) -> Result<(), AuthError>;
+
+    /// Count the tokens issued to a user since `since`, used or not
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn count_issued_since(&self, user_id: Uuid, since: DateTime<Utc>) -> Result<i64, AuthError>;
+
+    /// Delete tokens that expired before `cutoff`
+    ///
+    /// Returns the number of tokens removed.
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn purge_expired(&self, cutoff: DateTime<Utc>) -> Result<usize, AuthError>;
+}
+
+/// Async repository interface for signing in with magic links
+///
+/// The login awaits these instead of holding a blocking thread.
+pub trait AsyncMagicLinkTokenRepository {
+    /// Find a token by the hash of its secret
+    ///
+    /// # Errors
+    /// Returns `AuthError::InvalidToken` if no token has this hash
+    /// Returns `AuthError::Internal` on database errors
+    fn find_by_hash<'a>(
+        &'a self,
+        token_hash: &'a str,
+    ) -> BoxFuture<'a, Result<MagicLinkToken, AuthError>>;
+
+    /// Atomically mark a token as used, along with every other unused token
+    /// issued to the same user
+    ///
+    /// Returns `Ok(false)` if the token was already used (e.g. a concurrent
+    /// request won the race).
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn consume<'a>(&'a self, token: &'a MagicLinkToken) -> BoxFuture<'a, Result<bool, AuthError>>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_issue_is_usable() {
+        let token = MagicLinkToken::issue(Uuid::new_v4(), "h".to_string(), Duration::minutes(15));
+
+        assert!(!token.is_used());
+        assert!(!token.is_expired(Utc::now()));
+        assert!(token.is_expired(Utc::now() + Duration::minutes(15)));
+    }
+}
@@ -0,0 +1,24 @@
+//! Outgoing email port
+//!
+//! Use cases that need to reach a user out of band (password resets, email
+//! verification) hand a message to a `Mailer`. Delivery is an
+//! infrastructure concern.
+
+use super::error::AuthError;
+
+/// Plain-text email message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmailMessage {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+/// Service interface for sending email
+pub trait Mailer {
+    /// Send a message
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` if the message cannot be delivered
+    fn send(&self, message: &EmailMessage) -> Result<(), AuthError>;
+}
@@ -0,0 +1,412 @@
+//! Multi-factor authentication domain types
+//!
+//! Users can enroll a TOTP authenticator app. Enrollment only takes effect
+//! once the user proves possession by entering a first code, at which point
+//! a set of single-use recovery codes is handed out. Logging in to an
+//! enrolled account is a two-step process: the password yields a short-lived
+//! MFA challenge, which is exchanged together with a code for the real
+//! tokens.
+
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+use super::auth::BoxFuture;
+use super::error::AuthError;
+
+/// Number of recovery codes issued when TOTP is enabled
+pub const RECOVERY_CODE_COUNT: usize = 10;
+
+/// Wrong codes accepted for one challenge before it is burned
+pub const MAX_MFA_ATTEMPTS: i32 = 5;
+
+/// TOTP credential of a user
+///
+/// A credential is pending until confirmed with a first valid code; only
+/// confirmed credentials are enforced at login.
+#[derive(Debug, Clone)]
+pub struct TotpCredential {
+    user_id: Uuid,
+    secret: String,
+    confirmed_at: Option<DateTime<Utc>>,
+    last_used_step: Option<i64>,
+    created_at: DateTime<Utc>,
+}
+
+impl TotpCredential {
+    /// Start a new, unconfirmed enrollment
+    ///
+    /// # Arguments
+    /// * `user_id` - User enrolling the authenticator
+    /// * `secret` - Base32-encoded shared secret
+    #[must_use]
+    pub fn new(user_id: Uuid, secret: String) -> Self {
+        Self {
+            user_id,
+            secret,
+            confirmed_at: None,
+            last_used_step: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Reconstruct a credential from persistence
+    #[must_use]
+    pub fn from_persistence(
+        user_id: Uuid,
+        secret: String,
+        confirmed_at: Option<DateTime<Utc>>,
+        last_used_step: Option<i64>,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            user_id,
+            secret,
+            confirmed_at,
+            last_used_step,
+            created_at,
+        }
+    }
+
+    /// Get the owning user's ID
+    #[must_use]
+    pub fn user_id(&self) -> Uuid {
+        self.user_id
+    }
+
+    /// Get the base32-encoded shared secret
+    #[must_use]
+    pub fn secret(&self) -> &str {
+        &self.secret
+    }
+
+    /// Get the timestamp at which the enrollment was confirmed
+    #[must_use]
+    pub fn confirmed_at(&self) -> Option<DateTime<Utc>> {
+        self.confirmed_at
+    }
+
+    /// Get the time step of the last accepted code
+    #[must_use]
+    pub fn last_used_step(&self) -> Option<i64> {
+        self.last_used_step
+    }
+
+    /// Get the creation timestamp
+    #[must_use]
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    /// Check whether the enrollment has been confirmed
+    #[must_use]
+    pub fn is_confirmed(&self) -> bool {
+        self.confirmed_at.is_some()
+    }
+}
+
+/// Pending second login step, handed out after a correct password
+///
+/// Like other opaque tokens, only a hash of the challenge secret is stored.
+#[derive(Debug, Clone)]
+pub struct MfaChallenge {
+    id: Uuid,
+    user_id: Uuid,
+    token_hash: String,
+    failed_attempts: i32,
+    expires_at: DateTime<Utc>,
+    created_at: DateTime<Utc>,
+    used_at: Option<DateTime<Utc>>,
+}
+
+impl MfaChallenge {
+    /// Issue a new challenge
+    ///
+    /// # Arguments
+    /// * `user_id` - User who passed the password check
+    /// * `token_hash` - Hash of the opaque secret returned to the client
+    /// * `ttl` - Lifetime of the challenge
+    #[must_use]
+    pub fn issue(user_id: Uuid, token_hash: String, ttl: Duration) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            user_id,
+            token_hash,
+            failed_attempts: 0,
+            expires_at: now + ttl,
+            created_at: now,
+            used_at: None,
+        }
+    }
+
+    /// Reconstruct a challenge from persistence
+    #[must_use]
+    pub fn from_persistence(
+        id: Uuid,
+        user_id: Uuid,
+        token_hash: String,
+        failed_attempts: i32,
+        expires_at: DateTime<Utc>,
+        created_at: DateTime<Utc>,
+        used_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        Self {
+            id,
+            user_id,
+            token_hash,
+            failed_attempts,
+            expires_at,
+            created_at,
+            used_at,
+        }
+    }
+
+    /// Get the challenge ID
+    #[must_use]
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// Get the user's ID
+    #[must_use]
+    pub fn user_id(&self) -> Uuid {
+        self.user_id
+    }
+
+    /// Get the stored hash of the secret
+    #[must_use]
+    pub fn token_hash(&self) -> &str {
+        &self.token_hash
+    }
+
+    /// Get the number of wrong codes entered so far
+    #[must_use]
+    pub fn failed_attempts(&self) -> i32 {
+        self.failed_attempts
+    }
+
+    /// Get the expiration timestamp
+    #[must_use]
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        self.expires_at
+    }
+
+    /// Get the creation timestamp
+    #[must_use]
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    /// Get the timestamp at which the challenge was used
+    #[must_use]
+    pub fn used_at(&self) -> Option<DateTime<Utc>> {
+        self.used_at
+    }
+
+    /// Check whether the challenge has already been used or burned
+    #[must_use]
+    pub fn is_used(&self) -> bool {
+        self.used_at.is_some()
+    }
+
+    /// Check whether the challenge is past its expiry at `now`
+    #[must_use]
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        now >= self.expires_at
+    }
+}
+
+/// Check whether a submitted code has the shape of a TOTP code
+///
+/// Anything else is treated as a recovery code.
+#[must_use]
+pub fn is_totp_code(code: &str) -> bool {
+    code.len() == 6 && code.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Canonical form of a recovery code, ignoring case, spaces and dashes
+#[must_use]
+pub fn normalize_recovery_code(code: &str) -> String {
+    code.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+/// Service interface for TOTP secrets and codes (RFC 6238)
+pub trait TotpService {
+    /// Generate a new random, base32-encoded shared secret
+    fn generate_secret(&self) -> String;
+
+    /// Build the `otpauth://` URI that authenticator apps import
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` if the secret or account name is unusable
+    fn provisioning_uri(&self, secret: &str, account_name: &str) -> Result<String, AuthError>;
+
+    /// Check a code against the secret at `now`, allowing for clock skew
+    ///
+    /// Returns the time step the code belongs to, so callers can reject
+    /// codes that were already used, or `None` if the code is wrong.
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` if the stored secret is malformed
+    fn verify(
+        &self,
+        secret: &str,
+        code: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Option<i64>, AuthError>;
+
+    /// Generate a single-use recovery code, readable enough to type by hand
+    fn generate_recovery_code(&self) -> String;
+}
+
+/// Repository interface for TOTP credentials and recovery codes
+pub trait MfaRepository {
+    /// Find the TOTP credential of a user, confirmed or not
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn find_totp(&self, user_id: Uuid) -> Result<Option<TotpCredential>, AuthError>;
+
+    /// Store a pending credential, replacing any earlier pending one
+    ///
+    /// # Errors
+    /// Returns `AuthError::MfaAlreadyEnabled` if the user has a confirmed credential
+    /// Returns `AuthError::Internal` on database errors
+    fn save_pending_totp(&self, credential: &TotpCredential) -> Result<(), AuthError>;
+
+    /// Atomically confirm a pending credential, record `step` as used and
+    /// replace the user's recovery codes with `recovery_code_hashes`
+    ///
+    /// Returns `Ok(false)` if there is no pending credential (e.g. a
+    /// concurrent confirmation won the race).
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn activate_totp(
+        &self,
+        user_id: Uuid,
+        step: i64,
+        recovery_code_hashes: &[String],
+    ) -> Result<bool, AuthError>;
+}
+
+/// Async repository interface for the credentials and recovery codes a
+/// login checks
+///
+/// Awaited by password, magic-link, federated and two-factor logins instead
+/// of holding a blocking thread.
+pub trait AsyncMfaRepository {
+    /// Find the TOTP credential of a user, confirmed or not
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn find_totp(&self, user_id: Uuid) -> BoxFuture<'_, Result<Option<TotpCredential>, AuthError>>;
+
+    /// Atomically record `step` as used if it is newer than the last one
+    ///
+    /// Returns `Ok(false)` if a code from this or a later step was already
+    /// accepted, i.e. the code is being replayed.
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn record_totp_step(&self, user_id: Uuid, step: i64) -> BoxFuture<'_, Result<bool, AuthError>>;
+
+    /// Whether the user has an unused recovery code with this hash
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn has_recovery_code<'a>(
+        &'a self,
+        user_id: Uuid,
+        code_hash: &'a str,
+    ) -> BoxFuture<'a, Result<bool, AuthError>>;
+
+    /// Atomically mark an unused recovery code as used
+    ///
+    /// Returns `Ok(false)` if the user has no unused code with this hash.
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn consume_recovery_code<'a>(
+        &'a self,
+        user_id: Uuid,
+        code_hash: &'a str,
+    ) -> BoxFuture<'a, Result<bool, AuthError>>;
+}
+
+/// Repository interface for the upkeep of MFA challenges
+pub trait MfaChallengeRepository {
+    /// Delete challenges that have expired by `now`
+    ///
+    /// Returns the number of challenges removed.
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn purge_expired(&self, now: DateTime<Utc>) -> Result<usize, AuthError>;
+}
+
+/// Async repository interface for MFA challenges
+///
+/// Issued and answered during login, which awaits them instead of holding
+/// a blocking thread.
+pub trait AsyncMfaChallengeRepository {
+    /// Store a newly issued challenge
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn create<'a>(&'a self, challenge: &'a MfaChallenge) -> BoxFuture<'a, Result<(), AuthError>>;
+
+    /// Find a challenge by the hash of its secret
+    ///
+    /// # Errors
+    /// Returns `AuthError::InvalidToken` if no challenge has this hash
+    /// Returns `AuthError::Internal` on database errors
+    fn find_by_hash<'a>(
+        &'a self,
+        token_hash: &'a str,
+    ) -> BoxFuture<'a, Result<MfaChallenge, AuthError>>;
+
+    /// Count a wrong code against the challenge, burning it once
+    /// `MAX_MFA_ATTEMPTS` is reached
+    ///
+    /// Returns the number of failed attempts after this one.
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn record_failed_attempt<'a>(
+        &'a self,
+        challenge: &'a MfaChallenge,
+    ) -> BoxFuture<'a, Result<i32, AuthError>>;
+
+    /// Atomically mark a challenge as used
+    ///
+    /// Returns `Ok(false)` if it was already used or burned.
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn consume<'a>(&'a self, challenge: &'a MfaChallenge) -> BoxFuture<'a, Result<bool, AuthError>>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_credential_is_pending() {
+        let credential = TotpCredential::new(Uuid::new_v4(), "SECRET".to_string());
+
+        assert!(!credential.is_confirmed());
+        assert_eq!(credential.last_used_step(), None);
+    }
+
+    #[test]
+    fn test_code_shapes() {
+        assert!(is_totp_code("012345"));
+        assert!(!is_totp_code("01234"));
+        assert!(!is_totp_code("ABCDE-FGHIJ"));
+        assert_eq!(normalize_recovery_code(" abcde-fghij "), "ABCDEFGHIJ");
+    }
+}
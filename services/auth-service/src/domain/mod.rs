@@ -2,6 +2,30 @@
 //!
 //! This layer has no dependencies on infrastructure or frameworks.
 
+pub mod api_key;
+pub mod audit;
 pub mod auth;
+pub mod email_verification;
 pub mod error;
+pub mod events;
+pub mod federation;
+pub mod health;
+pub mod impersonation;
+pub mod lockout;
+pub mod magic_link;
+pub mod mailer;
+pub mod mfa;
+pub mod oauth_client;
+pub mod oidc;
+pub mod organization;
+pub mod outbox;
+pub mod password_policy;
+pub mod password_reset;
+pub mod personal_data;
+pub mod refresh_token;
+pub mod role;
+pub mod session;
+pub mod signing_key;
+pub mod token_revocation;
 pub mod user;
+pub mod user_search;
@@ -0,0 +1,274 @@
+//! OAuth clients for service-to-service calls
+//!
+//! Backend services authenticate as registered clients using the OAuth2
+//! client_credentials grant. A client is identified by its client ID and
+//! proves itself with a secret, of which only a hash is stored; the tokens
+//! it obtains carry no roles, only the scopes the client was registered with.
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use super::error::AuthError;
+use super::role::Permission;
+
+/// Longest accepted client name, in characters
+pub const MAX_CLIENT_NAME_LEN: usize = 100;
+
+/// Validate and normalize a client name
+///
+/// # Errors
+/// Returns `AuthError::InvalidClientName` if the name is empty, too long or
+/// contains control characters
+pub fn normalize_client_name(value: &str) -> Result<String, AuthError> {
+    let trimmed = value.trim();
+    if trimmed.is_empty()
+        || trimmed.chars().count() > MAX_CLIENT_NAME_LEN
+        || trimmed.chars().any(char::is_control)
+    {
+        return Err(AuthError::InvalidClientName);
+    }
+    Ok(trimmed.to_string())
+}
+
+/// Parse an OAuth `scope` parameter: space-delimited permission names
+///
+/// # Errors
+/// Returns `AuthError::InvalidScope` if a permission name is not recognised
+pub fn parse_scope(scope: &str) -> Result<Vec<Permission>, AuthError> {
+    scope.split_whitespace().map(str::parse).collect()
+}
+
+/// Format scopes as an OAuth `scope` parameter
+#[must_use]
+pub fn format_scope(scopes: &[Permission]) -> String {
+    scopes
+        .iter()
+        .map(Permission::as_str)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Registered OAuth client entity
+#[derive(Debug, Clone)]
+pub struct OAuthClient {
+    client_id: Uuid,
+    name: String,
+    secret_hash: String,
+    scopes: Vec<Permission>,
+    created_at: DateTime<Utc>,
+    revoked_at: Option<DateTime<Utc>>,
+}
+
+impl OAuthClient {
+    /// Register a new client
+    ///
+    /// # Arguments
+    /// * `client_id` - Identifier the client presents, and the `sub` of its tokens
+    /// * `name` - Already normalized name, usually the calling service's
+    /// * `secret_hash` - Hash of the secret given to the client
+    /// * `scopes` - Permissions the client may request
+    #[must_use]
+    pub fn register(
+        client_id: Uuid,
+        name: String,
+        secret_hash: String,
+        scopes: Vec<Permission>,
+    ) -> Self {
+        let mut scopes = scopes;
+        scopes.sort();
+        scopes.dedup();
+        Self {
+            client_id,
+            name,
+            secret_hash,
+            scopes,
+            created_at: Utc::now(),
+            revoked_at: None,
+        }
+    }
+
+    /// Reconstruct a client from persistence
+    #[must_use]
+    pub fn from_persistence(
+        client_id: Uuid,
+        name: String,
+        secret_hash: String,
+        scopes: Vec<Permission>,
+        created_at: DateTime<Utc>,
+        revoked_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        Self {
+            client_id,
+            name,
+            secret_hash,
+            scopes,
+            created_at,
+            revoked_at,
+        }
+    }
+
+    /// Get the client ID
+    #[must_use]
+    pub fn client_id(&self) -> Uuid {
+        self.client_id
+    }
+
+    /// Get the client name
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the stored hash of the client secret
+    #[must_use]
+    pub fn secret_hash(&self) -> &str {
+        &self.secret_hash
+    }
+
+    /// Get the permissions the client may request
+    #[must_use]
+    pub fn scopes(&self) -> &[Permission] {
+        &self.scopes
+    }
+
+    /// Get the registration timestamp
+    #[must_use]
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    /// Get the revocation timestamp
+    #[must_use]
+    pub fn revoked_at(&self) -> Option<DateTime<Utc>> {
+        self.revoked_at
+    }
+
+    /// Check if the client has been revoked
+    #[must_use]
+    pub fn is_revoked(&self) -> bool {
+        self.revoked_at.is_some()
+    }
+
+    /// Resolve the scopes granted for a token request
+    ///
+    /// An empty request is granted every scope the client was registered with.
+    ///
+    /// # Errors
+    /// Returns `AuthError::InvalidScope` if a requested scope was not
+    /// registered for the client
+    pub fn grant_scopes(&self, requested: &[Permission]) -> Result<Vec<Permission>, AuthError> {
+        if requested.is_empty() {
+            return Ok(self.scopes.clone());
+        }
+        if requested.iter().any(|scope| !self.scopes.contains(scope)) {
+            return Err(AuthError::InvalidScope);
+        }
+        let mut granted = requested.to_vec();
+        granted.sort();
+        granted.dedup();
+        Ok(granted)
+    }
+}
+
+/// Access token issued to a service
+#[derive(Debug, Clone)]
+pub struct ServiceToken {
+    pub access_token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Repository interface for OAuth client persistence
+pub trait OAuthClientRepository {
+    /// Persist a newly registered client
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn create(&self, client: &OAuthClient) -> Result<(), AuthError>;
+
+    /// Find a client by its ID
+    ///
+    /// # Errors
+    /// Returns `AuthError::OAuthClientNotFound` if the client doesn't exist
+    /// Returns `AuthError::Internal` on database errors
+    fn find_by_id(&self, client_id: Uuid) -> Result<OAuthClient, AuthError>;
+
+    /// List the unrevoked clients, newest first
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn list(&self) -> Result<Vec<OAuthClient>, AuthError>;
+
+    /// Revoke a client, returning false if it was already revoked or doesn't exist
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn revoke(&self, client_id: Uuid) -> Result<bool, AuthError>;
+}
+
+/// Service interface for issuing access tokens to OAuth clients
+pub trait ServiceTokenIssuer {
+    /// Create a token for `client`, limited to `scopes`
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` if token creation fails
+    fn create_service_token(
+        &self,
+        client: &OAuthClient,
+        scopes: &[Permission],
+    ) -> Result<ServiceToken, AuthError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client() -> OAuthClient {
+        OAuthClient::register(
+            Uuid::new_v4(),
+            "orders-service".to_string(),
+            "hash".to_string(),
+            vec![Permission::ReadUsers, Permission::ReadAllOrders],
+        )
+    }
+
+    #[test]
+    fn test_scope_round_trip() {
+        let scopes = parse_scope(" users:read  orders:read_all ").unwrap();
+
+        assert_eq!(
+            scopes,
+            vec![Permission::ReadUsers, Permission::ReadAllOrders]
+        );
+        assert_eq!(format_scope(&scopes), "users:read orders:read_all");
+        assert_eq!(parse_scope(""), Ok(vec![]));
+        assert_eq!(parse_scope("users:read root"), Err(AuthError::InvalidScope));
+    }
+
+    #[test]
+    fn test_grant_scopes() {
+        let client = client();
+
+        assert_eq!(client.grant_scopes(&[]).unwrap(), client.scopes());
+        assert_eq!(
+            client.grant_scopes(&[Permission::ReadUsers]),
+            Ok(vec![Permission::ReadUsers])
+        );
+        assert_eq!(
+            client.grant_scopes(&[Permission::ReadUsers, Permission::ManageUsers]),
+            Err(AuthError::InvalidScope)
+        );
+    }
+
+    #[test]
+    fn test_normalize_client_name() {
+        assert_eq!(
+            normalize_client_name(" orders-service "),
+            Ok("orders-service".to_string())
+        );
+        assert_eq!(normalize_client_name(""), Err(AuthError::InvalidClientName));
+        assert_eq!(
+            normalize_client_name("orders\nservice"),
+            Err(AuthError::InvalidClientName)
+        );
+    }
+}
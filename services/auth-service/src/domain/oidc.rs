@@ -0,0 +1,507 @@
+//! OpenID Connect provider for first-party web apps
+//!
+//! Web apps sign users in with the authorization-code flow. They are public
+//! clients: instead of a secret, every code is bound to a PKCE challenge that
+//! only the app that started the flow can answer. Clients are registered by
+//! administrators and trusted, so users are never asked for consent.
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Duration, Utc};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use super::error::AuthError;
+use super::user::User;
+
+/// Scopes this provider understands
+pub const SUPPORTED_SCOPES: [&str; 3] = ["openid", "email", "profile"];
+
+/// Longest accepted client ID, in characters
+pub const MAX_CLIENT_ID_LEN: usize = 64;
+
+/// Parse the `scope` of an authorization request
+///
+/// Unknown scopes are ignored, as OpenID Connect requires; the result keeps
+/// the order of [`SUPPORTED_SCOPES`].
+///
+/// # Errors
+/// Returns `AuthError::InvalidScope` if `openid` was not requested
+pub fn parse_oidc_scope(scope: &str) -> Result<Vec<String>, AuthError> {
+    let requested: Vec<&str> = scope.split_whitespace().collect();
+    if !requested.contains(&"openid") {
+        return Err(AuthError::InvalidScope);
+    }
+    Ok(SUPPORTED_SCOPES
+        .iter()
+        .filter(|scope| requested.contains(scope))
+        .map(|scope| scope.to_string())
+        .collect())
+}
+
+/// Derive the S256 PKCE challenge for a code verifier
+#[must_use]
+pub fn pkce_challenge(code_verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))
+}
+
+/// Check that a PKCE challenge could have been derived with S256
+#[must_use]
+pub fn is_valid_pkce_challenge(code_challenge: &str) -> bool {
+    code_challenge.len() == 43
+        && code_challenge
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+}
+
+/// Check a PKCE code verifier against the challenge sent with the request
+///
+/// Verifiers must be 43 to 128 unreserved characters (RFC 7636).
+#[must_use]
+pub fn verify_pkce(code_verifier: &str, code_challenge: &str) -> bool {
+    let well_formed = (43..=128).contains(&code_verifier.len())
+        && code_verifier
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~'));
+    well_formed && pkce_challenge(code_verifier) == code_challenge
+}
+
+/// Validate a client ID chosen by an administrator
+///
+/// # Errors
+/// Returns `AuthError::InvalidRequest` unless the ID is 1 to 64 letters,
+/// digits, dots, dashes or underscores
+pub fn validate_client_id(client_id: &str) -> Result<(), AuthError> {
+    let valid = !client_id.is_empty()
+        && client_id.len() <= MAX_CLIENT_ID_LEN
+        && client_id
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_'));
+    if valid {
+        Ok(())
+    } else {
+        Err(AuthError::InvalidRequest("invalid client_id".to_string()))
+    }
+}
+
+/// Validate a redirect URI for registration
+///
+/// # Errors
+/// Returns `AuthError::InvalidRequest` unless the URI is an absolute http(s)
+/// URL without a fragment
+pub fn validate_redirect_uri(redirect_uri: &str) -> Result<(), AuthError> {
+    let host = redirect_uri
+        .strip_prefix("https://")
+        .or_else(|| redirect_uri.strip_prefix("http://"))
+        .unwrap_or_default();
+    if host.is_empty()
+        || host.starts_with('/')
+        || redirect_uri.contains('#')
+        || redirect_uri
+            .chars()
+            .any(|c| c.is_whitespace() || c.is_control())
+    {
+        return Err(AuthError::InvalidRequest(format!(
+            "invalid redirect_uri: {}",
+            redirect_uri
+        )));
+    }
+    Ok(())
+}
+
+/// First-party web app allowed to sign users in
+#[derive(Debug, Clone)]
+pub struct OidcClient {
+    client_id: String,
+    name: String,
+    redirect_uris: Vec<String>,
+    created_at: DateTime<Utc>,
+}
+
+impl OidcClient {
+    /// Register a new client
+    ///
+    /// # Arguments
+    /// * `client_id` - Already validated identifier, the `aud` of its ID tokens
+    /// * `name` - Already normalized name of the app
+    /// * `redirect_uris` - Already validated URIs codes may be sent to
+    #[must_use]
+    pub fn register(client_id: String, name: String, redirect_uris: Vec<String>) -> Self {
+        let mut redirect_uris = redirect_uris;
+        redirect_uris.dedup();
+        Self {
+            client_id,
+            name,
+            redirect_uris,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Reconstruct a client from persistence
+    #[must_use]
+    pub fn from_persistence(
+        client_id: String,
+        name: String,
+        redirect_uris: Vec<String>,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            client_id,
+            name,
+            redirect_uris,
+            created_at,
+        }
+    }
+
+    /// Get the client ID
+    #[must_use]
+    pub fn client_id(&self) -> &str {
+        &self.client_id
+    }
+
+    /// Get the client name
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the registered redirect URIs
+    #[must_use]
+    pub fn redirect_uris(&self) -> &[String] {
+        &self.redirect_uris
+    }
+
+    /// Get the registration timestamp
+    #[must_use]
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    /// Check whether codes may be sent to `redirect_uri`
+    ///
+    /// URIs must match a registered one exactly.
+    #[must_use]
+    pub fn allows_redirect_uri(&self, redirect_uri: &str) -> bool {
+        self.redirect_uris.iter().any(|uri| uri == redirect_uri)
+    }
+}
+
+/// Single-use code handed to a client after the user signed in
+///
+/// Only a hash of the code is persisted.
+#[derive(Debug, Clone)]
+pub struct AuthorizationCode {
+    code_hash: String,
+    client_id: String,
+    user_id: Uuid,
+    session_id: Uuid,
+    redirect_uri: String,
+    scopes: Vec<String>,
+    code_challenge: String,
+    nonce: Option<String>,
+    expires_at: DateTime<Utc>,
+    created_at: DateTime<Utc>,
+}
+
+impl AuthorizationCode {
+    /// Issue a new code
+    ///
+    /// # Arguments
+    /// * `code_hash` - Hash of the opaque code sent to the client
+    /// * `client_id` - Client the code was issued to
+    /// * `user_id` - User who signed in
+    /// * `session_id` - Session the user signed in with; the access token is bound to it
+    /// * `redirect_uri` - URI the code was sent to; must be repeated when redeeming
+    /// * `scopes` - Granted OpenID Connect scopes
+    /// * `code_challenge` - S256 PKCE challenge
+    /// * `nonce` - Value to echo in the ID token
+    /// * `ttl` - Lifetime of the code
+    #[allow(clippy::too_many_arguments)]
+    #[must_use]
+    pub fn issue(
+        code_hash: String,
+        client_id: String,
+        user_id: Uuid,
+        session_id: Uuid,
+        redirect_uri: String,
+        scopes: Vec<String>,
+        code_challenge: String,
+        nonce: Option<String>,
+        ttl: Duration,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            code_hash,
+            client_id,
+            user_id,
+            session_id,
+            redirect_uri,
+            scopes,
+            code_challenge,
+            nonce,
+            expires_at: now + ttl,
+            created_at: now,
+        }
+    }
+
+    /// Reconstruct a code from persistence
+    #[allow(clippy::too_many_arguments)]
+    #[must_use]
+    pub fn from_persistence(
+        code_hash: String,
+        client_id: String,
+        user_id: Uuid,
+        session_id: Uuid,
+        redirect_uri: String,
+        scopes: Vec<String>,
+        code_challenge: String,
+        nonce: Option<String>,
+        expires_at: DateTime<Utc>,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            code_hash,
+            client_id,
+            user_id,
+            session_id,
+            redirect_uri,
+            scopes,
+            code_challenge,
+            nonce,
+            expires_at,
+            created_at,
+        }
+    }
+
+    /// Get the stored hash of the code
+    #[must_use]
+    pub fn code_hash(&self) -> &str {
+        &self.code_hash
+    }
+
+    /// Get the ID of the client the code was issued to
+    #[must_use]
+    pub fn client_id(&self) -> &str {
+        &self.client_id
+    }
+
+    /// Get the ID of the user who signed in
+    #[must_use]
+    pub fn user_id(&self) -> Uuid {
+        self.user_id
+    }
+
+    /// Get the ID of the session the user signed in with
+    #[must_use]
+    pub fn session_id(&self) -> Uuid {
+        self.session_id
+    }
+
+    /// Get the URI the code was sent to
+    #[must_use]
+    pub fn redirect_uri(&self) -> &str {
+        &self.redirect_uri
+    }
+
+    /// Get the granted scopes
+    #[must_use]
+    pub fn scopes(&self) -> &[String] {
+        &self.scopes
+    }
+
+    /// Check whether a scope was granted
+    #[must_use]
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|s| s == scope)
+    }
+
+    /// Get the PKCE challenge
+    #[must_use]
+    pub fn code_challenge(&self) -> &str {
+        &self.code_challenge
+    }
+
+    /// Get the nonce to echo in the ID token
+    #[must_use]
+    pub fn nonce(&self) -> Option<&str> {
+        self.nonce.as_deref()
+    }
+
+    /// Get the expiration timestamp
+    #[must_use]
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        self.expires_at
+    }
+
+    /// Get the creation timestamp
+    #[must_use]
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    /// Check that the code may be redeemed by this token request
+    ///
+    /// # Errors
+    /// Returns `AuthError::InvalidGrant` if the code expired, was issued to
+    /// another client or redirect URI, or the PKCE verifier doesn't match
+    pub fn redeem(
+        &self,
+        client_id: &str,
+        redirect_uri: &str,
+        code_verifier: &str,
+        now: DateTime<Utc>,
+    ) -> Result<(), AuthError> {
+        if now >= self.expires_at
+            || client_id != self.client_id
+            || redirect_uri != self.redirect_uri
+            || !verify_pkce(code_verifier, &self.code_challenge)
+        {
+            return Err(AuthError::InvalidGrant);
+        }
+        Ok(())
+    }
+}
+
+/// Repository interface for OpenID Connect client persistence
+pub trait OidcClientRepository {
+    /// Register a client, or replace the name and redirect URIs of an existing one
+    ///
+    /// Returns the stored client.
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn save(&self, client: &OidcClient) -> Result<OidcClient, AuthError>;
+
+    /// Find a client by its ID
+    ///
+    /// # Errors
+    /// Returns `AuthError::InvalidClient` if the client doesn't exist
+    /// Returns `AuthError::Internal` on database errors
+    fn find_by_id(&self, client_id: &str) -> Result<OidcClient, AuthError>;
+}
+
+/// Repository interface for authorization code persistence
+pub trait AuthorizationCodeRepository {
+    /// Store a newly issued code
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn create(&self, code: &AuthorizationCode) -> Result<(), AuthError>;
+
+    /// Atomically remove and return the code with this hash
+    ///
+    /// Returns `Ok(None)` if no such code exists, e.g. because it was
+    /// already redeemed.
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn take(&self, code_hash: &str) -> Result<Option<AuthorizationCode>, AuthError>;
+
+    /// Delete codes that have expired by `now`
+    ///
+    /// Returns the number of codes removed.
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn purge_expired(&self, now: DateTime<Utc>) -> Result<usize, AuthError>;
+}
+
+/// Service interface for issuing the tokens an authorization code is
+/// exchanged for
+pub trait IdTokenIssuer {
+    /// Create an access token for `user`, bound to the session that obtained
+    /// `code` and carrying the scopes granted with it
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` if token creation fails
+    fn create_access_token(
+        &self,
+        user: &User,
+        code: &AuthorizationCode,
+    ) -> Result<String, AuthError>;
+
+    /// Create an ID token for `user`, who signed in to obtain `code`
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` if token creation fails
+    fn create_id_token(&self, user: &User, code: &AuthorizationCode) -> Result<String, AuthError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Verifier and challenge from RFC 7636, appendix B
+    const VERIFIER: &str = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+    const CHALLENGE: &str = "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM";
+
+    fn code() -> AuthorizationCode {
+        AuthorizationCode::issue(
+            "hash".to_string(),
+            "web".to_string(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            "https://app.example.com/callback".to_string(),
+            vec!["openid".to_string()],
+            CHALLENGE.to_string(),
+            None,
+            Duration::seconds(60),
+        )
+    }
+
+    #[test]
+    fn test_pkce() {
+        assert_eq!(pkce_challenge(VERIFIER), CHALLENGE);
+        assert!(is_valid_pkce_challenge(CHALLENGE));
+        assert!(verify_pkce(VERIFIER, CHALLENGE));
+        assert!(!verify_pkce(&VERIFIER.replace('d', "e"), CHALLENGE));
+        // Too short, even though the challenge matches
+        assert!(!verify_pkce("short", &pkce_challenge("short")));
+    }
+
+    #[test]
+    fn test_parse_oidc_scope() {
+        assert_eq!(
+            parse_oidc_scope("profile openid offline_access"),
+            Ok(vec!["openid".to_string(), "profile".to_string()])
+        );
+        assert_eq!(
+            parse_oidc_scope("email profile"),
+            Err(AuthError::InvalidScope)
+        );
+    }
+
+    #[test]
+    fn test_redeem_checks_binding() {
+        let code = code();
+        let now = Utc::now();
+        let redirect_uri = "https://app.example.com/callback";
+
+        assert!(code.redeem("web", redirect_uri, VERIFIER, now).is_ok());
+        for (client_id, redirect_uri, verifier, now) in [
+            ("other", redirect_uri, VERIFIER, now),
+            ("web", "https://app.example.com/other", VERIFIER, now),
+            ("web", redirect_uri, "wrong-verifier", now),
+            ("web", redirect_uri, VERIFIER, now + Duration::seconds(61)),
+        ] {
+            assert_eq!(
+                code.redeem(client_id, redirect_uri, verifier, now),
+                Err(AuthError::InvalidGrant)
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_redirect_uri() {
+        assert!(validate_redirect_uri("https://app.example.com/callback").is_ok());
+        assert!(validate_redirect_uri("http://localhost:3000/callback").is_ok());
+        for uri in [
+            "app.example.com",
+            "https://",
+            "https://a.com/#x",
+            "javascript:x",
+        ] {
+            assert!(validate_redirect_uri(uri).is_err());
+        }
+    }
+}
@@ -0,0 +1,491 @@
+//! Organizations, their members and invitations
+//!
+//! Event organizers work in teams: an organization groups the users acting
+//! for one organizer, each with a role inside it that is independent of
+//! their platform-wide roles. New members join through an invitation mailed
+//! to their address; like reset tokens, only a hash of the invitation
+//! secret is stored, and an invitation works once and until it expires.
+//!
+//! A user picks one of their organizations as the active one; it is carried
+//! in the access token so other services can scope requests to it.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+use super::auth::BoxFuture;
+use super::error::AuthError;
+use super::user::User;
+
+/// Longest accepted organization name, in characters
+pub const MAX_ORGANIZATION_NAME_LEN: usize = 100;
+
+/// Validate and normalize an organization name
+///
+/// # Errors
+/// Returns `AuthError::InvalidOrganizationName` if the name is empty, too
+/// long or contains control characters
+pub fn normalize_organization_name(value: &str) -> Result<String, AuthError> {
+    let trimmed = value.trim();
+    if trimmed.is_empty()
+        || trimmed.chars().count() > MAX_ORGANIZATION_NAME_LEN
+        || trimmed.chars().any(char::is_control)
+    {
+        return Err(AuthError::InvalidOrganizationName);
+    }
+    Ok(trimmed.to_string())
+}
+
+/// Role a member holds within an organization
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum OrgRole {
+    /// Works on the organization's events
+    Member,
+    /// Also invites new members
+    Admin,
+    /// Created the organization; cannot be granted by invitation
+    Owner,
+}
+
+impl OrgRole {
+    /// All roles, in ascending order of privilege
+    pub const ALL: [OrgRole; 3] = [OrgRole::Member, OrgRole::Admin, OrgRole::Owner];
+
+    /// Stable identifier used in storage and tokens
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Member => "member",
+            Self::Admin => "admin",
+            Self::Owner => "owner",
+        }
+    }
+
+    /// Check whether members with this role may invite others
+    #[must_use]
+    pub fn can_invite(&self) -> bool {
+        matches!(self, Self::Admin | Self::Owner)
+    }
+}
+
+impl fmt::Display for OrgRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for OrgRole {
+    type Err = AuthError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|role| role.as_str() == value.trim().to_lowercase())
+            .ok_or(AuthError::InvalidRole)
+    }
+}
+
+/// Organization a token acts for (`org_id` and `org_role` claims)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActiveOrganization {
+    pub id: Uuid,
+    /// Role of the user in the organization when the token was issued
+    pub role: OrgRole,
+}
+
+/// Organization entity
+#[derive(Debug, Clone)]
+pub struct Organization {
+    id: Uuid,
+    name: String,
+    created_at: DateTime<Utc>,
+}
+
+impl Organization {
+    /// Create a new organization
+    ///
+    /// # Arguments
+    /// * `name` - Already normalized name
+    #[must_use]
+    pub fn new(name: String) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            name,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Reconstruct an organization from persistence
+    #[must_use]
+    pub fn from_persistence(id: Uuid, name: String, created_at: DateTime<Utc>) -> Self {
+        Self {
+            id,
+            name,
+            created_at,
+        }
+    }
+
+    /// Get the organization ID
+    #[must_use]
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// Get the display name
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the creation timestamp
+    #[must_use]
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
+
+/// Membership of a user in an organization
+#[derive(Debug, Clone)]
+pub struct Membership {
+    organization_id: Uuid,
+    user_id: Uuid,
+    role: OrgRole,
+    created_at: DateTime<Utc>,
+}
+
+impl Membership {
+    /// Admit a user to an organization
+    #[must_use]
+    pub fn new(organization_id: Uuid, user_id: Uuid, role: OrgRole) -> Self {
+        Self {
+            organization_id,
+            user_id,
+            role,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Reconstruct a membership from persistence
+    #[must_use]
+    pub fn from_persistence(
+        organization_id: Uuid,
+        user_id: Uuid,
+        role: OrgRole,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            organization_id,
+            user_id,
+            role,
+            created_at,
+        }
+    }
+
+    /// Get the organization's ID
+    #[must_use]
+    pub fn organization_id(&self) -> Uuid {
+        self.organization_id
+    }
+
+    /// Get the member's user ID
+    #[must_use]
+    pub fn user_id(&self) -> Uuid {
+        self.user_id
+    }
+
+    /// Get the member's role in the organization
+    #[must_use]
+    pub fn role(&self) -> OrgRole {
+        self.role
+    }
+
+    /// Get the time the user joined
+    #[must_use]
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
+
+/// Invitation to join an organization
+#[derive(Debug, Clone)]
+pub struct Invitation {
+    id: Uuid,
+    organization_id: Uuid,
+    email: String,
+    role: OrgRole,
+    token_hash: String,
+    invited_by: Uuid,
+    expires_at: DateTime<Utc>,
+    created_at: DateTime<Utc>,
+    accepted_at: Option<DateTime<Utc>>,
+}
+
+impl Invitation {
+    /// Issue a new invitation
+    ///
+    /// # Arguments
+    /// * `organization_id` - Organization to join
+    /// * `email` - Normalized address the invitation is mailed to; only the
+    ///   account with this address can accept it
+    /// * `role` - Role the new member gets
+    /// * `token_hash` - Hash of the opaque secret mailed to the invitee
+    /// * `invited_by` - Member who sent the invitation
+    /// * `ttl` - Time the invitee has to accept
+    #[must_use]
+    pub fn issue(
+        organization_id: Uuid,
+        email: String,
+        role: OrgRole,
+        token_hash: String,
+        invited_by: Uuid,
+        ttl: Duration,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            organization_id,
+            email,
+            role,
+            token_hash,
+            invited_by,
+            expires_at: now + ttl,
+            created_at: now,
+            accepted_at: None,
+        }
+    }
+
+    /// Reconstruct an invitation from persistence
+    #[must_use]
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_persistence(
+        id: Uuid,
+        organization_id: Uuid,
+        email: String,
+        role: OrgRole,
+        token_hash: String,
+        invited_by: Uuid,
+        expires_at: DateTime<Utc>,
+        created_at: DateTime<Utc>,
+        accepted_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        Self {
+            id,
+            organization_id,
+            email,
+            role,
+            token_hash,
+            invited_by,
+            expires_at,
+            created_at,
+            accepted_at,
+        }
+    }
+
+    /// Get the invitation ID
+    #[must_use]
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// Get the ID of the organization to join
+    #[must_use]
+    pub fn organization_id(&self) -> Uuid {
+        self.organization_id
+    }
+
+    /// Get the address the invitation was sent to
+    #[must_use]
+    pub fn email(&self) -> &str {
+        &self.email
+    }
+
+    /// Get the role the new member gets
+    #[must_use]
+    pub fn role(&self) -> OrgRole {
+        self.role
+    }
+
+    /// Get the stored hash of the secret
+    #[must_use]
+    pub fn token_hash(&self) -> &str {
+        &self.token_hash
+    }
+
+    /// Get the ID of the member who sent the invitation
+    #[must_use]
+    pub fn invited_by(&self) -> Uuid {
+        self.invited_by
+    }
+
+    /// Get the expiration timestamp
+    #[must_use]
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        self.expires_at
+    }
+
+    /// Get the creation timestamp
+    #[must_use]
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    /// Get the timestamp at which the invitation was accepted
+    #[must_use]
+    pub fn accepted_at(&self) -> Option<DateTime<Utc>> {
+        self.accepted_at
+    }
+
+    /// Check whether the invitation has already been accepted
+    #[must_use]
+    pub fn is_accepted(&self) -> bool {
+        self.accepted_at.is_some()
+    }
+
+    /// Check whether the invitation is past its expiry at `now`
+    #[must_use]
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        now >= self.expires_at
+    }
+}
+
+/// Repository interface for organizations and their memberships
+pub trait OrganizationRepository {
+    /// Store a new organization together with the membership of its owner
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn create(&self, organization: &Organization, owner: &Membership) -> Result<(), AuthError>;
+
+    /// Find an organization by its ID
+    ///
+    /// # Errors
+    /// Returns `AuthError::OrganizationNotFound` if no organization has this ID
+    /// Returns `AuthError::Internal` on database errors
+    fn find_by_id(&self, id: Uuid) -> Result<Organization, AuthError>;
+
+    /// Find a user's membership in an organization, `None` if they are not
+    /// a member
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn find_membership(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<Membership>, AuthError>;
+
+    /// List the organizations a user belongs to, oldest membership first
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn list_for_user(&self, user_id: Uuid) -> Result<Vec<(Organization, Membership)>, AuthError>;
+}
+
+/// Async variant of the [`OrganizationRepository`] lookup made when a
+/// session acting for an organization is refreshed
+///
+/// Errors are those of [`OrganizationRepository::find_membership`].
+pub trait AsyncOrganizationRepository {
+    /// Find a user's membership in an organization
+    fn find_membership(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+    ) -> BoxFuture<'_, Result<Option<Membership>, AuthError>>;
+}
+
+/// Repository interface for invitation persistence
+pub trait InvitationRepository {
+    /// Store a newly issued invitation
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn create(&self, invitation: &Invitation) -> Result<(), AuthError>;
+
+    /// Find an invitation by the hash of its secret
+    ///
+    /// # Errors
+    /// Returns `AuthError::InvalidInvitation` if no invitation has this hash
+    /// Returns `AuthError::Internal` on database errors
+    fn find_by_hash(&self, token_hash: &str) -> Result<Invitation, AuthError>;
+
+    /// Atomically mark an invitation as accepted and add the membership
+    ///
+    /// Returns `Ok(false)` if the invitation was already accepted (e.g. a
+    /// concurrent request won the race). Users who are already members
+    /// keep their current role.
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn accept(&self, invitation: &Invitation, membership: &Membership) -> Result<bool, AuthError>;
+
+    /// Delete invitations that expired before `cutoff`
+    ///
+    /// Returns the number of invitations removed.
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn purge_expired(&self, cutoff: DateTime<Utc>) -> Result<usize, AuthError>;
+}
+
+/// Service interface for issuing access tokens that act for an organization
+pub trait OrganizationTokenIssuer {
+    /// Create an access token for `user` within `session_id`, like
+    /// `TokenService::create_token`, naming `organization` as the active one
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` if token creation fails
+    fn create_organization_token(
+        &self,
+        user: &User,
+        session_id: Option<Uuid>,
+        organization: ActiveOrganization,
+    ) -> Result<String, AuthError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_organization_name() {
+        assert_eq!(
+            normalize_organization_name("  Riverside Concerts ").unwrap(),
+            "Riverside Concerts"
+        );
+        assert!(normalize_organization_name("   ").is_err());
+        assert!(normalize_organization_name("a\nb").is_err());
+        assert!(normalize_organization_name(&"x".repeat(MAX_ORGANIZATION_NAME_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn test_org_role_round_trip() {
+        for role in OrgRole::ALL {
+            assert_eq!(role.as_str().parse::<OrgRole>().unwrap(), role);
+        }
+        assert!(matches!(
+            "superuser".parse::<OrgRole>(),
+            Err(AuthError::InvalidRole)
+        ));
+        assert!(!OrgRole::Member.can_invite());
+        assert!(OrgRole::Admin.can_invite());
+    }
+
+    #[test]
+    fn test_invitation_expires() {
+        let invitation = Invitation::issue(
+            Uuid::new_v4(),
+            "new@example.com".to_string(),
+            OrgRole::Member,
+            "h".to_string(),
+            Uuid::new_v4(),
+            Duration::days(7),
+        );
+
+        assert!(!invitation.is_accepted());
+        assert!(!invitation.is_expired(Utc::now()));
+        assert!(invitation.is_expired(Utc::now() + Duration::days(7)));
+    }
+}
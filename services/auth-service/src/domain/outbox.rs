@@ -0,0 +1,94 @@
+//! Transactional outbox
+//!
+//! Events are written to the outbox in the same database transaction as the
+//! change they announce, so an event is stored if and only if the change is.
+//! A relay later hands the stored events to the message broker and marks
+//! them as published. An event can be published more than once when the
+//! relay fails between publishing and marking, so delivery is at least once
+//! and consumers must deduplicate by event ID.
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use super::error::AuthError;
+
+/// Event stored in the outbox, with its payload already encoded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutboxMessage {
+    /// Position in the outbox; events are published in this order
+    pub sequence: i64,
+    /// Unique event ID consumers deduplicate by
+    pub id: Uuid,
+    /// User the event is about; the broker keeps events with the same
+    /// aggregate ID in order
+    pub aggregate_id: Uuid,
+    /// Event name, such as `user.registered`
+    pub event_type: String,
+    /// Version of the payload schema
+    pub schema_version: i32,
+    /// Event payload as JSON
+    pub payload: String,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// Exclusive right to relay the outbox, given up when dropped
+///
+/// Holds whatever keeps the claim alive, such as the database connection an
+/// advisory lock belongs to.
+pub struct RelayLease {
+    _claim: Box<dyn Send>,
+}
+
+impl RelayLease {
+    /// Wrap the state that keeps a claim alive
+    #[must_use]
+    pub fn new(claim: impl Send + 'static) -> Self {
+        Self {
+            _claim: Box::new(claim),
+        }
+    }
+}
+
+/// Repository interface for events waiting in the outbox
+pub trait OutboxRepository {
+    /// Claim the outbox for one relay pass
+    ///
+    /// Returns `None` while another relay, possibly in another replica,
+    /// holds the claim. Only the holder publishes, so events of the same
+    /// aggregate stay in order however many replicas run a relay.
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn try_lease_relay(&self) -> Result<Option<RelayLease>, AuthError>;
+
+    /// Find up to `limit` unpublished events, oldest first
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn find_unpublished(&self, limit: usize) -> Result<Vec<OutboxMessage>, AuthError>;
+
+    /// Mark events as published at `now`
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn mark_published(&self, sequences: &[i64], now: DateTime<Utc>) -> Result<(), AuthError>;
+
+    /// Delete events published before `cutoff`, returning how many were removed
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn purge_published(&self, cutoff: DateTime<Utc>) -> Result<usize, AuthError>;
+}
+
+/// Service interface for handing outbox events to a message broker
+pub trait OutboxPublisher {
+    /// Publish events in the given order
+    ///
+    /// Returns only once the broker has acknowledged every event. Events of
+    /// the same aggregate must reach consumers in the given order.
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` if any event was not acknowledged; some
+    /// of them may have been delivered all the same
+    fn publish(&self, messages: &[OutboxMessage]) -> Result<(), AuthError>;
+}
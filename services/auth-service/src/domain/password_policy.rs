@@ -0,0 +1,293 @@
+//! Password policy
+//!
+//! Every new password - at registration, password change and reset - is
+//! checked against the same configurable rules. All failed rules are
+//! reported at once, so clients can show them together. Optionally the
+//! password is also looked up in a list of passwords known from breaches.
+
+use std::fmt;
+use std::sync::Arc;
+
+use super::error::AuthError;
+
+/// Shortest email local part that is banned from passwords; shorter ones
+/// would rule out too many unrelated passwords
+const MIN_BANNED_LOCAL_PART: usize = 3;
+
+/// A password requirement that was not met
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasswordRule {
+    /// Fewer characters than the minimum
+    MinLength(usize),
+    /// More characters than the maximum
+    MaxLength(usize),
+    /// No lowercase letter
+    Lowercase,
+    /// No uppercase letter
+    Uppercase,
+    /// No digit
+    Digit,
+    /// No character other than letters and digits
+    Symbol,
+    /// Contains the local part of the user's email address
+    NoEmail,
+    /// Appears in the breached password list
+    NotBreached,
+}
+
+impl PasswordRule {
+    /// Stable identifier exposed to clients
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::MinLength(_) => "min_length",
+            Self::MaxLength(_) => "max_length",
+            Self::Lowercase => "lowercase",
+            Self::Uppercase => "uppercase",
+            Self::Digit => "digit",
+            Self::Symbol => "symbol",
+            Self::NoEmail => "no_email",
+            Self::NotBreached => "not_breached",
+        }
+    }
+}
+
+impl fmt::Display for PasswordRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MinLength(min) => write!(f, "must be at least {} characters long", min),
+            Self::MaxLength(max) => write!(f, "must be at most {} characters long", max),
+            Self::Lowercase => write!(f, "must contain a lowercase letter"),
+            Self::Uppercase => write!(f, "must contain an uppercase letter"),
+            Self::Digit => write!(f, "must contain a digit"),
+            Self::Symbol => write!(f, "must contain a symbol"),
+            Self::NoEmail => write!(f, "must not contain the email address"),
+            Self::NotBreached => write!(f, "must not be a password known from a data breach"),
+        }
+    }
+}
+
+/// Configurable password requirements
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PasswordRules {
+    /// Minimum length in characters
+    pub min_length: usize,
+    /// Maximum length in characters; also bounds the hashing cost
+    pub max_length: usize,
+    pub require_lowercase: bool,
+    pub require_uppercase: bool,
+    pub require_digit: bool,
+    pub require_symbol: bool,
+    /// Reject passwords containing the local part of the email address
+    pub forbid_email: bool,
+}
+
+impl Default for PasswordRules {
+    fn default() -> Self {
+        Self {
+            min_length: 8,
+            max_length: 128,
+            require_lowercase: false,
+            require_uppercase: false,
+            require_digit: false,
+            require_symbol: false,
+            forbid_email: true,
+        }
+    }
+}
+
+impl PasswordRules {
+    /// Rules that `password` breaks for the account with address `email`
+    #[must_use]
+    pub fn violations(&self, password: &str, email: &str) -> Vec<PasswordRule> {
+        let mut violations = Vec::new();
+
+        let length = password.chars().count();
+        if length < self.min_length {
+            violations.push(PasswordRule::MinLength(self.min_length));
+        }
+        if length > self.max_length {
+            violations.push(PasswordRule::MaxLength(self.max_length));
+        }
+        if self.require_lowercase && !password.chars().any(char::is_lowercase) {
+            violations.push(PasswordRule::Lowercase);
+        }
+        if self.require_uppercase && !password.chars().any(char::is_uppercase) {
+            violations.push(PasswordRule::Uppercase);
+        }
+        if self.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+            violations.push(PasswordRule::Digit);
+        }
+        if self.require_symbol && password.chars().all(char::is_alphanumeric) {
+            violations.push(PasswordRule::Symbol);
+        }
+        if self.forbid_email {
+            let local_part = email
+                .rsplit_once('@')
+                .map_or(email, |(local, _)| local)
+                .trim()
+                .to_lowercase();
+            if local_part.chars().count() >= MIN_BANNED_LOCAL_PART
+                && password.to_lowercase().contains(&local_part)
+            {
+                violations.push(PasswordRule::NoEmail);
+            }
+        }
+
+        violations
+    }
+}
+
+/// Lookup port for passwords known from data breaches
+pub trait BreachedPasswordList {
+    /// Whether `password` appears in the list
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` if the list cannot be read
+    fn contains(&self, password: &str) -> Result<bool, AuthError>;
+}
+
+/// Domain service enforcing the password rules
+#[derive(Clone, Default)]
+pub struct PasswordPolicy {
+    rules: PasswordRules,
+    breached_list: Option<Arc<dyn BreachedPasswordList + Send + Sync>>,
+}
+
+impl fmt::Debug for PasswordPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PasswordPolicy")
+            .field("rules", &self.rules)
+            .field("breached_list", &self.breached_list.is_some())
+            .finish()
+    }
+}
+
+impl PasswordPolicy {
+    /// Create a policy enforcing `rules`
+    #[must_use]
+    pub fn new(rules: PasswordRules) -> Self {
+        Self {
+            rules,
+            breached_list: None,
+        }
+    }
+
+    /// Additionally reject passwords found in `list`
+    #[must_use]
+    pub fn with_breached_list(mut self, list: Arc<dyn BreachedPasswordList + Send + Sync>) -> Self {
+        self.breached_list = Some(list);
+        self
+    }
+
+    /// Get the configured rules
+    #[must_use]
+    pub fn rules(&self) -> &PasswordRules {
+        &self.rules
+    }
+
+    /// Check a new password for the account with address `email`
+    ///
+    /// # Errors
+    /// - `AuthError::WeakPassword` listing every rule the password breaks
+    /// - `AuthError::Internal` if the breached password list cannot be read
+    pub fn validate(&self, password: &str, email: &str) -> Result<(), AuthError> {
+        let mut violations = self.rules.violations(password, email);
+
+        if let Some(list) = &self.breached_list {
+            if list.contains(password)? {
+                violations.push(PasswordRule::NotBreached);
+            }
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(AuthError::WeakPassword(violations))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // List containing exactly one password
+    struct MockBreachedPasswordList;
+
+    impl BreachedPasswordList for MockBreachedPasswordList {
+        fn contains(&self, password: &str) -> Result<bool, AuthError> {
+            Ok(password == "password123")
+        }
+    }
+
+    #[test]
+    fn test_default_rules() {
+        let policy = PasswordPolicy::default();
+
+        assert!(policy
+            .validate("correct horse battery", "user@example.com")
+            .is_ok());
+        assert_eq!(
+            policy.validate("short", "user@example.com"),
+            Err(AuthError::WeakPassword(vec![PasswordRule::MinLength(8)]))
+        );
+        assert_eq!(
+            policy.validate(&"x".repeat(129), "user@example.com"),
+            Err(AuthError::WeakPassword(vec![PasswordRule::MaxLength(128)]))
+        );
+    }
+
+    #[test]
+    fn test_length_counts_characters() {
+        let policy = PasswordPolicy::default();
+
+        // 8 characters, 16 bytes
+        assert!(policy.validate("ääääääää", "user@example.com").is_ok());
+    }
+
+    #[test]
+    fn test_reports_every_failed_rule() {
+        let policy = PasswordPolicy::new(PasswordRules {
+            require_lowercase: true,
+            require_uppercase: true,
+            require_digit: true,
+            require_symbol: true,
+            ..PasswordRules::default()
+        });
+
+        assert_eq!(
+            policy.validate("JOHN", "john@example.com"),
+            Err(AuthError::WeakPassword(vec![
+                PasswordRule::MinLength(8),
+                PasswordRule::Lowercase,
+                PasswordRule::Digit,
+                PasswordRule::Symbol,
+                PasswordRule::NoEmail,
+            ]))
+        );
+        assert!(policy.validate("Tr0ub4dor&3", "john@example.com").is_ok());
+    }
+
+    #[test]
+    fn test_short_local_part_is_not_banned() {
+        let policy = PasswordPolicy::default();
+
+        assert!(policy.validate("jojojojo", "jo@example.com").is_ok());
+        assert!(policy
+            .validate("hello-john.doe", "John.Doe@example.com")
+            .is_err());
+    }
+
+    #[test]
+    fn test_breached_password_rejected() {
+        let policy =
+            PasswordPolicy::default().with_breached_list(Arc::new(MockBreachedPasswordList));
+
+        assert_eq!(
+            policy.validate("password123", "user@example.com"),
+            Err(AuthError::WeakPassword(vec![PasswordRule::NotBreached]))
+        );
+        assert!(policy.validate("password124", "user@example.com").is_ok());
+    }
+}
@@ -0,0 +1,158 @@
+//! Password reset token domain entity
+//!
+//! A reset token is an opaque secret mailed to the user. Like refresh
+//! tokens, only a hash of the secret is persisted. Tokens expire quickly and
+//! can be used at most once.
+
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+use super::error::AuthError;
+
+/// Password reset token entity
+#[derive(Debug, Clone)]
+pub struct PasswordResetToken {
+    id: Uuid,
+    user_id: Uuid,
+    token_hash: String,
+    expires_at: DateTime<Utc>,
+    created_at: DateTime<Utc>,
+    used_at: Option<DateTime<Utc>>,
+}
+
+impl PasswordResetToken {
+    /// Issue a new reset token
+    ///
+    /// # Arguments
+    /// * `user_id` - User whose password may be reset
+    /// * `token_hash` - Hash of the opaque secret mailed to the user
+    /// * `ttl` - Lifetime of the token
+    #[must_use]
+    pub fn issue(user_id: Uuid, token_hash: String, ttl: Duration) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            user_id,
+            token_hash,
+            expires_at: now + ttl,
+            created_at: now,
+            used_at: None,
+        }
+    }
+
+    /// Reconstruct a reset token from persistence
+    #[must_use]
+    pub fn from_persistence(
+        id: Uuid,
+        user_id: Uuid,
+        token_hash: String,
+        expires_at: DateTime<Utc>,
+        created_at: DateTime<Utc>,
+        used_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        Self {
+            id,
+            user_id,
+            token_hash,
+            expires_at,
+            created_at,
+            used_at,
+        }
+    }
+
+    /// Get the token ID
+    #[must_use]
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// Get the owning user's ID
+    #[must_use]
+    pub fn user_id(&self) -> Uuid {
+        self.user_id
+    }
+
+    /// Get the stored hash of the secret
+    #[must_use]
+    pub fn token_hash(&self) -> &str {
+        &self.token_hash
+    }
+
+    /// Get the expiration timestamp
+    #[must_use]
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        self.expires_at
+    }
+
+    /// Get the creation timestamp
+    #[must_use]
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    /// Get the timestamp at which the token was used
+    #[must_use]
+    pub fn used_at(&self) -> Option<DateTime<Utc>> {
+        self.used_at
+    }
+
+    /// Check whether the token has already been used
+    #[must_use]
+    pub fn is_used(&self) -> bool {
+        self.used_at.is_some()
+    }
+
+    /// Check whether the token is past its expiry at `now`
+    #[must_use]
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        now >= self.expires_at
+    }
+}
+
+/// Repository interface for password reset token persistence
+pub trait PasswordResetTokenRepository {
+    /// Store a newly issued reset token
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn create(&self, token: &PasswordResetToken) -> Result<(), AuthError>;
+
+    /// Find a reset token by the hash of its secret
+    ///
+    /// # Errors
+    /// Returns `AuthError::InvalidToken` if no token has this hash
+    /// Returns `AuthError::Internal` on database errors
+    fn find_by_hash(&self, token_hash: &str) -> Result<PasswordResetToken, AuthError>;
+
+    /// Atomically mark a token as used, along with every other unused token
+    /// issued to the same user
+    ///
+    /// Returns `Ok(false)` if the token was already used (e.g. a concurrent
+    /// reset won the race).
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn consume(&self, token: &PasswordResetToken) -> Result<bool, AuthError>;
+
+    /// Delete tokens that have expired by `now`
+    ///
+    /// Returns the number of tokens removed.
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn purge_expired(&self, now: DateTime<Utc>) -> Result<usize, AuthError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_issue_is_usable() {
+        let token = PasswordResetToken::issue(Uuid::new_v4(), "h".to_string(), Duration::hours(1));
+
+        assert!(!token.is_used());
+        assert!(!token.is_expired(Utc::now()));
+        assert!(token.is_expired(Utc::now() + Duration::hours(1)));
+    }
+}
@@ -0,0 +1,145 @@
+//! Personal data held about a user
+//!
+//! Users may ask for a copy of everything the service stores about them and
+//! for their account to be erased. Erasure anonymises the user row rather
+//! than deleting it, so ids referenced by other services stay valid.
+
+use std::net::IpAddr;
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use super::api_key::ApiKey;
+use super::audit::AuditEvent;
+use super::error::AuthError;
+use super::federation::UserIdentity;
+use super::organization::{Membership, Organization};
+use super::user::User;
+
+/// A signed-in session, i.e. one refresh token family
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionRecord {
+    pub family_id: Uuid,
+    /// Login that started the session
+    pub started_at: DateTime<Utc>,
+    /// Most recent refresh
+    pub last_used_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    /// Logout or revocation, if the session was ended early
+    pub ended_at: Option<DateTime<Utc>>,
+    /// Device that logged in, unknown for sessions started before devices
+    /// were recorded
+    pub user_agent: Option<String>,
+    pub ip_address: Option<IpAddr>,
+}
+
+impl SessionRecord {
+    /// Check if the session can still be refreshed at `now`
+    #[must_use]
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.ended_at.is_none() && self.expires_at > now
+    }
+}
+
+/// Everything stored about a user, as handed out on request
+#[derive(Debug, Clone)]
+pub struct PersonalDataExport {
+    pub user: User,
+    /// When two-factor authentication was enabled, if it is
+    pub totp_enabled_at: Option<DateTime<Utc>>,
+    /// Sessions, newest first
+    pub sessions: Vec<SessionRecord>,
+    /// Unrevoked API keys, newest first
+    pub api_keys: Vec<ApiKey>,
+    /// Accounts at identity providers linked to the user, oldest first
+    pub identities: Vec<UserIdentity>,
+    /// Organizations the user belongs to, oldest membership first
+    pub organizations: Vec<(Organization, Membership)>,
+    /// Audit trail entries the user performed or was the target of, newest
+    /// first
+    pub audit_events: Vec<AuditEvent>,
+    pub exported_at: DateTime<Utc>,
+}
+
+impl PersonalDataExport {
+    /// Successful logins, newest first
+    ///
+    /// Every login starts a session, so the history is read off the sessions.
+    #[must_use]
+    pub fn login_history(&self) -> Vec<DateTime<Utc>> {
+        self.sessions.iter().map(|s| s.started_at).collect()
+    }
+}
+
+/// Repository interface for personal data spread over several tables
+pub trait PersonalDataRepository {
+    /// Find a user's sessions, newest first
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn find_sessions(&self, user_id: Uuid) -> Result<Vec<SessionRecord>, AuthError>;
+
+    /// Find the upstream accounts linked to a user, oldest first
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn find_identities(&self, user_id: Uuid) -> Result<Vec<UserIdentity>, AuthError>;
+
+    /// Find the organizations a user belongs to, oldest membership first
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn find_organizations(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<(Organization, Membership)>, AuthError>;
+
+    /// Find the audit events a user performed or was the target of, newest
+    /// first
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn find_audit_events(&self, user_id: Uuid) -> Result<Vec<AuditEvent>, AuthError>;
+
+    /// Store an erased user and delete their credentials in one transaction
+    ///
+    /// Removes roles, refresh tokens, one-time tokens, two-factor secrets,
+    /// API keys, linked upstream accounts and organization memberships, as
+    /// well as the invitations and login throttle kept for `previous_email`.
+    /// Audit entries about the user, and failed logins that recorded
+    /// `previous_email`, keep their ids but lose their client and detail.
+    /// The user's earlier events are dropped from the outbox, since their
+    /// payloads may hold personal data, and the user's pending events, such
+    /// as `user.erased`, are stored in their place.
+    ///
+    /// # Errors
+    /// - `AuthError::UserNotFound` if the user does not exist
+    /// - `AuthError::Internal` on database errors
+    fn erase(&self, user: &User, previous_email: &str) -> Result<(), AuthError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_session_is_active() {
+        let now = Utc::now();
+        let mut session = SessionRecord {
+            family_id: Uuid::new_v4(),
+            started_at: now - Duration::days(1),
+            last_used_at: now,
+            expires_at: now + Duration::days(6),
+            ended_at: None,
+            user_agent: None,
+            ip_address: None,
+        };
+        assert!(session.is_active(now));
+        assert!(!session.is_active(now + Duration::days(7)));
+
+        session.ended_at = Some(now);
+        assert!(!session.is_active(now));
+    }
+}
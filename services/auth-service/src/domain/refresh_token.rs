@@ -0,0 +1,235 @@
+//! Refresh token domain entity
+//!
+//! Refresh tokens are opaque secrets handed out next to the short-lived JWT.
+//! Only a hash of the secret is persisted. Every token belongs to a family
+//! that starts at login; each refresh rotates the token inside its family so
+//! that replaying an already-rotated token can be detected.
+
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+use super::auth::BoxFuture;
+use super::error::AuthError;
+
+/// Refresh token entity
+#[derive(Debug, Clone)]
+pub struct RefreshToken {
+    id: Uuid,
+    user_id: Uuid,
+    family_id: Uuid,
+    token_hash: String,
+    expires_at: DateTime<Utc>,
+    created_at: DateTime<Utc>,
+    rotated_at: Option<DateTime<Utc>>,
+    revoked_at: Option<DateTime<Utc>>,
+}
+
+impl RefreshToken {
+    /// Issue a new refresh token
+    ///
+    /// # Arguments
+    /// * `user_id` - Owner of the token
+    /// * `family_id` - Family the token belongs to (a fresh id at login)
+    /// * `token_hash` - Hash of the opaque secret given to the client
+    /// * `ttl` - Lifetime of the token
+    #[must_use]
+    pub fn issue(user_id: Uuid, family_id: Uuid, token_hash: String, ttl: Duration) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            user_id,
+            family_id,
+            token_hash,
+            expires_at: now + ttl,
+            created_at: now,
+            rotated_at: None,
+            revoked_at: None,
+        }
+    }
+
+    /// Reconstruct a refresh token from persistence
+    #[must_use]
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_persistence(
+        id: Uuid,
+        user_id: Uuid,
+        family_id: Uuid,
+        token_hash: String,
+        expires_at: DateTime<Utc>,
+        created_at: DateTime<Utc>,
+        rotated_at: Option<DateTime<Utc>>,
+        revoked_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        Self {
+            id,
+            user_id,
+            family_id,
+            token_hash,
+            expires_at,
+            created_at,
+            rotated_at,
+            revoked_at,
+        }
+    }
+
+    /// Get the token ID
+    #[must_use]
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// Get the owning user's ID
+    #[must_use]
+    pub fn user_id(&self) -> Uuid {
+        self.user_id
+    }
+
+    /// Get the token family ID
+    #[must_use]
+    pub fn family_id(&self) -> Uuid {
+        self.family_id
+    }
+
+    /// Get the stored hash of the secret
+    #[must_use]
+    pub fn token_hash(&self) -> &str {
+        &self.token_hash
+    }
+
+    /// Get the expiration timestamp
+    #[must_use]
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        self.expires_at
+    }
+
+    /// Get the creation timestamp
+    #[must_use]
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    /// Get the timestamp at which the token was exchanged for a successor
+    #[must_use]
+    pub fn rotated_at(&self) -> Option<DateTime<Utc>> {
+        self.rotated_at
+    }
+
+    /// Get the revocation timestamp
+    #[must_use]
+    pub fn revoked_at(&self) -> Option<DateTime<Utc>> {
+        self.revoked_at
+    }
+
+    /// Check whether the token has already been exchanged
+    #[must_use]
+    pub fn is_rotated(&self) -> bool {
+        self.rotated_at.is_some()
+    }
+
+    /// Check whether the token (or its family) has been revoked
+    #[must_use]
+    pub fn is_revoked(&self) -> bool {
+        self.revoked_at.is_some()
+    }
+
+    /// Check whether the token is past its expiry at `now`
+    #[must_use]
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        now >= self.expires_at
+    }
+}
+
+/// Repository interface for refresh token persistence
+pub trait RefreshTokenRepository {
+    /// Store a newly issued refresh token
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn create(&self, token: &RefreshToken) -> Result<(), AuthError>;
+
+    /// Find a refresh token by the hash of its secret
+    ///
+    /// # Errors
+    /// Returns `AuthError::InvalidToken` if no token has this hash
+    /// Returns `AuthError::Internal` on database errors
+    fn find_by_hash(&self, token_hash: &str) -> Result<RefreshToken, AuthError>;
+
+    /// Atomically mark `current_id` as rotated and store its successor
+    ///
+    /// Returns `Ok(false)` without storing `next` if the current token was
+    /// already rotated or revoked (e.g. a concurrent refresh won the race).
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn rotate(&self, current_id: Uuid, next: &RefreshToken) -> Result<bool, AuthError>;
+
+    /// Revoke every token in a family, ending the session it makes up
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn revoke_family(&self, family_id: Uuid) -> Result<(), AuthError>;
+
+    /// Revoke every token issued to a user, ending all of their sessions
+    ///
+    /// Sessions are marked as ended along with the tokens, so access tokens
+    /// bound to them are rejected from then on.
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn revoke_all_for_user(&self, user_id: Uuid) -> Result<(), AuthError>;
+}
+
+/// Async variant of [`RefreshTokenRepository`]
+///
+/// Errors are those of the matching [`RefreshTokenRepository`] method.
+pub trait AsyncRefreshTokenRepository {
+    /// Store a newly issued refresh token
+    fn create<'a>(&'a self, token: &'a RefreshToken) -> BoxFuture<'a, Result<(), AuthError>>;
+
+    /// Find a refresh token by the hash of its secret
+    fn find_by_hash<'a>(&'a self, token_hash: &'a str)
+        -> BoxFuture<'a, Result<RefreshToken, AuthError>>;
+
+    /// Atomically mark `current_id` as rotated and store its successor
+    fn rotate<'a>(
+        &'a self,
+        current_id: Uuid,
+        next: &'a RefreshToken,
+    ) -> BoxFuture<'a, Result<bool, AuthError>>;
+
+    /// Revoke every token in a family, ending the session it makes up
+    fn revoke_family(&self, family_id: Uuid) -> BoxFuture<'_, Result<(), AuthError>>;
+
+    /// Revoke every token issued to a user, ending all of their sessions
+    fn revoke_all_for_user(&self, user_id: Uuid) -> BoxFuture<'_, Result<(), AuthError>>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_issue_is_usable() {
+        let token = RefreshToken::issue(
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            "h".to_string(),
+            Duration::days(1),
+        );
+
+        assert!(!token.is_rotated());
+        assert!(!token.is_revoked());
+        assert!(!token.is_expired(Utc::now()));
+    }
+
+    #[test]
+    fn test_is_expired() {
+        let token = RefreshToken::issue(
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            "h".to_string(),
+            Duration::seconds(-1),
+        );
+        assert!(token.is_expired(Utc::now()));
+    }
+}
@@ -0,0 +1,271 @@
+//! Role-based access control
+//!
+//! Roles are assigned to users and embedded in issued tokens. Each role
+//! grants a fixed set of permissions, so any service holding a validated
+//! token can authorize a request without calling back into auth-service.
+
+use std::fmt;
+use std::str::FromStr;
+
+use super::auth::{Principal, TokenData};
+use super::error::AuthError;
+
+/// Role a user can hold in the ticketing platform
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Role {
+    /// Buys tickets (granted to every new user)
+    Customer,
+    /// Runs events and manages their ticket inventory
+    Organizer,
+    /// Back-office staff helping customers
+    SupportAgent,
+    /// Full administrative access
+    Admin,
+}
+
+/// Fine-grained permission derived from roles
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Permission {
+    /// Purchase tickets and view own orders
+    PurchaseTickets,
+    /// Create and manage own events
+    ManageEvents,
+    /// Read any customer's orders
+    ReadAllOrders,
+    /// Read user accounts
+    ReadUsers,
+    /// Activate, deactivate and edit user accounts
+    ManageUsers,
+    /// Assign and revoke roles
+    ManageRoles,
+    /// Register and revoke OAuth clients for backend services
+    ManageClients,
+    /// Read the security audit log
+    ReadAuditLog,
+    /// Act as a customer or organizer through a short-lived token
+    ImpersonateUsers,
+}
+
+impl Role {
+    /// All roles, in ascending order of privilege
+    pub const ALL: [Role; 4] = [
+        Role::Customer,
+        Role::Organizer,
+        Role::SupportAgent,
+        Role::Admin,
+    ];
+
+    /// Stable identifier used in storage and tokens
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Customer => "customer",
+            Self::Organizer => "organizer",
+            Self::SupportAgent => "support_agent",
+            Self::Admin => "admin",
+        }
+    }
+
+    /// Permissions granted by this role
+    #[must_use]
+    pub fn permissions(&self) -> &'static [Permission] {
+        match self {
+            Self::Customer => &[Permission::PurchaseTickets],
+            Self::Organizer => &[Permission::PurchaseTickets, Permission::ManageEvents],
+            Self::SupportAgent => &[Permission::ReadAllOrders, Permission::ReadUsers],
+            Self::Admin => &[
+                Permission::PurchaseTickets,
+                Permission::ManageEvents,
+                Permission::ReadAllOrders,
+                Permission::ReadUsers,
+                Permission::ManageUsers,
+                Permission::ManageRoles,
+                Permission::ManageClients,
+                Permission::ReadAuditLog,
+                Permission::ImpersonateUsers,
+            ],
+        }
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Role {
+    type Err = AuthError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|role| role.as_str() == value.trim().to_lowercase())
+            .ok_or(AuthError::InvalidRole)
+    }
+}
+
+impl Permission {
+    /// All permissions
+    pub const ALL: [Permission; 9] = [
+        Permission::PurchaseTickets,
+        Permission::ManageEvents,
+        Permission::ReadAllOrders,
+        Permission::ReadUsers,
+        Permission::ManageUsers,
+        Permission::ManageRoles,
+        Permission::ManageClients,
+        Permission::ReadAuditLog,
+        Permission::ImpersonateUsers,
+    ];
+
+    /// Stable identifier exposed to other services
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::PurchaseTickets => "tickets:purchase",
+            Self::ManageEvents => "events:manage",
+            Self::ReadAllOrders => "orders:read_all",
+            Self::ReadUsers => "users:read",
+            Self::ManageUsers => "users:manage",
+            Self::ManageRoles => "roles:manage",
+            Self::ManageClients => "clients:manage",
+            Self::ReadAuditLog => "audit:read",
+            Self::ImpersonateUsers => "users:impersonate",
+        }
+    }
+}
+
+impl fmt::Display for Permission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Permission {
+    type Err = AuthError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|permission| permission.as_str() == value.trim().to_lowercase())
+            .ok_or(AuthError::InvalidScope)
+    }
+}
+
+/// Union of the permissions granted by `roles`, sorted and deduplicated
+#[must_use]
+pub fn permissions_for(roles: &[Role]) -> Vec<Permission> {
+    let mut permissions: Vec<Permission> = roles
+        .iter()
+        .flat_map(|role| role.permissions().iter().copied())
+        .collect();
+    permissions.sort();
+    permissions.dedup();
+    permissions
+}
+
+/// Ensure a token grants `permission`
+///
+/// API keys must also have been created with `permission` in their scopes.
+/// Services hold no roles; their scopes are all they are granted.
+///
+/// # Errors
+/// Returns `AuthError::Forbidden` if none of the token's roles grant it, or
+/// the token is an API key or service not scoped to it
+pub fn authorize(token: &TokenData, permission: Permission) -> Result<(), AuthError> {
+    let in_scope = token
+        .scopes
+        .as_ref()
+        .is_none_or(|scopes| scopes.contains(&permission));
+    let granted = match token.principal {
+        Principal::User => token
+            .roles
+            .iter()
+            .any(|role| role.permissions().contains(&permission)),
+        Principal::Service => token.scopes.is_some(),
+    };
+    if in_scope && granted {
+        Ok(())
+    } else {
+        Err(AuthError::Forbidden)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::commands::test_support::token_data;
+
+    #[test]
+    fn test_role_round_trip() {
+        for role in Role::ALL {
+            assert_eq!(role.as_str().parse::<Role>().unwrap(), role);
+        }
+        assert_eq!(
+            " Support_Agent ".parse::<Role>().unwrap(),
+            Role::SupportAgent
+        );
+        assert!(matches!(
+            "root".parse::<Role>(),
+            Err(AuthError::InvalidRole)
+        ));
+    }
+
+    #[test]
+    fn test_permissions_for_roles() {
+        assert_eq!(
+            permissions_for(&[Role::Customer]),
+            vec![Permission::PurchaseTickets]
+        );
+        assert_eq!(
+            permissions_for(&[Role::Customer, Role::Organizer]),
+            vec![Permission::PurchaseTickets, Permission::ManageEvents]
+        );
+        assert!(permissions_for(&[Role::Admin]).contains(&Permission::ManageRoles));
+        assert!(!permissions_for(&[Role::SupportAgent]).contains(&Permission::ManageRoles));
+    }
+
+    #[test]
+    fn test_permission_round_trip() {
+        for permission in Permission::ALL {
+            assert_eq!(permission.as_str().parse::<Permission>(), Ok(permission));
+        }
+        assert_eq!(
+            "tickets:refund".parse::<Permission>(),
+            Err(AuthError::InvalidScope)
+        );
+    }
+
+    #[test]
+    fn test_authorize_respects_api_key_scopes() {
+        let mut token = TokenData {
+            roles: vec![Role::Admin],
+            ..token_data(uuid::Uuid::new_v4())
+        };
+        assert_eq!(authorize(&token, Permission::ManageUsers), Ok(()));
+
+        token.scopes = Some(vec![Permission::ReadUsers]);
+        assert_eq!(authorize(&token, Permission::ReadUsers), Ok(()));
+        assert_eq!(
+            authorize(&token, Permission::ManageUsers),
+            Err(AuthError::Forbidden)
+        );
+    }
+
+    #[test]
+    fn test_authorize_service_by_scope() {
+        let token = TokenData {
+            email: String::new(),
+            scopes: Some(vec![Permission::ReadUsers]),
+            principal: Principal::Service,
+            ..token_data(uuid::Uuid::new_v4())
+        };
+
+        assert_eq!(authorize(&token, Permission::ReadUsers), Ok(()));
+        assert_eq!(
+            authorize(&token, Permission::ManageUsers),
+            Err(AuthError::Forbidden)
+        );
+    }
+}
@@ -0,0 +1,296 @@
+//! Session domain entity
+//!
+//! Every login starts a session: the refresh token family issued at login,
+//! plus the device it was started from. Access tokens carry the session id
+//! as their `sid` claim, so ending a session rejects its access tokens at
+//! once instead of when they expire. A session ends when its refresh token
+//! family is revoked - by logout, by the user, or on refresh token reuse.
+
+use std::net::IpAddr;
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use super::auth::BoxFuture;
+use super::error::AuthError;
+use super::events::AuthEvent;
+
+/// Longest stored user agent, in characters
+pub const MAX_USER_AGENT_LEN: usize = 512;
+
+/// Device a login comes from, as far as the request tells
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SessionClient {
+    /// `User-Agent` of the client
+    pub user_agent: Option<String>,
+    pub ip_address: Option<IpAddr>,
+}
+
+/// Session entity
+#[derive(Debug, Clone)]
+pub struct Session {
+    id: Uuid,
+    user_id: Uuid,
+    user_agent: Option<String>,
+    ip_address: Option<IpAddr>,
+    created_at: DateTime<Utc>,
+    last_seen_at: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+    revoked_at: Option<DateTime<Utc>>,
+    organization_id: Option<Uuid>,
+}
+
+impl Session {
+    /// Start a new session
+    ///
+    /// # Arguments
+    /// * `id` - ID of the refresh token family the session is made of
+    /// * `user_id` - User who logged in
+    /// * `client` - Device the login came from; long user agents are cut off
+    /// * `expires_at` - Expiry of the family's first refresh token
+    #[must_use]
+    pub fn start(
+        id: Uuid,
+        user_id: Uuid,
+        client: &SessionClient,
+        expires_at: DateTime<Utc>,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            id,
+            user_id,
+            user_agent: client
+                .user_agent
+                .as_deref()
+                .map(|agent| agent.chars().take(MAX_USER_AGENT_LEN).collect()),
+            ip_address: client.ip_address,
+            created_at: now,
+            last_seen_at: now,
+            expires_at,
+            revoked_at: None,
+            organization_id: None,
+        }
+    }
+
+    /// Reconstruct a session from persistence
+    #[must_use]
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_persistence(
+        id: Uuid,
+        user_id: Uuid,
+        user_agent: Option<String>,
+        ip_address: Option<IpAddr>,
+        created_at: DateTime<Utc>,
+        last_seen_at: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+        revoked_at: Option<DateTime<Utc>>,
+        organization_id: Option<Uuid>,
+    ) -> Self {
+        Self {
+            id,
+            user_id,
+            user_agent,
+            ip_address,
+            created_at,
+            last_seen_at,
+            expires_at,
+            revoked_at,
+            organization_id,
+        }
+    }
+
+    /// Get the session ID, which is also its refresh token family ID
+    #[must_use]
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// Get the owning user's ID
+    #[must_use]
+    pub fn user_id(&self) -> Uuid {
+        self.user_id
+    }
+
+    /// Get the user agent of the device that logged in
+    #[must_use]
+    pub fn user_agent(&self) -> Option<&str> {
+        self.user_agent.as_deref()
+    }
+
+    /// Get the address the login came from
+    #[must_use]
+    pub fn ip_address(&self) -> Option<IpAddr> {
+        self.ip_address
+    }
+
+    /// Get the login timestamp
+    #[must_use]
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    /// Get the timestamp of the login or most recent refresh
+    #[must_use]
+    pub fn last_seen_at(&self) -> DateTime<Utc> {
+        self.last_seen_at
+    }
+
+    /// Get the timestamp after which the session can no longer be refreshed
+    #[must_use]
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        self.expires_at
+    }
+
+    /// Get the timestamp at which the session was ended
+    #[must_use]
+    pub fn revoked_at(&self) -> Option<DateTime<Utc>> {
+        self.revoked_at
+    }
+
+    /// Get the organization the session acts for, which refreshed access
+    /// tokens carry again
+    #[must_use]
+    pub fn organization_id(&self) -> Option<Uuid> {
+        self.organization_id
+    }
+
+    /// Check if the session can still be refreshed at `now`
+    #[must_use]
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none() && self.expires_at > now
+    }
+
+    /// Event announcing the login that started the session
+    ///
+    /// A login changes no user data, so the repository stores this event
+    /// with the new session instead.
+    #[must_use]
+    pub fn login_event(&self) -> AuthEvent {
+        AuthEvent::UserLoggedIn {
+            user_id: self.user_id,
+            session_id: self.id,
+            occurred_at: self.created_at,
+        }
+    }
+}
+
+/// Repository interface for session persistence
+///
+/// Sessions are ended through `RefreshTokenRepository::revoke_family` and
+/// `revoke_all_for_user`, which revoke the session along with its tokens.
+pub trait SessionRepository {
+    /// Store a newly started session
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn create(&self, session: &Session) -> Result<(), AuthError>;
+
+    /// Find a session by its ID
+    ///
+    /// # Errors
+    /// Returns `AuthError::SessionNotFound` if no session has this ID
+    /// Returns `AuthError::Internal` on database errors
+    fn find_by_id(&self, id: Uuid) -> Result<Session, AuthError>;
+
+    /// Find a user's active sessions, most recently seen first
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn find_active_for_user(
+        &self,
+        user_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<Session>, AuthError>;
+
+    /// Record a refresh of the session, extending it to `expires_at`
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn touch(
+        &self,
+        id: Uuid,
+        now: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), AuthError>;
+
+    /// Set the organization the session acts for; `None` returns it to the
+    /// personal context
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn set_organization(&self, id: Uuid, organization_id: Option<Uuid>) -> Result<(), AuthError>;
+
+    /// Check whether a session has been ended
+    ///
+    /// Unknown sessions count as ended.
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn is_revoked(&self, id: Uuid) -> Result<bool, AuthError>;
+}
+
+/// Async variant of [`SessionRepository`]
+///
+/// Lets login, refresh and token validation await sessions instead of
+/// holding a blocking thread. Errors are those of the matching
+/// [`SessionRepository`] method.
+pub trait AsyncSessionRepository {
+    /// Store a newly started session
+    fn create<'a>(&'a self, session: &'a Session) -> BoxFuture<'a, Result<(), AuthError>>;
+
+    /// Find a session by its ID
+    fn find_by_id(&self, id: Uuid) -> BoxFuture<'_, Result<Session, AuthError>>;
+
+    /// Find a user's active sessions, most recently seen first
+    fn find_active_for_user(
+        &self,
+        user_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> BoxFuture<'_, Result<Vec<Session>, AuthError>>;
+
+    /// Record a refresh of the session, extending it to `expires_at`
+    fn touch(
+        &self,
+        id: Uuid,
+        now: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> BoxFuture<'_, Result<(), AuthError>>;
+
+    /// Set the organization the session acts for
+    fn set_organization(
+        &self,
+        id: Uuid,
+        organization_id: Option<Uuid>,
+    ) -> BoxFuture<'_, Result<(), AuthError>>;
+
+    /// Check whether a session has been ended; unknown sessions count as ended
+    fn is_revoked(&self, id: Uuid) -> BoxFuture<'_, Result<bool, AuthError>>;
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_start_is_active() {
+        let now = Utc::now();
+        let client = SessionClient {
+            user_agent: Some("x".repeat(MAX_USER_AGENT_LEN + 10)),
+            ip_address: Some("203.0.113.7".parse().unwrap()),
+        };
+
+        let session = Session::start(
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            &client,
+            now + Duration::days(30),
+        );
+
+        assert!(session.is_active(now));
+        assert!(!session.is_active(now + Duration::days(30)));
+        assert_eq!(session.user_agent().unwrap().len(), MAX_USER_AGENT_LEN);
+        assert_eq!(session.ip_address(), client.ip_address);
+    }
+}
@@ -0,0 +1,250 @@
+//! Asymmetric JWT signing keys
+//!
+//! Keys form a ring: exactly one key signs new tokens, while retired keys
+//! stay available for verification until every token they signed has
+//! expired. Public keys are published so other services can verify tokens
+//! locally.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+
+use super::error::AuthError;
+
+/// Asymmetric algorithm a signing key is used with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SigningAlgorithm {
+    /// RSASSA-PKCS1-v1_5 with SHA-256
+    Rs256,
+    /// Ed25519
+    EdDsa,
+}
+
+impl SigningAlgorithm {
+    /// JOSE algorithm name, as used in `alg` headers
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Rs256 => "RS256",
+            Self::EdDsa => "EdDSA",
+        }
+    }
+}
+
+impl fmt::Display for SigningAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SigningAlgorithm {
+    type Err = AuthError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_uppercase().as_str() {
+            "RS256" => Ok(Self::Rs256),
+            "EDDSA" => Ok(Self::EdDsa),
+            _ => Err(AuthError::Internal(format!(
+                "Unsupported signing algorithm: {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// Signing key pair with its lifecycle timestamps
+#[derive(Clone)]
+pub struct SigningKey {
+    kid: String,
+    algorithm: SigningAlgorithm,
+    private_key_pem: String,
+    public_key_pem: String,
+    created_at: DateTime<Utc>,
+    retired_at: Option<DateTime<Utc>>,
+    verify_until: Option<DateTime<Utc>>,
+}
+
+impl SigningKey {
+    /// Create a new, active signing key
+    ///
+    /// # Arguments
+    /// * `kid` - Key identifier placed in token headers
+    /// * `private_key_pem` - PKCS#8 PEM encoded private key
+    /// * `public_key_pem` - SPKI PEM encoded public key
+    #[must_use]
+    pub fn new(
+        kid: String,
+        algorithm: SigningAlgorithm,
+        private_key_pem: String,
+        public_key_pem: String,
+    ) -> Self {
+        Self {
+            kid,
+            algorithm,
+            private_key_pem,
+            public_key_pem,
+            created_at: Utc::now(),
+            retired_at: None,
+            verify_until: None,
+        }
+    }
+
+    /// Reconstruct a signing key from persistence
+    #[must_use]
+    pub fn from_persistence(
+        kid: String,
+        algorithm: SigningAlgorithm,
+        private_key_pem: String,
+        public_key_pem: String,
+        created_at: DateTime<Utc>,
+        retired_at: Option<DateTime<Utc>>,
+        verify_until: Option<DateTime<Utc>>,
+    ) -> Self {
+        Self {
+            kid,
+            algorithm,
+            private_key_pem,
+            public_key_pem,
+            created_at,
+            retired_at,
+            verify_until,
+        }
+    }
+
+    /// Get the key identifier
+    #[must_use]
+    pub fn kid(&self) -> &str {
+        &self.kid
+    }
+
+    /// Get the signing algorithm
+    #[must_use]
+    pub fn algorithm(&self) -> SigningAlgorithm {
+        self.algorithm
+    }
+
+    /// Get the PKCS#8 PEM encoded private key
+    #[must_use]
+    pub fn private_key_pem(&self) -> &str {
+        &self.private_key_pem
+    }
+
+    /// Get the SPKI PEM encoded public key
+    #[must_use]
+    pub fn public_key_pem(&self) -> &str {
+        &self.public_key_pem
+    }
+
+    /// Get the creation timestamp
+    #[must_use]
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    /// Get the time the key stopped signing new tokens
+    #[must_use]
+    pub fn retired_at(&self) -> Option<DateTime<Utc>> {
+        self.retired_at
+    }
+
+    /// Get the time after which the key no longer verifies tokens
+    #[must_use]
+    pub fn verify_until(&self) -> Option<DateTime<Utc>> {
+        self.verify_until
+    }
+
+    /// Check if the key signs new tokens
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        self.retired_at.is_none()
+    }
+
+    /// Check if the key still verifies tokens at `now`
+    #[must_use]
+    pub fn is_verifiable(&self, now: DateTime<Utc>) -> bool {
+        self.verify_until.is_none_or(|until| until > now)
+    }
+}
+
+// Keep private key material out of logs
+impl fmt::Debug for SigningKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SigningKey")
+            .field("kid", &self.kid)
+            .field("algorithm", &self.algorithm)
+            .field("created_at", &self.created_at)
+            .field("retired_at", &self.retired_at)
+            .field("verify_until", &self.verify_until)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Repository trait for signing key persistence
+pub trait SigningKeyRepository {
+    /// List keys that still verify tokens at `now`, newest first
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn find_verifiable(&self, now: DateTime<Utc>) -> Result<Vec<SigningKey>, AuthError>;
+
+    /// Make `next` the active key, retiring every currently active key
+    ///
+    /// Retired keys keep verifying tokens until `verify_until`.
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn rotate(&self, next: &SigningKey, verify_until: DateTime<Utc>) -> Result<(), AuthError>;
+
+    /// Delete keys that no longer verify tokens, returning how many were removed
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn purge_expired(&self, now: DateTime<Utc>) -> Result<usize, AuthError>;
+}
+
+/// Generator trait for fresh signing key pairs
+pub trait SigningKeyGenerator {
+    /// Generate a new active key pair for `algorithm`
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` if key generation fails
+    fn generate(&self, algorithm: SigningAlgorithm) -> Result<SigningKey, AuthError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_algorithm_round_trip() {
+        for algorithm in [SigningAlgorithm::Rs256, SigningAlgorithm::EdDsa] {
+            assert_eq!(
+                algorithm.as_str().parse::<SigningAlgorithm>().unwrap(),
+                algorithm
+            );
+        }
+        assert!("HS256".parse::<SigningAlgorithm>().is_err());
+    }
+
+    #[test]
+    fn test_retired_key_verifies_until_cutoff() {
+        let now = Utc::now();
+        let key = SigningKey::from_persistence(
+            "kid".to_string(),
+            SigningAlgorithm::EdDsa,
+            "private".to_string(),
+            "public".to_string(),
+            now - Duration::days(1),
+            Some(now - Duration::minutes(5)),
+            Some(now + Duration::minutes(55)),
+        );
+
+        assert!(!key.is_active());
+        assert!(key.is_verifiable(now));
+        assert!(!key.is_verifiable(now + Duration::hours(1)));
+        assert!(!format!("{:?}", key).contains("private"));
+    }
+}
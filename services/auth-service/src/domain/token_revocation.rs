@@ -0,0 +1,56 @@
+//! Token revocation list
+//!
+//! JWTs are stateless, so revoking one before it expires requires a shared
+//! list of revoked token ids (`jti`). Entries only need to live until the
+//! token would have expired anyway.
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use super::auth::BoxFuture;
+use super::error::AuthError;
+
+/// Store of revoked token ids, shared by every service replica
+pub trait TokenRevocationStore {
+    /// Record a token as revoked until its natural expiry
+    ///
+    /// Revoking an already revoked token is a no-op.
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on storage errors
+    fn revoke(&self, jti: &str, user_id: Uuid, expires_at: DateTime<Utc>) -> Result<(), AuthError>;
+
+    /// Check whether a token id has been revoked
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on storage errors
+    fn is_revoked(&self, jti: &str) -> Result<bool, AuthError>;
+
+    /// Drop entries for tokens that have expired by `now`
+    ///
+    /// Returns the number of entries removed.
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on storage errors
+    fn purge_expired(&self, now: DateTime<Utc>) -> Result<usize, AuthError>;
+}
+
+/// Async variant of [`TokenRevocationStore`]
+///
+/// Consulted on every token validation, which then needs no blocking
+/// thread. Errors are those of the matching [`TokenRevocationStore`] method.
+pub trait AsyncTokenRevocationStore {
+    /// Record a token as revoked until its natural expiry
+    fn revoke<'a>(
+        &'a self,
+        jti: &'a str,
+        user_id: Uuid,
+        expires_at: DateTime<Utc>,
+    ) -> BoxFuture<'a, Result<(), AuthError>>;
+
+    /// Check whether a token id has been revoked
+    fn is_revoked<'a>(&'a self, jti: &'a str) -> BoxFuture<'a, Result<bool, AuthError>>;
+
+    /// Drop entries for tokens that have expired by `now`
+    fn purge_expired(&self, now: DateTime<Utc>) -> BoxFuture<'_, Result<usize, AuthError>>;
+}
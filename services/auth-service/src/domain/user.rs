@@ -7,6 +7,8 @@ use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
 use super::error::AuthError;
+use super::events::AuthEvent;
+use super::role::Role;
 
 /// Unique identifier for a user
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -78,6 +80,36 @@ impl Email {
     }
 }
 
+/// Domain of the placeholder addresses given to erased accounts
+///
+/// `.invalid` is reserved and never resolves, so mail can never reach it.
+pub const ERASED_EMAIL_DOMAIN: &str = "erased.invalid";
+
+/// Password hash stored for erased accounts; no password verifies against it
+const ERASED_PASSWORD_HASH: &str = "!erased";
+
+/// Longest accepted display name, in characters
+pub const MAX_DISPLAY_NAME_LEN: usize = 100;
+
+/// Validate and normalize a display name
+///
+/// Surrounding whitespace is trimmed; an empty name means no display name.
+///
+/// # Errors
+/// Returns `AuthError::InvalidDisplayName` if the name is too long or
+/// contains control characters
+pub fn normalize_display_name(value: Option<&str>) -> Result<Option<String>, AuthError> {
+    let Some(trimmed) = value.map(str::trim).filter(|v| !v.is_empty()) else {
+        return Ok(None);
+    };
+
+    if trimmed.chars().count() > MAX_DISPLAY_NAME_LEN || trimmed.chars().any(char::is_control) {
+        return Err(AuthError::InvalidDisplayName);
+    }
+
+    Ok(Some(trimmed.to_string()))
+}
+
 /// Hashed password value object
 ///
 /// This type ensures passwords are never stored in plain text in the domain.
@@ -101,48 +133,100 @@ impl HashedPassword {
 }
 
 /// User domain entity
+///
+/// Users who only sign in through an external identity provider have no
+/// password.
+///
+/// Changes that other services hear about raise events, which the
+/// repository stores in the outbox when it writes the user.
 #[derive(Debug, Clone)]
 pub struct User {
     id: UserId,
     email: Email,
-    hashed_password: HashedPassword,
+    hashed_password: Option<HashedPassword>,
     display_name: Option<String>,
     is_active: bool,
+    roles: Vec<Role>,
+    email_verified_at: Option<DateTime<Utc>>,
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
+    events: Vec<AuthEvent>,
 }
 
 impl User {
-    /// Create a new user
+    /// Create a new user with the default customer role and an unverified email
     ///
     /// # Arguments
     /// * `email` - Valid email address
     /// * `hashed_password` - Already hashed password
     /// * `display_name` - Optional display name
     #[must_use]
-    pub fn new(email: Email, hashed_password: HashedPassword, display_name: Option<String>) -> Self {
+    pub fn new(
+        email: Email,
+        hashed_password: HashedPassword,
+        display_name: Option<String>,
+    ) -> Self {
         let now = Utc::now();
         Self {
             id: UserId::new(),
             email,
-            hashed_password,
+            hashed_password: Some(hashed_password),
             display_name,
             is_active: true,
+            roles: vec![Role::Customer],
+            email_verified_at: None,
             created_at: now,
             updated_at: now,
+            events: Vec::new(),
         }
+        .registered()
+    }
+
+    /// Create a user signing in through an external identity provider
+    ///
+    /// The user has no password and the default customer role; the email
+    /// address counts as verified because the provider vouched for it.
+    #[must_use]
+    pub fn new_federated(email: Email, display_name: Option<String>) -> Self {
+        let now = Utc::now();
+        Self {
+            id: UserId::new(),
+            email,
+            hashed_password: None,
+            display_name,
+            is_active: true,
+            roles: vec![Role::Customer],
+            email_verified_at: Some(now),
+            created_at: now,
+            updated_at: now,
+            events: Vec::new(),
+        }
+        .registered()
+    }
+
+    /// Raise the event announcing a new account
+    fn registered(mut self) -> Self {
+        self.events.push(AuthEvent::UserRegistered {
+            user_id: self.id.as_uuid(),
+            email: self.email.as_str().to_string(),
+            display_name: self.display_name.clone(),
+            occurred_at: self.created_at,
+        });
+        self
     }
 
     /// Reconstruct a user from persistence
     ///
     /// Used when loading from database - bypasses normal construction rules.
     #[must_use]
+    #[allow(clippy::too_many_arguments)]
     pub fn from_persistence(
         id: Uuid,
         email: String,
-        hashed_password: String,
+        hashed_password: Option<String>,
         display_name: Option<String>,
         is_active: bool,
+        email_verified_at: Option<DateTime<Utc>>,
         created_at: DateTime<Utc>,
         updated_at: DateTime<Utc>,
     ) -> Self {
@@ -150,14 +234,26 @@ impl User {
             id: UserId::from_uuid(id),
             // Trust persisted email is valid
             email: Email(email),
-            hashed_password: HashedPassword(hashed_password),
+            hashed_password: hashed_password.map(HashedPassword),
             display_name,
             is_active,
+            roles: Vec::new(),
+            email_verified_at,
             created_at,
             updated_at,
+            events: Vec::new(),
         }
     }
 
+    /// Attach persisted roles to a reconstructed user
+    #[must_use]
+    pub fn with_roles(mut self, roles: impl IntoIterator<Item = Role>) -> Self {
+        self.roles = roles.into_iter().collect();
+        self.roles.sort();
+        self.roles.dedup();
+        self
+    }
+
     /// Get the user's ID
     #[must_use]
     pub fn id(&self) -> UserId {
@@ -170,10 +266,16 @@ impl User {
         &self.email
     }
 
-    /// Get the user's hashed password
+    /// Get the user's hashed password, if they have one
+    #[must_use]
+    pub fn hashed_password(&self) -> Option<&HashedPassword> {
+        self.hashed_password.as_ref()
+    }
+
+    /// Check if the user can sign in with a password
     #[must_use]
-    pub fn hashed_password(&self) -> &HashedPassword {
-        &self.hashed_password
+    pub fn has_password(&self) -> bool {
+        self.hashed_password.is_some()
     }
 
     /// Get the user's display name
@@ -188,6 +290,30 @@ impl User {
         self.is_active
     }
 
+    /// Get the user's roles, sorted by privilege
+    #[must_use]
+    pub fn roles(&self) -> &[Role] {
+        &self.roles
+    }
+
+    /// Check if the user holds a role
+    #[must_use]
+    pub fn has_role(&self, role: Role) -> bool {
+        self.roles.contains(&role)
+    }
+
+    /// Check if the user has confirmed ownership of their email address
+    #[must_use]
+    pub fn is_email_verified(&self) -> bool {
+        self.email_verified_at.is_some()
+    }
+
+    /// Get the time the email address was verified
+    #[must_use]
+    pub fn email_verified_at(&self) -> Option<DateTime<Utc>> {
+        self.email_verified_at
+    }
+
     /// Get the creation timestamp
     #[must_use]
     pub fn created_at(&self) -> DateTime<Utc> {
@@ -200,10 +326,26 @@ impl User {
         self.updated_at
     }
 
+    /// Get the events raised since the user was created or loaded
+    ///
+    /// They are stored each time the user is written, so a user value is
+    /// written once and then replaced by the one the repository returns.
+    #[must_use]
+    pub fn pending_events(&self) -> &[AuthEvent] {
+        &self.events
+    }
+
     /// Deactivate the user account
     pub fn deactivate(&mut self) {
+        let now = Utc::now();
+        if self.is_active {
+            self.events.push(AuthEvent::UserDeactivated {
+                user_id: self.id.as_uuid(),
+                occurred_at: now,
+            });
+        }
         self.is_active = false;
-        self.updated_at = Utc::now();
+        self.updated_at = now;
     }
 
     /// Activate the user account
@@ -211,6 +353,113 @@ impl User {
         self.is_active = true;
         self.updated_at = Utc::now();
     }
+
+    /// Mark the email address as verified, returning false if it already was
+    pub fn verify_email(&mut self) -> bool {
+        if self.email_verified_at.is_some() {
+            return false;
+        }
+        let now = Utc::now();
+        self.email_verified_at = Some(now);
+        self.updated_at = now;
+        true
+    }
+
+    /// Replace the user's password hash, or set the first one
+    pub fn change_password(&mut self, hashed_password: HashedPassword) {
+        let now = Utc::now();
+        self.hashed_password = Some(hashed_password);
+        self.updated_at = now;
+        self.events.push(AuthEvent::PasswordChanged {
+            user_id: self.id.as_uuid(),
+            occurred_at: now,
+        });
+    }
+
+    /// Replace the email address, returning false if it is unchanged
+    ///
+    /// The new address starts out unverified.
+    pub fn change_email(&mut self, email: Email) -> bool {
+        if self.email == email {
+            return false;
+        }
+        self.email = email;
+        self.email_verified_at = None;
+        self.updated_at = Utc::now();
+        true
+    }
+
+    /// Replace the display name, returning false if it is unchanged
+    pub fn set_display_name(&mut self, display_name: Option<String>) -> bool {
+        if self.display_name == display_name {
+            return false;
+        }
+        self.display_name = display_name;
+        self.updated_at = Utc::now();
+        true
+    }
+
+    /// Anonymise the account, returning false if it already was
+    ///
+    /// The id and creation time are kept so that records referring to the
+    /// user stay valid; the email address is replaced by a placeholder, the
+    /// display name and roles are removed, the password can no longer be
+    /// used and the account is deactivated. Raises `UserErased`, telling
+    /// other services to erase their copies.
+    pub fn erase(&mut self) -> bool {
+        if self.is_erased() {
+            return false;
+        }
+        let now = Utc::now();
+        self.email = Email(format!(
+            "deleted-{}@{}",
+            self.id.as_uuid().simple(),
+            ERASED_EMAIL_DOMAIN
+        ));
+        self.hashed_password = Some(HashedPassword(ERASED_PASSWORD_HASH.to_string()));
+        self.display_name = None;
+        self.is_active = false;
+        self.roles.clear();
+        self.email_verified_at = None;
+        self.updated_at = now;
+        self.events.push(AuthEvent::UserErased {
+            user_id: self.id.as_uuid(),
+            erased_at: now,
+        });
+        true
+    }
+
+    /// Check if the account has been erased
+    #[must_use]
+    pub fn is_erased(&self) -> bool {
+        self.hashed_password
+            .as_ref()
+            .is_some_and(|hash| hash.as_str() == ERASED_PASSWORD_HASH)
+    }
+
+    /// Grant a role, returning false if the user already held it
+    pub fn assign_role(&mut self, role: Role) -> bool {
+        match self.roles.binary_search(&role) {
+            Ok(_) => false,
+            Err(pos) => {
+                self.roles.insert(pos, role);
+                self.updated_at = Utc::now();
+                true
+            }
+        }
+    }
+
+    /// Remove a role, returning false if the user did not hold it
+    pub fn revoke_role(&mut self, role: Role) -> bool {
+        match self.roles.binary_search(&role) {
+            Ok(pos) => {
+                self.roles.remove(pos);
+                self.updated_at = Utc::now();
+                true
+            }
+            Err(_) => false,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -247,5 +496,162 @@ mod tests {
         assert!(user.is_active());
         assert_eq!(user.email().as_str(), "test@example.com");
         assert_eq!(user.display_name(), Some("Test User"));
+        assert_eq!(user.roles(), &[Role::Customer]);
+    }
+
+    #[test]
+    fn test_federated_user_has_no_password() {
+        let email = Email::new("test@example.com").unwrap();
+        let mut user = User::new_federated(email, None);
+
+        assert!(!user.has_password());
+        assert!(user.hashed_password().is_none());
+        assert!(user.is_email_verified());
+        assert_eq!(user.roles(), &[Role::Customer]);
+
+        user.change_password(HashedPassword::from_hash("hashed".to_string()));
+        assert!(user.has_password());
+
+        let mut federated = User::new_federated(Email::new("sso@example.com").unwrap(), None);
+        assert!(federated.erase());
+        assert!(federated.is_erased());
+    }
+
+    #[test]
+    fn test_assign_and_revoke_roles() {
+        let email = Email::new("test@example.com").unwrap();
+        let password = HashedPassword::from_hash("hashed".to_string());
+        let mut user = User::new(email, password, None);
+
+        assert!(user.assign_role(Role::Admin));
+        assert!(!user.assign_role(Role::Admin));
+        assert!(user.assign_role(Role::Organizer));
+        assert_eq!(
+            user.roles(),
+            &[Role::Customer, Role::Organizer, Role::Admin]
+        );
+
+        assert!(user.revoke_role(Role::Organizer));
+        assert!(!user.revoke_role(Role::Organizer));
+        assert!(!user.has_role(Role::Organizer));
+        assert!(user.has_role(Role::Admin));
+    }
+
+    #[test]
+    fn test_verify_email() {
+        let email = Email::new("test@example.com").unwrap();
+        let password = HashedPassword::from_hash("hashed".to_string());
+        let mut user = User::new(email, password, None);
+
+        assert!(!user.is_email_verified());
+        assert!(user.verify_email());
+        assert!(!user.verify_email());
+        assert!(user.is_email_verified());
+    }
+
+    #[test]
+    fn test_change_email_requires_reverification() {
+        let email = Email::new("test@example.com").unwrap();
+        let password = HashedPassword::from_hash("hashed".to_string());
+        let mut user = User::new(email.clone(), password, None);
+        user.verify_email();
+        let verified_at = user.updated_at();
+
+        assert!(!user.change_email(email));
+        assert!(user.is_email_verified());
+        assert_eq!(user.updated_at(), verified_at);
+
+        assert!(user.change_email(Email::new("new@example.com").unwrap()));
+        assert_eq!(user.email().as_str(), "new@example.com");
+        assert!(!user.is_email_verified());
+        assert!(user.updated_at() >= verified_at);
+    }
+
+    #[test]
+    fn test_erase_anonymises_user() {
+        let email = Email::new("test@example.com").unwrap();
+        let password = HashedPassword::from_hash("hashed".to_string());
+        let mut user = User::new(email, password, Some("Test User".to_string()));
+        user.verify_email();
+        let id = user.id();
+
+        assert!(user.erase());
+        assert!(!user.erase());
+        assert!(user.is_erased());
+        assert_eq!(user.id(), id);
+        assert!(user.email().as_str().ends_with("@erased.invalid"));
+        assert_eq!(user.display_name(), None);
+        assert!(!user.is_active());
+        assert!(!user.is_email_verified());
+        assert!(user.roles().is_empty());
+        // Erasing twice announces it once
+        assert_eq!(
+            user.pending_events().last(),
+            Some(&AuthEvent::UserErased {
+                user_id: id.as_uuid(),
+                erased_at: user.updated_at(),
+            })
+        );
+        assert_eq!(
+            user.pending_events()
+                .iter()
+                .filter(|e| e.name() == "user.erased")
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_changes_raise_events() {
+        let email = Email::new("test@example.com").unwrap();
+        let password = HashedPassword::from_hash("hashed".to_string());
+        let mut user = User::new(email, password, None);
+        let id = user.id().as_uuid();
+
+        user.change_password(HashedPassword::from_hash("new".to_string()));
+        user.deactivate();
+        user.deactivate();
+        user.activate();
+
+        let names: Vec<_> = user.pending_events().iter().map(AuthEvent::name).collect();
+        assert_eq!(
+            names,
+            [
+                "user.registered",
+                "user.password_changed",
+                "user.deactivated"
+            ]
+        );
+        assert!(user.pending_events().iter().all(|e| e.user_id() == id));
+
+        let loaded = User::from_persistence(
+            id,
+            "test@example.com".to_string(),
+            None,
+            None,
+            true,
+            None,
+            user.created_at(),
+            user.updated_at(),
+        );
+        assert!(loaded.pending_events().is_empty());
+    }
+
+    #[test]
+    fn test_normalize_display_name() {
+        assert_eq!(
+            normalize_display_name(Some("  Ada Lovelace ")),
+            Ok(Some("Ada Lovelace".to_string()))
+        );
+        assert_eq!(normalize_display_name(Some("   ")), Ok(None));
+        assert_eq!(normalize_display_name(None), Ok(None));
+        assert_eq!(
+            normalize_display_name(Some("Ada\nLovelace")),
+            Err(AuthError::InvalidDisplayName)
+        );
+        assert_eq!(
+            normalize_display_name(Some(&"a".repeat(MAX_DISPLAY_NAME_LEN + 1))),
+            Err(AuthError::InvalidDisplayName)
+        );
     }
 }
@@ -0,0 +1,164 @@
+//! User search for administrators
+//!
+//! Users are listed newest first and paged with an opaque keyset cursor
+//! (creation time and id of the last user on a page), so pages stay stable
+//! while accounts are created or changed and deep pages cost no more than
+//! the first one.
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use super::error::AuthError;
+use super::role::Role;
+use super::user::User;
+
+/// Page size used when the caller does not ask for one
+pub const DEFAULT_PAGE_SIZE: usize = 50;
+
+/// Largest page a caller may ask for
+pub const MAX_PAGE_SIZE: usize = 100;
+
+/// How an email search term is matched
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EmailMatch {
+    /// Addresses starting with the term
+    Prefix(String),
+    /// Addresses containing the term anywhere
+    Contains(String),
+}
+
+impl EmailMatch {
+    /// Search term, normalized like stored addresses
+    #[must_use]
+    pub fn term(&self) -> String {
+        match self {
+            Self::Prefix(term) | Self::Contains(term) => term.trim().to_lowercase(),
+        }
+    }
+}
+
+/// Criteria a listed user must meet; unset fields match everyone
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UserFilter {
+    pub is_active: Option<bool>,
+    pub role: Option<Role>,
+    /// Created at or after this instant
+    pub created_after: Option<DateTime<Utc>>,
+    /// Created strictly before this instant
+    pub created_before: Option<DateTime<Utc>>,
+    pub email: Option<EmailMatch>,
+}
+
+/// Position after the last user of a page
+///
+/// Creation times are kept to the microsecond, the precision Postgres stores.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UserCursor {
+    pub created_at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl UserCursor {
+    /// Cursor pointing after `user`
+    #[must_use]
+    pub fn after(user: &User) -> Self {
+        Self {
+            created_at: user.created_at(),
+            id: user.id().as_uuid(),
+        }
+    }
+
+    /// Encode for handing to clients
+    #[must_use]
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(format!(
+            "{}:{}",
+            self.created_at.timestamp_micros(),
+            self.id
+        ))
+    }
+
+    /// Decode a cursor produced by `encode`
+    ///
+    /// # Errors
+    /// Returns `AuthError::InvalidCursor` if `value` is not a valid cursor
+    pub fn decode(value: &str) -> Result<Self, AuthError> {
+        let bytes = URL_SAFE_NO_PAD
+            .decode(value)
+            .map_err(|_| AuthError::InvalidCursor)?;
+        let text = String::from_utf8(bytes).map_err(|_| AuthError::InvalidCursor)?;
+        let (micros, id) = text.split_once(':').ok_or(AuthError::InvalidCursor)?;
+
+        let created_at = micros
+            .parse()
+            .ok()
+            .and_then(DateTime::from_timestamp_micros)
+            .ok_or(AuthError::InvalidCursor)?;
+        let id = id.parse().map_err(|_| AuthError::InvalidCursor)?;
+
+        Ok(Self { created_at, id })
+    }
+}
+
+/// One page of users
+#[derive(Debug, Clone)]
+pub struct UserPage {
+    pub users: Vec<User>,
+    /// Cursor for the following page, `None` on the last one
+    pub next_cursor: Option<UserCursor>,
+}
+
+/// Clamp a requested page size to the allowed range, 0 meaning the default
+#[must_use]
+pub fn page_size(requested: usize) -> usize {
+    match requested {
+        0 => DEFAULT_PAGE_SIZE,
+        n => n.min(MAX_PAGE_SIZE),
+    }
+}
+
+/// Repository interface for searching users
+pub trait UserSearchRepository {
+    /// List users matching `filter`, newest first, starting after `cursor`
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on database errors
+    fn search(
+        &self,
+        filter: &UserFilter,
+        cursor: Option<&UserCursor>,
+        limit: usize,
+    ) -> Result<UserPage, AuthError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let cursor = UserCursor {
+            created_at: DateTime::from_timestamp_micros(1_700_000_000_123_456).unwrap(),
+            id: Uuid::new_v4(),
+        };
+
+        assert_eq!(UserCursor::decode(&cursor.encode()), Ok(cursor));
+        assert_eq!(
+            UserCursor::decode("not a cursor"),
+            Err(AuthError::InvalidCursor)
+        );
+        assert_eq!(
+            UserCursor::decode(&URL_SAFE_NO_PAD.encode("12:not-a-uuid")),
+            Err(AuthError::InvalidCursor)
+        );
+    }
+
+    #[test]
+    fn test_page_size_is_clamped() {
+        assert_eq!(page_size(0), DEFAULT_PAGE_SIZE);
+        assert_eq!(page_size(10), 10);
+        assert_eq!(page_size(10_000), MAX_PAGE_SIZE);
+    }
+}
@@ -1,23 +1,29 @@
 //! Cached wrapper around TokenService for token validation
 //!
 //! Uses moka in-memory cache to avoid repeated JWT decode + DB lookups
-//! for the same token within a short TTL window.
+//! for the same token within a short TTL window. Cache hits are still
+//! checked against the token's expiry and the revocation list, so neither
+//! an expired token nor a logout outlives the TTL, on any replica.
 
 use std::sync::Arc;
 use std::time::Duration;
 
+use chrono::Utc;
 use moka::sync::Cache;
+use uuid::Uuid;
 
-use crate::domain::auth::{TokenData, TokenService};
+use crate::domain::auth::{self, AsyncTokenService, BoxFuture, TokenData, TokenService};
 use crate::domain::error::AuthError;
 use crate::domain::user::User;
 
 /// A caching decorator over any `TokenService` implementation.
 ///
 /// `create_token` delegates directly; `validate_token` results are cached
-/// by token string with a configurable TTL.
+/// by token string with a configurable TTL, whether validated blocking or
+/// async. Revocation is always delegated, and a cached token that has
+/// expired or been revoked is evicted.
 pub struct CachedTokenService {
-    inner: Arc<dyn TokenService + Send + Sync>,
+    inner: Arc<dyn AsyncTokenService + Send + Sync>,
     cache: Cache<String, TokenData>,
 }
 
@@ -29,7 +35,7 @@ impl CachedTokenService {
     /// * `ttl_secs` - Time-to-live for cached validation results in seconds
     /// * `max_capacity` - Maximum number of cached entries
     pub fn new(
-        inner: Arc<dyn TokenService + Send + Sync>,
+        inner: Arc<dyn AsyncTokenService + Send + Sync>,
         ttl_secs: u64,
         max_capacity: u64,
     ) -> Self {
@@ -40,54 +46,136 @@ impl CachedTokenService {
 
         Self { inner, cache }
     }
+
+    /// Look a token up in the cache, evicting it once it has expired
+    fn cached(&self, token: &str) -> Result<Option<TokenData>, AuthError> {
+        match self.cache.get(token) {
+            Some(data) if data.expires_at <= Utc::now() => {
+                self.cache.invalidate(token);
+                Err(AuthError::TokenExpired)
+            }
+            data => Ok(data),
+        }
+    }
 }
 
 impl TokenService for CachedTokenService {
-    fn create_token(&self, user: &User) -> Result<String, AuthError> {
-        self.inner.create_token(user)
+    fn create_token(&self, user: &User, session_id: Option<Uuid>) -> Result<String, AuthError> {
+        self.inner.create_token(user, session_id)
     }
 
     fn validate_token(&self, token: &str) -> Result<TokenData, AuthError> {
         // Check cache first
-        if let Some(data) = self.cache.get(&token.to_string()) {
+        if let Some(data) = self.cached(token)? {
+            if TokenService::is_revoked(self.inner.as_ref(), &data)? {
+                self.cache.invalidate(token);
+                return Err(AuthError::TokenRevoked);
+            }
             return Ok(data);
         }
 
         // Cache miss — validate via inner service
-        let data = self.inner.validate_token(token)?;
+        let data = TokenService::validate_token(self.inner.as_ref(), token)?;
         self.cache.insert(token.to_string(), data.clone());
         Ok(data)
     }
+
+    fn revoke_token(&self, token: &TokenData) -> Result<(), AuthError> {
+        self.inner.revoke_token(token)
+    }
+
+    fn is_revoked(&self, token: &TokenData) -> Result<bool, AuthError> {
+        TokenService::is_revoked(self.inner.as_ref(), token)
+    }
+}
+
+impl auth::AsyncTokenValidator for CachedTokenService {
+    fn validate_token<'a>(&'a self, token: &'a str) -> BoxFuture<'a, Result<TokenData, AuthError>> {
+        Box::pin(async move {
+            if let Some(data) = self.cached(token)? {
+                if auth::AsyncTokenValidator::is_revoked(self.inner.as_ref(), &data).await? {
+                    self.cache.invalidate(token);
+                    return Err(AuthError::TokenRevoked);
+                }
+                return Ok(data);
+            }
+
+            let data = auth::AsyncTokenValidator::validate_token(self.inner.as_ref(), token).await?;
+            self.cache.insert(token.to_string(), data.clone());
+            Ok(data)
+        })
+    }
+
+    fn is_revoked<'a>(&'a self, token: &'a TokenData) -> BoxFuture<'a, Result<bool, AuthError>> {
+        auth::AsyncTokenValidator::is_revoked(self.inner.as_ref(), token)
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Mutex;
+
     use super::*;
-    use crate::domain::user::{Email, HashedPassword};
+    use crate::application::commands::test_support::token_data;
+
+    use chrono::Duration as ChronoDuration;
     use uuid::Uuid;
 
-    struct FakeTokenService;
+    #[derive(Default)]
+    struct FakeTokenService {
+        revoked: Mutex<bool>,
+    }
 
     impl TokenService for FakeTokenService {
-        fn create_token(&self, _user: &User) -> Result<String, AuthError> {
+        fn create_token(
+            &self,
+            _user: &User,
+            _session_id: Option<Uuid>,
+        ) -> Result<String, AuthError> {
             Ok("fake_token".to_string())
         }
 
         fn validate_token(&self, token: &str) -> Result<TokenData, AuthError> {
-            if token == "valid" {
-                Ok(TokenData {
-                    user_id: Uuid::nil(),
-                    email: "cached@example.com".to_string(),
-                })
-            } else {
-                Err(AuthError::InvalidToken)
-            }
+            let lifetime = match token {
+                "valid" => ChronoDuration::hours(1),
+                "short_lived" => ChronoDuration::milliseconds(50),
+                _ => return Err(AuthError::InvalidToken),
+            };
+            Ok(TokenData {
+                expires_at: Utc::now() + lifetime,
+                ..token_data(Uuid::nil())
+            })
+        }
+
+        fn revoke_token(&self, _token: &TokenData) -> Result<(), AuthError> {
+            *self.revoked.lock().unwrap() = true;
+            Ok(())
+        }
+
+        fn is_revoked(&self, _token: &TokenData) -> Result<bool, AuthError> {
+            Ok(*self.revoked.lock().unwrap())
+        }
+    }
+
+    impl auth::AsyncTokenValidator for FakeTokenService {
+        fn validate_token<'a>(
+            &'a self,
+            token: &'a str,
+        ) -> BoxFuture<'a, Result<TokenData, AuthError>> {
+            Box::pin(async move { TokenService::validate_token(self, token) })
+        }
+
+        fn is_revoked<'a>(
+            &'a self,
+            token: &'a TokenData,
+        ) -> BoxFuture<'a, Result<bool, AuthError>> {
+            Box::pin(async move { TokenService::is_revoked(self, token) })
         }
     }
 
     #[test]
     fn test_cached_validation_returns_same_result() {
-        let inner = Arc::new(FakeTokenService);
+        let inner = Arc::new(FakeTokenService::default());
         let cached = CachedTokenService::new(inner, 60, 100);
 
         let first = cached.validate_token("valid").unwrap();
@@ -99,11 +187,53 @@ mod tests {
 
     #[test]
     fn test_cached_invalid_token_not_cached() {
-        let inner = Arc::new(FakeTokenService);
+        let inner = Arc::new(FakeTokenService::default());
         let cached = CachedTokenService::new(inner, 60, 100);
 
         assert!(cached.validate_token("invalid").is_err());
         // Errors are not cached, so this should also hit the inner service
         assert!(cached.validate_token("invalid").is_err());
     }
+
+    #[test]
+    fn test_revoked_token_rejected_from_cache() {
+        let inner = Arc::new(FakeTokenService::default());
+        let cached = CachedTokenService::new(inner, 60, 100);
+
+        let data = cached.validate_token("valid").unwrap();
+        cached.revoke_token(&data).unwrap();
+
+        let result = cached.validate_token("valid");
+        assert!(matches!(result, Err(AuthError::TokenRevoked)));
+    }
+
+    #[test]
+    fn test_expired_token_rejected_from_cache() {
+        let inner = Arc::new(FakeTokenService::default());
+        let cached = CachedTokenService::new(inner, 60, 100);
+
+        cached.validate_token("short_lived").unwrap();
+        assert!(cached.cache.get("short_lived").is_some());
+        std::thread::sleep(Duration::from_millis(100));
+
+        let result = cached.validate_token("short_lived");
+        assert!(matches!(result, Err(AuthError::TokenExpired)));
+        assert!(cached.cache.get("short_lived").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_async_validation_shares_the_cache() {
+        use crate::domain::auth::AsyncTokenValidator;
+
+        let inner = Arc::new(FakeTokenService::default());
+        let cached = CachedTokenService::new(inner, 60, 100);
+
+        let data = TokenService::validate_token(&cached, "valid").unwrap();
+        assert!(cached.cache.get("valid").is_some());
+        cached.revoke_token(&data).unwrap();
+
+        let result = AsyncTokenValidator::validate_token(&cached, "valid").await;
+        assert!(matches!(result, Err(AuthError::TokenRevoked)));
+        assert!(cached.cache.get("valid").is_none());
+    }
 }
@@ -2,17 +2,118 @@
 
 use std::env;
 
+use serde::Deserialize;
+
+use crate::domain::federation::{is_valid_provider_id, IdentityProvider};
+use crate::domain::lockout::LockoutPolicy;
+use crate::domain::magic_link::MagicLinkRateLimit;
+use crate::domain::password_policy::PasswordRules;
+
 /// Configuration for the auth service
 #[derive(Debug, Clone)]
 pub struct Config {
     pub database_url: String,
-    pub jwt_secret: String,
+    /// JWT signing algorithm: "HS256" (shared secret), "RS256" or "EdDSA" (key ring)
+    pub jwt_algorithm: String,
+    /// Shared secret, only used with HS256
+    pub jwt_secret: Option<String>,
     pub jwt_expiration_secs: i64,
+    /// How often the signing key ring is reloaded from the database, in seconds
+    pub jwt_key_refresh_secs: u64,
+    /// Refresh token lifetime in seconds
+    pub refresh_token_expiration_secs: i64,
+    /// Password reset token lifetime in seconds
+    pub password_reset_token_expiration_secs: i64,
+    /// Page that completes a password reset; the token is appended as `?token=`
+    pub password_reset_url: String,
+    /// Email verification token lifetime in seconds
+    pub email_verification_token_expiration_secs: i64,
+    /// Page that completes email verification; the token is appended as `?token=`
+    pub email_verification_url: String,
+    /// Reject logins from users who have not verified their email address
+    pub require_email_verification: bool,
+    /// Name shown next to the account in authenticator apps
+    pub mfa_issuer: String,
+    /// Lifetime of the challenge between the password and the second factor, in seconds
+    pub mfa_challenge_expiration_secs: i64,
+    /// Whether the OpenID Connect provider endpoints are served
+    pub oidc_provider_enabled: bool,
+    /// Public base URL of this service, the OpenID Connect issuer identifier
+    pub issuer_url: String,
+    /// Page that signs users in for OpenID Connect clients; the authorization
+    /// request's query string is passed on to it
+    pub oidc_login_url: String,
+    /// Authorization code lifetime in seconds
+    pub authorization_code_expiration_secs: i64,
+    /// Upstream OpenID Connect providers users may sign in with
+    pub identity_providers: Vec<IdentityProvider>,
+    /// Page the providers send users back to, registered with each of them
+    pub federated_login_callback_url: String,
+    /// Time a user has to sign in at an identity provider, in seconds
+    pub federated_login_expiration_secs: i64,
+    /// Limit for each request to an identity provider, in seconds
+    pub identity_provider_timeout_secs: u64,
+    /// Accept `http://` identity providers (local and test setups only)
+    pub identity_provider_allow_http: bool,
+    /// Magic link lifetime in seconds
+    pub magic_link_expiration_secs: i64,
+    /// Page that completes a magic-link login; the token is appended as `?token=`
+    pub magic_link_url: String,
+    /// Magic links mailed per account within the rate window
+    pub magic_link_max_per_window: i64,
+    /// Window the magic link rate limit applies to, in seconds
+    pub magic_link_rate_window_secs: i64,
+    /// Failed logins per email address before it is locked out
+    pub lockout_email_threshold: i32,
+    /// Failed logins per source IP before it is locked out
+    pub lockout_ip_threshold: i32,
+    /// First lockout in seconds; doubles with every further failure
+    pub lockout_base_secs: i64,
+    /// Longest lockout in seconds
+    pub lockout_max_secs: i64,
+    /// Seconds without failures after which the counters are forgotten
+    pub lockout_reset_secs: i64,
+    /// Take the client IP from `X-Forwarded-For` (only behind a trusted proxy)
+    pub trust_forwarded_for: bool,
+    /// Days audit entries are kept before they are purged
+    pub audit_retention_days: i64,
+    /// Impersonation token lifetime in seconds
+    pub impersonation_expiration_secs: i64,
+    /// Organization invitation lifetime in seconds
+    pub invitation_expiration_secs: i64,
+    /// Page that accepts an organization invitation; the token is appended as `?token=`
+    pub invitation_url: String,
+    /// Comma-separated Kafka bootstrap servers (`None` = events are only logged)
+    pub kafka_brokers: Option<String>,
+    /// Kafka topic domain events are published to
+    pub kafka_topic: String,
+    /// How often the outbox is checked for unpublished events, in milliseconds
+    pub outbox_poll_interval_ms: u64,
+    /// Most events published in one batch
+    pub outbox_batch_size: usize,
+    /// Days published events are kept in the outbox before they are purged
+    pub outbox_retention_days: i64,
+    /// Minimum password length in characters
+    pub password_min_length: usize,
+    /// Maximum password length in characters
+    pub password_max_length: usize,
+    pub password_require_lowercase: bool,
+    pub password_require_uppercase: bool,
+    pub password_require_digit: bool,
+    pub password_require_symbol: bool,
+    /// Reject passwords containing the local part of the email address
+    pub password_forbid_email: bool,
+    /// Pwned Passwords hash file to reject breached passwords with (`None` = no check)
+    pub breached_passwords_file: Option<String>,
+    /// Directory outgoing mail is written to as `.eml` files (`None` = log only)
+    pub mail_dir: Option<String>,
     pub server_host: String,
     pub server_port: u16,
     pub grpc_port: u16,
     /// Maximum number of connections in the database pool
     pub db_pool_max_size: u32,
+    /// Maximum number of connections in the async database pool
+    pub db_async_pool_max_size: usize,
     /// Token validation cache TTL in seconds (0 = disabled)
     pub token_cache_ttl_secs: u64,
     /// Token validation cache max capacity
@@ -32,14 +133,257 @@ impl Config {
         let database_url = env::var("DATABASE_URL")
             .map_err(|_| ConfigError::MissingEnv("DATABASE_URL"))?;
 
-        let jwt_secret = env::var("AUTH_JWT_SECRET")
-            .map_err(|_| ConfigError::MissingEnv("AUTH_JWT_SECRET"))?;
+        let jwt_algorithm = env::var("AUTH_JWT_ALGORITHM").unwrap_or_else(|_| "EdDSA".to_string());
+        if !matches!(jwt_algorithm.as_str(), "HS256" | "RS256" | "EdDSA") {
+            return Err(ConfigError::InvalidValue("AUTH_JWT_ALGORITHM"));
+        }
+
+        let jwt_secret = env::var("AUTH_JWT_SECRET").ok();
+        if jwt_algorithm == "HS256" && jwt_secret.is_none() {
+            return Err(ConfigError::MissingEnv("AUTH_JWT_SECRET"));
+        }
 
         let jwt_expiration_secs = env::var("AUTH_JWT_EXP_SECS")
             .unwrap_or_else(|_| "3600".to_string())
             .parse()
             .map_err(|_| ConfigError::InvalidValue("AUTH_JWT_EXP_SECS"))?;
 
+        let jwt_key_refresh_secs = env::var("AUTH_JWT_KEY_REFRESH_SECS")
+            .unwrap_or_else(|_| "60".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("AUTH_JWT_KEY_REFRESH_SECS"))?;
+
+        let refresh_token_expiration_secs = env::var("AUTH_REFRESH_TOKEN_EXP_SECS")
+            .unwrap_or_else(|_| "2592000".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("AUTH_REFRESH_TOKEN_EXP_SECS"))?;
+
+        let password_reset_token_expiration_secs = env::var("AUTH_PASSWORD_RESET_TOKEN_EXP_SECS")
+            .unwrap_or_else(|_| "3600".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("AUTH_PASSWORD_RESET_TOKEN_EXP_SECS"))?;
+
+        let password_reset_url = env::var("AUTH_PASSWORD_RESET_URL")
+            .unwrap_or_else(|_| "http://localhost:3000/reset-password".to_string());
+
+        let email_verification_token_expiration_secs =
+            env::var("AUTH_EMAIL_VERIFICATION_TOKEN_EXP_SECS")
+                .unwrap_or_else(|_| "86400".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("AUTH_EMAIL_VERIFICATION_TOKEN_EXP_SECS"))?;
+
+        let email_verification_url = env::var("AUTH_EMAIL_VERIFICATION_URL")
+            .unwrap_or_else(|_| "http://localhost:3000/verify-email".to_string());
+
+        let require_email_verification = env::var("AUTH_REQUIRE_EMAIL_VERIFICATION")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("AUTH_REQUIRE_EMAIL_VERIFICATION"))?;
+
+        // A colon would break the `issuer:account` label of otpauth URIs
+        let mfa_issuer =
+            env::var("AUTH_MFA_ISSUER").unwrap_or_else(|_| "Ticketing System".to_string());
+        if mfa_issuer.contains(':') {
+            return Err(ConfigError::InvalidValue("AUTH_MFA_ISSUER"));
+        }
+
+        let mfa_challenge_expiration_secs = env::var("AUTH_MFA_CHALLENGE_EXP_SECS")
+            .unwrap_or_else(|_| "300".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("AUTH_MFA_CHALLENGE_EXP_SECS"))?;
+
+        let oidc_provider_enabled = oidc_provider_enabled(
+            env::var("AUTH_OIDC_PROVIDER_ENABLED").ok().as_deref(),
+            &jwt_algorithm,
+        )?;
+
+        // Clients compare the issuer verbatim, so normalise away a trailing slash
+        let issuer_url = env::var("AUTH_ISSUER_URL")
+            .unwrap_or_else(|_| "http://localhost:8080".to_string())
+            .trim_end_matches('/')
+            .to_string();
+
+        let oidc_login_url = env::var("AUTH_OIDC_LOGIN_URL")
+            .unwrap_or_else(|_| "http://localhost:3000/login".to_string());
+
+        let authorization_code_expiration_secs = env::var("AUTH_AUTHORIZATION_CODE_EXP_SECS")
+            .unwrap_or_else(|_| "60".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("AUTH_AUTHORIZATION_CODE_EXP_SECS"))?;
+
+        let identity_provider_allow_http = env::var("AUTH_IDENTITY_PROVIDER_ALLOW_HTTP")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("AUTH_IDENTITY_PROVIDER_ALLOW_HTTP"))?;
+
+        let identity_providers = match env::var("AUTH_IDENTITY_PROVIDERS") {
+            Ok(value) => parse_identity_providers(&value, identity_provider_allow_http)?,
+            Err(_) => Vec::new(),
+        };
+
+        let federated_login_callback_url = env::var("AUTH_FEDERATED_LOGIN_CALLBACK_URL")
+            .unwrap_or_else(|_| "http://localhost:3000/sso/callback".to_string());
+
+        let federated_login_expiration_secs = env::var("AUTH_FEDERATED_LOGIN_EXP_SECS")
+            .unwrap_or_else(|_| "600".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("AUTH_FEDERATED_LOGIN_EXP_SECS"))?;
+
+        let identity_provider_timeout_secs = env::var("AUTH_IDENTITY_PROVIDER_TIMEOUT_SECS")
+            .unwrap_or_else(|_| "10".to_string())
+            .parse()
+            .ok()
+            .filter(|secs| *secs > 0)
+            .ok_or(ConfigError::InvalidValue("AUTH_IDENTITY_PROVIDER_TIMEOUT_SECS"))?;
+
+        let magic_link_expiration_secs = env::var("AUTH_MAGIC_LINK_EXP_SECS")
+            .unwrap_or_else(|_| "900".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("AUTH_MAGIC_LINK_EXP_SECS"))?;
+
+        let magic_link_url = env::var("AUTH_MAGIC_LINK_URL")
+            .unwrap_or_else(|_| "http://localhost:3000/magic-link".to_string());
+
+        let magic_link_max_per_window = env::var("AUTH_MAGIC_LINK_RATE_LIMIT")
+            .unwrap_or_else(|_| "3".to_string())
+            .parse()
+            .ok()
+            .filter(|limit| *limit > 0)
+            .ok_or(ConfigError::InvalidValue("AUTH_MAGIC_LINK_RATE_LIMIT"))?;
+
+        let magic_link_rate_window_secs = env::var("AUTH_MAGIC_LINK_RATE_WINDOW_SECS")
+            .unwrap_or_else(|_| "3600".to_string())
+            .parse()
+            .ok()
+            .filter(|secs| *secs > 0)
+            .ok_or(ConfigError::InvalidValue("AUTH_MAGIC_LINK_RATE_WINDOW_SECS"))?;
+
+        let lockout_email_threshold = env::var("AUTH_LOCKOUT_THRESHOLD")
+            .unwrap_or_else(|_| "5".to_string())
+            .parse()
+            .ok()
+            .filter(|threshold| *threshold > 0)
+            .ok_or(ConfigError::InvalidValue("AUTH_LOCKOUT_THRESHOLD"))?;
+
+        let lockout_ip_threshold = env::var("AUTH_LOCKOUT_IP_THRESHOLD")
+            .unwrap_or_else(|_| "20".to_string())
+            .parse()
+            .ok()
+            .filter(|threshold| *threshold > 0)
+            .ok_or(ConfigError::InvalidValue("AUTH_LOCKOUT_IP_THRESHOLD"))?;
+
+        let lockout_base_secs = env::var("AUTH_LOCKOUT_BASE_SECS")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("AUTH_LOCKOUT_BASE_SECS"))?;
+
+        let lockout_max_secs = env::var("AUTH_LOCKOUT_MAX_SECS")
+            .unwrap_or_else(|_| "3600".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("AUTH_LOCKOUT_MAX_SECS"))?;
+
+        let lockout_reset_secs = env::var("AUTH_LOCKOUT_RESET_SECS")
+            .unwrap_or_else(|_| "900".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("AUTH_LOCKOUT_RESET_SECS"))?;
+
+        let trust_forwarded_for = env::var("AUTH_TRUST_FORWARDED_FOR")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("AUTH_TRUST_FORWARDED_FOR"))?;
+
+        let audit_retention_days = env::var("AUTH_AUDIT_RETENTION_DAYS")
+            .unwrap_or_else(|_| "365".to_string())
+            .parse()
+            .ok()
+            .filter(|days| *days > 0)
+            .ok_or(ConfigError::InvalidValue("AUTH_AUDIT_RETENTION_DAYS"))?;
+
+        let impersonation_expiration_secs = env::var("AUTH_IMPERSONATION_TOKEN_EXP_SECS")
+            .unwrap_or_else(|_| "900".to_string())
+            .parse()
+            .ok()
+            .filter(|secs| *secs > 0)
+            .ok_or(ConfigError::InvalidValue("AUTH_IMPERSONATION_TOKEN_EXP_SECS"))?;
+
+        let invitation_expiration_secs = env::var("AUTH_INVITATION_EXP_SECS")
+            .unwrap_or_else(|_| "604800".to_string())
+            .parse()
+            .ok()
+            .filter(|secs| *secs > 0)
+            .ok_or(ConfigError::InvalidValue("AUTH_INVITATION_EXP_SECS"))?;
+
+        let invitation_url = env::var("AUTH_INVITATION_URL")
+            .unwrap_or_else(|_| "http://localhost:3000/invitations/accept".to_string());
+
+        let kafka_brokers = env::var("KAFKA_BROKERS").ok();
+
+        let kafka_topic = env::var("KAFKA_TOPIC").unwrap_or_else(|_| "auth.events".to_string());
+
+        let outbox_poll_interval_ms = env::var("AUTH_OUTBOX_POLL_INTERVAL_MS")
+            .unwrap_or_else(|_| "1000".to_string())
+            .parse()
+            .ok()
+            .filter(|ms| *ms > 0)
+            .ok_or(ConfigError::InvalidValue("AUTH_OUTBOX_POLL_INTERVAL_MS"))?;
+
+        let outbox_batch_size = env::var("AUTH_OUTBOX_BATCH_SIZE")
+            .unwrap_or_else(|_| "100".to_string())
+            .parse()
+            .ok()
+            .filter(|size| *size > 0)
+            .ok_or(ConfigError::InvalidValue("AUTH_OUTBOX_BATCH_SIZE"))?;
+
+        let outbox_retention_days = env::var("AUTH_OUTBOX_RETENTION_DAYS")
+            .unwrap_or_else(|_| "7".to_string())
+            .parse()
+            .ok()
+            .filter(|days| *days > 0)
+            .ok_or(ConfigError::InvalidValue("AUTH_OUTBOX_RETENTION_DAYS"))?;
+
+        let password_min_length = env::var("AUTH_PASSWORD_MIN_LENGTH")
+            .unwrap_or_else(|_| "8".to_string())
+            .parse()
+            .ok()
+            .filter(|length| *length > 0)
+            .ok_or(ConfigError::InvalidValue("AUTH_PASSWORD_MIN_LENGTH"))?;
+
+        let password_max_length = env::var("AUTH_PASSWORD_MAX_LENGTH")
+            .unwrap_or_else(|_| "128".to_string())
+            .parse()
+            .ok()
+            .filter(|length| *length >= password_min_length)
+            .ok_or(ConfigError::InvalidValue("AUTH_PASSWORD_MAX_LENGTH"))?;
+
+        let password_require_lowercase = env::var("AUTH_PASSWORD_REQUIRE_LOWERCASE")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("AUTH_PASSWORD_REQUIRE_LOWERCASE"))?;
+
+        let password_require_uppercase = env::var("AUTH_PASSWORD_REQUIRE_UPPERCASE")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("AUTH_PASSWORD_REQUIRE_UPPERCASE"))?;
+
+        let password_require_digit = env::var("AUTH_PASSWORD_REQUIRE_DIGIT")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("AUTH_PASSWORD_REQUIRE_DIGIT"))?;
+
+        let password_require_symbol = env::var("AUTH_PASSWORD_REQUIRE_SYMBOL")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("AUTH_PASSWORD_REQUIRE_SYMBOL"))?;
+
+        let password_forbid_email = env::var("AUTH_PASSWORD_FORBID_EMAIL")
+            .unwrap_or_else(|_| "true".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("AUTH_PASSWORD_FORBID_EMAIL"))?;
+
+        let breached_passwords_file = env::var("AUTH_BREACHED_PASSWORDS_FILE").ok();
+
+        let mail_dir = env::var("AUTH_MAIL_DIR").ok();
+
         let server_host = env::var("SERVER_HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
 
         let server_port = env::var("SERVER_PORT")
@@ -57,6 +401,13 @@ impl Config {
             .parse()
             .map_err(|_| ConfigError::InvalidValue("DB_POOL_MAX_SIZE"))?;
 
+        let db_async_pool_max_size = env::var("DB_ASYNC_POOL_MAX_SIZE")
+            .unwrap_or_else(|_| "10".to_string())
+            .parse()
+            .ok()
+            .filter(|size| *size > 0)
+            .ok_or(ConfigError::InvalidValue("DB_ASYNC_POOL_MAX_SIZE"))?;
+
         let token_cache_ttl_secs = env::var("TOKEN_CACHE_TTL_SECS")
             .unwrap_or_else(|_| "30".to_string())
             .parse()
@@ -76,12 +427,60 @@ impl Config {
 
         Ok(Self {
             database_url,
+            jwt_algorithm,
             jwt_secret,
             jwt_expiration_secs,
+            jwt_key_refresh_secs,
+            refresh_token_expiration_secs,
+            password_reset_token_expiration_secs,
+            password_reset_url,
+            email_verification_token_expiration_secs,
+            email_verification_url,
+            require_email_verification,
+            mfa_issuer,
+            mfa_challenge_expiration_secs,
+            oidc_provider_enabled,
+            issuer_url,
+            oidc_login_url,
+            authorization_code_expiration_secs,
+            identity_providers,
+            federated_login_callback_url,
+            federated_login_expiration_secs,
+            identity_provider_timeout_secs,
+            identity_provider_allow_http,
+            magic_link_expiration_secs,
+            magic_link_url,
+            magic_link_max_per_window,
+            magic_link_rate_window_secs,
+            lockout_email_threshold,
+            lockout_ip_threshold,
+            lockout_base_secs,
+            lockout_max_secs,
+            lockout_reset_secs,
+            trust_forwarded_for,
+            audit_retention_days,
+            impersonation_expiration_secs,
+            invitation_expiration_secs,
+            invitation_url,
+            kafka_brokers,
+            kafka_topic,
+            outbox_poll_interval_ms,
+            outbox_batch_size,
+            outbox_retention_days,
+            password_min_length,
+            password_max_length,
+            password_require_lowercase,
+            password_require_uppercase,
+            password_require_digit,
+            password_require_symbol,
+            password_forbid_email,
+            breached_passwords_file,
+            mail_dir,
             server_host,
             server_port,
             grpc_port,
             db_pool_max_size,
+            db_async_pool_max_size,
             token_cache_ttl_secs,
             token_cache_max_capacity,
             log_format,
@@ -90,6 +489,113 @@ impl Config {
     }
 }
 
+impl Config {
+    /// How long a retired signing key keeps verifying tokens
+    ///
+    /// Covers the token lifetime, the time other replicas may keep signing
+    /// with the old key before reloading, and the validation leeway.
+    #[must_use]
+    pub fn signing_key_grace(&self) -> chrono::Duration {
+        chrono::Duration::seconds(self.jwt_expiration_secs)
+            + chrono::Duration::seconds(self.jwt_key_refresh_secs as i64)
+            + chrono::Duration::seconds(60)
+    }
+
+    /// How many magic links one account may be mailed
+    #[must_use]
+    pub fn magic_link_rate_limit(&self) -> MagicLinkRateLimit {
+        MagicLinkRateLimit {
+            max_links: self.magic_link_max_per_window,
+            window: chrono::Duration::seconds(self.magic_link_rate_window_secs),
+        }
+    }
+
+    /// Thresholds and durations for login lockouts
+    #[must_use]
+    pub fn lockout_policy(&self) -> LockoutPolicy {
+        LockoutPolicy {
+            email_threshold: self.lockout_email_threshold,
+            ip_threshold: self.lockout_ip_threshold,
+            base_lockout: chrono::Duration::seconds(self.lockout_base_secs),
+            max_lockout: chrono::Duration::seconds(self.lockout_max_secs),
+            reset_after: chrono::Duration::seconds(self.lockout_reset_secs),
+        }
+    }
+
+    /// Requirements every new password must meet
+    #[must_use]
+    pub fn password_rules(&self) -> PasswordRules {
+        PasswordRules {
+            min_length: self.password_min_length,
+            max_length: self.password_max_length,
+            require_lowercase: self.password_require_lowercase,
+            require_uppercase: self.password_require_uppercase,
+            require_digit: self.password_require_digit,
+            require_symbol: self.password_require_symbol,
+            forbid_email: self.password_forbid_email,
+        }
+    }
+}
+
+/// Parse `AUTH_OIDC_PROVIDER_ENABLED`, on unless set otherwise
+///
+/// Relying parties verify ID tokens against the JWKS. With HS256 they would
+/// need the secret that signs every access token, so the provider is
+/// refused unless tokens are signed with an asymmetric algorithm.
+fn oidc_provider_enabled(value: Option<&str>, jwt_algorithm: &str) -> Result<bool, ConfigError> {
+    let enabled = value
+        .unwrap_or("true")
+        .parse()
+        .map_err(|_| ConfigError::InvalidValue("AUTH_OIDC_PROVIDER_ENABLED"))?;
+    if enabled && jwt_algorithm == "HS256" {
+        return Err(ConfigError::OidcProviderNeedsAsymmetricKeys);
+    }
+    Ok(enabled)
+}
+
+/// Identity provider entry of `AUTH_IDENTITY_PROVIDERS`
+#[derive(Deserialize)]
+struct IdentityProviderEntry {
+    id: String,
+    name: String,
+    issuer: String,
+    client_id: String,
+    client_secret: Option<String>,
+}
+
+/// Parse the JSON array of identity providers
+///
+/// Issuers must be `https://` URLs, or also `http://` ones if `allow_http`
+/// is set, and provider IDs unique.
+fn parse_identity_providers(
+    value: &str,
+    allow_http: bool,
+) -> Result<Vec<IdentityProvider>, ConfigError> {
+    let entries: Vec<IdentityProviderEntry> = serde_json::from_str(value)
+        .map_err(|_| ConfigError::InvalidValue("AUTH_IDENTITY_PROVIDERS"))?;
+
+    let mut providers: Vec<IdentityProvider> = Vec::with_capacity(entries.len());
+    for entry in entries {
+        if !is_valid_provider_id(&entry.id)
+            || !(entry.issuer.starts_with("https://")
+                || allow_http && entry.issuer.starts_with("http://"))
+            || entry.client_id.is_empty()
+            || providers.iter().any(|provider| provider.id == entry.id)
+        {
+            return Err(ConfigError::InvalidValue("AUTH_IDENTITY_PROVIDERS"));
+        }
+        providers.push(IdentityProvider {
+            id: entry.id,
+            name: entry.name,
+            issuer: entry.issuer,
+            client_id: entry.client_id,
+            client_secret: entry.client_secret.filter(|secret| !secret.is_empty()),
+        });
+    }
+
+    Ok(providers)
+}
+
 /// Configuration errors
 #[derive(Debug, thiserror::Error)]
 pub enum ConfigError {
@@ -98,4 +604,36 @@ pub enum ConfigError {
 
     #[error("Invalid value for environment variable: {0}")]
     InvalidValue(&'static str),
+
+    #[error(
+        "The OpenID Connect provider needs AUTH_JWT_ALGORITHM RS256 or EdDSA; \
+         set AUTH_OIDC_PROVIDER_ENABLED=false to sign with HS256"
+    )]
+    OidcProviderNeedsAsymmetricKeys,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_oidc_provider_refused_with_hs256() {
+        assert!(matches!(
+            oidc_provider_enabled(None, "HS256"),
+            Err(ConfigError::OidcProviderNeedsAsymmetricKeys)
+        ));
+        assert!(matches!(
+            oidc_provider_enabled(Some("true"), "HS256"),
+            Err(ConfigError::OidcProviderNeedsAsymmetricKeys)
+        ));
+        assert!(matches!(oidc_provider_enabled(Some("false"), "HS256"), Ok(false)));
+
+        for algorithm in ["RS256", "EdDSA"] {
+            assert!(matches!(oidc_provider_enabled(None, algorithm), Ok(true)));
+        }
+        assert!(matches!(
+            oidc_provider_enabled(Some("yes"), "EdDSA"),
+            Err(ConfigError::InvalidValue("AUTH_OIDC_PROVIDER_ENABLED"))
+        ));
+    }
 }
@@ -0,0 +1,151 @@
+//! Diesel implementation of the ApiKeyRepository trait
+
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::domain::api_key::{ApiKey, ApiKeyRepository};
+use crate::domain::error::AuthError;
+use crate::domain::role::Permission;
+
+use super::connection::DbPool;
+use super::models::{DbApiKey, NewDbApiKey};
+use super::schema::api_keys;
+
+/// Diesel-based implementation of ApiKeyRepository
+pub struct DieselApiKeyRepository {
+    pool: DbPool,
+}
+
+impl DieselApiKeyRepository {
+    /// Create a new repository instance
+    #[must_use]
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get a connection from the pool
+    fn conn(&self) -> Result<super::connection::PooledDbConnection, AuthError> {
+        self.pool
+            .get()
+            .map_err(|e| AuthError::Internal(format!("Failed to get database connection: {}", e)))
+    }
+}
+
+/// Convert a database row to the domain entity
+///
+/// Scopes that are no longer known are dropped rather than failing the key.
+pub(super) fn to_domain(db_key: DbApiKey) -> ApiKey {
+    let scopes = db_key
+        .scopes
+        .iter()
+        .filter_map(|scope| scope.parse::<Permission>().ok())
+        .collect();
+
+    ApiKey::from_persistence(
+        db_key.id,
+        db_key.user_id,
+        db_key.name,
+        db_key.prefix,
+        db_key.key_hash,
+        scopes,
+        db_key.expires_at,
+        db_key.last_used_at,
+        db_key.created_at,
+        db_key.revoked_at,
+    )
+}
+
+impl ApiKeyRepository for DieselApiKeyRepository {
+    fn create(&self, key: &ApiKey) -> Result<(), AuthError> {
+        let mut conn = self.conn()?;
+
+        let new_key = NewDbApiKey {
+            id: key.id(),
+            user_id: key.user_id(),
+            name: key.name(),
+            prefix: key.prefix(),
+            key_hash: key.key_hash(),
+            scopes: key.scopes().iter().map(Permission::as_str).collect(),
+            expires_at: key.expires_at(),
+            created_at: key.created_at(),
+        };
+
+        diesel::insert_into(api_keys::table)
+            .values(&new_key)
+            .execute(&mut conn)
+            .map_err(|e| AuthError::Internal(format!("Failed to create API key: {}", e)))?;
+
+        Ok(())
+    }
+
+    fn find_by_hash(&self, key_hash: &str) -> Result<ApiKey, AuthError> {
+        let mut conn = self.conn()?;
+
+        let db_key: DbApiKey = api_keys::table
+            .filter(api_keys::key_hash.eq(key_hash))
+            .select(DbApiKey::as_select())
+            .first(&mut conn)
+            .map_err(|e| match e {
+                diesel::result::Error::NotFound => AuthError::InvalidToken,
+                _ => AuthError::Internal(format!("Database error: {}", e)),
+            })?;
+
+        Ok(to_domain(db_key))
+    }
+
+    fn find_by_id(&self, id: Uuid) -> Result<ApiKey, AuthError> {
+        let mut conn = self.conn()?;
+
+        let db_key: DbApiKey = api_keys::table
+            .find(id)
+            .select(DbApiKey::as_select())
+            .first(&mut conn)
+            .map_err(|e| match e {
+                diesel::result::Error::NotFound => AuthError::ApiKeyNotFound,
+                _ => AuthError::Internal(format!("Database error: {}", e)),
+            })?;
+
+        Ok(to_domain(db_key))
+    }
+
+    fn list_for_user(&self, user_id: Uuid) -> Result<Vec<ApiKey>, AuthError> {
+        let mut conn = self.conn()?;
+
+        let db_keys: Vec<DbApiKey> = api_keys::table
+            .filter(api_keys::user_id.eq(user_id))
+            .filter(api_keys::revoked_at.is_null())
+            .order((api_keys::created_at.desc(), api_keys::id.desc()))
+            .select(DbApiKey::as_select())
+            .load(&mut conn)
+            .map_err(|e| AuthError::Internal(format!("Failed to list API keys: {}", e)))?;
+
+        Ok(db_keys.into_iter().map(to_domain).collect())
+    }
+
+    fn revoke(&self, id: Uuid) -> Result<bool, AuthError> {
+        let mut conn = self.conn()?;
+
+        let updated_rows = diesel::update(
+            api_keys::table
+                .filter(api_keys::id.eq(id))
+                .filter(api_keys::revoked_at.is_null()),
+        )
+        .set(api_keys::revoked_at.eq(Utc::now()))
+        .execute(&mut conn)
+        .map_err(|e| AuthError::Internal(format!("Failed to revoke API key: {}", e)))?;
+
+        Ok(updated_rows > 0)
+    }
+
+    fn record_use(&self, id: Uuid, at: DateTime<Utc>) -> Result<(), AuthError> {
+        let mut conn = self.conn()?;
+
+        diesel::update(api_keys::table.filter(api_keys::id.eq(id)))
+            .set(api_keys::last_used_at.eq(at))
+            .execute(&mut conn)
+            .map_err(|e| AuthError::Internal(format!("Failed to record API key use: {}", e)))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,91 @@
+//! diesel-async implementation of the AsyncApiKeyRepository trait
+
+use chrono::{DateTime, Utc};
+use diesel::{ExpressionMethods, QueryDsl, SelectableHelper};
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+use crate::domain::api_key::{ApiKey, AsyncApiKeyRepository};
+use crate::domain::auth::BoxFuture;
+use crate::domain::error::AuthError;
+
+use super::api_key_repository_diesel::to_domain;
+use super::connection::{AsyncDbPool, AsyncPooledDbConnection};
+use super::models::DbApiKey;
+use super::schema::api_keys;
+
+/// Async Diesel-based lookup of API keys
+#[derive(Clone)]
+pub struct AsyncDieselApiKeyRepository {
+    pool: AsyncDbPool,
+}
+
+impl AsyncDieselApiKeyRepository {
+    /// Create a new repository instance
+    #[must_use]
+    pub fn new(pool: AsyncDbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get a connection from the pool
+    async fn conn(&self) -> Result<AsyncPooledDbConnection, AuthError> {
+        self.pool
+            .get()
+            .await
+            .map_err(|e| AuthError::Internal(format!("Failed to get database connection: {}", e)))
+    }
+}
+
+impl AsyncApiKeyRepository for AsyncDieselApiKeyRepository {
+    fn find_by_hash<'a>(&'a self, key_hash: &'a str) -> BoxFuture<'a, Result<ApiKey, AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+
+            let db_key: DbApiKey = api_keys::table
+                .filter(api_keys::key_hash.eq(key_hash))
+                .select(DbApiKey::as_select())
+                .first(&mut conn)
+                .await
+                .map_err(|e| match e {
+                    diesel::result::Error::NotFound => AuthError::InvalidToken,
+                    _ => AuthError::Internal(format!("Database error: {}", e)),
+                })?;
+
+            Ok(to_domain(db_key))
+        })
+    }
+
+    fn find_by_id(&self, id: Uuid) -> BoxFuture<'_, Result<ApiKey, AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+
+            let db_key: DbApiKey = api_keys::table
+                .find(id)
+                .select(DbApiKey::as_select())
+                .first(&mut conn)
+                .await
+                .map_err(|e| match e {
+                    diesel::result::Error::NotFound => AuthError::ApiKeyNotFound,
+                    _ => AuthError::Internal(format!("Database error: {}", e)),
+                })?;
+
+            Ok(to_domain(db_key))
+        })
+    }
+
+    fn record_use(&self, id: Uuid, at: DateTime<Utc>) -> BoxFuture<'_, Result<(), AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+
+            diesel::update(api_keys::table.filter(api_keys::id.eq(id)))
+                .set(api_keys::last_used_at.eq(at))
+                .execute(&mut conn)
+                .await
+                .map_err(|e| {
+                    AuthError::Internal(format!("Failed to record API key use: {}", e))
+                })?;
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,58 @@
+//! diesel-async implementation of the AsyncAuditLog trait
+
+use diesel_async::RunQueryDsl;
+
+use crate::domain::audit::{AsyncAuditLog, AuditEvent};
+use crate::domain::auth::BoxFuture;
+use crate::domain::error::AuthError;
+
+use super::audit_event_repository_diesel::{to_new_db_event, warn_unrecorded};
+use super::connection::{AsyncDbPool, AsyncPooledDbConnection};
+use super::schema::auth_audit_events;
+
+/// Async Diesel-based implementation of AuditLog
+///
+/// Writes to the table `DieselAuditEventRepository` searches.
+#[derive(Clone)]
+pub struct AsyncDieselAuditLog {
+    pool: AsyncDbPool,
+}
+
+impl AsyncDieselAuditLog {
+    /// Create a new audit log instance
+    #[must_use]
+    pub fn new(pool: AsyncDbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get a connection from the pool
+    async fn conn(&self) -> Result<AsyncPooledDbConnection, AuthError> {
+        self.pool
+            .get()
+            .await
+            .map_err(|e| AuthError::Internal(format!("Failed to get database connection: {}", e)))
+    }
+
+    /// Insert an entry
+    async fn insert(&self, event: &AuditEvent) -> Result<(), AuthError> {
+        let mut conn = self.conn().await?;
+
+        diesel::insert_into(auth_audit_events::table)
+            .values(&to_new_db_event(event))
+            .execute(&mut conn)
+            .await
+            .map_err(|e| AuthError::Internal(format!("Failed to record audit event: {}", e)))?;
+
+        Ok(())
+    }
+}
+
+impl AsyncAuditLog for AsyncDieselAuditLog {
+    fn record(&self, event: AuditEvent) -> BoxFuture<'_, ()> {
+        Box::pin(async move {
+            if let Err(e) = self.insert(&event).await {
+                warn_unrecorded(&event, &e);
+            }
+        })
+    }
+}
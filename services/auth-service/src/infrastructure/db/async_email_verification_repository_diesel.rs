@@ -0,0 +1,56 @@
+//! diesel-async implementation of the AsyncEmailVerificationTokenRepository trait
+
+use diesel_async::RunQueryDsl;
+
+use crate::domain::auth::BoxFuture;
+use crate::domain::email_verification::{
+    AsyncEmailVerificationTokenRepository, EmailVerificationToken,
+};
+use crate::domain::error::AuthError;
+
+use super::connection::{AsyncDbPool, AsyncPooledDbConnection};
+use super::email_verification_repository_diesel::to_new_db_token;
+use super::schema::email_verification_tokens;
+
+/// Async Diesel-based storage of newly issued verification tokens
+#[derive(Clone)]
+pub struct AsyncDieselEmailVerificationTokenRepository {
+    pool: AsyncDbPool,
+}
+
+impl AsyncDieselEmailVerificationTokenRepository {
+    /// Create a new repository instance
+    #[must_use]
+    pub fn new(pool: AsyncDbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get a connection from the pool
+    async fn conn(&self) -> Result<AsyncPooledDbConnection, AuthError> {
+        self.pool
+            .get()
+            .await
+            .map_err(|e| AuthError::Internal(format!("Failed to get database connection: {}", e)))
+    }
+}
+
+impl AsyncEmailVerificationTokenRepository for AsyncDieselEmailVerificationTokenRepository {
+    fn create<'a>(
+        &'a self,
+        token: &'a EmailVerificationToken,
+    ) -> BoxFuture<'a, Result<(), AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+
+            diesel::insert_into(email_verification_tokens::table)
+                .values(&to_new_db_token(token))
+                .execute(&mut conn)
+                .await
+                .map_err(|e| {
+                    AuthError::Internal(format!("Failed to create verification token: {}", e))
+                })?;
+
+            Ok(())
+        })
+    }
+}
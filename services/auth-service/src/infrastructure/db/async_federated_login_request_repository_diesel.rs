@@ -0,0 +1,97 @@
+//! diesel-async implementation of the AsyncFederatedLoginRequestRepository trait
+
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, SelectableHelper};
+use diesel_async::RunQueryDsl;
+
+use crate::domain::auth::BoxFuture;
+use crate::domain::error::AuthError;
+use crate::domain::federation::{AsyncFederatedLoginRequestRepository, FederatedLoginRequest};
+
+use super::connection::{AsyncDbPool, AsyncPooledDbConnection};
+use super::models::{DbFederatedLoginRequest, NewDbFederatedLoginRequest};
+use super::schema::federated_login_requests;
+
+/// Async Diesel-based implementation of AsyncFederatedLoginRequestRepository
+#[derive(Clone)]
+pub struct AsyncDieselFederatedLoginRequestRepository {
+    pool: AsyncDbPool,
+}
+
+impl AsyncDieselFederatedLoginRequestRepository {
+    /// Create a new repository instance
+    #[must_use]
+    pub fn new(pool: AsyncDbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get a connection from the pool
+    async fn conn(&self) -> Result<AsyncPooledDbConnection, AuthError> {
+        self.pool
+            .get()
+            .await
+            .map_err(|e| AuthError::Internal(format!("Failed to get database connection: {}", e)))
+    }
+}
+
+impl AsyncFederatedLoginRequestRepository for AsyncDieselFederatedLoginRequestRepository {
+    fn create<'a>(
+        &'a self,
+        request: &'a FederatedLoginRequest,
+    ) -> BoxFuture<'a, Result<(), AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+
+            let new_request = NewDbFederatedLoginRequest {
+                state_hash: request.state_hash(),
+                provider: request.provider(),
+                nonce: request.nonce(),
+                code_verifier: request.code_verifier(),
+                expires_at: request.expires_at(),
+                created_at: request.created_at(),
+            };
+
+            diesel::insert_into(federated_login_requests::table)
+                .values(&new_request)
+                .execute(&mut conn)
+                .await
+                .map_err(|e| {
+                    AuthError::Internal(format!("Failed to create federated login request: {}", e))
+                })?;
+
+            Ok(())
+        })
+    }
+
+    fn take<'a>(
+        &'a self,
+        state_hash: &'a str,
+    ) -> BoxFuture<'a, Result<Option<FederatedLoginRequest>, AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+
+            // Only one of several concurrent callbacks gets the row
+            let db_request: Option<DbFederatedLoginRequest> = diesel::delete(
+                federated_login_requests::table
+                    .filter(federated_login_requests::state_hash.eq(state_hash)),
+            )
+            .returning(DbFederatedLoginRequest::as_returning())
+            .get_result(&mut conn)
+            .await
+            .optional()
+            .map_err(|e| {
+                AuthError::Internal(format!("Failed to take federated login request: {}", e))
+            })?;
+
+            Ok(db_request.map(|db_request| {
+                FederatedLoginRequest::from_persistence(
+                    db_request.state_hash,
+                    db_request.provider,
+                    db_request.nonce,
+                    db_request.code_verifier,
+                    db_request.expires_at,
+                    db_request.created_at,
+                )
+            }))
+        })
+    }
+}
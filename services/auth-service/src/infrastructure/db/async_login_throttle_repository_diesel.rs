@@ -0,0 +1,145 @@
+//! diesel-async implementation of the AsyncLoginThrottleRepository trait
+
+use chrono::{DateTime, Utc};
+use diesel::{BoolExpressionMethods, ExpressionMethods, OptionalExtension, QueryDsl};
+use diesel_async::scoped_futures::ScopedFutureExt;
+use diesel_async::{AsyncConnection, RunQueryDsl};
+
+use crate::domain::auth::BoxFuture;
+use crate::domain::error::AuthError;
+use crate::domain::lockout::{
+    AsyncLoginThrottleRepository, LockoutPolicy, LoginThrottle, ThrottleKey,
+};
+
+use super::connection::{AsyncDbPool, AsyncPooledDbConnection};
+use super::login_throttle_repository_diesel::db_throttle_to_domain;
+use super::models::{DbLoginThrottle, NewDbLoginThrottle};
+use super::schema::login_throttles;
+
+/// Async Diesel-based implementation of LoginThrottleRepository
+#[derive(Clone)]
+pub struct AsyncDieselLoginThrottleRepository {
+    pool: AsyncDbPool,
+}
+
+impl AsyncDieselLoginThrottleRepository {
+    /// Create a new repository instance
+    #[must_use]
+    pub fn new(pool: AsyncDbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get a connection from the pool
+    async fn conn(&self) -> Result<AsyncPooledDbConnection, AuthError> {
+        self.pool
+            .get()
+            .await
+            .map_err(|e| AuthError::Internal(format!("Failed to get database connection: {}", e)))
+    }
+}
+
+impl AsyncLoginThrottleRepository for AsyncDieselLoginThrottleRepository {
+    fn find<'a>(
+        &'a self,
+        key: &'a ThrottleKey,
+    ) -> BoxFuture<'a, Result<Option<LoginThrottle>, AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+
+            let db_throttle: Option<DbLoginThrottle> = login_throttles::table
+                .find((key.scope().as_str(), key.value()))
+                .first(&mut conn)
+                .await
+                .optional()
+                .map_err(|e| AuthError::Internal(format!("Database error: {}", e)))?;
+
+            Ok(db_throttle.map(|t| db_throttle_to_domain(key, t)))
+        })
+    }
+
+    fn record_failure<'a>(
+        &'a self,
+        key: &'a ThrottleKey,
+        policy: &'a LockoutPolicy,
+        now: DateTime<Utc>,
+    ) -> BoxFuture<'a, Result<LoginThrottle, AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+            let row = (key.scope().as_str(), key.value());
+
+            conn.transaction::<LoginThrottle, diesel::result::Error, _>(|conn| {
+                async move {
+                    // Make sure a row exists, so that concurrent first failures
+                    // serialize on the row lock below instead of overwriting each other
+                    diesel::insert_into(login_throttles::table)
+                        .values(&NewDbLoginThrottle {
+                            scope: row.0,
+                            subject: row.1,
+                            failed_attempts: 0,
+                            last_failed_at: now,
+                        })
+                        .on_conflict_do_nothing()
+                        .execute(conn)
+                        .await?;
+
+                    let current: DbLoginThrottle = login_throttles::table
+                        .find(row)
+                        .for_update()
+                        .first(conn)
+                        .await?;
+                    let mut throttle = db_throttle_to_domain(key, current);
+                    throttle.record_failure(now, policy);
+
+                    diesel::update(login_throttles::table.find(row))
+                        .set((
+                            login_throttles::failed_attempts.eq(throttle.failed_attempts()),
+                            login_throttles::last_failed_at.eq(throttle.last_failed_at()),
+                            login_throttles::locked_until.eq(throttle.locked_until()),
+                        ))
+                        .execute(conn)
+                        .await?;
+
+                    Ok(throttle)
+                }
+                .scope_boxed()
+            })
+            .await
+            .map_err(|e| AuthError::Internal(format!("Failed to record failed login: {}", e)))
+        })
+    }
+
+    fn clear<'a>(&'a self, key: &'a ThrottleKey) -> BoxFuture<'a, Result<bool, AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+
+            let deleted_rows =
+                diesel::delete(login_throttles::table.find((key.scope().as_str(), key.value())))
+                    .execute(&mut conn)
+                    .await
+                    .map_err(|e| {
+                        AuthError::Internal(format!("Failed to clear failed logins: {}", e))
+                    })?;
+
+            Ok(deleted_rows > 0)
+        })
+    }
+
+    fn purge_stale(&self, cutoff: DateTime<Utc>) -> BoxFuture<'_, Result<usize, AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+
+            diesel::delete(
+                login_throttles::table
+                    .filter(login_throttles::last_failed_at.lt(cutoff))
+                    .filter(
+                        login_throttles::locked_until
+                            .is_null()
+                            .or(login_throttles::locked_until.lt(cutoff)),
+                    ),
+            )
+            .execute(&mut conn)
+            .await
+            .map_err(|e| AuthError::Internal(format!("Failed to purge login throttles: {}", e)))
+        })
+    }
+}
@@ -0,0 +1,107 @@
+//! diesel-async implementation of the AsyncMagicLinkTokenRepository trait
+
+use chrono::Utc;
+use diesel::{ExpressionMethods, QueryDsl};
+use diesel_async::scoped_futures::ScopedFutureExt;
+use diesel_async::{AsyncConnection, RunQueryDsl};
+
+use crate::domain::auth::BoxFuture;
+use crate::domain::error::AuthError;
+use crate::domain::magic_link::{AsyncMagicLinkTokenRepository, MagicLinkToken};
+
+use super::connection::{AsyncDbPool, AsyncPooledDbConnection};
+use super::models::DbMagicLinkToken;
+use super::schema::magic_link_tokens;
+
+/// Async Diesel-based implementation of AsyncMagicLinkTokenRepository
+#[derive(Clone)]
+pub struct AsyncDieselMagicLinkTokenRepository {
+    pool: AsyncDbPool,
+}
+
+impl AsyncDieselMagicLinkTokenRepository {
+    /// Create a new repository instance
+    #[must_use]
+    pub fn new(pool: AsyncDbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get a connection from the pool
+    async fn conn(&self) -> Result<AsyncPooledDbConnection, AuthError> {
+        self.pool
+            .get()
+            .await
+            .map_err(|e| AuthError::Internal(format!("Failed to get database connection: {}", e)))
+    }
+}
+
+impl AsyncMagicLinkTokenRepository for AsyncDieselMagicLinkTokenRepository {
+    fn find_by_hash<'a>(
+        &'a self,
+        token_hash: &'a str,
+    ) -> BoxFuture<'a, Result<MagicLinkToken, AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+
+            let db_token: DbMagicLinkToken = magic_link_tokens::table
+                .filter(magic_link_tokens::token_hash.eq(token_hash))
+                .first(&mut conn)
+                .await
+                .map_err(|e| match e {
+                    diesel::result::Error::NotFound => AuthError::InvalidToken,
+                    _ => AuthError::Internal(format!("Database error: {}", e)),
+                })?;
+
+            Ok(MagicLinkToken::from_persistence(
+                db_token.id,
+                db_token.user_id,
+                db_token.token_hash,
+                db_token.expires_at,
+                db_token.created_at,
+                db_token.used_at,
+            ))
+        })
+    }
+
+    fn consume<'a>(&'a self, token: &'a MagicLinkToken) -> BoxFuture<'a, Result<bool, AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+            let now = Utc::now();
+            let (id, user_id) = (token.id(), token.user_id());
+
+            conn.transaction::<bool, diesel::result::Error, _>(|conn| {
+                async move {
+                    // Checking `used_at` in the UPDATE itself makes concurrent
+                    // logins race safely
+                    let updated_rows = diesel::update(
+                        magic_link_tokens::table
+                            .filter(magic_link_tokens::id.eq(id))
+                            .filter(magic_link_tokens::used_at.is_null()),
+                    )
+                    .set(magic_link_tokens::used_at.eq(now))
+                    .execute(conn)
+                    .await?;
+
+                    if updated_rows == 0 {
+                        return Ok(false);
+                    }
+
+                    // Other links still sitting in the inbox stop working too
+                    diesel::update(
+                        magic_link_tokens::table
+                            .filter(magic_link_tokens::user_id.eq(user_id))
+                            .filter(magic_link_tokens::used_at.is_null()),
+                    )
+                    .set(magic_link_tokens::used_at.eq(now))
+                    .execute(conn)
+                    .await?;
+
+                    Ok(true)
+                }
+                .scope_boxed()
+            })
+            .await
+            .map_err(|e| AuthError::Internal(format!("Failed to consume magic link: {}", e)))
+        })
+    }
+}
@@ -0,0 +1,151 @@
+//! diesel-async implementation of the AsyncMfaChallengeRepository trait
+
+use chrono::Utc;
+use diesel::{ExpressionMethods, QueryDsl};
+use diesel_async::scoped_futures::ScopedFutureExt;
+use diesel_async::{AsyncConnection, RunQueryDsl};
+
+use crate::domain::auth::BoxFuture;
+use crate::domain::error::AuthError;
+use crate::domain::mfa::{AsyncMfaChallengeRepository, MfaChallenge, MAX_MFA_ATTEMPTS};
+
+use super::connection::{AsyncDbPool, AsyncPooledDbConnection};
+use super::models::{DbMfaChallenge, NewDbMfaChallenge};
+use super::schema::mfa_challenges;
+
+/// Async Diesel-based implementation of AsyncMfaChallengeRepository
+#[derive(Clone)]
+pub struct AsyncDieselMfaChallengeRepository {
+    pool: AsyncDbPool,
+}
+
+impl AsyncDieselMfaChallengeRepository {
+    /// Create a new repository instance
+    #[must_use]
+    pub fn new(pool: AsyncDbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get a connection from the pool
+    async fn conn(&self) -> Result<AsyncPooledDbConnection, AuthError> {
+        self.pool
+            .get()
+            .await
+            .map_err(|e| AuthError::Internal(format!("Failed to get database connection: {}", e)))
+    }
+}
+
+/// Convert domain entity to insertable model
+fn to_new_db_challenge(challenge: &MfaChallenge) -> NewDbMfaChallenge<'_> {
+    NewDbMfaChallenge {
+        id: challenge.id(),
+        user_id: challenge.user_id(),
+        token_hash: challenge.token_hash(),
+        expires_at: challenge.expires_at(),
+        created_at: challenge.created_at(),
+    }
+}
+
+impl AsyncMfaChallengeRepository for AsyncDieselMfaChallengeRepository {
+    fn create<'a>(&'a self, challenge: &'a MfaChallenge) -> BoxFuture<'a, Result<(), AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+
+            diesel::insert_into(mfa_challenges::table)
+                .values(&to_new_db_challenge(challenge))
+                .execute(&mut conn)
+                .await
+                .map_err(|e| {
+                    AuthError::Internal(format!("Failed to create MFA challenge: {}", e))
+                })?;
+
+            Ok(())
+        })
+    }
+
+    fn find_by_hash<'a>(
+        &'a self,
+        token_hash: &'a str,
+    ) -> BoxFuture<'a, Result<MfaChallenge, AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+
+            let db_challenge: DbMfaChallenge = mfa_challenges::table
+                .filter(mfa_challenges::token_hash.eq(token_hash))
+                .first(&mut conn)
+                .await
+                .map_err(|e| match e {
+                    diesel::result::Error::NotFound => AuthError::InvalidToken,
+                    _ => AuthError::Internal(format!("Database error: {}", e)),
+                })?;
+
+            Ok(MfaChallenge::from_persistence(
+                db_challenge.id,
+                db_challenge.user_id,
+                db_challenge.token_hash,
+                db_challenge.failed_attempts,
+                db_challenge.expires_at,
+                db_challenge.created_at,
+                db_challenge.used_at,
+            ))
+        })
+    }
+
+    fn record_failed_attempt<'a>(
+        &'a self,
+        challenge: &'a MfaChallenge,
+    ) -> BoxFuture<'a, Result<i32, AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+            let id = challenge.id();
+
+            conn.transaction::<i32, diesel::result::Error, _>(|conn| {
+                async move {
+                    let attempts: i32 =
+                        diesel::update(mfa_challenges::table.filter(mfa_challenges::id.eq(id)))
+                            .set(
+                                mfa_challenges::failed_attempts
+                                    .eq(mfa_challenges::failed_attempts + 1),
+                            )
+                            .returning(mfa_challenges::failed_attempts)
+                            .get_result(conn)
+                            .await?;
+
+                    if attempts >= MAX_MFA_ATTEMPTS {
+                        diesel::update(
+                            mfa_challenges::table
+                                .filter(mfa_challenges::id.eq(id))
+                                .filter(mfa_challenges::used_at.is_null()),
+                        )
+                        .set(mfa_challenges::used_at.eq(Utc::now()))
+                        .execute(conn)
+                        .await?;
+                    }
+
+                    Ok(attempts)
+                }
+                .scope_boxed()
+            })
+            .await
+            .map_err(|e| AuthError::Internal(format!("Failed to record MFA attempt: {}", e)))
+        })
+    }
+
+    fn consume<'a>(&'a self, challenge: &'a MfaChallenge) -> BoxFuture<'a, Result<bool, AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+
+            let updated_rows = diesel::update(
+                mfa_challenges::table
+                    .filter(mfa_challenges::id.eq(challenge.id()))
+                    .filter(mfa_challenges::used_at.is_null()),
+            )
+            .set(mfa_challenges::used_at.eq(Utc::now()))
+            .execute(&mut conn)
+            .await
+            .map_err(|e| AuthError::Internal(format!("Failed to consume MFA challenge: {}", e)))?;
+
+            Ok(updated_rows > 0)
+        })
+    }
+}
@@ -0,0 +1,122 @@
+//! diesel-async implementation of the AsyncMfaRepository trait
+
+use chrono::Utc;
+use diesel::{BoolExpressionMethods, ExpressionMethods, OptionalExtension, QueryDsl};
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+use crate::domain::auth::BoxFuture;
+use crate::domain::error::AuthError;
+use crate::domain::mfa::{AsyncMfaRepository, TotpCredential};
+
+use super::connection::{AsyncDbPool, AsyncPooledDbConnection};
+use super::mfa_repository_diesel::db_totp_to_domain;
+use super::models::DbTotpCredential;
+use super::schema::{mfa_recovery_codes, totp_credentials};
+
+/// Async Diesel-based implementation of AsyncMfaRepository
+#[derive(Clone)]
+pub struct AsyncDieselMfaRepository {
+    pool: AsyncDbPool,
+}
+
+impl AsyncDieselMfaRepository {
+    /// Create a new repository instance
+    #[must_use]
+    pub fn new(pool: AsyncDbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get a connection from the pool
+    async fn conn(&self) -> Result<AsyncPooledDbConnection, AuthError> {
+        self.pool
+            .get()
+            .await
+            .map_err(|e| AuthError::Internal(format!("Failed to get database connection: {}", e)))
+    }
+}
+
+impl AsyncMfaRepository for AsyncDieselMfaRepository {
+    fn find_totp(&self, user_id: Uuid) -> BoxFuture<'_, Result<Option<TotpCredential>, AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+
+            let db_credential: Option<DbTotpCredential> = totp_credentials::table
+                .find(user_id)
+                .first(&mut conn)
+                .await
+                .optional()
+                .map_err(|e| AuthError::Internal(format!("Database error: {}", e)))?;
+
+            Ok(db_credential.map(db_totp_to_domain))
+        })
+    }
+
+    fn record_totp_step(&self, user_id: Uuid, step: i64) -> BoxFuture<'_, Result<bool, AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+
+            // Comparing in the UPDATE itself makes concurrent logins race safely
+            let updated_rows = diesel::update(
+                totp_credentials::table
+                    .filter(totp_credentials::user_id.eq(user_id))
+                    .filter(totp_credentials::confirmed_at.is_not_null())
+                    .filter(
+                        totp_credentials::last_used_step
+                            .is_null()
+                            .or(totp_credentials::last_used_step.lt(step)),
+                    ),
+            )
+            .set(totp_credentials::last_used_step.eq(step))
+            .execute(&mut conn)
+            .await
+            .map_err(|e| AuthError::Internal(format!("Failed to record TOTP step: {}", e)))?;
+
+            Ok(updated_rows == 1)
+        })
+    }
+
+    fn has_recovery_code<'a>(
+        &'a self,
+        user_id: Uuid,
+        code_hash: &'a str,
+    ) -> BoxFuture<'a, Result<bool, AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+
+            let count: i64 = mfa_recovery_codes::table
+                .filter(mfa_recovery_codes::user_id.eq(user_id))
+                .filter(mfa_recovery_codes::code_hash.eq(code_hash))
+                .filter(mfa_recovery_codes::used_at.is_null())
+                .count()
+                .get_result(&mut conn)
+                .await
+                .map_err(|e| AuthError::Internal(format!("Database error: {}", e)))?;
+
+            Ok(count > 0)
+        })
+    }
+
+    fn consume_recovery_code<'a>(
+        &'a self,
+        user_id: Uuid,
+        code_hash: &'a str,
+    ) -> BoxFuture<'a, Result<bool, AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+
+            let updated_rows = diesel::update(
+                mfa_recovery_codes::table
+                    .filter(mfa_recovery_codes::user_id.eq(user_id))
+                    .filter(mfa_recovery_codes::code_hash.eq(code_hash))
+                    .filter(mfa_recovery_codes::used_at.is_null()),
+            )
+            .set(mfa_recovery_codes::used_at.eq(Utc::now()))
+            .execute(&mut conn)
+            .await
+            .map_err(|e| AuthError::Internal(format!("Failed to consume recovery code: {}", e)))?;
+
+            Ok(updated_rows > 0)
+        })
+    }
+}
@@ -0,0 +1,57 @@
+//! diesel-async implementation of the AsyncOrganizationRepository trait
+
+use diesel::{OptionalExtension, QueryDsl};
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+use crate::domain::auth::BoxFuture;
+use crate::domain::error::AuthError;
+use crate::domain::organization::{AsyncOrganizationRepository, Membership};
+
+use super::connection::{AsyncDbPool, AsyncPooledDbConnection};
+use super::models::DbOrganizationMembership;
+use super::organization_repository_diesel::membership;
+use super::schema::organization_memberships;
+
+/// Async Diesel-based lookup of organization memberships
+#[derive(Clone)]
+pub struct AsyncDieselOrganizationRepository {
+    pool: AsyncDbPool,
+}
+
+impl AsyncDieselOrganizationRepository {
+    /// Create a new repository instance
+    #[must_use]
+    pub fn new(pool: AsyncDbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get a connection from the pool
+    async fn conn(&self) -> Result<AsyncPooledDbConnection, AuthError> {
+        self.pool
+            .get()
+            .await
+            .map_err(|e| AuthError::Internal(format!("Failed to get database connection: {}", e)))
+    }
+}
+
+impl AsyncOrganizationRepository for AsyncDieselOrganizationRepository {
+    fn find_membership(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+    ) -> BoxFuture<'_, Result<Option<Membership>, AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+
+            let db_membership: Option<DbOrganizationMembership> = organization_memberships::table
+                .find((organization_id, user_id))
+                .first(&mut conn)
+                .await
+                .optional()
+                .map_err(|e| AuthError::Internal(format!("Database error: {}", e)))?;
+
+            db_membership.map(membership).transpose()
+        })
+    }
+}
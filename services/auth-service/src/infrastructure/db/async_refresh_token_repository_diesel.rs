@@ -0,0 +1,184 @@
+//! diesel-async implementation of the AsyncRefreshTokenRepository trait
+
+use chrono::Utc;
+use diesel::{ExpressionMethods, QueryDsl};
+use diesel_async::scoped_futures::ScopedFutureExt;
+use diesel_async::{AsyncConnection, RunQueryDsl};
+use uuid::Uuid;
+
+use crate::domain::auth::BoxFuture;
+use crate::domain::error::AuthError;
+use crate::domain::refresh_token::{AsyncRefreshTokenRepository, RefreshToken};
+
+use super::connection::{AsyncDbPool, AsyncPooledDbConnection};
+use super::models::DbRefreshToken;
+use super::refresh_token_repository_diesel::{db_token_to_domain, to_new_db_token};
+use super::schema::{refresh_tokens, sessions};
+
+/// Async Diesel-based implementation of RefreshTokenRepository
+#[derive(Clone)]
+pub struct AsyncDieselRefreshTokenRepository {
+    pool: AsyncDbPool,
+}
+
+impl AsyncDieselRefreshTokenRepository {
+    /// Create a new repository instance
+    #[must_use]
+    pub fn new(pool: AsyncDbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get a connection from the pool
+    async fn conn(&self) -> Result<AsyncPooledDbConnection, AuthError> {
+        self.pool
+            .get()
+            .await
+            .map_err(|e| AuthError::Internal(format!("Failed to get database connection: {}", e)))
+    }
+}
+
+impl AsyncRefreshTokenRepository for AsyncDieselRefreshTokenRepository {
+    fn create<'a>(&'a self, token: &'a RefreshToken) -> BoxFuture<'a, Result<(), AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+
+            diesel::insert_into(refresh_tokens::table)
+                .values(&to_new_db_token(token))
+                .execute(&mut conn)
+                .await
+                .map_err(|e| {
+                    AuthError::Internal(format!("Failed to create refresh token: {}", e))
+                })?;
+
+            Ok(())
+        })
+    }
+
+    fn find_by_hash<'a>(
+        &'a self,
+        token_hash: &'a str,
+    ) -> BoxFuture<'a, Result<RefreshToken, AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+
+            let db_token: DbRefreshToken = refresh_tokens::table
+                .filter(refresh_tokens::token_hash.eq(token_hash))
+                .first(&mut conn)
+                .await
+                .map_err(|e| match e {
+                    diesel::result::Error::NotFound => AuthError::InvalidToken,
+                    _ => AuthError::Internal(format!("Database error: {}", e)),
+                })?;
+
+            Ok(db_token_to_domain(db_token))
+        })
+    }
+
+    fn rotate<'a>(
+        &'a self,
+        current_id: Uuid,
+        next: &'a RefreshToken,
+    ) -> BoxFuture<'a, Result<bool, AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+
+            conn.transaction::<bool, diesel::result::Error, _>(|conn| {
+                async move {
+                    // Only an unrotated, unrevoked token may be exchanged. Checking this
+                    // in the UPDATE itself makes concurrent refreshes race safely.
+                    let updated_rows = diesel::update(
+                        refresh_tokens::table
+                            .filter(refresh_tokens::id.eq(current_id))
+                            .filter(refresh_tokens::rotated_at.is_null())
+                            .filter(refresh_tokens::revoked_at.is_null()),
+                    )
+                    .set(refresh_tokens::rotated_at.eq(Utc::now()))
+                    .execute(conn)
+                    .await?;
+
+                    if updated_rows == 0 {
+                        return Ok(false);
+                    }
+
+                    diesel::insert_into(refresh_tokens::table)
+                        .values(&to_new_db_token(next))
+                        .execute(conn)
+                        .await?;
+
+                    Ok(true)
+                }
+                .scope_boxed()
+            })
+            .await
+            .map_err(|e| AuthError::Internal(format!("Failed to rotate refresh token: {}", e)))
+        })
+    }
+
+    fn revoke_family(&self, family_id: Uuid) -> BoxFuture<'_, Result<(), AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+            let now = Utc::now();
+
+            conn.transaction::<(), diesel::result::Error, _>(|conn| {
+                async move {
+                    diesel::update(
+                        refresh_tokens::table
+                            .filter(refresh_tokens::family_id.eq(family_id))
+                            .filter(refresh_tokens::revoked_at.is_null()),
+                    )
+                    .set(refresh_tokens::revoked_at.eq(now))
+                    .execute(conn)
+                    .await?;
+
+                    diesel::update(
+                        sessions::table
+                            .filter(sessions::id.eq(family_id))
+                            .filter(sessions::revoked_at.is_null()),
+                    )
+                    .set(sessions::revoked_at.eq(now))
+                    .execute(conn)
+                    .await?;
+
+                    Ok(())
+                }
+                .scope_boxed()
+            })
+            .await
+            .map_err(|e| AuthError::Internal(format!("Failed to revoke refresh tokens: {}", e)))
+        })
+    }
+
+    fn revoke_all_for_user(&self, user_id: Uuid) -> BoxFuture<'_, Result<(), AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+            let now = Utc::now();
+
+            conn.transaction::<(), diesel::result::Error, _>(|conn| {
+                async move {
+                    diesel::update(
+                        refresh_tokens::table
+                            .filter(refresh_tokens::user_id.eq(user_id))
+                            .filter(refresh_tokens::revoked_at.is_null()),
+                    )
+                    .set(refresh_tokens::revoked_at.eq(now))
+                    .execute(conn)
+                    .await?;
+
+                    diesel::update(
+                        sessions::table
+                            .filter(sessions::user_id.eq(user_id))
+                            .filter(sessions::revoked_at.is_null()),
+                    )
+                    .set(sessions::revoked_at.eq(now))
+                    .execute(conn)
+                    .await?;
+
+                    Ok(())
+                }
+                .scope_boxed()
+            })
+            .await
+            .map_err(|e| AuthError::Internal(format!("Failed to revoke refresh tokens: {}", e)))
+        })
+    }
+}
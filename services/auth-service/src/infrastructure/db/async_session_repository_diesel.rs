@@ -0,0 +1,161 @@
+//! diesel-async implementation of the AsyncSessionRepository trait
+
+use chrono::{DateTime, Utc};
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl};
+use diesel_async::scoped_futures::ScopedFutureExt;
+use diesel_async::{AsyncConnection, RunQueryDsl};
+use uuid::Uuid;
+
+use crate::domain::auth::BoxFuture;
+use crate::domain::error::AuthError;
+use crate::domain::session::{AsyncSessionRepository, Session};
+
+use super::connection::{AsyncDbPool, AsyncPooledDbConnection};
+use super::models::DbSession;
+use super::outbox_repository_diesel::to_new_db_outbox_events;
+use super::schema::{outbox, sessions};
+use super::session_repository_diesel::{db_session_to_domain, to_new_db_session};
+
+/// Async Diesel-based implementation of SessionRepository
+#[derive(Clone)]
+pub struct AsyncDieselSessionRepository {
+    pool: AsyncDbPool,
+}
+
+impl AsyncDieselSessionRepository {
+    /// Create a new repository instance
+    #[must_use]
+    pub fn new(pool: AsyncDbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get a connection from the pool
+    async fn conn(&self) -> Result<AsyncPooledDbConnection, AuthError> {
+        self.pool
+            .get()
+            .await
+            .map_err(|e| AuthError::Internal(format!("Failed to get database connection: {}", e)))
+    }
+}
+
+impl AsyncSessionRepository for AsyncDieselSessionRepository {
+    fn create<'a>(&'a self, session: &'a Session) -> BoxFuture<'a, Result<(), AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+            let new_session = to_new_db_session(session);
+            let events = to_new_db_outbox_events(&[session.login_event()]);
+
+            conn.transaction::<(), diesel::result::Error, _>(|conn| {
+                async move {
+                    diesel::insert_into(sessions::table)
+                        .values(&new_session)
+                        .execute(conn)
+                        .await?;
+                    diesel::insert_into(outbox::table)
+                        .values(&events)
+                        .execute(conn)
+                        .await?;
+                    Ok(())
+                }
+                .scope_boxed()
+            })
+            .await
+            .map_err(|e| AuthError::Internal(format!("Failed to create session: {}", e)))
+        })
+    }
+
+    fn find_by_id(&self, id: Uuid) -> BoxFuture<'_, Result<Session, AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+
+            let db_session: DbSession = sessions::table
+                .find(id)
+                .first(&mut conn)
+                .await
+                .map_err(|e| match e {
+                    diesel::result::Error::NotFound => AuthError::SessionNotFound,
+                    _ => AuthError::Internal(format!("Database error: {}", e)),
+                })?;
+
+            Ok(db_session_to_domain(db_session))
+        })
+    }
+
+    fn find_active_for_user(
+        &self,
+        user_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> BoxFuture<'_, Result<Vec<Session>, AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+
+            let db_sessions: Vec<DbSession> = sessions::table
+                .filter(sessions::user_id.eq(user_id))
+                .filter(sessions::revoked_at.is_null())
+                .filter(sessions::expires_at.gt(now))
+                .order(sessions::last_seen_at.desc())
+                .load(&mut conn)
+                .await
+                .map_err(|e| AuthError::Internal(format!("Database error: {}", e)))?;
+
+            Ok(db_sessions.into_iter().map(db_session_to_domain).collect())
+        })
+    }
+
+    fn touch(
+        &self,
+        id: Uuid,
+        now: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> BoxFuture<'_, Result<(), AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+
+            diesel::update(sessions::table.find(id))
+                .set((
+                    sessions::last_seen_at.eq(now),
+                    sessions::expires_at.eq(expires_at),
+                ))
+                .execute(&mut conn)
+                .await
+                .map_err(|e| AuthError::Internal(format!("Failed to update session: {}", e)))?;
+
+            Ok(())
+        })
+    }
+
+    fn set_organization(
+        &self,
+        id: Uuid,
+        organization_id: Option<Uuid>,
+    ) -> BoxFuture<'_, Result<(), AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+
+            diesel::update(sessions::table.find(id))
+                .set(sessions::organization_id.eq(organization_id))
+                .execute(&mut conn)
+                .await
+                .map_err(|e| AuthError::Internal(format!("Failed to update session: {}", e)))?;
+
+            Ok(())
+        })
+    }
+
+    fn is_revoked(&self, id: Uuid) -> BoxFuture<'_, Result<bool, AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+
+            let revoked_at: Option<Option<DateTime<Utc>>> = sessions::table
+                .find(id)
+                .select(sessions::revoked_at)
+                .first(&mut conn)
+                .await
+                .optional()
+                .map_err(|e| AuthError::Internal(format!("Database error: {}", e)))?;
+
+            // Only a stored, unrevoked session is live; deleted ones count as ended
+            Ok(!matches!(revoked_at, Some(None)))
+        })
+    }
+}
@@ -0,0 +1,93 @@
+//! diesel-async implementation of the AsyncTokenRevocationStore trait
+
+use chrono::{DateTime, Utc};
+use diesel::{ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+use crate::domain::auth::BoxFuture;
+use crate::domain::error::AuthError;
+use crate::domain::token_revocation::AsyncTokenRevocationStore;
+
+use super::connection::{AsyncDbPool, AsyncPooledDbConnection};
+use super::models::NewDbRevokedToken;
+use super::schema::revoked_tokens;
+
+/// Async access to the Postgres-backed revocation list shared by all replicas
+#[derive(Clone)]
+pub struct AsyncDieselTokenRevocationStore {
+    pool: AsyncDbPool,
+}
+
+impl AsyncDieselTokenRevocationStore {
+    /// Create a new store instance
+    #[must_use]
+    pub fn new(pool: AsyncDbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get a connection from the pool
+    async fn conn(&self) -> Result<AsyncPooledDbConnection, AuthError> {
+        self.pool
+            .get()
+            .await
+            .map_err(|e| AuthError::Internal(format!("Failed to get database connection: {}", e)))
+    }
+}
+
+impl AsyncTokenRevocationStore for AsyncDieselTokenRevocationStore {
+    fn revoke<'a>(
+        &'a self,
+        jti: &'a str,
+        user_id: Uuid,
+        expires_at: DateTime<Utc>,
+    ) -> BoxFuture<'a, Result<(), AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+
+            let new_entry = NewDbRevokedToken {
+                jti,
+                user_id,
+                expires_at,
+                revoked_at: Utc::now(),
+            };
+
+            diesel::insert_into(revoked_tokens::table)
+                .values(&new_entry)
+                .on_conflict_do_nothing()
+                .execute(&mut conn)
+                .await
+                .map_err(|e| AuthError::Internal(format!("Failed to revoke token: {}", e)))?;
+
+            Ok(())
+        })
+    }
+
+    fn is_revoked<'a>(&'a self, jti: &'a str) -> BoxFuture<'a, Result<bool, AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+
+            let count: i64 = revoked_tokens::table
+                .filter(revoked_tokens::jti.eq(jti))
+                .count()
+                .get_result(&mut conn)
+                .await
+                .map_err(|e| AuthError::Internal(format!("Database error: {}", e)))?;
+
+            Ok(count > 0)
+        })
+    }
+
+    fn purge_expired(&self, now: DateTime<Utc>) -> BoxFuture<'_, Result<usize, AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+
+            diesel::delete(revoked_tokens::table.filter(revoked_tokens::expires_at.lt(now)))
+                .execute(&mut conn)
+                .await
+                .map_err(|e| {
+                    AuthError::Internal(format!("Failed to purge revoked tokens: {}", e))
+                })
+        })
+    }
+}
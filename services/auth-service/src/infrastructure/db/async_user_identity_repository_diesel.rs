@@ -0,0 +1,152 @@
+//! diesel-async implementation of the AsyncUserIdentityRepository trait
+
+use chrono::{DateTime, Utc};
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, SelectableHelper};
+use diesel_async::scoped_futures::ScopedFutureExt;
+use diesel_async::{AsyncConnection, RunQueryDsl};
+
+use crate::domain::auth::BoxFuture;
+use crate::domain::error::AuthError;
+use crate::domain::federation::{AsyncUserIdentityRepository, UserIdentity};
+use crate::domain::user::User;
+
+use super::async_user_repository_diesel::insert_user;
+use super::connection::{AsyncDbPool, AsyncPooledDbConnection};
+use super::models::{DbUserIdentity, NewDbUserIdentity};
+use super::schema::user_identities;
+
+/// Async Diesel-based implementation of AsyncUserIdentityRepository
+#[derive(Clone)]
+pub struct AsyncDieselUserIdentityRepository {
+    pool: AsyncDbPool,
+}
+
+impl AsyncDieselUserIdentityRepository {
+    /// Create a new repository instance
+    #[must_use]
+    pub fn new(pool: AsyncDbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get a connection from the pool
+    async fn conn(&self) -> Result<AsyncPooledDbConnection, AuthError> {
+        self.pool
+            .get()
+            .await
+            .map_err(|e| AuthError::Internal(format!("Failed to get database connection: {}", e)))
+    }
+}
+
+impl AsyncUserIdentityRepository for AsyncDieselUserIdentityRepository {
+    fn find<'a>(
+        &'a self,
+        provider: &'a str,
+        subject: &'a str,
+    ) -> BoxFuture<'a, Result<Option<UserIdentity>, AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+
+            let db_identity: Option<DbUserIdentity> = user_identities::table
+                .find((provider, subject))
+                .select(DbUserIdentity::as_select())
+                .first(&mut conn)
+                .await
+                .optional()
+                .map_err(|e| AuthError::Internal(format!("Database error: {}", e)))?;
+
+            Ok(db_identity.map(db_identity_to_domain))
+        })
+    }
+
+    fn create<'a>(&'a self, identity: &'a UserIdentity) -> BoxFuture<'a, Result<(), AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+
+            diesel::insert_into(user_identities::table)
+                .values(&to_new_db_identity(identity))
+                .execute(&mut conn)
+                .await
+                .map_err(|e| AuthError::Internal(format!("Failed to link identity: {}", e)))?;
+
+            Ok(())
+        })
+    }
+
+    fn create_with_user<'a>(
+        &'a self,
+        user: &'a User,
+        identity: &'a UserIdentity,
+    ) -> BoxFuture<'a, Result<(), AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+
+            conn.transaction::<(), diesel::result::Error, _>(|conn| {
+                async move {
+                    insert_user(conn, user).await?;
+                    diesel::insert_into(user_identities::table)
+                        .values(&to_new_db_identity(identity))
+                        .execute(conn)
+                        .await?;
+                    Ok(())
+                }
+                .scope_boxed()
+            })
+            .await
+            .map_err(|e| {
+                // A concurrent first sign-in with the same email or upstream account won
+                if let diesel::result::Error::DatabaseError(
+                    diesel::result::DatabaseErrorKind::UniqueViolation,
+                    _,
+                ) = e
+                {
+                    AuthError::UserAlreadyExists
+                } else {
+                    AuthError::Internal(format!("Failed to create user: {}", e))
+                }
+            })
+        })
+    }
+
+    fn record_login<'a>(
+        &'a self,
+        provider: &'a str,
+        subject: &'a str,
+        at: DateTime<Utc>,
+    ) -> BoxFuture<'a, Result<(), AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+
+            diesel::update(user_identities::table.find((provider, subject)))
+                .set(user_identities::last_login_at.eq(at))
+                .execute(&mut conn)
+                .await
+                .map_err(|e| AuthError::Internal(format!("Failed to record login: {}", e)))?;
+
+            Ok(())
+        })
+    }
+}
+
+/// Build an identity row for insertion
+fn to_new_db_identity(identity: &UserIdentity) -> NewDbUserIdentity<'_> {
+    NewDbUserIdentity {
+        provider: identity.provider(),
+        subject: identity.subject(),
+        user_id: identity.user_id(),
+        email: identity.email(),
+        created_at: identity.created_at(),
+        last_login_at: identity.last_login_at(),
+    }
+}
+
+/// Convert database model to domain entity
+pub(super) fn db_identity_to_domain(db_identity: DbUserIdentity) -> UserIdentity {
+    UserIdentity::from_persistence(
+        db_identity.provider,
+        db_identity.subject,
+        db_identity.user_id,
+        db_identity.email,
+        db_identity.created_at,
+        db_identity.last_login_at,
+    )
+}
@@ -0,0 +1,260 @@
+//! diesel-async implementation of the AsyncUserRepository trait
+//!
+//! Shares its models and row conversions with the blocking
+//! `DieselUserRepository`; both can be used against the same database.
+
+use diesel::{ExpressionMethods, QueryDsl};
+use diesel_async::scoped_futures::ScopedFutureExt;
+use diesel_async::{AsyncConnection, AsyncPgConnection, RunQueryDsl};
+use uuid::Uuid;
+
+use crate::domain::auth::{AsyncUserRepository, BoxFuture};
+use crate::domain::error::AuthError;
+use crate::domain::role::Role;
+use crate::domain::user::{HashedPassword, User};
+
+use super::connection::{AsyncDbPool, AsyncPooledDbConnection};
+use super::models::DbUser;
+use super::outbox_repository_diesel::to_new_db_outbox_events;
+use super::schema::{outbox, user_roles, users};
+use super::user_repository_diesel::{
+    db_user_to_domain, parse_role, to_new_db_roles, to_new_db_user,
+};
+
+/// Async Diesel-based implementation of UserRepository
+#[derive(Clone)]
+pub struct AsyncDieselUserRepository {
+    pool: AsyncDbPool,
+}
+
+impl AsyncDieselUserRepository {
+    /// Create a new repository instance
+    #[must_use]
+    pub fn new(pool: AsyncDbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get a connection from the pool
+    async fn conn(&self) -> Result<AsyncPooledDbConnection, AuthError> {
+        self.pool
+            .get()
+            .await
+            .map_err(|e| AuthError::Internal(format!("Failed to get database connection: {}", e)))
+    }
+
+    /// Load a user's roles and attach them to the domain entity
+    async fn with_roles(
+        &self,
+        conn: &mut AsyncPgConnection,
+        db_user: DbUser,
+    ) -> Result<User, AuthError> {
+        let names: Vec<String> = user_roles::table
+            .filter(user_roles::user_id.eq(db_user.id))
+            .select(user_roles::role)
+            .load(conn)
+            .await
+            .map_err(|e| AuthError::Internal(format!("Database error: {}", e)))?;
+
+        let roles = names
+            .iter()
+            .map(|name| parse_role(name))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(db_user_to_domain(db_user).with_roles(roles))
+    }
+}
+
+impl AsyncUserRepository for AsyncDieselUserRepository {
+    fn find_by_id(&self, id: Uuid) -> BoxFuture<'_, Result<User, AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+
+            let db_user: DbUser = users::table
+                .filter(users::id.eq(id))
+                .first(&mut conn)
+                .await
+                .map_err(|e| match e {
+                    diesel::result::Error::NotFound => AuthError::UserNotFound,
+                    _ => AuthError::Internal(format!("Database error: {}", e)),
+                })?;
+
+            self.with_roles(&mut conn, db_user).await
+        })
+    }
+
+    fn find_by_email<'a>(&'a self, email: &'a str) -> BoxFuture<'a, Result<User, AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+            let normalized_email = email.trim().to_lowercase();
+
+            let db_user: DbUser = users::table
+                .filter(users::email.eq(&normalized_email))
+                .first(&mut conn)
+                .await
+                .map_err(|e| match e {
+                    diesel::result::Error::NotFound => AuthError::UserNotFound,
+                    _ => AuthError::Internal(format!("Database error: {}", e)),
+                })?;
+
+            self.with_roles(&mut conn, db_user).await
+        })
+    }
+
+    fn exists_by_email<'a>(&'a self, email: &'a str) -> BoxFuture<'a, Result<bool, AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+            let normalized_email = email.trim().to_lowercase();
+
+            let count: i64 = users::table
+                .filter(users::email.eq(&normalized_email))
+                .count()
+                .get_result(&mut conn)
+                .await
+                .map_err(|e| AuthError::Internal(format!("Database error: {}", e)))?;
+
+            Ok(count > 0)
+        })
+    }
+
+    fn create<'a>(&'a self, user: &'a User) -> BoxFuture<'a, Result<User, AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+
+            conn.transaction::<(), diesel::result::Error, _>(|conn| {
+                async move { insert_user(conn, user).await }.scope_boxed()
+            })
+            .await
+            .map_err(|e| {
+                if let diesel::result::Error::DatabaseError(
+                    diesel::result::DatabaseErrorKind::UniqueViolation,
+                    _,
+                ) = e
+                {
+                    AuthError::UserAlreadyExists
+                } else {
+                    AuthError::Internal(format!("Failed to create user: {}", e))
+                }
+            })?;
+
+            // Return the user as created
+            self.find_by_id(user.id().as_uuid()).await
+        })
+    }
+
+    fn update<'a>(&'a self, user: &'a User) -> BoxFuture<'a, Result<User, AuthError>> {
+        Box::pin(async move {
+            let mut conn = self.conn().await?;
+
+            let user_id = user.id().as_uuid();
+            let role_names: Vec<&str> = user.roles().iter().map(Role::as_str).collect();
+
+            let updated_rows = conn
+                .transaction::<usize, diesel::result::Error, _>(|conn| {
+                    async move {
+                        let updated_rows =
+                            diesel::update(users::table.filter(users::id.eq(user_id)))
+                                .set((
+                                    users::email.eq(user.email().as_str()),
+                                    users::hashed_password
+                                        .eq(user.hashed_password().map(HashedPassword::as_str)),
+                                    users::display_name.eq(user.display_name()),
+                                    users::is_active.eq(user.is_active()),
+                                    users::email_verified_at.eq(user.email_verified_at()),
+                                    users::updated_at.eq(user.updated_at()),
+                                ))
+                                .execute(conn)
+                                .await?;
+
+                        if updated_rows == 0 {
+                            return Ok(0);
+                        }
+
+                        // Sync roles without touching the grant time of kept roles
+                        diesel::delete(
+                            user_roles::table
+                                .filter(user_roles::user_id.eq(user_id))
+                                .filter(user_roles::role.ne_all(&role_names)),
+                        )
+                        .execute(conn)
+                        .await?;
+
+                        let roles = to_new_db_roles(user);
+                        if !roles.is_empty() {
+                            diesel::insert_into(user_roles::table)
+                                .values(&roles)
+                                .on_conflict_do_nothing()
+                                .execute(conn)
+                                .await?;
+                        }
+
+                        insert_events(conn, user).await?;
+
+                        Ok(updated_rows)
+                    }
+                    .scope_boxed()
+                })
+                .await
+                .map_err(|e| {
+                    // Another account took the new email address first
+                    if let diesel::result::Error::DatabaseError(
+                        diesel::result::DatabaseErrorKind::UniqueViolation,
+                        _,
+                    ) = e
+                    {
+                        AuthError::UserAlreadyExists
+                    } else {
+                        AuthError::Internal(format!("Failed to update user: {}", e))
+                    }
+                })?;
+
+            if updated_rows == 0 {
+                return Err(AuthError::UserNotFound);
+            }
+
+            self.find_by_id(user_id).await
+        })
+    }
+}
+
+/// Insert a user, their roles and the events they raised
+///
+/// Meant to run inside a transaction.
+pub(super) async fn insert_user(
+    conn: &mut AsyncPgConnection,
+    user: &User,
+) -> Result<(), diesel::result::Error> {
+    diesel::insert_into(users::table)
+        .values(&to_new_db_user(user))
+        .execute(conn)
+        .await?;
+
+    let roles = to_new_db_roles(user);
+    if !roles.is_empty() {
+        diesel::insert_into(user_roles::table)
+            .values(&roles)
+            .execute(conn)
+            .await?;
+    }
+
+    insert_events(conn, user).await
+}
+
+/// Store the events a user raised in the outbox
+///
+/// Meant to run inside the transaction that writes the user.
+async fn insert_events(
+    conn: &mut AsyncPgConnection,
+    user: &User,
+) -> Result<(), diesel::result::Error> {
+    let events = user.pending_events();
+    if events.is_empty() {
+        return Ok(());
+    }
+
+    diesel::insert_into(outbox::table)
+        .values(&to_new_db_outbox_events(events))
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,179 @@
+//! Diesel implementation of the AuditLog and AuditEventRepository traits
+
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use tracing::warn;
+
+use crate::domain::audit::{
+    AuditAction, AuditCursor, AuditEvent, AuditEventRepository, AuditFilter, AuditLog,
+    AuditOutcome, AuditPage,
+};
+use crate::domain::error::AuthError;
+
+use super::connection::DbPool;
+use super::models::{DbAuditEvent, NewDbAuditEvent};
+use super::schema::auth_audit_events;
+
+/// Diesel-based implementation of AuditLog and AuditEventRepository
+pub struct DieselAuditEventRepository {
+    pool: DbPool,
+}
+
+impl DieselAuditEventRepository {
+    /// Create a new repository instance
+    #[must_use]
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get a connection from the pool
+    fn conn(&self) -> Result<super::connection::PooledDbConnection, AuthError> {
+        self.pool
+            .get()
+            .map_err(|e| AuthError::Internal(format!("Failed to get database connection: {}", e)))
+    }
+
+    /// Insert an entry
+    fn insert(&self, event: &AuditEvent) -> Result<(), AuthError> {
+        let mut conn = self.conn()?;
+
+        diesel::insert_into(auth_audit_events::table)
+            .values(&to_new_db_event(event))
+            .execute(&mut conn)
+            .map_err(|e| AuthError::Internal(format!("Failed to record audit event: {}", e)))?;
+
+        Ok(())
+    }
+}
+
+/// Convert domain entity to insertable model
+pub(super) fn to_new_db_event(event: &AuditEvent) -> NewDbAuditEvent<'_> {
+    NewDbAuditEvent {
+        id: event.id(),
+        occurred_at: event.occurred_at(),
+        action: event.action().as_str(),
+        outcome: event.outcome().as_str(),
+        actor_id: event.actor_id(),
+        target_id: event.target_id(),
+        ip_address: event.ip_address().map(|ip| ip.to_string()),
+        user_agent: event.user_agent(),
+        detail: event.detail(),
+    }
+}
+
+/// Keep an entry that could not be written in the service log rather than
+/// lose it entirely
+pub(super) fn warn_unrecorded(event: &AuditEvent, e: &AuthError) {
+    warn!(
+        action = event.action().as_str(),
+        outcome = event.outcome().as_str(),
+        actor_id = ?event.actor_id(),
+        target_id = ?event.target_id(),
+        "Failed to write audit log: {}",
+        e
+    );
+}
+
+impl AuditLog for DieselAuditEventRepository {
+    fn record(&self, event: AuditEvent) {
+        if let Err(e) = self.insert(&event) {
+            warn_unrecorded(&event, &e);
+        }
+    }
+}
+
+impl AuditEventRepository for DieselAuditEventRepository {
+    fn search(
+        &self,
+        filter: &AuditFilter,
+        cursor: Option<&AuditCursor>,
+        limit: usize,
+    ) -> Result<AuditPage, AuthError> {
+        let mut conn = self.conn()?;
+
+        let mut query = auth_audit_events::table.into_boxed();
+        if let Some(action) = filter.action {
+            query = query.filter(auth_audit_events::action.eq(action.as_str()));
+        }
+        if let Some(outcome) = filter.outcome {
+            query = query.filter(auth_audit_events::outcome.eq(outcome.as_str()));
+        }
+        if let Some(actor_id) = filter.actor_id {
+            query = query.filter(auth_audit_events::actor_id.eq(actor_id));
+        }
+        if let Some(target_id) = filter.target_id {
+            query = query.filter(auth_audit_events::target_id.eq(target_id));
+        }
+        if let Some(occurred_after) = filter.occurred_after {
+            query = query.filter(auth_audit_events::occurred_at.ge(occurred_after));
+        }
+        if let Some(occurred_before) = filter.occurred_before {
+            query = query.filter(auth_audit_events::occurred_at.lt(occurred_before));
+        }
+        if let Some(cursor) = cursor {
+            query = query.filter(
+                auth_audit_events::occurred_at.lt(cursor.occurred_at).or(
+                    auth_audit_events::occurred_at
+                        .eq(cursor.occurred_at)
+                        .and(auth_audit_events::id.lt(cursor.id)),
+                ),
+            );
+        }
+
+        // One extra row tells whether another page follows
+        let mut db_events: Vec<DbAuditEvent> = query
+            .order((
+                auth_audit_events::occurred_at.desc(),
+                auth_audit_events::id.desc(),
+            ))
+            .limit(limit as i64 + 1)
+            .load(&mut conn)
+            .map_err(|e| AuthError::Internal(format!("Database error: {}", e)))?;
+        let has_more = db_events.len() > limit;
+        db_events.truncate(limit);
+
+        let events = db_events
+            .into_iter()
+            .map(db_audit_event_to_domain)
+            .collect::<Result<Vec<_>, _>>()?;
+        let next_cursor = if has_more {
+            events.last().map(AuditCursor::after)
+        } else {
+            None
+        };
+
+        Ok(AuditPage {
+            events,
+            next_cursor,
+        })
+    }
+
+    fn purge_older_than(&self, cutoff: DateTime<Utc>) -> Result<usize, AuthError> {
+        let mut conn = self.conn()?;
+
+        diesel::delete(auth_audit_events::table.filter(auth_audit_events::occurred_at.lt(cutoff)))
+            .execute(&mut conn)
+            .map_err(|e| AuthError::Internal(format!("Database error: {}", e)))
+    }
+}
+
+/// Convert database model to domain entity
+pub(super) fn db_audit_event_to_domain(db_event: DbAuditEvent) -> Result<AuditEvent, AuthError> {
+    let action = db_event
+        .action
+        .parse::<AuditAction>()
+        .map_err(|_| AuthError::Internal(format!("Unknown audit action: {}", db_event.action)))?;
+
+    Ok(AuditEvent::from_persistence(
+        db_event.id,
+        db_event.occurred_at,
+        action,
+        AuditOutcome::parse(&db_event.outcome)?,
+        db_event.actor_id,
+        db_event.target_id,
+        // Addresses are written by this service; skip any that do not parse
+        db_event.ip_address.and_then(|ip| ip.parse().ok()),
+        db_event.user_agent,
+        db_event.detail,
+    ))
+}
@@ -0,0 +1,96 @@
+//! Diesel implementation of the AuthorizationCodeRepository trait
+
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+
+use crate::domain::error::AuthError;
+use crate::domain::oidc::{AuthorizationCode, AuthorizationCodeRepository};
+
+use super::connection::DbPool;
+use super::models::{DbAuthorizationCode, NewDbAuthorizationCode};
+use super::schema::authorization_codes;
+
+/// Diesel-based implementation of AuthorizationCodeRepository
+pub struct DieselAuthorizationCodeRepository {
+    pool: DbPool,
+}
+
+impl DieselAuthorizationCodeRepository {
+    /// Create a new repository instance
+    #[must_use]
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get a connection from the pool
+    fn conn(&self) -> Result<super::connection::PooledDbConnection, AuthError> {
+        self.pool
+            .get()
+            .map_err(|e| AuthError::Internal(format!("Failed to get database connection: {}", e)))
+    }
+}
+
+impl AuthorizationCodeRepository for DieselAuthorizationCodeRepository {
+    fn create(&self, code: &AuthorizationCode) -> Result<(), AuthError> {
+        let mut conn = self.conn()?;
+
+        let new_code = NewDbAuthorizationCode {
+            code_hash: code.code_hash(),
+            client_id: code.client_id(),
+            user_id: code.user_id(),
+            redirect_uri: code.redirect_uri(),
+            scopes: code.scopes().iter().map(String::as_str).collect(),
+            code_challenge: code.code_challenge(),
+            nonce: code.nonce(),
+            expires_at: code.expires_at(),
+            created_at: code.created_at(),
+            session_id: code.session_id(),
+        };
+
+        diesel::insert_into(authorization_codes::table)
+            .values(&new_code)
+            .execute(&mut conn)
+            .map_err(|e| {
+                AuthError::Internal(format!("Failed to create authorization code: {}", e))
+            })?;
+
+        Ok(())
+    }
+
+    fn take(&self, code_hash: &str) -> Result<Option<AuthorizationCode>, AuthError> {
+        let mut conn = self.conn()?;
+
+        // Deleting and returning in one statement makes concurrent redemptions
+        // race safely: only one of them gets the row
+        let db_code: Option<DbAuthorizationCode> = diesel::delete(
+            authorization_codes::table.filter(authorization_codes::code_hash.eq(code_hash)),
+        )
+        .returning(DbAuthorizationCode::as_returning())
+        .get_result(&mut conn)
+        .optional()
+        .map_err(|e| AuthError::Internal(format!("Failed to redeem authorization code: {}", e)))?;
+
+        Ok(db_code.map(|db_code| {
+            AuthorizationCode::from_persistence(
+                db_code.code_hash,
+                db_code.client_id,
+                db_code.user_id,
+                db_code.session_id,
+                db_code.redirect_uri,
+                db_code.scopes,
+                db_code.code_challenge,
+                db_code.nonce,
+                db_code.expires_at,
+                db_code.created_at,
+            )
+        }))
+    }
+
+    fn purge_expired(&self, now: DateTime<Utc>) -> Result<usize, AuthError> {
+        let mut conn = self.conn()?;
+
+        diesel::delete(authorization_codes::table.filter(authorization_codes::expires_at.lt(now)))
+            .execute(&mut conn)
+            .map_err(|e| AuthError::Internal(format!("Failed to purge authorization codes: {}", e)))
+    }
+}
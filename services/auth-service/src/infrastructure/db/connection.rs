@@ -2,6 +2,15 @@
 
 use diesel::pg::PgConnection;
 use diesel::r2d2::{ConnectionManager, Pool, PooledConnection};
+use diesel_async::pooled_connection::{deadpool, AsyncDieselConnectionManager};
+use diesel_async::AsyncPgConnection;
+use diesel_migrations::{embed_migrations, EmbeddedMigrations};
+
+use crate::domain::health::HealthCheck;
+
+/// Embedded database migrations — compiled into the binaries so no external
+/// diesel CLI is needed at runtime.
+pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
 
 /// Type alias for the connection pool
 pub type DbPool = Pool<ConnectionManager<PgConnection>>;
@@ -23,3 +32,34 @@ pub fn create_connection_pool(database_url: &str, max_size: u32) -> Result<DbPoo
         .max_size(max_size)
         .build(manager)
 }
+
+impl HealthCheck for DbPool {
+    fn is_healthy(&self) -> bool {
+        self.get().is_ok()
+    }
+}
+
+/// Type alias for the async connection pool
+pub type AsyncDbPool = deadpool::Pool<AsyncPgConnection>;
+
+/// Type alias for a connection from the async pool
+pub type AsyncPooledDbConnection = deadpool::Object<AsyncPgConnection>;
+
+/// Creates a new async database connection pool
+///
+/// Connections are opened lazily, on first use; waiting for one suspends
+/// the task instead of blocking a thread.
+///
+/// # Arguments
+/// * `database_url` - PostgreSQL connection string
+/// * `max_size` - Maximum number of connections in the pool
+///
+/// # Errors
+/// Returns error if pool creation fails
+pub fn create_async_connection_pool(
+    database_url: &str,
+    max_size: usize,
+) -> Result<AsyncDbPool, deadpool::BuildError> {
+    let manager = AsyncDieselConnectionManager::<AsyncPgConnection>::new(database_url);
+    deadpool::Pool::builder(manager).max_size(max_size).build()
+}
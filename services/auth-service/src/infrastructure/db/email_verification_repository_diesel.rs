@@ -0,0 +1,123 @@
+//! Diesel implementation of the EmailVerificationTokenRepository trait
+
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+
+use crate::domain::email_verification::{EmailVerificationToken, EmailVerificationTokenRepository};
+use crate::domain::error::AuthError;
+
+use super::connection::DbPool;
+use super::models::{DbEmailVerificationToken, NewDbEmailVerificationToken};
+use super::schema::email_verification_tokens;
+
+/// Diesel-based implementation of EmailVerificationTokenRepository
+pub struct DieselEmailVerificationTokenRepository {
+    pool: DbPool,
+}
+
+impl DieselEmailVerificationTokenRepository {
+    /// Create a new repository instance
+    #[must_use]
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get a connection from the pool
+    fn conn(&self) -> Result<super::connection::PooledDbConnection, AuthError> {
+        self.pool
+            .get()
+            .map_err(|e| AuthError::Internal(format!("Failed to get database connection: {}", e)))
+    }
+}
+
+/// Convert domain entity to insertable model
+pub(super) fn to_new_db_token(token: &EmailVerificationToken) -> NewDbEmailVerificationToken<'_> {
+    NewDbEmailVerificationToken {
+        id: token.id(),
+        user_id: token.user_id(),
+        email: token.email(),
+        token_hash: token.token_hash(),
+        expires_at: token.expires_at(),
+        created_at: token.created_at(),
+    }
+}
+
+impl EmailVerificationTokenRepository for DieselEmailVerificationTokenRepository {
+    fn create(&self, token: &EmailVerificationToken) -> Result<(), AuthError> {
+        let mut conn = self.conn()?;
+
+        diesel::insert_into(email_verification_tokens::table)
+            .values(&to_new_db_token(token))
+            .execute(&mut conn)
+            .map_err(|e| {
+                AuthError::Internal(format!("Failed to create verification token: {}", e))
+            })?;
+
+        Ok(())
+    }
+
+    fn find_by_hash(&self, token_hash: &str) -> Result<EmailVerificationToken, AuthError> {
+        let mut conn = self.conn()?;
+
+        let db_token: DbEmailVerificationToken = email_verification_tokens::table
+            .filter(email_verification_tokens::token_hash.eq(token_hash))
+            .first(&mut conn)
+            .map_err(|e| match e {
+                diesel::result::Error::NotFound => AuthError::InvalidToken,
+                _ => AuthError::Internal(format!("Database error: {}", e)),
+            })?;
+
+        Ok(EmailVerificationToken::from_persistence(
+            db_token.id,
+            db_token.user_id,
+            db_token.email,
+            db_token.token_hash,
+            db_token.expires_at,
+            db_token.created_at,
+            db_token.used_at,
+        ))
+    }
+
+    fn consume(&self, token: &EmailVerificationToken) -> Result<bool, AuthError> {
+        let mut conn = self.conn()?;
+        let now = Utc::now();
+
+        conn.transaction::<bool, diesel::result::Error, _>(|conn| {
+            // Checking `used_at` in the UPDATE itself makes concurrent
+            // verifications race safely
+            let updated_rows = diesel::update(
+                email_verification_tokens::table
+                    .filter(email_verification_tokens::id.eq(token.id()))
+                    .filter(email_verification_tokens::used_at.is_null()),
+            )
+            .set(email_verification_tokens::used_at.eq(now))
+            .execute(conn)?;
+
+            if updated_rows == 0 {
+                return Ok(false);
+            }
+
+            // Links mailed earlier are no longer needed
+            diesel::update(
+                email_verification_tokens::table
+                    .filter(email_verification_tokens::user_id.eq(token.user_id()))
+                    .filter(email_verification_tokens::used_at.is_null()),
+            )
+            .set(email_verification_tokens::used_at.eq(now))
+            .execute(conn)?;
+
+            Ok(true)
+        })
+        .map_err(|e| AuthError::Internal(format!("Failed to consume verification token: {}", e)))
+    }
+
+    fn purge_expired(&self, now: DateTime<Utc>) -> Result<usize, AuthError> {
+        let mut conn = self.conn()?;
+
+        diesel::delete(
+            email_verification_tokens::table.filter(email_verification_tokens::expires_at.lt(now)),
+        )
+        .execute(&mut conn)
+        .map_err(|e| AuthError::Internal(format!("Failed to purge verification tokens: {}", e)))
+    }
+}
@@ -0,0 +1,44 @@
+//! Diesel implementation of the FederatedLoginRequestRepository trait
+
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+
+use crate::domain::error::AuthError;
+use crate::domain::federation::FederatedLoginRequestRepository;
+
+use super::connection::DbPool;
+use super::schema::federated_login_requests;
+
+/// Diesel-based implementation of FederatedLoginRequestRepository
+pub struct DieselFederatedLoginRequestRepository {
+    pool: DbPool,
+}
+
+impl DieselFederatedLoginRequestRepository {
+    /// Create a new repository instance
+    #[must_use]
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get a connection from the pool
+    fn conn(&self) -> Result<super::connection::PooledDbConnection, AuthError> {
+        self.pool
+            .get()
+            .map_err(|e| AuthError::Internal(format!("Failed to get database connection: {}", e)))
+    }
+}
+
+impl FederatedLoginRequestRepository for DieselFederatedLoginRequestRepository {
+    fn purge_expired(&self, now: DateTime<Utc>) -> Result<usize, AuthError> {
+        let mut conn = self.conn()?;
+
+        diesel::delete(
+            federated_login_requests::table.filter(federated_login_requests::expires_at.lt(now)),
+        )
+        .execute(&mut conn)
+        .map_err(|e| {
+            AuthError::Internal(format!("Failed to purge federated login requests: {}", e))
+        })
+    }
+}
@@ -0,0 +1,119 @@
+//! Diesel implementation of the InvitationRepository trait
+
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+
+use crate::domain::error::AuthError;
+use crate::domain::organization::{Invitation, InvitationRepository, Membership};
+
+use super::connection::DbPool;
+use super::models::{DbOrganizationInvitation, NewDbOrganizationInvitation};
+use super::organization_repository_diesel::{new_membership, parse_org_role};
+use super::schema::{organization_invitations, organization_memberships};
+
+/// Diesel-based implementation of InvitationRepository
+pub struct DieselInvitationRepository {
+    pool: DbPool,
+}
+
+impl DieselInvitationRepository {
+    /// Create a new repository instance
+    #[must_use]
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get a connection from the pool
+    fn conn(&self) -> Result<super::connection::PooledDbConnection, AuthError> {
+        self.pool
+            .get()
+            .map_err(|e| AuthError::Internal(format!("Failed to get database connection: {}", e)))
+    }
+}
+
+impl InvitationRepository for DieselInvitationRepository {
+    fn create(&self, invitation: &Invitation) -> Result<(), AuthError> {
+        let mut conn = self.conn()?;
+
+        let new_invitation = NewDbOrganizationInvitation {
+            id: invitation.id(),
+            organization_id: invitation.organization_id(),
+            email: invitation.email(),
+            role: invitation.role().as_str(),
+            token_hash: invitation.token_hash(),
+            invited_by: invitation.invited_by(),
+            expires_at: invitation.expires_at(),
+            created_at: invitation.created_at(),
+        };
+
+        diesel::insert_into(organization_invitations::table)
+            .values(&new_invitation)
+            .execute(&mut conn)
+            .map_err(|e| AuthError::Internal(format!("Failed to create invitation: {}", e)))?;
+
+        Ok(())
+    }
+
+    fn find_by_hash(&self, token_hash: &str) -> Result<Invitation, AuthError> {
+        let mut conn = self.conn()?;
+
+        let db_invitation: DbOrganizationInvitation = organization_invitations::table
+            .filter(organization_invitations::token_hash.eq(token_hash))
+            .first(&mut conn)
+            .map_err(|e| match e {
+                diesel::result::Error::NotFound => AuthError::InvalidInvitation,
+                _ => AuthError::Internal(format!("Database error: {}", e)),
+            })?;
+
+        Ok(Invitation::from_persistence(
+            db_invitation.id,
+            db_invitation.organization_id,
+            db_invitation.email,
+            parse_org_role(&db_invitation.role)?,
+            db_invitation.token_hash,
+            db_invitation.invited_by,
+            db_invitation.expires_at,
+            db_invitation.created_at,
+            db_invitation.accepted_at,
+        ))
+    }
+
+    fn accept(&self, invitation: &Invitation, membership: &Membership) -> Result<bool, AuthError> {
+        let mut conn = self.conn()?;
+
+        conn.transaction::<bool, diesel::result::Error, _>(|conn| {
+            // Checking `accepted_at` in the UPDATE itself makes concurrent
+            // acceptances race safely
+            let updated_rows = diesel::update(
+                organization_invitations::table
+                    .filter(organization_invitations::id.eq(invitation.id()))
+                    .filter(organization_invitations::accepted_at.is_null()),
+            )
+            .set(organization_invitations::accepted_at.eq(Utc::now()))
+            .execute(conn)?;
+
+            if updated_rows == 0 {
+                return Ok(false);
+            }
+
+            // Existing members keep the role they have
+            diesel::insert_into(organization_memberships::table)
+                .values(&new_membership(membership))
+                .on_conflict_do_nothing()
+                .execute(conn)?;
+
+            Ok(true)
+        })
+        .map_err(|e| AuthError::Internal(format!("Failed to accept invitation: {}", e)))
+    }
+
+    fn purge_expired(&self, cutoff: DateTime<Utc>) -> Result<usize, AuthError> {
+        let mut conn = self.conn()?;
+
+        diesel::delete(
+            organization_invitations::table.filter(organization_invitations::expires_at.lt(cutoff)),
+        )
+        .execute(&mut conn)
+        .map_err(|e| AuthError::Internal(format!("Failed to purge invitations: {}", e)))
+    }
+}
@@ -0,0 +1,125 @@
+//! Diesel implementation of the LoginThrottleRepository trait
+
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+
+use crate::domain::error::AuthError;
+use crate::domain::lockout::{LockoutPolicy, LoginThrottle, LoginThrottleRepository, ThrottleKey};
+
+use super::connection::DbPool;
+use super::models::{DbLoginThrottle, NewDbLoginThrottle};
+use super::schema::login_throttles;
+
+/// Diesel-based implementation of LoginThrottleRepository
+pub struct DieselLoginThrottleRepository {
+    pool: DbPool,
+}
+
+impl DieselLoginThrottleRepository {
+    /// Create a new repository instance
+    #[must_use]
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get a connection from the pool
+    fn conn(&self) -> Result<super::connection::PooledDbConnection, AuthError> {
+        self.pool
+            .get()
+            .map_err(|e| AuthError::Internal(format!("Failed to get database connection: {}", e)))
+    }
+}
+
+impl LoginThrottleRepository for DieselLoginThrottleRepository {
+    fn find(&self, key: &ThrottleKey) -> Result<Option<LoginThrottle>, AuthError> {
+        let mut conn = self.conn()?;
+
+        let db_throttle: Option<DbLoginThrottle> = login_throttles::table
+            .find((key.scope().as_str(), key.value()))
+            .first(&mut conn)
+            .optional()
+            .map_err(|e| AuthError::Internal(format!("Database error: {}", e)))?;
+
+        Ok(db_throttle.map(|t| db_throttle_to_domain(key, t)))
+    }
+
+    fn record_failure(
+        &self,
+        key: &ThrottleKey,
+        policy: &LockoutPolicy,
+        now: DateTime<Utc>,
+    ) -> Result<LoginThrottle, AuthError> {
+        let mut conn = self.conn()?;
+        let row = (key.scope().as_str(), key.value());
+
+        conn.transaction::<LoginThrottle, diesel::result::Error, _>(|conn| {
+            // Make sure a row exists, so that concurrent first failures
+            // serialize on the row lock below instead of overwriting each other
+            diesel::insert_into(login_throttles::table)
+                .values(&NewDbLoginThrottle {
+                    scope: row.0,
+                    subject: row.1,
+                    failed_attempts: 0,
+                    last_failed_at: now,
+                })
+                .on_conflict_do_nothing()
+                .execute(conn)?;
+
+            let current: DbLoginThrottle =
+                login_throttles::table.find(row).for_update().first(conn)?;
+
+            let mut throttle = db_throttle_to_domain(key, current);
+            throttle.record_failure(now, policy);
+
+            diesel::update(login_throttles::table.find(row))
+                .set((
+                    login_throttles::failed_attempts.eq(throttle.failed_attempts()),
+                    login_throttles::last_failed_at.eq(throttle.last_failed_at()),
+                    login_throttles::locked_until.eq(throttle.locked_until()),
+                ))
+                .execute(conn)?;
+
+            Ok(throttle)
+        })
+        .map_err(|e| AuthError::Internal(format!("Failed to record failed login: {}", e)))
+    }
+
+    fn clear(&self, key: &ThrottleKey) -> Result<bool, AuthError> {
+        let mut conn = self.conn()?;
+
+        let deleted_rows =
+            diesel::delete(login_throttles::table.find((key.scope().as_str(), key.value())))
+                .execute(&mut conn)
+                .map_err(|e| {
+                    AuthError::Internal(format!("Failed to clear failed logins: {}", e))
+                })?;
+
+        Ok(deleted_rows > 0)
+    }
+
+    fn purge_stale(&self, cutoff: DateTime<Utc>) -> Result<usize, AuthError> {
+        let mut conn = self.conn()?;
+
+        diesel::delete(
+            login_throttles::table
+                .filter(login_throttles::last_failed_at.lt(cutoff))
+                .filter(
+                    login_throttles::locked_until
+                        .is_null()
+                        .or(login_throttles::locked_until.lt(cutoff)),
+                ),
+        )
+        .execute(&mut conn)
+        .map_err(|e| AuthError::Internal(format!("Failed to purge login throttles: {}", e)))
+    }
+}
+
+/// Convert database model to domain entity
+pub(super) fn db_throttle_to_domain(key: &ThrottleKey, db_throttle: DbLoginThrottle) -> LoginThrottle {
+    LoginThrottle::from_persistence(
+        key.clone(),
+        db_throttle.failed_attempts,
+        db_throttle.last_failed_at,
+        db_throttle.locked_until,
+    )
+}
@@ -0,0 +1,72 @@
+//! Diesel implementation of the MagicLinkTokenRepository trait
+
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::domain::error::AuthError;
+use crate::domain::magic_link::{MagicLinkToken, MagicLinkTokenRepository};
+
+use super::connection::DbPool;
+use super::models::NewDbMagicLinkToken;
+use super::schema::magic_link_tokens;
+
+/// Diesel-based implementation of MagicLinkTokenRepository
+pub struct DieselMagicLinkTokenRepository {
+    pool: DbPool,
+}
+
+impl DieselMagicLinkTokenRepository {
+    /// Create a new repository instance
+    #[must_use]
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get a connection from the pool
+    fn conn(&self) -> Result<super::connection::PooledDbConnection, AuthError> {
+        self.pool
+            .get()
+            .map_err(|e| AuthError::Internal(format!("Failed to get database connection: {}", e)))
+    }
+}
+
+impl MagicLinkTokenRepository for DieselMagicLinkTokenRepository {
+    fn create(&self, token: &MagicLinkToken) -> Result<(), AuthError> {
+        let mut conn = self.conn()?;
+
+        let new_token = NewDbMagicLinkToken {
+            id: token.id(),
+            user_id: token.user_id(),
+            token_hash: token.token_hash(),
+            expires_at: token.expires_at(),
+            created_at: token.created_at(),
+        };
+
+        diesel::insert_into(magic_link_tokens::table)
+            .values(&new_token)
+            .execute(&mut conn)
+            .map_err(|e| AuthError::Internal(format!("Failed to create magic link: {}", e)))?;
+
+        Ok(())
+    }
+
+    fn count_issued_since(&self, user_id: Uuid, since: DateTime<Utc>) -> Result<i64, AuthError> {
+        let mut conn = self.conn()?;
+
+        magic_link_tokens::table
+            .filter(magic_link_tokens::user_id.eq(user_id))
+            .filter(magic_link_tokens::created_at.ge(since))
+            .count()
+            .get_result(&mut conn)
+            .map_err(|e| AuthError::Internal(format!("Database error: {}", e)))
+    }
+
+    fn purge_expired(&self, cutoff: DateTime<Utc>) -> Result<usize, AuthError> {
+        let mut conn = self.conn()?;
+
+        diesel::delete(magic_link_tokens::table.filter(magic_link_tokens::expires_at.lt(cutoff)))
+            .execute(&mut conn)
+            .map_err(|e| AuthError::Internal(format!("Failed to purge magic links: {}", e)))
+    }
+}
@@ -0,0 +1,40 @@
+//! Diesel implementation of the MfaChallengeRepository trait
+
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+
+use crate::domain::error::AuthError;
+use crate::domain::mfa::MfaChallengeRepository;
+
+use super::connection::DbPool;
+use super::schema::mfa_challenges;
+
+/// Diesel-based implementation of MfaChallengeRepository
+pub struct DieselMfaChallengeRepository {
+    pool: DbPool,
+}
+
+impl DieselMfaChallengeRepository {
+    /// Create a new repository instance
+    #[must_use]
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get a connection from the pool
+    fn conn(&self) -> Result<super::connection::PooledDbConnection, AuthError> {
+        self.pool
+            .get()
+            .map_err(|e| AuthError::Internal(format!("Failed to get database connection: {}", e)))
+    }
+}
+
+impl MfaChallengeRepository for DieselMfaChallengeRepository {
+    fn purge_expired(&self, now: DateTime<Utc>) -> Result<usize, AuthError> {
+        let mut conn = self.conn()?;
+
+        diesel::delete(mfa_challenges::table.filter(mfa_challenges::expires_at.lt(now)))
+            .execute(&mut conn)
+            .map_err(|e| AuthError::Internal(format!("Failed to purge MFA challenges: {}", e)))
+    }
+}
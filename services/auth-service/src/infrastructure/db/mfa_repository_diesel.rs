@@ -0,0 +1,141 @@
+//! Diesel implementation of the MfaRepository trait
+
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+use uuid::Uuid;
+
+use crate::domain::error::AuthError;
+use crate::domain::mfa::{MfaRepository, TotpCredential};
+
+use super::connection::DbPool;
+use super::models::{DbTotpCredential, NewDbRecoveryCode, NewDbTotpCredential};
+use super::schema::{mfa_recovery_codes, totp_credentials};
+
+/// Diesel-based implementation of MfaRepository
+pub struct DieselMfaRepository {
+    pool: DbPool,
+}
+
+impl DieselMfaRepository {
+    /// Create a new repository instance
+    #[must_use]
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get a connection from the pool
+    fn conn(&self) -> Result<super::connection::PooledDbConnection, AuthError> {
+        self.pool
+            .get()
+            .map_err(|e| AuthError::Internal(format!("Failed to get database connection: {}", e)))
+    }
+}
+
+/// Convert database model to domain entity
+pub(super) fn db_totp_to_domain(db_credential: DbTotpCredential) -> TotpCredential {
+    TotpCredential::from_persistence(
+        db_credential.user_id,
+        db_credential.secret,
+        db_credential.confirmed_at,
+        db_credential.last_used_step,
+        db_credential.created_at,
+    )
+}
+
+impl MfaRepository for DieselMfaRepository {
+    fn find_totp(&self, user_id: Uuid) -> Result<Option<TotpCredential>, AuthError> {
+        let mut conn = self.conn()?;
+
+        let db_credential: Option<DbTotpCredential> = totp_credentials::table
+            .find(user_id)
+            .first(&mut conn)
+            .optional()
+            .map_err(|e| AuthError::Internal(format!("Database error: {}", e)))?;
+
+        Ok(db_credential.map(db_totp_to_domain))
+    }
+
+    fn save_pending_totp(&self, credential: &TotpCredential) -> Result<(), AuthError> {
+        let mut conn = self.conn()?;
+
+        let new_credential = NewDbTotpCredential {
+            user_id: credential.user_id(),
+            secret: credential.secret(),
+            created_at: credential.created_at(),
+        };
+
+        let upsert = diesel::insert_into(totp_credentials::table)
+            .values(&new_credential)
+            .on_conflict(totp_credentials::user_id)
+            .do_update()
+            .set((
+                totp_credentials::secret.eq(excluded(totp_credentials::secret)),
+                totp_credentials::created_at.eq(excluded(totp_credentials::created_at)),
+            ));
+
+        // Only an unconfirmed enrollment may be replaced. Upserts get their
+        // WHERE clause through `FilterDsl` rather than `QueryDsl`.
+        let affected_rows = diesel::query_dsl::methods::FilterDsl::filter(
+            upsert,
+            totp_credentials::confirmed_at.is_null(),
+        )
+        .execute(&mut conn)
+        .map_err(|e| AuthError::Internal(format!("Failed to save TOTP credential: {}", e)))?;
+
+        if affected_rows == 0 {
+            return Err(AuthError::MfaAlreadyEnabled);
+        }
+
+        Ok(())
+    }
+
+    fn activate_totp(
+        &self,
+        user_id: Uuid,
+        step: i64,
+        recovery_code_hashes: &[String],
+    ) -> Result<bool, AuthError> {
+        let mut conn = self.conn()?;
+        let now = Utc::now();
+
+        conn.transaction::<bool, diesel::result::Error, _>(|conn| {
+            let updated_rows = diesel::update(
+                totp_credentials::table
+                    .filter(totp_credentials::user_id.eq(user_id))
+                    .filter(totp_credentials::confirmed_at.is_null()),
+            )
+            .set((
+                totp_credentials::confirmed_at.eq(now),
+                totp_credentials::last_used_step.eq(step),
+            ))
+            .execute(conn)?;
+
+            if updated_rows == 0 {
+                return Ok(false);
+            }
+
+            diesel::delete(
+                mfa_recovery_codes::table.filter(mfa_recovery_codes::user_id.eq(user_id)),
+            )
+            .execute(conn)?;
+
+            let new_codes: Vec<NewDbRecoveryCode> = recovery_code_hashes
+                .iter()
+                .map(|code_hash| NewDbRecoveryCode {
+                    id: Uuid::new_v4(),
+                    user_id,
+                    code_hash,
+                    created_at: now,
+                })
+                .collect();
+
+            diesel::insert_into(mfa_recovery_codes::table)
+                .values(&new_codes)
+                .execute(conn)?;
+
+            Ok(true)
+        })
+        .map_err(|e| AuthError::Internal(format!("Failed to activate TOTP: {}", e)))
+    }
+}
@@ -1,7 +1,40 @@
 //! Database infrastructure - Diesel + PostgreSQL
 
+pub mod api_key_repository_diesel;
+pub mod async_api_key_repository_diesel;
+pub mod async_audit_log_diesel;
+pub mod async_email_verification_repository_diesel;
+pub mod async_federated_login_request_repository_diesel;
+pub mod async_login_throttle_repository_diesel;
+pub mod async_magic_link_repository_diesel;
+pub mod async_mfa_challenge_repository_diesel;
+pub mod async_mfa_repository_diesel;
+pub mod async_organization_repository_diesel;
+pub mod async_refresh_token_repository_diesel;
+pub mod async_session_repository_diesel;
+pub mod async_token_revocation_store_diesel;
+pub mod async_user_identity_repository_diesel;
+pub mod async_user_repository_diesel;
+pub mod audit_event_repository_diesel;
+pub mod authorization_code_repository_diesel;
 pub mod connection;
+pub mod email_verification_repository_diesel;
+pub mod federated_login_request_repository_diesel;
+pub mod invitation_repository_diesel;
+pub mod login_throttle_repository_diesel;
+pub mod magic_link_repository_diesel;
+pub mod mfa_challenge_repository_diesel;
+pub mod mfa_repository_diesel;
+pub mod oauth_client_repository_diesel;
+pub mod oidc_client_repository_diesel;
 pub mod models;
+pub mod organization_repository_diesel;
+pub mod outbox_repository_diesel;
+pub mod password_reset_repository_diesel;
+pub mod personal_data_repository_diesel;
+pub mod refresh_token_repository_diesel;
 pub mod schema;
-pub mod seed;
+pub mod session_repository_diesel;
+pub mod signing_key_repository_diesel;
+pub mod token_revocation_store_diesel;
 pub mod user_repository_diesel;
@@ -6,7 +6,13 @@ use chrono::{DateTime, Utc};
 use diesel::prelude::*;
 use uuid::Uuid;
 
-use super::schema::users;
+use super::schema::{
+    api_keys, auth_audit_events, authorization_codes, email_verification_tokens,
+    federated_login_requests, jwt_signing_keys, login_throttles, magic_link_tokens, mfa_challenges,
+    mfa_recovery_codes, oauth_clients, oidc_clients, organization_invitations,
+    organization_memberships, organizations, outbox, password_reset_tokens, refresh_tokens,
+    revoked_tokens, sessions, totp_credentials, user_identities, user_roles, users,
+};
 
 /// Database model for users table (for querying)
 #[derive(Debug, Clone, Queryable, Selectable)]
@@ -15,11 +21,12 @@ use super::schema::users;
 pub struct DbUser {
     pub id: Uuid,
     pub email: String,
-    pub hashed_password: String,
+    pub hashed_password: Option<String>,
     pub display_name: Option<String>,
     pub is_active: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub email_verified_at: Option<DateTime<Utc>>,
 }
 
 /// New user model for insertion
@@ -28,9 +35,548 @@ pub struct DbUser {
 pub struct NewDbUser<'a> {
     pub id: Uuid,
     pub email: &'a str,
-    pub hashed_password: &'a str,
+    pub hashed_password: Option<&'a str>,
     pub display_name: Option<&'a str>,
     pub is_active: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub email_verified_at: Option<DateTime<Utc>>,
+}
+
+/// New user role model for insertion
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = user_roles)]
+pub struct NewDbUserRole<'a> {
+    pub user_id: Uuid,
+    pub role: &'a str,
+    pub granted_at: DateTime<Utc>,
+}
+
+/// Database model for refresh_tokens table (for querying)
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = refresh_tokens)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct DbRefreshToken {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub family_id: Uuid,
+    pub token_hash: String,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub rotated_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// New refresh token model for insertion
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = refresh_tokens)]
+pub struct NewDbRefreshToken<'a> {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub family_id: Uuid,
+    pub token_hash: &'a str,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Database model for sessions table (for querying)
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = sessions)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct DbSession {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub organization_id: Option<Uuid>,
+}
+
+/// New session model for insertion
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = sessions)]
+pub struct NewDbSession<'a> {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub user_agent: Option<&'a str>,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Database model for password_reset_tokens table (for querying)
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = password_reset_tokens)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct DbPasswordResetToken {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub token_hash: String,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub used_at: Option<DateTime<Utc>>,
+}
+
+/// New password reset token model for insertion
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = password_reset_tokens)]
+pub struct NewDbPasswordResetToken<'a> {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub token_hash: &'a str,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Database model for magic_link_tokens table (for querying)
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = magic_link_tokens)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct DbMagicLinkToken {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub token_hash: String,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub used_at: Option<DateTime<Utc>>,
+}
+
+/// New magic link token model for insertion
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = magic_link_tokens)]
+pub struct NewDbMagicLinkToken<'a> {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub token_hash: &'a str,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Database model for email_verification_tokens table (for querying)
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = email_verification_tokens)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct DbEmailVerificationToken {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub email: String,
+    pub token_hash: String,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub used_at: Option<DateTime<Utc>>,
+}
+
+/// New email verification token model for insertion
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = email_verification_tokens)]
+pub struct NewDbEmailVerificationToken<'a> {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub email: &'a str,
+    pub token_hash: &'a str,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Database model for totp_credentials table (for querying)
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = totp_credentials)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct DbTotpCredential {
+    pub user_id: Uuid,
+    pub secret: String,
+    pub confirmed_at: Option<DateTime<Utc>>,
+    pub last_used_step: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// New TOTP credential model for insertion
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = totp_credentials)]
+pub struct NewDbTotpCredential<'a> {
+    pub user_id: Uuid,
+    pub secret: &'a str,
+    pub created_at: DateTime<Utc>,
+}
+
+/// New recovery code model for insertion
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = mfa_recovery_codes)]
+pub struct NewDbRecoveryCode<'a> {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub code_hash: &'a str,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Database model for mfa_challenges table (for querying)
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = mfa_challenges)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct DbMfaChallenge {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub token_hash: String,
+    pub failed_attempts: i32,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub used_at: Option<DateTime<Utc>>,
+}
+
+/// New MFA challenge model for insertion
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = mfa_challenges)]
+pub struct NewDbMfaChallenge<'a> {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub token_hash: &'a str,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Database model for login_throttles table
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = login_throttles)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct DbLoginThrottle {
+    pub scope: String,
+    pub subject: String,
+    pub failed_attempts: i32,
+    pub last_failed_at: DateTime<Utc>,
+    pub locked_until: Option<DateTime<Utc>>,
+}
+
+/// New login throttle model for insertion
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = login_throttles)]
+pub struct NewDbLoginThrottle<'a> {
+    pub scope: &'a str,
+    pub subject: &'a str,
+    pub failed_attempts: i32,
+    pub last_failed_at: DateTime<Utc>,
+}
+
+/// New revoked token model for insertion
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = revoked_tokens)]
+pub struct NewDbRevokedToken<'a> {
+    pub jti: &'a str,
+    pub user_id: Uuid,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: DateTime<Utc>,
+}
+
+/// Database model for jwt_signing_keys table (for querying)
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = jwt_signing_keys)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct DbSigningKey {
+    pub kid: String,
+    pub algorithm: String,
+    pub private_key: String,
+    pub public_key: String,
+    pub created_at: DateTime<Utc>,
+    pub retired_at: Option<DateTime<Utc>>,
+    pub verify_until: Option<DateTime<Utc>>,
+}
+
+/// New signing key model for insertion
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = jwt_signing_keys)]
+pub struct NewDbSigningKey<'a> {
+    pub kid: &'a str,
+    pub algorithm: &'a str,
+    pub private_key: &'a str,
+    pub public_key: &'a str,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Database model for api_keys table (for querying)
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = api_keys)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct DbApiKey {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub prefix: String,
+    pub key_hash: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// New API key model for insertion
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = api_keys)]
+pub struct NewDbApiKey<'a> {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: &'a str,
+    pub prefix: &'a str,
+    pub key_hash: &'a str,
+    pub scopes: Vec<&'a str>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Database model for oauth_clients table (for querying)
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = oauth_clients)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct DbOAuthClient {
+    pub client_id: Uuid,
+    pub name: String,
+    pub secret_hash: String,
+    pub scopes: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// New OAuth client model for insertion
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = oauth_clients)]
+pub struct NewDbOAuthClient<'a> {
+    pub client_id: Uuid,
+    pub name: &'a str,
+    pub secret_hash: &'a str,
+    pub scopes: Vec<&'a str>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Database model for oidc_clients table (for querying)
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = oidc_clients)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct DbOidcClient {
+    pub client_id: String,
+    pub name: String,
+    pub redirect_uris: Vec<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// New OpenID Connect client model for insertion
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = oidc_clients)]
+pub struct NewDbOidcClient<'a> {
+    pub client_id: &'a str,
+    pub name: &'a str,
+    pub redirect_uris: Vec<&'a str>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Database model for authorization_codes table (for querying)
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = authorization_codes)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct DbAuthorizationCode {
+    pub code_hash: String,
+    pub client_id: String,
+    pub user_id: Uuid,
+    pub redirect_uri: String,
+    pub scopes: Vec<String>,
+    pub code_challenge: String,
+    pub nonce: Option<String>,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub session_id: Uuid,
+}
+
+/// New authorization code model for insertion
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = authorization_codes)]
+pub struct NewDbAuthorizationCode<'a> {
+    pub code_hash: &'a str,
+    pub client_id: &'a str,
+    pub user_id: Uuid,
+    pub redirect_uri: &'a str,
+    pub scopes: Vec<&'a str>,
+    pub code_challenge: &'a str,
+    pub nonce: Option<&'a str>,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub session_id: Uuid,
+}
+
+/// Database model for user_identities table (for querying)
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = user_identities)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct DbUserIdentity {
+    pub provider: String,
+    pub subject: String,
+    pub user_id: Uuid,
+    pub email: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_login_at: Option<DateTime<Utc>>,
+}
+
+/// New user identity model for insertion
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = user_identities)]
+pub struct NewDbUserIdentity<'a> {
+    pub provider: &'a str,
+    pub subject: &'a str,
+    pub user_id: Uuid,
+    pub email: Option<&'a str>,
+    pub created_at: DateTime<Utc>,
+    pub last_login_at: Option<DateTime<Utc>>,
+}
+
+/// Database model for federated_login_requests table (for querying)
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = federated_login_requests)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct DbFederatedLoginRequest {
+    pub state_hash: String,
+    pub provider: String,
+    pub nonce: String,
+    pub code_verifier: String,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// New federated login request model for insertion
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = federated_login_requests)]
+pub struct NewDbFederatedLoginRequest<'a> {
+    pub state_hash: &'a str,
+    pub provider: &'a str,
+    pub nonce: &'a str,
+    pub code_verifier: &'a str,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Database model for auth_audit_events table (for querying)
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = auth_audit_events)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct DbAuditEvent {
+    pub id: Uuid,
+    pub occurred_at: DateTime<Utc>,
+    pub action: String,
+    pub outcome: String,
+    pub actor_id: Option<Uuid>,
+    pub target_id: Option<Uuid>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub detail: Option<String>,
+}
+
+/// New audit event model for insertion
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = auth_audit_events)]
+pub struct NewDbAuditEvent<'a> {
+    pub id: Uuid,
+    pub occurred_at: DateTime<Utc>,
+    pub action: &'a str,
+    pub outcome: &'a str,
+    pub actor_id: Option<Uuid>,
+    pub target_id: Option<Uuid>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<&'a str>,
+    pub detail: Option<&'a str>,
+}
+
+/// Database model for organizations table (for querying)
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = organizations)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct DbOrganization {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// New organization model for insertion
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = organizations)]
+pub struct NewDbOrganization<'a> {
+    pub id: Uuid,
+    pub name: &'a str,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Database model for organization_memberships table (for querying)
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = organization_memberships)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct DbOrganizationMembership {
+    pub organization_id: Uuid,
+    pub user_id: Uuid,
+    pub role: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// New organization membership model for insertion
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = organization_memberships)]
+pub struct NewDbOrganizationMembership<'a> {
+    pub organization_id: Uuid,
+    pub user_id: Uuid,
+    pub role: &'a str,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Database model for organization_invitations table (for querying)
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = organization_invitations)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct DbOrganizationInvitation {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub email: String,
+    pub role: String,
+    pub token_hash: String,
+    pub invited_by: Uuid,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub accepted_at: Option<DateTime<Utc>>,
+}
+
+/// New organization invitation model for insertion
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = organization_invitations)]
+pub struct NewDbOrganizationInvitation<'a> {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub email: &'a str,
+    pub role: &'a str,
+    pub token_hash: &'a str,
+    pub invited_by: Uuid,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Database model for outbox table (for querying)
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = outbox)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct DbOutboxEvent {
+    pub sequence: i64,
+    pub id: Uuid,
+    pub aggregate_id: Uuid,
+    pub event_type: String,
+    pub schema_version: i32,
+    pub payload: String,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// New outbox event model for insertion
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = outbox)]
+pub struct NewDbOutboxEvent<'a> {
+    pub id: Uuid,
+    pub aggregate_id: Uuid,
+    pub event_type: &'a str,
+    pub schema_version: i32,
+    pub payload: String,
+    pub occurred_at: DateTime<Utc>,
 }
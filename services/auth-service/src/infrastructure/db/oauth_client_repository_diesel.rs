@@ -0,0 +1,120 @@
+//! Diesel implementation of the OAuthClientRepository trait
+
+use chrono::Utc;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::domain::error::AuthError;
+use crate::domain::oauth_client::{OAuthClient, OAuthClientRepository};
+use crate::domain::role::Permission;
+
+use super::connection::DbPool;
+use super::models::{DbOAuthClient, NewDbOAuthClient};
+use super::schema::oauth_clients;
+
+/// Diesel-based implementation of OAuthClientRepository
+pub struct DieselOAuthClientRepository {
+    pool: DbPool,
+}
+
+impl DieselOAuthClientRepository {
+    /// Create a new repository instance
+    #[must_use]
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get a connection from the pool
+    fn conn(&self) -> Result<super::connection::PooledDbConnection, AuthError> {
+        self.pool
+            .get()
+            .map_err(|e| AuthError::Internal(format!("Failed to get database connection: {}", e)))
+    }
+}
+
+/// Convert a database row to the domain entity
+///
+/// Scopes that are no longer known are dropped rather than failing the client.
+fn to_domain(db_client: DbOAuthClient) -> OAuthClient {
+    let scopes = db_client
+        .scopes
+        .iter()
+        .filter_map(|scope| scope.parse::<Permission>().ok())
+        .collect();
+
+    OAuthClient::from_persistence(
+        db_client.client_id,
+        db_client.name,
+        db_client.secret_hash,
+        scopes,
+        db_client.created_at,
+        db_client.revoked_at,
+    )
+}
+
+impl OAuthClientRepository for DieselOAuthClientRepository {
+    fn create(&self, client: &OAuthClient) -> Result<(), AuthError> {
+        let mut conn = self.conn()?;
+
+        let new_client = NewDbOAuthClient {
+            client_id: client.client_id(),
+            name: client.name(),
+            secret_hash: client.secret_hash(),
+            scopes: client.scopes().iter().map(Permission::as_str).collect(),
+            created_at: client.created_at(),
+        };
+
+        diesel::insert_into(oauth_clients::table)
+            .values(&new_client)
+            .execute(&mut conn)
+            .map_err(|e| AuthError::Internal(format!("Failed to create OAuth client: {}", e)))?;
+
+        Ok(())
+    }
+
+    fn find_by_id(&self, client_id: Uuid) -> Result<OAuthClient, AuthError> {
+        let mut conn = self.conn()?;
+
+        let db_client: DbOAuthClient = oauth_clients::table
+            .find(client_id)
+            .select(DbOAuthClient::as_select())
+            .first(&mut conn)
+            .map_err(|e| match e {
+                diesel::result::Error::NotFound => AuthError::OAuthClientNotFound,
+                _ => AuthError::Internal(format!("Database error: {}", e)),
+            })?;
+
+        Ok(to_domain(db_client))
+    }
+
+    fn list(&self) -> Result<Vec<OAuthClient>, AuthError> {
+        let mut conn = self.conn()?;
+
+        let db_clients: Vec<DbOAuthClient> = oauth_clients::table
+            .filter(oauth_clients::revoked_at.is_null())
+            .order((
+                oauth_clients::created_at.desc(),
+                oauth_clients::client_id.desc(),
+            ))
+            .select(DbOAuthClient::as_select())
+            .load(&mut conn)
+            .map_err(|e| AuthError::Internal(format!("Failed to list OAuth clients: {}", e)))?;
+
+        Ok(db_clients.into_iter().map(to_domain).collect())
+    }
+
+    fn revoke(&self, client_id: Uuid) -> Result<bool, AuthError> {
+        let mut conn = self.conn()?;
+
+        let updated_rows = diesel::update(
+            oauth_clients::table
+                .filter(oauth_clients::client_id.eq(client_id))
+                .filter(oauth_clients::revoked_at.is_null()),
+        )
+        .set(oauth_clients::revoked_at.eq(Utc::now()))
+        .execute(&mut conn)
+        .map_err(|e| AuthError::Internal(format!("Failed to revoke OAuth client: {}", e)))?;
+
+        Ok(updated_rows > 0)
+    }
+}
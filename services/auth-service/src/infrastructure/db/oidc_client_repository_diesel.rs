@@ -0,0 +1,84 @@
+//! Diesel implementation of the OidcClientRepository trait
+
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+
+use crate::domain::error::AuthError;
+use crate::domain::oidc::{OidcClient, OidcClientRepository};
+
+use super::connection::DbPool;
+use super::models::{DbOidcClient, NewDbOidcClient};
+use super::schema::oidc_clients;
+
+/// Diesel-based implementation of OidcClientRepository
+pub struct DieselOidcClientRepository {
+    pool: DbPool,
+}
+
+impl DieselOidcClientRepository {
+    /// Create a new repository instance
+    #[must_use]
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get a connection from the pool
+    fn conn(&self) -> Result<super::connection::PooledDbConnection, AuthError> {
+        self.pool
+            .get()
+            .map_err(|e| AuthError::Internal(format!("Failed to get database connection: {}", e)))
+    }
+}
+
+/// Convert a database row to the domain entity
+fn to_domain(db_client: DbOidcClient) -> OidcClient {
+    OidcClient::from_persistence(
+        db_client.client_id,
+        db_client.name,
+        db_client.redirect_uris,
+        db_client.created_at,
+    )
+}
+
+impl OidcClientRepository for DieselOidcClientRepository {
+    fn save(&self, client: &OidcClient) -> Result<OidcClient, AuthError> {
+        let mut conn = self.conn()?;
+
+        let new_client = NewDbOidcClient {
+            client_id: client.client_id(),
+            name: client.name(),
+            redirect_uris: client.redirect_uris().iter().map(String::as_str).collect(),
+            created_at: client.created_at(),
+        };
+
+        // Re-registering keeps the original registration time
+        let db_client: DbOidcClient = diesel::insert_into(oidc_clients::table)
+            .values(&new_client)
+            .on_conflict(oidc_clients::client_id)
+            .do_update()
+            .set((
+                oidc_clients::name.eq(excluded(oidc_clients::name)),
+                oidc_clients::redirect_uris.eq(excluded(oidc_clients::redirect_uris)),
+            ))
+            .returning(DbOidcClient::as_returning())
+            .get_result(&mut conn)
+            .map_err(|e| AuthError::Internal(format!("Failed to save OIDC client: {}", e)))?;
+
+        Ok(to_domain(db_client))
+    }
+
+    fn find_by_id(&self, client_id: &str) -> Result<OidcClient, AuthError> {
+        let mut conn = self.conn()?;
+
+        let db_client: DbOidcClient = oidc_clients::table
+            .find(client_id)
+            .select(DbOidcClient::as_select())
+            .first(&mut conn)
+            .map_err(|e| match e {
+                diesel::result::Error::NotFound => AuthError::InvalidClient,
+                _ => AuthError::Internal(format!("Database error: {}", e)),
+            })?;
+
+        Ok(to_domain(db_client))
+    }
+}
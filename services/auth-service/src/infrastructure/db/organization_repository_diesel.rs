@@ -0,0 +1,145 @@
+//! Diesel implementation of the OrganizationRepository trait
+
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::domain::error::AuthError;
+use crate::domain::organization::{Membership, OrgRole, Organization, OrganizationRepository};
+
+use super::connection::DbPool;
+use super::models::{
+    DbOrganization, DbOrganizationMembership, NewDbOrganization, NewDbOrganizationMembership,
+};
+use super::schema::{organization_memberships, organizations};
+
+/// Diesel-based implementation of OrganizationRepository
+pub struct DieselOrganizationRepository {
+    pool: DbPool,
+}
+
+impl DieselOrganizationRepository {
+    /// Create a new repository instance
+    #[must_use]
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get a connection from the pool
+    fn conn(&self) -> Result<super::connection::PooledDbConnection, AuthError> {
+        self.pool
+            .get()
+            .map_err(|e| AuthError::Internal(format!("Failed to get database connection: {}", e)))
+    }
+}
+
+/// Parse a stored organization role
+pub(super) fn parse_org_role(name: &str) -> Result<OrgRole, AuthError> {
+    name.parse::<OrgRole>().map_err(|_| {
+        AuthError::Internal(format!("Unknown organization role in database: {}", name))
+    })
+}
+
+/// Convert a membership row to the domain entity
+pub(super) fn membership(db_membership: DbOrganizationMembership) -> Result<Membership, AuthError> {
+    Ok(Membership::from_persistence(
+        db_membership.organization_id,
+        db_membership.user_id,
+        parse_org_role(&db_membership.role)?,
+        db_membership.created_at,
+    ))
+}
+
+/// Insertable row for a membership
+pub(super) fn new_membership(membership: &Membership) -> NewDbOrganizationMembership<'static> {
+    NewDbOrganizationMembership {
+        organization_id: membership.organization_id(),
+        user_id: membership.user_id(),
+        role: membership.role().as_str(),
+        created_at: membership.created_at(),
+    }
+}
+
+impl OrganizationRepository for DieselOrganizationRepository {
+    fn create(&self, organization: &Organization, owner: &Membership) -> Result<(), AuthError> {
+        let mut conn = self.conn()?;
+
+        let new_organization = NewDbOrganization {
+            id: organization.id(),
+            name: organization.name(),
+            created_at: organization.created_at(),
+        };
+
+        conn.transaction::<(), diesel::result::Error, _>(|conn| {
+            diesel::insert_into(organizations::table)
+                .values(&new_organization)
+                .execute(conn)?;
+            diesel::insert_into(organization_memberships::table)
+                .values(&new_membership(owner))
+                .execute(conn)?;
+            Ok(())
+        })
+        .map_err(|e| AuthError::Internal(format!("Failed to create organization: {}", e)))
+    }
+
+    fn find_by_id(&self, id: Uuid) -> Result<Organization, AuthError> {
+        let mut conn = self.conn()?;
+
+        let db_organization: DbOrganization = organizations::table
+            .find(id)
+            .first(&mut conn)
+            .map_err(|e| match e {
+                diesel::result::Error::NotFound => AuthError::OrganizationNotFound,
+                _ => AuthError::Internal(format!("Database error: {}", e)),
+            })?;
+
+        Ok(Organization::from_persistence(
+            db_organization.id,
+            db_organization.name,
+            db_organization.created_at,
+        ))
+    }
+
+    fn find_membership(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<Membership>, AuthError> {
+        let mut conn = self.conn()?;
+
+        let db_membership: Option<DbOrganizationMembership> = organization_memberships::table
+            .find((organization_id, user_id))
+            .first(&mut conn)
+            .optional()
+            .map_err(|e| AuthError::Internal(format!("Database error: {}", e)))?;
+
+        db_membership.map(membership).transpose()
+    }
+
+    fn list_for_user(&self, user_id: Uuid) -> Result<Vec<(Organization, Membership)>, AuthError> {
+        let mut conn = self.conn()?;
+
+        let rows: Vec<(DbOrganization, DbOrganizationMembership)> = organizations::table
+            .inner_join(organization_memberships::table)
+            .filter(organization_memberships::user_id.eq(user_id))
+            .order(organization_memberships::created_at.asc())
+            .select((
+                DbOrganization::as_select(),
+                DbOrganizationMembership::as_select(),
+            ))
+            .load(&mut conn)
+            .map_err(|e| AuthError::Internal(format!("Database error: {}", e)))?;
+
+        rows.into_iter()
+            .map(|(db_organization, db_membership)| {
+                Ok((
+                    Organization::from_persistence(
+                        db_organization.id,
+                        db_organization.name,
+                        db_organization.created_at,
+                    ),
+                    membership(db_membership)?,
+                ))
+            })
+            .collect()
+    }
+}
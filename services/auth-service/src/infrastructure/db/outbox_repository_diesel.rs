@@ -0,0 +1,159 @@
+//! Diesel implementation of the OutboxRepository trait
+
+use chrono::{DateTime, Utc};
+use diesel::dsl::sql;
+use diesel::prelude::*;
+use diesel::sql_types::Bool;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::domain::error::AuthError;
+use crate::domain::events::AuthEvent;
+use crate::domain::outbox::{OutboxMessage, OutboxRepository, RelayLease};
+use crate::infrastructure::events::envelope;
+
+use super::connection::{DbPool, PooledDbConnection};
+use super::models::{DbOutboxEvent, NewDbOutboxEvent};
+use super::schema::outbox;
+
+/// Advisory lock key of the outbox relay, shared by every replica
+const RELAY_LOCK_KEY: i64 = 0x6f75_7462_6f78;
+
+/// Session-level advisory lock on the outbox, released when dropped
+///
+/// Postgres also releases it if the connection is lost, so a crashed relay
+/// never keeps the others waiting.
+struct RelayLock {
+    conn: PooledDbConnection,
+}
+
+impl Drop for RelayLock {
+    fn drop(&mut self) {
+        let unlocked = diesel::select(sql::<Bool>(&format!(
+            "pg_advisory_unlock({})",
+            RELAY_LOCK_KEY
+        )))
+        .get_result::<bool>(&mut self.conn);
+        if let Err(e) = unlocked {
+            warn!("Failed to release the outbox relay lock: {}", e);
+        }
+    }
+}
+
+/// Diesel-based implementation of OutboxRepository
+pub struct DieselOutboxRepository {
+    pool: DbPool,
+}
+
+impl DieselOutboxRepository {
+    /// Create a new repository instance
+    #[must_use]
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get a connection from the pool
+    fn conn(&self) -> Result<PooledDbConnection, AuthError> {
+        self.pool
+            .get()
+            .map_err(|e| AuthError::Internal(format!("Failed to get database connection: {}", e)))
+    }
+}
+
+impl OutboxRepository for DieselOutboxRepository {
+    fn try_lease_relay(&self) -> Result<Option<RelayLease>, AuthError> {
+        let mut conn = self.conn()?;
+
+        let locked = diesel::select(sql::<Bool>(&format!(
+            "pg_try_advisory_lock({})",
+            RELAY_LOCK_KEY
+        )))
+        .get_result::<bool>(&mut conn)
+        .map_err(|e| AuthError::Internal(format!("Database error: {}", e)))?;
+
+        Ok(locked.then(|| RelayLease::new(RelayLock { conn })))
+    }
+
+    fn find_unpublished(&self, limit: usize) -> Result<Vec<OutboxMessage>, AuthError> {
+        let mut conn = self.conn()?;
+
+        let db_events: Vec<DbOutboxEvent> = outbox::table
+            .filter(outbox::published_at.is_null())
+            .order(outbox::sequence.asc())
+            .limit(limit as i64)
+            .select(DbOutboxEvent::as_select())
+            .load(&mut conn)
+            .map_err(|e| AuthError::Internal(format!("Database error: {}", e)))?;
+
+        Ok(db_events
+            .into_iter()
+            .map(db_outbox_event_to_domain)
+            .collect())
+    }
+
+    fn mark_published(&self, sequences: &[i64], now: DateTime<Utc>) -> Result<(), AuthError> {
+        let mut conn = self.conn()?;
+
+        diesel::update(outbox::table.filter(outbox::sequence.eq_any(sequences)))
+            .set(outbox::published_at.eq(now))
+            .execute(&mut conn)
+            .map_err(|e| AuthError::Internal(format!("Database error: {}", e)))?;
+
+        Ok(())
+    }
+
+    fn purge_published(&self, cutoff: DateTime<Utc>) -> Result<usize, AuthError> {
+        let mut conn = self.conn()?;
+
+        diesel::delete(outbox::table.filter(outbox::published_at.lt(cutoff)))
+            .execute(&mut conn)
+            .map_err(|e| AuthError::Internal(format!("Database error: {}", e)))
+    }
+}
+
+/// Store events in the outbox
+///
+/// Meant to run inside the transaction that makes the announced change.
+pub(super) fn insert_events(
+    conn: &mut PooledDbConnection,
+    events: &[AuthEvent],
+) -> Result<(), diesel::result::Error> {
+    if events.is_empty() {
+        return Ok(());
+    }
+
+    // Rows are numbered in insertion order, which keeps the events in order
+    diesel::insert_into(outbox::table)
+        .values(&to_new_db_outbox_events(events))
+        .execute(conn)?;
+
+    Ok(())
+}
+
+/// Build outbox rows for insertion, one per event
+pub(super) fn to_new_db_outbox_events(events: &[AuthEvent]) -> Vec<NewDbOutboxEvent<'static>> {
+    events
+        .iter()
+        .map(|event| NewDbOutboxEvent {
+            id: Uuid::new_v4(),
+            aggregate_id: event.user_id(),
+            event_type: event.name(),
+            schema_version: event.schema_version(),
+            payload: envelope::payload(event),
+            occurred_at: event.occurred_at(),
+        })
+        .collect()
+}
+
+/// Convert database model to domain message
+fn db_outbox_event_to_domain(db_event: DbOutboxEvent) -> OutboxMessage {
+    OutboxMessage {
+        sequence: db_event.sequence,
+        id: db_event.id,
+        aggregate_id: db_event.aggregate_id,
+        event_type: db_event.event_type,
+        schema_version: db_event.schema_version,
+        payload: db_event.payload,
+        occurred_at: db_event.occurred_at,
+    }
+}
@@ -0,0 +1,116 @@
+//! Diesel implementation of the PasswordResetTokenRepository trait
+
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+
+use crate::domain::error::AuthError;
+use crate::domain::password_reset::{PasswordResetToken, PasswordResetTokenRepository};
+
+use super::connection::DbPool;
+use super::models::{DbPasswordResetToken, NewDbPasswordResetToken};
+use super::schema::password_reset_tokens;
+
+/// Diesel-based implementation of PasswordResetTokenRepository
+pub struct DieselPasswordResetTokenRepository {
+    pool: DbPool,
+}
+
+impl DieselPasswordResetTokenRepository {
+    /// Create a new repository instance
+    #[must_use]
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get a connection from the pool
+    fn conn(&self) -> Result<super::connection::PooledDbConnection, AuthError> {
+        self.pool
+            .get()
+            .map_err(|e| AuthError::Internal(format!("Failed to get database connection: {}", e)))
+    }
+}
+
+impl PasswordResetTokenRepository for DieselPasswordResetTokenRepository {
+    fn create(&self, token: &PasswordResetToken) -> Result<(), AuthError> {
+        let mut conn = self.conn()?;
+
+        let new_token = NewDbPasswordResetToken {
+            id: token.id(),
+            user_id: token.user_id(),
+            token_hash: token.token_hash(),
+            expires_at: token.expires_at(),
+            created_at: token.created_at(),
+        };
+
+        diesel::insert_into(password_reset_tokens::table)
+            .values(&new_token)
+            .execute(&mut conn)
+            .map_err(|e| AuthError::Internal(format!("Failed to create reset token: {}", e)))?;
+
+        Ok(())
+    }
+
+    fn find_by_hash(&self, token_hash: &str) -> Result<PasswordResetToken, AuthError> {
+        let mut conn = self.conn()?;
+
+        let db_token: DbPasswordResetToken = password_reset_tokens::table
+            .filter(password_reset_tokens::token_hash.eq(token_hash))
+            .first(&mut conn)
+            .map_err(|e| match e {
+                diesel::result::Error::NotFound => AuthError::InvalidToken,
+                _ => AuthError::Internal(format!("Database error: {}", e)),
+            })?;
+
+        Ok(PasswordResetToken::from_persistence(
+            db_token.id,
+            db_token.user_id,
+            db_token.token_hash,
+            db_token.expires_at,
+            db_token.created_at,
+            db_token.used_at,
+        ))
+    }
+
+    fn consume(&self, token: &PasswordResetToken) -> Result<bool, AuthError> {
+        let mut conn = self.conn()?;
+        let now = Utc::now();
+
+        conn.transaction::<bool, diesel::result::Error, _>(|conn| {
+            // Checking `used_at` in the UPDATE itself makes concurrent resets
+            // race safely
+            let updated_rows = diesel::update(
+                password_reset_tokens::table
+                    .filter(password_reset_tokens::id.eq(token.id()))
+                    .filter(password_reset_tokens::used_at.is_null()),
+            )
+            .set(password_reset_tokens::used_at.eq(now))
+            .execute(conn)?;
+
+            if updated_rows == 0 {
+                return Ok(false);
+            }
+
+            // Links mailed earlier must not work after a reset
+            diesel::update(
+                password_reset_tokens::table
+                    .filter(password_reset_tokens::user_id.eq(token.user_id()))
+                    .filter(password_reset_tokens::used_at.is_null()),
+            )
+            .set(password_reset_tokens::used_at.eq(now))
+            .execute(conn)?;
+
+            Ok(true)
+        })
+        .map_err(|e| AuthError::Internal(format!("Failed to consume reset token: {}", e)))
+    }
+
+    fn purge_expired(&self, now: DateTime<Utc>) -> Result<usize, AuthError> {
+        let mut conn = self.conn()?;
+
+        diesel::delete(
+            password_reset_tokens::table.filter(password_reset_tokens::expires_at.lt(now)),
+        )
+        .execute(&mut conn)
+        .map_err(|e| AuthError::Internal(format!("Failed to purge reset tokens: {}", e)))
+    }
+}
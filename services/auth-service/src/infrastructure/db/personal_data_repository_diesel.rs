@@ -0,0 +1,220 @@
+//! Diesel implementation of the PersonalDataRepository trait
+
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::domain::audit::AuditEvent;
+use crate::domain::error::AuthError;
+use crate::domain::federation::UserIdentity;
+use crate::domain::lockout::ThrottleKey;
+use crate::domain::organization::{Membership, Organization, OrganizationRepository};
+use crate::domain::personal_data::{PersonalDataRepository, SessionRecord};
+use crate::domain::user::{HashedPassword, User};
+
+use super::async_user_identity_repository_diesel::db_identity_to_domain;
+use super::audit_event_repository_diesel::db_audit_event_to_domain;
+use super::connection::DbPool;
+use super::models::{DbAuditEvent, DbSession, DbUserIdentity};
+use super::organization_repository_diesel::DieselOrganizationRepository;
+use super::outbox_repository_diesel::insert_events;
+use super::schema::{
+    api_keys, auth_audit_events, email_verification_tokens, login_throttles, magic_link_tokens,
+    mfa_challenges, mfa_recovery_codes, organization_invitations, organization_memberships,
+    outbox, password_reset_tokens, refresh_tokens, sessions, totp_credentials, user_identities,
+    user_roles, users,
+};
+
+/// Diesel-based implementation of PersonalDataRepository
+pub struct DieselPersonalDataRepository {
+    pool: DbPool,
+}
+
+impl DieselPersonalDataRepository {
+    /// Create a new repository instance
+    #[must_use]
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get a connection from the pool
+    fn conn(&self) -> Result<super::connection::PooledDbConnection, AuthError> {
+        self.pool
+            .get()
+            .map_err(|e| AuthError::Internal(format!("Failed to get database connection: {}", e)))
+    }
+}
+
+impl PersonalDataRepository for DieselPersonalDataRepository {
+    fn find_sessions(&self, user_id: Uuid) -> Result<Vec<SessionRecord>, AuthError> {
+        let mut conn = self.conn()?;
+
+        let rows: Vec<DbSession> = sessions::table
+            .filter(sessions::user_id.eq(user_id))
+            .order(sessions::created_at.desc())
+            .select(DbSession::as_select())
+            .load(&mut conn)
+            .map_err(|e| AuthError::Internal(format!("Database error: {}", e)))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|session| SessionRecord {
+                family_id: session.id,
+                started_at: session.created_at,
+                last_used_at: session.last_seen_at,
+                expires_at: session.expires_at,
+                ended_at: session.revoked_at,
+                user_agent: session.user_agent,
+                ip_address: session.ip_address.and_then(|ip| ip.parse().ok()),
+            })
+            .collect())
+    }
+
+    fn find_identities(&self, user_id: Uuid) -> Result<Vec<UserIdentity>, AuthError> {
+        let mut conn = self.conn()?;
+
+        let rows: Vec<DbUserIdentity> = user_identities::table
+            .filter(user_identities::user_id.eq(user_id))
+            .order(user_identities::created_at.asc())
+            .select(DbUserIdentity::as_select())
+            .load(&mut conn)
+            .map_err(|e| AuthError::Internal(format!("Database error: {}", e)))?;
+
+        Ok(rows.into_iter().map(db_identity_to_domain).collect())
+    }
+
+    fn find_organizations(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<(Organization, Membership)>, AuthError> {
+        DieselOrganizationRepository::new(self.pool.clone()).list_for_user(user_id)
+    }
+
+    fn find_audit_events(&self, user_id: Uuid) -> Result<Vec<AuditEvent>, AuthError> {
+        let mut conn = self.conn()?;
+
+        let rows: Vec<DbAuditEvent> = auth_audit_events::table
+            .filter(
+                auth_audit_events::actor_id
+                    .eq(user_id)
+                    .or(auth_audit_events::target_id.eq(user_id)),
+            )
+            .order((auth_audit_events::occurred_at.desc(), auth_audit_events::id.desc()))
+            .select(DbAuditEvent::as_select())
+            .load(&mut conn)
+            .map_err(|e| AuthError::Internal(format!("Database error: {}", e)))?;
+
+        rows.into_iter().map(db_audit_event_to_domain).collect()
+    }
+
+    fn erase(&self, user: &User, previous_email: &str) -> Result<(), AuthError> {
+        let mut conn = self.conn()?;
+        let user_id = user.id().as_uuid();
+        let throttle = ThrottleKey::email(previous_email);
+        let legacy_detail = legacy_login_detail(previous_email);
+
+        let updated_rows = conn
+            .transaction::<usize, diesel::result::Error, _>(|conn| {
+                let updated_rows = diesel::update(users::table.filter(users::id.eq(user_id)))
+                    .set((
+                        users::email.eq(user.email().as_str()),
+                        users::hashed_password
+                            .eq(user.hashed_password().map(HashedPassword::as_str)),
+                        users::display_name.eq(user.display_name()),
+                        users::is_active.eq(user.is_active()),
+                        users::email_verified_at.eq(user.email_verified_at()),
+                        users::updated_at.eq(user.updated_at()),
+                    ))
+                    .execute(conn)?;
+
+                if updated_rows == 0 {
+                    return Ok(0);
+                }
+
+                diesel::delete(user_roles::table.filter(user_roles::user_id.eq(user_id)))
+                    .execute(conn)?;
+                diesel::delete(refresh_tokens::table.filter(refresh_tokens::user_id.eq(user_id)))
+                    .execute(conn)?;
+                diesel::delete(sessions::table.filter(sessions::user_id.eq(user_id)))
+                    .execute(conn)?;
+                diesel::delete(
+                    password_reset_tokens::table.filter(password_reset_tokens::user_id.eq(user_id)),
+                )
+                .execute(conn)?;
+                diesel::delete(
+                    magic_link_tokens::table.filter(magic_link_tokens::user_id.eq(user_id)),
+                )
+                .execute(conn)?;
+                diesel::delete(
+                    email_verification_tokens::table
+                        .filter(email_verification_tokens::user_id.eq(user_id)),
+                )
+                .execute(conn)?;
+                diesel::delete(
+                    totp_credentials::table.filter(totp_credentials::user_id.eq(user_id)),
+                )
+                .execute(conn)?;
+                diesel::delete(
+                    mfa_recovery_codes::table.filter(mfa_recovery_codes::user_id.eq(user_id)),
+                )
+                .execute(conn)?;
+                diesel::delete(mfa_challenges::table.filter(mfa_challenges::user_id.eq(user_id)))
+                    .execute(conn)?;
+                diesel::delete(api_keys::table.filter(api_keys::user_id.eq(user_id)))
+                    .execute(conn)?;
+                diesel::delete(user_identities::table.filter(user_identities::user_id.eq(user_id)))
+                    .execute(conn)?;
+                diesel::delete(
+                    organization_memberships::table
+                        .filter(organization_memberships::user_id.eq(user_id)),
+                )
+                .execute(conn)?;
+                diesel::delete(
+                    organization_invitations::table
+                        .filter(organization_invitations::email.eq(previous_email)),
+                )
+                .execute(conn)?;
+                diesel::delete(
+                    login_throttles::table.find((throttle.scope().as_str(), throttle.value())),
+                )
+                .execute(conn)?;
+                // Audit entries stay, keyed by the now meaningless id, but lose the
+                // client and detail; older failed logins kept the address tried
+                diesel::update(
+                    auth_audit_events::table.filter(
+                        auth_audit_events::actor_id
+                            .eq(user_id)
+                            .or(auth_audit_events::target_id.eq(user_id))
+                            .or(auth_audit_events::detail.ilike(&legacy_detail)),
+                    ),
+                )
+                .set((
+                    auth_audit_events::ip_address.eq(None::<String>),
+                    auth_audit_events::user_agent.eq(None::<String>),
+                    auth_audit_events::detail.eq(None::<String>),
+                ))
+                .execute(conn)?;
+                // Earlier events may carry the old address; the erasure supersedes them
+                diesel::delete(outbox::table.filter(outbox::aggregate_id.eq(user_id)))
+                    .execute(conn)?;
+                insert_events(conn, user.pending_events())?;
+
+                Ok(updated_rows)
+            })
+            .map_err(|e| AuthError::Internal(format!("Failed to erase user: {}", e)))?;
+
+        if updated_rows == 0 {
+            return Err(AuthError::UserNotFound);
+        }
+
+        Ok(())
+    }
+}
+
+/// Pattern matching the detail of failed logins that recorded `email`
+fn legacy_login_detail(email: &str) -> String {
+    let escaped = email
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%: {}", escaped)
+}
@@ -0,0 +1,165 @@
+//! Diesel implementation of the RefreshTokenRepository trait
+
+use chrono::Utc;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::domain::error::AuthError;
+use crate::domain::refresh_token::{RefreshToken, RefreshTokenRepository};
+
+use super::connection::DbPool;
+use super::models::{DbRefreshToken, NewDbRefreshToken};
+use super::schema::{refresh_tokens, sessions};
+
+/// Diesel-based implementation of RefreshTokenRepository
+pub struct DieselRefreshTokenRepository {
+    pool: DbPool,
+}
+
+impl DieselRefreshTokenRepository {
+    /// Create a new repository instance
+    #[must_use]
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get a connection from the pool
+    fn conn(&self) -> Result<super::connection::PooledDbConnection, AuthError> {
+        self.pool
+            .get()
+            .map_err(|e| AuthError::Internal(format!("Failed to get database connection: {}", e)))
+    }
+}
+
+impl RefreshTokenRepository for DieselRefreshTokenRepository {
+    fn create(&self, token: &RefreshToken) -> Result<(), AuthError> {
+        let mut conn = self.conn()?;
+
+        diesel::insert_into(refresh_tokens::table)
+            .values(&to_new_db_token(token))
+            .execute(&mut conn)
+            .map_err(|e| AuthError::Internal(format!("Failed to create refresh token: {}", e)))?;
+
+        Ok(())
+    }
+
+    fn find_by_hash(&self, token_hash: &str) -> Result<RefreshToken, AuthError> {
+        let mut conn = self.conn()?;
+
+        let db_token: DbRefreshToken = refresh_tokens::table
+            .filter(refresh_tokens::token_hash.eq(token_hash))
+            .first(&mut conn)
+            .map_err(|e| match e {
+                diesel::result::Error::NotFound => AuthError::InvalidToken,
+                _ => AuthError::Internal(format!("Database error: {}", e)),
+            })?;
+
+        Ok(db_token_to_domain(db_token))
+    }
+
+    fn rotate(&self, current_id: Uuid, next: &RefreshToken) -> Result<bool, AuthError> {
+        let mut conn = self.conn()?;
+
+        conn.transaction::<bool, diesel::result::Error, _>(|conn| {
+            // Only an unrotated, unrevoked token may be exchanged. Checking this
+            // in the UPDATE itself makes concurrent refreshes race safely.
+            let updated_rows = diesel::update(
+                refresh_tokens::table
+                    .filter(refresh_tokens::id.eq(current_id))
+                    .filter(refresh_tokens::rotated_at.is_null())
+                    .filter(refresh_tokens::revoked_at.is_null()),
+            )
+            .set(refresh_tokens::rotated_at.eq(Utc::now()))
+            .execute(conn)?;
+
+            if updated_rows == 0 {
+                return Ok(false);
+            }
+
+            diesel::insert_into(refresh_tokens::table)
+                .values(&to_new_db_token(next))
+                .execute(conn)?;
+
+            Ok(true)
+        })
+        .map_err(|e| AuthError::Internal(format!("Failed to rotate refresh token: {}", e)))
+    }
+
+    fn revoke_family(&self, family_id: Uuid) -> Result<(), AuthError> {
+        let mut conn = self.conn()?;
+        let now = Utc::now();
+
+        conn.transaction::<(), diesel::result::Error, _>(|conn| {
+            diesel::update(
+                refresh_tokens::table
+                    .filter(refresh_tokens::family_id.eq(family_id))
+                    .filter(refresh_tokens::revoked_at.is_null()),
+            )
+            .set(refresh_tokens::revoked_at.eq(now))
+            .execute(conn)?;
+
+            diesel::update(
+                sessions::table
+                    .filter(sessions::id.eq(family_id))
+                    .filter(sessions::revoked_at.is_null()),
+            )
+            .set(sessions::revoked_at.eq(now))
+            .execute(conn)?;
+
+            Ok(())
+        })
+        .map_err(|e| AuthError::Internal(format!("Failed to revoke refresh tokens: {}", e)))
+    }
+
+    fn revoke_all_for_user(&self, user_id: Uuid) -> Result<(), AuthError> {
+        let mut conn = self.conn()?;
+        let now = Utc::now();
+
+        conn.transaction::<(), diesel::result::Error, _>(|conn| {
+            diesel::update(
+                refresh_tokens::table
+                    .filter(refresh_tokens::user_id.eq(user_id))
+                    .filter(refresh_tokens::revoked_at.is_null()),
+            )
+            .set(refresh_tokens::revoked_at.eq(now))
+            .execute(conn)?;
+
+            diesel::update(
+                sessions::table
+                    .filter(sessions::user_id.eq(user_id))
+                    .filter(sessions::revoked_at.is_null()),
+            )
+            .set(sessions::revoked_at.eq(now))
+            .execute(conn)?;
+
+            Ok(())
+        })
+        .map_err(|e| AuthError::Internal(format!("Failed to revoke refresh tokens: {}", e)))
+    }
+}
+
+/// Convert domain entity to insertable model
+pub(super) fn to_new_db_token(token: &RefreshToken) -> NewDbRefreshToken<'_> {
+    NewDbRefreshToken {
+        id: token.id(),
+        user_id: token.user_id(),
+        family_id: token.family_id(),
+        token_hash: token.token_hash(),
+        expires_at: token.expires_at(),
+        created_at: token.created_at(),
+    }
+}
+
+/// Convert database model to domain entity
+pub(super) fn db_token_to_domain(db_token: DbRefreshToken) -> RefreshToken {
+    RefreshToken::from_persistence(
+        db_token.id,
+        db_token.user_id,
+        db_token.family_id,
+        db_token.token_hash,
+        db_token.expires_at,
+        db_token.created_at,
+        db_token.rotated_at,
+        db_token.revoked_at,
+    )
+}
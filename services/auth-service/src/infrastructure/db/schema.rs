@@ -2,14 +2,324 @@
 // This file will be generated by `diesel migration run`
 // Placeholder until migrations are set up
 
+diesel::table! {
+    api_keys (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        name -> Varchar,
+        prefix -> Varchar,
+        key_hash -> Varchar,
+        scopes -> Array<Text>,
+        expires_at -> Nullable<Timestamptz>,
+        last_used_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+        revoked_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    auth_audit_events (id) {
+        id -> Uuid,
+        occurred_at -> Timestamptz,
+        action -> Varchar,
+        outcome -> Varchar,
+        actor_id -> Nullable<Uuid>,
+        target_id -> Nullable<Uuid>,
+        ip_address -> Nullable<Varchar>,
+        user_agent -> Nullable<Varchar>,
+        detail -> Nullable<Varchar>,
+    }
+}
+
+diesel::table! {
+    authorization_codes (code_hash) {
+        code_hash -> Varchar,
+        client_id -> Varchar,
+        user_id -> Uuid,
+        redirect_uri -> Text,
+        scopes -> Array<Text>,
+        code_challenge -> Varchar,
+        nonce -> Nullable<Text>,
+        expires_at -> Timestamptz,
+        created_at -> Timestamptz,
+        session_id -> Uuid,
+    }
+}
+
+diesel::table! {
+    email_verification_tokens (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        email -> Varchar,
+        token_hash -> Varchar,
+        expires_at -> Timestamptz,
+        created_at -> Timestamptz,
+        used_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    federated_login_requests (state_hash) {
+        state_hash -> Varchar,
+        provider -> Varchar,
+        nonce -> Varchar,
+        code_verifier -> Varchar,
+        expires_at -> Timestamptz,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    jwt_signing_keys (kid) {
+        kid -> Varchar,
+        algorithm -> Varchar,
+        private_key -> Text,
+        public_key -> Text,
+        created_at -> Timestamptz,
+        retired_at -> Nullable<Timestamptz>,
+        verify_until -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    login_throttles (scope, subject) {
+        scope -> Varchar,
+        subject -> Varchar,
+        failed_attempts -> Int4,
+        last_failed_at -> Timestamptz,
+        locked_until -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    magic_link_tokens (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        token_hash -> Varchar,
+        expires_at -> Timestamptz,
+        created_at -> Timestamptz,
+        used_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    mfa_challenges (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        token_hash -> Varchar,
+        failed_attempts -> Int4,
+        expires_at -> Timestamptz,
+        created_at -> Timestamptz,
+        used_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    mfa_recovery_codes (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        code_hash -> Varchar,
+        created_at -> Timestamptz,
+        used_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    oauth_clients (client_id) {
+        client_id -> Uuid,
+        name -> Varchar,
+        secret_hash -> Varchar,
+        scopes -> Array<Text>,
+        created_at -> Timestamptz,
+        revoked_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    oidc_clients (client_id) {
+        client_id -> Varchar,
+        name -> Varchar,
+        redirect_uris -> Array<Text>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    organization_invitations (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        email -> Varchar,
+        role -> Varchar,
+        token_hash -> Varchar,
+        invited_by -> Uuid,
+        expires_at -> Timestamptz,
+        created_at -> Timestamptz,
+        accepted_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    organization_memberships (organization_id, user_id) {
+        organization_id -> Uuid,
+        user_id -> Uuid,
+        role -> Varchar,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    organizations (id) {
+        id -> Uuid,
+        name -> Varchar,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    outbox (sequence) {
+        sequence -> Int8,
+        id -> Uuid,
+        aggregate_id -> Uuid,
+        event_type -> Varchar,
+        schema_version -> Int4,
+        payload -> Text,
+        occurred_at -> Timestamptz,
+        published_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    password_reset_tokens (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        token_hash -> Varchar,
+        expires_at -> Timestamptz,
+        created_at -> Timestamptz,
+        used_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    refresh_tokens (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        family_id -> Uuid,
+        token_hash -> Varchar,
+        expires_at -> Timestamptz,
+        created_at -> Timestamptz,
+        rotated_at -> Nullable<Timestamptz>,
+        revoked_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    revoked_tokens (jti) {
+        jti -> Varchar,
+        user_id -> Uuid,
+        expires_at -> Timestamptz,
+        revoked_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    sessions (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        user_agent -> Nullable<Varchar>,
+        ip_address -> Nullable<Varchar>,
+        created_at -> Timestamptz,
+        last_seen_at -> Timestamptz,
+        expires_at -> Timestamptz,
+        revoked_at -> Nullable<Timestamptz>,
+        organization_id -> Nullable<Uuid>,
+    }
+}
+
+diesel::table! {
+    totp_credentials (user_id) {
+        user_id -> Uuid,
+        secret -> Varchar,
+        confirmed_at -> Nullable<Timestamptz>,
+        last_used_step -> Nullable<Int8>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    user_identities (provider, subject) {
+        provider -> Varchar,
+        subject -> Varchar,
+        user_id -> Uuid,
+        email -> Nullable<Varchar>,
+        created_at -> Timestamptz,
+        last_login_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    user_roles (user_id, role) {
+        user_id -> Uuid,
+        role -> Varchar,
+        granted_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     users (id) {
         id -> Uuid,
         email -> Varchar,
-        hashed_password -> Varchar,
+        hashed_password -> Nullable<Varchar>,
         display_name -> Nullable<Varchar>,
         is_active -> Bool,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
+        email_verified_at -> Nullable<Timestamptz>,
     }
 }
+
+diesel::joinable!(api_keys -> users (user_id));
+diesel::joinable!(authorization_codes -> oidc_clients (client_id));
+diesel::joinable!(authorization_codes -> sessions (session_id));
+diesel::joinable!(authorization_codes -> users (user_id));
+diesel::joinable!(email_verification_tokens -> users (user_id));
+diesel::joinable!(magic_link_tokens -> users (user_id));
+diesel::joinable!(mfa_challenges -> users (user_id));
+diesel::joinable!(mfa_recovery_codes -> users (user_id));
+diesel::joinable!(organization_invitations -> organizations (organization_id));
+diesel::joinable!(organization_invitations -> users (invited_by));
+diesel::joinable!(organization_memberships -> organizations (organization_id));
+diesel::joinable!(organization_memberships -> users (user_id));
+diesel::joinable!(password_reset_tokens -> users (user_id));
+diesel::joinable!(refresh_tokens -> users (user_id));
+diesel::joinable!(sessions -> organizations (organization_id));
+diesel::joinable!(sessions -> users (user_id));
+diesel::joinable!(totp_credentials -> users (user_id));
+diesel::joinable!(user_identities -> users (user_id));
+diesel::joinable!(user_roles -> users (user_id));
+
+diesel::allow_tables_to_appear_in_same_query!(
+    api_keys,
+    auth_audit_events,
+    authorization_codes,
+    email_verification_tokens,
+    federated_login_requests,
+    jwt_signing_keys,
+    login_throttles,
+    magic_link_tokens,
+    mfa_challenges,
+    mfa_recovery_codes,
+    oauth_clients,
+    oidc_clients,
+    organization_invitations,
+    organization_memberships,
+    organizations,
+    outbox,
+    password_reset_tokens,
+    refresh_tokens,
+    revoked_tokens,
+    sessions,
+    totp_credentials,
+    user_identities,
+    user_roles,
+    users,
+);
@@ -0,0 +1,156 @@
+//! Diesel implementation of the SessionRepository trait
+
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::domain::error::AuthError;
+use crate::domain::session::{Session, SessionRepository};
+
+use super::connection::DbPool;
+use super::models::{DbSession, NewDbSession};
+use super::outbox_repository_diesel::insert_events;
+use super::schema::sessions;
+
+/// Diesel-based implementation of SessionRepository
+pub struct DieselSessionRepository {
+    pool: DbPool,
+}
+
+impl DieselSessionRepository {
+    /// Create a new repository instance
+    #[must_use]
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get a connection from the pool
+    fn conn(&self) -> Result<super::connection::PooledDbConnection, AuthError> {
+        self.pool
+            .get()
+            .map_err(|e| AuthError::Internal(format!("Failed to get database connection: {}", e)))
+    }
+}
+
+impl SessionRepository for DieselSessionRepository {
+    fn create(&self, session: &Session) -> Result<(), AuthError> {
+        let mut conn = self.conn()?;
+        let new_session = to_new_db_session(session);
+
+        conn.transaction::<(), diesel::result::Error, _>(|conn| {
+            diesel::insert_into(sessions::table)
+                .values(&new_session)
+                .execute(conn)?;
+            insert_events(conn, &[session.login_event()])
+        })
+        .map_err(|e| AuthError::Internal(format!("Failed to create session: {}", e)))?;
+
+        Ok(())
+    }
+
+    fn find_by_id(&self, id: Uuid) -> Result<Session, AuthError> {
+        let mut conn = self.conn()?;
+
+        let db_session: DbSession =
+            sessions::table
+                .find(id)
+                .first(&mut conn)
+                .map_err(|e| match e {
+                    diesel::result::Error::NotFound => AuthError::SessionNotFound,
+                    _ => AuthError::Internal(format!("Database error: {}", e)),
+                })?;
+
+        Ok(db_session_to_domain(db_session))
+    }
+
+    fn find_active_for_user(
+        &self,
+        user_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<Session>, AuthError> {
+        let mut conn = self.conn()?;
+
+        let db_sessions: Vec<DbSession> = sessions::table
+            .filter(sessions::user_id.eq(user_id))
+            .filter(sessions::revoked_at.is_null())
+            .filter(sessions::expires_at.gt(now))
+            .order(sessions::last_seen_at.desc())
+            .load(&mut conn)
+            .map_err(|e| AuthError::Internal(format!("Database error: {}", e)))?;
+
+        Ok(db_sessions.into_iter().map(db_session_to_domain).collect())
+    }
+
+    fn touch(
+        &self,
+        id: Uuid,
+        now: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), AuthError> {
+        let mut conn = self.conn()?;
+
+        diesel::update(sessions::table.find(id))
+            .set((
+                sessions::last_seen_at.eq(now),
+                sessions::expires_at.eq(expires_at),
+            ))
+            .execute(&mut conn)
+            .map_err(|e| AuthError::Internal(format!("Failed to update session: {}", e)))?;
+
+        Ok(())
+    }
+
+    fn set_organization(&self, id: Uuid, organization_id: Option<Uuid>) -> Result<(), AuthError> {
+        let mut conn = self.conn()?;
+
+        diesel::update(sessions::table.find(id))
+            .set(sessions::organization_id.eq(organization_id))
+            .execute(&mut conn)
+            .map_err(|e| AuthError::Internal(format!("Failed to update session: {}", e)))?;
+
+        Ok(())
+    }
+
+    fn is_revoked(&self, id: Uuid) -> Result<bool, AuthError> {
+        let mut conn = self.conn()?;
+
+        let revoked_at: Option<Option<DateTime<Utc>>> = sessions::table
+            .find(id)
+            .select(sessions::revoked_at)
+            .first(&mut conn)
+            .optional()
+            .map_err(|e| AuthError::Internal(format!("Database error: {}", e)))?;
+
+        // Only a stored, unrevoked session is live; deleted ones count as ended
+        Ok(!matches!(revoked_at, Some(None)))
+    }
+}
+
+/// Convert domain entity to insertable model
+pub(super) fn to_new_db_session(session: &Session) -> NewDbSession<'_> {
+    NewDbSession {
+        id: session.id(),
+        user_id: session.user_id(),
+        user_agent: session.user_agent(),
+        ip_address: session.ip_address().map(|ip| ip.to_string()),
+        created_at: session.created_at(),
+        last_seen_at: session.last_seen_at(),
+        expires_at: session.expires_at(),
+    }
+}
+
+/// Convert database model to domain entity
+pub(super) fn db_session_to_domain(db_session: DbSession) -> Session {
+    Session::from_persistence(
+        db_session.id,
+        db_session.user_id,
+        db_session.user_agent,
+        // Addresses are written by this service; skip any that do not parse
+        db_session.ip_address.and_then(|ip| ip.parse().ok()),
+        db_session.created_at,
+        db_session.last_seen_at,
+        db_session.expires_at,
+        db_session.revoked_at,
+        db_session.organization_id,
+    )
+}
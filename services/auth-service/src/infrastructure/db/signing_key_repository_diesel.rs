@@ -0,0 +1,99 @@
+//! Diesel implementation of the SigningKeyRepository trait
+
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+
+use crate::domain::error::AuthError;
+use crate::domain::signing_key::{SigningKey, SigningKeyRepository};
+
+use super::connection::DbPool;
+use super::models::{DbSigningKey, NewDbSigningKey};
+use super::schema::jwt_signing_keys;
+
+/// Postgres-backed JWT key ring, shared by all service replicas
+pub struct DieselSigningKeyRepository {
+    pool: DbPool,
+}
+
+impl DieselSigningKeyRepository {
+    /// Create a new repository instance
+    #[must_use]
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get a connection from the pool
+    fn conn(&self) -> Result<super::connection::PooledDbConnection, AuthError> {
+        self.pool
+            .get()
+            .map_err(|e| AuthError::Internal(format!("Failed to get database connection: {}", e)))
+    }
+}
+
+impl SigningKeyRepository for DieselSigningKeyRepository {
+    fn find_verifiable(&self, now: DateTime<Utc>) -> Result<Vec<SigningKey>, AuthError> {
+        let mut conn = self.conn()?;
+
+        let db_keys: Vec<DbSigningKey> = jwt_signing_keys::table
+            .filter(
+                jwt_signing_keys::verify_until
+                    .is_null()
+                    .or(jwt_signing_keys::verify_until.gt(now)),
+            )
+            .order(jwt_signing_keys::created_at.desc())
+            .select(DbSigningKey::as_select())
+            .load(&mut conn)
+            .map_err(|e| AuthError::Internal(format!("Database error: {}", e)))?;
+
+        db_keys.into_iter().map(db_key_to_domain).collect()
+    }
+
+    fn rotate(&self, next: &SigningKey, verify_until: DateTime<Utc>) -> Result<(), AuthError> {
+        let mut conn = self.conn()?;
+
+        let new_key = NewDbSigningKey {
+            kid: next.kid(),
+            algorithm: next.algorithm().as_str(),
+            private_key: next.private_key_pem(),
+            public_key: next.public_key_pem(),
+            created_at: next.created_at(),
+        };
+
+        conn.transaction::<(), diesel::result::Error, _>(|conn| {
+            diesel::update(jwt_signing_keys::table.filter(jwt_signing_keys::retired_at.is_null()))
+                .set((
+                    jwt_signing_keys::retired_at.eq(Utc::now()),
+                    jwt_signing_keys::verify_until.eq(verify_until),
+                ))
+                .execute(conn)?;
+
+            diesel::insert_into(jwt_signing_keys::table)
+                .values(&new_key)
+                .execute(conn)?;
+
+            Ok(())
+        })
+        .map_err(|e| AuthError::Internal(format!("Failed to rotate signing key: {}", e)))
+    }
+
+    fn purge_expired(&self, now: DateTime<Utc>) -> Result<usize, AuthError> {
+        let mut conn = self.conn()?;
+
+        diesel::delete(jwt_signing_keys::table.filter(jwt_signing_keys::verify_until.lt(now)))
+            .execute(&mut conn)
+            .map_err(|e| AuthError::Internal(format!("Failed to purge signing keys: {}", e)))
+    }
+}
+
+/// Convert database model to domain entity
+fn db_key_to_domain(db_key: DbSigningKey) -> Result<SigningKey, AuthError> {
+    Ok(SigningKey::from_persistence(
+        db_key.kid,
+        db_key.algorithm.parse()?,
+        db_key.private_key,
+        db_key.public_key,
+        db_key.created_at,
+        db_key.retired_at,
+        db_key.verify_until,
+    ))
+}
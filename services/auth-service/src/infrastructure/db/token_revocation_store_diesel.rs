@@ -0,0 +1,73 @@
+//! Diesel implementation of the TokenRevocationStore trait
+
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::domain::error::AuthError;
+use crate::domain::token_revocation::TokenRevocationStore;
+
+use super::connection::DbPool;
+use super::models::NewDbRevokedToken;
+use super::schema::revoked_tokens;
+
+/// Postgres-backed revocation list, shared by all service replicas
+pub struct DieselTokenRevocationStore {
+    pool: DbPool,
+}
+
+impl DieselTokenRevocationStore {
+    /// Create a new store instance
+    #[must_use]
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get a connection from the pool
+    fn conn(&self) -> Result<super::connection::PooledDbConnection, AuthError> {
+        self.pool
+            .get()
+            .map_err(|e| AuthError::Internal(format!("Failed to get database connection: {}", e)))
+    }
+}
+
+impl TokenRevocationStore for DieselTokenRevocationStore {
+    fn revoke(&self, jti: &str, user_id: Uuid, expires_at: DateTime<Utc>) -> Result<(), AuthError> {
+        let mut conn = self.conn()?;
+
+        let new_entry = NewDbRevokedToken {
+            jti,
+            user_id,
+            expires_at,
+            revoked_at: Utc::now(),
+        };
+
+        diesel::insert_into(revoked_tokens::table)
+            .values(&new_entry)
+            .on_conflict_do_nothing()
+            .execute(&mut conn)
+            .map_err(|e| AuthError::Internal(format!("Failed to revoke token: {}", e)))?;
+
+        Ok(())
+    }
+
+    fn is_revoked(&self, jti: &str) -> Result<bool, AuthError> {
+        let mut conn = self.conn()?;
+
+        let count: i64 = revoked_tokens::table
+            .filter(revoked_tokens::jti.eq(jti))
+            .count()
+            .get_result(&mut conn)
+            .map_err(|e| AuthError::Internal(format!("Database error: {}", e)))?;
+
+        Ok(count > 0)
+    }
+
+    fn purge_expired(&self, now: DateTime<Utc>) -> Result<usize, AuthError> {
+        let mut conn = self.conn()?;
+
+        diesel::delete(revoked_tokens::table.filter(revoked_tokens::expires_at.lt(now)))
+            .execute(&mut conn)
+            .map_err(|e| AuthError::Internal(format!("Failed to purge revoked tokens: {}", e)))
+    }
+}
@@ -1,15 +1,23 @@
-//! Diesel implementation of the UserRepository trait
+//! Diesel implementation of the UserRepository and UserSearchRepository traits
 
+use std::collections::HashMap;
+
+use chrono::Utc;
 use diesel::prelude::*;
 use uuid::Uuid;
 
 use crate::domain::auth::UserRepository;
 use crate::domain::error::AuthError;
-use crate::domain::user::User;
+use crate::domain::role::Role;
+use crate::domain::user::{HashedPassword, User};
+use crate::domain::user_search::{
+    EmailMatch, UserCursor, UserFilter, UserPage, UserSearchRepository,
+};
 
-use super::connection::DbPool;
-use super::models::{DbUser, NewDbUser};
-use super::schema::users;
+use super::connection::{DbPool, PooledDbConnection};
+use super::models::{DbUser, NewDbUser, NewDbUserRole};
+use super::outbox_repository_diesel::insert_events;
+use super::schema::{user_roles, users};
 
 /// Diesel-based implementation of UserRepository
 pub struct DieselUserRepository {
@@ -24,11 +32,31 @@ impl DieselUserRepository {
     }
 
     /// Get a connection from the pool
-    fn conn(&self) -> Result<super::connection::PooledDbConnection, AuthError> {
+    fn conn(&self) -> Result<PooledDbConnection, AuthError> {
         self.pool
             .get()
             .map_err(|e| AuthError::Internal(format!("Failed to get database connection: {}", e)))
     }
+
+    /// Load a user's roles and attach them to the domain entity
+    fn with_roles(
+        &self,
+        conn: &mut PooledDbConnection,
+        db_user: DbUser,
+    ) -> Result<User, AuthError> {
+        let names: Vec<String> = user_roles::table
+            .filter(user_roles::user_id.eq(db_user.id))
+            .select(user_roles::role)
+            .load(conn)
+            .map_err(|e| AuthError::Internal(format!("Database error: {}", e)))?;
+
+        let roles = names
+            .iter()
+            .map(|name| parse_role(name))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(db_user_to_domain(db_user).with_roles(roles))
+    }
 }
 
 impl UserRepository for DieselUserRepository {
@@ -43,7 +71,7 @@ impl UserRepository for DieselUserRepository {
                 _ => AuthError::Internal(format!("Database error: {}", e)),
             })?;
 
-        Ok(db_user_to_domain(db_user))
+        self.with_roles(&mut conn, db_user)
     }
 
     fn find_by_email(&self, email: &str) -> Result<User, AuthError> {
@@ -58,7 +86,7 @@ impl UserRepository for DieselUserRepository {
                 _ => AuthError::Internal(format!("Database error: {}", e)),
             })?;
 
-        Ok(db_user_to_domain(db_user))
+        self.with_roles(&mut conn, db_user)
     }
 
     fn exists_by_email(&self, email: &str) -> Result<bool, AuthError> {
@@ -77,19 +105,7 @@ impl UserRepository for DieselUserRepository {
     fn create(&self, user: &User) -> Result<User, AuthError> {
         let mut conn = self.conn()?;
 
-        let new_user = NewDbUser {
-            id: user.id().as_uuid(),
-            email: user.email().as_str(),
-            hashed_password: user.hashed_password().as_str(),
-            display_name: user.display_name(),
-            is_active: user.is_active(),
-            created_at: user.created_at(),
-            updated_at: user.updated_at(),
-        };
-
-        diesel::insert_into(users::table)
-            .values(&new_user)
-            .execute(&mut conn)
+        conn.transaction::<(), diesel::result::Error, _>(|conn| insert_user(conn, user))
             .map_err(|e| {
                 if let diesel::result::Error::DatabaseError(
                     diesel::result::DatabaseErrorKind::UniqueViolation,
@@ -109,16 +125,59 @@ impl UserRepository for DieselUserRepository {
     fn update(&self, user: &User) -> Result<User, AuthError> {
         let mut conn = self.conn()?;
 
-        let updated_rows = diesel::update(users::table.filter(users::id.eq(user.id().as_uuid())))
-            .set((
-                users::email.eq(user.email().as_str()),
-                users::hashed_password.eq(user.hashed_password().as_str()),
-                users::display_name.eq(user.display_name()),
-                users::is_active.eq(user.is_active()),
-                users::updated_at.eq(user.updated_at()),
-            ))
-            .execute(&mut conn)
-            .map_err(|e| AuthError::Internal(format!("Failed to update user: {}", e)))?;
+        let user_id = user.id().as_uuid();
+        let role_names: Vec<&str> = user.roles().iter().map(Role::as_str).collect();
+
+        let updated_rows = conn
+            .transaction::<usize, diesel::result::Error, _>(|conn| {
+                let updated_rows = diesel::update(users::table.filter(users::id.eq(user_id)))
+                    .set((
+                        users::email.eq(user.email().as_str()),
+                        users::hashed_password
+                            .eq(user.hashed_password().map(HashedPassword::as_str)),
+                        users::display_name.eq(user.display_name()),
+                        users::is_active.eq(user.is_active()),
+                        users::email_verified_at.eq(user.email_verified_at()),
+                        users::updated_at.eq(user.updated_at()),
+                    ))
+                    .execute(conn)?;
+
+                if updated_rows == 0 {
+                    return Ok(0);
+                }
+
+                // Sync roles without touching the grant time of kept roles
+                diesel::delete(
+                    user_roles::table
+                        .filter(user_roles::user_id.eq(user_id))
+                        .filter(user_roles::role.ne_all(&role_names)),
+                )
+                .execute(conn)?;
+
+                let roles = to_new_db_roles(user);
+                if !roles.is_empty() {
+                    diesel::insert_into(user_roles::table)
+                        .values(&roles)
+                        .on_conflict_do_nothing()
+                        .execute(conn)?;
+                }
+
+                insert_events(conn, user.pending_events())?;
+
+                Ok(updated_rows)
+            })
+            .map_err(|e| {
+                // Another account took the new email address first
+                if let diesel::result::Error::DatabaseError(
+                    diesel::result::DatabaseErrorKind::UniqueViolation,
+                    _,
+                ) = e
+                {
+                    AuthError::UserAlreadyExists
+                } else {
+                    AuthError::Internal(format!("Failed to update user: {}", e))
+                }
+            })?;
 
         if updated_rows == 0 {
             return Err(AuthError::UserNotFound);
@@ -128,14 +187,165 @@ impl UserRepository for DieselUserRepository {
     }
 }
 
+impl UserSearchRepository for DieselUserRepository {
+    fn search(
+        &self,
+        filter: &UserFilter,
+        cursor: Option<&UserCursor>,
+        limit: usize,
+    ) -> Result<UserPage, AuthError> {
+        let mut conn = self.conn()?;
+
+        let mut query = users::table.into_boxed();
+        if let Some(is_active) = filter.is_active {
+            query = query.filter(users::is_active.eq(is_active));
+        }
+        if let Some(role) = filter.role {
+            query = query.filter(
+                users::id.eq_any(
+                    user_roles::table
+                        .filter(user_roles::role.eq(role.as_str()))
+                        .select(user_roles::user_id),
+                ),
+            );
+        }
+        if let Some(created_after) = filter.created_after {
+            query = query.filter(users::created_at.ge(created_after));
+        }
+        if let Some(created_before) = filter.created_before {
+            query = query.filter(users::created_at.lt(created_before));
+        }
+        if let Some(email) = &filter.email {
+            let term = escape_like(&email.term());
+            let pattern = match email {
+                EmailMatch::Prefix(_) => format!("{}%", term),
+                EmailMatch::Contains(_) => format!("%{}%", term),
+            };
+            query = query.filter(users::email.like(pattern).escape('\\'));
+        }
+        if let Some(cursor) = cursor {
+            query = query.filter(
+                users::created_at.lt(cursor.created_at).or(users::created_at
+                    .eq(cursor.created_at)
+                    .and(users::id.lt(cursor.id))),
+            );
+        }
+
+        // One extra row tells whether another page follows
+        let mut db_users: Vec<DbUser> = query
+            .order((users::created_at.desc(), users::id.desc()))
+            .limit(limit as i64 + 1)
+            .load(&mut conn)
+            .map_err(|e| AuthError::Internal(format!("Database error: {}", e)))?;
+        let has_more = db_users.len() > limit;
+        db_users.truncate(limit);
+
+        // Load the roles of the whole page at once
+        let ids: Vec<Uuid> = db_users.iter().map(|u| u.id).collect();
+        let role_rows: Vec<(Uuid, String)> = user_roles::table
+            .filter(user_roles::user_id.eq_any(&ids))
+            .select((user_roles::user_id, user_roles::role))
+            .load(&mut conn)
+            .map_err(|e| AuthError::Internal(format!("Database error: {}", e)))?;
+        let mut roles: HashMap<Uuid, Vec<Role>> = HashMap::new();
+        for (user_id, name) in role_rows {
+            roles.entry(user_id).or_default().push(parse_role(&name)?);
+        }
+
+        let users: Vec<User> = db_users
+            .into_iter()
+            .map(|db_user| {
+                let user_roles = roles.remove(&db_user.id).unwrap_or_default();
+                db_user_to_domain(db_user).with_roles(user_roles)
+            })
+            .collect();
+        let next_cursor = if has_more {
+            users.last().map(UserCursor::after)
+        } else {
+            None
+        };
+
+        Ok(UserPage { users, next_cursor })
+    }
+}
+
+/// Parse a stored role name
+pub(crate) fn parse_role(name: &str) -> Result<Role, AuthError> {
+    name.parse::<Role>()
+        .map_err(|_| AuthError::Internal(format!("Unknown role in database: {}", name)))
+}
+
+/// Escape `LIKE` wildcards so a search term matches literally
+fn escape_like(term: &str) -> String {
+    let mut escaped = String::with_capacity(term.len());
+    for c in term.chars() {
+        if matches!(c, '\\' | '%' | '_') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Insert a user, their roles and the events they raised
+///
+/// Meant to run inside a transaction.
+fn insert_user(
+    conn: &mut PooledDbConnection,
+    user: &User,
+) -> Result<(), diesel::result::Error> {
+    diesel::insert_into(users::table)
+        .values(&to_new_db_user(user))
+        .execute(conn)?;
+
+    let roles = to_new_db_roles(user);
+    if !roles.is_empty() {
+        diesel::insert_into(user_roles::table)
+            .values(&roles)
+            .execute(conn)?;
+    }
+
+    insert_events(conn, user.pending_events())?;
+
+    Ok(())
+}
+
+/// Build the user row for insertion
+pub(super) fn to_new_db_user(user: &User) -> NewDbUser<'_> {
+    NewDbUser {
+        id: user.id().as_uuid(),
+        email: user.email().as_str(),
+        hashed_password: user.hashed_password().map(HashedPassword::as_str),
+        display_name: user.display_name(),
+        is_active: user.is_active(),
+        email_verified_at: user.email_verified_at(),
+        created_at: user.created_at(),
+        updated_at: user.updated_at(),
+    }
+}
+
+/// Build role rows for insertion
+pub(super) fn to_new_db_roles(user: &User) -> Vec<NewDbUserRole<'static>> {
+    let granted_at = Utc::now();
+    user.roles()
+        .iter()
+        .map(|role| NewDbUserRole {
+            user_id: user.id().as_uuid(),
+            role: role.as_str(),
+            granted_at,
+        })
+        .collect()
+}
+
 /// Convert database model to domain entity
-fn db_user_to_domain(db_user: DbUser) -> User {
+pub(super) fn db_user_to_domain(db_user: DbUser) -> User {
     User::from_persistence(
         db_user.id,
         db_user.email,
         db_user.hashed_password,
         db_user.display_name,
         db_user.is_active,
+        db_user.email_verified_at,
         db_user.created_at,
         db_user.updated_at,
     )
@@ -0,0 +1,126 @@
+//! JSON encoding of published events
+//!
+//! Each Kafka message carries one event in a versioned envelope:
+//!
+//! ```json
+//! {
+//!   "id": "…",
+//!   "type": "user.registered",
+//!   "version": 1,
+//!   "aggregate_id": "…",
+//!   "sequence": 42,
+//!   "occurred_at": "2024-01-01T12:00:00Z",
+//!   "data": { "user_id": "…", "email": "…", "display_name": null }
+//! }
+//! ```
+//!
+//! `data` depends on `type` and `version`; consumers skip versions they do
+//! not know. `sequence` grows with every event, so consumers can drop events
+//! older than the last one they applied for the same aggregate.
+
+use serde_json::{json, Value};
+
+use crate::domain::error::AuthError;
+use crate::domain::events::AuthEvent;
+use crate::domain::outbox::OutboxMessage;
+
+/// Encode the `data` of an event, as stored in the outbox
+#[must_use]
+pub fn payload(event: &AuthEvent) -> String {
+    let data = match event {
+        AuthEvent::UserRegistered {
+            user_id,
+            email,
+            display_name,
+            ..
+        } => json!({
+            "user_id": user_id,
+            "email": email,
+            "display_name": display_name,
+        }),
+        AuthEvent::UserLoggedIn {
+            user_id,
+            session_id,
+            ..
+        } => json!({ "user_id": user_id, "session_id": session_id }),
+        AuthEvent::UserDeactivated { user_id, .. }
+        | AuthEvent::PasswordChanged { user_id, .. }
+        | AuthEvent::UserErased { user_id, .. } => json!({ "user_id": user_id }),
+    };
+    data.to_string()
+}
+
+/// Encode an outbox event as a Kafka message value
+///
+/// # Errors
+/// Returns `AuthError::Internal` if the stored payload is not valid JSON
+pub fn envelope(message: &OutboxMessage) -> Result<String, AuthError> {
+    let data: Value = serde_json::from_str(&message.payload).map_err(|e| {
+        AuthError::Internal(format!("Invalid payload of event {}: {}", message.id, e))
+    })?;
+
+    Ok(json!({
+        "id": message.id,
+        "type": message.event_type,
+        "version": message.schema_version,
+        "aggregate_id": message.aggregate_id,
+        "sequence": message.sequence,
+        "occurred_at": message.occurred_at.to_rfc3339(),
+        "data": data,
+    })
+    .to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use super::*;
+
+    #[test]
+    fn test_envelope_wraps_payload() {
+        let user_id = Uuid::new_v4();
+        let event = AuthEvent::UserRegistered {
+            user_id,
+            email: "new@example.com".to_string(),
+            display_name: None,
+            occurred_at: Utc::now(),
+        };
+        let message = OutboxMessage {
+            sequence: 7,
+            id: Uuid::new_v4(),
+            aggregate_id: event.user_id(),
+            event_type: event.name().to_string(),
+            schema_version: event.schema_version(),
+            payload: payload(&event),
+            occurred_at: event.occurred_at(),
+        };
+
+        let value: Value = serde_json::from_str(&envelope(&message).unwrap()).unwrap();
+
+        assert_eq!(value["id"], message.id.to_string());
+        assert_eq!(value["type"], "user.registered");
+        assert_eq!(value["version"], 1);
+        assert_eq!(value["aggregate_id"], user_id.to_string());
+        assert_eq!(value["sequence"], 7);
+        assert_eq!(value["data"]["user_id"], user_id.to_string());
+        assert_eq!(value["data"]["email"], "new@example.com");
+        assert!(value["data"]["display_name"].is_null());
+    }
+
+    #[test]
+    fn test_envelope_rejects_corrupt_payload() {
+        let message = OutboxMessage {
+            sequence: 1,
+            id: Uuid::new_v4(),
+            aggregate_id: Uuid::new_v4(),
+            event_type: "user.deactivated".to_string(),
+            schema_version: 1,
+            payload: "{".to_string(),
+            occurred_at: Utc::now(),
+        };
+
+        assert!(matches!(envelope(&message), Err(AuthError::Internal(_))));
+    }
+}
@@ -0,0 +1,47 @@
+//! In-memory outbox publisher, standing in for the broker in tests
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use crate::domain::error::AuthError;
+use crate::domain::outbox::{OutboxMessage, OutboxPublisher};
+
+/// In-memory implementation of the OutboxPublisher trait
+///
+/// Keeps every published event in order. It can be made unavailable to
+/// simulate a broker outage, in which case publishing fails without
+/// keeping anything.
+#[derive(Debug, Default)]
+pub struct InMemoryOutboxPublisher {
+    messages: Mutex<Vec<OutboxMessage>>,
+    unavailable: AtomicBool,
+}
+
+impl InMemoryOutboxPublisher {
+    /// Create a new, available publisher
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Make publishing fail, or succeed again
+    pub fn set_available(&self, available: bool) {
+        self.unavailable.store(!available, Ordering::SeqCst);
+    }
+
+    /// Get the events published so far, in publishing order
+    #[must_use]
+    pub fn messages(&self) -> Vec<OutboxMessage> {
+        self.messages.lock().unwrap().clone()
+    }
+}
+
+impl OutboxPublisher for InMemoryOutboxPublisher {
+    fn publish(&self, messages: &[OutboxMessage]) -> Result<(), AuthError> {
+        if self.unavailable.load(Ordering::SeqCst) {
+            return Err(AuthError::Internal("Broker unavailable".to_string()));
+        }
+        self.messages.lock().unwrap().extend_from_slice(messages);
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+//! Kafka implementation of the OutboxPublisher trait
+//!
+//! Events go to a single topic, keyed by aggregate ID so that all events
+//! about one user land on the same partition and stay in order. The
+//! producer is idempotent: librdkafka's retries neither reorder nor
+//! duplicate messages within a partition. Calls block on the Tokio runtime,
+//! so they must be made from blocking threads, as the outbox relay does.
+
+use rdkafka::config::ClientConfig;
+use rdkafka::message::{Header, OwnedHeaders};
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use tokio::runtime::Handle;
+
+use crate::domain::error::AuthError;
+use crate::domain::outbox::{OutboxMessage, OutboxPublisher};
+
+use super::envelope;
+
+/// How long librdkafka keeps retrying a message before reporting it failed
+const MESSAGE_TIMEOUT_MS: &str = "30000";
+
+/// Outbox publisher producing to a Kafka topic
+pub struct KafkaOutboxPublisher {
+    producer: FutureProducer,
+    topic: String,
+    runtime: Handle,
+}
+
+impl KafkaOutboxPublisher {
+    /// Create a producer for `topic` on the comma-separated `brokers`
+    ///
+    /// No connection is made until the first event is published.
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` if the producer configuration is invalid
+    pub fn new(
+        brokers: &str,
+        topic: impl Into<String>,
+        runtime: Handle,
+    ) -> Result<Self, AuthError> {
+        let producer = ClientConfig::new()
+            .set("bootstrap.servers", brokers)
+            .set("enable.idempotence", "true")
+            .set("acks", "all")
+            .set("message.timeout.ms", MESSAGE_TIMEOUT_MS)
+            .create()
+            .map_err(|e| AuthError::Internal(format!("Failed to create Kafka producer: {}", e)))?;
+
+        Ok(Self {
+            producer,
+            topic: topic.into(),
+            runtime,
+        })
+    }
+}
+
+impl OutboxPublisher for KafkaOutboxPublisher {
+    fn publish(&self, messages: &[OutboxMessage]) -> Result<(), AuthError> {
+        // Queue the whole batch before waiting, so it is sent in few requests
+        let mut deliveries = Vec::with_capacity(messages.len());
+        for message in messages {
+            let key = message.aggregate_id.to_string();
+            let value = envelope::envelope(message)?;
+            let version = message.schema_version.to_string();
+            let headers = OwnedHeaders::new()
+                .insert(Header {
+                    key: "event_type",
+                    value: Some(&message.event_type),
+                })
+                .insert(Header {
+                    key: "schema_version",
+                    value: Some(&version),
+                });
+            let record = FutureRecord::to(&self.topic)
+                .key(&key)
+                .payload(&value)
+                .headers(headers)
+                .timestamp(message.occurred_at.timestamp_millis());
+
+            let delivery = self.producer.send_result(record).map_err(|(e, _)| {
+                AuthError::Internal(format!("Failed to queue event {}: {}", message.id, e))
+            })?;
+            deliveries.push((message.id, delivery));
+        }
+
+        self.runtime.block_on(async {
+            for (id, delivery) in deliveries {
+                match delivery.await {
+                    Ok(Ok(_)) => {}
+                    Ok(Err((e, _))) => {
+                        return Err(AuthError::Internal(format!(
+                            "Failed to publish event {}: {}",
+                            id, e
+                        )))
+                    }
+                    Err(_) => {
+                        return Err(AuthError::Internal(format!(
+                            "Delivery of event {} was cancelled",
+                            id
+                        )))
+                    }
+                }
+            }
+            Ok(())
+        })
+    }
+}
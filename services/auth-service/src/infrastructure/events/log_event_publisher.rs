@@ -0,0 +1,36 @@
+//! Event publisher that writes events to the service log
+
+use tracing::info;
+
+use crate::domain::error::AuthError;
+use crate::domain::outbox::{OutboxMessage, OutboxPublisher};
+
+/// Log-only implementation of the OutboxPublisher trait
+///
+/// Events are not delivered anywhere but still leave the outbox, so this is
+/// only suitable when no message broker is configured.
+#[derive(Debug, Default)]
+pub struct LogEventPublisher;
+
+impl LogEventPublisher {
+    /// Create a new publisher
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl OutboxPublisher for LogEventPublisher {
+    fn publish(&self, messages: &[OutboxMessage]) -> Result<(), AuthError> {
+        for message in messages {
+            info!(
+                event = %message.event_type,
+                event_id = %message.id,
+                user_id = %message.aggregate_id,
+                "Published event: {}",
+                message.payload
+            );
+        }
+        Ok(())
+    }
+}
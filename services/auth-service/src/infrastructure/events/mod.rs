@@ -0,0 +1,7 @@
+//! Event infrastructure - outbox relay and publishers for domain events
+
+pub mod envelope;
+pub mod in_memory_outbox_publisher;
+pub mod kafka_outbox_publisher;
+pub mod log_event_publisher;
+pub mod outbox_relay;
@@ -0,0 +1,246 @@
+//! Relay from the outbox to the message broker
+//!
+//! Events are published in outbox order and only marked as published once
+//! the broker has acknowledged them. If the relay stops in between, the
+//! same events are published again on the next run: delivery is at least
+//! once. A failed batch is retried whole, so an event is never overtaken by
+//! a later one of the same batch on the next attempt.
+//!
+//! Every replica runs a relay, but each pass first leases the outbox and
+//! skips the tick while another relay holds it, so only one relay publishes
+//! at a time and the outbox order is kept.
+
+use std::sync::Arc;
+
+use chrono::Utc;
+
+use crate::domain::error::AuthError;
+use crate::domain::outbox::{OutboxPublisher, OutboxRepository};
+
+/// Moves events from the outbox to a publisher
+pub struct OutboxRelay<R: ?Sized, P: ?Sized> {
+    outbox: Arc<R>,
+    publisher: Arc<P>,
+    batch_size: usize,
+}
+
+impl<R, P> OutboxRelay<R, P>
+where
+    R: OutboxRepository + ?Sized,
+    P: OutboxPublisher + ?Sized,
+{
+    /// Create a relay publishing up to `batch_size` events at a time
+    pub fn new(outbox: Arc<R>, publisher: Arc<P>, batch_size: usize) -> Self {
+        Self {
+            outbox,
+            publisher,
+            batch_size: batch_size.max(1),
+        }
+    }
+
+    /// Publish every unpublished event, returning how many were published
+    ///
+    /// Publishes nothing while another relay holds the outbox.
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` if the outbox cannot be read or the
+    /// broker does not acknowledge a batch; batches published before the
+    /// failure stay published
+    pub fn relay(&self) -> Result<usize, AuthError> {
+        let Some(_lease) = self.outbox.try_lease_relay()? else {
+            return Ok(0);
+        };
+
+        let mut published = 0;
+        loop {
+            let batch = self.outbox.find_unpublished(self.batch_size)?;
+            if batch.is_empty() {
+                return Ok(published);
+            }
+
+            self.publisher.publish(&batch)?;
+            let sequences: Vec<i64> = batch.iter().map(|message| message.sequence).collect();
+            self.outbox.mark_published(&sequences, Utc::now())?;
+            published += batch.len();
+
+            if batch.len() < self.batch_size {
+                return Ok(published);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Mutex;
+
+    use chrono::{DateTime, Utc};
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::domain::events::AuthEvent;
+    use crate::domain::outbox::{OutboxMessage, RelayLease};
+    use crate::infrastructure::events::envelope;
+    use crate::infrastructure::events::in_memory_outbox_publisher::InMemoryOutboxPublisher;
+
+    // Outbox keeping events and their published flag in memory; marking can
+    // be made to fail once, as if the relay crashed after publishing, and the
+    // lease can be held by a relay elsewhere
+    #[derive(Default)]
+    struct MockOutboxRepository {
+        events: Mutex<Vec<(OutboxMessage, bool)>>,
+        fail_next_mark: AtomicBool,
+        leased: Arc<AtomicBool>,
+    }
+
+    // Lease handing the outbox back when dropped
+    struct MockLease(Arc<AtomicBool>);
+
+    impl Drop for MockLease {
+        fn drop(&mut self) {
+            self.0.store(false, Ordering::SeqCst);
+        }
+    }
+
+    impl MockOutboxRepository {
+        fn append(&self, event: &AuthEvent) {
+            let mut events = self.events.lock().unwrap();
+            let message = OutboxMessage {
+                sequence: events.len() as i64 + 1,
+                id: Uuid::new_v4(),
+                aggregate_id: event.user_id(),
+                event_type: event.name().to_string(),
+                schema_version: event.schema_version(),
+                payload: envelope::payload(event),
+                occurred_at: event.occurred_at(),
+            };
+            events.push((message, false));
+        }
+
+        fn unpublished(&self) -> usize {
+            let events = self.events.lock().unwrap();
+            events.iter().filter(|(_, published)| !published).count()
+        }
+    }
+
+    impl OutboxRepository for MockOutboxRepository {
+        fn try_lease_relay(&self) -> Result<Option<RelayLease>, AuthError> {
+            if self.leased.swap(true, Ordering::SeqCst) {
+                return Ok(None);
+            }
+            Ok(Some(RelayLease::new(MockLease(Arc::clone(&self.leased)))))
+        }
+
+        fn find_unpublished(&self, limit: usize) -> Result<Vec<OutboxMessage>, AuthError> {
+            let events = self.events.lock().unwrap();
+            Ok(events
+                .iter()
+                .filter(|(_, published)| !published)
+                .take(limit)
+                .map(|(message, _)| message.clone())
+                .collect())
+        }
+
+        fn mark_published(&self, sequences: &[i64], _now: DateTime<Utc>) -> Result<(), AuthError> {
+            if self.fail_next_mark.swap(false, Ordering::SeqCst) {
+                return Err(AuthError::Internal("Database unavailable".to_string()));
+            }
+            let mut events = self.events.lock().unwrap();
+            for (message, published) in events.iter_mut() {
+                if sequences.contains(&message.sequence) {
+                    *published = true;
+                }
+            }
+            Ok(())
+        }
+
+        fn purge_published(&self, _cutoff: DateTime<Utc>) -> Result<usize, AuthError> {
+            Ok(0)
+        }
+    }
+
+    fn outbox_with_logins(user_ids: &[Uuid]) -> Arc<MockOutboxRepository> {
+        let outbox = Arc::new(MockOutboxRepository::default());
+        for user_id in user_ids {
+            outbox.append(&AuthEvent::UserLoggedIn {
+                user_id: *user_id,
+                session_id: Uuid::new_v4(),
+                occurred_at: Utc::now(),
+            });
+        }
+        outbox
+    }
+
+    fn sequences(publisher: &InMemoryOutboxPublisher) -> Vec<i64> {
+        publisher.messages().iter().map(|m| m.sequence).collect()
+    }
+
+    #[test]
+    fn test_relay_publishes_in_order_across_batches() {
+        let (alice, bob) = (Uuid::new_v4(), Uuid::new_v4());
+        let outbox = outbox_with_logins(&[alice, bob, alice, alice, bob]);
+        let publisher = Arc::new(InMemoryOutboxPublisher::new());
+        let relay = OutboxRelay::new(outbox.clone(), publisher.clone(), 2);
+
+        assert_eq!(relay.relay().unwrap(), 5);
+        assert_eq!(relay.relay().unwrap(), 0);
+
+        assert_eq!(sequences(&publisher), [1, 2, 3, 4, 5]);
+        let alice_events: Vec<i64> = publisher
+            .messages()
+            .iter()
+            .filter(|m| m.aggregate_id == alice)
+            .map(|m| m.sequence)
+            .collect();
+        assert_eq!(alice_events, [1, 3, 4]);
+        assert_eq!(outbox.unpublished(), 0);
+    }
+
+    #[test]
+    fn test_broker_outage_keeps_events_until_it_recovers() {
+        let outbox = outbox_with_logins(&[Uuid::new_v4(), Uuid::new_v4()]);
+        let publisher = Arc::new(InMemoryOutboxPublisher::new());
+        let relay = OutboxRelay::new(outbox.clone(), publisher.clone(), 10);
+
+        publisher.set_available(false);
+        assert!(matches!(relay.relay(), Err(AuthError::Internal(_))));
+        assert_eq!(outbox.unpublished(), 2);
+
+        publisher.set_available(true);
+        assert_eq!(relay.relay().unwrap(), 2);
+        assert_eq!(sequences(&publisher), [1, 2]);
+    }
+
+    #[test]
+    fn test_events_not_marked_are_published_again() {
+        let outbox = outbox_with_logins(&[Uuid::new_v4(), Uuid::new_v4()]);
+        let publisher = Arc::new(InMemoryOutboxPublisher::new());
+        let relay = OutboxRelay::new(outbox.clone(), publisher.clone(), 10);
+
+        outbox.fail_next_mark.store(true, Ordering::SeqCst);
+        assert!(relay.relay().is_err());
+        assert_eq!(relay.relay().unwrap(), 2);
+
+        // At least once: consumers see the duplicates and drop them by ID
+        assert_eq!(sequences(&publisher), [1, 2, 1, 2]);
+        assert_eq!(outbox.unpublished(), 0);
+    }
+
+    #[test]
+    fn test_relay_skips_outbox_leased_elsewhere() {
+        let outbox = outbox_with_logins(&[Uuid::new_v4(), Uuid::new_v4()]);
+        let publisher = Arc::new(InMemoryOutboxPublisher::new());
+        let relay = OutboxRelay::new(outbox.clone(), publisher.clone(), 10);
+
+        // Another replica is relaying
+        let lease = outbox.try_lease_relay().unwrap().unwrap();
+        assert_eq!(relay.relay().unwrap(), 0);
+        assert!(publisher.messages().is_empty());
+
+        drop(lease);
+        assert_eq!(relay.relay().unwrap(), 2);
+        // The lease is given back after each pass
+        assert!(!outbox.leased.load(Ordering::SeqCst));
+    }
+}
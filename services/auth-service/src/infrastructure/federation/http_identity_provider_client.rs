@@ -0,0 +1,512 @@
+//! HTTP implementation of the AsyncIdentityProviderClient trait
+//!
+//! Endpoints and signing keys are read from each provider's discovery
+//! document and cached.
+//!
+//! Connections use HTTPS, verified against the Mozilla root certificates.
+//! Plain HTTP can be allowed for providers on a local or test network.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::Bytes;
+use hyper::{header, Method, Request, StatusCode, Uri};
+use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
+use hyper_util::client::legacy::connect::HttpConnector;
+use hyper_util::client::legacy::Client;
+use hyper_util::rt::TokioExecutor;
+use jsonwebtoken::jwk::{AlgorithmParameters, Jwk, JwkSet};
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use moka::sync::Cache;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use tracing::{debug, warn};
+
+use crate::domain::auth::BoxFuture;
+use crate::domain::error::AuthError;
+use crate::domain::federation::{
+    AsyncIdentityProviderClient, ExternalIdentity, IdentityProvider, FEDERATED_SCOPES,
+};
+
+/// Largest response body accepted from a provider
+const MAX_RESPONSE_BYTES: usize = 256 * 1024;
+
+/// How long discovery documents and key sets are cached
+const METADATA_CACHE_TTL: Duration = Duration::from_secs(3600);
+
+/// Clock skew tolerated on ID token timestamps, in seconds
+const CLOCK_SKEW_SECS: u64 = 60;
+
+/// Endpoints from a provider's discovery document
+#[derive(Debug, Deserialize)]
+struct ProviderMetadata {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+/// Successful token endpoint response; only the ID token is used
+#[derive(Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+/// Error response of the token endpoint
+#[derive(Deserialize)]
+struct TokenErrorResponse {
+    error: String,
+}
+
+/// ID token claims read by the service
+#[derive(Deserialize)]
+struct IdTokenClaims {
+    sub: String,
+    nonce: Option<String>,
+    email: Option<String>,
+    /// A boolean, or the string "true" for some providers
+    email_verified: Option<serde_json::Value>,
+    name: Option<String>,
+}
+
+/// Identity provider client over HTTP/1.1
+pub struct HttpIdentityProviderClient {
+    client: Client<HttpsConnector<HttpConnector>, Full<Bytes>>,
+    /// Whether `http://` URLs are accepted
+    plain_http: bool,
+    timeout: Duration,
+    metadata: Cache<String, Arc<ProviderMetadata>>,
+    key_sets: Cache<String, Arc<JwkSet>>,
+}
+
+impl HttpIdentityProviderClient {
+    /// Create a new client that only connects over HTTPS
+    ///
+    /// # Arguments
+    /// * `timeout` - Limit for each request, including reading the response
+    #[must_use]
+    pub fn new(timeout: Duration) -> Self {
+        let connector = HttpsConnectorBuilder::new()
+            .with_webpki_roots()
+            .https_only()
+            .enable_http1()
+            .build();
+        let client = Client::builder(TokioExecutor::new()).build(connector);
+        let metadata = Cache::builder()
+            .max_capacity(64)
+            .time_to_live(METADATA_CACHE_TTL)
+            .build();
+        let key_sets = Cache::builder()
+            .max_capacity(64)
+            .time_to_live(METADATA_CACHE_TTL)
+            .build();
+
+        Self {
+            client,
+            plain_http: false,
+            timeout,
+            metadata,
+            key_sets,
+        }
+    }
+
+    /// Also connect to `http://` URLs
+    ///
+    /// Only for providers on a local or test network: tokens and client
+    /// secrets are sent in the clear.
+    #[must_use]
+    pub fn with_plain_http(mut self) -> Self {
+        let connector = HttpsConnectorBuilder::new()
+            .with_webpki_roots()
+            .https_or_http()
+            .enable_http1()
+            .build();
+        self.client = Client::builder(TokioExecutor::new()).build(connector);
+        self.plain_http = true;
+        self
+    }
+
+    /// Send a request and read the response
+    async fn send(&self, request: Request<Full<Bytes>>) -> Result<(StatusCode, Bytes), AuthError> {
+        let uri = request.uri().clone();
+        let exchange = async {
+            let response = self.client.request(request).await.map_err(|e| {
+                warn!(uri = %uri, error = %e, "Identity provider request failed");
+                AuthError::IdentityProvider("Identity provider unreachable".to_string())
+            })?;
+            let status = response.status();
+            let body = Limited::new(response.into_body(), MAX_RESPONSE_BYTES)
+                .collect()
+                .await
+                .map_err(|e| {
+                    warn!(uri = %uri, error = %e, "Identity provider response unreadable");
+                    AuthError::IdentityProvider("Invalid identity provider response".to_string())
+                })?
+                .to_bytes();
+            Ok((status, body))
+        };
+
+        tokio::time::timeout(self.timeout, exchange)
+            .await
+            .map_err(|_| {
+                warn!(uri = %uri, "Identity provider request timed out");
+                AuthError::IdentityProvider("Identity provider timed out".to_string())
+            })?
+    }
+
+    /// Fetch a JSON document
+    async fn get_json<T: DeserializeOwned>(&self, url: &str) -> Result<T, AuthError> {
+        let request = Request::builder()
+            .method(Method::GET)
+            .uri(parse_uri(url, self.plain_http)?)
+            .header(header::ACCEPT, "application/json")
+            .body(Full::default())
+            .map_err(|e| AuthError::Internal(format!("Failed to build request: {}", e)))?;
+
+        let (status, body) = self.send(request).await?;
+        if !status.is_success() {
+            warn!(url = %url, status = %status, "Identity provider returned an error");
+            return Err(AuthError::IdentityProvider(format!(
+                "Identity provider returned {}",
+                status
+            )));
+        }
+        parse_json(url, &body)
+    }
+
+    /// Get a provider's endpoints, from the cache or its discovery document
+    async fn metadata(&self, provider: &IdentityProvider) -> Result<Arc<ProviderMetadata>, AuthError> {
+        if let Some(metadata) = self.metadata.get(&provider.issuer) {
+            return Ok(metadata);
+        }
+
+        let url = format!(
+            "{}/.well-known/openid-configuration",
+            provider.issuer.trim_end_matches('/')
+        );
+        let metadata: ProviderMetadata = self.get_json(&url).await?;
+        // A document naming another issuer could smuggle in another key set
+        if metadata.issuer != provider.issuer {
+            warn!(
+                provider = %provider.id,
+                issuer = %metadata.issuer,
+                "Discovery document issuer does not match the configured issuer"
+            );
+            return Err(AuthError::IdentityProvider(
+                "Identity provider misconfigured".to_string(),
+            ));
+        }
+
+        debug!(provider = %provider.id, "Identity provider metadata loaded");
+        let metadata = Arc::new(metadata);
+        self.metadata
+            .insert(provider.issuer.clone(), Arc::clone(&metadata));
+        Ok(metadata)
+    }
+
+    /// Get a provider's signing keys, refetching them when asked to
+    async fn key_set(&self, jwks_uri: &str, refresh: bool) -> Result<Arc<JwkSet>, AuthError> {
+        if !refresh {
+            if let Some(keys) = self.key_sets.get(jwks_uri) {
+                return Ok(keys);
+            }
+        }
+
+        let keys = Arc::new(self.get_json::<JwkSet>(jwks_uri).await?);
+        self.key_sets
+            .insert(jwks_uri.to_string(), Arc::clone(&keys));
+        Ok(keys)
+    }
+
+    /// Get the key an ID token is signed with
+    ///
+    /// An unknown key ID triggers one refetch of the key set, so key
+    /// rotations at the provider are picked up before the cache expires.
+    async fn decoding_key(&self, jwks_uri: &str, kid: Option<&str>) -> Result<DecodingKey, AuthError> {
+        let mut keys = self.key_set(jwks_uri, false).await?;
+        if find_jwk(&keys, kid).is_none() {
+            keys = self.key_set(jwks_uri, true).await?;
+        }
+
+        let jwk = find_jwk(&keys, kid).ok_or_else(|| {
+            AuthError::IdentityProvider("ID token signed with an unknown key".to_string())
+        })?;
+        DecodingKey::from_jwk(jwk).map_err(|e| {
+            warn!(jwks_uri = %jwks_uri, error = %e, "Unusable identity provider key");
+            AuthError::IdentityProvider("Unusable identity provider key".to_string())
+        })
+    }
+
+    /// Verify an ID token and read the identity from it
+    async fn verify_id_token(
+        &self,
+        provider: &IdentityProvider,
+        metadata: &ProviderMetadata,
+        id_token: &str,
+        nonce: &str,
+    ) -> Result<ExternalIdentity, AuthError> {
+        let invalid = || AuthError::IdentityProvider("Invalid ID token".to_string());
+
+        let token_header = decode_header(id_token).map_err(|_| invalid())?;
+        let key = match token_header.alg {
+            // Symmetric signatures use the client secret, never a published key
+            Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512 => {
+                let secret = provider.client_secret.as_deref().ok_or_else(invalid)?;
+                DecodingKey::from_secret(secret.as_bytes())
+            }
+            _ => {
+                self.decoding_key(&metadata.jwks_uri, token_header.kid.as_deref())
+                    .await?
+            }
+        };
+
+        let mut validation = Validation::new(token_header.alg);
+        validation.set_issuer(&[&metadata.issuer]);
+        validation.set_audience(&[&provider.client_id]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+        validation.leeway = CLOCK_SKEW_SECS;
+
+        let claims = decode::<IdTokenClaims>(id_token, &key, &validation)
+            .map_err(|e| {
+                warn!(provider = %provider.id, error = %e, "ID token rejected");
+                invalid()
+            })?
+            .claims;
+
+        // The nonce ties the token to the sign-in this browser started
+        if claims.nonce.as_deref() != Some(nonce) {
+            warn!(provider = %provider.id, "ID token nonce mismatch");
+            return Err(invalid());
+        }
+
+        Ok(ExternalIdentity {
+            subject: claims.sub,
+            email: claims.email,
+            email_verified: is_true(claims.email_verified.as_ref()),
+            name: claims.name,
+        })
+    }
+}
+
+impl AsyncIdentityProviderClient for HttpIdentityProviderClient {
+    fn authorization_url<'a>(
+        &'a self,
+        provider: &'a IdentityProvider,
+        redirect_uri: &'a str,
+        state: &'a str,
+        nonce: &'a str,
+        code_challenge: &'a str,
+    ) -> BoxFuture<'a, Result<String, AuthError>> {
+        Box::pin(async move {
+            let metadata = self.metadata(provider).await?;
+            let scope = FEDERATED_SCOPES.join(" ");
+
+            Ok(append_query(
+                &metadata.authorization_endpoint,
+                &[
+                    ("response_type", "code"),
+                    ("client_id", &provider.client_id),
+                    ("redirect_uri", redirect_uri),
+                    ("scope", &scope),
+                    ("state", state),
+                    ("nonce", nonce),
+                    ("code_challenge", code_challenge),
+                    ("code_challenge_method", "S256"),
+                ],
+            ))
+        })
+    }
+
+    fn redeem_code<'a>(
+        &'a self,
+        provider: &'a IdentityProvider,
+        code: &'a str,
+        redirect_uri: &'a str,
+        code_verifier: &'a str,
+        nonce: &'a str,
+    ) -> BoxFuture<'a, Result<ExternalIdentity, AuthError>> {
+        Box::pin(async move {
+            let metadata = self.metadata(provider).await?;
+
+            let mut form = vec![
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", redirect_uri),
+                ("code_verifier", code_verifier),
+                ("client_id", provider.client_id.as_str()),
+            ];
+            if let Some(secret) = provider.client_secret.as_deref() {
+                form.push(("client_secret", secret));
+            }
+            let body = serde_urlencoded::to_string(&form)
+                .map_err(|e| AuthError::Internal(format!("Failed to encode form: {}", e)))?;
+
+            let request = Request::builder()
+                .method(Method::POST)
+                .uri(parse_uri(&metadata.token_endpoint, self.plain_http)?)
+                .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .header(header::ACCEPT, "application/json")
+                .body(Full::new(Bytes::from(body)))
+                .map_err(|e| AuthError::Internal(format!("Failed to build request: {}", e)))?;
+
+            let (status, body) = self.send(request).await?;
+            if !status.is_success() {
+                let error = serde_json::from_slice::<TokenErrorResponse>(&body)
+                    .map(|response| response.error)
+                    .unwrap_or_else(|_| status.to_string());
+                warn!(
+                    provider = %provider.id,
+                    error = %error,
+                    "Identity provider rejected the code"
+                );
+                return Err(AuthError::IdentityProvider(format!(
+                    "Identity provider rejected the code: {}",
+                    error
+                )));
+            }
+
+            let response: TokenResponse = parse_json(&metadata.token_endpoint, &body)?;
+            self.verify_id_token(provider, &metadata, &response.id_token, nonce)
+                .await
+        })
+    }
+}
+
+/// Parse a provider URL, which must use HTTPS unless plain HTTP is allowed
+fn parse_uri(url: &str, plain_http: bool) -> Result<Uri, AuthError> {
+    let uri: Uri = url.parse().map_err(|_| {
+        AuthError::IdentityProvider(format!("Invalid identity provider URL: {}", url))
+    })?;
+    match uri.scheme_str() {
+        Some("https") => Ok(uri),
+        Some("http") if plain_http => Ok(uri),
+        _ => Err(AuthError::IdentityProvider(format!(
+            "Unsupported identity provider URL, only https:// is supported: {}",
+            url
+        ))),
+    }
+}
+
+/// Parse a JSON response body
+fn parse_json<T: DeserializeOwned>(url: &str, body: &[u8]) -> Result<T, AuthError> {
+    serde_json::from_slice(body).map_err(|e| {
+        warn!(url = %url, error = %e, "Identity provider response unreadable");
+        AuthError::IdentityProvider("Invalid identity provider response".to_string())
+    })
+}
+
+/// Add query parameters to a URL that may already have some
+fn append_query(url: &str, params: &[(&str, &str)]) -> String {
+    let separator = if url.contains('?') { '&' } else { '?' };
+    // Encoding string pairs cannot fail
+    let query = serde_urlencoded::to_string(params).unwrap_or_default();
+    format!("{}{}{}", url, separator, query)
+}
+
+/// Find the published key for a key ID
+///
+/// Symmetric keys are never taken from a key set. Without a key ID, the key
+/// set must hold exactly one key.
+fn find_jwk<'a>(keys: &'a JwkSet, kid: Option<&str>) -> Option<&'a Jwk> {
+    let mut candidates = keys
+        .keys
+        .iter()
+        .filter(|jwk| !matches!(jwk.algorithm, AlgorithmParameters::OctetKey(_)));
+
+    match kid {
+        Some(kid) => candidates.find(|jwk| jwk.common.key_id.as_deref() == Some(kid)),
+        None => {
+            let first = candidates.next();
+            candidates.next().is_none().then_some(first).flatten()
+        }
+    }
+}
+
+/// Read a boolean claim that some providers send as a string
+fn is_true(value: Option<&serde_json::Value>) -> bool {
+    match value {
+        Some(serde_json::Value::Bool(value)) => *value,
+        Some(serde_json::Value::String(value)) => value == "true",
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn key_set(keys: serde_json::Value) -> JwkSet {
+        serde_json::from_value(json!({ "keys": keys })).unwrap()
+    }
+
+    fn okp_key(kid: &str) -> serde_json::Value {
+        json!({
+            "kty": "OKP",
+            "crv": "Ed25519",
+            "x": "11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo",
+            "kid": kid,
+        })
+    }
+
+    #[test]
+    fn test_find_jwk_by_key_id() {
+        let keys = key_set(json!([okp_key("a"), okp_key("b")]));
+
+        assert_eq!(
+            find_jwk(&keys, Some("b")).unwrap().common.key_id.as_deref(),
+            Some("b")
+        );
+        assert!(find_jwk(&keys, Some("c")).is_none());
+        // Ambiguous without a key ID
+        assert!(find_jwk(&keys, None).is_none());
+
+        let single = key_set(json!([okp_key("a")]));
+        assert!(find_jwk(&single, None).is_some());
+    }
+
+    #[test]
+    fn test_find_jwk_skips_symmetric_keys() {
+        let keys = key_set(json!([{ "kty": "oct", "k": "c2VjcmV0", "kid": "a" }]));
+
+        assert!(find_jwk(&keys, Some("a")).is_none());
+        assert!(find_jwk(&keys, None).is_none());
+    }
+
+    #[test]
+    fn test_append_query() {
+        assert_eq!(
+            append_query("http://idp/authorize", &[("scope", "openid email")]),
+            "http://idp/authorize?scope=openid+email"
+        );
+        assert_eq!(
+            append_query("http://idp/authorize?tenant=x", &[("state", "s")]),
+            "http://idp/authorize?tenant=x&state=s"
+        );
+    }
+
+    #[test]
+    fn test_parse_uri_requires_https() {
+        assert!(parse_uri("https://accounts.example.com/token", false).is_ok());
+        assert!(matches!(
+            parse_uri("http://idp.internal/token", false),
+            Err(AuthError::IdentityProvider(_))
+        ));
+        assert!(parse_uri("http://idp.internal/token", true).is_ok());
+        assert!(matches!(
+            parse_uri("ftp://idp.internal/token", true),
+            Err(AuthError::IdentityProvider(_))
+        ));
+    }
+
+    #[test]
+    fn test_is_true() {
+        assert!(is_true(Some(&json!(true))));
+        assert!(is_true(Some(&json!("true"))));
+        assert!(!is_true(Some(&json!("false"))));
+        assert!(!is_true(Some(&json!(1))));
+        assert!(!is_true(None));
+    }
+}
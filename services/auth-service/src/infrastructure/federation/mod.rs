@@ -0,0 +1,3 @@
+//! Federation infrastructure - clients for upstream OpenID Connect providers
+
+pub mod http_identity_provider_client;
@@ -0,0 +1,91 @@
+//! Mailer that drops messages into a directory as `.eml` files
+
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::domain::error::AuthError;
+use crate::domain::mailer::{EmailMessage, Mailer};
+
+/// File-based implementation of the Mailer trait
+///
+/// Every message becomes one RFC 5322 file, which most mail clients can
+/// open directly. Useful for tests and local development without SMTP.
+#[derive(Debug)]
+pub struct FileMailer {
+    directory: PathBuf,
+}
+
+impl FileMailer {
+    /// Create a mailer writing into `directory`, creating it if needed
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` if the directory cannot be created
+    pub fn new(directory: impl Into<PathBuf>) -> Result<Self, AuthError> {
+        let directory = directory.into();
+        fs::create_dir_all(&directory).map_err(|e| {
+            AuthError::Internal(format!(
+                "Failed to create mail directory {}: {}",
+                directory.display(),
+                e
+            ))
+        })?;
+        Ok(Self { directory })
+    }
+}
+
+impl Mailer for FileMailer {
+    fn send(&self, message: &EmailMessage) -> Result<(), AuthError> {
+        let now = Utc::now();
+        let path = self.directory.join(format!(
+            "{}-{}.eml",
+            now.format("%Y%m%dT%H%M%S%.3fZ"),
+            Uuid::new_v4().simple()
+        ));
+
+        let contents = format!(
+            "Date: {}\r\nTo: {}\r\nSubject: {}\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n{}",
+            now.to_rfc2822(),
+            message.to,
+            message.subject,
+            message.body
+        );
+
+        fs::write(&path, contents)
+            .map_err(|e| AuthError::Internal(format!("Failed to write {}: {}", path.display(), e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_writes_one_file_per_message() {
+        let directory = std::env::temp_dir().join(format!("auth-mail-{}", Uuid::new_v4()));
+        let mailer = FileMailer::new(&directory).unwrap();
+        let message = EmailMessage {
+            to: "test@example.com".to_string(),
+            subject: "Hello".to_string(),
+            body: "Line one\nLine two\n".to_string(),
+        };
+
+        mailer.send(&message).unwrap();
+        mailer.send(&message).unwrap();
+
+        let files: Vec<PathBuf> = fs::read_dir(&directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        assert_eq!(files.len(), 2);
+
+        let contents = fs::read_to_string(&files[0]).unwrap();
+        assert!(contents.contains("To: test@example.com\r\n"));
+        assert!(contents.contains("Subject: Hello\r\n"));
+        assert!(contents.ends_with("\r\n\r\nLine one\nLine two\n"));
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,33 @@
+//! Mailer that writes messages to the service log
+
+use tracing::info;
+
+use crate::domain::error::AuthError;
+use crate::domain::mailer::{EmailMessage, Mailer};
+
+/// Log-only implementation of the Mailer trait
+///
+/// Message bodies contain live secrets such as reset links, so this is only
+/// suitable for local development.
+#[derive(Debug, Default)]
+pub struct LogMailer;
+
+impl LogMailer {
+    /// Create a new mailer
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Mailer for LogMailer {
+    fn send(&self, message: &EmailMessage) -> Result<(), AuthError> {
+        info!(
+            to = %message.to,
+            subject = %message.subject,
+            "Outgoing email:\n{}",
+            message.body
+        );
+        Ok(())
+    }
+}
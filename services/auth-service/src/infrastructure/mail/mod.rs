@@ -0,0 +1,4 @@
+//! Mail infrastructure - development mailers that never leave the host
+
+pub mod file_mailer;
+pub mod log_mailer;
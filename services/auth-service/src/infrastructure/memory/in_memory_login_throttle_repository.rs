@@ -0,0 +1,162 @@
+//! In-memory implementation of the LoginThrottleRepository trait
+
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+
+use crate::domain::auth::BoxFuture;
+use crate::domain::error::AuthError;
+use crate::domain::lockout::{
+    AsyncLoginThrottleRepository, LockoutPolicy, LoginThrottle, LoginThrottleRepository,
+    ThrottleKey,
+};
+
+/// In-memory implementation of LoginThrottleRepository
+///
+/// Failures are counted under one lock, which makes `record_failure`
+/// atomic within the process; counters are not shared between replicas.
+#[derive(Debug, Default)]
+pub struct InMemoryLoginThrottleRepository {
+    throttles: Mutex<Vec<LoginThrottle>>,
+}
+
+impl InMemoryLoginThrottleRepository {
+    /// Create an empty repository
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl LoginThrottleRepository for InMemoryLoginThrottleRepository {
+    fn find(&self, key: &ThrottleKey) -> Result<Option<LoginThrottle>, AuthError> {
+        Ok(self
+            .throttles
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|throttle| throttle.key() == key)
+            .cloned())
+    }
+
+    fn record_failure(
+        &self,
+        key: &ThrottleKey,
+        policy: &LockoutPolicy,
+        now: DateTime<Utc>,
+    ) -> Result<LoginThrottle, AuthError> {
+        let mut throttles = self.throttles.lock().unwrap();
+        let index = match throttles.iter().position(|throttle| throttle.key() == key) {
+            Some(index) => index,
+            None => {
+                throttles.push(LoginThrottle::new(key.clone(), now));
+                throttles.len() - 1
+            }
+        };
+        throttles[index].record_failure(now, policy);
+        Ok(throttles[index].clone())
+    }
+
+    fn clear(&self, key: &ThrottleKey) -> Result<bool, AuthError> {
+        let mut throttles = self.throttles.lock().unwrap();
+        let before = throttles.len();
+        throttles.retain(|throttle| throttle.key() != key);
+        Ok(throttles.len() < before)
+    }
+
+    fn purge_stale(&self, cutoff: DateTime<Utc>) -> Result<usize, AuthError> {
+        let mut throttles = self.throttles.lock().unwrap();
+        let before = throttles.len();
+        throttles.retain(|throttle| {
+            throttle.last_failed_at() >= cutoff
+                || throttle.locked_until().is_some_and(|until| until >= cutoff)
+        });
+        Ok(before - throttles.len())
+    }
+}
+
+impl AsyncLoginThrottleRepository for InMemoryLoginThrottleRepository {
+    fn find<'a>(
+        &'a self,
+        key: &'a ThrottleKey,
+    ) -> BoxFuture<'a, Result<Option<LoginThrottle>, AuthError>> {
+        Box::pin(async move { LoginThrottleRepository::find(self, key) })
+    }
+
+    fn record_failure<'a>(
+        &'a self,
+        key: &'a ThrottleKey,
+        policy: &'a LockoutPolicy,
+        now: DateTime<Utc>,
+    ) -> BoxFuture<'a, Result<LoginThrottle, AuthError>> {
+        Box::pin(async move { LoginThrottleRepository::record_failure(self, key, policy, now) })
+    }
+
+    fn clear<'a>(&'a self, key: &'a ThrottleKey) -> BoxFuture<'a, Result<bool, AuthError>> {
+        Box::pin(async move { LoginThrottleRepository::clear(self, key) })
+    }
+
+    fn purge_stale(&self, cutoff: DateTime<Utc>) -> BoxFuture<'_, Result<usize, AuthError>> {
+        Box::pin(async move { LoginThrottleRepository::purge_stale(self, cutoff) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    fn policy() -> LockoutPolicy {
+        LockoutPolicy {
+            email_threshold: 2,
+            ip_threshold: 10,
+            base_lockout: Duration::seconds(30),
+            max_lockout: Duration::minutes(15),
+            reset_after: Duration::minutes(15),
+        }
+    }
+
+    #[test]
+    fn test_failures_lock_until_cleared() {
+        let repo = InMemoryLoginThrottleRepository::new();
+        let key = ThrottleKey::email("ada@example.com");
+        let now = Utc::now();
+
+        LoginThrottleRepository::record_failure(&repo, &key, &policy(), now).unwrap();
+        let throttle = LoginThrottleRepository::record_failure(&repo, &key, &policy(), now).unwrap();
+        assert_eq!(throttle.failed_attempts(), 2);
+        assert!(throttle.retry_after(now).is_some());
+        assert_eq!(LoginThrottleRepository::find(&repo, &key).unwrap().unwrap().failed_attempts(), 2);
+
+        assert!(LoginThrottleRepository::clear(&repo, &key).unwrap());
+        assert!(!LoginThrottleRepository::clear(&repo, &key).unwrap());
+        assert!(LoginThrottleRepository::find(&repo, &key).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_purge_keeps_recent_and_locked_counters() {
+        let repo = InMemoryLoginThrottleRepository::new();
+        let now = Utc::now();
+        let stale = ThrottleKey::email("stale@example.com");
+        let locked = ThrottleKey::email("locked@example.com");
+        let recent = ThrottleKey::email("recent@example.com");
+        let long_ago = now - Duration::hours(1);
+        LoginThrottleRepository::record_failure(&repo, &stale, &policy(), long_ago).unwrap();
+        let long_lockout = LockoutPolicy {
+            base_lockout: Duration::hours(2),
+            max_lockout: Duration::hours(2),
+            ..policy()
+        };
+        LoginThrottleRepository::record_failure(&repo, &locked, &long_lockout, long_ago)
+            .unwrap();
+        LoginThrottleRepository::record_failure(&repo, &locked, &long_lockout, long_ago)
+            .unwrap();
+        LoginThrottleRepository::record_failure(&repo, &recent, &policy(), now).unwrap();
+
+        assert_eq!(LoginThrottleRepository::purge_stale(&repo, now - Duration::minutes(15)).unwrap(), 1);
+        assert!(LoginThrottleRepository::find(&repo, &stale).unwrap().is_none());
+        assert!(LoginThrottleRepository::find(&repo, &locked).unwrap().is_some());
+        assert!(LoginThrottleRepository::find(&repo, &recent).unwrap().is_some());
+    }
+}
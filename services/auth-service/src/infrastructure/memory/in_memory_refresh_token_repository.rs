@@ -0,0 +1,236 @@
+//! In-memory implementation of the RefreshTokenRepository trait
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::domain::auth::BoxFuture;
+use crate::domain::error::AuthError;
+use crate::domain::refresh_token::{
+    AsyncRefreshTokenRepository, RefreshToken, RefreshTokenRepository,
+};
+
+use super::in_memory_session_repository::InMemorySessionRepository;
+
+/// In-memory implementation of RefreshTokenRepository
+///
+/// Behaves like `DieselRefreshTokenRepository`: revoking tokens ends the
+/// sessions they make up in the given session store, and only an
+/// unrotated, unrevoked token can be rotated.
+#[derive(Debug)]
+pub struct InMemoryRefreshTokenRepository {
+    tokens: Mutex<HashMap<Uuid, RefreshToken>>,
+    sessions: Arc<InMemorySessionRepository>,
+}
+
+impl InMemoryRefreshTokenRepository {
+    /// Create an empty repository ending sessions in `sessions`
+    #[must_use]
+    pub fn new(sessions: Arc<InMemorySessionRepository>) -> Self {
+        Self {
+            tokens: Mutex::new(HashMap::new()),
+            sessions,
+        }
+    }
+
+    /// Revoke the live tokens `revokes` picks, and their sessions
+    fn revoke_where(&self, revokes: impl Fn(Uuid, Uuid) -> bool) {
+        let now = Utc::now();
+        for token in self.tokens.lock().unwrap().values_mut() {
+            if !token.is_revoked() && revokes(token.user_id(), token.family_id()) {
+                *token = with(token, token.rotated_at(), Some(now));
+            }
+        }
+        self.sessions
+            .end_where(now, |session| revokes(session.user_id(), session.id()));
+    }
+}
+
+impl RefreshTokenRepository for InMemoryRefreshTokenRepository {
+    fn create(&self, token: &RefreshToken) -> Result<(), AuthError> {
+        self.tokens
+            .lock()
+            .unwrap()
+            .insert(token.id(), token.clone());
+        Ok(())
+    }
+
+    fn find_by_hash(&self, token_hash: &str) -> Result<RefreshToken, AuthError> {
+        self.tokens
+            .lock()
+            .unwrap()
+            .values()
+            .find(|token| token.token_hash() == token_hash)
+            .cloned()
+            .ok_or(AuthError::InvalidToken)
+    }
+
+    fn rotate(&self, current_id: Uuid, next: &RefreshToken) -> Result<bool, AuthError> {
+        let mut tokens = self.tokens.lock().unwrap();
+        let Some(current) = tokens
+            .get_mut(&current_id)
+            .filter(|token| !token.is_rotated() && !token.is_revoked())
+        else {
+            return Ok(false);
+        };
+
+        *current = with(current, Some(Utc::now()), None);
+        tokens.insert(next.id(), next.clone());
+        Ok(true)
+    }
+
+    fn revoke_family(&self, family_id: Uuid) -> Result<(), AuthError> {
+        self.revoke_where(|_, family| family == family_id);
+        Ok(())
+    }
+
+    fn revoke_all_for_user(&self, user_id: Uuid) -> Result<(), AuthError> {
+        self.revoke_where(|user, _| user == user_id);
+        Ok(())
+    }
+}
+
+impl AsyncRefreshTokenRepository for InMemoryRefreshTokenRepository {
+    fn create<'a>(&'a self, token: &'a RefreshToken) -> BoxFuture<'a, Result<(), AuthError>> {
+        Box::pin(async move { RefreshTokenRepository::create(self, token) })
+    }
+
+    fn find_by_hash<'a>(
+        &'a self,
+        token_hash: &'a str,
+    ) -> BoxFuture<'a, Result<RefreshToken, AuthError>> {
+        Box::pin(async move { RefreshTokenRepository::find_by_hash(self, token_hash) })
+    }
+
+    fn rotate<'a>(
+        &'a self,
+        current_id: Uuid,
+        next: &'a RefreshToken,
+    ) -> BoxFuture<'a, Result<bool, AuthError>> {
+        Box::pin(async move { RefreshTokenRepository::rotate(self, current_id, next) })
+    }
+
+    fn revoke_family(&self, family_id: Uuid) -> BoxFuture<'_, Result<(), AuthError>> {
+        Box::pin(async move { RefreshTokenRepository::revoke_family(self, family_id) })
+    }
+
+    fn revoke_all_for_user(&self, user_id: Uuid) -> BoxFuture<'_, Result<(), AuthError>> {
+        Box::pin(async move { RefreshTokenRepository::revoke_all_for_user(self, user_id) })
+    }
+}
+
+/// Copy of a token with new rotation and revocation timestamps
+fn with(
+    token: &RefreshToken,
+    rotated_at: Option<DateTime<Utc>>,
+    revoked_at: Option<DateTime<Utc>>,
+) -> RefreshToken {
+    RefreshToken::from_persistence(
+        token.id(),
+        token.user_id(),
+        token.family_id(),
+        token.token_hash().to_string(),
+        token.expires_at(),
+        token.created_at(),
+        rotated_at,
+        revoked_at,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::domain::session::{Session, SessionClient, SessionRepository};
+
+    fn repositories() -> (
+        Arc<InMemorySessionRepository>,
+        InMemoryRefreshTokenRepository,
+    ) {
+        let sessions = Arc::new(InMemorySessionRepository::new());
+        let tokens = InMemoryRefreshTokenRepository::new(Arc::clone(&sessions));
+        (sessions, tokens)
+    }
+
+    fn login(
+        sessions: &InMemorySessionRepository,
+        tokens: &InMemoryRefreshTokenRepository,
+        user_id: Uuid,
+        hash: &str,
+    ) -> RefreshToken {
+        let token =
+            RefreshToken::issue(user_id, Uuid::new_v4(), hash.to_string(), Duration::days(1));
+        let session = Session::start(
+            token.family_id(),
+            user_id,
+            &SessionClient::default(),
+            token.expires_at(),
+        );
+        SessionRepository::create(sessions, &session).unwrap();
+        RefreshTokenRepository::create(tokens, &token).unwrap();
+        token
+    }
+
+    #[test]
+    fn test_token_rotates_once() {
+        let (sessions, tokens) = repositories();
+        let current = login(&sessions, &tokens, Uuid::new_v4(), "first");
+        let next = RefreshToken::issue(
+            current.user_id(),
+            current.family_id(),
+            "second".to_string(),
+            Duration::days(1),
+        );
+
+        assert!(RefreshTokenRepository::rotate(&tokens, current.id(), &next).unwrap());
+        assert!(!RefreshTokenRepository::rotate(&tokens, current.id(), &next).unwrap());
+        assert!(RefreshTokenRepository::find_by_hash(&tokens, "first").unwrap().is_rotated());
+        assert_eq!(RefreshTokenRepository::find_by_hash(&tokens, "second").unwrap().id(), next.id());
+        assert!(matches!(
+            RefreshTokenRepository::find_by_hash(&tokens, "third"),
+            Err(AuthError::InvalidToken)
+        ));
+    }
+
+    #[test]
+    fn test_revoking_a_family_ends_its_session_only() {
+        let (sessions, tokens) = repositories();
+        let user_id = Uuid::new_v4();
+        let ended = login(&sessions, &tokens, user_id, "ended");
+        let kept = login(&sessions, &tokens, user_id, "kept");
+
+        RefreshTokenRepository::revoke_family(&tokens, ended.family_id()).unwrap();
+
+        assert!(RefreshTokenRepository::find_by_hash(&tokens, "ended").unwrap().is_revoked());
+        assert!(SessionRepository::is_revoked(&*sessions, ended.family_id()).unwrap());
+        assert!(!RefreshTokenRepository::find_by_hash(&tokens, "kept").unwrap().is_revoked());
+        assert!(!SessionRepository::is_revoked(&*sessions, kept.family_id()).unwrap());
+
+        let next = RefreshToken::issue(
+            user_id,
+            ended.family_id(),
+            "next".to_string(),
+            Duration::days(1),
+        );
+        assert!(!RefreshTokenRepository::rotate(&tokens, ended.id(), &next).unwrap());
+    }
+
+    #[test]
+    fn test_revoking_all_for_user_ends_their_sessions() {
+        let (sessions, tokens) = repositories();
+        let user_id = Uuid::new_v4();
+        let first = login(&sessions, &tokens, user_id, "first");
+        let second = login(&sessions, &tokens, user_id, "second");
+        let other = login(&sessions, &tokens, Uuid::new_v4(), "other");
+
+        RefreshTokenRepository::revoke_all_for_user(&tokens, user_id).unwrap();
+
+        assert!(SessionRepository::is_revoked(&*sessions, first.family_id()).unwrap());
+        assert!(SessionRepository::is_revoked(&*sessions, second.family_id()).unwrap());
+        assert!(!SessionRepository::is_revoked(&*sessions, other.family_id()).unwrap());
+        assert!(!RefreshTokenRepository::find_by_hash(&tokens, "other").unwrap().is_revoked());
+    }
+}
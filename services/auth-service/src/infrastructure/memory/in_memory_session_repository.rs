@@ -0,0 +1,237 @@
+//! In-memory implementation of the SessionRepository trait
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::domain::auth::BoxFuture;
+use crate::domain::error::AuthError;
+use crate::domain::session::{AsyncSessionRepository, Session, SessionRepository};
+
+/// In-memory implementation of SessionRepository
+///
+/// Behaves like `DieselSessionRepository`. Sessions are ended through the
+/// `InMemoryRefreshTokenRepository` sharing this store, as the Diesel
+/// repositories end them through the shared `sessions` table. Login events
+/// are not recorded anywhere.
+#[derive(Debug, Default)]
+pub struct InMemorySessionRepository {
+    sessions: Mutex<HashMap<Uuid, Session>>,
+}
+
+impl InMemorySessionRepository {
+    /// Create an empty repository
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// End the live sessions `ends` picks, as of `now`
+    pub(super) fn end_where(&self, now: DateTime<Utc>, ends: impl Fn(&Session) -> bool) {
+        for session in self.sessions.lock().unwrap().values_mut() {
+            if session.revoked_at().is_none() && ends(session) {
+                *session = with(session, |s| s.revoked_at = Some(now));
+            }
+        }
+    }
+}
+
+impl SessionRepository for InMemorySessionRepository {
+    fn create(&self, session: &Session) -> Result<(), AuthError> {
+        self.sessions
+            .lock()
+            .unwrap()
+            .insert(session.id(), session.clone());
+        Ok(())
+    }
+
+    fn find_by_id(&self, id: Uuid) -> Result<Session, AuthError> {
+        self.sessions
+            .lock()
+            .unwrap()
+            .get(&id)
+            .cloned()
+            .ok_or(AuthError::SessionNotFound)
+    }
+
+    fn find_active_for_user(
+        &self,
+        user_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<Session>, AuthError> {
+        let mut active: Vec<Session> = self
+            .sessions
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|session| session.user_id() == user_id && session.is_active(now))
+            .cloned()
+            .collect();
+        active.sort_by_key(|session| std::cmp::Reverse(session.last_seen_at()));
+        Ok(active)
+    }
+
+    fn touch(
+        &self,
+        id: Uuid,
+        now: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), AuthError> {
+        if let Some(session) = self.sessions.lock().unwrap().get_mut(&id) {
+            *session = with(session, |s| {
+                s.last_seen_at = now;
+                s.expires_at = expires_at;
+            });
+        }
+        Ok(())
+    }
+
+    fn set_organization(&self, id: Uuid, organization_id: Option<Uuid>) -> Result<(), AuthError> {
+        if let Some(session) = self.sessions.lock().unwrap().get_mut(&id) {
+            *session = with(session, |s| s.organization_id = organization_id);
+        }
+        Ok(())
+    }
+
+    fn is_revoked(&self, id: Uuid) -> Result<bool, AuthError> {
+        // Only a stored, unrevoked session is live, as in the database
+        Ok(self
+            .sessions
+            .lock()
+            .unwrap()
+            .get(&id)
+            .is_none_or(|session| session.revoked_at().is_some()))
+    }
+}
+
+impl AsyncSessionRepository for InMemorySessionRepository {
+    fn create<'a>(&'a self, session: &'a Session) -> BoxFuture<'a, Result<(), AuthError>> {
+        Box::pin(async move { SessionRepository::create(self, session) })
+    }
+
+    fn find_by_id(&self, id: Uuid) -> BoxFuture<'_, Result<Session, AuthError>> {
+        Box::pin(async move { SessionRepository::find_by_id(self, id) })
+    }
+
+    fn find_active_for_user(
+        &self,
+        user_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> BoxFuture<'_, Result<Vec<Session>, AuthError>> {
+        Box::pin(async move { SessionRepository::find_active_for_user(self, user_id, now) })
+    }
+
+    fn touch(
+        &self,
+        id: Uuid,
+        now: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> BoxFuture<'_, Result<(), AuthError>> {
+        Box::pin(async move { SessionRepository::touch(self, id, now, expires_at) })
+    }
+
+    fn set_organization(
+        &self,
+        id: Uuid,
+        organization_id: Option<Uuid>,
+    ) -> BoxFuture<'_, Result<(), AuthError>> {
+        Box::pin(async move { SessionRepository::set_organization(self, id, organization_id) })
+    }
+
+    fn is_revoked(&self, id: Uuid) -> BoxFuture<'_, Result<bool, AuthError>> {
+        Box::pin(async move { SessionRepository::is_revoked(self, id) })
+    }
+}
+
+/// Columns of a stored session that change after it starts
+struct Changes {
+    last_seen_at: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+    revoked_at: Option<DateTime<Utc>>,
+    organization_id: Option<Uuid>,
+}
+
+/// Copy of a session with some of its columns changed, as an `UPDATE` would
+fn with(session: &Session, change: impl FnOnce(&mut Changes)) -> Session {
+    let mut changes = Changes {
+        last_seen_at: session.last_seen_at(),
+        expires_at: session.expires_at(),
+        revoked_at: session.revoked_at(),
+        organization_id: session.organization_id(),
+    };
+    change(&mut changes);
+
+    Session::from_persistence(
+        session.id(),
+        session.user_id(),
+        session.user_agent().map(str::to_string),
+        session.ip_address(),
+        session.created_at(),
+        changes.last_seen_at,
+        changes.expires_at,
+        changes.revoked_at,
+        changes.organization_id,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::domain::session::SessionClient;
+
+    fn session(user_id: Uuid, expires_at: DateTime<Utc>) -> Session {
+        Session::start(
+            Uuid::new_v4(),
+            user_id,
+            &SessionClient::default(),
+            expires_at,
+        )
+    }
+
+    #[test]
+    fn test_unknown_and_ended_sessions_count_as_revoked() {
+        let repo = InMemorySessionRepository::new();
+        let user_id = Uuid::new_v4();
+        let live = session(user_id, Utc::now() + Duration::days(1));
+        let ended = session(user_id, Utc::now() + Duration::days(1));
+        SessionRepository::create(&repo, &live).unwrap();
+        SessionRepository::create(&repo, &ended).unwrap();
+
+        repo.end_where(Utc::now(), |session| session.id() == ended.id());
+
+        assert!(!SessionRepository::is_revoked(&repo, live.id()).unwrap());
+        assert!(SessionRepository::is_revoked(&repo, ended.id()).unwrap());
+        assert!(SessionRepository::is_revoked(&repo, Uuid::new_v4()).unwrap());
+    }
+
+    #[test]
+    fn test_active_sessions_are_most_recently_seen_first() {
+        let repo = InMemorySessionRepository::new();
+        let user_id = Uuid::new_v4();
+        let now = Utc::now();
+        let older = session(user_id, now + Duration::days(1));
+        let newer = session(user_id, now + Duration::days(1));
+        let expired = session(user_id, now - Duration::seconds(1));
+        for session in [&older, &newer, &expired] {
+            SessionRepository::create(&repo, session).unwrap();
+        }
+        SessionRepository::create(&repo, &session(Uuid::new_v4(), now + Duration::days(1)))
+            .unwrap();
+
+        SessionRepository::touch(&repo, 
+            newer.id(),
+            now + Duration::minutes(1),
+            now + Duration::days(2),
+        )
+        .unwrap();
+
+        let active = SessionRepository::find_active_for_user(&repo, user_id, now).unwrap();
+        let ids: Vec<Uuid> = active.iter().map(Session::id).collect();
+        assert_eq!(ids, [newer.id(), older.id()]);
+        assert_eq!(active[0].expires_at(), now + Duration::days(2));
+    }
+}
@@ -0,0 +1,99 @@
+//! In-memory implementation of the TokenRevocationStore trait
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::domain::auth::BoxFuture;
+use crate::domain::error::AuthError;
+use crate::domain::token_revocation::{AsyncTokenRevocationStore, TokenRevocationStore};
+
+/// Revocation list of a single process
+///
+/// Behaves like `DieselTokenRevocationStore`, but is not shared between
+/// replicas, so it only suits tests and single-instance development.
+#[derive(Debug, Default)]
+pub struct InMemoryTokenRevocationStore {
+    /// Expiry of each revoked token, by `jti`
+    revoked: Mutex<HashMap<String, DateTime<Utc>>>,
+}
+
+impl InMemoryTokenRevocationStore {
+    /// Create an empty store
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl TokenRevocationStore for InMemoryTokenRevocationStore {
+    fn revoke(
+        &self,
+        jti: &str,
+        _user_id: Uuid,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), AuthError> {
+        self.revoked
+            .lock()
+            .unwrap()
+            .entry(jti.to_string())
+            .or_insert(expires_at);
+        Ok(())
+    }
+
+    fn is_revoked(&self, jti: &str) -> Result<bool, AuthError> {
+        Ok(self.revoked.lock().unwrap().contains_key(jti))
+    }
+
+    fn purge_expired(&self, now: DateTime<Utc>) -> Result<usize, AuthError> {
+        let mut revoked = self.revoked.lock().unwrap();
+        let before = revoked.len();
+        revoked.retain(|_, expires_at| *expires_at >= now);
+        Ok(before - revoked.len())
+    }
+}
+
+impl AsyncTokenRevocationStore for InMemoryTokenRevocationStore {
+    fn revoke<'a>(
+        &'a self,
+        jti: &'a str,
+        user_id: Uuid,
+        expires_at: DateTime<Utc>,
+    ) -> BoxFuture<'a, Result<(), AuthError>> {
+        Box::pin(async move { TokenRevocationStore::revoke(self, jti, user_id, expires_at) })
+    }
+
+    fn is_revoked<'a>(&'a self, jti: &'a str) -> BoxFuture<'a, Result<bool, AuthError>> {
+        Box::pin(async move { TokenRevocationStore::is_revoked(self, jti) })
+    }
+
+    fn purge_expired(&self, now: DateTime<Utc>) -> BoxFuture<'_, Result<usize, AuthError>> {
+        Box::pin(async move { TokenRevocationStore::purge_expired(self, now) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_revoked_until_purged_after_expiry() {
+        let store = InMemoryTokenRevocationStore::new();
+        let now = Utc::now();
+        TokenRevocationStore::revoke(&store, "expired", Uuid::new_v4(), now - Duration::seconds(1))
+            .unwrap();
+        TokenRevocationStore::revoke(&store, "live", Uuid::new_v4(), now + Duration::hours(1))
+            .unwrap();
+
+        assert!(TokenRevocationStore::is_revoked(&store, "expired").unwrap());
+        assert!(!TokenRevocationStore::is_revoked(&store, "other").unwrap());
+
+        assert_eq!(TokenRevocationStore::purge_expired(&store, now).unwrap(), 1);
+        assert!(!TokenRevocationStore::is_revoked(&store, "expired").unwrap());
+        assert!(TokenRevocationStore::is_revoked(&store, "live").unwrap());
+    }
+}
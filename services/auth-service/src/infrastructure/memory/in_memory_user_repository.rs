@@ -0,0 +1,297 @@
+//! In-memory implementation of the UserRepository and UserSearchRepository traits
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::domain::auth::{AsyncUserRepository, BoxFuture, UserRepository};
+use crate::domain::error::AuthError;
+use crate::domain::user::{HashedPassword, User};
+use crate::domain::user_search::{
+    EmailMatch, UserCursor, UserFilter, UserPage, UserSearchRepository,
+};
+
+/// In-memory implementation of UserRepository
+///
+/// Behaves like `DieselUserRepository`: emails are looked up trimmed and
+/// lowercased, an email address belongs to at most one account, and users
+/// come back as stored, without the events they raised. Timestamps are
+/// kept to the microsecond, like Postgres, so search cursors round-trip.
+/// Events are not recorded anywhere.
+#[derive(Debug, Default)]
+pub struct InMemoryUserRepository {
+    users: Mutex<HashMap<Uuid, User>>,
+}
+
+impl InMemoryUserRepository {
+    /// Create an empty repository
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl UserRepository for InMemoryUserRepository {
+    fn find_by_id(&self, id: Uuid) -> Result<User, AuthError> {
+        self.users
+            .lock()
+            .unwrap()
+            .get(&id)
+            .cloned()
+            .ok_or(AuthError::UserNotFound)
+    }
+
+    fn find_by_email(&self, email: &str) -> Result<User, AuthError> {
+        let normalized_email = email.trim().to_lowercase();
+
+        self.users
+            .lock()
+            .unwrap()
+            .values()
+            .find(|user| user.email().as_str() == normalized_email)
+            .cloned()
+            .ok_or(AuthError::UserNotFound)
+    }
+
+    fn exists_by_email(&self, email: &str) -> Result<bool, AuthError> {
+        match UserRepository::find_by_email(self, email) {
+            Ok(_) => Ok(true),
+            Err(AuthError::UserNotFound) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn create(&self, user: &User) -> Result<User, AuthError> {
+        let mut users = self.users.lock().unwrap();
+        let id = user.id().as_uuid();
+        if users.contains_key(&id) || email_taken(&users, user) {
+            return Err(AuthError::UserAlreadyExists);
+        }
+
+        let stored = stored(user);
+        users.insert(id, stored.clone());
+        Ok(stored)
+    }
+
+    fn update(&self, user: &User) -> Result<User, AuthError> {
+        let mut users = self.users.lock().unwrap();
+        let id = user.id().as_uuid();
+        if !users.contains_key(&id) {
+            return Err(AuthError::UserNotFound);
+        }
+        // Another account took the new email address first
+        if email_taken(&users, user) {
+            return Err(AuthError::UserAlreadyExists);
+        }
+
+        let stored = stored(user);
+        users.insert(id, stored.clone());
+        Ok(stored)
+    }
+}
+
+impl AsyncUserRepository for InMemoryUserRepository {
+    fn find_by_id(&self, id: Uuid) -> BoxFuture<'_, Result<User, AuthError>> {
+        Box::pin(async move { UserRepository::find_by_id(self, id) })
+    }
+
+    fn find_by_email<'a>(&'a self, email: &'a str) -> BoxFuture<'a, Result<User, AuthError>> {
+        Box::pin(async move { UserRepository::find_by_email(self, email) })
+    }
+
+    fn exists_by_email<'a>(&'a self, email: &'a str) -> BoxFuture<'a, Result<bool, AuthError>> {
+        Box::pin(async move { UserRepository::exists_by_email(self, email) })
+    }
+
+    fn create<'a>(&'a self, user: &'a User) -> BoxFuture<'a, Result<User, AuthError>> {
+        Box::pin(async move { UserRepository::create(self, user) })
+    }
+
+    fn update<'a>(&'a self, user: &'a User) -> BoxFuture<'a, Result<User, AuthError>> {
+        Box::pin(async move { UserRepository::update(self, user) })
+    }
+}
+
+impl UserSearchRepository for InMemoryUserRepository {
+    fn search(
+        &self,
+        filter: &UserFilter,
+        cursor: Option<&UserCursor>,
+        limit: usize,
+    ) -> Result<UserPage, AuthError> {
+        let users = self.users.lock().unwrap();
+
+        let mut matching: Vec<&User> = users
+            .values()
+            .filter(|user| matches(user, filter))
+            .filter(|user| cursor.is_none_or(|cursor| is_after(user, cursor)))
+            .collect();
+        matching.sort_by(|a, b| {
+            (b.created_at(), b.id().as_uuid()).cmp(&(a.created_at(), a.id().as_uuid()))
+        });
+
+        let has_more = matching.len() > limit;
+        let users: Vec<User> = matching.into_iter().take(limit).cloned().collect();
+        let next_cursor = if has_more {
+            users.last().map(UserCursor::after)
+        } else {
+            None
+        };
+
+        Ok(UserPage { users, next_cursor })
+    }
+}
+
+/// Check whether another account already has the user's email address
+fn email_taken(users: &HashMap<Uuid, User>, user: &User) -> bool {
+    users
+        .values()
+        .any(|other| other.id() != user.id() && other.email() == user.email())
+}
+
+/// Copy of a user as the database would store and return it
+fn stored(user: &User) -> User {
+    User::from_persistence(
+        user.id().as_uuid(),
+        user.email().as_str().to_string(),
+        user.hashed_password()
+            .map(HashedPassword::as_str)
+            .map(str::to_string),
+        user.display_name().map(str::to_string),
+        user.is_active(),
+        user.email_verified_at().map(to_micros),
+        to_micros(user.created_at()),
+        to_micros(user.updated_at()),
+    )
+    .with_roles(user.roles().iter().copied())
+}
+
+/// Truncate a timestamp to the microsecond
+fn to_micros(at: DateTime<Utc>) -> DateTime<Utc> {
+    DateTime::from_timestamp_micros(at.timestamp_micros()).unwrap_or(at)
+}
+
+/// Check whether a user meets every criterion of a filter
+fn matches(user: &User, filter: &UserFilter) -> bool {
+    let email = user.email().as_str();
+    filter
+        .is_active
+        .is_none_or(|active| user.is_active() == active)
+        && filter.role.is_none_or(|role| user.has_role(role))
+        && filter
+            .created_after
+            .is_none_or(|after| user.created_at() >= after)
+        && filter
+            .created_before
+            .is_none_or(|before| user.created_at() < before)
+        && filter.email.as_ref().is_none_or(|term| match term {
+            EmailMatch::Prefix(_) => email.starts_with(&term.term()),
+            EmailMatch::Contains(_) => email.contains(&term.term()),
+        })
+}
+
+/// Check whether a user comes after the cursor, newest first
+fn is_after(user: &User, cursor: &UserCursor) -> bool {
+    (user.created_at(), user.id().as_uuid()) < (cursor.created_at, cursor.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::domain::role::Role;
+    use crate::domain::user::Email;
+
+    fn user(email: &str) -> User {
+        User::new_federated(Email::new(email).unwrap(), None)
+    }
+
+    fn user_created_at(email: &str, created_at: DateTime<Utc>) -> User {
+        User::from_persistence(
+            Uuid::new_v4(),
+            email.to_string(),
+            None,
+            None,
+            true,
+            None,
+            created_at,
+            created_at,
+        )
+        .with_roles([Role::Customer])
+    }
+
+    #[test]
+    fn test_email_lookup_is_normalized() {
+        let repo = InMemoryUserRepository::new();
+        let alice = UserRepository::create(&repo, &user("alice@example.com")).unwrap();
+
+        let found = UserRepository::find_by_email(&repo, "  Alice@Example.COM ").unwrap();
+        assert_eq!(found.id(), alice.id());
+        assert!(UserRepository::exists_by_email(&repo, "ALICE@example.com").unwrap());
+        assert!(!UserRepository::exists_by_email(&repo, "bob@example.com").unwrap());
+    }
+
+    #[test]
+    fn test_email_belongs_to_one_account() {
+        let repo = InMemoryUserRepository::new();
+        UserRepository::create(&repo, &user("alice@example.com")).unwrap();
+        let bob = UserRepository::create(&repo, &user("bob@example.com")).unwrap();
+
+        let duplicate = UserRepository::create(&repo, &user("Alice@example.com"));
+        assert!(matches!(duplicate, Err(AuthError::UserAlreadyExists)));
+
+        let mut bob = bob;
+        bob.change_email(Email::new("alice@example.com").unwrap());
+        let taken = UserRepository::update(&repo, &bob);
+        assert!(matches!(taken, Err(AuthError::UserAlreadyExists)));
+        let unchanged = UserRepository::find_by_id(&repo, bob.id().as_uuid()).unwrap();
+        assert_eq!(unchanged.email().as_str(), "bob@example.com");
+    }
+
+    #[test]
+    fn test_update_of_unknown_user_fails() {
+        let repo = InMemoryUserRepository::new();
+
+        let result = UserRepository::update(&repo, &user("alice@example.com"));
+        assert!(matches!(result, Err(AuthError::UserNotFound)));
+    }
+
+    #[test]
+    fn test_users_come_back_without_events() {
+        let repo = InMemoryUserRepository::new();
+        let alice = user("alice@example.com");
+        assert!(!alice.pending_events().is_empty());
+
+        let stored = UserRepository::create(&repo, &alice).unwrap();
+        assert!(stored.pending_events().is_empty());
+    }
+
+    #[test]
+    fn test_search_pages_newest_first() {
+        let repo = InMemoryUserRepository::new();
+        let now = Utc::now();
+        for (i, email) in ["a@example.com", "b@example.com", "c@example.com"]
+            .iter()
+            .enumerate()
+        {
+            let created_at = now - Duration::minutes(i as i64);
+            UserRepository::create(&repo, &user_created_at(email, created_at)).unwrap();
+        }
+
+        let first = repo.search(&UserFilter::default(), None, 2).unwrap();
+        let emails: Vec<&str> = first.users.iter().map(|u| u.email().as_str()).collect();
+        assert_eq!(emails, ["a@example.com", "b@example.com"]);
+
+        // The cursor survives encoding, as timestamps are stored to the microsecond
+        let cursor = UserCursor::decode(&first.next_cursor.unwrap().encode()).unwrap();
+        let second = repo
+            .search(&UserFilter::default(), Some(&cursor), 2)
+            .unwrap();
+        let emails: Vec<&str> = second.users.iter().map(|u| u.email().as_str()).collect();
+        assert_eq!(emails, ["c@example.com"]);
+        assert!(second.next_cursor.is_none());
+    }
+}
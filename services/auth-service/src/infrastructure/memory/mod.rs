@@ -0,0 +1,7 @@
+//! In-memory infrastructure - repositories for tests and local development without a database
+
+pub mod in_memory_login_throttle_repository;
+pub mod in_memory_refresh_token_repository;
+pub mod in_memory_session_repository;
+pub mod in_memory_token_revocation_store;
+pub mod in_memory_user_repository;
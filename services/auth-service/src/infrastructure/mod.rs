@@ -1,8 +1,13 @@
 //! Infrastructure layer - external system integrations
 //!
-//! Contains implementations for database, JWT, password hashing, caching, etc.
+//! Contains implementations for database, JWT, password hashing, caching, mail, events,
+//! identity providers, etc.
 
 pub mod cache;
 pub mod config;
 pub mod db;
+pub mod events;
+pub mod federation;
+pub mod mail;
+pub mod memory;
 pub mod security;
@@ -0,0 +1,469 @@
+//! Token service accepting API keys alongside JWTs
+//!
+//! Wraps the JWT token service so every caller of `validate_token`
+//! (HTTP, gRPC and the gateway through `ValidateToken`) accepts API keys
+//! without knowing about them. Keys are recognised by their prefix and
+//! looked up by hash; anything else is handed to the inner service.
+
+use std::sync::Arc;
+
+use chrono::Utc;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::domain::api_key::{is_api_key, ApiKey, ApiKeyRepository, AsyncApiKeyRepository};
+use crate::domain::auth::{
+    self, AsyncTokenService, AsyncUserRepository, BoxFuture, OpaqueTokenGenerator, TokenData,
+    TokenService, UserRepository,
+};
+use crate::domain::error::AuthError;
+use crate::domain::user::User;
+
+/// A decorator over a `TokenService` that also validates API keys.
+pub struct ApiKeyTokenService {
+    inner: Arc<dyn AsyncTokenService + Send + Sync>,
+    api_keys: Arc<dyn ApiKeyRepository + Send + Sync>,
+    users: Arc<dyn UserRepository + Send + Sync>,
+    async_repositories: Option<AsyncRepositories>,
+    token_generator: Arc<dyn OpaqueTokenGenerator + Send + Sync>,
+}
+
+/// Where keys and their owners are looked up when validating asynchronously
+struct AsyncRepositories {
+    api_keys: Arc<dyn AsyncApiKeyRepository + Send + Sync>,
+    users: Arc<dyn AsyncUserRepository + Send + Sync>,
+}
+
+impl ApiKeyTokenService {
+    /// Create a new API key aware token service.
+    ///
+    /// # Arguments
+    /// * `inner` - Token service for everything that is not an API key
+    /// * `api_keys` - Where keys are looked up
+    /// * `users` - Where key owners are loaded, for their current roles
+    /// * `token_generator` - Hashes presented keys the way they were stored
+    pub fn new(
+        inner: Arc<dyn AsyncTokenService + Send + Sync>,
+        api_keys: Arc<dyn ApiKeyRepository + Send + Sync>,
+        users: Arc<dyn UserRepository + Send + Sync>,
+        token_generator: Arc<dyn OpaqueTokenGenerator + Send + Sync>,
+    ) -> Self {
+        Self {
+            inner,
+            api_keys,
+            users,
+            async_repositories: None,
+            token_generator,
+        }
+    }
+
+    /// Look keys and their owners up through async repositories when
+    /// validating asynchronously
+    ///
+    /// Without them, API keys are only accepted by the blocking
+    /// `validate_token`.
+    #[must_use]
+    pub fn with_async_repositories(
+        mut self,
+        api_keys: Arc<dyn AsyncApiKeyRepository + Send + Sync>,
+        users: Arc<dyn AsyncUserRepository + Send + Sync>,
+    ) -> Self {
+        self.async_repositories = Some(AsyncRepositories { api_keys, users });
+        self
+    }
+
+    fn validate_api_key(&self, key: &str) -> Result<TokenData, AuthError> {
+        let api_key = self
+            .api_keys
+            .find_by_hash(&self.token_generator.hash(key))?;
+        let owner = self.users.find_by_id(api_key.user_id())?;
+        let now = Utc::now();
+        let data = api_key.authenticate(&owner, now)?;
+
+        if api_key.needs_last_used_update(now) {
+            // Usage tracking is best effort and must not fail the request
+            if let Err(e) = self.api_keys.record_use(api_key.id(), now) {
+                warn_unrecorded_use(&api_key, &e);
+            }
+        }
+        Ok(data)
+    }
+
+    async fn validate_api_key_async(&self, key: &str) -> Result<TokenData, AuthError> {
+        let repositories = self.async_repositories()?;
+        let api_key = repositories
+            .api_keys
+            .find_by_hash(&self.token_generator.hash(key))
+            .await?;
+        let owner = repositories.users.find_by_id(api_key.user_id()).await?;
+        let now = Utc::now();
+        let data = api_key.authenticate(&owner, now)?;
+
+        if api_key.needs_last_used_update(now) {
+            if let Err(e) = repositories.api_keys.record_use(api_key.id(), now).await {
+                warn_unrecorded_use(&api_key, &e);
+            }
+        }
+        Ok(data)
+    }
+
+    fn async_repositories(&self) -> Result<&AsyncRepositories, AuthError> {
+        self.async_repositories.as_ref().ok_or_else(|| {
+            AuthError::Internal("Async API key repositories are not configured".to_string())
+        })
+    }
+}
+
+/// Id of the API key a validated token stands for
+fn key_id(token: &TokenData) -> Result<Uuid, AuthError> {
+    Uuid::parse_str(&token.jti).map_err(|_| AuthError::InvalidToken)
+}
+
+/// Whether the key found for a token is revoked; deleted keys count as revoked
+fn is_key_revoked(found: Result<ApiKey, AuthError>) -> Result<bool, AuthError> {
+    match found {
+        Ok(api_key) => Ok(api_key.is_revoked()),
+        Err(AuthError::ApiKeyNotFound) => Ok(true),
+        Err(e) => Err(e),
+    }
+}
+
+fn warn_unrecorded_use(api_key: &ApiKey, e: &AuthError) {
+    warn!(key_id = %api_key.id(), error = %e, "Failed to record API key use");
+}
+
+impl TokenService for ApiKeyTokenService {
+    fn create_token(&self, user: &User, session_id: Option<Uuid>) -> Result<String, AuthError> {
+        self.inner.create_token(user, session_id)
+    }
+
+    fn validate_token(&self, token: &str) -> Result<TokenData, AuthError> {
+        if is_api_key(token) {
+            self.validate_api_key(token)
+        } else {
+            TokenService::validate_token(self.inner.as_ref(), token)
+        }
+    }
+
+    fn revoke_token(&self, token: &TokenData) -> Result<(), AuthError> {
+        if !token.is_api_key() {
+            return self.inner.revoke_token(token);
+        }
+        self.api_keys.revoke(key_id(token)?).map(|_| ())
+    }
+
+    fn is_revoked(&self, token: &TokenData) -> Result<bool, AuthError> {
+        if !token.is_api_key() {
+            return TokenService::is_revoked(self.inner.as_ref(), token);
+        }
+        is_key_revoked(self.api_keys.find_by_id(key_id(token)?))
+    }
+}
+
+impl auth::AsyncTokenValidator for ApiKeyTokenService {
+    fn validate_token<'a>(&'a self, token: &'a str) -> BoxFuture<'a, Result<TokenData, AuthError>> {
+        if is_api_key(token) {
+            Box::pin(self.validate_api_key_async(token))
+        } else {
+            auth::AsyncTokenValidator::validate_token(self.inner.as_ref(), token)
+        }
+    }
+
+    fn is_revoked<'a>(&'a self, token: &'a TokenData) -> BoxFuture<'a, Result<bool, AuthError>> {
+        if !token.is_api_key() {
+            return auth::AsyncTokenValidator::is_revoked(self.inner.as_ref(), token);
+        }
+        Box::pin(async move {
+            let id = key_id(token)?;
+            is_key_revoked(self.async_repositories()?.api_keys.find_by_id(id).await)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use chrono::DateTime;
+
+    use super::*;
+    use crate::application::commands::test_support::token_data;
+    use crate::domain::api_key::ApiKey;
+    use crate::domain::role::{Permission, Role};
+    use crate::domain::user::{Email, HashedPassword};
+
+    // Token service accepting only the "jwt" token
+    struct FakeTokenService;
+
+    impl TokenService for FakeTokenService {
+        fn create_token(
+            &self,
+            _user: &User,
+            _session_id: Option<Uuid>,
+        ) -> Result<String, AuthError> {
+            Ok("jwt".to_string())
+        }
+
+        fn validate_token(&self, token: &str) -> Result<TokenData, AuthError> {
+            if token != "jwt" {
+                return Err(AuthError::InvalidToken);
+            }
+            Ok(TokenData {
+                roles: vec![Role::Customer],
+                ..token_data(Uuid::nil())
+            })
+        }
+
+        fn revoke_token(&self, _token: &TokenData) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        fn is_revoked(&self, _token: &TokenData) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+    }
+
+    impl auth::AsyncTokenValidator for FakeTokenService {
+        fn validate_token<'a>(
+            &'a self,
+            token: &'a str,
+        ) -> BoxFuture<'a, Result<TokenData, AuthError>> {
+            Box::pin(async move { TokenService::validate_token(self, token) })
+        }
+
+        fn is_revoked<'a>(
+            &'a self,
+            token: &'a TokenData,
+        ) -> BoxFuture<'a, Result<bool, AuthError>> {
+            Box::pin(async move { TokenService::is_revoked(self, token) })
+        }
+    }
+
+    // Key store holding keys in memory
+    #[derive(Default)]
+    struct FakeApiKeyRepository {
+        keys: Mutex<Vec<ApiKey>>,
+        uses: Mutex<Vec<Uuid>>,
+    }
+
+    impl ApiKeyRepository for FakeApiKeyRepository {
+        fn create(&self, key: &ApiKey) -> Result<(), AuthError> {
+            self.keys.lock().unwrap().push(key.clone());
+            Ok(())
+        }
+
+        fn find_by_hash(&self, key_hash: &str) -> Result<ApiKey, AuthError> {
+            self.keys
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|key| key.key_hash() == key_hash)
+                .cloned()
+                .ok_or(AuthError::InvalidToken)
+        }
+
+        fn find_by_id(&self, id: Uuid) -> Result<ApiKey, AuthError> {
+            self.keys
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|key| key.id() == id)
+                .cloned()
+                .ok_or(AuthError::ApiKeyNotFound)
+        }
+
+        fn list_for_user(&self, _user_id: Uuid) -> Result<Vec<ApiKey>, AuthError> {
+            Ok(self.keys.lock().unwrap().clone())
+        }
+
+        fn revoke(&self, id: Uuid) -> Result<bool, AuthError> {
+            let mut keys = self.keys.lock().unwrap();
+            let Some(key) = keys.iter_mut().find(|key| key.id() == id) else {
+                return Ok(false);
+            };
+            *key = ApiKey::from_persistence(
+                key.id(),
+                key.user_id(),
+                key.name().to_string(),
+                key.prefix().to_string(),
+                key.key_hash().to_string(),
+                key.scopes().to_vec(),
+                key.expires_at(),
+                key.last_used_at(),
+                key.created_at(),
+                Some(Utc::now()),
+            );
+            Ok(true)
+        }
+
+        fn record_use(&self, id: Uuid, _at: DateTime<Utc>) -> Result<(), AuthError> {
+            self.uses.lock().unwrap().push(id);
+            Ok(())
+        }
+    }
+
+    impl AsyncApiKeyRepository for FakeApiKeyRepository {
+        fn find_by_hash<'a>(
+            &'a self,
+            key_hash: &'a str,
+        ) -> BoxFuture<'a, Result<ApiKey, AuthError>> {
+            Box::pin(async move { ApiKeyRepository::find_by_hash(self, key_hash) })
+        }
+
+        fn find_by_id(&self, id: Uuid) -> BoxFuture<'_, Result<ApiKey, AuthError>> {
+            Box::pin(async move { ApiKeyRepository::find_by_id(self, id) })
+        }
+
+        fn record_use(&self, id: Uuid, at: DateTime<Utc>) -> BoxFuture<'_, Result<(), AuthError>> {
+            Box::pin(async move { ApiKeyRepository::record_use(self, id, at) })
+        }
+    }
+
+    // Repository holding a single user
+    struct FakeUserRepository {
+        user: User,
+    }
+
+    impl UserRepository for FakeUserRepository {
+        fn find_by_id(&self, id: Uuid) -> Result<User, AuthError> {
+            if self.user.id().as_uuid() == id {
+                Ok(self.user.clone())
+            } else {
+                Err(AuthError::UserNotFound)
+            }
+        }
+
+        fn find_by_email(&self, _email: &str) -> Result<User, AuthError> {
+            Err(AuthError::UserNotFound)
+        }
+
+        fn exists_by_email(&self, _email: &str) -> Result<bool, AuthError> {
+            Ok(false)
+        }
+
+        fn create(&self, user: &User) -> Result<User, AuthError> {
+            Ok(user.clone())
+        }
+
+        fn update(&self, user: &User) -> Result<User, AuthError> {
+            Ok(user.clone())
+        }
+    }
+
+    impl AsyncUserRepository for FakeUserRepository {
+        fn find_by_id(&self, id: Uuid) -> BoxFuture<'_, Result<User, AuthError>> {
+            Box::pin(async move { UserRepository::find_by_id(self, id) })
+        }
+
+        fn find_by_email<'a>(&'a self, email: &'a str) -> BoxFuture<'a, Result<User, AuthError>> {
+            Box::pin(async move { UserRepository::find_by_email(self, email) })
+        }
+
+        fn exists_by_email<'a>(&'a self, email: &'a str) -> BoxFuture<'a, Result<bool, AuthError>> {
+            Box::pin(async move { UserRepository::exists_by_email(self, email) })
+        }
+
+        fn create<'a>(&'a self, user: &'a User) -> BoxFuture<'a, Result<User, AuthError>> {
+            Box::pin(async move { UserRepository::create(self, user) })
+        }
+
+        fn update<'a>(&'a self, user: &'a User) -> BoxFuture<'a, Result<User, AuthError>> {
+            Box::pin(async move { UserRepository::update(self, user) })
+        }
+    }
+
+    struct FakeTokenGenerator;
+
+    impl OpaqueTokenGenerator for FakeTokenGenerator {
+        fn generate(&self) -> String {
+            "secret".to_string()
+        }
+
+        fn hash(&self, token: &str) -> String {
+            format!("hashed:{}", token)
+        }
+    }
+
+    const KEY: &str = "tsk_0123abcd_secret";
+
+    fn service() -> (ApiKeyTokenService, Arc<FakeApiKeyRepository>, Uuid) {
+        let user = User::new(
+            Email::new("kiosk@example.com").unwrap(),
+            HashedPassword::from_hash("hashed".to_string()),
+            None,
+        );
+        let api_keys = Arc::new(FakeApiKeyRepository::default());
+        let key = ApiKey::issue(
+            Uuid::new_v4(),
+            user.id().as_uuid(),
+            "Kiosk".to_string(),
+            format!("hashed:{}", KEY),
+            vec![Permission::PurchaseTickets],
+            None,
+        );
+        let key_id = key.id();
+        api_keys.create(&key).unwrap();
+
+        let users = Arc::new(FakeUserRepository { user });
+        let service = ApiKeyTokenService::new(
+            Arc::new(FakeTokenService),
+            api_keys.clone(),
+            users.clone(),
+            Arc::new(FakeTokenGenerator),
+        )
+        .with_async_repositories(api_keys.clone(), users);
+        (service, api_keys, key_id)
+    }
+
+    #[test]
+    fn test_validates_api_keys_and_jwts() {
+        let (service, api_keys, key_id) = service();
+
+        let data = service.validate_token(KEY).unwrap();
+        assert_eq!(data.jti, key_id.to_string());
+        assert_eq!(data.scopes, Some(vec![Permission::PurchaseTickets]));
+        assert_eq!(*api_keys.uses.lock().unwrap(), vec![key_id]);
+
+        assert!(!service.validate_token("jwt").unwrap().is_api_key());
+        assert!(matches!(
+            service.validate_token("tsk_unknown"),
+            Err(AuthError::InvalidToken)
+        ));
+    }
+
+    #[test]
+    fn test_revoked_key_rejected() {
+        let (service, _api_keys, _key_id) = service();
+        let data = service.validate_token(KEY).unwrap();
+
+        service.revoke_token(&data).unwrap();
+
+        assert!(service.is_revoked(&data).unwrap());
+        assert!(matches!(
+            service.validate_token(KEY),
+            Err(AuthError::TokenRevoked)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_validates_api_keys_asynchronously() {
+        use crate::domain::auth::AsyncTokenValidator;
+
+        let (service, api_keys, key_id) = service();
+
+        let data = AsyncTokenValidator::validate_token(&service, KEY).await.unwrap();
+        assert_eq!(data.jti, key_id.to_string());
+        assert_eq!(*api_keys.uses.lock().unwrap(), vec![key_id]);
+        assert!(!AsyncTokenValidator::validate_token(&service, "jwt")
+            .await
+            .unwrap()
+            .is_api_key());
+
+        service.revoke_token(&data).unwrap();
+        assert!(AsyncTokenValidator::is_revoked(&service, &data).await.unwrap());
+        assert!(matches!(
+            AsyncTokenValidator::validate_token(&service, KEY).await,
+            Err(AuthError::TokenRevoked)
+        ));
+    }
+}
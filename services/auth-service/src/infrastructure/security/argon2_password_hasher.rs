@@ -6,11 +6,12 @@ use argon2::{
 };
 use rand::rngs::OsRng;
 
-use crate::domain::auth::PasswordHasher;
+use crate::domain::auth::{self, BoxFuture, PasswordHasher};
 use crate::domain::error::AuthError;
 use crate::domain::user::HashedPassword;
 
 /// Argon2 implementation of the PasswordHasher trait
+#[derive(Clone)]
 pub struct Argon2PasswordHasher {
     argon2: Argon2<'static>,
 }
@@ -55,6 +56,41 @@ impl PasswordHasher for Argon2PasswordHasher {
     }
 }
 
+/// Hashes on the blocking thread pool: Argon2 is CPU-bound by design and
+/// would stall an async worker for the whole computation.
+impl auth::AsyncPasswordHasher for Argon2PasswordHasher {
+    fn hash<'a>(
+        &'a self,
+        plain_password: &'a str,
+    ) -> BoxFuture<'a, Result<HashedPassword, AuthError>> {
+        let hasher = self.clone();
+        let plain_password = plain_password.to_string();
+        Box::pin(off_worker(move || PasswordHasher::hash(&hasher, &plain_password)))
+    }
+
+    fn verify<'a>(
+        &'a self,
+        plain_password: &'a str,
+        hashed: &'a HashedPassword,
+    ) -> BoxFuture<'a, Result<bool, AuthError>> {
+        let hasher = self.clone();
+        let plain_password = plain_password.to_string();
+        let hashed = hashed.clone();
+        Box::pin(off_worker(move || {
+            PasswordHasher::verify(&hasher, &plain_password, &hashed)
+        }))
+    }
+}
+
+/// Run `compute` on the blocking thread pool
+async fn off_worker<T: Send + 'static>(
+    compute: impl FnOnce() -> Result<T, AuthError> + Send + 'static,
+) -> Result<T, AuthError> {
+    tokio::task::spawn_blocking(compute)
+        .await
+        .map_err(|e| AuthError::Internal(format!("Task join error: {}", e)))?
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -85,4 +121,22 @@ mod tests {
         assert!(hasher.verify(password, &hash1).unwrap());
         assert!(hasher.verify(password, &hash2).unwrap());
     }
+
+    #[tokio::test]
+    async fn test_async_hash_and_verify() {
+        use crate::domain::auth::AsyncPasswordHasher;
+
+        let hasher = Argon2PasswordHasher::new();
+
+        let hashed = AsyncPasswordHasher::hash(&hasher, "SecurePassword123!")
+            .await
+            .unwrap();
+
+        assert!(PasswordHasher::verify(&hasher, "SecurePassword123!", &hashed).unwrap());
+        assert!(
+            !AsyncPasswordHasher::verify(&hasher, "WrongPassword", &hashed)
+                .await
+                .unwrap()
+        );
+    }
 }
@@ -0,0 +1,159 @@
+//! Breached password list read from a local Pwned Passwords file
+//!
+//! The file holds the k-anonymity range responses of the Pwned Passwords
+//! API joined into one, as written by its downloader: one `HASH:COUNT` line
+//! per password, where `HASH` is the 5-character SHA-1 prefix followed by
+//! the 35-character suffix, sorted by hash. Lookups binary-search the file
+//! for the prefix's range and compare suffixes within it, so the file is
+//! never loaded into memory and no request leaves the host.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::PathBuf;
+
+use sha1::{Digest, Sha1};
+
+use crate::domain::error::AuthError;
+use crate::domain::password_policy::BreachedPasswordList;
+
+/// Length of the hash prefix that identifies a range
+const PREFIX_LEN: usize = 5;
+
+/// File-based implementation of the BreachedPasswordList trait
+#[derive(Debug)]
+pub struct BreachedPasswordFile {
+    path: PathBuf,
+    len: u64,
+}
+
+impl BreachedPasswordFile {
+    /// Use the hash list at `path`
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` if the file cannot be opened
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, AuthError> {
+        let path = path.into();
+        let len = File::open(&path)
+            .and_then(|file| file.metadata())
+            .map_err(|e| {
+                AuthError::Internal(format!(
+                    "Failed to open breached password list {}: {}",
+                    path.display(),
+                    e
+                ))
+            })?
+            .len();
+        Ok(Self { path, len })
+    }
+
+    /// Find `hash` (40 uppercase hex digits) in the file
+    fn lookup(&self, hash: &str) -> std::io::Result<bool> {
+        let mut reader = BufReader::new(File::open(&self.path)?);
+        let (prefix, suffix) = hash.split_at(PREFIX_LEN);
+
+        // Smallest offset whose next line belongs to the range or a later one
+        let (mut low, mut high) = (0, self.len);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            match line_at(&mut reader, mid)? {
+                Some(line) if line_prefix(&line).as_str() < prefix => low = mid + 1,
+                _ => high = mid,
+            }
+        }
+
+        let mut next = line_at(&mut reader, low)?;
+        while let Some(line) = next {
+            if line_prefix(&line) != prefix {
+                break;
+            }
+            let line_suffix = line[PREFIX_LEN..].split(':').next().unwrap_or_default();
+            if line_suffix.trim().eq_ignore_ascii_case(suffix) {
+                return Ok(true);
+            }
+            next = read_line(&mut reader)?;
+        }
+
+        Ok(false)
+    }
+}
+
+impl BreachedPasswordList for BreachedPasswordFile {
+    fn contains(&self, password: &str) -> Result<bool, AuthError> {
+        let hash = hex::encode_upper(Sha1::digest(password.as_bytes()));
+        self.lookup(&hash).map_err(|e| {
+            AuthError::Internal(format!(
+                "Failed to read breached password list {}: {}",
+                self.path.display(),
+                e
+            ))
+        })
+    }
+}
+
+/// Read the first complete line starting at or after `offset`
+fn line_at(reader: &mut BufReader<File>, offset: u64) -> std::io::Result<Option<String>> {
+    if offset == 0 {
+        reader.seek(SeekFrom::Start(0))?;
+    } else {
+        // Skip the rest of the line `offset` falls into; if the byte before
+        // it ends a line, this consumes just that newline
+        reader.seek(SeekFrom::Start(offset - 1))?;
+        reader.read_until(b'\n', &mut Vec::new())?;
+    }
+    read_line(reader)
+}
+
+/// Read the next line, `None` at the end of the file
+fn read_line(reader: &mut BufReader<File>) -> std::io::Result<Option<String>> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(line))
+}
+
+/// Uppercased range prefix of a line
+fn line_prefix(line: &str) -> String {
+    line.chars()
+        .take(PREFIX_LEN)
+        .collect::<String>()
+        .to_ascii_uppercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use uuid::Uuid;
+
+    use super::*;
+
+    #[test]
+    fn test_finds_hashes_within_their_range() {
+        // SHA-1 of "password" is 5BAA61E4C9B93F3F0682250B6CF8331B7EE68FD8
+        let path = std::env::temp_dir().join(format!("breached-{}.txt", Uuid::new_v4()));
+        fs::write(
+            &path,
+            "00000A1F9B0E3A4E0C6B5D84F5A9D7C6A0B2E1F3:3\r\n\
+             5BAA600000000000000000000000000000000000:1\r\n\
+             5baa61e4c9b93f3f0682250b6cf8331b7ee68fd7:2\r\n\
+             5BAA61E4C9B93F3F0682250B6CF8331B7EE68FD8:9545824\r\n\
+             FFFFF00000000000000000000000000000000000:1\r\n",
+        )
+        .unwrap();
+        let list = BreachedPasswordFile::open(&path).unwrap();
+
+        assert!(list.contains("password").unwrap());
+        assert!(!list.contains("password1").unwrap());
+        assert!(!list.contains("correct horse battery staple").unwrap());
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_missing_file_is_an_error() {
+        let path = std::env::temp_dir().join(format!("breached-{}.txt", Uuid::new_v4()));
+
+        assert!(BreachedPasswordFile::open(path).is_err());
+    }
+}
@@ -0,0 +1,349 @@
+//! JWT key ring backed by the signing key repository
+//!
+//! Keys are loaded from storage and cached in memory. The ring reloads
+//! periodically so every replica picks up a rotation, and also when a token
+//! names a `kid` it has not seen yet.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::Utc;
+use ed25519_dalek::pkcs8::DecodePublicKey;
+use ed25519_dalek::VerifyingKey;
+use jsonwebtoken::jwk::{
+    AlgorithmParameters, CommonParameters, EllipticCurve, Jwk, JwkSet, KeyAlgorithm,
+    OctetKeyPairParameters, OctetKeyPairType, PublicKeyUse, RSAKeyParameters, RSAKeyType,
+};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey};
+use rsa::traits::PublicKeyParts;
+use rsa::RsaPublicKey;
+
+use crate::domain::error::AuthError;
+use crate::domain::signing_key::{SigningAlgorithm, SigningKey, SigningKeyRepository};
+
+/// Minimum time between reloads triggered by unknown key IDs, so forged
+/// `kid` headers cannot hammer the database
+const MIN_RELOAD_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Key material ready for signing and verification
+pub struct RingKey {
+    pub kid: String,
+    pub algorithm: Algorithm,
+    pub encoding_key: EncodingKey,
+    pub decoding_key: DecodingKey,
+}
+
+/// Snapshot of the keys loaded from storage
+#[derive(Default)]
+struct Snapshot {
+    active: Option<Arc<RingKey>>,
+    keys: HashMap<String, Arc<RingKey>>,
+    jwks: Vec<Jwk>,
+    loaded_at: Option<Instant>,
+}
+
+impl Snapshot {
+    /// Time since the snapshot was loaded, or `None` if it never was
+    fn age(&self) -> Option<Duration> {
+        self.loaded_at.map(|at| at.elapsed())
+    }
+}
+
+/// Cached set of signing keys with a single active key
+pub struct JwtKeyRing {
+    repository: Arc<dyn SigningKeyRepository + Send + Sync>,
+    refresh_interval: Duration,
+    snapshot: RwLock<Snapshot>,
+}
+
+impl JwtKeyRing {
+    /// Create a key ring that reloads from `repository` every `refresh_interval`
+    #[must_use]
+    pub fn new(
+        repository: Arc<dyn SigningKeyRepository + Send + Sync>,
+        refresh_interval: Duration,
+    ) -> Self {
+        Self {
+            repository,
+            refresh_interval,
+            snapshot: RwLock::new(Snapshot::default()),
+        }
+    }
+
+    /// Reload all verifiable keys from storage
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` on storage failures or malformed keys
+    pub fn reload(&self) -> Result<(), AuthError> {
+        let stored = self.repository.find_verifiable(Utc::now())?;
+
+        let mut snapshot = Snapshot {
+            loaded_at: Some(Instant::now()),
+            ..Snapshot::default()
+        };
+        // Keys come newest first, so the first active one signs
+        for key in &stored {
+            let (ring_key, jwk) = load_key(key)?;
+            let ring_key = Arc::new(ring_key);
+            if key.is_active() && snapshot.active.is_none() {
+                snapshot.active = Some(Arc::clone(&ring_key));
+            }
+            snapshot.keys.insert(key.kid().to_string(), ring_key);
+            snapshot.jwks.push(jwk);
+        }
+
+        *self.write() = snapshot;
+        Ok(())
+    }
+
+    /// Get the key new tokens are signed with
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` if no key is active or loading fails
+    pub fn signing_key(&self) -> Result<Arc<RingKey>, AuthError> {
+        self.refresh_if_stale()?;
+        self.read()
+            .active
+            .clone()
+            .ok_or_else(|| AuthError::Internal("No active JWT signing key".to_string()))
+    }
+
+    /// Get the key that verifies tokens carrying `kid`
+    ///
+    /// # Errors
+    /// - `AuthError::InvalidToken` if no verifiable key has this ID
+    /// - `AuthError::Internal` if loading fails
+    pub fn verification_key(&self, kid: &str) -> Result<Arc<RingKey>, AuthError> {
+        self.refresh_if_stale()?;
+        if let Some(key) = self.read().keys.get(kid) {
+            return Ok(Arc::clone(key));
+        }
+
+        // Another replica may have rotated since our last load
+        if self
+            .read()
+            .age()
+            .is_none_or(|age| age >= MIN_RELOAD_INTERVAL)
+        {
+            self.reload()?;
+        }
+        self.read()
+            .keys
+            .get(kid)
+            .cloned()
+            .ok_or(AuthError::InvalidToken)
+    }
+
+    /// Public keys of every verifiable key, for the JWKS endpoint
+    ///
+    /// # Errors
+    /// Returns `AuthError::Internal` if loading fails
+    pub fn jwks(&self) -> Result<JwkSet, AuthError> {
+        self.refresh_if_stale()?;
+        Ok(JwkSet {
+            keys: self.read().jwks.clone(),
+        })
+    }
+
+    fn refresh_if_stale(&self) -> Result<(), AuthError> {
+        if self
+            .read()
+            .age()
+            .is_none_or(|age| age >= self.refresh_interval)
+        {
+            self.reload()?;
+        }
+        Ok(())
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, Snapshot> {
+        // A poisoned lock still holds a complete snapshot
+        self.snapshot.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, Snapshot> {
+        self.snapshot.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Parse a stored key into signing material and its public JWK
+fn load_key(key: &SigningKey) -> Result<(RingKey, Jwk), AuthError> {
+    let private_pem = key.private_key_pem().as_bytes();
+
+    let (algorithm, key_algorithm, encoding_key, parameters) = match key.algorithm() {
+        SigningAlgorithm::Rs256 => {
+            let public_key = RsaPublicKey::from_public_key_pem(key.public_key_pem())
+                .map_err(|e| key_error(key, e))?;
+            let parameters = AlgorithmParameters::RSA(RSAKeyParameters {
+                key_type: RSAKeyType::RSA,
+                n: URL_SAFE_NO_PAD.encode(public_key.n().to_bytes_be()),
+                e: URL_SAFE_NO_PAD.encode(public_key.e().to_bytes_be()),
+            });
+            let encoding_key =
+                EncodingKey::from_rsa_pem(private_pem).map_err(|e| key_error(key, e))?;
+            (
+                Algorithm::RS256,
+                KeyAlgorithm::RS256,
+                encoding_key,
+                parameters,
+            )
+        }
+        SigningAlgorithm::EdDsa => {
+            let public_key = VerifyingKey::from_public_key_pem(key.public_key_pem())
+                .map_err(|e| key_error(key, e))?;
+            let parameters = AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+                key_type: OctetKeyPairType::OctetKeyPair,
+                curve: EllipticCurve::Ed25519,
+                x: URL_SAFE_NO_PAD.encode(public_key.as_bytes()),
+            });
+            let encoding_key =
+                EncodingKey::from_ed_pem(private_pem).map_err(|e| key_error(key, e))?;
+            (
+                Algorithm::EdDSA,
+                KeyAlgorithm::EdDSA,
+                encoding_key,
+                parameters,
+            )
+        }
+    };
+
+    let jwk = Jwk {
+        common: CommonParameters {
+            public_key_use: Some(PublicKeyUse::Signature),
+            key_algorithm: Some(key_algorithm),
+            key_id: Some(key.kid().to_string()),
+            ..CommonParameters::default()
+        },
+        algorithm: parameters,
+    };
+    let decoding_key = DecodingKey::from_jwk(&jwk).map_err(|e| key_error(key, e))?;
+
+    let ring_key = RingKey {
+        kid: key.kid().to_string(),
+        algorithm,
+        encoding_key,
+        decoding_key,
+    };
+
+    Ok((ring_key, jwk))
+}
+
+/// Wrap a key parsing failure in an internal error
+fn key_error(key: &SigningKey, e: impl std::fmt::Display) -> AuthError {
+    AuthError::Internal(format!("Invalid signing key {}: {}", key.kid(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use chrono::DateTime;
+
+    use super::*;
+    use crate::domain::signing_key::SigningKeyGenerator;
+    use crate::infrastructure::security::signing_key_generator::RandomSigningKeyGenerator;
+
+    // In-memory key store
+    #[derive(Default)]
+    struct MemorySigningKeyRepository {
+        keys: Mutex<Vec<SigningKey>>,
+    }
+
+    impl SigningKeyRepository for MemorySigningKeyRepository {
+        fn find_verifiable(&self, now: DateTime<Utc>) -> Result<Vec<SigningKey>, AuthError> {
+            let mut keys: Vec<SigningKey> = self
+                .keys
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|k| k.is_verifiable(now))
+                .cloned()
+                .collect();
+            keys.sort_by_key(|k| std::cmp::Reverse(k.created_at()));
+            Ok(keys)
+        }
+
+        fn rotate(&self, next: &SigningKey, verify_until: DateTime<Utc>) -> Result<(), AuthError> {
+            let mut keys = self.keys.lock().unwrap();
+            for key in keys.iter_mut().filter(|k| k.is_active()) {
+                *key = SigningKey::from_persistence(
+                    key.kid().to_string(),
+                    key.algorithm(),
+                    key.private_key_pem().to_string(),
+                    key.public_key_pem().to_string(),
+                    key.created_at(),
+                    Some(Utc::now()),
+                    Some(verify_until),
+                );
+            }
+            keys.push(next.clone());
+            Ok(())
+        }
+
+        fn purge_expired(&self, _now: DateTime<Utc>) -> Result<usize, AuthError> {
+            Ok(0)
+        }
+    }
+
+    fn rotate(repo: &MemorySigningKeyRepository, algorithm: SigningAlgorithm) -> String {
+        let key = RandomSigningKeyGenerator::new()
+            .generate(algorithm)
+            .unwrap();
+        repo.rotate(&key, Utc::now() + chrono::Duration::hours(1))
+            .unwrap();
+        key.kid().to_string()
+    }
+
+    #[test]
+    fn test_publishes_all_verifiable_keys() {
+        let repo = Arc::new(MemorySigningKeyRepository::default());
+        let old_kid = rotate(&repo, SigningAlgorithm::Rs256);
+        let new_kid = rotate(&repo, SigningAlgorithm::EdDsa);
+        let ring = JwtKeyRing::new(repo, Duration::from_secs(60));
+
+        assert_eq!(ring.signing_key().unwrap().kid, new_kid);
+
+        let jwks = ring.jwks().unwrap();
+        assert_eq!(jwks.keys.len(), 2);
+        assert!(matches!(
+            jwks.find(&old_kid).unwrap().algorithm,
+            AlgorithmParameters::RSA(_)
+        ));
+        assert!(matches!(
+            jwks.find(&new_kid).unwrap().algorithm,
+            AlgorithmParameters::OctetKeyPair(_)
+        ));
+    }
+
+    #[test]
+    fn test_unknown_kid_triggers_reload() {
+        let repo = Arc::new(MemorySigningKeyRepository::default());
+        rotate(&repo, SigningAlgorithm::EdDsa);
+        let ring = JwtKeyRing::new(repo.clone(), Duration::from_secs(3600));
+        ring.reload().unwrap();
+
+        // Rotated elsewhere after our last load
+        let kid = rotate(&repo, SigningAlgorithm::EdDsa);
+        ring.write().loaded_at = Some(Instant::now() - MIN_RELOAD_INTERVAL);
+
+        assert_eq!(ring.verification_key(&kid).unwrap().kid, kid);
+        assert!(matches!(
+            ring.verification_key("unknown"),
+            Err(AuthError::InvalidToken)
+        ));
+    }
+
+    #[test]
+    fn test_no_active_key() {
+        let ring = JwtKeyRing::new(
+            Arc::new(MemorySigningKeyRepository::default()),
+            Duration::from_secs(60),
+        );
+
+        assert!(matches!(ring.signing_key(), Err(AuthError::Internal(_))));
+        assert!(ring.jwks().unwrap().keys.is_empty());
+    }
+}
@@ -1,36 +1,153 @@
 //! JWT token service implementation
 
-use chrono::{Duration, Utc};
-use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::{decode, decode_header, encode, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::domain::auth::{TokenData, TokenService};
+use crate::domain::auth::{self, BoxFuture, Principal, TokenData, TokenService};
 use crate::domain::error::AuthError;
+use crate::domain::impersonation::{ImpersonationToken, ImpersonationTokenIssuer};
+use crate::domain::oauth_client::{format_scope, OAuthClient, ServiceToken, ServiceTokenIssuer};
+use crate::domain::oidc::{parse_oidc_scope, AuthorizationCode, IdTokenIssuer};
+use crate::domain::organization::{ActiveOrganization, OrgRole, OrganizationTokenIssuer};
+use crate::domain::role::{Permission, Role};
+use crate::domain::session::{AsyncSessionRepository, SessionRepository};
+use crate::domain::token_revocation::{AsyncTokenRevocationStore, TokenRevocationStore};
 use crate::domain::user::User;
 
+use super::jwt_key_ring::JwtKeyRing;
+
+/// Kind of principal a token was issued to
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum TokenType {
+    #[default]
+    User,
+    Service,
+}
+
 /// JWT claims structure
 #[derive(Debug, Serialize, Deserialize)]
 struct Claims {
-    /// Subject (user ID)
+    /// Subject (user ID, or client ID for service tokens)
     sub: String,
-    /// User email
+    /// User email (absent in service tokens)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     email: String,
     /// Issued at timestamp
     iat: i64,
     /// Expiration timestamp
     exp: i64,
+    /// Unique token identifier
+    jti: String,
+    /// Roles held by the user (absent in tokens issued before RBAC)
+    #[serde(default)]
+    roles: Vec<String>,
+    /// Principal kind (absent in user tokens issued before service tokens)
+    #[serde(default)]
+    token_type: TokenType,
+    /// Space-delimited scopes granted to a service, or the OpenID Connect
+    /// scopes granted to a client acting for a user
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    scope: Option<String>,
+    /// Session the token was issued for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sid: Option<String>,
+    /// Administrator acting as the subject (RFC 8693 actor claim)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    act: Option<ActorClaim>,
+    /// Organization the subject acts for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    org_id: Option<String>,
+    /// Subject's role in that organization
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    org_role: Option<String>,
+}
+
+/// The `act` claim of an impersonation token
+#[derive(Debug, Serialize, Deserialize)]
+struct ActorClaim {
+    sub: String,
+}
+
+/// Claims of an access token for `user`, valid until `expiration`
+fn user_claims(
+    user: &User,
+    session_id: Option<Uuid>,
+    now: DateTime<Utc>,
+    expiration: DateTime<Utc>,
+) -> Claims {
+    Claims {
+        sub: user.id().as_uuid().to_string(),
+        email: user.email().as_str().to_string(),
+        iat: now.timestamp(),
+        exp: expiration.timestamp(),
+        jti: Uuid::new_v4().to_string(),
+        roles: user
+            .roles()
+            .iter()
+            .map(|r| r.as_str().to_string())
+            .collect(),
+        token_type: TokenType::User,
+        scope: None,
+        sid: session_id.map(|id| id.to_string()),
+        act: None,
+        org_id: None,
+        org_role: None,
+    }
+}
+
+/// OpenID Connect ID token claims
+///
+/// ID tokens lack a `jti`, so they are never accepted as access tokens.
+#[derive(Debug, Serialize, Deserialize)]
+struct IdTokenClaims {
+    iss: String,
+    sub: String,
+    /// Client the token was issued to
+    aud: String,
+    iat: i64,
+    exp: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    nonce: Option<String>,
+    /// Present with the `email` scope
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    email: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    email_verified: Option<bool>,
+    /// Display name, present with the `profile` scope
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+}
+
+/// Keys used to sign and verify tokens
+enum Keys {
+    /// Single shared HMAC secret (HS256)
+    Secret {
+        encoding_key: EncodingKey,
+        decoding_key: DecodingKey,
+    },
+    /// Asymmetric key ring; tokens carry the signing key's `kid`
+    Ring(Arc<JwtKeyRing>),
 }
 
 /// JWT-based implementation of the TokenService trait
 pub struct JwtTokenService {
-    encoding_key: EncodingKey,
-    decoding_key: DecodingKey,
+    keys: Keys,
     expiration_secs: i64,
+    revocations: Option<Arc<dyn TokenRevocationStore + Send + Sync>>,
+    sessions: Option<Arc<dyn SessionRepository + Send + Sync>>,
+    async_revocations: Option<Arc<dyn AsyncTokenRevocationStore + Send + Sync>>,
+    async_sessions: Option<Arc<dyn AsyncSessionRepository + Send + Sync>>,
+    /// `iss` of ID tokens; none can be issued without it
+    issuer: Option<String>,
 }
 
 impl JwtTokenService {
-    /// Create a new JWT token service
+    /// Create a new JWT token service signing with a shared HMAC secret
     ///
     /// # Arguments
     /// * `secret` - The secret key for signing tokens
@@ -38,53 +155,395 @@ impl JwtTokenService {
     #[must_use]
     pub fn new(secret: String, expiration_secs: i64) -> Self {
         Self {
-            encoding_key: EncodingKey::from_secret(secret.as_bytes()),
-            decoding_key: DecodingKey::from_secret(secret.as_bytes()),
+            keys: Keys::Secret {
+                encoding_key: EncodingKey::from_secret(secret.as_bytes()),
+                decoding_key: DecodingKey::from_secret(secret.as_bytes()),
+            },
+            expiration_secs,
+            revocations: None,
+            sessions: None,
+            async_revocations: None,
+            async_sessions: None,
+            issuer: None,
+        }
+    }
+
+    /// Create a new JWT token service signing with an asymmetric key ring
+    ///
+    /// # Arguments
+    /// * `key_ring` - Ring providing the active key and all verifiable keys
+    /// * `expiration_secs` - Token expiration time in seconds
+    #[must_use]
+    pub fn with_key_ring(key_ring: Arc<JwtKeyRing>, expiration_secs: i64) -> Self {
+        Self {
+            keys: Keys::Ring(key_ring),
             expiration_secs,
+            revocations: None,
+            sessions: None,
+            async_revocations: None,
+            async_sessions: None,
+            issuer: None,
         }
     }
+
+    /// Check revocations against a shared store
+    ///
+    /// Without a store, tokens cannot be revoked before they expire.
+    #[must_use]
+    pub fn with_revocation_store(
+        mut self,
+        store: Arc<dyn TokenRevocationStore + Send + Sync>,
+    ) -> Self {
+        self.revocations = Some(store);
+        self
+    }
+
+    /// Reject tokens of ended sessions, looked up in a shared store
+    ///
+    /// Without a store, ending a session only stops it from being refreshed.
+    #[must_use]
+    pub fn with_session_store(mut self, store: Arc<dyn SessionRepository + Send + Sync>) -> Self {
+        self.sessions = Some(store);
+        self
+    }
+
+    /// Check revocations against a shared store when validating asynchronously
+    ///
+    /// The async counterpart of [`Self::with_revocation_store`]; give both
+    /// the same revocation list.
+    #[must_use]
+    pub fn with_async_revocation_store(
+        mut self,
+        store: Arc<dyn AsyncTokenRevocationStore + Send + Sync>,
+    ) -> Self {
+        self.async_revocations = Some(store);
+        self
+    }
+
+    /// Reject tokens of ended sessions when validating asynchronously
+    ///
+    /// The async counterpart of [`Self::with_session_store`].
+    #[must_use]
+    pub fn with_async_session_store(
+        mut self,
+        store: Arc<dyn AsyncSessionRepository + Send + Sync>,
+    ) -> Self {
+        self.async_sessions = Some(store);
+        self
+    }
+
+    /// Issue ID tokens as the OpenID Connect provider at `issuer`
+    #[must_use]
+    pub fn with_issuer(mut self, issuer: String) -> Self {
+        self.issuer = Some(issuer);
+        self
+    }
+
+    /// Sign claims with the shared secret or the ring's active key
+    fn sign<T: Serialize>(&self, claims: &T) -> Result<String, AuthError> {
+        let encoded = match &self.keys {
+            Keys::Secret { encoding_key, .. } => encode(&Header::default(), claims, encoding_key),
+            Keys::Ring(ring) => {
+                let key = ring.signing_key()?;
+                let mut header = Header::new(key.algorithm);
+                header.kid = Some(key.kid.clone());
+                encode(&header, claims, &key.encoding_key)
+            }
+        };
+
+        encoded.map_err(|e| AuthError::Internal(format!("Failed to create token: {}", e)))
+    }
+
+    /// Verify a token's signature and expiry and read its claims, without
+    /// checking for revocation
+    fn decode(&self, token: &str) -> Result<TokenData, AuthError> {
+        let decoded = match &self.keys {
+            Keys::Secret { decoding_key, .. } => {
+                decode::<Claims>(token, decoding_key, &Validation::default())
+            }
+            Keys::Ring(ring) => {
+                let header = decode_header(token).map_err(|_| AuthError::InvalidToken)?;
+                let kid = header.kid.ok_or(AuthError::InvalidToken)?;
+                let key = ring.verification_key(&kid)?;
+                // Pin the algorithm to the key, never trust the header's `alg`
+                decode::<Claims>(token, &key.decoding_key, &Validation::new(key.algorithm))
+            }
+        };
+
+        let token_data = decoded.map_err(|e| match e.kind() {
+            jsonwebtoken::errors::ErrorKind::ExpiredSignature => AuthError::TokenExpired,
+            _ => AuthError::InvalidToken,
+        })?;
+
+        let claims = token_data.claims;
+        let user_id = Uuid::parse_str(&claims.sub).map_err(|_| AuthError::InvalidToken)?;
+        let expires_at = DateTime::from_timestamp(claims.exp, 0).ok_or(AuthError::InvalidToken)?;
+        let session_id = claims
+            .sid
+            .as_deref()
+            .map(Uuid::parse_str)
+            .transpose()
+            .map_err(|_| AuthError::InvalidToken)?;
+        let actor = claims
+            .act
+            .map(|act| Uuid::parse_str(&act.sub))
+            .transpose()
+            .map_err(|_| AuthError::InvalidToken)?;
+        let org_id = claims
+            .org_id
+            .as_deref()
+            .map(Uuid::parse_str)
+            .transpose()
+            .map_err(|_| AuthError::InvalidToken)?;
+        // An organization role this build does not know drops the organization, not the token
+        let organization = org_id.zip(
+            claims
+                .org_role
+                .as_deref()
+                .and_then(|role| role.parse::<OrgRole>().ok()),
+        );
+        // Ignore roles and scopes this build does not know about rather than rejecting the token
+        let (roles, scopes, oidc_scopes, principal) = match claims.token_type {
+            TokenType::User => (
+                claims
+                    .roles
+                    .iter()
+                    .filter_map(|r| r.parse::<Role>().ok())
+                    .collect(),
+                None,
+                claims
+                    .scope
+                    .map(|scope| parse_oidc_scope(&scope).unwrap_or_default()),
+                Principal::User,
+            ),
+            TokenType::Service => (
+                Vec::new(),
+                Some(
+                    claims
+                        .scope
+                        .unwrap_or_default()
+                        .split_whitespace()
+                        .filter_map(|s| s.parse::<Permission>().ok())
+                        .collect(),
+                ),
+                None,
+                Principal::Service,
+            ),
+        };
+
+        Ok(TokenData {
+            user_id,
+            email: claims.email,
+            jti: claims.jti,
+            session_id,
+            expires_at,
+            roles,
+            scopes,
+            principal,
+            actor,
+            organization: organization.map(|(id, role)| ActiveOrganization { id, role }),
+            oidc_scopes,
+        })
+    }
 }
 
 impl TokenService for JwtTokenService {
-    fn create_token(&self, user: &User) -> Result<String, AuthError> {
+    fn create_token(&self, user: &User, session_id: Option<Uuid>) -> Result<String, AuthError> {
+        let now = Utc::now();
+        let expiration = now + Duration::seconds(self.expiration_secs);
+
+        self.sign(&user_claims(user, session_id, now, expiration))
+    }
+
+    fn validate_token(&self, token: &str) -> Result<TokenData, AuthError> {
+        let data = self.decode(token)?;
+
+        if self.is_revoked(&data)? {
+            return Err(AuthError::TokenRevoked);
+        }
+
+        Ok(data)
+    }
+
+    fn revoke_token(&self, token: &TokenData) -> Result<(), AuthError> {
+        let store = self.revocations.as_ref().ok_or_else(|| {
+            AuthError::Internal("Token revocation store is not configured".to_string())
+        })?;
+
+        store.revoke(&token.jti, token.user_id, token.expires_at)
+    }
+
+    fn is_revoked(&self, token: &TokenData) -> Result<bool, AuthError> {
+        if let Some(store) = &self.revocations {
+            if store.is_revoked(&token.jti)? {
+                return Ok(true);
+            }
+        }
+
+        match (&self.sessions, token.session_id) {
+            (Some(store), Some(session_id)) => store.is_revoked(session_id),
+            _ => Ok(false),
+        }
+    }
+}
+
+impl auth::AsyncTokenValidator for JwtTokenService {
+    fn validate_token<'a>(&'a self, token: &'a str) -> BoxFuture<'a, Result<TokenData, AuthError>> {
+        Box::pin(async move {
+            let data = self.decode(token)?;
+
+            if auth::AsyncTokenValidator::is_revoked(self, &data).await? {
+                return Err(AuthError::TokenRevoked);
+            }
+
+            Ok(data)
+        })
+    }
+
+    fn is_revoked<'a>(&'a self, token: &'a TokenData) -> BoxFuture<'a, Result<bool, AuthError>> {
+        Box::pin(async move {
+            if let Some(store) = &self.async_revocations {
+                if store.is_revoked(&token.jti).await? {
+                    return Ok(true);
+                }
+            }
+
+            match (&self.async_sessions, token.session_id) {
+                (Some(store), Some(session_id)) => store.is_revoked(session_id).await,
+                _ => Ok(false),
+            }
+        })
+    }
+}
+
+impl ServiceTokenIssuer for JwtTokenService {
+    fn create_service_token(
+        &self,
+        client: &OAuthClient,
+        scopes: &[Permission],
+    ) -> Result<ServiceToken, AuthError> {
         let now = Utc::now();
         let expiration = now + Duration::seconds(self.expiration_secs);
 
         let claims = Claims {
-            sub: user.id().as_uuid().to_string(),
-            email: user.email().as_str().to_string(),
+            sub: client.client_id().to_string(),
+            email: String::new(),
             iat: now.timestamp(),
             exp: expiration.timestamp(),
+            jti: Uuid::new_v4().to_string(),
+            roles: Vec::new(),
+            token_type: TokenType::Service,
+            scope: Some(format_scope(scopes)),
+            sid: None,
+            act: None,
+            org_id: None,
+            org_role: None,
         };
 
-        encode(&Header::default(), &claims, &self.encoding_key)
-            .map_err(|e| AuthError::Internal(format!("Failed to create token: {}", e)))
+        Ok(ServiceToken {
+            access_token: self.sign(&claims)?,
+            expires_at: expiration,
+        })
     }
+}
 
-    fn validate_token(&self, token: &str) -> Result<TokenData, AuthError> {
-        let validation = Validation::default();
+impl ImpersonationTokenIssuer for JwtTokenService {
+    fn create_impersonation_token(
+        &self,
+        user: &User,
+        actor_id: Uuid,
+        session_id: Uuid,
+        ttl: Duration,
+    ) -> Result<ImpersonationToken, AuthError> {
+        let now = Utc::now();
+        let expiration = now + ttl;
 
-        let token_data = decode::<Claims>(token, &self.decoding_key, &validation).map_err(|e| {
-            match e.kind() {
-                jsonwebtoken::errors::ErrorKind::ExpiredSignature => AuthError::TokenExpired,
-                _ => AuthError::InvalidToken,
-            }
-        })?;
+        let mut claims = user_claims(user, Some(session_id), now, expiration);
+        claims.act = Some(ActorClaim {
+            sub: actor_id.to_string(),
+        });
 
-        let user_id = Uuid::parse_str(&token_data.claims.sub)
-            .map_err(|_| AuthError::InvalidToken)?;
-
-        Ok(TokenData {
-            user_id,
-            email: token_data.claims.email,
+        Ok(ImpersonationToken {
+            access_token: self.sign(&claims)?,
+            expires_at: expiration,
         })
     }
 }
 
+impl OrganizationTokenIssuer for JwtTokenService {
+    fn create_organization_token(
+        &self,
+        user: &User,
+        session_id: Option<Uuid>,
+        organization: ActiveOrganization,
+    ) -> Result<String, AuthError> {
+        let now = Utc::now();
+        let expiration = now + Duration::seconds(self.expiration_secs);
+
+        let mut claims = user_claims(user, session_id, now, expiration);
+        claims.org_id = Some(organization.id.to_string());
+        claims.org_role = Some(organization.role.as_str().to_string());
+
+        self.sign(&claims)
+    }
+}
+
+impl IdTokenIssuer for JwtTokenService {
+    fn create_access_token(
+        &self,
+        user: &User,
+        code: &AuthorizationCode,
+    ) -> Result<String, AuthError> {
+        let now = Utc::now();
+        let expiration = now + Duration::seconds(self.expiration_secs);
+
+        let mut claims = user_claims(user, Some(code.session_id()), now, expiration);
+        claims.scope = Some(code.scopes().join(" "));
+
+        self.sign(&claims)
+    }
+
+    fn create_id_token(&self, user: &User, code: &AuthorizationCode) -> Result<String, AuthError> {
+        let issuer = self
+            .issuer
+            .clone()
+            .ok_or_else(|| AuthError::Internal("Token issuer is not configured".to_string()))?;
+        let now = Utc::now();
+        let with_email = code.has_scope("email");
+
+        let claims = IdTokenClaims {
+            iss: issuer,
+            sub: user.id().as_uuid().to_string(),
+            aud: code.client_id().to_string(),
+            iat: now.timestamp(),
+            exp: (now + Duration::seconds(self.expiration_secs)).timestamp(),
+            nonce: code.nonce().map(String::from),
+            email: with_email.then(|| user.email().as_str().to_string()),
+            email_verified: with_email.then(|| user.is_email_verified()),
+            name: if code.has_scope("profile") {
+                user.display_name().map(String::from)
+            } else {
+                None
+            },
+        };
+
+        self.sign(&claims)
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
+    use std::sync::Mutex;
+
     use super::*;
+    use crate::domain::signing_key::{
+        SigningAlgorithm, SigningKey, SigningKeyGenerator, SigningKeyRepository,
+    };
+    use crate::domain::session::{Session, SessionClient};
     use crate::domain::user::{Email, HashedPassword};
+    use crate::infrastructure::memory::in_memory_session_repository::InMemorySessionRepository;
+    use crate::infrastructure::security::signing_key_generator::RandomSigningKeyGenerator;
 
     fn create_test_user() -> User {
         let email = Email::new("test@example.com").unwrap();
@@ -97,11 +556,228 @@ mod tests {
         let service = JwtTokenService::new("test-secret-key".to_string(), 3600);
         let user = create_test_user();
 
-        let token = service.create_token(&user).unwrap();
+        let token = service.create_token(&user, None).unwrap();
         let data = service.validate_token(&token).unwrap();
 
         assert_eq!(data.user_id, user.id().as_uuid());
         assert_eq!(data.email, "test@example.com");
+        assert_eq!(data.roles, vec![Role::Customer]);
+    }
+
+    #[test]
+    fn test_token_carries_assigned_roles() {
+        let service = JwtTokenService::new("test-secret-key".to_string(), 3600);
+        let mut user = create_test_user();
+        user.assign_role(Role::Admin);
+
+        let token = service.create_token(&user, None).unwrap();
+        let data = service.validate_token(&token).unwrap();
+
+        assert_eq!(data.roles, vec![Role::Customer, Role::Admin]);
+    }
+
+    #[test]
+    fn test_service_token_identifies_client() {
+        let service = JwtTokenService::new("test-secret-key".to_string(), 3600);
+        let client = OAuthClient::register(
+            Uuid::new_v4(),
+            "orders-service".to_string(),
+            "hash".to_string(),
+            vec![Permission::ReadUsers, Permission::ReadAllOrders],
+        );
+
+        let token = service
+            .create_service_token(&client, &[Permission::ReadUsers])
+            .unwrap();
+        let data = service.validate_token(&token.access_token).unwrap();
+
+        assert_eq!(data.principal, Principal::Service);
+        assert_eq!(data.user_id, client.client_id());
+        assert!(data.roles.is_empty());
+        assert_eq!(data.scopes, Some(vec![Permission::ReadUsers]));
+        assert_eq!(data.expires_at.timestamp(), token.expires_at.timestamp());
+
+        let user_token = service.create_token(&create_test_user(), None).unwrap();
+        assert!(service.validate_token(&user_token).unwrap().is_session());
+    }
+
+    #[test]
+    fn test_impersonation_token_names_actor() {
+        let service = JwtTokenService::new("test-secret-key".to_string(), 3600);
+        let user = create_test_user();
+        let admin_id = Uuid::new_v4();
+        let session_id = Uuid::new_v4();
+
+        let token = service
+            .create_impersonation_token(&user, admin_id, session_id, Duration::seconds(900))
+            .unwrap();
+        let claims = decode::<serde_json::Value>(
+            &token.access_token,
+            &DecodingKey::from_secret(b"test-secret-key"),
+            &Validation::default(),
+        )
+        .unwrap()
+        .claims;
+        assert_eq!(claims["act"]["sub"], admin_id.to_string());
+        assert_eq!(claims["sid"], session_id.to_string());
+
+        let data = service.validate_token(&token.access_token).unwrap();
+        assert_eq!(data.user_id, user.id().as_uuid());
+        assert_eq!(data.actor, Some(admin_id));
+        assert_eq!(data.roles, vec![Role::Customer]);
+        assert!(data.is_impersonated());
+        assert!(!data.is_session());
+        assert!(data.expires_at < Utc::now() + Duration::seconds(901));
+    }
+
+    #[test]
+    fn test_impersonation_token_ends_with_admin_session() {
+        let sessions = Arc::new(InMemorySessionRepository::new());
+        let service = JwtTokenService::new("test-secret-key".to_string(), 3600)
+            .with_session_store(sessions.clone());
+        let user = create_test_user();
+        let admin_id = Uuid::new_v4();
+        let session = Session::start(
+            Uuid::new_v4(),
+            admin_id,
+            &SessionClient::default(),
+            Utc::now() + Duration::days(1),
+        );
+        SessionRepository::create(sessions.as_ref(), &session).unwrap();
+
+        let live = service
+            .create_impersonation_token(&user, admin_id, session.id(), Duration::seconds(900))
+            .unwrap();
+        assert!(service.validate_token(&live.access_token).is_ok());
+
+        // Unknown sessions count as ended
+        let ended = service
+            .create_impersonation_token(&user, admin_id, Uuid::new_v4(), Duration::seconds(900))
+            .unwrap();
+        assert!(matches!(
+            service.validate_token(&ended.access_token),
+            Err(AuthError::TokenRevoked)
+        ));
+    }
+
+    #[test]
+    fn test_organization_token_carries_org_claims() {
+        let service = JwtTokenService::new("test-secret-key".to_string(), 3600);
+        let user = create_test_user();
+        let session_id = Uuid::new_v4();
+        let organization = ActiveOrganization {
+            id: Uuid::new_v4(),
+            role: OrgRole::Admin,
+        };
+
+        let token = service
+            .create_organization_token(&user, Some(session_id), organization)
+            .unwrap();
+        let claims = decode::<serde_json::Value>(
+            &token,
+            &DecodingKey::from_secret(b"test-secret-key"),
+            &Validation::default(),
+        )
+        .unwrap()
+        .claims;
+        assert_eq!(claims["org_id"], organization.id.to_string());
+        assert_eq!(claims["org_role"], "admin");
+
+        let data = service.validate_token(&token).unwrap();
+        assert_eq!(data.organization, Some(organization));
+        assert_eq!(data.session_id, Some(session_id));
+        assert!(data.is_session());
+
+        let personal = service
+            .validate_token(&service.create_token(&user, None).unwrap())
+            .unwrap();
+        assert_eq!(personal.organization, None);
+    }
+
+    #[test]
+    fn test_id_token_claims_follow_scopes() {
+        let service = JwtTokenService::new("test-secret-key".to_string(), 3600);
+        let user = create_test_user();
+        let code = |scopes: &[&str]| {
+            AuthorizationCode::issue(
+                "hash".to_string(),
+                "web".to_string(),
+                user.id().as_uuid(),
+                Uuid::new_v4(),
+                "https://app.example.com/callback".to_string(),
+                scopes.iter().map(|s| s.to_string()).collect(),
+                "challenge".to_string(),
+                Some("n-0S6_WzA2Mj".to_string()),
+                Duration::seconds(60),
+            )
+        };
+        assert!(service.create_id_token(&user, &code(&["openid"])).is_err());
+
+        let service = service.with_issuer("https://auth.example.com".to_string());
+        let mut validation = Validation::default();
+        validation.set_audience(&["web"]);
+        validation.set_issuer(&["https://auth.example.com"]);
+        let decode_claims = |token: &str| {
+            decode::<IdTokenClaims>(
+                token,
+                &DecodingKey::from_secret(b"test-secret-key"),
+                &validation,
+            )
+            .unwrap()
+            .claims
+        };
+
+        let minimal = service.create_id_token(&user, &code(&["openid"])).unwrap();
+        let claims = decode_claims(&minimal);
+        assert_eq!(claims.sub, user.id().as_uuid().to_string());
+        assert_eq!(claims.nonce.as_deref(), Some("n-0S6_WzA2Mj"));
+        assert_eq!(claims.email, None);
+        assert_eq!(claims.name, None);
+        // ID tokens are not access tokens
+        assert!(matches!(
+            service.validate_token(&minimal),
+            Err(AuthError::InvalidToken)
+        ));
+
+        let full = service
+            .create_id_token(&user, &code(&["openid", "email", "profile"]))
+            .unwrap();
+        let claims = decode_claims(&full);
+        assert_eq!(claims.email.as_deref(), Some("test@example.com"));
+        assert_eq!(claims.email_verified, Some(false));
+        assert_eq!(claims.name.as_deref(), Some("Test User"));
+    }
+
+    #[test]
+    fn test_access_token_carries_granted_scopes() {
+        let service = JwtTokenService::new("test-secret-key".to_string(), 3600);
+        let user = create_test_user();
+        let session_id = Uuid::new_v4();
+        let code = AuthorizationCode::issue(
+            "hash".to_string(),
+            "web".to_string(),
+            user.id().as_uuid(),
+            session_id,
+            "https://app.example.com/callback".to_string(),
+            vec!["openid".to_string(), "email".to_string()],
+            "challenge".to_string(),
+            None,
+            Duration::seconds(60),
+        );
+
+        let token = service.create_access_token(&user, &code).unwrap();
+        let data = service.validate_token(&token).unwrap();
+
+        assert_eq!(data.session_id, Some(session_id));
+        assert_eq!(
+            data.oidc_scopes,
+            Some(vec!["openid".to_string(), "email".to_string()])
+        );
+        // Still the user's own token, not an API key
+        assert!(data.is_session());
+
+        let login_token = service.create_token(&user, Some(session_id)).unwrap();
+        assert_eq!(service.validate_token(&login_token).unwrap().oidc_scopes, None);
     }
 
     #[test]
@@ -114,23 +790,184 @@ mod tests {
 
     #[test]
     fn test_expired_token() {
-        // Create a service with 0 second expiration (immediate expiry)
-        let service = JwtTokenService::new("test-secret-key".to_string(), -1);
+        // Expire the token beyond the default 60 second validation leeway
+        let service = JwtTokenService::new("test-secret-key".to_string(), -120);
         let user = create_test_user();
 
-        let token = service.create_token(&user).unwrap();
+        let token = service.create_token(&user, None).unwrap();
         let result = service.validate_token(&token);
 
         assert!(matches!(result, Err(AuthError::TokenExpired)));
     }
 
+    // In-memory revocation list
+    #[derive(Default)]
+    struct MemoryRevocationStore {
+        revoked: Mutex<HashSet<String>>,
+    }
+
+    impl TokenRevocationStore for MemoryRevocationStore {
+        fn revoke(
+            &self,
+            jti: &str,
+            _user_id: Uuid,
+            _expires_at: DateTime<Utc>,
+        ) -> Result<(), AuthError> {
+            self.revoked.lock().unwrap().insert(jti.to_string());
+            Ok(())
+        }
+
+        fn is_revoked(&self, jti: &str) -> Result<bool, AuthError> {
+            Ok(self.revoked.lock().unwrap().contains(jti))
+        }
+
+        fn purge_expired(&self, _now: DateTime<Utc>) -> Result<usize, AuthError> {
+            Ok(0)
+        }
+    }
+
+    #[test]
+    fn test_tokens_have_unique_jti() {
+        let service = JwtTokenService::new("test-secret-key".to_string(), 3600);
+        let user = create_test_user();
+
+        let first = service.validate_token(&service.create_token(&user, None).unwrap());
+        let second = service.validate_token(&service.create_token(&user, None).unwrap());
+
+        assert_ne!(first.unwrap().jti, second.unwrap().jti);
+    }
+
+    #[test]
+    fn test_revoked_token_rejected() {
+        let service = JwtTokenService::new("test-secret-key".to_string(), 3600)
+            .with_revocation_store(Arc::new(MemoryRevocationStore::default()));
+        let user = create_test_user();
+
+        let token = service.create_token(&user, None).unwrap();
+        let data = service.validate_token(&token).unwrap();
+        service.revoke_token(&data).unwrap();
+
+        let result = service.validate_token(&token);
+        assert!(matches!(result, Err(AuthError::TokenRevoked)));
+
+        // Other tokens of the same user are unaffected
+        let other = service.create_token(&user, None).unwrap();
+        assert!(service.validate_token(&other).is_ok());
+    }
+
+    // In-memory key store
+    #[derive(Default)]
+    struct MemorySigningKeyRepository {
+        keys: Mutex<Vec<SigningKey>>,
+    }
+
+    impl SigningKeyRepository for MemorySigningKeyRepository {
+        fn find_verifiable(&self, now: DateTime<Utc>) -> Result<Vec<SigningKey>, AuthError> {
+            let mut keys: Vec<SigningKey> = self
+                .keys
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|k| k.is_verifiable(now))
+                .cloned()
+                .collect();
+            keys.sort_by_key(|k| std::cmp::Reverse(k.created_at()));
+            Ok(keys)
+        }
+
+        fn rotate(&self, next: &SigningKey, verify_until: DateTime<Utc>) -> Result<(), AuthError> {
+            let mut keys = self.keys.lock().unwrap();
+            for key in keys.iter_mut().filter(|k| k.is_active()) {
+                *key = SigningKey::from_persistence(
+                    key.kid().to_string(),
+                    key.algorithm(),
+                    key.private_key_pem().to_string(),
+                    key.public_key_pem().to_string(),
+                    key.created_at(),
+                    Some(Utc::now()),
+                    Some(verify_until),
+                );
+            }
+            keys.push(next.clone());
+            Ok(())
+        }
+
+        fn purge_expired(&self, _now: DateTime<Utc>) -> Result<usize, AuthError> {
+            Ok(0)
+        }
+    }
+
+    fn rotate(repo: &MemorySigningKeyRepository, algorithm: SigningAlgorithm) -> String {
+        let key = RandomSigningKeyGenerator::new()
+            .generate(algorithm)
+            .unwrap();
+        repo.rotate(&key, Utc::now() + Duration::hours(1)).unwrap();
+        key.kid().to_string()
+    }
+
+    fn ring_service(repo: &Arc<MemorySigningKeyRepository>) -> JwtTokenService {
+        // Reload on every call so rotations are visible immediately
+        let ring = JwtKeyRing::new(repo.clone(), std::time::Duration::ZERO);
+        JwtTokenService::with_key_ring(Arc::new(ring), 3600)
+    }
+
+    #[test]
+    fn test_asymmetric_tokens_carry_kid() {
+        for algorithm in [SigningAlgorithm::Rs256, SigningAlgorithm::EdDsa] {
+            let repo = Arc::new(MemorySigningKeyRepository::default());
+            let kid = rotate(&repo, algorithm);
+            let service = ring_service(&repo);
+            let user = create_test_user();
+
+            let token = service.create_token(&user, None).unwrap();
+            let header = decode_header(&token).unwrap();
+            assert_eq!(header.kid.as_deref(), Some(kid.as_str()));
+            assert_eq!(format!("{:?}", header.alg), algorithm.as_str());
+
+            let data = service.validate_token(&token).unwrap();
+            assert_eq!(data.user_id, user.id().as_uuid());
+        }
+    }
+
+    #[test]
+    fn test_rotation_keeps_old_tokens_valid() {
+        let repo = Arc::new(MemorySigningKeyRepository::default());
+        let old_kid = rotate(&repo, SigningAlgorithm::EdDsa);
+        let service = ring_service(&repo);
+        let user = create_test_user();
+        let old_token = service.create_token(&user, None).unwrap();
+
+        let new_kid = rotate(&repo, SigningAlgorithm::Rs256);
+        let new_token = service.create_token(&user, None).unwrap();
+
+        assert_eq!(decode_header(&old_token).unwrap().kid, Some(old_kid));
+        assert_eq!(decode_header(&new_token).unwrap().kid, Some(new_kid));
+        assert!(service.validate_token(&old_token).is_ok());
+        assert!(service.validate_token(&new_token).is_ok());
+    }
+
+    #[test]
+    fn test_ring_rejects_secret_signed_token() {
+        let repo = Arc::new(MemorySigningKeyRepository::default());
+        rotate(&repo, SigningAlgorithm::EdDsa);
+        let service = ring_service(&repo);
+        let user = create_test_user();
+
+        let hmac_token = JwtTokenService::new("secret".to_string(), 3600)
+            .create_token(&user, None)
+            .unwrap();
+
+        let result = service.validate_token(&hmac_token);
+        assert!(matches!(result, Err(AuthError::InvalidToken)));
+    }
+
     #[test]
     fn test_wrong_secret() {
         let service1 = JwtTokenService::new("secret-1".to_string(), 3600);
         let service2 = JwtTokenService::new("secret-2".to_string(), 3600);
         let user = create_test_user();
 
-        let token = service1.create_token(&user).unwrap();
+        let token = service1.create_token(&user, None).unwrap();
         let result = service2.validate_token(&token);
 
         assert!(matches!(result, Err(AuthError::InvalidToken)));
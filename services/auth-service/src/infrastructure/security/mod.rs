@@ -1,4 +1,11 @@
-//! Security infrastructure - JWT and password hashing
+//! Security infrastructure - JWT signing keys, API keys, operator tokens, password hashing, breached passwords and TOTP
 
+pub mod api_key_token_service;
 pub mod argon2_password_hasher;
+pub mod breached_password_file;
+pub mod jwt_key_ring;
 pub mod jwt_token_service;
+pub mod opaque_token_generator;
+pub mod operator_token_service;
+pub mod signing_key_generator;
+pub mod totp_service;
@@ -0,0 +1,110 @@
+//! Tokens for operators running admin use cases in-process
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::domain::auth::{OpaqueTokenGenerator, Principal, TokenData, TokenService};
+use crate::domain::error::AuthError;
+use crate::domain::role::Permission;
+use crate::domain::user::User;
+
+use super::opaque_token_generator::RandomOpaqueTokenGenerator;
+
+/// Actor recorded in the audit log for operator actions
+pub const OPERATOR_ID: Uuid = Uuid::nil();
+
+/// TokenService for an operator with direct access to the database
+///
+/// Holds a single random token, valid for the life of the process, that
+/// authenticates a service principal limited to `scopes`. Tools such as
+/// `auth-admin` pass it to the admin use cases, so their permission checks
+/// and audit entries apply without anyone signing in. Nothing else is
+/// accepted, and no tokens are issued for users.
+pub struct OperatorTokenService {
+    token: String,
+    scopes: Vec<Permission>,
+}
+
+impl OperatorTokenService {
+    /// Create a service whose token grants `scopes`
+    #[must_use]
+    pub fn new(scopes: Vec<Permission>) -> Self {
+        Self {
+            token: RandomOpaqueTokenGenerator::new().generate(),
+            scopes,
+        }
+    }
+
+    /// The operator's token
+    #[must_use]
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+}
+
+impl TokenService for OperatorTokenService {
+    fn create_token(&self, _user: &User, _session_id: Option<Uuid>) -> Result<String, AuthError> {
+        Err(AuthError::Internal(
+            "Operator token service does not issue user tokens".to_string(),
+        ))
+    }
+
+    fn validate_token(&self, token: &str) -> Result<TokenData, AuthError> {
+        if token != self.token {
+            return Err(AuthError::InvalidToken);
+        }
+
+        Ok(TokenData {
+            user_id: OPERATOR_ID,
+            email: String::new(),
+            jti: OPERATOR_ID.to_string(),
+            session_id: None,
+            expires_at: DateTime::<Utc>::MAX_UTC,
+            roles: Vec::new(),
+            scopes: Some(self.scopes.clone()),
+            principal: Principal::Service,
+            actor: None,
+            organization: None,
+            oidc_scopes: None,
+        })
+    }
+
+    fn revoke_token(&self, _token: &TokenData) -> Result<(), AuthError> {
+        Ok(())
+    }
+
+    fn is_revoked(&self, _token: &TokenData) -> Result<bool, AuthError> {
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::role::authorize;
+
+    #[test]
+    fn test_token_grants_only_its_scopes() {
+        let service = OperatorTokenService::new(vec![Permission::ManageUsers]);
+
+        let operator = service.validate_token(service.token()).unwrap();
+        assert_eq!(operator.user_id, OPERATOR_ID);
+        assert!(operator.is_service());
+        assert!(authorize(&operator, Permission::ManageUsers).is_ok());
+        assert!(matches!(
+            authorize(&operator, Permission::ManageRoles),
+            Err(AuthError::Forbidden)
+        ));
+    }
+
+    #[test]
+    fn test_other_tokens_are_rejected() {
+        let service = OperatorTokenService::new(vec![Permission::ManageUsers]);
+        let other = OperatorTokenService::new(vec![Permission::ManageUsers]);
+
+        assert!(matches!(
+            service.validate_token(other.token()),
+            Err(AuthError::InvalidToken)
+        ));
+    }
+}
@@ -0,0 +1,85 @@
+//! Client IP and user agent resolution shared by the HTTP and gRPC adapters
+
+use std::net::IpAddr;
+
+/// Resolve the address a request originated from
+///
+/// Behind a trusted reverse proxy (the GraphQL gateway) the peer is the
+/// proxy itself, so the last `X-Forwarded-For` entry - the one the proxy
+/// appended - is used instead. The header is ignored unless
+/// `trust_forwarded_for` is set, as any client can send it.
+#[must_use]
+pub fn client_ip(
+    forwarded_for: Option<&str>,
+    peer: Option<IpAddr>,
+    trust_forwarded_for: bool,
+) -> Option<IpAddr> {
+    if trust_forwarded_for {
+        let forwarded = forwarded_for
+            .and_then(|value| value.rsplit(',').next())
+            .and_then(|entry| entry.trim().parse().ok());
+        if forwarded.is_some() {
+            return forwarded;
+        }
+    }
+
+    peer
+}
+
+/// Resolve the user agent of the client a request originated from
+///
+/// A trusted reverse proxy sends its own `User-Agent` and passes the
+/// client's on in `X-Forwarded-User-Agent`, which is ignored unless
+/// `trust_forwarded_for` is set.
+#[must_use]
+pub fn client_user_agent(
+    forwarded_user_agent: Option<&str>,
+    user_agent: Option<&str>,
+    trust_forwarded_for: bool,
+) -> Option<String> {
+    let forwarded = forwarded_user_agent.filter(|_| trust_forwarded_for);
+    forwarded
+        .or(user_agent)
+        .map(str::trim)
+        .filter(|agent| !agent.is_empty())
+        .map(String::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forwarded_for_used_only_when_trusted() {
+        let peer: IpAddr = "10.0.0.2".parse().unwrap();
+        let header = Some("203.0.113.7, 198.51.100.1");
+
+        assert_eq!(
+            client_ip(header, Some(peer), true),
+            Some("198.51.100.1".parse().unwrap())
+        );
+        assert_eq!(client_ip(header, Some(peer), false), Some(peer));
+        assert_eq!(client_ip(Some("garbage"), Some(peer), true), Some(peer));
+        assert_eq!(client_ip(None, None, true), None);
+    }
+
+    #[test]
+    fn test_forwarded_user_agent_used_only_when_trusted() {
+        let forwarded = Some("Mozilla/5.0");
+        let direct = Some("api-gateway");
+
+        assert_eq!(
+            client_user_agent(forwarded, direct, true).as_deref(),
+            Some("Mozilla/5.0")
+        );
+        assert_eq!(
+            client_user_agent(forwarded, direct, false).as_deref(),
+            Some("api-gateway")
+        );
+        assert_eq!(
+            client_user_agent(None, direct, true).as_deref(),
+            Some("api-gateway")
+        );
+        assert_eq!(client_user_agent(None, Some(" "), true), None);
+    }
+}
@@ -0,0 +1,313 @@
+//! JSON rendering of personal data exports, shared by the HTTP and gRPC adapters
+
+use std::net::IpAddr;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::domain::api_key::ApiKey;
+use crate::domain::audit::AuditEvent;
+use crate::domain::federation::UserIdentity;
+use crate::domain::organization::{Membership, Organization};
+use crate::domain::personal_data::{PersonalDataExport, SessionRecord};
+
+/// Version of the archive layout, bumped on incompatible changes
+const FORMAT_VERSION: u32 = 1;
+
+/// Personal data archive as handed to the user
+#[derive(Debug, Serialize)]
+pub struct DataExportArchive {
+    pub format_version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub profile: ProfileData,
+    pub two_factor: TwoFactorData,
+    pub sessions: Vec<SessionData>,
+    /// Times of successful logins, newest first
+    pub login_history: Vec<DateTime<Utc>>,
+    pub api_keys: Vec<ApiKeyData>,
+    pub linked_accounts: Vec<LinkedAccountData>,
+    pub organizations: Vec<OrganizationData>,
+    /// Audit trail entries about the user, newest first
+    pub activity: Vec<ActivityData>,
+}
+
+/// Account details
+#[derive(Debug, Serialize)]
+pub struct ProfileData {
+    pub user_id: Uuid,
+    pub email: String,
+    pub email_verified_at: Option<DateTime<Utc>>,
+    pub display_name: Option<String>,
+    pub is_active: bool,
+    pub roles: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Two-factor authentication state, without the secret
+#[derive(Debug, Serialize)]
+pub struct TwoFactorData {
+    pub enabled: bool,
+    pub enabled_at: Option<DateTime<Utc>>,
+}
+
+/// One signed-in session
+#[derive(Debug, Serialize)]
+pub struct SessionData {
+    pub session_id: Uuid,
+    pub started_at: DateTime<Utc>,
+    pub last_used_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub active: bool,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<IpAddr>,
+}
+
+/// One API key, without the key or its hash
+#[derive(Debug, Serialize)]
+pub struct ApiKeyData {
+    pub key_id: Uuid,
+    pub name: String,
+    pub prefix: String,
+    pub scopes: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// One account at an identity provider the user signs in with
+#[derive(Debug, Serialize)]
+pub struct LinkedAccountData {
+    pub provider: String,
+    pub subject: String,
+    pub email: Option<String>,
+    pub linked_at: DateTime<Utc>,
+    pub last_login_at: Option<DateTime<Utc>>,
+}
+
+/// One organization the user belongs to
+#[derive(Debug, Serialize)]
+pub struct OrganizationData {
+    pub organization_id: Uuid,
+    pub name: String,
+    pub role: String,
+    pub joined_at: DateTime<Utc>,
+}
+
+/// One audit trail entry the user performed or was the target of
+#[derive(Debug, Serialize)]
+pub struct ActivityData {
+    pub occurred_at: DateTime<Utc>,
+    pub action: String,
+    pub outcome: String,
+    pub actor_id: Option<Uuid>,
+    pub target_id: Option<Uuid>,
+    pub ip_address: Option<IpAddr>,
+    pub user_agent: Option<String>,
+    pub detail: Option<String>,
+}
+
+impl DataExportArchive {
+    /// Build the archive for an export
+    #[must_use]
+    pub fn new(export: &PersonalDataExport) -> Self {
+        let user = &export.user;
+        Self {
+            format_version: FORMAT_VERSION,
+            exported_at: export.exported_at,
+            profile: ProfileData {
+                user_id: user.id().as_uuid(),
+                email: user.email().as_str().to_string(),
+                email_verified_at: user.email_verified_at(),
+                display_name: user.display_name().map(String::from),
+                is_active: user.is_active(),
+                roles: user
+                    .roles()
+                    .iter()
+                    .map(|r| r.as_str().to_string())
+                    .collect(),
+                created_at: user.created_at(),
+                updated_at: user.updated_at(),
+            },
+            two_factor: TwoFactorData {
+                enabled: export.totp_enabled_at.is_some(),
+                enabled_at: export.totp_enabled_at,
+            },
+            sessions: export
+                .sessions
+                .iter()
+                .map(|session| SessionData::new(session, export.exported_at))
+                .collect(),
+            login_history: export.login_history(),
+            api_keys: export.api_keys.iter().map(ApiKeyData::new).collect(),
+            linked_accounts: export
+                .identities
+                .iter()
+                .map(LinkedAccountData::new)
+                .collect(),
+            organizations: export
+                .organizations
+                .iter()
+                .map(|(organization, membership)| OrganizationData::new(organization, membership))
+                .collect(),
+            activity: export.audit_events.iter().map(ActivityData::new).collect(),
+        }
+    }
+
+    /// Render the archive as pretty-printed JSON
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        // Plain data with string keys always serializes
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+}
+
+impl SessionData {
+    fn new(session: &SessionRecord, now: DateTime<Utc>) -> Self {
+        Self {
+            session_id: session.family_id,
+            started_at: session.started_at,
+            last_used_at: session.last_used_at,
+            expires_at: session.expires_at,
+            ended_at: session.ended_at,
+            active: session.is_active(now),
+            user_agent: session.user_agent.clone(),
+            ip_address: session.ip_address,
+        }
+    }
+}
+
+impl ApiKeyData {
+    fn new(key: &ApiKey) -> Self {
+        Self {
+            key_id: key.id(),
+            name: key.name().to_string(),
+            prefix: key.prefix().to_string(),
+            scopes: key
+                .scopes()
+                .iter()
+                .map(|s| s.as_str().to_string())
+                .collect(),
+            created_at: key.created_at(),
+            last_used_at: key.last_used_at(),
+            expires_at: key.expires_at(),
+        }
+    }
+}
+
+impl LinkedAccountData {
+    fn new(identity: &UserIdentity) -> Self {
+        Self {
+            provider: identity.provider().to_string(),
+            subject: identity.subject().to_string(),
+            email: identity.email().map(String::from),
+            linked_at: identity.created_at(),
+            last_login_at: identity.last_login_at(),
+        }
+    }
+}
+
+impl OrganizationData {
+    fn new(organization: &Organization, membership: &Membership) -> Self {
+        Self {
+            organization_id: organization.id(),
+            name: organization.name().to_string(),
+            role: membership.role().as_str().to_string(),
+            joined_at: membership.created_at(),
+        }
+    }
+}
+
+impl ActivityData {
+    fn new(event: &AuditEvent) -> Self {
+        Self {
+            occurred_at: event.occurred_at(),
+            action: event.action().as_str().to_string(),
+            outcome: event.outcome().as_str().to_string(),
+            actor_id: event.actor_id(),
+            target_id: event.target_id(),
+            ip_address: event.ip_address(),
+            user_agent: event.user_agent().map(String::from),
+            detail: event.detail().map(String::from),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::domain::audit::AuditAction;
+    use crate::domain::organization::OrgRole;
+    use crate::domain::role::Permission;
+    use crate::domain::user::{Email, HashedPassword, User};
+
+    #[test]
+    fn test_archive_contains_no_secrets() {
+        let now = Utc::now();
+        let user = User::new(
+            Email::new("test@example.com").unwrap(),
+            HashedPassword::from_hash("$argon2id$secret-hash".to_string()),
+            Some("Test User".to_string()),
+        );
+        let user_id = user.id().as_uuid();
+        let export = PersonalDataExport {
+            user,
+            totp_enabled_at: Some(now),
+            sessions: vec![SessionRecord {
+                family_id: Uuid::new_v4(),
+                started_at: now - Duration::days(1),
+                last_used_at: now,
+                expires_at: now + Duration::days(6),
+                ended_at: None,
+                user_agent: Some("Mozilla/5.0".to_string()),
+                ip_address: Some("203.0.113.7".parse().unwrap()),
+            }],
+            api_keys: vec![ApiKey::issue(
+                Uuid::new_v4(),
+                user_id,
+                "Kiosk".to_string(),
+                "secret-key-hash".to_string(),
+                vec![Permission::PurchaseTickets],
+                None,
+            )],
+            identities: vec![UserIdentity::from_persistence(
+                "corp".to_string(),
+                "248289761001".to_string(),
+                user_id,
+                Some("test@example.com".to_string()),
+                now - Duration::days(30),
+                Some(now),
+            )],
+            organizations: vec![{
+                let organization = Organization::new("Riverside Concerts".to_string());
+                let membership = Membership::new(organization.id(), user_id, OrgRole::Admin);
+                (organization, membership)
+            }],
+            audit_events: vec![AuditEvent::new(AuditAction::UserDataExport)
+                .with_actor(Uuid::new_v4())
+                .with_target(user_id)],
+            exported_at: now,
+        };
+
+        let json = DataExportArchive::new(&export).to_json();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(value["format_version"], 1);
+        assert_eq!(value["profile"]["email"], "test@example.com");
+        assert_eq!(value["two_factor"]["enabled"], true);
+        assert_eq!(value["sessions"][0]["active"], true);
+        assert_eq!(value["sessions"][0]["ip_address"], "203.0.113.7");
+        assert_eq!(value["login_history"].as_array().unwrap().len(), 1);
+        assert_eq!(value["api_keys"][0]["scopes"][0], "tickets:purchase");
+        assert_eq!(value["linked_accounts"][0]["provider"], "corp");
+        assert_eq!(value["organizations"][0]["role"], "admin");
+        assert_eq!(value["activity"][0]["action"], "admin.user_export");
+        assert_eq!(value["activity"][0]["target_id"], user_id.to_string());
+        assert!(!json.contains("secret-hash"));
+        assert!(!json.contains("secret-key-hash"));
+    }
+}
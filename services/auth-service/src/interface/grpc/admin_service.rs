@@ -0,0 +1,299 @@
+//! gRPC AdminUserService implementation
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use tonic::{Request, Response, Status};
+
+use super::service::pb::admin_user_service_server::AdminUserService;
+use super::service::pb::{
+    AdminUser, AuditEvent as AuditEventResponse, DeleteAccountResponse, DeleteUserRequest,
+    EmailMatchMode, ExportUserDataRequest, ImpersonateUserRequest, ImpersonateUserResponse,
+    ListAuditEventsRequest, ListAuditEventsResponse, ListUsersRequest, ListUsersResponse,
+    SetUserActiveRequest, UserDataExport,
+};
+use super::service::{delete_account, export_user_data, map_auth_error, session_client};
+use crate::application::commands::{
+    impersonate_user::{ImpersonateUserCommand, ImpersonateUserUseCase},
+    list_audit_events::{ListAuditEventsCommand, ListAuditEventsUseCase},
+    list_users::{ListUsersCommand, ListUsersUseCase},
+    set_user_active::{SetUserActiveCommand, SetUserActiveUseCase},
+};
+use crate::domain::audit::{AuditEvent, AuditOutcome, ClientAuditLog};
+use crate::domain::session::SessionClient;
+use crate::domain::user::User;
+use crate::domain::user_search::EmailMatch;
+use crate::AppState;
+
+/// gRPC implementation of the AdminUserService
+pub struct AdminUserServiceGrpc {
+    state: Arc<AppState>,
+}
+
+impl AdminUserServiceGrpc {
+    /// Create a new gRPC admin service with shared application state
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    /// Deactivate or reactivate the requested user
+    async fn set_active(
+        &self,
+        client: SessionClient,
+        req: SetUserActiveRequest,
+        active: bool,
+    ) -> Result<Response<AdminUser>, Status> {
+        let user_id = uuid::Uuid::parse_str(&req.user_id)
+            .map_err(|_| Status::invalid_argument("Invalid user ID"))?;
+        let state = Arc::clone(&self.state);
+
+        let user = tokio::task::spawn_blocking(move || {
+            let repo = state.users.as_ref();
+            let refresh_tokens = state.refresh_tokens.as_ref();
+            let audit_log = ClientAuditLog::new(state.audit_log.as_ref(), client);
+            let use_case = SetUserActiveUseCase::new(
+                repo,
+                refresh_tokens,
+                state.token_service.as_ref(),
+                &audit_log,
+            );
+
+            let command = SetUserActiveCommand {
+                token: req.token,
+                user_id,
+                active,
+            };
+
+            use_case.execute(command)
+        })
+        .await
+        .map_err(|e| Status::internal(format!("Task join error: {}", e)))?
+        .map_err(map_auth_error)?;
+
+        Ok(Response::new(admin_user(&user)))
+    }
+}
+
+/// Render a user for back-office clients
+fn admin_user(user: &User) -> AdminUser {
+    AdminUser {
+        user_id: user.id().as_uuid().to_string(),
+        email: user.email().as_str().to_string(),
+        display_name: user.display_name().map(String::from),
+        is_active: user.is_active(),
+        email_verified: user.is_email_verified(),
+        roles: user
+            .roles()
+            .iter()
+            .map(|r| r.as_str().to_string())
+            .collect(),
+        created_at: user.created_at().to_rfc3339(),
+        updated_at: user.updated_at().to_rfc3339(),
+    }
+}
+
+/// Render an audit log entry for back-office clients
+fn audit_event(event: &AuditEvent) -> AuditEventResponse {
+    AuditEventResponse {
+        id: event.id().to_string(),
+        occurred_at: event.occurred_at().to_rfc3339(),
+        action: event.action().as_str().to_string(),
+        outcome: event.outcome().as_str().to_string(),
+        actor_id: event.actor_id().map(|id| id.to_string()),
+        target_id: event.target_id().map(|id| id.to_string()),
+        ip_address: event.ip_address().map(|ip| ip.to_string()),
+        user_agent: event.user_agent().map(String::from),
+        detail: event.detail().map(String::from),
+    }
+}
+
+/// Parse an optional user ID field
+fn parse_user_id(value: Option<&str>) -> Result<Option<uuid::Uuid>, uuid::Error> {
+    value.map(uuid::Uuid::parse_str).transpose()
+}
+
+/// Parse an optional RFC 3339 timestamp field
+fn parse_timestamp(value: Option<&str>) -> Result<Option<DateTime<Utc>>, chrono::ParseError> {
+    value
+        .map(|v| DateTime::parse_from_rfc3339(v).map(|t| t.with_timezone(&Utc)))
+        .transpose()
+}
+
+#[tonic::async_trait]
+impl AdminUserService for AdminUserServiceGrpc {
+    async fn list_users(
+        &self,
+        request: Request<ListUsersRequest>,
+    ) -> Result<Response<ListUsersResponse>, Status> {
+        let req = request.into_inner();
+        let email_match = req.email_match();
+        let created_after = parse_timestamp(req.created_after.as_deref())
+            .map_err(|_| Status::invalid_argument("Invalid created_after"))?;
+        let created_before = parse_timestamp(req.created_before.as_deref())
+            .map_err(|_| Status::invalid_argument("Invalid created_before"))?;
+        let email = req.email_query.map(|term| match email_match {
+            EmailMatchMode::Prefix => EmailMatch::Prefix(term),
+            EmailMatchMode::Contains => EmailMatch::Contains(term),
+        });
+        let state = Arc::clone(&self.state);
+
+        let page = tokio::task::spawn_blocking(move || {
+            let use_case =
+                ListUsersUseCase::new(state.user_search.as_ref(), state.token_service.as_ref());
+
+            let command = ListUsersCommand {
+                token: req.token,
+                is_active: req.is_active,
+                role: req.role,
+                created_after,
+                created_before,
+                email,
+                cursor: req.cursor,
+                limit: req.limit as usize,
+            };
+
+            use_case.execute(command)
+        })
+        .await
+        .map_err(|e| Status::internal(format!("Task join error: {}", e)))?
+        .map_err(map_auth_error)?;
+
+        Ok(Response::new(ListUsersResponse {
+            users: page.users.iter().map(admin_user).collect(),
+            next_cursor: page.next_cursor.map(|cursor| cursor.encode()),
+        }))
+    }
+
+    async fn deactivate_user(
+        &self,
+        request: Request<SetUserActiveRequest>,
+    ) -> Result<Response<AdminUser>, Status> {
+        let client = session_client(&self.state, &request);
+        self.set_active(client, request.into_inner(), false).await
+    }
+
+    async fn reactivate_user(
+        &self,
+        request: Request<SetUserActiveRequest>,
+    ) -> Result<Response<AdminUser>, Status> {
+        let client = session_client(&self.state, &request);
+        self.set_active(client, request.into_inner(), true).await
+    }
+    async fn export_user_data(
+        &self,
+        request: Request<ExportUserDataRequest>,
+    ) -> Result<Response<UserDataExport>, Status> {
+        let client = session_client(&self.state, &request);
+        let req = request.into_inner();
+        let user_id = uuid::Uuid::parse_str(&req.user_id)
+            .map_err(|_| Status::invalid_argument("Invalid user ID"))?;
+
+        export_user_data(Arc::clone(&self.state), client, req.token, Some(user_id)).await
+    }
+
+    async fn delete_user(
+        &self,
+        request: Request<DeleteUserRequest>,
+    ) -> Result<Response<DeleteAccountResponse>, Status> {
+        let client = session_client(&self.state, &request);
+        let req = request.into_inner();
+        let user_id = uuid::Uuid::parse_str(&req.user_id)
+            .map_err(|_| Status::invalid_argument("Invalid user ID"))?;
+
+        delete_account(
+            Arc::clone(&self.state),
+            client,
+            req.token,
+            Some(user_id),
+            None,
+        )
+        .await
+    }
+
+    async fn list_audit_events(
+        &self,
+        request: Request<ListAuditEventsRequest>,
+    ) -> Result<Response<ListAuditEventsResponse>, Status> {
+        let req = request.into_inner();
+        let outcome = match req.outcome.as_deref() {
+            None => None,
+            Some("success") => Some(AuditOutcome::Success),
+            Some("failure") => Some(AuditOutcome::Failure),
+            Some(_) => return Err(Status::invalid_argument("Invalid outcome")),
+        };
+        let actor_id = parse_user_id(req.actor_id.as_deref())
+            .map_err(|_| Status::invalid_argument("Invalid actor_id"))?;
+        let target_id = parse_user_id(req.target_id.as_deref())
+            .map_err(|_| Status::invalid_argument("Invalid target_id"))?;
+        let occurred_after = parse_timestamp(req.occurred_after.as_deref())
+            .map_err(|_| Status::invalid_argument("Invalid occurred_after"))?;
+        let occurred_before = parse_timestamp(req.occurred_before.as_deref())
+            .map_err(|_| Status::invalid_argument("Invalid occurred_before"))?;
+        let state = Arc::clone(&self.state);
+
+        let page = tokio::task::spawn_blocking(move || {
+            let repo = state.audit_events.as_ref();
+            let use_case = ListAuditEventsUseCase::new(repo, state.token_service.as_ref());
+
+            let command = ListAuditEventsCommand {
+                token: req.token,
+                action: req.action,
+                outcome,
+                actor_id,
+                target_id,
+                occurred_after,
+                occurred_before,
+                cursor: req.cursor,
+                limit: req.limit as usize,
+            };
+
+            use_case.execute(command)
+        })
+        .await
+        .map_err(|e| Status::internal(format!("Task join error: {}", e)))?
+        .map_err(map_auth_error)?;
+
+        Ok(Response::new(ListAuditEventsResponse {
+            events: page.events.iter().map(audit_event).collect(),
+            next_cursor: page.next_cursor.map(|cursor| cursor.encode()),
+        }))
+    }
+
+    async fn impersonate_user(
+        &self,
+        request: Request<ImpersonateUserRequest>,
+    ) -> Result<Response<ImpersonateUserResponse>, Status> {
+        let client = session_client(&self.state, &request);
+        let req = request.into_inner();
+        let user_id = uuid::Uuid::parse_str(&req.user_id)
+            .map_err(|_| Status::invalid_argument("Invalid user ID"))?;
+        let state = Arc::clone(&self.state);
+
+        let result = tokio::task::spawn_blocking(move || {
+            let repo = state.users.as_ref();
+            let audit_log = ClientAuditLog::new(state.audit_log.as_ref(), client);
+            let use_case = ImpersonateUserUseCase::new(
+                repo,
+                state.token_service.as_ref(),
+                state.impersonation_token_issuer.as_ref(),
+                state.impersonation_ttl,
+                &audit_log,
+            );
+
+            use_case.execute(ImpersonateUserCommand {
+                token: req.token,
+                user_id,
+            })
+        })
+        .await
+        .map_err(|e| Status::internal(format!("Task join error: {}", e)))?
+        .map_err(map_auth_error)?;
+
+        Ok(Response::new(ImpersonateUserResponse {
+            token: result.token,
+            expires_in: result.expires_in,
+            user_id: result.user_id.to_string(),
+            email: result.email,
+        }))
+    }
+}
@@ -1,3 +1,6 @@
 //! gRPC interface layer
 
+pub mod admin_service;
+pub mod organization_service;
 pub mod service;
+pub mod token_audit;
@@ -0,0 +1,225 @@
+//! gRPC OrganizationService implementation
+
+use std::sync::Arc;
+
+use tonic::{Request, Response, Status};
+
+use super::service::pb::organization_service_server::OrganizationService;
+use super::service::pb::{
+    AcceptInvitationRequest, CreateOrganizationRequest, InviteMemberRequest, InviteMemberResponse,
+    ListMyOrganizationsRequest, ListMyOrganizationsResponse, Organization as OrganizationResponse,
+    SwitchOrganizationRequest, SwitchOrganizationResponse,
+};
+use super::service::{map_auth_error, session_client};
+use crate::application::commands::{
+    accept_invitation::{AcceptInvitationCommand, AcceptInvitationUseCase},
+    create_organization::{CreateOrganizationCommand, CreateOrganizationUseCase},
+    invite_member::{InviteMemberCommand, InviteMemberUseCase},
+    list_organizations::ListOrganizationsUseCase,
+    switch_organization::{SwitchOrganizationCommand, SwitchOrganizationUseCase},
+};
+use crate::domain::audit::ClientAuditLog;
+use crate::domain::organization::{Membership, Organization};
+use crate::AppState;
+
+/// gRPC implementation of the OrganizationService
+pub struct OrganizationServiceGrpc {
+    state: Arc<AppState>,
+}
+
+impl OrganizationServiceGrpc {
+    /// Create a new gRPC organization service with shared application state
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+}
+
+/// Render an organization as seen by one of its members
+fn organization(organization: &Organization, membership: &Membership) -> OrganizationResponse {
+    OrganizationResponse {
+        organization_id: organization.id().to_string(),
+        name: organization.name().to_string(),
+        role: membership.role().as_str().to_string(),
+        created_at: organization.created_at().to_rfc3339(),
+        joined_at: membership.created_at().to_rfc3339(),
+    }
+}
+
+#[tonic::async_trait]
+impl OrganizationService for OrganizationServiceGrpc {
+    async fn create_organization(
+        &self,
+        request: Request<CreateOrganizationRequest>,
+    ) -> Result<Response<OrganizationResponse>, Status> {
+        let client = session_client(&self.state, &request);
+        let req = request.into_inner();
+        let state = Arc::clone(&self.state);
+
+        let (created, owner) = tokio::task::spawn_blocking(move || {
+            let users = state.users.as_ref();
+            let organizations = state.organizations.as_ref();
+            let audit_log = ClientAuditLog::new(state.audit_log.as_ref(), client);
+            let use_case = CreateOrganizationUseCase::new(
+                users,
+                organizations,
+                state.token_service.as_ref(),
+                &audit_log,
+            );
+
+            use_case.execute(CreateOrganizationCommand {
+                token: req.token,
+                name: req.name,
+            })
+        })
+        .await
+        .map_err(|e| Status::internal(format!("Task join error: {}", e)))?
+        .map_err(map_auth_error)?;
+
+        Ok(Response::new(organization(&created, &owner)))
+    }
+
+    async fn list_my_organizations(
+        &self,
+        request: Request<ListMyOrganizationsRequest>,
+    ) -> Result<Response<ListMyOrganizationsResponse>, Status> {
+        let req = request.into_inner();
+        let state = Arc::clone(&self.state);
+
+        let memberships = tokio::task::spawn_blocking(move || {
+            let organizations = state.organizations.as_ref();
+            let use_case =
+                ListOrganizationsUseCase::new(organizations, state.token_service.as_ref());
+
+            use_case.execute(&req.token)
+        })
+        .await
+        .map_err(|e| Status::internal(format!("Task join error: {}", e)))?
+        .map_err(map_auth_error)?;
+
+        Ok(Response::new(ListMyOrganizationsResponse {
+            organizations: memberships
+                .iter()
+                .map(|(org, membership)| organization(org, membership))
+                .collect(),
+        }))
+    }
+
+    async fn invite_member(
+        &self,
+        request: Request<InviteMemberRequest>,
+    ) -> Result<Response<InviteMemberResponse>, Status> {
+        let client = session_client(&self.state, &request);
+        let req = request.into_inner();
+        let organization_id = uuid::Uuid::parse_str(&req.organization_id)
+            .map_err(|_| Status::invalid_argument("Invalid organization ID"))?;
+        let state = Arc::clone(&self.state);
+
+        let result = tokio::task::spawn_blocking(move || {
+            let organizations = state.organizations.as_ref();
+            let invitations = state.invitations.as_ref();
+            let audit_log = ClientAuditLog::new(state.audit_log.as_ref(), client);
+            let use_case = InviteMemberUseCase::new(
+                organizations,
+                invitations,
+                state.token_service.as_ref(),
+                state.token_generator.as_ref(),
+                state.mailer.as_ref(),
+                state.invitation_ttl,
+                &state.invitation_url,
+                &audit_log,
+            );
+
+            use_case.execute(InviteMemberCommand {
+                token: req.token,
+                organization_id,
+                email: req.email,
+                role: req.role,
+            })
+        })
+        .await
+        .map_err(|e| Status::internal(format!("Task join error: {}", e)))?
+        .map_err(map_auth_error)?;
+
+        Ok(Response::new(InviteMemberResponse {
+            invitation_id: result.invitation_id.to_string(),
+            expires_at: result.expires_at.to_rfc3339(),
+        }))
+    }
+
+    async fn accept_invitation(
+        &self,
+        request: Request<AcceptInvitationRequest>,
+    ) -> Result<Response<OrganizationResponse>, Status> {
+        let client = session_client(&self.state, &request);
+        let req = request.into_inner();
+        let state = Arc::clone(&self.state);
+
+        let result = tokio::task::spawn_blocking(move || {
+            let users = state.users.as_ref();
+            let organizations = state.organizations.as_ref();
+            let invitations = state.invitations.as_ref();
+            let audit_log = ClientAuditLog::new(state.audit_log.as_ref(), client);
+            let use_case = AcceptInvitationUseCase::new(
+                users,
+                organizations,
+                invitations,
+                state.token_service.as_ref(),
+                state.token_generator.as_ref(),
+                &audit_log,
+            );
+
+            use_case.execute(AcceptInvitationCommand {
+                token: req.token,
+                invitation_token: req.invitation_token,
+            })
+        })
+        .await
+        .map_err(|e| Status::internal(format!("Task join error: {}", e)))?
+        .map_err(map_auth_error)?;
+
+        Ok(Response::new(organization(
+            &result.organization,
+            &result.membership,
+        )))
+    }
+
+    async fn switch_organization(
+        &self,
+        request: Request<SwitchOrganizationRequest>,
+    ) -> Result<Response<SwitchOrganizationResponse>, Status> {
+        let req = request.into_inner();
+        let organization_id = req
+            .organization_id
+            .as_deref()
+            .map(uuid::Uuid::parse_str)
+            .transpose()
+            .map_err(|_| Status::invalid_argument("Invalid organization ID"))?;
+        let state = Arc::clone(&self.state);
+
+        let result = tokio::task::spawn_blocking(move || {
+            let users = state.users.as_ref();
+            let organizations = state.organizations.as_ref();
+            let use_case = SwitchOrganizationUseCase::new(
+                users,
+                organizations,
+                state.sessions.as_ref(),
+                state.token_service.as_ref(),
+                state.organization_token_issuer.as_ref(),
+            );
+
+            use_case.execute(SwitchOrganizationCommand {
+                token: req.token,
+                organization_id,
+            })
+        })
+        .await
+        .map_err(|e| Status::internal(format!("Task join error: {}", e)))?
+        .map_err(map_auth_error)?;
+
+        Ok(Response::new(SwitchOrganizationResponse {
+            token: result.token,
+            organization_id: result.organization.map(|org| org.id.to_string()),
+            role: result.organization.map(|org| org.role.as_str().to_string()),
+        }))
+    }
+}
@@ -2,15 +2,49 @@
 
 use std::sync::Arc;
 
+use tonic::metadata::MetadataValue;
 use tonic::{Request, Response, Status};
 
 use crate::application::commands::{
-    login_user::{LoginUserCommand, LoginUserUseCase},
+    assign_role::{AssignRoleCommand, AssignRoleUseCase},
+    change_email::{ChangeEmailCommand, ChangeEmailUseCase},
+    change_password::{ChangePasswordCommand, ChangePasswordUseCase},
+    confirm_password_reset::{ConfirmPasswordResetCommand, ConfirmPasswordResetUseCase},
+    confirm_totp_enrollment::{ConfirmTotpEnrollmentCommand, ConfirmTotpEnrollmentUseCase},
+    consume_magic_link::{ConsumeMagicLinkCommand, ConsumeMagicLinkUseCase},
+    create_api_key::{CreateApiKeyCommand, CreateApiKeyUseCase},
+    delete_account::{DeleteAccountCommand, DeleteAccountUseCase},
+    enroll_totp::{EnrollTotpCommand, EnrollTotpUseCase},
+    export_user_data::{ExportUserDataCommand, ExportUserDataUseCase},
+    get_current_user::GetCurrentUserUseCase,
+    list_api_keys::ListApiKeysUseCase,
+    list_sessions::ListSessionsUseCase,
+    login_user::{LoginOutcome, LoginUserCommand, LoginUserResult, LoginUserUseCase},
+    logout_user::{LogoutUserCommand, LogoutUserUseCase},
+    refresh_session::{RefreshSessionCommand, RefreshSessionUseCase},
     register_user::{RegisterUserCommand, RegisterUserUseCase},
+    request_magic_link::{RequestMagicLinkCommand, RequestMagicLinkUseCase},
+    request_password_reset::{RequestPasswordResetCommand, RequestPasswordResetUseCase},
+    revoke_api_key::{RevokeApiKeyCommand, RevokeApiKeyUseCase},
+    revoke_other_sessions::RevokeOtherSessionsUseCase,
+    revoke_role::{RevokeRoleCommand, RevokeRoleUseCase},
+    revoke_session::{RevokeSessionCommand, RevokeSessionUseCase},
+    send_email_verification::{SendEmailVerificationCommand, SendEmailVerificationUseCase},
+    unlock_account::{UnlockAccountCommand, UnlockAccountUseCase},
+    update_profile::{UpdateProfileCommand, UpdateProfileUseCase},
+    verify_email::{VerifyEmailCommand, VerifyEmailUseCase},
+    verify_mfa::{VerifyMfaCommand, VerifyMfaUseCase},
 };
-use crate::domain::auth::UserRepository;
+use crate::domain::api_key::ApiKey;
+use crate::domain::audit::ClientAuditLog;
 use crate::domain::error::AuthError;
-use crate::infrastructure::db::user_repository_diesel::DieselUserRepository;
+use crate::domain::role::{permissions_for, Permission, Role};
+use crate::domain::session::{Session, SessionClient};
+use crate::domain::user::User;
+use crate::interface::client_ip::{client_ip, client_user_agent};
+use crate::interface::data_export::DataExportArchive;
+use crate::interface::grpc::token_audit::TOKEN_ERROR_METADATA;
+use crate::interface::token_audit::token_error_code;
 use crate::AppState;
 
 pub mod pb {
@@ -19,8 +53,20 @@ pub mod pb {
 
 use pb::auth_service_server::AuthService;
 use pb::{
-    GetMeRequest, GetMeResponse, LoginRequest, LoginResponse, RegisterRequest, RegisterResponse,
-    ValidateTokenRequest, ValidateTokenResponse,
+    AssignRoleRequest, ChangeEmailRequest, ChangePasswordRequest, ChangePasswordResponse,
+    ConfirmPasswordResetRequest, ConfirmPasswordResetResponse, ConfirmTotpEnrollmentRequest,
+    ConfirmTotpEnrollmentResponse, ConsumeMagicLinkRequest, CreateApiKeyRequest,
+    CreateApiKeyResponse, DeleteAccountRequest, DeleteAccountResponse, EnrollTotpRequest,
+    EnrollTotpResponse, ExportMyDataRequest, GetMeRequest, GetMeResponse, ListApiKeysRequest,
+    ListApiKeysResponse, ListSessionsRequest, ListSessionsResponse, LoginRequest, LoginResponse,
+    LogoutRequest, LogoutResponse, RefreshRequest, RefreshResponse, RegisterRequest,
+    RegisterResponse, RequestMagicLinkRequest, RequestMagicLinkResponse,
+    RequestPasswordResetRequest, RequestPasswordResetResponse, ResendEmailVerificationRequest,
+    ResendEmailVerificationResponse, RevokeApiKeyRequest, RevokeApiKeyResponse,
+    RevokeOtherSessionsRequest, RevokeOtherSessionsResponse, RevokeRoleRequest,
+    RevokeSessionRequest, RevokeSessionResponse, UnlockAccountRequest, UnlockAccountResponse,
+    UpdateProfileRequest, UserDataExport, UserRolesResponse, ValidateTokenRequest,
+    ValidateTokenResponse, VerifyEmailRequest, VerifyEmailResponse, VerifyMfaRequest,
 };
 
 /// gRPC implementation of the AuthService
@@ -33,47 +79,271 @@ impl AuthServiceGrpc {
     pub fn new(state: Arc<AppState>) -> Self {
         Self { state }
     }
+
+    /// Address and user agent of the client, recorded with new sessions
+    fn session_client<T>(&self, request: &Request<T>) -> SessionClient {
+        session_client(&self.state, request)
+    }
+}
+
+/// Address and user agent of the client of a request (shared with
+/// AdminUserService), recorded with new sessions and audit entries
+pub(crate) fn session_client<T>(state: &AppState, request: &Request<T>) -> SessionClient {
+    let metadata = |key: &str| request.metadata().get(key).and_then(|v| v.to_str().ok());
+    SessionClient {
+        user_agent: client_user_agent(
+            metadata("x-forwarded-user-agent"),
+            metadata("user-agent"),
+            state.trust_forwarded_for,
+        ),
+        ip_address: client_ip(
+            metadata("x-forwarded-for"),
+            request.remote_addr().map(|addr| addr.ip()),
+            state.trust_forwarded_for,
+        ),
+    }
 }
 
 /// Map domain AuthError to gRPC Status
-fn map_auth_error(err: AuthError) -> Status {
+pub(crate) fn map_auth_error(err: AuthError) -> Status {
     match err {
         AuthError::UserAlreadyExists => Status::already_exists(err.to_string()),
         AuthError::UserNotFound => Status::not_found(err.to_string()),
         AuthError::InvalidCredentials => Status::unauthenticated(err.to_string()),
-        AuthError::InvalidToken => Status::unauthenticated(err.to_string()),
-        AuthError::TokenExpired => Status::unauthenticated(err.to_string()),
+        AuthError::InvalidToken | AuthError::TokenExpired | AuthError::TokenRevoked => {
+            let mut status = Status::unauthenticated(err.to_string());
+            if let Some(code) = token_error_code(&err) {
+                status
+                    .metadata_mut()
+                    .insert(TOKEN_ERROR_METADATA, MetadataValue::from_static(code));
+            }
+            status
+        }
+        AuthError::TokenReused => Status::unauthenticated(err.to_string()),
         AuthError::InvalidEmail => Status::invalid_argument(err.to_string()),
-        AuthError::WeakPassword => Status::invalid_argument(err.to_string()),
+        AuthError::InvalidDisplayName => Status::invalid_argument(err.to_string()),
+        AuthError::WeakPassword(ref rules) => {
+            let mut status = Status::invalid_argument(err.to_string());
+            let names: Vec<&str> = rules.iter().map(|r| r.as_str()).collect();
+            if let Ok(value) = names.join(",").parse() {
+                status.metadata_mut().insert("password-rules", value);
+            }
+            status
+        }
         AuthError::AccountInactive => Status::permission_denied(err.to_string()),
+        AuthError::AccountLocked { retry_after_secs } => {
+            let mut status = Status::resource_exhausted(err.to_string());
+            if let Ok(value) = retry_after_secs.to_string().parse() {
+                status.metadata_mut().insert("retry-after", value);
+            }
+            status
+        }
+        AuthError::EmailNotVerified => Status::permission_denied(err.to_string()),
+        AuthError::RecentLoginRequired => Status::permission_denied(err.to_string()),
+        AuthError::InvalidMfaCode => Status::unauthenticated(err.to_string()),
+        AuthError::MfaAlreadyEnabled => Status::already_exists(err.to_string()),
+        AuthError::MfaNotEnrolled => Status::failed_precondition(err.to_string()),
+        AuthError::Forbidden => Status::permission_denied(err.to_string()),
+        AuthError::CannotImpersonate => Status::permission_denied(err.to_string()),
+        AuthError::InvalidRole => Status::invalid_argument(err.to_string()),
+        AuthError::InvalidCursor => Status::invalid_argument(err.to_string()),
+        AuthError::InvalidAuditAction => Status::invalid_argument(err.to_string()),
+        AuthError::InvalidScope => Status::invalid_argument(err.to_string()),
+        AuthError::InvalidApiKeyName => Status::invalid_argument(err.to_string()),
+        AuthError::ApiKeyNotFound => Status::not_found(err.to_string()),
+        AuthError::SessionNotFound => Status::not_found(err.to_string()),
+        AuthError::InvalidOrganizationName => Status::invalid_argument(err.to_string()),
+        AuthError::OrganizationNotFound => Status::not_found(err.to_string()),
+        AuthError::InvalidInvitation => Status::invalid_argument(err.to_string()),
+        AuthError::InvalidClient => Status::unauthenticated(err.to_string()),
+        AuthError::UnsupportedGrantType => Status::invalid_argument(err.to_string()),
+        AuthError::InvalidClientName => Status::invalid_argument(err.to_string()),
+        AuthError::OAuthClientNotFound => Status::not_found(err.to_string()),
+        AuthError::InvalidGrant => Status::invalid_argument(err.to_string()),
+        AuthError::InvalidRequest(_) => Status::invalid_argument(err.to_string()),
+        AuthError::IdentityProviderNotFound => Status::not_found(err.to_string()),
+        AuthError::IdentityProvider(_) => Status::unavailable(err.to_string()),
         AuthError::Internal(msg) => Status::internal(msg),
     }
 }
 
+/// Render roles by their stable identifiers
+fn role_names(roles: &[Role]) -> Vec<String> {
+    roles.iter().map(|r| r.as_str().to_string()).collect()
+}
+
+/// Render permissions by their stable identifiers
+fn permission_names(permissions: &[Permission]) -> Vec<String> {
+    permissions.iter().map(|p| p.as_str().to_string()).collect()
+}
+
+/// Render an API key without its secret
+fn api_key_message(key: &ApiKey) -> pb::ApiKey {
+    pb::ApiKey {
+        key_id: key.id().to_string(),
+        name: key.name().to_string(),
+        prefix: key.prefix().to_string(),
+        scopes: permission_names(key.scopes()),
+        created_at: key.created_at().to_rfc3339(),
+        last_used_at: key.last_used_at().map(|at| at.to_rfc3339()),
+        expires_at: key.expires_at().map(|at| at.to_rfc3339()),
+    }
+}
+
+/// Render a session, flagging the caller's own
+fn session_message(session: &Session, current_session_id: Option<uuid::Uuid>) -> pb::Session {
+    pb::Session {
+        session_id: session.id().to_string(),
+        user_agent: session.user_agent().map(String::from),
+        ip_address: session.ip_address().map(|ip| ip.to_string()),
+        created_at: session.created_at().to_rfc3339(),
+        last_seen_at: session.last_seen_at().to_rfc3339(),
+        expires_at: session.expires_at().to_rfc3339(),
+        current: Some(session.id()) == current_session_id,
+    }
+}
+
+/// Render the current user
+fn me_response(user: &User) -> GetMeResponse {
+    GetMeResponse {
+        user_id: user.id().as_uuid().to_string(),
+        email: user.email().as_str().to_string(),
+        display_name: user.display_name().map(String::from),
+        is_active: user.is_active(),
+        email_verified: user.is_email_verified(),
+        roles: role_names(user.roles()),
+        permissions: permission_names(&permissions_for(user.roles())),
+    }
+}
+
+/// Export a user's data, `None` meaning the caller (shared with AdminUserService)
+pub(crate) async fn export_user_data(
+    state: Arc<AppState>,
+    client: SessionClient,
+    token: String,
+    user_id: Option<uuid::Uuid>,
+) -> Result<Response<UserDataExport>, Status> {
+    let export = tokio::task::spawn_blocking(move || {
+        let repo = state.users.as_ref();
+        let personal_data = state.personal_data.as_ref();
+        let mfa = state.mfa.as_ref();
+        let api_keys = state.api_keys.as_ref();
+        let audit_log = ClientAuditLog::new(state.audit_log.as_ref(), client);
+        let use_case = ExportUserDataUseCase::new(
+            repo,
+            personal_data,
+            mfa,
+            api_keys,
+            state.token_service.as_ref(),
+            &audit_log,
+        );
+
+        use_case.execute(ExportUserDataCommand { token, user_id })
+    })
+    .await
+    .map_err(|e| Status::internal(format!("Task join error: {}", e)))?
+    .map_err(map_auth_error)?;
+
+    Ok(Response::new(UserDataExport {
+        data: DataExportArchive::new(&export).to_json(),
+    }))
+}
+
+/// Erase an account, `None` meaning the caller's own (shared with AdminUserService)
+pub(crate) async fn delete_account(
+    state: Arc<AppState>,
+    client: SessionClient,
+    token: String,
+    user_id: Option<uuid::Uuid>,
+    current_password: Option<String>,
+) -> Result<Response<DeleteAccountResponse>, Status> {
+    tokio::task::spawn_blocking(move || {
+        let repo = state.users.as_ref();
+        let personal_data = state.personal_data.as_ref();
+        let throttles = state.login_throttles.as_ref();
+        let audit_log = ClientAuditLog::new(state.audit_log.as_ref(), client);
+        let use_case = DeleteAccountUseCase::new(
+            repo,
+            personal_data,
+            state.password_hasher.as_ref(),
+            state.token_service.as_ref(),
+            state.sessions.as_ref(),
+            throttles,
+            state.lockout_policy,
+            &audit_log,
+        );
+
+        use_case.execute(DeleteAccountCommand {
+            token,
+            user_id,
+            current_password,
+        })
+    })
+    .await
+    .map_err(|e| Status::internal(format!("Task join error: {}", e)))?
+    .map_err(map_auth_error)?;
+
+    Ok(Response::new(DeleteAccountResponse {}))
+}
+
+/// Render a completed login
+fn login_response(result: LoginUserResult) -> LoginResponse {
+    LoginResponse {
+        token: result.token,
+        user_id: result.user_id.to_string(),
+        email: result.email,
+        display_name: result.display_name,
+        refresh_token: result.refresh_token,
+        ..Default::default()
+    }
+}
+
+/// Render the outcome of a first factor: tokens, or an MFA challenge
+fn login_outcome_response(outcome: LoginOutcome) -> LoginResponse {
+    match outcome {
+        LoginOutcome::Authenticated(result) => login_response(result),
+        LoginOutcome::MfaRequired {
+            challenge_token,
+            expires_in,
+        } => LoginResponse {
+            mfa_required: true,
+            challenge_token,
+            challenge_expires_in: expires_in,
+            ..Default::default()
+        },
+    }
+}
+
 #[tonic::async_trait]
 impl AuthService for AuthServiceGrpc {
     async fn register(
         &self,
         request: Request<RegisterRequest>,
     ) -> Result<Response<RegisterResponse>, Status> {
+        let client = self.session_client(&request);
         let req = request.into_inner();
-        let state = Arc::clone(&self.state);
+        let state = &self.state;
 
-        let result = tokio::task::spawn_blocking(move || {
-            let repo = DieselUserRepository::new(state.pool.clone());
-            let use_case = RegisterUserUseCase::new(&repo, state.password_hasher.as_ref());
+        let audit_log = ClientAuditLog::new(state.async_audit_log.as_ref(), client);
+        let use_case = RegisterUserUseCase::new(
+            state.async_users.as_ref(),
+            state.async_password_hasher.as_ref(),
+            &state.password_policy,
+            state.async_email_verification_tokens.as_ref(),
+            state.token_generator.as_ref(),
+            state.mailer.as_ref(),
+            state.email_verification_ttl,
+            &state.email_verification_url,
+            &audit_log,
+        );
 
-            let command = RegisterUserCommand {
-                email: req.email,
-                password: req.password,
-                display_name: req.display_name,
-            };
+        let command = RegisterUserCommand {
+            email: req.email,
+            password: req.password,
+            display_name: req.display_name,
+        };
 
-            use_case.execute(command)
-        })
-        .await
-        .map_err(|e| Status::internal(format!("Task join error: {}", e)))?
-        .map_err(map_auth_error)?;
+        let result = use_case.execute(command).await.map_err(map_auth_error)?;
 
         Ok(Response::new(RegisterResponse {
             user_id: result.user_id.to_string(),
@@ -86,20 +356,195 @@ impl AuthService for AuthServiceGrpc {
         &self,
         request: Request<LoginRequest>,
     ) -> Result<Response<LoginResponse>, Status> {
+        let client = self.session_client(&request);
+        let req = request.into_inner();
+        let state = &self.state;
+
+        let audit_log = ClientAuditLog::new(state.async_audit_log.as_ref(), client.clone());
+        let use_case = LoginUserUseCase::new(
+            state.async_users.as_ref(),
+            state.async_password_hasher.as_ref(),
+            state.token_service.as_ref(),
+            state.async_refresh_tokens.as_ref(),
+            state.async_sessions.as_ref(),
+            state.token_generator.as_ref(),
+            state.async_mfa.as_ref(),
+            state.async_mfa_challenges.as_ref(),
+            state.async_login_throttles.as_ref(),
+            state.lockout_policy,
+            state.refresh_token_ttl,
+            state.mfa_challenge_ttl,
+            state.require_verified_email,
+            &audit_log,
+        );
+
+        let command = LoginUserCommand {
+            email: req.email,
+            password: req.password,
+            client_ip: client.ip_address,
+            user_agent: client.user_agent,
+        };
+
+        let result = use_case.execute(command).await.map_err(map_auth_error)?;
+
+        Ok(Response::new(login_outcome_response(result)))
+    }
+
+    async fn verify_mfa(
+        &self,
+        request: Request<VerifyMfaRequest>,
+    ) -> Result<Response<LoginResponse>, Status> {
+        let client = self.session_client(&request);
+        let req = request.into_inner();
+        let state = &self.state;
+
+        let audit_log = ClientAuditLog::new(state.async_audit_log.as_ref(), client.clone());
+        let use_case = VerifyMfaUseCase::new(
+            state.async_users.as_ref(),
+            state.async_mfa_challenges.as_ref(),
+            state.async_mfa.as_ref(),
+            state.totp_service.as_ref(),
+            state.token_service.as_ref(),
+            state.async_refresh_tokens.as_ref(),
+            state.async_sessions.as_ref(),
+            state.token_generator.as_ref(),
+            state.async_login_throttles.as_ref(),
+            state.lockout_policy,
+            state.refresh_token_ttl,
+            &audit_log,
+        );
+
+        let command = VerifyMfaCommand {
+            challenge_token: req.challenge_token,
+            code: req.code,
+            client_ip: client.ip_address,
+            user_agent: client.user_agent,
+        };
+
+        let result = use_case.execute(command).await.map_err(map_auth_error)?;
+
+        Ok(Response::new(login_response(result)))
+    }
+
+    async fn request_magic_link(
+        &self,
+        request: Request<RequestMagicLinkRequest>,
+    ) -> Result<Response<RequestMagicLinkResponse>, Status> {
+        let client = self.session_client(&request);
+        let req = request.into_inner();
+        let state = Arc::clone(&self.state);
+
+        tokio::task::spawn_blocking(move || {
+            let repo = state.users.as_ref();
+            let links = state.magic_link_tokens.as_ref();
+            let audit_log = ClientAuditLog::new(state.audit_log.as_ref(), client);
+            let use_case = RequestMagicLinkUseCase::new(
+                repo,
+                links,
+                state.token_generator.as_ref(),
+                state.mailer.as_ref(),
+                &audit_log,
+                state.magic_link_ttl,
+                &state.magic_link_url,
+                state.magic_link_rate_limit,
+            );
+
+            let command = RequestMagicLinkCommand { email: req.email };
+
+            use_case.execute(command)
+        })
+        .await
+        .map_err(|e| Status::internal(format!("Task join error: {}", e)))?
+        .map_err(map_auth_error)?;
+
+        Ok(Response::new(RequestMagicLinkResponse {}))
+    }
+
+    async fn consume_magic_link(
+        &self,
+        request: Request<ConsumeMagicLinkRequest>,
+    ) -> Result<Response<LoginResponse>, Status> {
+        let client = self.session_client(&request);
+        let req = request.into_inner();
+        let state = &self.state;
+
+        let audit_log = ClientAuditLog::new(state.async_audit_log.as_ref(), client.clone());
+        let use_case = ConsumeMagicLinkUseCase::new(
+            state.async_users.as_ref(),
+            state.async_magic_link_tokens.as_ref(),
+            state.token_service.as_ref(),
+            state.async_refresh_tokens.as_ref(),
+            state.async_sessions.as_ref(),
+            state.token_generator.as_ref(),
+            state.async_mfa.as_ref(),
+            state.async_mfa_challenges.as_ref(),
+            state.refresh_token_ttl,
+            state.mfa_challenge_ttl,
+            state.require_verified_email,
+            &audit_log,
+        );
+
+        let command = ConsumeMagicLinkCommand {
+            token: req.token,
+            client_ip: client.ip_address,
+            user_agent: client.user_agent,
+        };
+
+        let result = use_case.execute(command).await.map_err(map_auth_error)?;
+
+        Ok(Response::new(login_outcome_response(result)))
+    }
+
+    async fn enroll_totp(
+        &self,
+        request: Request<EnrollTotpRequest>,
+    ) -> Result<Response<EnrollTotpResponse>, Status> {
         let req = request.into_inner();
         let state = Arc::clone(&self.state);
 
         let result = tokio::task::spawn_blocking(move || {
-            let repo = DieselUserRepository::new(state.pool.clone());
-            let use_case = LoginUserUseCase::new(
-                &repo,
-                state.password_hasher.as_ref(),
+            let repo = state.users.as_ref();
+            let mfa = state.mfa.as_ref();
+            let use_case = EnrollTotpUseCase::new(
+                repo,
+                mfa,
+                state.totp_service.as_ref(),
                 state.token_service.as_ref(),
             );
 
-            let command = LoginUserCommand {
-                email: req.email,
-                password: req.password,
+            let command = EnrollTotpCommand { token: req.token };
+
+            use_case.execute(command)
+        })
+        .await
+        .map_err(|e| Status::internal(format!("Task join error: {}", e)))?
+        .map_err(map_auth_error)?;
+
+        Ok(Response::new(EnrollTotpResponse {
+            secret: result.secret,
+            otpauth_uri: result.otpauth_uri,
+        }))
+    }
+
+    async fn confirm_totp_enrollment(
+        &self,
+        request: Request<ConfirmTotpEnrollmentRequest>,
+    ) -> Result<Response<ConfirmTotpEnrollmentResponse>, Status> {
+        let req = request.into_inner();
+        let state = Arc::clone(&self.state);
+
+        let result = tokio::task::spawn_blocking(move || {
+            let mfa = state.mfa.as_ref();
+            let use_case = ConfirmTotpEnrollmentUseCase::new(
+                mfa,
+                state.totp_service.as_ref(),
+                state.token_service.as_ref(),
+                state.token_generator.as_ref(),
+            );
+
+            let command = ConfirmTotpEnrollmentCommand {
+                token: req.token,
+                code: req.code,
             };
 
             use_case.execute(command)
@@ -108,69 +553,672 @@ impl AuthService for AuthServiceGrpc {
         .map_err(|e| Status::internal(format!("Task join error: {}", e)))?
         .map_err(map_auth_error)?;
 
-        Ok(Response::new(LoginResponse {
+        Ok(Response::new(ConfirmTotpEnrollmentResponse {
+            recovery_codes: result.recovery_codes,
+        }))
+    }
+
+    async fn refresh(
+        &self,
+        request: Request<RefreshRequest>,
+    ) -> Result<Response<RefreshResponse>, Status> {
+        let req = request.into_inner();
+        let state = &self.state;
+
+        let use_case = RefreshSessionUseCase::new(
+            state.async_users.as_ref(),
+            state.async_refresh_tokens.as_ref(),
+            state.async_sessions.as_ref(),
+            state.async_organizations.as_ref(),
+            state.token_service.as_ref(),
+            state.organization_token_issuer.as_ref(),
+            state.token_generator.as_ref(),
+            state.refresh_token_ttl,
+        );
+
+        let command = RefreshSessionCommand {
+            refresh_token: req.refresh_token,
+        };
+
+        let result = use_case.execute(command).await.map_err(map_auth_error)?;
+
+        Ok(Response::new(RefreshResponse {
             token: result.token,
+            refresh_token: result.refresh_token,
+            user_id: result.user_id.to_string(),
+            organization_id: result.organization.map(|org| org.id.to_string()),
+            role: result.organization.map(|org| org.role.as_str().to_string()),
+        }))
+    }
+
+    async fn logout(
+        &self,
+        request: Request<LogoutRequest>,
+    ) -> Result<Response<LogoutResponse>, Status> {
+        let req = request.into_inner();
+        let state = Arc::clone(&self.state);
+
+        tokio::task::spawn_blocking(move || {
+            let refresh_tokens = state.refresh_tokens.as_ref();
+            let use_case = LogoutUserUseCase::new(
+                state.token_service.as_ref(),
+                refresh_tokens,
+                state.token_generator.as_ref(),
+            );
+
+            let command = LogoutUserCommand {
+                token: req.token,
+                refresh_token: req.refresh_token,
+            };
+
+            use_case.execute(command)
+        })
+        .await
+        .map_err(|e| Status::internal(format!("Task join error: {}", e)))?
+        .map_err(map_auth_error)?;
+
+        Ok(Response::new(LogoutResponse {}))
+    }
+
+    async fn request_password_reset(
+        &self,
+        request: Request<RequestPasswordResetRequest>,
+    ) -> Result<Response<RequestPasswordResetResponse>, Status> {
+        let req = request.into_inner();
+        let state = Arc::clone(&self.state);
+
+        tokio::task::spawn_blocking(move || {
+            let repo = state.users.as_ref();
+            let reset_tokens = state.password_reset_tokens.as_ref();
+            let use_case = RequestPasswordResetUseCase::new(
+                repo,
+                reset_tokens,
+                state.token_generator.as_ref(),
+                state.mailer.as_ref(),
+                state.password_reset_ttl,
+                &state.password_reset_url,
+            );
+
+            let command = RequestPasswordResetCommand { email: req.email };
+
+            use_case.execute(command)
+        })
+        .await
+        .map_err(|e| Status::internal(format!("Task join error: {}", e)))?
+        .map_err(map_auth_error)?;
+
+        Ok(Response::new(RequestPasswordResetResponse {}))
+    }
+
+    async fn confirm_password_reset(
+        &self,
+        request: Request<ConfirmPasswordResetRequest>,
+    ) -> Result<Response<ConfirmPasswordResetResponse>, Status> {
+        let client = self.session_client(&request);
+        let req = request.into_inner();
+        let state = Arc::clone(&self.state);
+
+        tokio::task::spawn_blocking(move || {
+            let repo = state.users.as_ref();
+            let reset_tokens = state.password_reset_tokens.as_ref();
+            let refresh_tokens = state.refresh_tokens.as_ref();
+            let audit_log = ClientAuditLog::new(state.audit_log.as_ref(), client);
+            let use_case = ConfirmPasswordResetUseCase::new(
+                repo,
+                reset_tokens,
+                refresh_tokens,
+                state.password_hasher.as_ref(),
+                &state.password_policy,
+                state.token_generator.as_ref(),
+                &audit_log,
+            );
+
+            let command = ConfirmPasswordResetCommand {
+                token: req.token,
+                new_password: req.new_password,
+            };
+
+            use_case.execute(command)
+        })
+        .await
+        .map_err(|e| Status::internal(format!("Task join error: {}", e)))?
+        .map_err(map_auth_error)?;
+
+        Ok(Response::new(ConfirmPasswordResetResponse {}))
+    }
+
+    async fn verify_email(
+        &self,
+        request: Request<VerifyEmailRequest>,
+    ) -> Result<Response<VerifyEmailResponse>, Status> {
+        let req = request.into_inner();
+        let state = Arc::clone(&self.state);
+
+        let result = tokio::task::spawn_blocking(move || {
+            let repo = state.users.as_ref();
+            let verification_tokens = state.email_verification_tokens.as_ref();
+            let use_case =
+                VerifyEmailUseCase::new(repo, verification_tokens, state.token_generator.as_ref());
+
+            let command = VerifyEmailCommand { token: req.token };
+
+            use_case.execute(command)
+        })
+        .await
+        .map_err(|e| Status::internal(format!("Task join error: {}", e)))?
+        .map_err(map_auth_error)?;
+
+        Ok(Response::new(VerifyEmailResponse {
             user_id: result.user_id.to_string(),
             email: result.email,
-            display_name: result.display_name,
         }))
     }
 
+    async fn resend_email_verification(
+        &self,
+        request: Request<ResendEmailVerificationRequest>,
+    ) -> Result<Response<ResendEmailVerificationResponse>, Status> {
+        let req = request.into_inner();
+        let state = Arc::clone(&self.state);
+
+        tokio::task::spawn_blocking(move || {
+            let repo = state.users.as_ref();
+            let verification_tokens = state.email_verification_tokens.as_ref();
+            let use_case = SendEmailVerificationUseCase::new(
+                repo,
+                verification_tokens,
+                state.token_generator.as_ref(),
+                state.mailer.as_ref(),
+                state.email_verification_ttl,
+                &state.email_verification_url,
+            );
+
+            let command = SendEmailVerificationCommand { email: req.email };
+
+            use_case.execute(command)
+        })
+        .await
+        .map_err(|e| Status::internal(format!("Task join error: {}", e)))?
+        .map_err(map_auth_error)?;
+
+        Ok(Response::new(ResendEmailVerificationResponse {}))
+    }
+
     async fn get_me(
         &self,
         request: Request<GetMeRequest>,
     ) -> Result<Response<GetMeResponse>, Status> {
+        let req = request.into_inner();
+        let state = &self.state;
+
+        // Validate token (may hit moka cache)
+        let token_data = state
+            .async_tokens
+            .validate_token(&req.token)
+            .await
+            .map_err(map_auth_error)?;
+
+        // Fetch user through the async repository
+        let user = GetCurrentUserUseCase::new(state.async_users.as_ref())
+            .execute(&token_data)
+            .await
+            .map_err(map_auth_error)?;
+
+        Ok(Response::new(me_response(&user)))
+    }
+
+    async fn change_password(
+        &self,
+        request: Request<ChangePasswordRequest>,
+    ) -> Result<Response<ChangePasswordResponse>, Status> {
+        let client = self.session_client(&request);
+        let req = request.into_inner();
+        let state = Arc::clone(&self.state);
+
+        tokio::task::spawn_blocking(move || {
+            let repo = state.users.as_ref();
+            let refresh_tokens = state.refresh_tokens.as_ref();
+            let throttles = state.login_throttles.as_ref();
+            let audit_log = ClientAuditLog::new(state.audit_log.as_ref(), client);
+            let use_case = ChangePasswordUseCase::new(
+                repo,
+                state.password_hasher.as_ref(),
+                &state.password_policy,
+                state.token_service.as_ref(),
+                refresh_tokens,
+                state.sessions.as_ref(),
+                throttles,
+                state.lockout_policy,
+                &audit_log,
+            );
+
+            let command = ChangePasswordCommand {
+                token: req.token,
+                current_password: req.current_password,
+                new_password: req.new_password,
+            };
+
+            use_case.execute(command)
+        })
+        .await
+        .map_err(|e| Status::internal(format!("Task join error: {}", e)))?
+        .map_err(map_auth_error)?;
+
+        Ok(Response::new(ChangePasswordResponse {}))
+    }
+
+    async fn change_email(
+        &self,
+        request: Request<ChangeEmailRequest>,
+    ) -> Result<Response<GetMeResponse>, Status> {
+        let client = self.session_client(&request);
+        let req = request.into_inner();
+        let state = Arc::clone(&self.state);
+
+        let user = tokio::task::spawn_blocking(move || {
+            let repo = state.users.as_ref();
+            let verification_tokens = state.email_verification_tokens.as_ref();
+            let throttles = state.login_throttles.as_ref();
+            let audit_log = ClientAuditLog::new(state.audit_log.as_ref(), client);
+            let use_case = ChangeEmailUseCase::new(
+                repo,
+                state.password_hasher.as_ref(),
+                state.token_service.as_ref(),
+                verification_tokens,
+                state.token_generator.as_ref(),
+                state.mailer.as_ref(),
+                state.sessions.as_ref(),
+                throttles,
+                state.lockout_policy,
+                state.email_verification_ttl,
+                &state.email_verification_url,
+                &audit_log,
+            );
+
+            let command = ChangeEmailCommand {
+                token: req.token,
+                current_password: req.current_password,
+                new_email: req.new_email,
+            };
+
+            use_case.execute(command)
+        })
+        .await
+        .map_err(|e| Status::internal(format!("Task join error: {}", e)))?
+        .map_err(map_auth_error)?;
+
+        Ok(Response::new(me_response(&user)))
+    }
+
+    async fn update_profile(
+        &self,
+        request: Request<UpdateProfileRequest>,
+    ) -> Result<Response<GetMeResponse>, Status> {
+        let client = self.session_client(&request);
+        let req = request.into_inner();
+        let state = Arc::clone(&self.state);
+
+        let user = tokio::task::spawn_blocking(move || {
+            let repo = state.users.as_ref();
+            let audit_log = ClientAuditLog::new(state.audit_log.as_ref(), client);
+            let use_case =
+                UpdateProfileUseCase::new(repo, state.token_service.as_ref(), &audit_log);
+
+            let command = UpdateProfileCommand {
+                token: req.token,
+                display_name: req.display_name,
+            };
+
+            use_case.execute(command)
+        })
+        .await
+        .map_err(|e| Status::internal(format!("Task join error: {}", e)))?
+        .map_err(map_auth_error)?;
+
+        Ok(Response::new(me_response(&user)))
+    }
+
+    async fn export_my_data(
+        &self,
+        request: Request<ExportMyDataRequest>,
+    ) -> Result<Response<UserDataExport>, Status> {
+        let client = self.session_client(&request);
+        let req = request.into_inner();
+        export_user_data(Arc::clone(&self.state), client, req.token, None).await
+    }
+
+    async fn delete_account(
+        &self,
+        request: Request<DeleteAccountRequest>,
+    ) -> Result<Response<DeleteAccountResponse>, Status> {
+        let client = self.session_client(&request);
+        let req = request.into_inner();
+        delete_account(
+            Arc::clone(&self.state),
+            client,
+            req.token,
+            None,
+            Some(req.current_password),
+        )
+        .await
+    }
+
+    async fn create_api_key(
+        &self,
+        request: Request<CreateApiKeyRequest>,
+    ) -> Result<Response<CreateApiKeyResponse>, Status> {
         let req = request.into_inner();
         let state = Arc::clone(&self.state);
 
         let result = tokio::task::spawn_blocking(move || {
-            // Validate token (may hit moka cache)
-            let token_data = state
-                .token_service
-                .validate_token(&req.token)
-                .map_err(map_auth_error)?;
+            let repo = state.users.as_ref();
+            let api_keys = state.api_keys.as_ref();
+            let use_case = CreateApiKeyUseCase::new(
+                repo,
+                api_keys,
+                state.token_generator.as_ref(),
+                state.token_service.as_ref(),
+            );
 
-            // Fetch user from database
-            let repo = DieselUserRepository::new(state.pool.clone());
-            let user = repo.find_by_id(token_data.user_id).map_err(map_auth_error)?;
+            let command = CreateApiKeyCommand {
+                token: req.token,
+                name: req.name,
+                scopes: req.scopes,
+                expires_in_days: req.expires_in_days,
+            };
 
-            Ok::<GetMeResponse, Status>(GetMeResponse {
-                user_id: user.id().as_uuid().to_string(),
-                email: user.email().as_str().to_string(),
-                display_name: user.display_name().map(String::from),
-                is_active: user.is_active(),
-            })
+            use_case.execute(command)
         })
         .await
-        .map_err(|e| Status::internal(format!("Task join error: {}", e)))??;
+        .map_err(|e| Status::internal(format!("Task join error: {}", e)))?
+        .map_err(map_auth_error)?;
 
-        Ok(Response::new(result))
+        Ok(Response::new(CreateApiKeyResponse {
+            key: result.key,
+            api_key: Some(api_key_message(&result.api_key)),
+        }))
+    }
+
+    async fn list_api_keys(
+        &self,
+        request: Request<ListApiKeysRequest>,
+    ) -> Result<Response<ListApiKeysResponse>, Status> {
+        let req = request.into_inner();
+        let state = Arc::clone(&self.state);
+
+        let keys = tokio::task::spawn_blocking(move || {
+            let api_keys = state.api_keys.as_ref();
+            let use_case = ListApiKeysUseCase::new(api_keys, state.token_service.as_ref());
+
+            use_case.execute(&req.token)
+        })
+        .await
+        .map_err(|e| Status::internal(format!("Task join error: {}", e)))?
+        .map_err(map_auth_error)?;
+
+        Ok(Response::new(ListApiKeysResponse {
+            api_keys: keys.iter().map(api_key_message).collect(),
+        }))
+    }
+
+    async fn revoke_api_key(
+        &self,
+        request: Request<RevokeApiKeyRequest>,
+    ) -> Result<Response<RevokeApiKeyResponse>, Status> {
+        let req = request.into_inner();
+        let key_id = uuid::Uuid::parse_str(&req.key_id)
+            .map_err(|_| Status::invalid_argument("Invalid API key ID"))?;
+        let state = Arc::clone(&self.state);
+
+        tokio::task::spawn_blocking(move || {
+            let api_keys = state.api_keys.as_ref();
+            let use_case = RevokeApiKeyUseCase::new(api_keys, state.token_service.as_ref());
+
+            let command = RevokeApiKeyCommand {
+                token: req.token,
+                key_id,
+            };
+
+            use_case.execute(command)
+        })
+        .await
+        .map_err(|e| Status::internal(format!("Task join error: {}", e)))?
+        .map_err(map_auth_error)?;
+
+        Ok(Response::new(RevokeApiKeyResponse {}))
+    }
+
+    async fn list_sessions(
+        &self,
+        request: Request<ListSessionsRequest>,
+    ) -> Result<Response<ListSessionsResponse>, Status> {
+        let req = request.into_inner();
+        let state = Arc::clone(&self.state);
+
+        let result = tokio::task::spawn_blocking(move || {
+            let sessions = state.sessions.as_ref();
+            let use_case = ListSessionsUseCase::new(sessions, state.token_service.as_ref());
+
+            use_case.execute(&req.token)
+        })
+        .await
+        .map_err(|e| Status::internal(format!("Task join error: {}", e)))?
+        .map_err(map_auth_error)?;
+
+        Ok(Response::new(ListSessionsResponse {
+            sessions: result
+                .sessions
+                .iter()
+                .map(|session| session_message(session, result.current_session_id))
+                .collect(),
+        }))
+    }
+
+    async fn revoke_session(
+        &self,
+        request: Request<RevokeSessionRequest>,
+    ) -> Result<Response<RevokeSessionResponse>, Status> {
+        let req = request.into_inner();
+        let session_id = uuid::Uuid::parse_str(&req.session_id)
+            .map_err(|_| Status::invalid_argument("Invalid session ID"))?;
+        let state = Arc::clone(&self.state);
+
+        tokio::task::spawn_blocking(move || {
+            let sessions = state.sessions.as_ref();
+            let refresh_tokens = state.refresh_tokens.as_ref();
+            let use_case =
+                RevokeSessionUseCase::new(sessions, refresh_tokens, state.token_service.as_ref());
+
+            let command = RevokeSessionCommand {
+                token: req.token,
+                session_id,
+            };
+
+            use_case.execute(command)
+        })
+        .await
+        .map_err(|e| Status::internal(format!("Task join error: {}", e)))?
+        .map_err(map_auth_error)?;
+
+        Ok(Response::new(RevokeSessionResponse {}))
+    }
+
+    async fn revoke_other_sessions(
+        &self,
+        request: Request<RevokeOtherSessionsRequest>,
+    ) -> Result<Response<RevokeOtherSessionsResponse>, Status> {
+        let req = request.into_inner();
+        let state = Arc::clone(&self.state);
+
+        let revoked_count = tokio::task::spawn_blocking(move || {
+            let sessions = state.sessions.as_ref();
+            let refresh_tokens = state.refresh_tokens.as_ref();
+            let use_case = RevokeOtherSessionsUseCase::new(
+                sessions,
+                refresh_tokens,
+                state.token_service.as_ref(),
+            );
+
+            use_case.execute(&req.token)
+        })
+        .await
+        .map_err(|e| Status::internal(format!("Task join error: {}", e)))?
+        .map_err(map_auth_error)?;
+
+        Ok(Response::new(RevokeOtherSessionsResponse {
+            revoked_count: u32::try_from(revoked_count).unwrap_or(u32::MAX),
+        }))
     }
 
     async fn validate_token(
         &self,
         request: Request<ValidateTokenRequest>,
     ) -> Result<Response<ValidateTokenResponse>, Status> {
+        let client = self.session_client(&request);
         let req = request.into_inner();
         let state = Arc::clone(&self.state);
 
-        let result = tokio::task::spawn_blocking(move || {
-            match state.token_service.validate_token(&req.token) {
-                Ok(token_data) => ValidateTokenResponse {
-                    valid: true,
-                    user_id: token_data.user_id.to_string(),
-                    email: token_data.email,
-                },
-                Err(_) => ValidateTokenResponse {
+        // Answered with `valid: false` rather than an error, so the layer
+        // never sees these rejections
+        let result = match state.async_tokens.validate_token(&req.token).await {
+            Ok(token_data) => ValidateTokenResponse {
+                valid: true,
+                user_id: token_data.user_id.to_string(),
+                email: token_data.email.clone(),
+                roles: role_names(&token_data.roles),
+                permissions: permission_names(&token_data.permissions()),
+                scopes: token_data
+                    .scopes
+                    .as_deref()
+                    .map(permission_names)
+                    .unwrap_or_default(),
+                principal: token_data.principal.as_str().to_string(),
+                impersonator_id: token_data.actor.map(|id| id.to_string()),
+                org_id: token_data.organization.map(|org| org.id.to_string()),
+                org_role: token_data
+                    .organization
+                    .map(|org| org.role.as_str().to_string()),
+            },
+            Err(err) => {
+                tokio::task::spawn_blocking(move || {
+                    state.token_rejections.record(&client, &err);
+                });
+                ValidateTokenResponse {
                     valid: false,
                     user_id: String::new(),
                     email: String::new(),
-                },
+                    roles: Vec::new(),
+                    permissions: Vec::new(),
+                    scopes: Vec::new(),
+                    principal: String::new(),
+                    impersonator_id: None,
+                    org_id: None,
+                    org_role: None,
+                }
             }
+        };
+
+        Ok(Response::new(result))
+    }
+
+    async fn assign_role(
+        &self,
+        request: Request<AssignRoleRequest>,
+    ) -> Result<Response<UserRolesResponse>, Status> {
+        let client = self.session_client(&request);
+        let req = request.into_inner();
+        let user_id = uuid::Uuid::parse_str(&req.user_id)
+            .map_err(|_| Status::invalid_argument("Invalid user ID"))?;
+        let state = Arc::clone(&self.state);
+
+        let result = tokio::task::spawn_blocking(move || {
+            let repo = state.users.as_ref();
+            let audit_log = ClientAuditLog::new(state.audit_log.as_ref(), client);
+            let use_case = AssignRoleUseCase::new(repo, state.token_service.as_ref(), &audit_log);
+
+            let command = AssignRoleCommand {
+                token: req.token,
+                user_id,
+                role: req.role,
+            };
+
+            use_case.execute(command)
         })
         .await
-        .map_err(|e| Status::internal(format!("Task join error: {}", e)))?;
+        .map_err(|e| Status::internal(format!("Task join error: {}", e)))?
+        .map_err(map_auth_error)?;
 
-        Ok(Response::new(result))
+        Ok(Response::new(UserRolesResponse {
+            user_id: result.user_id.to_string(),
+            roles: role_names(&result.roles),
+        }))
+    }
+
+    async fn revoke_role(
+        &self,
+        request: Request<RevokeRoleRequest>,
+    ) -> Result<Response<UserRolesResponse>, Status> {
+        let client = self.session_client(&request);
+        let req = request.into_inner();
+        let user_id = uuid::Uuid::parse_str(&req.user_id)
+            .map_err(|_| Status::invalid_argument("Invalid user ID"))?;
+        let state = Arc::clone(&self.state);
+
+        let result = tokio::task::spawn_blocking(move || {
+            let repo = state.users.as_ref();
+            let audit_log = ClientAuditLog::new(state.audit_log.as_ref(), client);
+            let use_case = RevokeRoleUseCase::new(repo, state.token_service.as_ref(), &audit_log);
+
+            let command = RevokeRoleCommand {
+                token: req.token,
+                user_id,
+                role: req.role,
+            };
+
+            use_case.execute(command)
+        })
+        .await
+        .map_err(|e| Status::internal(format!("Task join error: {}", e)))?
+        .map_err(map_auth_error)?;
+
+        Ok(Response::new(UserRolesResponse {
+            user_id: result.user_id.to_string(),
+            roles: role_names(&result.roles),
+        }))
+    }
+
+    async fn unlock_account(
+        &self,
+        request: Request<UnlockAccountRequest>,
+    ) -> Result<Response<UnlockAccountResponse>, Status> {
+        let client = self.session_client(&request);
+        let req = request.into_inner();
+        let user_id = uuid::Uuid::parse_str(&req.user_id)
+            .map_err(|_| Status::invalid_argument("Invalid user ID"))?;
+        let state = Arc::clone(&self.state);
+
+        tokio::task::spawn_blocking(move || {
+            let repo = state.users.as_ref();
+            let throttles = state.login_throttles.as_ref();
+            let audit_log = ClientAuditLog::new(state.audit_log.as_ref(), client);
+            let use_case = UnlockAccountUseCase::new(
+                repo,
+                throttles,
+                state.token_service.as_ref(),
+                &audit_log,
+            );
+
+            let command = UnlockAccountCommand {
+                token: req.token,
+                user_id,
+            };
+
+            use_case.execute(command)
+        })
+        .await
+        .map_err(|e| Status::internal(format!("Task join error: {}", e)))?
+        .map_err(map_auth_error)?;
+
+        Ok(Response::new(UnlockAccountResponse {}))
     }
 }
@@ -0,0 +1,108 @@
+//! Tower layer auditing the tokens the gRPC services reject
+//!
+//! Errors leave the services as plain `Status` values, so rejections are
+//! told apart by the `token-error` metadata `map_auth_error` attaches and
+//! recorded with the client of the call.
+
+use std::sync::Arc;
+
+use tonic::codegen::http::{Request, Response};
+use tonic::codegen::{BoxFuture, Context, Poll, Service};
+use tonic::transport::server::TcpConnectInfo;
+use tower::Layer;
+
+use crate::domain::session::SessionClient;
+use crate::interface::client_ip::{client_ip, client_user_agent};
+use crate::interface::token_audit::token_error;
+use crate::AppState;
+
+/// Metadata key carrying the stable code of a token rejection
+pub const TOKEN_ERROR_METADATA: &str = "token-error";
+
+/// Wraps the gRPC services with [`TokenAuditService`]
+#[derive(Clone)]
+pub struct TokenAuditLayer {
+    state: Arc<AppState>,
+}
+
+impl TokenAuditLayer {
+    /// Report rejections to the audit of `state`
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+}
+
+impl<S> Layer<S> for TokenAuditLayer {
+    type Service = TokenAuditService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TokenAuditService {
+            inner,
+            state: Arc::clone(&self.state),
+        }
+    }
+}
+
+/// Records calls answered with a token rejection
+#[derive(Clone)]
+pub struct TokenAuditService<S> {
+    inner: S,
+    state: Arc<AppState>,
+}
+
+impl<S, B, ResBody> Service<Request<B>> for TokenAuditService<S>
+where
+    S: Service<Request<B>, Response = Response<ResBody>>,
+    S::Future: Send + 'static,
+    S::Error: Send + 'static,
+    ResBody: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<S::Response, S::Error>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<B>) -> Self::Future {
+        let client = request_client(&self.state, &request);
+        let state = Arc::clone(&self.state);
+        let response = self.inner.call(request);
+
+        Box::pin(async move {
+            let response = response.await?;
+            let rejection = response
+                .headers()
+                .get(TOKEN_ERROR_METADATA)
+                .and_then(|value| value.to_str().ok())
+                .and_then(token_error);
+            if let Some(error) = rejection {
+                tokio::task::spawn_blocking(move || state.token_rejections.record(&client, &error));
+            }
+            Ok(response)
+        })
+    }
+}
+
+/// Address and user agent of the client of a call, as `session_client`
+/// reads them from a `tonic::Request`
+fn request_client<B>(state: &AppState, request: &Request<B>) -> SessionClient {
+    let header = |name: &str| request.headers().get(name).and_then(|v| v.to_str().ok());
+    SessionClient {
+        user_agent: client_user_agent(
+            header("x-forwarded-user-agent"),
+            header("user-agent"),
+            state.trust_forwarded_for,
+        ),
+        ip_address: client_ip(
+            header("x-forwarded-for"),
+            request
+                .extensions()
+                .get::<TcpConnectInfo>()
+                .and_then(TcpConnectInfo::remote_addr)
+                .map(|addr| addr.ip()),
+            state.trust_forwarded_for,
+        ),
+    }
+}
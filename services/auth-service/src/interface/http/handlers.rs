@@ -1,22 +1,75 @@
 //! HTTP request handlers for auth endpoints
+//!
+//! Diesel and Argon2 operations block, so they are wrapped in
+//! `spawn_blocking` to avoid stalling the Tokio async runtime. Registration,
+//! the logins (password, MFA, magic link and identity provider), refresh and
+//! `/auth/me` await the async stores of [`AppState`] instead.
 
+use std::net::SocketAddr;
 use std::sync::Arc;
 
 use axum::{
-    extract::State,
-    http::{header, HeaderMap, StatusCode},
-    response::IntoResponse,
+    extract::{ConnectInfo, Path, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
+use chrono::{DateTime, Utc};
+use jsonwebtoken::jwk::JwkSet;
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 use crate::application::commands::{
-    login_user::{LoginUserCommand, LoginUserUseCase},
+    accept_invitation::{AcceptInvitationCommand, AcceptInvitationUseCase},
+    assign_role::{AssignRoleCommand, AssignRoleUseCase},
+    change_email::{ChangeEmailCommand, ChangeEmailUseCase},
+    change_password::{ChangePasswordCommand, ChangePasswordUseCase},
+    complete_federated_login::{CompleteFederatedLoginCommand, CompleteFederatedLoginUseCase},
+    confirm_password_reset::{ConfirmPasswordResetCommand, ConfirmPasswordResetUseCase},
+    confirm_totp_enrollment::{ConfirmTotpEnrollmentCommand, ConfirmTotpEnrollmentUseCase},
+    consume_magic_link::{ConsumeMagicLinkCommand, ConsumeMagicLinkUseCase},
+    create_api_key::{CreateApiKeyCommand, CreateApiKeyUseCase},
+    create_organization::{CreateOrganizationCommand, CreateOrganizationUseCase},
+    delete_account::{DeleteAccountCommand, DeleteAccountUseCase},
+    enroll_totp::{EnrollTotpCommand, EnrollTotpUseCase},
+    export_user_data::{ExportUserDataCommand, ExportUserDataUseCase},
+    get_current_user::GetCurrentUserUseCase,
+    impersonate_user::{ImpersonateUserCommand, ImpersonateUserUseCase},
+    invite_member::{InviteMemberCommand, InviteMemberUseCase},
+    list_api_keys::ListApiKeysUseCase,
+    list_oauth_clients::ListOAuthClientsUseCase,
+    list_organizations::ListOrganizationsUseCase,
+    list_sessions::ListSessionsUseCase,
+    login_user::{LoginOutcome, LoginUserCommand, LoginUserResult, LoginUserUseCase},
+    logout_user::{LogoutUserCommand, LogoutUserUseCase},
+    refresh_session::{RefreshSessionCommand, RefreshSessionUseCase},
+    register_oauth_client::{RegisterOAuthClientCommand, RegisterOAuthClientUseCase},
     register_user::{RegisterUserCommand, RegisterUserUseCase},
+    request_magic_link::{RequestMagicLinkCommand, RequestMagicLinkUseCase},
+    request_password_reset::{RequestPasswordResetCommand, RequestPasswordResetUseCase},
+    revoke_api_key::{RevokeApiKeyCommand, RevokeApiKeyUseCase},
+    revoke_oauth_client::{RevokeOAuthClientCommand, RevokeOAuthClientUseCase},
+    revoke_other_sessions::RevokeOtherSessionsUseCase,
+    revoke_role::{RevokeRoleCommand, RevokeRoleUseCase},
+    revoke_session::{RevokeSessionCommand, RevokeSessionUseCase},
+    send_email_verification::{SendEmailVerificationCommand, SendEmailVerificationUseCase},
+    start_federated_login::{StartFederatedLoginCommand, StartFederatedLoginUseCase},
+    switch_organization::{SwitchOrganizationCommand, SwitchOrganizationUseCase},
+    unlock_account::{UnlockAccountCommand, UnlockAccountUseCase},
+    update_profile::{UpdateProfileCommand, UpdateProfileUseCase},
+    verify_email::{VerifyEmailCommand, VerifyEmailUseCase},
+    verify_mfa::{VerifyMfaCommand, VerifyMfaUseCase},
 };
-use crate::domain::auth::UserRepository;
+use crate::domain::api_key::ApiKey;
+use crate::domain::audit::ClientAuditLog;
 use crate::domain::error::AuthError;
-use crate::infrastructure::db::user_repository_diesel::DieselUserRepository;
+use crate::domain::oauth_client::OAuthClient;
+use crate::domain::organization::{Membership, Organization};
+use crate::domain::role::{permissions_for, Permission, Role};
+use crate::domain::session::{Session, SessionClient};
+use crate::domain::user::User;
+use crate::interface::client_ip::{client_ip, client_user_agent};
+use crate::interface::data_export::DataExportArchive;
 use crate::AppState;
 
 // ============================================================================
@@ -38,6 +91,153 @@ pub struct LoginRequest {
     pub password: String,
 }
 
+/// Request body for refreshing a session
+#[derive(Debug, Deserialize)]
+pub struct RefreshRequest {
+    pub refresh_token: String,
+}
+
+/// Optional request body for logout
+#[derive(Debug, Default, Deserialize)]
+pub struct LogoutRequest {
+    /// Refresh token whose family should be revoked as well
+    pub refresh_token: Option<String>,
+}
+
+/// Request body for starting a password reset
+#[derive(Debug, Deserialize)]
+pub struct PasswordResetRequest {
+    pub email: String,
+}
+
+/// Request body for completing a password reset
+#[derive(Debug, Deserialize)]
+pub struct ConfirmPasswordResetRequest {
+    /// Secret from the reset email
+    pub token: String,
+    pub new_password: String,
+}
+
+/// Request body for verifying an email address
+#[derive(Debug, Deserialize)]
+pub struct VerifyEmailRequest {
+    /// Secret from the verification email
+    pub token: String,
+}
+
+/// Request body for resending a verification email
+#[derive(Debug, Deserialize)]
+pub struct ResendEmailVerificationRequest {
+    pub email: String,
+}
+
+/// Request body for updating the current user
+///
+/// Absent fields are left unchanged; an empty `display_name` clears it.
+/// Changing the email address or password requires `current_password`.
+#[derive(Debug, Deserialize)]
+pub struct UpdateMeRequest {
+    pub display_name: Option<String>,
+    pub email: Option<String>,
+    pub new_password: Option<String>,
+    pub current_password: Option<String>,
+}
+
+/// Request body for deleting the current user's account
+#[derive(Debug, Deserialize)]
+pub struct DeleteMeRequest {
+    pub current_password: String,
+}
+
+/// Request body for completing a two-factor login
+#[derive(Debug, Deserialize)]
+pub struct VerifyMfaRequest {
+    /// Challenge token returned by login
+    pub challenge_token: String,
+    /// TOTP code or recovery code
+    pub code: String,
+}
+
+/// Request body for confirming TOTP enrollment
+#[derive(Debug, Deserialize)]
+pub struct ConfirmTotpEnrollmentRequest {
+    pub code: String,
+}
+
+/// Request body for creating an API key
+#[derive(Debug, Deserialize)]
+pub struct CreateApiKeyRequest {
+    pub name: String,
+    /// Permissions the key is limited to, e.g. `tickets:purchase`
+    pub scopes: Vec<String>,
+    /// Days until the key expires; absent or 0 for a key that never expires
+    #[serde(default)]
+    pub expires_in_days: u32,
+}
+
+/// Request body for registering an OAuth client
+#[derive(Debug, Deserialize)]
+pub struct RegisterOAuthClientRequest {
+    pub name: String,
+    /// Permissions the client may request, e.g. `users:read`
+    pub scopes: Vec<String>,
+}
+
+/// Request to start signing in at an identity provider
+#[derive(Debug, Deserialize)]
+pub struct StartFederatedLoginRequest {
+    pub provider: String,
+}
+
+/// Request to complete a sign-in at an identity provider
+#[derive(Debug, Deserialize)]
+pub struct FederatedLoginCallbackRequest {
+    pub state: String,
+    pub code: String,
+}
+
+/// Request body for mailing a magic link
+#[derive(Debug, Deserialize)]
+pub struct MagicLinkRequest {
+    pub email: String,
+}
+
+/// Request body for logging in with a magic link
+#[derive(Debug, Deserialize)]
+pub struct ConsumeMagicLinkRequest {
+    /// Secret from the magic link email
+    pub token: String,
+}
+
+/// Request body for creating an organization
+#[derive(Debug, Deserialize)]
+pub struct CreateOrganizationRequest {
+    pub name: String,
+}
+
+/// Request body for inviting a member to an organization
+#[derive(Debug, Deserialize)]
+pub struct InviteMemberRequest {
+    pub email: String,
+    /// `member` or `admin`
+    pub role: String,
+}
+
+/// Request body for accepting an organization invitation
+#[derive(Debug, Deserialize)]
+pub struct AcceptInvitationRequest {
+    /// Secret from the invitation email
+    pub token: String,
+}
+
+/// Request body for switching organization
+#[derive(Debug, Deserialize)]
+pub struct SwitchOrganizationRequest {
+    /// Organization to act for; absent or null for the personal context
+    #[serde(default)]
+    pub organization_id: Option<Uuid>,
+}
+
 /// Response for successful registration
 #[derive(Debug, Serialize)]
 pub struct RegisterResponse {
@@ -50,11 +250,67 @@ pub struct RegisterResponse {
 #[derive(Debug, Serialize)]
 pub struct LoginResponse {
     pub token: String,
+    pub refresh_token: String,
     pub user_id: String,
     pub email: String,
     pub display_name: Option<String>,
 }
 
+/// Response for a correct password on an account with two-factor authentication
+#[derive(Debug, Serialize)]
+pub struct MfaChallengeResponse {
+    pub mfa_required: bool,
+    pub challenge_token: String,
+    pub expires_in: i64,
+}
+
+/// Identity provider offered on the login page
+#[derive(Debug, Serialize)]
+pub struct IdentityProviderResponse {
+    pub id: String,
+    pub name: String,
+}
+
+/// Response for a started federated login
+#[derive(Debug, Serialize)]
+pub struct StartFederatedLoginResponse {
+    pub authorization_url: String,
+    pub state: String,
+    pub expires_in: i64,
+}
+
+/// Response for started TOTP enrollment
+#[derive(Debug, Serialize)]
+pub struct EnrollTotpResponse {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+/// Response for confirmed TOTP enrollment
+#[derive(Debug, Serialize)]
+pub struct ConfirmTotpEnrollmentResponse {
+    pub recovery_codes: Vec<String>,
+}
+
+/// Response for successful refresh
+#[derive(Debug, Serialize)]
+pub struct RefreshResponse {
+    pub token: String,
+    pub refresh_token: String,
+    pub user_id: String,
+    /// Organization the session switched to, if the user is still a member
+    pub organization_id: Option<String>,
+    pub organization_role: Option<String>,
+}
+
+/// Response for a successful email verification
+#[derive(Debug, Serialize)]
+pub struct VerifyEmailResponse {
+    pub user_id: String,
+    pub email: String,
+    pub email_verified: bool,
+}
+
 /// Response for current user info
 #[derive(Debug, Serialize)]
 pub struct MeResponse {
@@ -62,6 +318,118 @@ pub struct MeResponse {
     pub email: String,
     pub display_name: Option<String>,
     pub is_active: bool,
+    pub email_verified: bool,
+    pub roles: Vec<String>,
+    pub permissions: Vec<String>,
+    /// Administrator acting as the user; only present for impersonation tokens
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub impersonator_id: Option<String>,
+    /// Organization the token acts for; only present after switching to one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub organization_id: Option<String>,
+    /// Role in that organization
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub organization_role: Option<String>,
+}
+
+/// Response describing an API key, without the key itself
+#[derive(Debug, Serialize)]
+pub struct ApiKeyResponse {
+    pub key_id: String,
+    pub name: String,
+    /// Start of the key, to tell keys apart
+    pub prefix: String,
+    pub scopes: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Response for a created API key; the key is not shown again
+#[derive(Debug, Serialize)]
+pub struct CreateApiKeyResponse {
+    pub key: String,
+    #[serde(flatten)]
+    pub api_key: ApiKeyResponse,
+}
+
+/// Response describing a session, i.e. a device the user is logged in on
+#[derive(Debug, Serialize)]
+pub struct SessionResponse {
+    pub session_id: String,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    /// Whether this is the session making the request
+    pub current: bool,
+}
+
+/// Response for revoking all sessions but the current one
+#[derive(Debug, Serialize)]
+pub struct RevokeOtherSessionsResponse {
+    pub revoked_count: usize,
+}
+
+/// Response describing an OAuth client, without its secret
+#[derive(Debug, Serialize)]
+pub struct OAuthClientResponse {
+    pub client_id: String,
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Response for a registered OAuth client; the secret is not shown again
+#[derive(Debug, Serialize)]
+pub struct RegisterOAuthClientResponse {
+    pub client_secret: String,
+    #[serde(flatten)]
+    pub client: OAuthClientResponse,
+}
+
+/// Response for an impersonation token
+#[derive(Debug, Serialize)]
+pub struct ImpersonateUserResponse {
+    pub token: String,
+    pub expires_in: i64,
+    pub user_id: String,
+    pub email: String,
+}
+
+/// Response describing an organization as seen by one of its members
+#[derive(Debug, Serialize)]
+pub struct OrganizationResponse {
+    pub organization_id: String,
+    pub name: String,
+    /// The caller's role: `member`, `admin` or `owner`
+    pub role: String,
+    pub created_at: DateTime<Utc>,
+    /// When the caller became a member
+    pub joined_at: DateTime<Utc>,
+}
+
+/// Response for a sent invitation
+#[derive(Debug, Serialize)]
+pub struct InviteMemberResponse {
+    pub invitation_id: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Response for a token re-issued for another organization
+#[derive(Debug, Serialize)]
+pub struct SwitchOrganizationResponse {
+    pub token: String,
+    pub organization_id: Option<String>,
+    pub organization_role: Option<String>,
+}
+
+/// Response listing a user's roles after a change
+#[derive(Debug, Serialize)]
+pub struct UserRolesResponse {
+    pub user_id: String,
+    pub roles: Vec<String>,
 }
 
 /// Error response
@@ -69,6 +437,9 @@ pub struct MeResponse {
 pub struct ErrorResponse {
     pub error: String,
     pub message: String,
+    /// Identifiers of the password rules a rejected password breaks
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<String>,
 }
 
 // ============================================================================
@@ -83,47 +454,261 @@ impl IntoResponse for AuthError {
             AuthError::InvalidCredentials => (StatusCode::UNAUTHORIZED, "invalid_credentials"),
             AuthError::InvalidToken => (StatusCode::UNAUTHORIZED, "invalid_token"),
             AuthError::TokenExpired => (StatusCode::UNAUTHORIZED, "token_expired"),
+            AuthError::TokenRevoked => (StatusCode::UNAUTHORIZED, "token_revoked"),
+            AuthError::TokenReused => (StatusCode::UNAUTHORIZED, "token_reused"),
             AuthError::InvalidEmail => (StatusCode::BAD_REQUEST, "invalid_email"),
-            AuthError::WeakPassword => (StatusCode::BAD_REQUEST, "weak_password"),
+            AuthError::InvalidDisplayName => (StatusCode::BAD_REQUEST, "invalid_display_name"),
+            AuthError::WeakPassword(_) => (StatusCode::BAD_REQUEST, "weak_password"),
             AuthError::AccountInactive => (StatusCode::FORBIDDEN, "account_inactive"),
+            AuthError::AccountLocked { .. } => (StatusCode::TOO_MANY_REQUESTS, "account_locked"),
+            AuthError::EmailNotVerified => (StatusCode::FORBIDDEN, "email_not_verified"),
+            AuthError::RecentLoginRequired => (StatusCode::FORBIDDEN, "recent_login_required"),
+            AuthError::InvalidMfaCode => (StatusCode::UNAUTHORIZED, "invalid_mfa_code"),
+            AuthError::MfaAlreadyEnabled => (StatusCode::CONFLICT, "mfa_already_enabled"),
+            AuthError::MfaNotEnrolled => (StatusCode::BAD_REQUEST, "mfa_not_enrolled"),
+            AuthError::Forbidden => (StatusCode::FORBIDDEN, "forbidden"),
+            AuthError::CannotImpersonate => (StatusCode::FORBIDDEN, "cannot_impersonate"),
+            AuthError::InvalidRole => (StatusCode::BAD_REQUEST, "invalid_role"),
+            AuthError::InvalidCursor => (StatusCode::BAD_REQUEST, "invalid_cursor"),
+            AuthError::InvalidAuditAction => (StatusCode::BAD_REQUEST, "invalid_audit_action"),
+            AuthError::InvalidScope => (StatusCode::BAD_REQUEST, "invalid_scope"),
+            AuthError::InvalidApiKeyName => (StatusCode::BAD_REQUEST, "invalid_api_key_name"),
+            AuthError::ApiKeyNotFound => (StatusCode::NOT_FOUND, "api_key_not_found"),
+            AuthError::SessionNotFound => (StatusCode::NOT_FOUND, "session_not_found"),
+            AuthError::InvalidOrganizationName => {
+                (StatusCode::BAD_REQUEST, "invalid_organization_name")
+            }
+            AuthError::OrganizationNotFound => (StatusCode::NOT_FOUND, "organization_not_found"),
+            AuthError::InvalidInvitation => (StatusCode::BAD_REQUEST, "invalid_invitation"),
+            AuthError::InvalidClient => (StatusCode::UNAUTHORIZED, "invalid_client"),
+            AuthError::UnsupportedGrantType => (StatusCode::BAD_REQUEST, "unsupported_grant_type"),
+            AuthError::InvalidClientName => (StatusCode::BAD_REQUEST, "invalid_client_name"),
+            AuthError::OAuthClientNotFound => (StatusCode::NOT_FOUND, "oauth_client_not_found"),
+            AuthError::InvalidGrant => (StatusCode::BAD_REQUEST, "invalid_grant"),
+            AuthError::InvalidRequest(_) => (StatusCode::BAD_REQUEST, "invalid_request"),
+            AuthError::IdentityProviderNotFound => {
+                (StatusCode::NOT_FOUND, "identity_provider_not_found")
+            }
+            AuthError::IdentityProvider(_) => (StatusCode::BAD_GATEWAY, "identity_provider_error"),
             AuthError::Internal(_) => (StatusCode::INTERNAL_SERVER_ERROR, "internal_error"),
         };
 
+        let rules = match &self {
+            AuthError::WeakPassword(rules) => {
+                rules.iter().map(|r| r.as_str().to_string()).collect()
+            }
+            _ => Vec::new(),
+        };
         let body = ErrorResponse {
             error: error_type.to_string(),
             message: self.to_string(),
+            rules,
         };
 
-        (status, Json(body)).into_response()
+        let mut response = (status, Json(body)).into_response();
+        if let AuthError::AccountLocked { retry_after_secs } = self {
+            if let Ok(value) = HeaderValue::from_str(&retry_after_secs.to_string()) {
+                response.headers_mut().insert(header::RETRY_AFTER, value);
+            }
+        }
+        if let AuthError::InvalidClient = self {
+            response
+                .headers_mut()
+                .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Basic"));
+        }
+        // Left for middleware, such as the audit of rejected tokens
+        response.extensions_mut().insert(self);
+        response
+    }
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+/// Extract the bearer token from the Authorization header
+pub(super) fn bearer_token(headers: &HeaderMap) -> Result<String, AuthError> {
+    let auth_header = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .ok_or(AuthError::InvalidToken)?;
+
+    auth_header
+        .strip_prefix("Bearer ")
+        .map(String::from)
+        .ok_or(AuthError::InvalidToken)
+}
+
+/// Address and user agent of the client, recorded with new sessions and
+/// audit entries
+fn session_client(
+    state: &AppState,
+    peer: Option<ConnectInfo<SocketAddr>>,
+    headers: &HeaderMap,
+) -> SessionClient {
+    request_client(state.trust_forwarded_for, peer, headers)
+}
+
+/// Address and user agent of the client, taking forwarded headers only from
+/// a trusted proxy
+pub(super) fn request_client(
+    trust_forwarded_for: bool,
+    peer: Option<ConnectInfo<SocketAddr>>,
+    headers: &HeaderMap,
+) -> SessionClient {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    SessionClient {
+        user_agent: client_user_agent(
+            header("x-forwarded-user-agent"),
+            header(header::USER_AGENT.as_str()),
+            trust_forwarded_for,
+        ),
+        ip_address: client_ip(
+            header("x-forwarded-for"),
+            peer.map(|ConnectInfo(addr)| addr.ip()),
+            trust_forwarded_for,
+        ),
+    }
+}
+
+/// Render a completed login
+fn login_response(result: LoginUserResult) -> LoginResponse {
+    LoginResponse {
+        token: result.token,
+        refresh_token: result.refresh_token,
+        user_id: result.user_id.to_string(),
+        email: result.email,
+        display_name: result.display_name,
+    }
+}
+
+/// Render the outcome of a first factor: tokens, or an MFA challenge
+fn login_outcome_response(outcome: LoginOutcome) -> Response {
+    match outcome {
+        LoginOutcome::Authenticated(result) => Json(login_response(result)).into_response(),
+        LoginOutcome::MfaRequired {
+            challenge_token,
+            expires_in,
+        } => Json(MfaChallengeResponse {
+            mfa_required: true,
+            challenge_token,
+            expires_in,
+        })
+        .into_response(),
+    }
+}
+
+/// Render the current user
+fn me_response(user: &User) -> MeResponse {
+    MeResponse {
+        user_id: user.id().as_uuid().to_string(),
+        email: user.email().as_str().to_string(),
+        display_name: user.display_name().map(String::from),
+        is_active: user.is_active(),
+        email_verified: user.is_email_verified(),
+        roles: role_names(user.roles()),
+        permissions: permission_names(&permissions_for(user.roles())),
+        impersonator_id: None,
+        organization_id: None,
+        organization_role: None,
+    }
+}
+
+/// Render an API key without its secret
+fn api_key_response(key: &ApiKey) -> ApiKeyResponse {
+    ApiKeyResponse {
+        key_id: key.id().to_string(),
+        name: key.name().to_string(),
+        prefix: key.prefix().to_string(),
+        scopes: permission_names(key.scopes()),
+        created_at: key.created_at(),
+        last_used_at: key.last_used_at(),
+        expires_at: key.expires_at(),
+    }
+}
+
+/// Render a session, flagging the caller's own
+fn session_response(session: &Session, current_session_id: Option<Uuid>) -> SessionResponse {
+    SessionResponse {
+        session_id: session.id().to_string(),
+        user_agent: session.user_agent().map(String::from),
+        ip_address: session.ip_address().map(|ip| ip.to_string()),
+        created_at: session.created_at(),
+        last_seen_at: session.last_seen_at(),
+        expires_at: session.expires_at(),
+        current: Some(session.id()) == current_session_id,
+    }
+}
+
+/// Render an OAuth client without its secret
+fn oauth_client_response(client: &OAuthClient) -> OAuthClientResponse {
+    OAuthClientResponse {
+        client_id: client.client_id().to_string(),
+        name: client.name().to_string(),
+        scopes: permission_names(client.scopes()),
+        created_at: client.created_at(),
     }
 }
 
+/// Render an organization as seen by one of its members
+fn organization_response(
+    organization: &Organization,
+    membership: &Membership,
+) -> OrganizationResponse {
+    OrganizationResponse {
+        organization_id: organization.id().to_string(),
+        name: organization.name().to_string(),
+        role: membership.role().as_str().to_string(),
+        created_at: organization.created_at(),
+        joined_at: membership.created_at(),
+    }
+}
+
+/// Render roles by their stable identifiers
+fn role_names(roles: &[Role]) -> Vec<String> {
+    roles.iter().map(|r| r.as_str().to_string()).collect()
+}
+
+/// Render permissions by their stable identifiers
+fn permission_names(permissions: &[Permission]) -> Vec<String> {
+    permissions.iter().map(|p| p.as_str().to_string()).collect()
+}
+
 // ============================================================================
 // Handlers
 // ============================================================================
 
 /// POST /auth/register - Register a new user
 ///
-/// Diesel and Argon2 operations are wrapped in `spawn_blocking` to avoid
-/// blocking the Tokio async runtime.
+/// Mails a verification link to the new address.
 pub async fn register(
     State(state): State<Arc<AppState>>,
+    peer: Option<ConnectInfo<SocketAddr>>,
+    headers: HeaderMap,
     Json(body): Json<RegisterRequest>,
 ) -> Result<impl IntoResponse, AuthError> {
-    let result = tokio::task::spawn_blocking(move || {
-        let repo = DieselUserRepository::new(state.pool.clone());
-        let use_case = RegisterUserUseCase::new(&repo, state.password_hasher.as_ref());
+    let client = session_client(&state, peer, &headers);
 
-        let command = RegisterUserCommand {
-            email: body.email,
-            password: body.password,
-            display_name: body.display_name,
-        };
+    let audit_log = ClientAuditLog::new(state.async_audit_log.as_ref(), client);
+    let use_case = RegisterUserUseCase::new(
+        state.async_users.as_ref(),
+        state.async_password_hasher.as_ref(),
+        &state.password_policy,
+        state.async_email_verification_tokens.as_ref(),
+        state.token_generator.as_ref(),
+        state.mailer.as_ref(),
+        state.email_verification_ttl,
+        &state.email_verification_url,
+        &audit_log,
+    );
 
-        use_case.execute(command)
-    })
-    .await
-    .map_err(|e| AuthError::Internal(format!("Task join error: {}", e)))??;
+    let command = RegisterUserCommand {
+        email: body.email,
+        password: body.password,
+        display_name: body.display_name,
+    };
+
+    let result = use_case.execute(command).await?;
 
     let response = RegisterResponse {
         user_id: result.user_id.to_string(),
@@ -136,85 +721,1331 @@ pub async fn register(
 
 /// POST /auth/login - Authenticate user and return JWT
 ///
-/// Diesel and Argon2 operations are wrapped in `spawn_blocking`.
+/// Accounts with two-factor authentication get an MFA challenge instead,
+/// to be completed at `/auth/mfa/verify`. Repeated failures lock the email
+/// address and the client IP out for a while.
 pub async fn login(
     State(state): State<Arc<AppState>>,
+    peer: Option<ConnectInfo<SocketAddr>>,
+    headers: HeaderMap,
     Json(body): Json<LoginRequest>,
+) -> Result<Response, AuthError> {
+    let client = session_client(&state, peer, &headers);
+
+    let audit_log = ClientAuditLog::new(state.async_audit_log.as_ref(), client.clone());
+    let use_case = LoginUserUseCase::new(
+        state.async_users.as_ref(),
+        state.async_password_hasher.as_ref(),
+        state.token_service.as_ref(),
+        state.async_refresh_tokens.as_ref(),
+        state.async_sessions.as_ref(),
+        state.token_generator.as_ref(),
+        state.async_mfa.as_ref(),
+        state.async_mfa_challenges.as_ref(),
+        state.async_login_throttles.as_ref(),
+        state.lockout_policy,
+        state.refresh_token_ttl,
+        state.mfa_challenge_ttl,
+        state.require_verified_email,
+        &audit_log,
+    );
+
+    let command = LoginUserCommand {
+        email: body.email,
+        password: body.password,
+        client_ip: client.ip_address,
+        user_agent: client.user_agent,
+    };
+
+    let result = use_case.execute(command).await?;
+
+    Ok(login_outcome_response(result))
+}
+
+/// GET /auth/sso/providers - List the identity providers users may sign in with
+pub async fn list_identity_providers(
+    State(state): State<Arc<AppState>>,
+) -> Json<Vec<IdentityProviderResponse>> {
+    Json(
+        state
+            .identity_providers
+            .iter()
+            .map(|provider| IdentityProviderResponse {
+                id: provider.id.clone(),
+                name: provider.name.clone(),
+            })
+            .collect(),
+    )
+}
+
+/// POST /auth/sso/start - Start signing in at an identity provider
+///
+/// The login page sends the user to `authorization_url` and keeps `state`
+/// to compare with the one the provider returns.
+pub async fn start_federated_login(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<StartFederatedLoginRequest>,
 ) -> Result<impl IntoResponse, AuthError> {
-    let result = tokio::task::spawn_blocking(move || {
-        let repo = DieselUserRepository::new(state.pool.clone());
-        let use_case = LoginUserUseCase::new(
-            &repo,
-            state.password_hasher.as_ref(),
-            state.token_service.as_ref(),
-        );
+    let use_case = StartFederatedLoginUseCase::new(
+        &state.identity_providers,
+        state.federated_login_requests.as_ref(),
+        state.identity_provider_client.as_ref(),
+        state.token_generator.as_ref(),
+        &state.federated_login_callback_url,
+        state.federated_login_ttl,
+    );
 
-        let command = LoginUserCommand {
-            email: body.email,
-            password: body.password,
-        };
+    let result = use_case
+        .execute(StartFederatedLoginCommand {
+            provider: body.provider,
+        })
+        .await?;
 
-        use_case.execute(command)
-    })
-    .await
-    .map_err(|e| AuthError::Internal(format!("Task join error: {}", e)))??;
+    Ok(Json(StartFederatedLoginResponse {
+        authorization_url: result.authorization_url,
+        state: result.state,
+        expires_in: result.expires_in,
+    }))
+}
 
-    let response = LoginResponse {
-        token: result.token,
-        user_id: result.user_id.to_string(),
-        email: result.email,
-        display_name: result.display_name,
-    };
+/// POST /auth/sso/callback - Complete a sign-in at an identity provider
+///
+/// Takes the `state` and `code` the provider sent to the callback page and
+/// answers like `/auth/login`.
+pub async fn federated_login_callback(
+    State(state): State<Arc<AppState>>,
+    peer: Option<ConnectInfo<SocketAddr>>,
+    headers: HeaderMap,
+    Json(body): Json<FederatedLoginCallbackRequest>,
+) -> Result<Response, AuthError> {
+    let client = session_client(&state, peer, &headers);
 
-    Ok(Json(response))
+    let audit_log = ClientAuditLog::new(state.async_audit_log.as_ref(), client.clone());
+    let use_case = CompleteFederatedLoginUseCase::new(
+        &state.identity_providers,
+        state.federated_login_requests.as_ref(),
+        state.identity_provider_client.as_ref(),
+        state.async_users.as_ref(),
+        state.user_identities.as_ref(),
+        state.token_service.as_ref(),
+        state.async_refresh_tokens.as_ref(),
+        state.async_sessions.as_ref(),
+        state.token_generator.as_ref(),
+        state.async_mfa.as_ref(),
+        state.async_mfa_challenges.as_ref(),
+        &state.federated_login_callback_url,
+        state.refresh_token_ttl,
+        state.mfa_challenge_ttl,
+        state.require_verified_email,
+        &audit_log,
+    );
+
+    let result = use_case
+        .execute(CompleteFederatedLoginCommand {
+            state: body.state,
+            code: body.code,
+            client_ip: client.ip_address,
+            user_agent: client.user_agent,
+        })
+        .await?;
+
+    Ok(login_outcome_response(result))
 }
 
-/// GET /auth/me - Get current user info from JWT
+/// POST /auth/magic-link - Mail a single-use login link
 ///
-/// Diesel operations are wrapped in `spawn_blocking`.
-pub async fn me(
+/// Always answers 202 for well-formed addresses, so callers cannot tell
+/// whether an account exists or was rate limited.
+pub async fn request_magic_link(
     State(state): State<Arc<AppState>>,
+    peer: Option<ConnectInfo<SocketAddr>>,
     headers: HeaderMap,
+    Json(body): Json<MagicLinkRequest>,
 ) -> Result<impl IntoResponse, AuthError> {
-    // Extract token from Authorization header
-    let auth_header = headers
-        .get(header::AUTHORIZATION)
-        .and_then(|v| v.to_str().ok())
-        .ok_or(AuthError::InvalidToken)?;
+    let client = session_client(&state, peer, &headers);
 
-    let token = auth_header
-        .strip_prefix("Bearer ")
-        .ok_or(AuthError::InvalidToken)?
-        .to_string();
+    tokio::task::spawn_blocking(move || {
+        let repo = state.users.as_ref();
+        let links = state.magic_link_tokens.as_ref();
+        let audit_log = ClientAuditLog::new(state.audit_log.as_ref(), client);
+        let use_case = RequestMagicLinkUseCase::new(
+            repo,
+            links,
+            state.token_generator.as_ref(),
+            state.mailer.as_ref(),
+            &audit_log,
+            state.magic_link_ttl,
+            &state.magic_link_url,
+            state.magic_link_rate_limit,
+        );
 
-    let result = tokio::task::spawn_blocking(move || {
-        // Validate token (may hit moka cache)
-        let token_data = state.token_service.validate_token(&token)?;
-
-        // Get user from database
-        let repo = DieselUserRepository::new(state.pool.clone());
-        let user = repo.find_by_id(token_data.user_id)?;
-
-        Ok::<MeResponse, AuthError>(MeResponse {
-            user_id: user.id().as_uuid().to_string(),
-            email: user.email().as_str().to_string(),
-            display_name: user.display_name().map(String::from),
-            is_active: user.is_active(),
-        })
+        use_case.execute(RequestMagicLinkCommand { email: body.email })
     })
     .await
     .map_err(|e| AuthError::Internal(format!("Task join error: {}", e)))??;
 
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// POST /auth/magic-link/consume - Log in with a magic link
+///
+/// Answers like `/auth/login`.
+pub async fn consume_magic_link(
+    State(state): State<Arc<AppState>>,
+    peer: Option<ConnectInfo<SocketAddr>>,
+    headers: HeaderMap,
+    Json(body): Json<ConsumeMagicLinkRequest>,
+) -> Result<Response, AuthError> {
+    let client = session_client(&state, peer, &headers);
+
+    let audit_log = ClientAuditLog::new(state.async_audit_log.as_ref(), client.clone());
+    let use_case = ConsumeMagicLinkUseCase::new(
+        state.async_users.as_ref(),
+        state.async_magic_link_tokens.as_ref(),
+        state.token_service.as_ref(),
+        state.async_refresh_tokens.as_ref(),
+        state.async_sessions.as_ref(),
+        state.token_generator.as_ref(),
+        state.async_mfa.as_ref(),
+        state.async_mfa_challenges.as_ref(),
+        state.refresh_token_ttl,
+        state.mfa_challenge_ttl,
+        state.require_verified_email,
+        &audit_log,
+    );
+
+    let result = use_case
+        .execute(ConsumeMagicLinkCommand {
+            token: body.token,
+            client_ip: client.ip_address,
+            user_agent: client.user_agent,
+        })
+        .await?;
+
+    Ok(login_outcome_response(result))
+}
+
+/// POST /auth/mfa/verify - Complete a two-factor login
+///
+/// Accepts a TOTP code or an unused recovery code.
+pub async fn verify_mfa(
+    State(state): State<Arc<AppState>>,
+    peer: Option<ConnectInfo<SocketAddr>>,
+    headers: HeaderMap,
+    Json(body): Json<VerifyMfaRequest>,
+) -> Result<impl IntoResponse, AuthError> {
+    let client = session_client(&state, peer, &headers);
+
+    let audit_log = ClientAuditLog::new(state.async_audit_log.as_ref(), client.clone());
+    let use_case = VerifyMfaUseCase::new(
+        state.async_users.as_ref(),
+        state.async_mfa_challenges.as_ref(),
+        state.async_mfa.as_ref(),
+        state.totp_service.as_ref(),
+        state.token_service.as_ref(),
+        state.async_refresh_tokens.as_ref(),
+        state.async_sessions.as_ref(),
+        state.token_generator.as_ref(),
+        state.async_login_throttles.as_ref(),
+        state.lockout_policy,
+        state.refresh_token_ttl,
+        &audit_log,
+    );
+
+    let command = VerifyMfaCommand {
+        challenge_token: body.challenge_token,
+        code: body.code,
+        client_ip: client.ip_address,
+        user_agent: client.user_agent,
+    };
+
+    let result = use_case.execute(command).await?;
+
+    Ok(Json(login_response(result)))
+}
+
+/// POST /auth/mfa/totp - Start TOTP enrollment for the current user
+pub async fn enroll_totp(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, AuthError> {
+    let token = bearer_token(&headers)?;
+
+    let result = tokio::task::spawn_blocking(move || {
+        let repo = state.users.as_ref();
+        let mfa = state.mfa.as_ref();
+        let use_case = EnrollTotpUseCase::new(
+            repo,
+            mfa,
+            state.totp_service.as_ref(),
+            state.token_service.as_ref(),
+        );
+
+        use_case.execute(EnrollTotpCommand { token })
+    })
+    .await
+    .map_err(|e| AuthError::Internal(format!("Task join error: {}", e)))??;
+
+    let response = EnrollTotpResponse {
+        secret: result.secret,
+        otpauth_uri: result.otpauth_uri,
+    };
+
+    Ok(Json(response))
+}
+
+/// POST /auth/mfa/totp/confirm - Enable TOTP with a first code
+///
+/// Returns the recovery codes; they are not shown again.
+pub async fn confirm_totp_enrollment(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(body): Json<ConfirmTotpEnrollmentRequest>,
+) -> Result<impl IntoResponse, AuthError> {
+    let token = bearer_token(&headers)?;
+
+    let result = tokio::task::spawn_blocking(move || {
+        let mfa = state.mfa.as_ref();
+        let use_case = ConfirmTotpEnrollmentUseCase::new(
+            mfa,
+            state.totp_service.as_ref(),
+            state.token_service.as_ref(),
+            state.token_generator.as_ref(),
+        );
+
+        let command = ConfirmTotpEnrollmentCommand {
+            token,
+            code: body.code,
+        };
+
+        use_case.execute(command)
+    })
+    .await
+    .map_err(|e| AuthError::Internal(format!("Task join error: {}", e)))??;
+
+    let response = ConfirmTotpEnrollmentResponse {
+        recovery_codes: result.recovery_codes,
+    };
+
+    Ok(Json(response))
+}
+
+/// POST /auth/refresh - Exchange a refresh token for a new token pair
+///
+/// The presented refresh token is rotated; replaying it afterwards revokes
+/// the whole token family.
+pub async fn refresh(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<RefreshRequest>,
+) -> Result<impl IntoResponse, AuthError> {
+    let use_case = RefreshSessionUseCase::new(
+        state.async_users.as_ref(),
+        state.async_refresh_tokens.as_ref(),
+        state.async_sessions.as_ref(),
+        state.async_organizations.as_ref(),
+        state.token_service.as_ref(),
+        state.organization_token_issuer.as_ref(),
+        state.token_generator.as_ref(),
+        state.refresh_token_ttl,
+    );
+
+    let command = RefreshSessionCommand {
+        refresh_token: body.refresh_token,
+    };
+
+    let result = use_case.execute(command).await?;
+
+    let response = RefreshResponse {
+        token: result.token,
+        refresh_token: result.refresh_token,
+        user_id: result.user_id.to_string(),
+        organization_id: result.organization.map(|org| org.id.to_string()),
+        organization_role: result.organization.map(|org| org.role.as_str().to_string()),
+    };
+
+    Ok(Json(response))
+}
+
+/// POST /auth/logout - Revoke the current JWT and end its session
+///
+/// A refresh token passed along ends its session too. The revocation is
+/// honoured immediately by every replica, including tokens already sitting
+/// in the validation cache.
+pub async fn logout(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    body: Option<Json<LogoutRequest>>,
+) -> Result<impl IntoResponse, AuthError> {
+    let token = bearer_token(&headers)?;
+    let Json(body) = body.unwrap_or_default();
+
+    tokio::task::spawn_blocking(move || {
+        let refresh_tokens = state.refresh_tokens.as_ref();
+        let use_case = LogoutUserUseCase::new(
+            state.token_service.as_ref(),
+            refresh_tokens,
+            state.token_generator.as_ref(),
+        );
+
+        let command = LogoutUserCommand {
+            token,
+            refresh_token: body.refresh_token,
+        };
+
+        use_case.execute(command)
+    })
+    .await
+    .map_err(|e| AuthError::Internal(format!("Task join error: {}", e)))??;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /auth/password-reset - Mail a password reset link
+///
+/// Always answers 202 for well-formed addresses, so callers cannot tell
+/// whether an account exists.
+pub async fn request_password_reset(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<PasswordResetRequest>,
+) -> Result<impl IntoResponse, AuthError> {
+    tokio::task::spawn_blocking(move || {
+        let repo = state.users.as_ref();
+        let reset_tokens = state.password_reset_tokens.as_ref();
+        let use_case = RequestPasswordResetUseCase::new(
+            repo,
+            reset_tokens,
+            state.token_generator.as_ref(),
+            state.mailer.as_ref(),
+            state.password_reset_ttl,
+            &state.password_reset_url,
+        );
+
+        let command = RequestPasswordResetCommand { email: body.email };
+
+        use_case.execute(command)
+    })
+    .await
+    .map_err(|e| AuthError::Internal(format!("Task join error: {}", e)))??;
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// POST /auth/password-reset/confirm - Set a new password with a reset token
+///
+/// Ends every existing session of the user.
+pub async fn confirm_password_reset(
+    State(state): State<Arc<AppState>>,
+    peer: Option<ConnectInfo<SocketAddr>>,
+    headers: HeaderMap,
+    Json(body): Json<ConfirmPasswordResetRequest>,
+) -> Result<impl IntoResponse, AuthError> {
+    let client = session_client(&state, peer, &headers);
+
+    tokio::task::spawn_blocking(move || {
+        let repo = state.users.as_ref();
+        let reset_tokens = state.password_reset_tokens.as_ref();
+        let refresh_tokens = state.refresh_tokens.as_ref();
+        let audit_log = ClientAuditLog::new(state.audit_log.as_ref(), client);
+        let use_case = ConfirmPasswordResetUseCase::new(
+            repo,
+            reset_tokens,
+            refresh_tokens,
+            state.password_hasher.as_ref(),
+            &state.password_policy,
+            state.token_generator.as_ref(),
+            &audit_log,
+        );
+
+        let command = ConfirmPasswordResetCommand {
+            token: body.token,
+            new_password: body.new_password,
+        };
+
+        use_case.execute(command)
+    })
+    .await
+    .map_err(|e| AuthError::Internal(format!("Task join error: {}", e)))??;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /auth/verify-email - Confirm an email address with a verification token
+pub async fn verify_email(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<VerifyEmailRequest>,
+) -> Result<impl IntoResponse, AuthError> {
+    let result = tokio::task::spawn_blocking(move || {
+        let repo = state.users.as_ref();
+        let verification_tokens = state.email_verification_tokens.as_ref();
+        let use_case =
+            VerifyEmailUseCase::new(repo, verification_tokens, state.token_generator.as_ref());
+
+        let command = VerifyEmailCommand { token: body.token };
+
+        use_case.execute(command)
+    })
+    .await
+    .map_err(|e| AuthError::Internal(format!("Task join error: {}", e)))??;
+
+    let response = VerifyEmailResponse {
+        user_id: result.user_id.to_string(),
+        email: result.email,
+        email_verified: true,
+    };
+
+    Ok(Json(response))
+}
+
+/// POST /auth/verify-email/resend - Mail a new verification link
+///
+/// Always answers 202 for well-formed addresses, so callers cannot tell
+/// whether an account exists.
+pub async fn resend_email_verification(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<ResendEmailVerificationRequest>,
+) -> Result<impl IntoResponse, AuthError> {
+    tokio::task::spawn_blocking(move || {
+        let repo = state.users.as_ref();
+        let verification_tokens = state.email_verification_tokens.as_ref();
+        let use_case = SendEmailVerificationUseCase::new(
+            repo,
+            verification_tokens,
+            state.token_generator.as_ref(),
+            state.mailer.as_ref(),
+            state.email_verification_ttl,
+            &state.email_verification_url,
+        );
+
+        let command = SendEmailVerificationCommand { email: body.email };
+
+        use_case.execute(command)
+    })
+    .await
+    .map_err(|e| AuthError::Internal(format!("Task join error: {}", e)))??;
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// GET /auth/me - Get current user info from JWT
+///
+/// The token is validated and the user loaded without `spawn_blocking`,
+/// through the async token validator and user repository.
+pub async fn me(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, AuthError> {
+    let token = bearer_token(&headers)?;
+
+    // Validate token (may hit moka cache)
+    let token_data = state.async_tokens.validate_token(&token).await?;
+
+    let user = GetCurrentUserUseCase::new(state.async_users.as_ref())
+        .execute(&token_data)
+        .await?;
+
+    let mut response = me_response(&user);
+    response.impersonator_id = token_data.actor.map(|id| id.to_string());
+    response.organization_id = token_data.organization.map(|org| org.id.to_string());
+    response.organization_role = token_data
+        .organization
+        .map(|org| org.role.as_str().to_string());
+
+    Ok(Json(response))
+}
+
+/// PATCH /auth/me - Update the current user
+///
+/// Changes are applied in order: email address, password, profile. A new
+/// email address must be verified again; a new password ends every session,
+/// including the current one.
+pub async fn update_me(
+    State(state): State<Arc<AppState>>,
+    peer: Option<ConnectInfo<SocketAddr>>,
+    headers: HeaderMap,
+    Json(body): Json<UpdateMeRequest>,
+) -> Result<impl IntoResponse, AuthError> {
+    let token = bearer_token(&headers)?;
+    let client = session_client(&state, peer, &headers);
+
+    let result = tokio::task::spawn_blocking(move || {
+        let repo = state.users.as_ref();
+        let throttles = state.login_throttles.as_ref();
+        let audit_log = ClientAuditLog::new(state.audit_log.as_ref(), client);
+        let current_password = body.current_password.unwrap_or_default();
+
+        if let Some(new_email) = body.email {
+            let verification_tokens = state.email_verification_tokens.as_ref();
+            let use_case = ChangeEmailUseCase::new(
+                repo,
+                state.password_hasher.as_ref(),
+                state.token_service.as_ref(),
+                verification_tokens,
+                state.token_generator.as_ref(),
+                state.mailer.as_ref(),
+                state.sessions.as_ref(),
+                throttles,
+                state.lockout_policy,
+                state.email_verification_ttl,
+                &state.email_verification_url,
+                &audit_log,
+            );
+            use_case.execute(ChangeEmailCommand {
+                token: token.clone(),
+                current_password: current_password.clone(),
+                new_email,
+            })?;
+        }
+
+        if let Some(new_password) = body.new_password {
+            let refresh_tokens = state.refresh_tokens.as_ref();
+            let use_case = ChangePasswordUseCase::new(
+                repo,
+                state.password_hasher.as_ref(),
+                &state.password_policy,
+                state.token_service.as_ref(),
+                refresh_tokens,
+                state.sessions.as_ref(),
+                throttles,
+                state.lockout_policy,
+                &audit_log,
+            );
+            use_case.execute(ChangePasswordCommand {
+                token: token.clone(),
+                current_password,
+                new_password,
+            })?;
+        }
+
+        let user = match body.display_name {
+            Some(display_name) => {
+                UpdateProfileUseCase::new(repo, state.token_service.as_ref(), &audit_log).execute(
+                    UpdateProfileCommand {
+                        token,
+                        display_name: Some(display_name),
+                    },
+                )?
+            }
+            None => {
+                let token_data = state.token_service.validate_token(&token)?;
+                repo.find_by_id(token_data.user_id)?
+            }
+        };
+
+        Ok::<MeResponse, AuthError>(me_response(&user))
+    })
+    .await
+    .map_err(|e| AuthError::Internal(format!("Task join error: {}", e)))??;
+
+    Ok(Json(result))
+}
+
+/// GET /auth/me/export - Download everything stored about the current user
+pub async fn export_me(
+    State(state): State<Arc<AppState>>,
+    peer: Option<ConnectInfo<SocketAddr>>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, AuthError> {
+    let token = bearer_token(&headers)?;
+    let client = session_client(&state, peer, &headers);
+    Ok(Json(export_user_data(state, client, token, None).await?))
+}
+
+/// DELETE /auth/me - Erase the current user's account
+///
+/// Requires the current password. The access token used is revoked.
+pub async fn delete_me(
+    State(state): State<Arc<AppState>>,
+    peer: Option<ConnectInfo<SocketAddr>>,
+    headers: HeaderMap,
+    Json(body): Json<DeleteMeRequest>,
+) -> Result<impl IntoResponse, AuthError> {
+    let token = bearer_token(&headers)?;
+    let client = session_client(&state, peer, &headers);
+    delete_account(state, client, token, None, Some(body.current_password)).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// GET /admin/users/:user_id/export - Download everything stored about a user
+///
+/// Requires the `users:manage` permission.
+pub async fn export_user(
+    State(state): State<Arc<AppState>>,
+    peer: Option<ConnectInfo<SocketAddr>>,
+    headers: HeaderMap,
+    Path(user_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AuthError> {
+    let token = bearer_token(&headers)?;
+    let client = session_client(&state, peer, &headers);
+    Ok(Json(export_user_data(state, client, token, Some(user_id)).await?))
+}
+
+/// DELETE /admin/users/:user_id - Erase a user's account
+///
+/// Requires the `users:manage` permission.
+pub async fn delete_user(
+    State(state): State<Arc<AppState>>,
+    peer: Option<ConnectInfo<SocketAddr>>,
+    headers: HeaderMap,
+    Path(user_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AuthError> {
+    let token = bearer_token(&headers)?;
+    let client = session_client(&state, peer, &headers);
+    delete_account(state, client, token, Some(user_id), None).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Export a user's data
+async fn export_user_data(
+    state: Arc<AppState>,
+    client: SessionClient,
+    token: String,
+    user_id: Option<Uuid>,
+) -> Result<DataExportArchive, AuthError> {
+    tokio::task::spawn_blocking(move || {
+        let repo = state.users.as_ref();
+        let personal_data = state.personal_data.as_ref();
+        let mfa = state.mfa.as_ref();
+        let api_keys = state.api_keys.as_ref();
+        let audit_log = ClientAuditLog::new(state.audit_log.as_ref(), client);
+        let use_case = ExportUserDataUseCase::new(
+            repo,
+            personal_data,
+            mfa,
+            api_keys,
+            state.token_service.as_ref(),
+            &audit_log,
+        );
+
+        let export = use_case.execute(ExportUserDataCommand { token, user_id })?;
+        Ok(DataExportArchive::new(&export))
+    })
+    .await
+    .map_err(|e| AuthError::Internal(format!("Task join error: {}", e)))?
+}
+
+/// Erase an account
+async fn delete_account(
+    state: Arc<AppState>,
+    client: SessionClient,
+    token: String,
+    user_id: Option<Uuid>,
+    current_password: Option<String>,
+) -> Result<(), AuthError> {
+    tokio::task::spawn_blocking(move || {
+        let repo = state.users.as_ref();
+        let personal_data = state.personal_data.as_ref();
+        let throttles = state.login_throttles.as_ref();
+        let audit_log = ClientAuditLog::new(state.audit_log.as_ref(), client);
+        let use_case = DeleteAccountUseCase::new(
+            repo,
+            personal_data,
+            state.password_hasher.as_ref(),
+            state.token_service.as_ref(),
+            state.sessions.as_ref(),
+            throttles,
+            state.lockout_policy,
+            &audit_log,
+        );
+
+        use_case.execute(DeleteAccountCommand {
+            token,
+            user_id,
+            current_password,
+        })
+    })
+    .await
+    .map_err(|e| AuthError::Internal(format!("Task join error: {}", e)))?
+}
+
+/// POST /auth/api-keys - Create an API key for the current user
+///
+/// Returns the key; it is not shown again.
+pub async fn create_api_key(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(body): Json<CreateApiKeyRequest>,
+) -> Result<impl IntoResponse, AuthError> {
+    let token = bearer_token(&headers)?;
+
+    let result = tokio::task::spawn_blocking(move || {
+        let repo = state.users.as_ref();
+        let api_keys = state.api_keys.as_ref();
+        let use_case = CreateApiKeyUseCase::new(
+            repo,
+            api_keys,
+            state.token_generator.as_ref(),
+            state.token_service.as_ref(),
+        );
+
+        let command = CreateApiKeyCommand {
+            token,
+            name: body.name,
+            scopes: body.scopes,
+            expires_in_days: body.expires_in_days,
+        };
+
+        use_case.execute(command)
+    })
+    .await
+    .map_err(|e| AuthError::Internal(format!("Task join error: {}", e)))??;
+
+    let response = CreateApiKeyResponse {
+        key: result.key,
+        api_key: api_key_response(&result.api_key),
+    };
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+/// GET /auth/api-keys - List the current user's API keys
+pub async fn list_api_keys(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, AuthError> {
+    let token = bearer_token(&headers)?;
+
+    let keys = tokio::task::spawn_blocking(move || {
+        let api_keys = state.api_keys.as_ref();
+        let use_case = ListApiKeysUseCase::new(api_keys, state.token_service.as_ref());
+
+        use_case.execute(&token)
+    })
+    .await
+    .map_err(|e| AuthError::Internal(format!("Task join error: {}", e)))??;
+
+    let response: Vec<ApiKeyResponse> = keys.iter().map(api_key_response).collect();
+    Ok(Json(response))
+}
+
+/// DELETE /auth/api-keys/:key_id - Revoke an API key
+///
+/// Owners revoke their own keys; the `users:manage` permission allows
+/// revoking anyone's.
+pub async fn revoke_api_key(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(key_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AuthError> {
+    let token = bearer_token(&headers)?;
+
+    tokio::task::spawn_blocking(move || {
+        let api_keys = state.api_keys.as_ref();
+        let use_case = RevokeApiKeyUseCase::new(api_keys, state.token_service.as_ref());
+
+        use_case.execute(RevokeApiKeyCommand { token, key_id })
+    })
+    .await
+    .map_err(|e| AuthError::Internal(format!("Task join error: {}", e)))??;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// GET /auth/sessions - List the devices the current user is logged in on
+pub async fn list_sessions(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, AuthError> {
+    let token = bearer_token(&headers)?;
+
+    let result = tokio::task::spawn_blocking(move || {
+        let sessions = state.sessions.as_ref();
+        let use_case = ListSessionsUseCase::new(sessions, state.token_service.as_ref());
+
+        use_case.execute(&token)
+    })
+    .await
+    .map_err(|e| AuthError::Internal(format!("Task join error: {}", e)))??;
+
+    let response: Vec<SessionResponse> = result
+        .sessions
+        .iter()
+        .map(|session| session_response(session, result.current_session_id))
+        .collect();
+    Ok(Json(response))
+}
+
+/// DELETE /auth/sessions/:session_id - Log out one of the current user's devices
+///
+/// The session's tokens stop working at once.
+pub async fn revoke_session(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(session_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AuthError> {
+    let token = bearer_token(&headers)?;
+
+    tokio::task::spawn_blocking(move || {
+        let sessions = state.sessions.as_ref();
+        let refresh_tokens = state.refresh_tokens.as_ref();
+        let use_case =
+            RevokeSessionUseCase::new(sessions, refresh_tokens, state.token_service.as_ref());
+
+        use_case.execute(RevokeSessionCommand { token, session_id })
+    })
+    .await
+    .map_err(|e| AuthError::Internal(format!("Task join error: {}", e)))??;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// DELETE /auth/sessions/others - Log out every device but the current one
+pub async fn revoke_other_sessions(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, AuthError> {
+    let token = bearer_token(&headers)?;
+
+    let revoked_count = tokio::task::spawn_blocking(move || {
+        let sessions = state.sessions.as_ref();
+        let refresh_tokens = state.refresh_tokens.as_ref();
+        let use_case =
+            RevokeOtherSessionsUseCase::new(sessions, refresh_tokens, state.token_service.as_ref());
+
+        use_case.execute(&token)
+    })
+    .await
+    .map_err(|e| AuthError::Internal(format!("Task join error: {}", e)))??;
+
+    Ok(Json(RevokeOtherSessionsResponse { revoked_count }))
+}
+
+/// POST /admin/oauth-clients - Register an OAuth client for a backend service
+///
+/// Requires the `clients:manage` permission. Returns the client secret; it
+/// is not shown again.
+pub async fn register_oauth_client(
+    State(state): State<Arc<AppState>>,
+    peer: Option<ConnectInfo<SocketAddr>>,
+    headers: HeaderMap,
+    Json(body): Json<RegisterOAuthClientRequest>,
+) -> Result<impl IntoResponse, AuthError> {
+    let token = bearer_token(&headers)?;
+    let client = session_client(&state, peer, &headers);
+
+    let result = tokio::task::spawn_blocking(move || {
+        let clients = state.oauth_clients.as_ref();
+        let audit_log = ClientAuditLog::new(state.audit_log.as_ref(), client);
+        let use_case = RegisterOAuthClientUseCase::new(
+            clients,
+            state.token_generator.as_ref(),
+            state.token_service.as_ref(),
+            &audit_log,
+        );
+
+        use_case.execute(RegisterOAuthClientCommand {
+            token,
+            name: body.name,
+            scopes: body.scopes,
+        })
+    })
+    .await
+    .map_err(|e| AuthError::Internal(format!("Task join error: {}", e)))??;
+
+    let response = RegisterOAuthClientResponse {
+        client_secret: result.client_secret,
+        client: oauth_client_response(&result.client),
+    };
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+/// GET /admin/oauth-clients - List registered OAuth clients
+///
+/// Requires the `clients:manage` permission.
+pub async fn list_oauth_clients(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, AuthError> {
+    let token = bearer_token(&headers)?;
+
+    let clients = tokio::task::spawn_blocking(move || {
+        let clients = state.oauth_clients.as_ref();
+        let use_case = ListOAuthClientsUseCase::new(clients, state.token_service.as_ref());
+
+        use_case.execute(&token)
+    })
+    .await
+    .map_err(|e| AuthError::Internal(format!("Task join error: {}", e)))??;
+
+    let response: Vec<OAuthClientResponse> = clients.iter().map(oauth_client_response).collect();
+    Ok(Json(response))
+}
+
+/// DELETE /admin/oauth-clients/:client_id - Revoke an OAuth client
+///
+/// Requires the `clients:manage` permission.
+pub async fn revoke_oauth_client(
+    State(state): State<Arc<AppState>>,
+    peer: Option<ConnectInfo<SocketAddr>>,
+    headers: HeaderMap,
+    Path(client_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AuthError> {
+    let token = bearer_token(&headers)?;
+    let client = session_client(&state, peer, &headers);
+
+    tokio::task::spawn_blocking(move || {
+        let clients = state.oauth_clients.as_ref();
+        let audit_log = ClientAuditLog::new(state.audit_log.as_ref(), client);
+        let use_case =
+            RevokeOAuthClientUseCase::new(clients, state.token_service.as_ref(), &audit_log);
+
+        use_case.execute(RevokeOAuthClientCommand { token, client_id })
+    })
+    .await
+    .map_err(|e| AuthError::Internal(format!("Task join error: {}", e)))??;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// PUT /admin/users/:user_id/roles/:role - Grant a role to a user
+///
+/// Requires the `roles:manage` permission.
+pub async fn assign_role(
+    State(state): State<Arc<AppState>>,
+    peer: Option<ConnectInfo<SocketAddr>>,
+    headers: HeaderMap,
+    Path((user_id, role)): Path<(Uuid, String)>,
+) -> Result<impl IntoResponse, AuthError> {
+    let token = bearer_token(&headers)?;
+    let client = session_client(&state, peer, &headers);
+
+    let result = tokio::task::spawn_blocking(move || {
+        let repo = state.users.as_ref();
+        let audit_log = ClientAuditLog::new(state.audit_log.as_ref(), client);
+        let use_case = AssignRoleUseCase::new(repo, state.token_service.as_ref(), &audit_log);
+
+        let command = AssignRoleCommand {
+            token,
+            user_id,
+            role,
+        };
+
+        use_case.execute(command)
+    })
+    .await
+    .map_err(|e| AuthError::Internal(format!("Task join error: {}", e)))??;
+
+    let response = UserRolesResponse {
+        user_id: result.user_id.to_string(),
+        roles: role_names(&result.roles),
+    };
+
+    Ok(Json(response))
+}
+
+/// DELETE /admin/users/:user_id/roles/:role - Revoke a role from a user
+///
+/// Requires the `roles:manage` permission.
+pub async fn revoke_role(
+    State(state): State<Arc<AppState>>,
+    peer: Option<ConnectInfo<SocketAddr>>,
+    headers: HeaderMap,
+    Path((user_id, role)): Path<(Uuid, String)>,
+) -> Result<impl IntoResponse, AuthError> {
+    let token = bearer_token(&headers)?;
+    let client = session_client(&state, peer, &headers);
+
+    let result = tokio::task::spawn_blocking(move || {
+        let repo = state.users.as_ref();
+        let audit_log = ClientAuditLog::new(state.audit_log.as_ref(), client);
+        let use_case = RevokeRoleUseCase::new(repo, state.token_service.as_ref(), &audit_log);
+
+        let command = RevokeRoleCommand {
+            token,
+            user_id,
+            role,
+        };
+
+        use_case.execute(command)
+    })
+    .await
+    .map_err(|e| AuthError::Internal(format!("Task join error: {}", e)))??;
+
+    let response = UserRolesResponse {
+        user_id: result.user_id.to_string(),
+        roles: role_names(&result.roles),
+    };
+
+    Ok(Json(response))
+}
+
+/// DELETE /admin/users/:user_id/lockout - Lift a login lockout
+///
+/// Requires the `users:manage` permission.
+pub async fn unlock_account(
+    State(state): State<Arc<AppState>>,
+    peer: Option<ConnectInfo<SocketAddr>>,
+    headers: HeaderMap,
+    Path(user_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AuthError> {
+    let token = bearer_token(&headers)?;
+    let client = session_client(&state, peer, &headers);
+
+    tokio::task::spawn_blocking(move || {
+        let repo = state.users.as_ref();
+        let throttles = state.login_throttles.as_ref();
+        let audit_log = ClientAuditLog::new(state.audit_log.as_ref(), client);
+        let use_case =
+            UnlockAccountUseCase::new(repo, throttles, state.token_service.as_ref(), &audit_log);
+
+        use_case.execute(UnlockAccountCommand { token, user_id })
+    })
+    .await
+    .map_err(|e| AuthError::Internal(format!("Task join error: {}", e)))??;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /admin/users/{user_id}/impersonate - Act as a customer or organizer
+///
+/// Requires the `users:impersonate` permission and the caller's own session.
+/// Returns a short-lived access token naming the caller in its `act` claim;
+/// no refresh token is issued.
+pub async fn impersonate_user(
+    State(state): State<Arc<AppState>>,
+    peer: Option<ConnectInfo<SocketAddr>>,
+    headers: HeaderMap,
+    Path(user_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AuthError> {
+    let token = bearer_token(&headers)?;
+    let client = session_client(&state, peer, &headers);
+
+    let result = tokio::task::spawn_blocking(move || {
+        let repo = state.users.as_ref();
+        let audit_log = ClientAuditLog::new(state.audit_log.as_ref(), client);
+        let use_case = ImpersonateUserUseCase::new(
+            repo,
+            state.token_service.as_ref(),
+            state.impersonation_token_issuer.as_ref(),
+            state.impersonation_ttl,
+            &audit_log,
+        );
+
+        use_case.execute(ImpersonateUserCommand { token, user_id })
+    })
+    .await
+    .map_err(|e| AuthError::Internal(format!("Task join error: {}", e)))??;
+
+    let response = ImpersonateUserResponse {
+        token: result.token,
+        expires_in: result.expires_in,
+        user_id: result.user_id.to_string(),
+        email: result.email,
+    };
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+/// POST /organizations - Create an organization
+///
+/// The caller becomes its owner.
+pub async fn create_organization(
+    State(state): State<Arc<AppState>>,
+    peer: Option<ConnectInfo<SocketAddr>>,
+    headers: HeaderMap,
+    Json(body): Json<CreateOrganizationRequest>,
+) -> Result<impl IntoResponse, AuthError> {
+    let token = bearer_token(&headers)?;
+    let client = session_client(&state, peer, &headers);
+
+    let (organization, owner) = tokio::task::spawn_blocking(move || {
+        let repo = state.users.as_ref();
+        let organizations = state.organizations.as_ref();
+        let audit_log = ClientAuditLog::new(state.audit_log.as_ref(), client);
+        let use_case = CreateOrganizationUseCase::new(
+            repo,
+            organizations,
+            state.token_service.as_ref(),
+            &audit_log,
+        );
+
+        use_case.execute(CreateOrganizationCommand {
+            token,
+            name: body.name,
+        })
+    })
+    .await
+    .map_err(|e| AuthError::Internal(format!("Task join error: {}", e)))??;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(organization_response(&organization, &owner)),
+    ))
+}
+
+/// GET /organizations - List the current user's organizations
+///
+/// Oldest membership first.
+pub async fn list_organizations(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, AuthError> {
+    let token = bearer_token(&headers)?;
+
+    let memberships = tokio::task::spawn_blocking(move || {
+        let organizations = state.organizations.as_ref();
+        let use_case = ListOrganizationsUseCase::new(organizations, state.token_service.as_ref());
+
+        use_case.execute(&token)
+    })
+    .await
+    .map_err(|e| AuthError::Internal(format!("Task join error: {}", e)))??;
+
+    let response: Vec<OrganizationResponse> = memberships
+        .iter()
+        .map(|(organization, membership)| organization_response(organization, membership))
+        .collect();
+    Ok(Json(response))
+}
+
+/// POST /organizations/:organization_id/invitations - Invite someone by email
+///
+/// Requires the `admin` or `owner` role in the organization. The invitation
+/// link is mailed to the address.
+pub async fn invite_member(
+    State(state): State<Arc<AppState>>,
+    peer: Option<ConnectInfo<SocketAddr>>,
+    headers: HeaderMap,
+    Path(organization_id): Path<Uuid>,
+    Json(body): Json<InviteMemberRequest>,
+) -> Result<impl IntoResponse, AuthError> {
+    let token = bearer_token(&headers)?;
+    let client = session_client(&state, peer, &headers);
+
+    let result = tokio::task::spawn_blocking(move || {
+        let organizations = state.organizations.as_ref();
+        let invitations = state.invitations.as_ref();
+        let audit_log = ClientAuditLog::new(state.audit_log.as_ref(), client);
+        let use_case = InviteMemberUseCase::new(
+            organizations,
+            invitations,
+            state.token_service.as_ref(),
+            state.token_generator.as_ref(),
+            state.mailer.as_ref(),
+            state.invitation_ttl,
+            &state.invitation_url,
+            &audit_log,
+        );
+
+        use_case.execute(InviteMemberCommand {
+            token,
+            organization_id,
+            email: body.email,
+            role: body.role,
+        })
+    })
+    .await
+    .map_err(|e| AuthError::Internal(format!("Task join error: {}", e)))??;
+
+    let response = InviteMemberResponse {
+        invitation_id: result.invitation_id.to_string(),
+        expires_at: result.expires_at,
+    };
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+/// POST /organizations/invitations/accept - Join an organization
+///
+/// The invitation must have been sent to the caller's email address.
+pub async fn accept_invitation(
+    State(state): State<Arc<AppState>>,
+    peer: Option<ConnectInfo<SocketAddr>>,
+    headers: HeaderMap,
+    Json(body): Json<AcceptInvitationRequest>,
+) -> Result<impl IntoResponse, AuthError> {
+    let token = bearer_token(&headers)?;
+    let client = session_client(&state, peer, &headers);
+
+    let result = tokio::task::spawn_blocking(move || {
+        let repo = state.users.as_ref();
+        let organizations = state.organizations.as_ref();
+        let invitations = state.invitations.as_ref();
+        let audit_log = ClientAuditLog::new(state.audit_log.as_ref(), client);
+        let use_case = AcceptInvitationUseCase::new(
+            repo,
+            organizations,
+            invitations,
+            state.token_service.as_ref(),
+            state.token_generator.as_ref(),
+            &audit_log,
+        );
+
+        use_case.execute(AcceptInvitationCommand {
+            token,
+            invitation_token: body.token,
+        })
+    })
+    .await
+    .map_err(|e| AuthError::Internal(format!("Task join error: {}", e)))??;
+
+    Ok(Json(organization_response(
+        &result.organization,
+        &result.membership,
+    )))
+}
+
+/// POST /auth/switch-organization - Re-issue the access token for an organization
+///
+/// The new token carries the organization and the caller's role in it, and
+/// stays bound to the current session, which keeps the organization across
+/// refreshes.
+pub async fn switch_organization(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(body): Json<SwitchOrganizationRequest>,
+) -> Result<impl IntoResponse, AuthError> {
+    let token = bearer_token(&headers)?;
+
+    let result = tokio::task::spawn_blocking(move || {
+        let repo = state.users.as_ref();
+        let organizations = state.organizations.as_ref();
+        let use_case = SwitchOrganizationUseCase::new(
+            repo,
+            organizations,
+            state.sessions.as_ref(),
+            state.token_service.as_ref(),
+            state.organization_token_issuer.as_ref(),
+        );
+
+        use_case.execute(SwitchOrganizationCommand {
+            token,
+            organization_id: body.organization_id,
+        })
+    })
+    .await
+    .map_err(|e| AuthError::Internal(format!("Task join error: {}", e)))??;
+
+    Ok(Json(SwitchOrganizationResponse {
+        token: result.token,
+        organization_id: result.organization.map(|org| org.id.to_string()),
+        organization_role: result.organization.map(|org| org.role.as_str().to_string()),
+    }))
+}
+
+/// GET /.well-known/jwks.json - Public keys for verifying issued JWTs
+///
+/// Lists every key that may still verify a live token, including keys
+/// retired by a recent rotation. Empty when tokens are signed with HS256.
+pub async fn jwks(State(state): State<Arc<AppState>>) -> Result<impl IntoResponse, AuthError> {
+    let Some(key_ring) = state.key_ring.clone() else {
+        return Ok(Json(JwkSet { keys: Vec::new() }));
+    };
+
+    let result = tokio::task::spawn_blocking(move || key_ring.jwks())
+        .await
+        .map_err(|e| AuthError::Internal(format!("Task join error: {}", e)))??;
+
     Ok(Json(result))
 }
 
 /// GET /health - Health check endpoint
 ///
-/// Verifies database connectivity by attempting to acquire a pool connection.
+/// Verifies database connectivity, e.g. by acquiring a pool connection.
 pub async fn health(
     State(state): State<Arc<AppState>>,
 ) -> impl IntoResponse {
-    let db_ok = tokio::task::spawn_blocking(move || state.pool.get().is_ok())
+    let db_ok = tokio::task::spawn_blocking(move || state.database.is_healthy())
         .await
         .unwrap_or(false);
 
@@ -1,4 +1,5 @@
 //! HTTP interface layer
 
 pub mod handlers;
+pub mod oauth;
 pub mod router;
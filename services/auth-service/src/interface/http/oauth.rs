@@ -0,0 +1,501 @@
+//! HTTP handlers for the OAuth2 / OpenID Connect provider
+//!
+//! Serves the token endpoint for both grants, and the authorization-code
+//! flow with which first-party web apps sign users in. Diesel operations
+//! are wrapped in `spawn_blocking`, as in the other handlers.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::{
+    extract::{ConnectInfo, Path, Query, RawQuery, State},
+    http::{header, HeaderMap},
+    response::{IntoResponse, Redirect},
+    routing::{get, post, put},
+    Form, Json, Router,
+};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::handlers::{bearer_token, request_client};
+use crate::application::commands::{
+    authorize::{AuthorizationRequest, AuthorizeCommand, AuthorizeUseCase},
+    client_credentials::{ClientCredentialsCommand, ClientCredentialsUseCase},
+    exchange_authorization_code::{
+        ExchangeAuthorizationCodeCommand, ExchangeAuthorizationCodeUseCase,
+    },
+    register_oidc_client::{RegisterOidcClientCommand, RegisterOidcClientUseCase},
+};
+use crate::domain::audit::ClientAuditLog;
+use crate::domain::error::AuthError;
+use crate::domain::oauth_client::format_scope;
+use crate::domain::oidc::{OidcClient, SUPPORTED_SCOPES};
+use crate::OAuthState;
+
+// ============================================================================
+// Request/Response DTOs
+// ============================================================================
+
+/// Form body of an OAuth2 token request
+///
+/// Client credentials may be sent here or with HTTP Basic authentication.
+#[derive(Debug, Deserialize)]
+pub struct TokenRequest {
+    #[serde(default)]
+    pub grant_type: String,
+    pub client_id: Option<String>,
+    pub client_secret: Option<String>,
+    /// Space-delimited scopes; all registered scopes when absent
+    pub scope: Option<String>,
+    /// Authorization code (authorization_code grant)
+    pub code: Option<String>,
+    /// Redirect URI the code was sent to (authorization_code grant)
+    pub redirect_uri: Option<String>,
+    /// PKCE verifier (authorization_code grant)
+    pub code_verifier: Option<String>,
+}
+
+/// Parameters of an authorization request, as query string or JSON body
+///
+/// Missing parameters are reported by validation rather than rejected by
+/// the extractor, so every error has the usual body.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct AuthorizeParams {
+    pub response_type: String,
+    pub client_id: String,
+    pub redirect_uri: String,
+    pub scope: String,
+    pub state: Option<String>,
+    pub code_challenge: String,
+    pub code_challenge_method: String,
+    pub nonce: Option<String>,
+}
+
+/// Request body for registering an OpenID Connect client
+#[derive(Debug, Deserialize)]
+pub struct RegisterOidcClientRequest {
+    pub name: String,
+    pub redirect_uris: Vec<String>,
+}
+
+/// OAuth2 access token response
+#[derive(Debug, Serialize)]
+pub struct TokenResponse {
+    pub access_token: String,
+    pub token_type: &'static str,
+    /// Seconds until the token expires
+    pub expires_in: i64,
+    pub scope: String,
+    /// ID token (authorization_code grant)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id_token: Option<String>,
+}
+
+/// Response to a completed authorization request
+#[derive(Debug, Serialize)]
+pub struct AuthorizeResponse {
+    /// Client URL carrying the code, to send the user's browser to
+    pub redirect_to: String,
+}
+
+/// OpenID Connect userinfo response
+///
+/// Claims beyond `sub` follow the scopes granted to the access token.
+#[derive(Debug, Serialize)]
+pub struct UserInfoResponse {
+    pub sub: String,
+    /// Present with the `email` scope
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email_verified: Option<bool>,
+    /// Display name, present with the `profile` scope
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// Response describing an OpenID Connect client
+#[derive(Debug, Serialize)]
+pub struct OidcClientResponse {
+    pub client_id: String,
+    pub name: String,
+    pub redirect_uris: Vec<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// OpenID Connect discovery document
+#[derive(Debug, Serialize)]
+pub struct DiscoveryDocument {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub userinfo_endpoint: String,
+    pub jwks_uri: String,
+    pub scopes_supported: Vec<&'static str>,
+    pub response_types_supported: Vec<&'static str>,
+    pub grant_types_supported: Vec<&'static str>,
+    pub subject_types_supported: Vec<&'static str>,
+    pub id_token_signing_alg_values_supported: Vec<String>,
+    pub token_endpoint_auth_methods_supported: Vec<&'static str>,
+    pub code_challenge_methods_supported: Vec<&'static str>,
+    pub claims_supported: Vec<&'static str>,
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+impl From<AuthorizeParams> for AuthorizationRequest {
+    fn from(params: AuthorizeParams) -> Self {
+        Self {
+            response_type: params.response_type,
+            client_id: params.client_id,
+            redirect_uri: params.redirect_uri,
+            scope: params.scope,
+            state: params.state,
+            code_challenge: params.code_challenge,
+            code_challenge_method: params.code_challenge_method,
+            nonce: params.nonce,
+        }
+    }
+}
+
+/// Extract OAuth client credentials from HTTP Basic authentication or the form
+fn client_credentials(
+    headers: &HeaderMap,
+    body: &TokenRequest,
+) -> Result<(String, String), AuthError> {
+    let basic = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Basic "));
+    if let Some(encoded) = basic {
+        let decoded = STANDARD
+            .decode(encoded.trim())
+            .ok()
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+            .ok_or(AuthError::InvalidClient)?;
+        let (client_id, client_secret) = decoded.split_once(':').ok_or(AuthError::InvalidClient)?;
+        return Ok((client_id.to_string(), client_secret.to_string()));
+    }
+
+    match (&body.client_id, &body.client_secret) {
+        (Some(client_id), Some(client_secret)) => Ok((client_id.clone(), client_secret.clone())),
+        _ => Err(AuthError::InvalidClient),
+    }
+}
+
+/// Require a token request parameter
+fn required(value: Option<String>, name: &str) -> Result<String, AuthError> {
+    value
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| AuthError::InvalidRequest(format!("missing {}", name)))
+}
+
+/// Append query parameters to a URL that may already have a query
+fn with_query(url: &str, query: &str) -> String {
+    let separator = if url.contains('?') { '&' } else { '?' };
+    format!("{}{}{}", url, separator, query)
+}
+
+/// Render an OpenID Connect client
+fn oidc_client_response(client: &OidcClient) -> OidcClientResponse {
+    OidcClientResponse {
+        client_id: client.client_id().to_string(),
+        name: client.name().to_string(),
+        redirect_uris: client.redirect_uris().to_vec(),
+        created_at: client.created_at(),
+    }
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// GET /.well-known/openid-configuration - OpenID Connect discovery document
+pub async fn discovery(State(state): State<Arc<OAuthState>>) -> Json<DiscoveryDocument> {
+    let issuer = &state.issuer;
+    Json(DiscoveryDocument {
+        issuer: issuer.clone(),
+        authorization_endpoint: format!("{}/oauth/authorize", issuer),
+        token_endpoint: format!("{}/oauth/token", issuer),
+        userinfo_endpoint: format!("{}/oauth/userinfo", issuer),
+        jwks_uri: format!("{}/.well-known/jwks.json", issuer),
+        scopes_supported: SUPPORTED_SCOPES.to_vec(),
+        response_types_supported: vec!["code"],
+        grant_types_supported: vec!["authorization_code", "client_credentials"],
+        subject_types_supported: vec!["public"],
+        id_token_signing_alg_values_supported: vec![state.id_token_signing_alg.clone()],
+        token_endpoint_auth_methods_supported: vec![
+            "none",
+            "client_secret_basic",
+            "client_secret_post",
+        ],
+        code_challenge_methods_supported: vec!["S256"],
+        claims_supported: vec![
+            "iss",
+            "sub",
+            "aud",
+            "iat",
+            "exp",
+            "nonce",
+            "email",
+            "email_verified",
+            "name",
+        ],
+    })
+}
+
+/// GET /oauth/authorize - Start the authorization-code flow
+///
+/// Checks the request and sends the browser on to the login page with the
+/// same query string. Once the user is signed in, the login page completes
+/// the request with `POST /oauth/authorize`. Errors are returned to the
+/// browser rather than to the client, as the client may not be genuine.
+pub async fn authorize_redirect(
+    State(state): State<Arc<OAuthState>>,
+    RawQuery(query): RawQuery,
+    Query(params): Query<AuthorizeParams>,
+) -> Result<impl IntoResponse, AuthError> {
+    let request = AuthorizationRequest::from(params);
+
+    let login_url = state.login_url.clone();
+    tokio::task::spawn_blocking(move || {
+        let use_case = AuthorizeUseCase::new(
+            state.oidc_clients.as_ref(),
+            state.authorization_codes.as_ref(),
+            state.users.as_ref(),
+            state.token_service.as_ref(),
+            state.token_generator.as_ref(),
+            state.authorization_code_ttl,
+        );
+
+        use_case.validate(&request)
+    })
+    .await
+    .map_err(|e| AuthError::Internal(format!("Task join error: {}", e)))??;
+
+    Ok(Redirect::to(&with_query(
+        &login_url,
+        &query.unwrap_or_default(),
+    )))
+}
+
+/// POST /oauth/authorize - Complete an authorization request for the signed-in user
+///
+/// Takes the parameters of the original request as JSON and returns the
+/// client URL carrying the code.
+pub async fn authorize(
+    State(state): State<Arc<OAuthState>>,
+    headers: HeaderMap,
+    Json(params): Json<AuthorizeParams>,
+) -> Result<impl IntoResponse, AuthError> {
+    let token = bearer_token(&headers)?;
+
+    let grant = tokio::task::spawn_blocking(move || {
+        let use_case = AuthorizeUseCase::new(
+            state.oidc_clients.as_ref(),
+            state.authorization_codes.as_ref(),
+            state.users.as_ref(),
+            state.token_service.as_ref(),
+            state.token_generator.as_ref(),
+            state.authorization_code_ttl,
+        );
+
+        use_case.execute(AuthorizeCommand {
+            token,
+            request: params.into(),
+        })
+    })
+    .await
+    .map_err(|e| AuthError::Internal(format!("Task join error: {}", e)))??;
+
+    let mut params = vec![("code", grant.code)];
+    if let Some(client_state) = grant.state {
+        params.push(("state", client_state));
+    }
+    let query = serde_urlencoded::to_string(&params)
+        .map_err(|e| AuthError::Internal(format!("Failed to encode redirect: {}", e)))?;
+
+    Ok(Json(AuthorizeResponse {
+        redirect_to: with_query(&grant.redirect_uri, &query),
+    }))
+}
+
+/// POST /oauth/token - Issue tokens (client_credentials and authorization_code grants)
+///
+/// Takes a form-encoded body. Services authenticate with their client
+/// credentials, which may also be sent with HTTP Basic authentication; web
+/// apps redeem an authorization code with its PKCE verifier.
+pub async fn token(
+    State(state): State<Arc<OAuthState>>,
+    headers: HeaderMap,
+    Form(body): Form<TokenRequest>,
+) -> Result<impl IntoResponse, AuthError> {
+    let response = match body.grant_type.as_str() {
+        "client_credentials" => client_credentials_grant(state, &headers, body).await?,
+        "authorization_code" if state.oidc_provider_enabled => {
+            authorization_code_grant(state, body).await?
+        }
+        _ => return Err(AuthError::UnsupportedGrantType),
+    };
+
+    Ok(Json(response))
+}
+
+/// Issue a service token (client_credentials grant)
+async fn client_credentials_grant(
+    state: Arc<OAuthState>,
+    headers: &HeaderMap,
+    body: TokenRequest,
+) -> Result<TokenResponse, AuthError> {
+    let (client_id, client_secret) = client_credentials(headers, &body)?;
+
+    let result = tokio::task::spawn_blocking(move || {
+        let use_case = ClientCredentialsUseCase::new(
+            state.oauth_clients.as_ref(),
+            state.token_generator.as_ref(),
+            state.service_token_issuer.as_ref(),
+        );
+
+        use_case.execute(ClientCredentialsCommand {
+            client_id,
+            client_secret,
+            scope: body.scope,
+        })
+    })
+    .await
+    .map_err(|e| AuthError::Internal(format!("Task join error: {}", e)))??;
+
+    let expires_in = (result.token.expires_at - Utc::now()).num_seconds().max(0);
+    Ok(TokenResponse {
+        access_token: result.token.access_token,
+        token_type: "Bearer",
+        expires_in,
+        scope: format_scope(&result.scopes),
+        id_token: None,
+    })
+}
+
+/// Redeem an authorization code (authorization_code grant)
+async fn authorization_code_grant(
+    state: Arc<OAuthState>,
+    body: TokenRequest,
+) -> Result<TokenResponse, AuthError> {
+    let command = ExchangeAuthorizationCodeCommand {
+        client_id: required(body.client_id, "client_id")?,
+        code: required(body.code, "code")?,
+        redirect_uri: required(body.redirect_uri, "redirect_uri")?,
+        code_verifier: required(body.code_verifier, "code_verifier")?,
+    };
+
+    let expires_in = state.access_token_ttl.num_seconds();
+    let result = tokio::task::spawn_blocking(move || {
+        let use_case = ExchangeAuthorizationCodeUseCase::new(
+            state.oidc_clients.as_ref(),
+            state.authorization_codes.as_ref(),
+            state.users.as_ref(),
+            state.sessions.as_ref(),
+            state.id_token_issuer.as_ref(),
+            state.token_generator.as_ref(),
+        );
+
+        use_case.execute(command)
+    })
+    .await
+    .map_err(|e| AuthError::Internal(format!("Task join error: {}", e)))??;
+
+    Ok(TokenResponse {
+        access_token: result.access_token,
+        token_type: "Bearer",
+        expires_in,
+        scope: result.scopes.join(" "),
+        id_token: Some(result.id_token),
+    })
+}
+
+/// GET /oauth/userinfo - Claims about the user an access token was issued to
+pub async fn userinfo(
+    State(state): State<Arc<OAuthState>>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, AuthError> {
+    let token = bearer_token(&headers)?;
+
+    let response = tokio::task::spawn_blocking(move || {
+        let token_data = state.token_service.validate_token(&token)?;
+        // Service tokens name a client, not a user
+        if token_data.is_service() {
+            return Err(AuthError::Forbidden);
+        }
+        let user = state.users.find_by_id(token_data.user_id)?;
+        // Tokens issued outside the authorization-code flow were granted no scopes
+        let granted = token_data.oidc_scopes.unwrap_or_default();
+        let has_scope = |scope: &str| granted.iter().any(|s| s == scope);
+        let with_email = has_scope("email");
+
+        Ok::<UserInfoResponse, AuthError>(UserInfoResponse {
+            sub: user.id().as_uuid().to_string(),
+            email: with_email.then(|| user.email().as_str().to_string()),
+            email_verified: with_email.then(|| user.is_email_verified()),
+            name: if has_scope("profile") {
+                user.display_name().map(String::from)
+            } else {
+                None
+            },
+        })
+    })
+    .await
+    .map_err(|e| AuthError::Internal(format!("Task join error: {}", e)))??;
+
+    Ok(Json(response))
+}
+
+/// PUT /admin/oidc-clients/:client_id - Register or update an OpenID Connect client
+///
+/// Requires the `clients:manage` permission.
+pub async fn register_oidc_client(
+    State(state): State<Arc<OAuthState>>,
+    peer: Option<ConnectInfo<SocketAddr>>,
+    headers: HeaderMap,
+    Path(client_id): Path<String>,
+    Json(body): Json<RegisterOidcClientRequest>,
+) -> Result<impl IntoResponse, AuthError> {
+    let token = bearer_token(&headers)?;
+    let client = request_client(state.trust_forwarded_for, peer, &headers);
+
+    let client = tokio::task::spawn_blocking(move || {
+        let audit_log = ClientAuditLog::new(state.audit_log.as_ref(), client);
+        let use_case = RegisterOidcClientUseCase::new(
+            state.oidc_clients.as_ref(),
+            state.token_service.as_ref(),
+            &audit_log,
+        );
+
+        use_case.execute(RegisterOidcClientCommand {
+            token,
+            client_id,
+            name: body.name,
+            redirect_uris: body.redirect_uris,
+        })
+    })
+    .await
+    .map_err(|e| AuthError::Internal(format!("Task join error: {}", e)))??;
+
+    Ok(Json(oidc_client_response(&client)))
+}
+
+/// Create the router for the OAuth2 / OpenID Connect provider endpoints
+pub fn router(state: Arc<OAuthState>) -> Router {
+    let mut router = Router::new().route("/oauth/token", post(token));
+    if state.oidc_provider_enabled {
+        router = router
+            .route("/.well-known/openid-configuration", get(discovery))
+            .route("/oauth/authorize", get(authorize_redirect).post(authorize))
+            .route("/oauth/userinfo", get(userinfo))
+            .route("/admin/oidc-clients/:client_id", put(register_oidc_client));
+    }
+    router.with_state(state)
+}
@@ -1,14 +1,15 @@
 //! HTTP router configuration with security middleware
 
+use std::net::SocketAddr;
 use std::num::NonZeroU32;
 use std::sync::Arc;
 
 use axum::{
-    extract::Request,
+    extract::{ConnectInfo, Request, State},
     http::{HeaderName, HeaderValue, StatusCode},
     middleware::{self, Next},
     response::Response,
-    routing::{get, post},
+    routing::{delete, get, post, put},
     Extension, Router,
 };
 use governor::clock::DefaultClock;
@@ -18,8 +19,10 @@ use tower_http::limit::RequestBodyLimitLayer;
 use tower_http::set_header::SetResponseHeaderLayer;
 use tower_http::trace::TraceLayer;
 
-use super::handlers;
-use crate::AppState;
+use super::{handlers, oauth};
+use crate::domain::error::AuthError;
+use crate::interface::token_audit::token_error_code;
+use crate::{AppState, OAuthState};
 
 /// Shared rate limiter type
 type SharedRateLimiter = Arc<RateLimiter<NotKeyed, InMemoryState, DefaultClock>>;
@@ -38,15 +41,137 @@ async fn rate_limit_middleware(
     }
 }
 
+/// Audit middleware for rejected tokens
+///
+/// Handlers leave their `AuthError` on the response; token rejections are
+/// reported with the client that presented the token.
+async fn audit_rejected_tokens(
+    State(state): State<Arc<AppState>>,
+    peer: Option<ConnectInfo<SocketAddr>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let client = handlers::request_client(state.trust_forwarded_for, peer, request.headers());
+    let response = next.run(request).await;
+    if let Some(error) = response.extensions().get::<AuthError>() {
+        if token_error_code(error).is_some() {
+            let error = error.clone();
+            tokio::task::spawn_blocking(move || state.token_rejections.record(&client, &error));
+        }
+    }
+    response
+}
+
 /// Create the application router with all routes and security middleware
-pub fn create_router(state: Arc<AppState>, rate_limit_per_second: u32) -> Router {
+pub fn create_router(
+    state: Arc<AppState>,
+    oauth_state: Arc<OAuthState>,
+    rate_limit_per_second: u32,
+) -> Router {
     let mut router = Router::new()
         // Auth routes
         .route("/auth/register", post(handlers::register))
         .route("/auth/login", post(handlers::login))
-        .route("/auth/me", get(handlers::me))
+        .route("/auth/refresh", post(handlers::refresh))
+        .route("/auth/logout", post(handlers::logout))
+        .route(
+            "/auth/me",
+            get(handlers::me)
+                .patch(handlers::update_me)
+                .delete(handlers::delete_me),
+        )
+        .route("/auth/me/export", get(handlers::export_me))
+        .route(
+            "/auth/password-reset",
+            post(handlers::request_password_reset),
+        )
+        .route(
+            "/auth/password-reset/confirm",
+            post(handlers::confirm_password_reset),
+        )
+        .route("/auth/verify-email", post(handlers::verify_email))
+        .route(
+            "/auth/verify-email/resend",
+            post(handlers::resend_email_verification),
+        )
+        .route(
+            "/auth/api-keys",
+            post(handlers::create_api_key).get(handlers::list_api_keys),
+        )
+        .route("/auth/api-keys/:key_id", delete(handlers::revoke_api_key))
+        .route("/auth/sessions", get(handlers::list_sessions))
+        .route(
+            "/auth/sessions/others",
+            delete(handlers::revoke_other_sessions),
+        )
+        .route(
+            "/auth/sessions/:session_id",
+            delete(handlers::revoke_session),
+        )
+        .route("/auth/mfa/verify", post(handlers::verify_mfa))
+        .route("/auth/mfa/totp", post(handlers::enroll_totp))
+        .route(
+            "/auth/mfa/totp/confirm",
+            post(handlers::confirm_totp_enrollment),
+        )
+        .route("/auth/sso/providers", get(handlers::list_identity_providers))
+        .route("/auth/sso/start", post(handlers::start_federated_login))
+        .route(
+            "/auth/sso/callback",
+            post(handlers::federated_login_callback),
+        )
+        .route("/auth/magic-link", post(handlers::request_magic_link))
+        .route(
+            "/auth/magic-link/consume",
+            post(handlers::consume_magic_link),
+        )
+        .route(
+            "/auth/switch-organization",
+            post(handlers::switch_organization),
+        )
+        .route("/.well-known/jwks.json", get(handlers::jwks))
+        // Organization routes
+        .route(
+            "/organizations",
+            post(handlers::create_organization).get(handlers::list_organizations),
+        )
+        .route(
+            "/organizations/invitations/accept",
+            post(handlers::accept_invitation),
+        )
+        .route(
+            "/organizations/:organization_id/invitations",
+            post(handlers::invite_member),
+        )
+        // Admin routes
+        .route("/admin/users/:user_id", delete(handlers::delete_user))
+        .route("/admin/users/:user_id/export", get(handlers::export_user))
+        .route(
+            "/admin/users/:user_id/roles/:role",
+            put(handlers::assign_role).delete(handlers::revoke_role),
+        )
+        .route(
+            "/admin/users/:user_id/lockout",
+            delete(handlers::unlock_account),
+        )
+        .route(
+            "/admin/users/:user_id/impersonate",
+            post(handlers::impersonate_user),
+        )
+        .route(
+            "/admin/oauth-clients",
+            post(handlers::register_oauth_client).get(handlers::list_oauth_clients),
+        )
+        .route(
+            "/admin/oauth-clients/:client_id",
+            delete(handlers::revoke_oauth_client),
+        )
         // Health check
-        .route("/health", get(handlers::health));
+        .route("/health", get(handlers::health))
+        .with_state(Arc::clone(&state))
+        // OAuth2 / OpenID Connect provider
+        .merge(oauth::router(oauth_state))
+        .layer(middleware::from_fn_with_state(state, audit_rejected_tokens));
 
     // Rate limiting (if configured)
     if rate_limit_per_second > 0 {
@@ -85,6 +210,4 @@ pub fn create_router(state: Arc<AppState>, rate_limit_per_second: u32) -> Router
         ))
         // Tracing
         .layer(TraceLayer::new_for_http())
-        // State
-        .with_state(state)
 }
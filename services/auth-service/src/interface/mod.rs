@@ -2,5 +2,8 @@
 //!
 //! Translates external requests into application commands.
 
+pub mod client_ip;
+pub mod data_export;
 pub mod grpc;
 pub mod http;
+pub mod token_audit;
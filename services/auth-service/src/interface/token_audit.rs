@@ -0,0 +1,165 @@
+//! Audit of rejected tokens shared by the HTTP and gRPC adapters
+//!
+//! Both transports report the access tokens and API keys they reject
+//! here, together with the address and user agent of the client. A client
+//! retrying a bad token would otherwise write one row per request, so each
+//! client address gets at most one entry per reason and minute.
+
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use moka::sync::Cache;
+
+use crate::domain::audit::{AuditAction, AuditEvent, AuditLog};
+use crate::domain::error::AuthError;
+use crate::domain::session::SessionClient;
+
+/// Time during which further rejections of a client for the same reason
+/// are not recorded again
+const AUDIT_WINDOW: Duration = Duration::from_secs(60);
+
+/// Most client address and reason pairs remembered at once
+const MAX_TRACKED: u64 = 10_000;
+
+/// Records rejected tokens in the audit log, rate-limited per client
+pub struct TokenRejectionAudit {
+    audit_log: Arc<dyn AuditLog + Send + Sync>,
+    recent: Cache<(Option<IpAddr>, &'static str), ()>,
+}
+
+impl TokenRejectionAudit {
+    /// Create an audit writing to `audit_log`
+    pub fn new(audit_log: Arc<dyn AuditLog + Send + Sync>) -> Self {
+        Self {
+            audit_log,
+            recent: Cache::builder()
+                .max_capacity(MAX_TRACKED)
+                .time_to_live(AUDIT_WINDOW)
+                .build(),
+        }
+    }
+
+    /// Record `error` as a rejected token presented by `client`
+    ///
+    /// Errors other than token rejections are ignored, as are rejections
+    /// already recorded for the same client and reason within the window.
+    pub fn record(&self, client: &SessionClient, error: &AuthError) {
+        let Some(code) = token_error_code(error) else {
+            return;
+        };
+        let entry = self.recent.entry((client.ip_address, code)).or_insert(());
+        if entry.is_fresh() {
+            let result: Result<(), AuthError> = Err(error.clone());
+            self.audit_log.record(
+                AuditEvent::new(AuditAction::TokenValidation)
+                    .with_client(client)
+                    .with_result(&result),
+            );
+        }
+    }
+}
+
+/// Stable code of a token rejection, as in the `error` field of HTTP
+/// responses; `None` for other errors
+#[must_use]
+pub fn token_error_code(error: &AuthError) -> Option<&'static str> {
+    match error {
+        AuthError::InvalidToken => Some("invalid_token"),
+        AuthError::TokenExpired => Some("token_expired"),
+        AuthError::TokenRevoked => Some("token_revoked"),
+        _ => None,
+    }
+}
+
+/// Token rejection with the given stable code
+#[must_use]
+pub fn token_error(code: &str) -> Option<AuthError> {
+    match code {
+        "invalid_token" => Some(AuthError::InvalidToken),
+        "token_expired" => Some(AuthError::TokenExpired),
+        "token_revoked" => Some(AuthError::TokenRevoked),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::domain::audit::AuditOutcome;
+
+    #[derive(Default)]
+    struct MockAuditLog {
+        events: Mutex<Vec<AuditEvent>>,
+    }
+
+    impl AuditLog for MockAuditLog {
+        fn record(&self, event: AuditEvent) {
+            self.events.lock().unwrap().push(event);
+        }
+    }
+
+    fn client(ip: &str) -> SessionClient {
+        SessionClient {
+            ip_address: Some(ip.parse().unwrap()),
+            user_agent: Some("curl/8.0".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_records_rejection_with_client() {
+        let audit_log = Arc::new(MockAuditLog::default());
+        let audit = TokenRejectionAudit::new(audit_log.clone());
+
+        audit.record(&client("203.0.113.7"), &AuthError::TokenExpired);
+
+        let events = audit_log.events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].action(), AuditAction::TokenValidation);
+        assert_eq!(events[0].outcome(), AuditOutcome::Failure);
+        assert_eq!(events[0].ip_address(), Some("203.0.113.7".parse().unwrap()));
+        assert_eq!(events[0].user_agent(), Some("curl/8.0"));
+    }
+
+    #[test]
+    fn test_repeated_rejections_are_recorded_once_per_client_and_reason() {
+        let audit_log = Arc::new(MockAuditLog::default());
+        let audit = TokenRejectionAudit::new(audit_log.clone());
+
+        for _ in 0..5 {
+            audit.record(&client("203.0.113.7"), &AuthError::InvalidToken);
+        }
+        audit.record(&client("203.0.113.7"), &AuthError::TokenRevoked);
+        audit.record(&client("198.51.100.1"), &AuthError::InvalidToken);
+
+        assert_eq!(audit_log.events.lock().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_ignores_other_errors() {
+        let audit_log = Arc::new(MockAuditLog::default());
+        let audit = TokenRejectionAudit::new(audit_log.clone());
+
+        audit.record(&client("203.0.113.7"), &AuthError::InvalidCredentials);
+        audit.record(
+            &client("203.0.113.7"),
+            &AuthError::Internal("db down".to_string()),
+        );
+
+        assert!(audit_log.events.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_token_error_code_round_trip() {
+        for error in [
+            AuthError::InvalidToken,
+            AuthError::TokenExpired,
+            AuthError::TokenRevoked,
+        ] {
+            let code = token_error_code(&error).unwrap();
+            assert_eq!(token_error(code), Some(error));
+        }
+    }
+}
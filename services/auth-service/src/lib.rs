@@ -1,6 +1,6 @@
 //! Auth Service Library
 //!
-//! This module exposes the service's internal modules for testing purposes.
+//! Exposes the service's modules to the `auth-service` binary and to tests.
 
 use std::sync::Arc;
 
@@ -10,8 +10,148 @@ pub mod infrastructure;
 pub mod interface;
 
 /// Application state shared across handlers
+///
+/// Holds its repositories as trait objects, so the HTTP and gRPC surfaces
+/// can run against in-memory implementations as well as the database.
 pub struct AppState {
-    pub pool: infrastructure::db::connection::DbPool,
+    /// Database behind the repositories below, probed by the health check
+    pub database: Arc<dyn domain::health::HealthCheck + Send + Sync>,
+    pub users: Arc<dyn domain::auth::UserRepository + Send + Sync>,
+    /// The same users, awaited without `spawn_blocking`, like the other
+    /// `async_` stores below
+    pub async_users: Arc<dyn domain::auth::AsyncUserRepository + Send + Sync>,
+    pub user_search: Arc<dyn domain::user_search::UserSearchRepository + Send + Sync>,
+    /// Accounts at upstream identity providers linked to users
+    pub user_identities: Arc<dyn domain::federation::AsyncUserIdentityRepository + Send + Sync>,
+    pub federated_login_requests:
+        Arc<dyn domain::federation::AsyncFederatedLoginRequestRepository + Send + Sync>,
+    pub sessions: Arc<dyn domain::session::SessionRepository + Send + Sync>,
+    pub async_sessions: Arc<dyn domain::session::AsyncSessionRepository + Send + Sync>,
+    pub refresh_tokens: Arc<dyn domain::refresh_token::RefreshTokenRepository + Send + Sync>,
+    pub async_refresh_tokens:
+        Arc<dyn domain::refresh_token::AsyncRefreshTokenRepository + Send + Sync>,
+    pub login_throttles: Arc<dyn domain::lockout::LoginThrottleRepository + Send + Sync>,
+    pub async_login_throttles:
+        Arc<dyn domain::lockout::AsyncLoginThrottleRepository + Send + Sync>,
+    pub mfa: Arc<dyn domain::mfa::MfaRepository + Send + Sync>,
+    pub async_mfa: Arc<dyn domain::mfa::AsyncMfaRepository + Send + Sync>,
+    pub async_mfa_challenges: Arc<dyn domain::mfa::AsyncMfaChallengeRepository + Send + Sync>,
+    pub email_verification_tokens:
+        Arc<dyn domain::email_verification::EmailVerificationTokenRepository + Send + Sync>,
+    pub async_email_verification_tokens:
+        Arc<dyn domain::email_verification::AsyncEmailVerificationTokenRepository + Send + Sync>,
+    pub password_reset_tokens:
+        Arc<dyn domain::password_reset::PasswordResetTokenRepository + Send + Sync>,
+    pub magic_link_tokens: Arc<dyn domain::magic_link::MagicLinkTokenRepository + Send + Sync>,
+    pub async_magic_link_tokens:
+        Arc<dyn domain::magic_link::AsyncMagicLinkTokenRepository + Send + Sync>,
+    pub api_keys: Arc<dyn domain::api_key::ApiKeyRepository + Send + Sync>,
+    pub oauth_clients: Arc<dyn domain::oauth_client::OAuthClientRepository + Send + Sync>,
+    pub organizations: Arc<dyn domain::organization::OrganizationRepository + Send + Sync>,
+    pub async_organizations:
+        Arc<dyn domain::organization::AsyncOrganizationRepository + Send + Sync>,
+    pub invitations: Arc<dyn domain::organization::InvitationRepository + Send + Sync>,
+    pub personal_data: Arc<dyn domain::personal_data::PersonalDataRepository + Send + Sync>,
+    /// Search over the audit trail written through `audit_log`
+    pub audit_events: Arc<dyn domain::audit::AuditEventRepository + Send + Sync>,
     pub password_hasher: Arc<dyn domain::auth::PasswordHasher + Send + Sync>,
+    /// The same hasher for the async use cases, which must not hash on an
+    /// async worker
+    pub async_password_hasher: Arc<dyn domain::auth::AsyncPasswordHasher + Send + Sync>,
     pub token_service: Arc<dyn domain::auth::TokenService + Send + Sync>,
+    /// Validates the tokens of `token_service`, awaiting the revocation,
+    /// session and API key lookups
+    pub async_tokens: Arc<dyn domain::auth::AsyncTokenValidator + Send + Sync>,
+    /// Asymmetric signing keys, published via JWKS (`None` when signing with HS256)
+    pub key_ring: Option<Arc<infrastructure::security::jwt_key_ring::JwtKeyRing>>,
+    pub token_generator: Arc<dyn domain::auth::OpaqueTokenGenerator + Send + Sync>,
+    /// Lifetime of issued refresh tokens
+    pub refresh_token_ttl: chrono::Duration,
+    pub mailer: Arc<dyn domain::mailer::Mailer + Send + Sync>,
+    /// Security audit trail of logins, profile changes and admin actions
+    pub audit_log: Arc<dyn domain::audit::AuditLog + Send + Sync>,
+    /// The same audit trail, written without `spawn_blocking`
+    pub async_audit_log: Arc<dyn domain::audit::AsyncAuditLog + Send + Sync>,
+    /// Rejected tokens reported by both transports, written to `audit_log`
+    pub token_rejections: Arc<interface::token_audit::TokenRejectionAudit>,
+    /// Rules for new passwords (registration, change and reset)
+    pub password_policy: domain::password_policy::PasswordPolicy,
+    /// Lifetime of password reset tokens
+    pub password_reset_ttl: chrono::Duration,
+    /// Page that completes a password reset, linked from reset emails
+    pub password_reset_url: String,
+    /// Lifetime of email verification tokens
+    pub email_verification_ttl: chrono::Duration,
+    /// Page that completes email verification, linked from verification emails
+    pub email_verification_url: String,
+    /// Whether login requires a verified email address
+    pub require_verified_email: bool,
+    pub totp_service: Arc<dyn domain::mfa::TotpService + Send + Sync>,
+    /// Lifetime of the challenge between the password and the second factor
+    pub mfa_challenge_ttl: chrono::Duration,
+    /// Thresholds and durations for login lockouts
+    pub lockout_policy: domain::lockout::LockoutPolicy,
+    /// Whether the client IP is taken from `X-Forwarded-For` / `x-forwarded-for` metadata
+    pub trust_forwarded_for: bool,
+    /// Upstream OpenID Connect providers users may sign in with
+    pub identity_providers: Vec<domain::federation::IdentityProvider>,
+    pub identity_provider_client:
+        Arc<dyn domain::federation::AsyncIdentityProviderClient + Send + Sync>,
+    /// Page identity providers send users back to
+    pub federated_login_callback_url: String,
+    /// Time a user has to sign in at an identity provider
+    pub federated_login_ttl: chrono::Duration,
+    /// Lifetime of magic links
+    pub magic_link_ttl: chrono::Duration,
+    /// Page that completes a magic-link login, linked from magic link emails
+    pub magic_link_url: String,
+    /// How many magic links one account may be mailed
+    pub magic_link_rate_limit: domain::magic_link::MagicLinkRateLimit,
+    /// Issues tokens to administrators acting as another user
+    pub impersonation_token_issuer:
+        Arc<dyn domain::impersonation::ImpersonationTokenIssuer + Send + Sync>,
+    /// Lifetime of impersonation tokens
+    pub impersonation_ttl: chrono::Duration,
+    /// Issues tokens acting for one of the user's organizations
+    pub organization_token_issuer:
+        Arc<dyn domain::organization::OrganizationTokenIssuer + Send + Sync>,
+    /// Lifetime of organization invitations
+    pub invitation_ttl: chrono::Duration,
+    /// Page that accepts an organization invitation, linked from invitation emails
+    pub invitation_url: String,
+}
+
+/// State of the OAuth2 / OpenID Connect provider endpoints
+///
+/// Holds its collaborators as trait objects rather than a pool, so the
+/// provider can run in-process against other implementations.
+pub struct OAuthState {
+    pub users: Arc<dyn domain::auth::UserRepository + Send + Sync>,
+    /// Sessions that authorization codes are bound to
+    pub sessions: Arc<dyn domain::session::SessionRepository + Send + Sync>,
+    pub token_service: Arc<dyn domain::auth::TokenService + Send + Sync>,
+    pub token_generator: Arc<dyn domain::auth::OpaqueTokenGenerator + Send + Sync>,
+    pub oauth_clients: Arc<dyn domain::oauth_client::OAuthClientRepository + Send + Sync>,
+    /// Issues tokens to OAuth clients (client_credentials grant)
+    pub service_token_issuer: Arc<dyn domain::oauth_client::ServiceTokenIssuer + Send + Sync>,
+    pub oidc_clients: Arc<dyn domain::oidc::OidcClientRepository + Send + Sync>,
+    pub authorization_codes: Arc<dyn domain::oidc::AuthorizationCodeRepository + Send + Sync>,
+    pub id_token_issuer: Arc<dyn domain::oidc::IdTokenIssuer + Send + Sync>,
+    pub audit_log: Arc<dyn domain::audit::AuditLog + Send + Sync>,
+    /// Whether the client IP is taken from `X-Forwarded-For`
+    pub trust_forwarded_for: bool,
+    /// Whether the OpenID Connect endpoints and the authorization_code grant
+    /// are served; only with asymmetric keys, which relying parties verify
+    /// through the JWKS
+    pub oidc_provider_enabled: bool,
+    /// Issuer identifier: the provider's base URL, the `iss` of ID tokens
+    pub issuer: String,
+    /// Page that signs the user in and completes authorization requests
+    pub login_url: String,
+    /// Algorithm ID tokens are signed with, as advertised in discovery
+    pub id_token_signing_alg: String,
+    /// Lifetime of access tokens issued for authorization codes
+    pub access_token_ttl: chrono::Duration,
+    /// Lifetime of authorization codes
+    pub authorization_code_ttl: chrono::Duration,
 }
@@ -4,40 +4,103 @@
 //! Built with Clean Architecture principles.
 //! Exposes both HTTP (Axum) and gRPC (Tonic) interfaces.
 
-mod application;
-mod domain;
-mod infrastructure;
-mod interface;
-
+use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use axum::Router;
-use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
+use diesel_migrations::MigrationHarness;
 use tokio::net::TcpListener;
 use tokio::signal;
 use tonic::transport::Server as TonicServer;
-use tracing::info;
+use tracing::{debug, info, warn};
 
-use crate::infrastructure::{
+use auth_service::application::commands::rotate_signing_key::{
+    RotateSigningKeyCommand, RotateSigningKeyUseCase,
+};
+use auth_service::domain::audit::AuditEventRepository;
+use auth_service::domain::email_verification::EmailVerificationTokenRepository;
+use auth_service::domain::error::AuthError;
+use auth_service::domain::federation::FederatedLoginRequestRepository;
+use auth_service::domain::lockout::LoginThrottleRepository;
+use auth_service::domain::magic_link::MagicLinkTokenRepository;
+use auth_service::domain::mailer::Mailer;
+use auth_service::domain::mfa::MfaChallengeRepository;
+use auth_service::domain::oidc::AuthorizationCodeRepository;
+use auth_service::domain::organization::InvitationRepository;
+use auth_service::domain::outbox::{OutboxPublisher, OutboxRepository};
+use auth_service::domain::password_policy::PasswordPolicy;
+use auth_service::domain::password_reset::PasswordResetTokenRepository;
+use auth_service::domain::signing_key::{SigningAlgorithm, SigningKeyRepository};
+use auth_service::domain::token_revocation::TokenRevocationStore;
+use auth_service::infrastructure::{
+    self,
     cache::token_cache::CachedTokenService,
     config::Config,
-    db::connection::create_connection_pool,
-    security::{argon2_password_hasher::Argon2PasswordHasher, jwt_token_service::JwtTokenService},
+    db::api_key_repository_diesel::DieselApiKeyRepository,
+    db::async_api_key_repository_diesel::AsyncDieselApiKeyRepository,
+    db::async_audit_log_diesel::AsyncDieselAuditLog,
+    db::async_email_verification_repository_diesel::AsyncDieselEmailVerificationTokenRepository,
+    db::async_federated_login_request_repository_diesel::AsyncDieselFederatedLoginRequestRepository,
+    db::async_login_throttle_repository_diesel::AsyncDieselLoginThrottleRepository,
+    db::async_magic_link_repository_diesel::AsyncDieselMagicLinkTokenRepository,
+    db::async_mfa_challenge_repository_diesel::AsyncDieselMfaChallengeRepository,
+    db::async_mfa_repository_diesel::AsyncDieselMfaRepository,
+    db::async_organization_repository_diesel::AsyncDieselOrganizationRepository,
+    db::async_refresh_token_repository_diesel::AsyncDieselRefreshTokenRepository,
+    db::async_session_repository_diesel::AsyncDieselSessionRepository,
+    db::async_token_revocation_store_diesel::AsyncDieselTokenRevocationStore,
+    db::async_user_identity_repository_diesel::AsyncDieselUserIdentityRepository,
+    db::async_user_repository_diesel::AsyncDieselUserRepository,
+    db::audit_event_repository_diesel::DieselAuditEventRepository,
+    db::authorization_code_repository_diesel::DieselAuthorizationCodeRepository,
+    db::connection::{create_async_connection_pool, create_connection_pool, DbPool, MIGRATIONS},
+    db::email_verification_repository_diesel::DieselEmailVerificationTokenRepository,
+    db::federated_login_request_repository_diesel::DieselFederatedLoginRequestRepository,
+    db::invitation_repository_diesel::DieselInvitationRepository,
+    db::login_throttle_repository_diesel::DieselLoginThrottleRepository,
+    db::magic_link_repository_diesel::DieselMagicLinkTokenRepository,
+    db::mfa_challenge_repository_diesel::DieselMfaChallengeRepository,
+    db::mfa_repository_diesel::DieselMfaRepository,
+    db::oauth_client_repository_diesel::DieselOAuthClientRepository,
+    db::oidc_client_repository_diesel::DieselOidcClientRepository,
+    db::organization_repository_diesel::DieselOrganizationRepository,
+    db::outbox_repository_diesel::DieselOutboxRepository,
+    db::password_reset_repository_diesel::DieselPasswordResetTokenRepository,
+    db::personal_data_repository_diesel::DieselPersonalDataRepository,
+    db::refresh_token_repository_diesel::DieselRefreshTokenRepository,
+    db::session_repository_diesel::DieselSessionRepository,
+    db::signing_key_repository_diesel::DieselSigningKeyRepository,
+    db::token_revocation_store_diesel::DieselTokenRevocationStore,
+    db::user_repository_diesel::DieselUserRepository,
+    events::{
+        kafka_outbox_publisher::KafkaOutboxPublisher, log_event_publisher::LogEventPublisher,
+        outbox_relay::OutboxRelay,
+    },
+    federation::http_identity_provider_client::HttpIdentityProviderClient,
+    mail::{file_mailer::FileMailer, log_mailer::LogMailer},
+    security::{
+        api_key_token_service::ApiKeyTokenService,
+        argon2_password_hasher::Argon2PasswordHasher,
+        breached_password_file::BreachedPasswordFile,
+        jwt_key_ring::JwtKeyRing, jwt_token_service::JwtTokenService,
+        opaque_token_generator::RandomOpaqueTokenGenerator,
+        signing_key_generator::RandomSigningKeyGenerator, totp_service::Rfc6238TotpService,
+    },
 };
-use crate::interface::grpc::service::pb::auth_service_server::AuthServiceServer;
-use crate::interface::grpc::service::AuthServiceGrpc;
-use crate::interface::http;
-
-/// Embedded database migrations — compiled into the binary so no external
-/// diesel CLI is needed at runtime.
-pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
-
-/// Application state shared across handlers
-pub struct AppState {
-    pub pool: infrastructure::db::connection::DbPool,
-    pub password_hasher: Arc<dyn domain::auth::PasswordHasher + Send + Sync>,
-    pub token_service: Arc<dyn domain::auth::TokenService + Send + Sync>,
-}
+use auth_service::interface::grpc::admin_service::AdminUserServiceGrpc;
+use auth_service::interface::grpc::organization_service::OrganizationServiceGrpc;
+use auth_service::interface::grpc::service::pb::admin_user_service_server::AdminUserServiceServer;
+use auth_service::interface::grpc::service::pb::auth_service_server::AuthServiceServer;
+use auth_service::interface::grpc::service::pb::organization_service_server::OrganizationServiceServer;
+use auth_service::interface::grpc::service::AuthServiceGrpc;
+use auth_service::interface::grpc::token_audit::TokenAuditLayer;
+use auth_service::interface::http;
+use auth_service::interface::token_audit::TokenRejectionAudit;
+use auth_service::{domain, AppState, OAuthState};
+
+/// How often expired revocation entries, signing keys and one-time tokens are purged
+const PURGE_INTERVAL: Duration = Duration::from_secs(3600);
 
 /// Initialize tracing/logging based on LOG_FORMAT env var.
 ///
@@ -91,15 +154,151 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Run embedded migrations
     run_migrations(&pool);
 
+    // Async pool for the handlers that await their queries instead of
+    // moving them to the blocking thread pool: registration, login,
+    // refresh and token validation
+    let async_pool =
+        create_async_connection_pool(&config.database_url, config.db_async_pool_max_size)?;
+
     // Initialize services
     let password_hasher = Arc::new(Argon2PasswordHasher::new());
-    let jwt_service = Arc::new(JwtTokenService::new(
-        config.jwt_secret.clone(),
-        config.jwt_expiration_secs,
-    ));
+    let revocation_store = Arc::new(DieselTokenRevocationStore::new(pool.clone()));
+    let key_ring = match config.jwt_algorithm.as_str() {
+        "HS256" => None,
+        algorithm => Some(init_key_ring(&pool, &config, algorithm.parse()?)?),
+    };
+    let jwt_service = match &key_ring {
+        Some(ring) => JwtTokenService::with_key_ring(Arc::clone(ring), config.jwt_expiration_secs),
+        None => JwtTokenService::new(
+            config.jwt_secret.clone().unwrap_or_default(),
+            config.jwt_expiration_secs,
+        ),
+    };
+    let async_sessions = Arc::new(AsyncDieselSessionRepository::new(async_pool.clone()));
+    let jwt_service = Arc::new(
+        jwt_service
+            .with_revocation_store(revocation_store.clone())
+            .with_session_store(Arc::new(DieselSessionRepository::new(pool.clone())))
+            .with_async_revocation_store(Arc::new(AsyncDieselTokenRevocationStore::new(
+                async_pool.clone(),
+            )))
+            .with_async_session_store(async_sessions.clone())
+            .with_issuer(config.issuer_url.clone()),
+    );
+
+    // Periodically drop revocation entries and signing keys for tokens that expired anyway
+    spawn_purge("token revocations", move |now| {
+        revocation_store.purge_expired(now)
+    });
+    if key_ring.is_some() {
+        let signing_keys = DieselSigningKeyRepository::new(pool.clone());
+        spawn_purge("signing keys", move |now| signing_keys.purge_expired(now));
+    }
+    let reset_tokens = DieselPasswordResetTokenRepository::new(pool.clone());
+    spawn_purge("password reset tokens", move |now| {
+        reset_tokens.purge_expired(now)
+    });
+    let verification_tokens = DieselEmailVerificationTokenRepository::new(pool.clone());
+    spawn_purge("email verification tokens", move |now| {
+        verification_tokens.purge_expired(now)
+    });
+    let mfa_challenges = DieselMfaChallengeRepository::new(pool.clone());
+    spawn_purge("MFA challenges", move |now| {
+        mfa_challenges.purge_expired(now)
+    });
+    let authorization_codes = Arc::new(DieselAuthorizationCodeRepository::new(pool.clone()));
+    let expired_codes = authorization_codes.clone();
+    spawn_purge("authorization codes", move |now| {
+        expired_codes.purge_expired(now)
+    });
+    let federated_logins = DieselFederatedLoginRequestRepository::new(pool.clone());
+    spawn_purge("federated login requests", move |now| {
+        federated_logins.purge_expired(now)
+    });
+    // Links stay until the rate window has passed, as they count towards the limit
+    let magic_links = DieselMagicLinkTokenRepository::new(pool.clone());
+    let magic_link_window = config.magic_link_rate_limit().window;
+    spawn_purge("magic links", move |now| {
+        magic_links.purge_expired(now - magic_link_window)
+    });
+    let invitations = DieselInvitationRepository::new(pool.clone());
+    spawn_purge("organization invitations", move |now| {
+        invitations.purge_expired(now)
+    });
+    let login_throttles = DieselLoginThrottleRepository::new(pool.clone());
+    let lockout_reset = chrono::Duration::seconds(config.lockout_reset_secs);
+    spawn_purge("login throttles", move |now| {
+        login_throttles.purge_stale(now - lockout_reset)
+    });
+    let audit_events = Arc::new(DieselAuditEventRepository::new(pool.clone()));
+    let expired_audit_events = audit_events.clone();
+    let audit_retention = chrono::Duration::days(config.audit_retention_days);
+    spawn_purge("audit events", move |now| {
+        expired_audit_events.purge_older_than(now - audit_retention)
+    });
+
+    // Events reach other services through the outbox; without a broker they are only logged
+    let outbox = Arc::new(DieselOutboxRepository::new(pool.clone()));
+    let outbox_publisher: Arc<dyn OutboxPublisher + Send + Sync> = match &config.kafka_brokers {
+        Some(brokers) => {
+            info!(
+                "Publishing events to Kafka topic {} on {}",
+                config.kafka_topic, brokers
+            );
+            Arc::new(KafkaOutboxPublisher::new(
+                brokers,
+                config.kafka_topic.clone(),
+                tokio::runtime::Handle::current(),
+            )?)
+        }
+        None => {
+            warn!("KAFKA_BROKERS is not set, events are only logged");
+            Arc::new(LogEventPublisher::new())
+        }
+    };
+    spawn_outbox_relay(
+        OutboxRelay::new(outbox.clone(), outbox_publisher, config.outbox_batch_size),
+        Duration::from_millis(config.outbox_poll_interval_ms),
+    );
+    let published_events = outbox;
+    let outbox_retention = chrono::Duration::days(config.outbox_retention_days);
+    spawn_purge("published outbox events", move |now| {
+        published_events.purge_published(now - outbox_retention)
+    });
+
+    // Without SMTP configured, mail is written to disk or the log
+    let mailer: Arc<dyn Mailer + Send + Sync> = match &config.mail_dir {
+        Some(dir) => {
+            info!("Writing outgoing mail to {}", dir);
+            Arc::new(FileMailer::new(dir)?)
+        }
+        None => Arc::new(LogMailer::new()),
+    };
+
+    for provider in &config.identity_providers {
+        info!("Federated login through {} ({})", provider.name, provider.issuer);
+    }
+    let mut identity_provider_client =
+        HttpIdentityProviderClient::new(Duration::from_secs(config.identity_provider_timeout_secs));
+    if config.identity_provider_allow_http {
+        warn!("AUTH_IDENTITY_PROVIDER_ALLOW_HTTP is set, identity providers may use plain HTTP");
+        identity_provider_client = identity_provider_client.with_plain_http();
+    }
+
+    let mut password_policy = PasswordPolicy::new(config.password_rules());
+    if let Some(path) = &config.breached_passwords_file {
+        info!("Checking new passwords against {}", path);
+        password_policy =
+            password_policy.with_breached_list(Arc::new(BreachedPasswordFile::open(path)?));
+    }
+
+    let service_token_issuer = jwt_service.clone();
+    let id_token_issuer = jwt_service.clone();
+    let impersonation_token_issuer = jwt_service.clone();
+    let organization_token_issuer = jwt_service.clone();
 
     // Wrap token service with moka cache (if configured)
-    let token_service: Arc<dyn domain::auth::TokenService + Send + Sync> =
+    let token_service: Arc<dyn domain::auth::AsyncTokenService + Send + Sync> =
         if config.token_cache_ttl_secs > 0 {
             info!(
                 "Token validation cache enabled: TTL={}s, max_capacity={}",
@@ -114,18 +313,146 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             jwt_service
         };
 
+    // API keys are looked up on every use, so revocation and role changes apply at once
+    let token_generator = Arc::new(RandomOpaqueTokenGenerator::new());
+    let users = Arc::new(DieselUserRepository::new(pool.clone()));
+    let async_users = Arc::new(AsyncDieselUserRepository::new(async_pool.clone()));
+    let api_keys = Arc::new(DieselApiKeyRepository::new(pool.clone()));
+    let api_key_token_service = Arc::new(
+        ApiKeyTokenService::new(
+            token_service,
+            api_keys.clone(),
+            users.clone(),
+            token_generator.clone(),
+        )
+        .with_async_repositories(
+            Arc::new(AsyncDieselApiKeyRepository::new(async_pool.clone())),
+            async_users.clone(),
+        ),
+    );
+    let token_service: Arc<dyn domain::auth::TokenService + Send + Sync> =
+        api_key_token_service.clone();
+
+    let audit_log: Arc<dyn domain::audit::AuditLog + Send + Sync> = audit_events.clone();
+
+    let oauth_clients = Arc::new(DieselOAuthClientRepository::new(pool.clone()));
+    let sessions = Arc::new(DieselSessionRepository::new(pool.clone()));
+    let oauth_state = Arc::new(OAuthState {
+        users: users.clone(),
+        sessions: sessions.clone(),
+        token_service: token_service.clone(),
+        token_generator: token_generator.clone(),
+        oauth_clients: oauth_clients.clone(),
+        service_token_issuer,
+        oidc_clients: Arc::new(DieselOidcClientRepository::new(pool.clone())),
+        authorization_codes,
+        id_token_issuer,
+        audit_log: audit_log.clone(),
+        trust_forwarded_for: config.trust_forwarded_for,
+        oidc_provider_enabled: config.oidc_provider_enabled,
+        issuer: config.issuer_url.clone(),
+        login_url: config.oidc_login_url.clone(),
+        id_token_signing_alg: config.jwt_algorithm.clone(),
+        access_token_ttl: chrono::Duration::seconds(config.jwt_expiration_secs),
+        authorization_code_ttl: chrono::Duration::seconds(
+            config.authorization_code_expiration_secs,
+        ),
+    });
+
     // Build application state
     let state = Arc::new(AppState {
-        pool,
+        database: Arc::new(pool.clone()),
+        users: users.clone(),
+        async_users,
+        user_search: users,
+        user_identities: Arc::new(AsyncDieselUserIdentityRepository::new(async_pool.clone())),
+        federated_login_requests: Arc::new(AsyncDieselFederatedLoginRequestRepository::new(
+            async_pool.clone(),
+        )),
+        sessions,
+        async_sessions,
+        refresh_tokens: Arc::new(DieselRefreshTokenRepository::new(pool.clone())),
+        async_refresh_tokens: Arc::new(AsyncDieselRefreshTokenRepository::new(
+            async_pool.clone(),
+        )),
+        login_throttles: Arc::new(DieselLoginThrottleRepository::new(pool.clone())),
+        async_login_throttles: Arc::new(AsyncDieselLoginThrottleRepository::new(
+            async_pool.clone(),
+        )),
+        mfa: Arc::new(DieselMfaRepository::new(pool.clone())),
+        async_mfa: Arc::new(AsyncDieselMfaRepository::new(async_pool.clone())),
+        async_mfa_challenges: Arc::new(AsyncDieselMfaChallengeRepository::new(
+            async_pool.clone(),
+        )),
+        email_verification_tokens: Arc::new(DieselEmailVerificationTokenRepository::new(
+            pool.clone(),
+        )),
+        async_email_verification_tokens: Arc::new(
+            AsyncDieselEmailVerificationTokenRepository::new(async_pool.clone()),
+        ),
+        password_reset_tokens: Arc::new(DieselPasswordResetTokenRepository::new(pool.clone())),
+        magic_link_tokens: Arc::new(DieselMagicLinkTokenRepository::new(pool.clone())),
+        async_magic_link_tokens: Arc::new(AsyncDieselMagicLinkTokenRepository::new(
+            async_pool.clone(),
+        )),
+        api_keys,
+        oauth_clients,
+        organizations: Arc::new(DieselOrganizationRepository::new(pool.clone())),
+        async_organizations: Arc::new(AsyncDieselOrganizationRepository::new(
+            async_pool.clone(),
+        )),
+        invitations: Arc::new(DieselInvitationRepository::new(pool.clone())),
+        personal_data: Arc::new(DieselPersonalDataRepository::new(pool.clone())),
+        audit_events,
+        async_password_hasher: password_hasher.clone(),
         password_hasher,
         token_service,
+        async_tokens: api_key_token_service,
+        key_ring,
+        token_generator,
+        refresh_token_ttl: chrono::Duration::seconds(config.refresh_token_expiration_secs),
+        mailer,
+        token_rejections: Arc::new(TokenRejectionAudit::new(audit_log.clone())),
+        audit_log,
+        async_audit_log: Arc::new(AsyncDieselAuditLog::new(async_pool)),
+        password_policy,
+        password_reset_ttl: chrono::Duration::seconds(config.password_reset_token_expiration_secs),
+        password_reset_url: config.password_reset_url.clone(),
+        email_verification_ttl: chrono::Duration::seconds(
+            config.email_verification_token_expiration_secs,
+        ),
+        email_verification_url: config.email_verification_url.clone(),
+        require_verified_email: config.require_email_verification,
+        totp_service: Arc::new(Rfc6238TotpService::new(config.mfa_issuer.clone())),
+        mfa_challenge_ttl: chrono::Duration::seconds(config.mfa_challenge_expiration_secs),
+        lockout_policy: config.lockout_policy(),
+        trust_forwarded_for: config.trust_forwarded_for,
+        identity_providers: config.identity_providers.clone(),
+        identity_provider_client: Arc::new(identity_provider_client),
+        federated_login_callback_url: config.federated_login_callback_url.clone(),
+        federated_login_ttl: chrono::Duration::seconds(config.federated_login_expiration_secs),
+        magic_link_ttl: chrono::Duration::seconds(config.magic_link_expiration_secs),
+        magic_link_url: config.magic_link_url.clone(),
+        magic_link_rate_limit: config.magic_link_rate_limit(),
+        impersonation_token_issuer,
+        impersonation_ttl: chrono::Duration::seconds(config.impersonation_expiration_secs),
+        organization_token_issuer,
+        invitation_ttl: chrono::Duration::seconds(config.invitation_expiration_secs),
+        invitation_url: config.invitation_url.clone(),
     });
 
     // Build HTTP router (with rate limiting + security middleware)
-    let app: Router = http::router::create_router(Arc::clone(&state), config.rate_limit_per_second);
+    let app: Router = http::router::create_router(
+        Arc::clone(&state),
+        oauth_state,
+        config.rate_limit_per_second,
+    );
 
-    // Build gRPC service
+    // Build gRPC services
     let grpc_service = AuthServiceGrpc::new(Arc::clone(&state));
+    let admin_grpc_service = AdminUserServiceGrpc::new(Arc::clone(&state));
+    let organization_grpc_service = OrganizationServiceGrpc::new(Arc::clone(&state));
+    let grpc_token_audit = TokenAuditLayer::new(Arc::clone(&state));
 
     // Start HTTP server
     let http_addr = format!("{}:{}", config.server_host, config.server_port);
@@ -133,10 +460,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     info!("Auth Service HTTP listening on {}", http_addr);
 
     let http_handle = tokio::spawn(async move {
-        axum::serve(listener, app)
-            .with_graceful_shutdown(shutdown_signal())
-            .await
-            .unwrap();
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .with_graceful_shutdown(shutdown_signal())
+        .await
+        .unwrap();
     });
 
     // Start gRPC server
@@ -147,7 +477,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let grpc_handle = tokio::spawn(async move {
         TonicServer::builder()
+            .layer(grpc_token_audit)
             .add_service(AuthServiceServer::new(grpc_service))
+            .add_service(AdminUserServiceServer::new(admin_grpc_service))
+            .add_service(OrganizationServiceServer::new(organization_grpc_service))
             .serve_with_shutdown(grpc_addr, shutdown_signal())
             .await
             .unwrap();
@@ -163,6 +496,80 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Ensure an active signing key exists for `algorithm` and load the key ring.
+///
+/// A key is generated on first start, or when the configured algorithm changes.
+fn init_key_ring(
+    pool: &DbPool,
+    config: &Config,
+    algorithm: SigningAlgorithm,
+) -> Result<Arc<JwtKeyRing>, AuthError> {
+    let repository = Arc::new(DieselSigningKeyRepository::new(pool.clone()));
+    let generator = RandomSigningKeyGenerator::new();
+
+    let use_case =
+        RotateSigningKeyUseCase::new(repository.as_ref(), &generator, config.signing_key_grace());
+    let result = use_case.execute(RotateSigningKeyCommand {
+        algorithm,
+        force: false,
+    })?;
+    info!("JWT signing key {} ({}) is active", result.kid, algorithm);
+
+    let key_ring = Arc::new(JwtKeyRing::new(
+        repository,
+        Duration::from_secs(config.jwt_key_refresh_secs),
+    ));
+    key_ring.reload()?;
+    Ok(key_ring)
+}
+
+/// Spawn a background task that periodically purges expired entries.
+fn spawn_purge<F>(what: &'static str, purge: F)
+where
+    F: Fn(chrono::DateTime<chrono::Utc>) -> Result<usize, AuthError> + Send + Sync + 'static,
+{
+    let purge = Arc::new(purge);
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PURGE_INTERVAL);
+        loop {
+            interval.tick().await;
+            let purge = Arc::clone(&purge);
+            match tokio::task::spawn_blocking(move || purge(chrono::Utc::now())).await {
+                Ok(Ok(purged)) => info!("Purged {} expired {}", purged, what),
+                Ok(Err(e)) => warn!("Failed to purge {}: {}", what, e),
+                Err(e) => warn!("Purge task for {} failed: {}", what, e),
+            }
+        }
+    });
+}
+
+/// Spawn the background task that publishes outbox events to the broker.
+///
+/// Events left unpublished by a failure are retried on the next tick. Every
+/// replica spawns one; ticks where another replica holds the outbox publish
+/// nothing.
+fn spawn_outbox_relay<R, P>(relay: OutboxRelay<R, P>, every: Duration)
+where
+    R: OutboxRepository + Send + Sync + ?Sized + 'static,
+    P: OutboxPublisher + Send + Sync + ?Sized + 'static,
+{
+    let relay = Arc::new(relay);
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(every);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            let relay = Arc::clone(&relay);
+            match tokio::task::spawn_blocking(move || relay.relay()).await {
+                Ok(Ok(0)) => {}
+                Ok(Ok(published)) => debug!("Published {} outbox events", published),
+                Ok(Err(e)) => warn!("Failed to publish outbox events: {}", e),
+                Err(e) => warn!("Outbox relay task failed: {}", e),
+            }
+        }
+    });
+}
+
 /// Listen for SIGTERM / SIGINT (Ctrl-C) for graceful shutdown.
 async fn shutdown_signal() {
     let ctrl_c = async {
@@ -0,0 +1,294 @@
+//! Tests of the identity provider client against a mock OpenID Connect issuer
+//!
+//! The issuer serves discovery, JWKS and token endpoints on a local port and
+//! hands out ID tokens signed with a fresh Ed25519 key.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Form, Json, Router};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use ed25519_dalek::pkcs8::EncodePrivateKey;
+use ed25519_dalek::SigningKey;
+use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+use rand::rngs::OsRng;
+use rsa::pkcs8::LineEnding;
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+
+use auth_service::domain::error::AuthError;
+use auth_service::domain::federation::{
+    AsyncIdentityProviderClient, ExternalIdentity, IdentityProvider,
+};
+use auth_service::infrastructure::federation::http_identity_provider_client::HttpIdentityProviderClient;
+
+const CLIENT_ID: &str = "ticketing";
+const CLIENT_SECRET: &str = "upstream-secret";
+const CODE: &str = "upstream-code";
+const VERIFIER: &str = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+const NONCE: &str = "n-0S6_WzA2Mj";
+const KID: &str = "issuer-key-1";
+const REDIRECT_URI: &str = "http://app.example.com/sso/callback";
+
+#[derive(Clone)]
+struct IssuerState {
+    issuer: String,
+    jwks: Value,
+    /// ID token handed out for the next code
+    id_token: Arc<Mutex<String>>,
+}
+
+/// OpenID Connect issuer serving a fixed key and a settable ID token
+struct MockIssuer {
+    provider: IdentityProvider,
+    key: SigningKey,
+    id_token: Arc<Mutex<String>>,
+}
+
+impl MockIssuer {
+    async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let issuer = format!("http://{}", listener.local_addr().unwrap());
+        let key = SigningKey::generate(&mut OsRng);
+        let id_token = Arc::new(Mutex::new(String::new()));
+
+        let state = IssuerState {
+            issuer: issuer.clone(),
+            jwks: json!({
+                "keys": [{
+                    "kty": "OKP",
+                    "crv": "Ed25519",
+                    "use": "sig",
+                    "alg": "EdDSA",
+                    "kid": KID,
+                    "x": URL_SAFE_NO_PAD.encode(key.verifying_key().to_bytes()),
+                }]
+            }),
+            id_token: Arc::clone(&id_token),
+        };
+        let app = Router::new()
+            .route("/.well-known/openid-configuration", get(discovery))
+            .route("/jwks", get(jwks))
+            .route("/token", post(token))
+            .with_state(state);
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        Self {
+            provider: IdentityProvider {
+                id: "corp".to_string(),
+                name: "Corp SSO".to_string(),
+                issuer,
+                client_id: CLIENT_ID.to_string(),
+                client_secret: Some(CLIENT_SECRET.to_string()),
+            },
+            key,
+            id_token,
+        }
+    }
+
+    /// Claims of a valid ID token
+    fn claims(&self) -> Value {
+        let now = chrono::Utc::now().timestamp();
+        json!({
+            "iss": self.provider.issuer,
+            "aud": CLIENT_ID,
+            "sub": "248289761001",
+            "iat": now,
+            "exp": now + 300,
+            "nonce": NONCE,
+            "email": "jane@example.com",
+            "email_verified": "true",
+            "name": "Jane Doe",
+        })
+    }
+
+    /// Have the token endpoint hand out an ID token with `claims`, signed by `key`
+    fn issue(&self, claims: &Value, key: &SigningKey) {
+        let pem = key.to_pkcs8_pem(LineEnding::LF).unwrap();
+        let mut header = Header::new(Algorithm::EdDSA);
+        header.kid = Some(KID.to_string());
+        let token = encode(
+            &header,
+            claims,
+            &EncodingKey::from_ed_pem(pem.as_bytes()).unwrap(),
+        )
+        .unwrap();
+        *self.id_token.lock().unwrap() = token;
+    }
+
+    /// Redeem a code with a new client
+    async fn redeem(&self, code: &str) -> Result<ExternalIdentity, AuthError> {
+        let client = HttpIdentityProviderClient::new(Duration::from_secs(5)).with_plain_http();
+        client
+            .redeem_code(&self.provider, code, REDIRECT_URI, VERIFIER, NONCE)
+            .await
+    }
+}
+
+async fn discovery(State(state): State<IssuerState>) -> Json<Value> {
+    Json(json!({
+        "issuer": state.issuer,
+        "authorization_endpoint": format!("{}/authorize", state.issuer),
+        "token_endpoint": format!("{}/token", state.issuer),
+        "jwks_uri": format!("{}/jwks", state.issuer),
+    }))
+}
+
+async fn jwks(State(state): State<IssuerState>) -> Json<Value> {
+    Json(state.jwks)
+}
+
+async fn token(
+    State(state): State<IssuerState>,
+    Form(form): Form<HashMap<String, String>>,
+) -> Response {
+    let field = |name: &str| form.get(name).map(String::as_str);
+    let valid = field("grant_type") == Some("authorization_code")
+        && field("code") == Some(CODE)
+        && field("code_verifier") == Some(VERIFIER)
+        && field("redirect_uri") == Some(REDIRECT_URI)
+        && field("client_id") == Some(CLIENT_ID)
+        && field("client_secret") == Some(CLIENT_SECRET);
+    if !valid {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "error": "invalid_grant" })),
+        )
+            .into_response();
+    }
+
+    let id_token = state.id_token.lock().unwrap().clone();
+    Json(json!({
+        "access_token": "upstream-access-token",
+        "token_type": "Bearer",
+        "id_token": id_token,
+    }))
+    .into_response()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_redeem_code_returns_verified_identity() {
+    let issuer = MockIssuer::start().await;
+    issuer.issue(&issuer.claims(), &issuer.key);
+
+    let identity = issuer.redeem(CODE).await.unwrap();
+
+    assert_eq!(identity.subject, "248289761001");
+    assert_eq!(identity.verified_email(), Some("jane@example.com"));
+    assert_eq!(identity.name.as_deref(), Some("Jane Doe"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_authorization_url_points_at_discovered_endpoint() {
+    let issuer = MockIssuer::start().await;
+    let client = HttpIdentityProviderClient::new(Duration::from_secs(5)).with_plain_http();
+
+    let url = client
+        .authorization_url(&issuer.provider, REDIRECT_URI, "state-1", NONCE, "challenge")
+        .await
+        .unwrap();
+
+    assert!(url.starts_with(&format!("{}/authorize?", issuer.provider.issuer)));
+    assert!(url.contains("response_type=code"));
+    assert!(url.contains("client_id=ticketing"));
+    assert!(url.contains("scope=openid+email+profile"));
+    assert!(url.contains("state=state-1"));
+    assert!(url.contains("code_challenge_method=S256"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_plain_http_provider_refused_by_default() {
+    let issuer = MockIssuer::start().await;
+    let client = HttpIdentityProviderClient::new(Duration::from_secs(5));
+
+    let result = client
+        .authorization_url(&issuer.provider, REDIRECT_URI, "state-1", NONCE, "challenge")
+        .await;
+
+    assert!(
+        matches!(result, Err(AuthError::IdentityProvider(message)) if message.contains("https://"))
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_rejected_code() {
+    let issuer = MockIssuer::start().await;
+    issuer.issue(&issuer.claims(), &issuer.key);
+
+    let result = issuer.redeem("stolen-code").await;
+
+    assert!(
+        matches!(result, Err(AuthError::IdentityProvider(message)) if message.contains("invalid_grant"))
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_id_token_must_match_the_sign_in() {
+    let issuer = MockIssuer::start().await;
+
+    // Replayed from another sign-in
+    let mut claims = issuer.claims();
+    claims["nonce"] = json!("other-nonce");
+    issuer.issue(&claims, &issuer.key);
+    assert!(matches!(
+        issuer.redeem(CODE).await,
+        Err(AuthError::IdentityProvider(_))
+    ));
+
+    // Issued to another client
+    let mut claims = issuer.claims();
+    claims["aud"] = json!("other-client");
+    issuer.issue(&claims, &issuer.key);
+    assert!(matches!(
+        issuer.redeem(CODE).await,
+        Err(AuthError::IdentityProvider(_))
+    ));
+
+    // Issued by someone else
+    let mut claims = issuer.claims();
+    claims["iss"] = json!("http://evil.example.com");
+    issuer.issue(&claims, &issuer.key);
+    assert!(matches!(
+        issuer.redeem(CODE).await,
+        Err(AuthError::IdentityProvider(_))
+    ));
+
+    // Expired
+    let mut claims = issuer.claims();
+    claims["exp"] = json!(chrono::Utc::now().timestamp() - 3600);
+    issuer.issue(&claims, &issuer.key);
+    assert!(matches!(
+        issuer.redeem(CODE).await,
+        Err(AuthError::IdentityProvider(_))
+    ));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_id_token_signed_with_another_key() {
+    let issuer = MockIssuer::start().await;
+    let forged_key = SigningKey::generate(&mut OsRng);
+    issuer.issue(&issuer.claims(), &forged_key);
+
+    let result = issuer.redeem(CODE).await;
+
+    assert!(matches!(result, Err(AuthError::IdentityProvider(_))));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_unverified_email_is_not_vouched_for() {
+    let issuer = MockIssuer::start().await;
+    let mut claims = issuer.claims();
+    claims["email_verified"] = json!(false);
+    issuer.issue(&claims, &issuer.key);
+
+    let identity = issuer.redeem(CODE).await.unwrap();
+
+    assert_eq!(identity.email.as_deref(), Some("jane@example.com"));
+    assert_eq!(identity.verified_email(), None);
+}
@@ -0,0 +1,846 @@
+//! Account and session routes over HTTP and gRPC without a database
+//!
+//! Builds `AppState` around the in-memory user, session, refresh token,
+//! revocation and login throttle stores, behind both their blocking and
+//! async traits, and drives the real router and gRPC service in-process.
+//! The remaining stores have no in-memory implementation and fail every
+//! call, so only registration, login, lockouts, `/auth/me`, refresh and
+//! logout are exercised here.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use axum::Router;
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use auth_service::domain::api_key::{ApiKey, ApiKeyRepository};
+use auth_service::domain::audit::{
+    AsyncAuditLog, AuditCursor, AuditEvent, AuditEventRepository, AuditFilter, AuditLog,
+    AuditPage,
+};
+use auth_service::domain::auth::BoxFuture;
+use auth_service::domain::email_verification::{
+    AsyncEmailVerificationTokenRepository, EmailVerificationToken,
+    EmailVerificationTokenRepository,
+};
+use auth_service::domain::error::AuthError;
+use auth_service::domain::federation::{
+    AsyncFederatedLoginRequestRepository, AsyncUserIdentityRepository, FederatedLoginRequest,
+    UserIdentity,
+};
+use auth_service::domain::health::HealthCheck;
+use auth_service::domain::lockout::LockoutPolicy;
+use auth_service::domain::magic_link::{
+    AsyncMagicLinkTokenRepository, MagicLinkRateLimit, MagicLinkToken, MagicLinkTokenRepository,
+};
+use auth_service::domain::mfa::{
+    AsyncMfaChallengeRepository, AsyncMfaRepository, MfaChallenge, MfaRepository, TotpCredential,
+};
+use auth_service::domain::oauth_client::{OAuthClient, OAuthClientRepository};
+use auth_service::domain::oidc::{
+    AuthorizationCode, AuthorizationCodeRepository, OidcClient, OidcClientRepository,
+};
+use auth_service::domain::organization::{
+    AsyncOrganizationRepository, Invitation, InvitationRepository, Membership, Organization,
+    OrganizationRepository,
+};
+use auth_service::domain::password_policy::{PasswordPolicy, PasswordRules};
+use auth_service::domain::password_reset::{PasswordResetToken, PasswordResetTokenRepository};
+use auth_service::domain::personal_data::{PersonalDataRepository, SessionRecord};
+use auth_service::domain::user::User;
+use auth_service::infrastructure::federation::http_identity_provider_client::HttpIdentityProviderClient;
+use auth_service::infrastructure::mail::log_mailer::LogMailer;
+use auth_service::infrastructure::memory::in_memory_login_throttle_repository::InMemoryLoginThrottleRepository;
+use auth_service::infrastructure::memory::in_memory_refresh_token_repository::InMemoryRefreshTokenRepository;
+use auth_service::infrastructure::memory::in_memory_session_repository::InMemorySessionRepository;
+use auth_service::infrastructure::memory::in_memory_token_revocation_store::InMemoryTokenRevocationStore;
+use auth_service::infrastructure::memory::in_memory_user_repository::InMemoryUserRepository;
+use auth_service::infrastructure::security::argon2_password_hasher::Argon2PasswordHasher;
+use auth_service::infrastructure::security::jwt_token_service::JwtTokenService;
+use auth_service::infrastructure::security::opaque_token_generator::RandomOpaqueTokenGenerator;
+use auth_service::infrastructure::security::totp_service::Rfc6238TotpService;
+use auth_service::interface::grpc::service::pb::auth_service_server::AuthService;
+use auth_service::interface::grpc::service::pb::{GetMeRequest, LoginRequest, RegisterRequest};
+use auth_service::interface::grpc::service::AuthServiceGrpc;
+use auth_service::interface::http::router::create_router;
+use auth_service::interface::token_audit::TokenRejectionAudit;
+use auth_service::{AppState, OAuthState};
+
+const SECRET: &str = "test-secret";
+const PASSWORD: &str = "correct horse battery staple";
+
+fn not_under_test<T>() -> Result<T, AuthError> {
+    Err(AuthError::Internal("not under test".to_string()))
+}
+
+// There is no database to be unhealthy
+struct AlwaysHealthy;
+
+impl HealthCheck for AlwaysHealthy {
+    fn is_healthy(&self) -> bool {
+        true
+    }
+}
+
+// Nobody in these tests has enrolled a second factor
+struct NoMfa;
+
+impl MfaRepository for NoMfa {
+    fn find_totp(&self, _user_id: Uuid) -> Result<Option<TotpCredential>, AuthError> {
+        Ok(None)
+    }
+
+    fn save_pending_totp(&self, _credential: &TotpCredential) -> Result<(), AuthError> {
+        not_under_test()
+    }
+
+    fn activate_totp(
+        &self,
+        _user_id: Uuid,
+        _step: i64,
+        _recovery_code_hashes: &[String],
+    ) -> Result<bool, AuthError> {
+        not_under_test()
+    }
+
+}
+
+impl AsyncMfaRepository for NoMfa {
+    fn find_totp(&self, _user_id: Uuid) -> BoxFuture<'_, Result<Option<TotpCredential>, AuthError>> {
+        Box::pin(async { Ok(None) })
+    }
+
+    fn record_totp_step(&self, _user_id: Uuid, _step: i64) -> BoxFuture<'_, Result<bool, AuthError>> {
+        Box::pin(async { not_under_test() })
+    }
+
+    fn has_recovery_code<'a>(
+        &'a self,
+        _user_id: Uuid,
+        _code_hash: &'a str,
+    ) -> BoxFuture<'a, Result<bool, AuthError>> {
+        Box::pin(async { not_under_test() })
+    }
+
+    fn consume_recovery_code<'a>(
+        &'a self,
+        _user_id: Uuid,
+        _code_hash: &'a str,
+    ) -> BoxFuture<'a, Result<bool, AuthError>> {
+        Box::pin(async { not_under_test() })
+    }
+}
+
+// Verification links are mailed on registration but never followed here
+struct DiscardedVerificationTokens;
+
+impl EmailVerificationTokenRepository for DiscardedVerificationTokens {
+    fn create(&self, _token: &EmailVerificationToken) -> Result<(), AuthError> {
+        Ok(())
+    }
+
+    fn find_by_hash(&self, _token_hash: &str) -> Result<EmailVerificationToken, AuthError> {
+        not_under_test()
+    }
+
+    fn consume(&self, _token: &EmailVerificationToken) -> Result<bool, AuthError> {
+        not_under_test()
+    }
+
+    fn purge_expired(&self, _now: DateTime<Utc>) -> Result<usize, AuthError> {
+        not_under_test()
+    }
+}
+
+impl AsyncEmailVerificationTokenRepository for DiscardedVerificationTokens {
+    fn create<'a>(
+        &'a self,
+        _token: &'a EmailVerificationToken,
+    ) -> BoxFuture<'a, Result<(), AuthError>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+// The audit trail is not under test here
+struct NoAuditLog;
+
+impl AuditLog for NoAuditLog {
+    fn record(&self, _event: AuditEvent) {}
+}
+
+impl AsyncAuditLog for NoAuditLog {
+    fn record(&self, _event: AuditEvent) -> BoxFuture<'_, ()> {
+        Box::pin(async {})
+    }
+}
+
+/// Every other store, none of which these tests reach
+struct Unused;
+
+impl AsyncUserIdentityRepository for Unused {
+    fn find<'a>(
+        &'a self,
+        _provider: &'a str,
+        _subject: &'a str,
+    ) -> BoxFuture<'a, Result<Option<UserIdentity>, AuthError>> {
+        Box::pin(async { not_under_test() })
+    }
+
+    fn create<'a>(&'a self, _identity: &'a UserIdentity) -> BoxFuture<'a, Result<(), AuthError>> {
+        Box::pin(async { not_under_test() })
+    }
+
+    fn create_with_user<'a>(
+        &'a self,
+        _user: &'a User,
+        _identity: &'a UserIdentity,
+    ) -> BoxFuture<'a, Result<(), AuthError>> {
+        Box::pin(async { not_under_test() })
+    }
+
+    fn record_login<'a>(
+        &'a self,
+        _provider: &'a str,
+        _subject: &'a str,
+        _at: DateTime<Utc>,
+    ) -> BoxFuture<'a, Result<(), AuthError>> {
+        Box::pin(async { not_under_test() })
+    }
+}
+
+impl AsyncFederatedLoginRequestRepository for Unused {
+    fn create<'a>(
+        &'a self,
+        _request: &'a FederatedLoginRequest,
+    ) -> BoxFuture<'a, Result<(), AuthError>> {
+        Box::pin(async { not_under_test() })
+    }
+
+    fn take<'a>(
+        &'a self,
+        _state_hash: &'a str,
+    ) -> BoxFuture<'a, Result<Option<FederatedLoginRequest>, AuthError>> {
+        Box::pin(async { not_under_test() })
+    }
+}
+
+impl AsyncMfaChallengeRepository for Unused {
+    fn create<'a>(&'a self, _challenge: &'a MfaChallenge) -> BoxFuture<'a, Result<(), AuthError>> {
+        Box::pin(async { not_under_test() })
+    }
+
+    fn find_by_hash<'a>(
+        &'a self,
+        _token_hash: &'a str,
+    ) -> BoxFuture<'a, Result<MfaChallenge, AuthError>> {
+        Box::pin(async { not_under_test() })
+    }
+
+    fn record_failed_attempt<'a>(
+        &'a self,
+        _challenge: &'a MfaChallenge,
+    ) -> BoxFuture<'a, Result<i32, AuthError>> {
+        Box::pin(async { not_under_test() })
+    }
+
+    fn consume<'a>(&'a self, _challenge: &'a MfaChallenge) -> BoxFuture<'a, Result<bool, AuthError>> {
+        Box::pin(async { not_under_test() })
+    }
+}
+
+impl PasswordResetTokenRepository for Unused {
+    fn create(&self, _token: &PasswordResetToken) -> Result<(), AuthError> {
+        not_under_test()
+    }
+
+    fn find_by_hash(&self, _token_hash: &str) -> Result<PasswordResetToken, AuthError> {
+        not_under_test()
+    }
+
+    fn consume(&self, _token: &PasswordResetToken) -> Result<bool, AuthError> {
+        not_under_test()
+    }
+
+    fn purge_expired(&self, _now: DateTime<Utc>) -> Result<usize, AuthError> {
+        not_under_test()
+    }
+}
+
+impl MagicLinkTokenRepository for Unused {
+    fn create(&self, _token: &MagicLinkToken) -> Result<(), AuthError> {
+        not_under_test()
+    }
+
+    fn count_issued_since(&self, _user_id: Uuid, _since: DateTime<Utc>) -> Result<i64, AuthError> {
+        not_under_test()
+    }
+
+    fn purge_expired(&self, _cutoff: DateTime<Utc>) -> Result<usize, AuthError> {
+        not_under_test()
+    }
+}
+
+impl AsyncMagicLinkTokenRepository for Unused {
+    fn find_by_hash<'a>(
+        &'a self,
+        _token_hash: &'a str,
+    ) -> BoxFuture<'a, Result<MagicLinkToken, AuthError>> {
+        Box::pin(async { not_under_test() })
+    }
+
+    fn consume<'a>(&'a self, _token: &'a MagicLinkToken) -> BoxFuture<'a, Result<bool, AuthError>> {
+        Box::pin(async { not_under_test() })
+    }
+}
+
+impl ApiKeyRepository for Unused {
+    fn create(&self, _key: &ApiKey) -> Result<(), AuthError> {
+        not_under_test()
+    }
+
+    fn find_by_hash(&self, _key_hash: &str) -> Result<ApiKey, AuthError> {
+        not_under_test()
+    }
+
+    fn find_by_id(&self, _id: Uuid) -> Result<ApiKey, AuthError> {
+        not_under_test()
+    }
+
+    fn list_for_user(&self, _user_id: Uuid) -> Result<Vec<ApiKey>, AuthError> {
+        not_under_test()
+    }
+
+    fn revoke(&self, _id: Uuid) -> Result<bool, AuthError> {
+        not_under_test()
+    }
+
+    fn record_use(&self, _id: Uuid, _at: DateTime<Utc>) -> Result<(), AuthError> {
+        not_under_test()
+    }
+}
+
+impl OAuthClientRepository for Unused {
+    fn create(&self, _client: &OAuthClient) -> Result<(), AuthError> {
+        not_under_test()
+    }
+
+    fn find_by_id(&self, _client_id: Uuid) -> Result<OAuthClient, AuthError> {
+        not_under_test()
+    }
+
+    fn list(&self) -> Result<Vec<OAuthClient>, AuthError> {
+        not_under_test()
+    }
+
+    fn revoke(&self, _client_id: Uuid) -> Result<bool, AuthError> {
+        not_under_test()
+    }
+}
+
+impl OidcClientRepository for Unused {
+    fn save(&self, _client: &OidcClient) -> Result<OidcClient, AuthError> {
+        not_under_test()
+    }
+
+    fn find_by_id(&self, _client_id: &str) -> Result<OidcClient, AuthError> {
+        not_under_test()
+    }
+}
+
+impl AuthorizationCodeRepository for Unused {
+    fn create(&self, _code: &AuthorizationCode) -> Result<(), AuthError> {
+        not_under_test()
+    }
+
+    fn take(&self, _code_hash: &str) -> Result<Option<AuthorizationCode>, AuthError> {
+        not_under_test()
+    }
+
+    fn purge_expired(&self, _now: DateTime<Utc>) -> Result<usize, AuthError> {
+        not_under_test()
+    }
+}
+
+impl OrganizationRepository for Unused {
+    fn create(&self, _organization: &Organization, _owner: &Membership) -> Result<(), AuthError> {
+        not_under_test()
+    }
+
+    fn find_by_id(&self, _id: Uuid) -> Result<Organization, AuthError> {
+        not_under_test()
+    }
+
+    fn find_membership(
+        &self,
+        _organization_id: Uuid,
+        _user_id: Uuid,
+    ) -> Result<Option<Membership>, AuthError> {
+        not_under_test()
+    }
+
+    fn list_for_user(&self, _user_id: Uuid) -> Result<Vec<(Organization, Membership)>, AuthError> {
+        not_under_test()
+    }
+}
+
+impl AsyncOrganizationRepository for Unused {
+    fn find_membership(
+        &self,
+        _organization_id: Uuid,
+        _user_id: Uuid,
+    ) -> BoxFuture<'_, Result<Option<Membership>, AuthError>> {
+        Box::pin(async { not_under_test() })
+    }
+}
+
+impl InvitationRepository for Unused {
+    fn create(&self, _invitation: &Invitation) -> Result<(), AuthError> {
+        not_under_test()
+    }
+
+    fn find_by_hash(&self, _token_hash: &str) -> Result<Invitation, AuthError> {
+        not_under_test()
+    }
+
+    fn accept(
+        &self,
+        _invitation: &Invitation,
+        _membership: &Membership,
+    ) -> Result<bool, AuthError> {
+        not_under_test()
+    }
+
+    fn purge_expired(&self, _cutoff: DateTime<Utc>) -> Result<usize, AuthError> {
+        not_under_test()
+    }
+}
+
+impl PersonalDataRepository for Unused {
+    fn find_sessions(&self, _user_id: Uuid) -> Result<Vec<SessionRecord>, AuthError> {
+        not_under_test()
+    }
+
+    fn find_identities(&self, _user_id: Uuid) -> Result<Vec<UserIdentity>, AuthError> {
+        not_under_test()
+    }
+
+    fn find_organizations(
+        &self,
+        _user_id: Uuid,
+    ) -> Result<Vec<(Organization, Membership)>, AuthError> {
+        not_under_test()
+    }
+
+    fn find_audit_events(&self, _user_id: Uuid) -> Result<Vec<AuditEvent>, AuthError> {
+        not_under_test()
+    }
+
+    fn erase(&self, _user: &User, _previous_email: &str) -> Result<(), AuthError> {
+        not_under_test()
+    }
+}
+
+impl AuditEventRepository for Unused {
+    fn search(
+        &self,
+        _filter: &AuditFilter,
+        _cursor: Option<&AuditCursor>,
+        _limit: usize,
+    ) -> Result<AuditPage, AuthError> {
+        not_under_test()
+    }
+
+    fn purge_older_than(&self, _cutoff: DateTime<Utc>) -> Result<usize, AuthError> {
+        not_under_test()
+    }
+}
+
+struct TestApp {
+    router: Router,
+    grpc: AuthServiceGrpc,
+}
+
+impl TestApp {
+    fn new() -> Self {
+        let users = Arc::new(InMemoryUserRepository::new());
+        let sessions = Arc::new(InMemorySessionRepository::new());
+        let refresh_tokens = Arc::new(InMemoryRefreshTokenRepository::new(sessions.clone()));
+        let login_throttles = Arc::new(InMemoryLoginThrottleRepository::new());
+        let revocations = Arc::new(InMemoryTokenRevocationStore::new());
+        let tokens = Arc::new(
+            JwtTokenService::new(SECRET.to_string(), 3600)
+                .with_revocation_store(revocations.clone())
+                .with_session_store(sessions.clone())
+                .with_async_revocation_store(revocations)
+                .with_async_session_store(sessions.clone()),
+        );
+        let password_hasher = Arc::new(Argon2PasswordHasher::new());
+        let unused = Arc::new(Unused);
+        let state = Arc::new(AppState {
+            database: Arc::new(AlwaysHealthy),
+            users: users.clone(),
+            async_users: users.clone(),
+            user_search: users.clone(),
+            user_identities: unused.clone(),
+            federated_login_requests: unused.clone(),
+            sessions: sessions.clone(),
+            async_sessions: sessions.clone(),
+            refresh_tokens: refresh_tokens.clone(),
+            async_refresh_tokens: refresh_tokens,
+            login_throttles: login_throttles.clone(),
+            async_login_throttles: login_throttles,
+            mfa: Arc::new(NoMfa),
+            async_mfa: Arc::new(NoMfa),
+            async_mfa_challenges: unused.clone(),
+            email_verification_tokens: Arc::new(DiscardedVerificationTokens),
+            async_email_verification_tokens: Arc::new(DiscardedVerificationTokens),
+            password_reset_tokens: unused.clone(),
+            magic_link_tokens: unused.clone(),
+            async_magic_link_tokens: unused.clone(),
+            api_keys: unused.clone(),
+            oauth_clients: unused.clone(),
+            organizations: unused.clone(),
+            async_organizations: unused.clone(),
+            invitations: unused.clone(),
+            personal_data: unused.clone(),
+            audit_events: unused.clone(),
+            password_hasher: password_hasher.clone(),
+            async_password_hasher: password_hasher,
+            token_service: tokens.clone(),
+            async_tokens: tokens.clone(),
+            key_ring: None,
+            token_generator: Arc::new(RandomOpaqueTokenGenerator::new()),
+            refresh_token_ttl: chrono::Duration::days(30),
+            mailer: Arc::new(LogMailer::new()),
+            audit_log: Arc::new(NoAuditLog),
+            async_audit_log: Arc::new(NoAuditLog),
+            token_rejections: Arc::new(TokenRejectionAudit::new(Arc::new(NoAuditLog))),
+            password_policy: PasswordPolicy::new(PasswordRules::default()),
+            password_reset_ttl: chrono::Duration::hours(1),
+            password_reset_url: "https://app.example.com/reset-password".to_string(),
+            email_verification_ttl: chrono::Duration::days(1),
+            email_verification_url: "https://app.example.com/verify-email".to_string(),
+            require_verified_email: false,
+            totp_service: Arc::new(Rfc6238TotpService::new("Ticketing".to_string())),
+            mfa_challenge_ttl: chrono::Duration::minutes(5),
+            lockout_policy: LockoutPolicy {
+                email_threshold: 5,
+                ip_threshold: 20,
+                base_lockout: chrono::Duration::seconds(30),
+                max_lockout: chrono::Duration::minutes(15),
+                reset_after: chrono::Duration::minutes(15),
+            },
+            trust_forwarded_for: false,
+            identity_providers: Vec::new(),
+            identity_provider_client: Arc::new(HttpIdentityProviderClient::new(Duration::from_secs(5))),
+            federated_login_callback_url: "https://app.example.com/sso/callback".to_string(),
+            federated_login_ttl: chrono::Duration::minutes(10),
+            magic_link_ttl: chrono::Duration::minutes(15),
+            magic_link_url: "https://app.example.com/magic-link".to_string(),
+            magic_link_rate_limit: MagicLinkRateLimit {
+                max_links: 3,
+                window: chrono::Duration::hours(1),
+            },
+            impersonation_token_issuer: tokens.clone(),
+            impersonation_ttl: chrono::Duration::minutes(15),
+            organization_token_issuer: tokens.clone(),
+            invitation_ttl: chrono::Duration::days(7),
+            invitation_url: "https://app.example.com/invitations".to_string(),
+        });
+        let oauth_state = Arc::new(OAuthState {
+            users,
+            sessions,
+            token_service: tokens.clone(),
+            token_generator: Arc::new(RandomOpaqueTokenGenerator::new()),
+            oauth_clients: unused.clone(),
+            service_token_issuer: tokens.clone(),
+            oidc_clients: unused.clone(),
+            authorization_codes: unused,
+            id_token_issuer: tokens,
+            audit_log: Arc::new(NoAuditLog),
+            trust_forwarded_for: false,
+            oidc_provider_enabled: false,
+            issuer: "https://auth.example.com".to_string(),
+            login_url: "https://app.example.com/login".to_string(),
+            id_token_signing_alg: "HS256".to_string(),
+            access_token_ttl: chrono::Duration::seconds(3600),
+            authorization_code_ttl: chrono::Duration::seconds(60),
+        });
+
+        Self {
+            router: create_router(Arc::clone(&state), oauth_state, 0),
+            grpc: AuthServiceGrpc::new(state),
+        }
+    }
+
+    async fn send(&self, request: Request<Body>) -> (StatusCode, Value) {
+        let response = self.router.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+        (status, body)
+    }
+
+    async fn post(&self, uri: &str, body: Value) -> (StatusCode, Value) {
+        let request = Request::post(uri)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        self.send(request).await
+    }
+
+    async fn post_with_token(&self, uri: &str, token: &str, body: Value) -> (StatusCode, Value) {
+        let request = Request::post(uri)
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        self.send(request).await
+    }
+
+    async fn me(&self, token: &str) -> (StatusCode, Value) {
+        let request = Request::get("/auth/me")
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap();
+        self.send(request).await
+    }
+
+    /// Register `email` and log in, returning the login response
+    async fn login(&self, email: &str) -> Value {
+        let (status, _) = self
+            .post(
+                "/auth/register",
+                json!({ "email": email, "password": PASSWORD }),
+            )
+            .await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        let (status, login) = self
+            .post(
+                "/auth/login",
+                json!({ "email": email, "password": PASSWORD }),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "{}", login);
+        login
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_health_without_database() {
+    let app = TestApp::new();
+
+    let (status, body) = app
+        .send(Request::get("/health").body(Body::empty()).unwrap())
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["status"], "healthy");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_register_login_and_me_over_http() {
+    let app = TestApp::new();
+
+    let (status, registered) = app
+        .post(
+            "/auth/register",
+            json!({ "email": "Ada@Example.com", "password": PASSWORD, "display_name": "Ada" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CREATED, "{}", registered);
+    assert_eq!(registered["email"], "ada@example.com");
+
+    let (status, _) = app
+        .post(
+            "/auth/login",
+            json!({ "email": "ada@example.com", "password": "not the password" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, login) = app
+        .post(
+            "/auth/login",
+            json!({ "email": "ADA@example.com", "password": PASSWORD }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", login);
+    assert_eq!(login["user_id"], registered["user_id"]);
+
+    let (status, me) = app.me(login["token"].as_str().unwrap()).await;
+    assert_eq!(status, StatusCode::OK, "{}", me);
+    assert_eq!(me["user_id"], registered["user_id"]);
+    assert_eq!(me["display_name"], "Ada");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_email_registers_once() {
+    let app = TestApp::new();
+    let body = json!({ "email": "ada@example.com", "password": PASSWORD });
+
+    let (status, _) = app.post("/auth/register", body.clone()).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let (status, _) = app.post("/auth/register", body).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_register_login_and_me_over_grpc() {
+    let app = TestApp::new();
+
+    let registered = app
+        .grpc
+        .register(tonic::Request::new(RegisterRequest {
+            email: "ada@example.com".to_string(),
+            password: PASSWORD.to_string(),
+            display_name: None,
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+
+    let login = app
+        .grpc
+        .login(tonic::Request::new(LoginRequest {
+            email: "ada@example.com".to_string(),
+            password: PASSWORD.to_string(),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(!login.mfa_required);
+
+    let me = app
+        .grpc
+        .get_me(tonic::Request::new(GetMeRequest { token: login.token }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(me.user_id, registered.user_id);
+    assert_eq!(me.roles, ["customer"]);
+
+    let invalid = app
+        .grpc
+        .get_me(tonic::Request::new(GetMeRequest {
+            token: "not-a-token".to_string(),
+        }))
+        .await
+        .unwrap_err();
+    assert_eq!(invalid.code(), tonic::Code::Unauthenticated);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_me_with_invalid_token() {
+    let app = TestApp::new();
+
+    let (status, body) = app.me("invalid_token").await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["error"], "invalid_token");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_login_with_wrong_password() {
+    let app = TestApp::new();
+    app.login("ada@example.com").await;
+
+    let (status, body) = app
+        .post(
+            "/auth/login",
+            json!({ "email": "ada@example.com", "password": "not the password" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["error"], "invalid_credentials");
+
+    let (status, _) = app
+        .post(
+            "/auth/login",
+            json!({ "email": "nobody@example.com", "password": PASSWORD }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_repeated_failures_lock_the_login() {
+    let app = TestApp::new();
+    app.login("ada@example.com").await;
+    let wrong = json!({ "email": "ada@example.com", "password": "not the password" });
+
+    for _ in 0..5 {
+        let (status, _) = app.post("/auth/login", wrong.clone()).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    let (status, body) = app
+        .post(
+            "/auth/login",
+            json!({ "email": "ada@example.com", "password": PASSWORD }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(body["error"], "account_locked");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_refresh_rotates_the_refresh_token() {
+    let app = TestApp::new();
+    let login = app.login("ada@example.com").await;
+    let first = login["refresh_token"].clone();
+
+    let (status, refreshed) = app
+        .post("/auth/refresh", json!({ "refresh_token": first }))
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", refreshed);
+    assert_eq!(refreshed["user_id"], login["user_id"]);
+    assert_ne!(refreshed["refresh_token"], first);
+
+    let (status, _) = app.me(refreshed["token"].as_str().unwrap()).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Replaying the rotated token ends the session
+    let (status, _) = app
+        .post("/auth/refresh", json!({ "refresh_token": first }))
+        .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, body) = app.me(refreshed["token"].as_str().unwrap()).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["error"], "token_revoked");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_logout_revokes_the_access_and_refresh_tokens() {
+    let app = TestApp::new();
+    let login = app.login("ada@example.com").await;
+    let token = login["token"].as_str().unwrap();
+
+    let (status, _) = app
+        .post_with_token(
+            "/auth/logout",
+            token,
+            json!({ "refresh_token": login["refresh_token"] }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+
+    let (status, body) = app.me(token).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["error"], "token_revoked");
+
+    let (status, _) = app
+        .post(
+            "/auth/refresh",
+            json!({ "refresh_token": login["refresh_token"] }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
@@ -1,133 +1,7 @@
 //! Integration tests for Auth Service
 //!
-//! Note: These tests require a running PostgreSQL database.
-//! Set DATABASE_URL environment variable before running.
-//!
-//! For CI/CD, use a test container or dedicated test database.
-
-use std::sync::Arc;
-
-use axum::{
-    body::Body,
-    http::{header, Request, StatusCode},
-};
-use serde_json::{json, Value};
-use tower::ServiceExt;
-
-// Note: These integration tests demonstrate the test structure.
-// In a real environment, you would:
-// 1. Use testcontainers or a test database
-// 2. Set up proper test fixtures
-// 3. Clean up data between tests
-
-/// Test helper to create a request body
-fn json_body(body: Value) -> Body {
-    Body::from(serde_json::to_vec(&body).unwrap())
-}
-
-/// Test health endpoint (doesn't require database)
-#[tokio::test]
-async fn test_health_endpoint() {
-    // This test would require setting up the full app
-    // Skipped in basic integration test suite
-    // In production, use test fixtures with proper database setup
-}
-
-/// Example of how to structure integration tests
-/// These would be run against a real test database
-mod with_database {
-    use super::*;
-
-    /// Test user registration flow
-    /// Requires: DATABASE_URL, AUTH_JWT_SECRET environment variables
-    #[tokio::test]
-    #[ignore = "Requires database - run with: cargo test -- --ignored"]
-    async fn test_register_user() {
-        // Setup would include:
-        // 1. Create test database or use testcontainers
-        // 2. Run migrations
-        // 3. Create app state with test config
-        // 4. Build router
-        
-        // Example test structure:
-        // let app = create_test_app().await;
-        // 
-        // let response = app
-        //     .oneshot(
-        //         Request::builder()
-        //             .method("POST")
-        //             .uri("/auth/register")
-        //             .header("Content-Type", "application/json")
-        //             .body(json_body(json!({
-        //                 "email": "test@example.com",
-        //                 "password": "SecurePass123!",
-        //                 "display_name": "Test User"
-        //             })))
-        //             .unwrap(),
-        //     )
-        //     .await
-        //     .unwrap();
-        // 
-        // assert_eq!(response.status(), StatusCode::CREATED);
-    }
-
-    /// Test login flow
-    #[tokio::test]
-    #[ignore = "Requires database - run with: cargo test -- --ignored"]
-    async fn test_login_user() {
-        // Similar structure to register test
-    }
-
-    /// Test /auth/me endpoint with valid token
-    #[tokio::test]
-    #[ignore = "Requires database - run with: cargo test -- --ignored"]
-    async fn test_me_with_valid_token() {
-        // 1. Register a user
-        // 2. Login to get token
-        // 3. Call /auth/me with token
-        // 4. Verify response
-    }
-
-    /// Test /auth/me endpoint with invalid token
-    #[tokio::test]
-    #[ignore = "Requires database - run with: cargo test -- --ignored"]
-    async fn test_me_with_invalid_token() {
-        // Example:
-        // let app = create_test_app().await;
-        // 
-        // let response = app
-        //     .oneshot(
-        //         Request::builder()
-        //             .method("GET")
-        //             .uri("/auth/me")
-        //             .header("Authorization", "Bearer invalid_token")
-        //             .body(Body::empty())
-        //             .unwrap(),
-        //     )
-        //     .await
-        //     .unwrap();
-        // 
-        // assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
-    }
-
-    /// Test duplicate email registration
-    #[tokio::test]
-    #[ignore = "Requires database - run with: cargo test -- --ignored"]
-    async fn test_register_duplicate_email() {
-        // 1. Register a user
-        // 2. Try to register with same email
-        // 3. Expect 409 Conflict
-    }
-
-    /// Test login with wrong password
-    #[tokio::test]
-    #[ignore = "Requires database - run with: cargo test -- --ignored"]
-    async fn test_login_wrong_password() {
-        // 1. Register a user
-        // 2. Try to login with wrong password
-        // 3. Expect 401 Unauthorized
-    }
-}
+//! The HTTP and gRPC flows - registration, login, `/auth/me`, refresh and
+//! logout - run against the in-memory stores in `in_memory_app_test.rs`.
 
 /// Unit-level tests that don't require external dependencies
 mod unit {
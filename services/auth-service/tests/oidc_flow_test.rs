@@ -0,0 +1,579 @@
+//! End-to-end test of the OpenID Connect authorization-code flow
+//!
+//! Drives the provider's HTTP endpoints in-process, with in-memory
+//! repositories in place of the database.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use axum::Router;
+use chrono::{DateTime, Utc};
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use auth_service::domain::audit::{AuditEvent, AuditLog};
+use auth_service::domain::auth::{TokenService, UserRepository};
+use auth_service::domain::error::AuthError;
+use auth_service::domain::oauth_client::{OAuthClient, OAuthClientRepository};
+use auth_service::domain::oidc::{
+    pkce_challenge, AuthorizationCode, AuthorizationCodeRepository, OidcClient,
+    OidcClientRepository,
+};
+use auth_service::domain::role::Role;
+use auth_service::domain::session::{Session, SessionClient, SessionRepository};
+use auth_service::domain::signing_key::{
+    SigningAlgorithm, SigningKey, SigningKeyGenerator, SigningKeyRepository,
+};
+use auth_service::domain::user::{Email, HashedPassword, User};
+use auth_service::infrastructure::security::jwt_key_ring::JwtKeyRing;
+use auth_service::infrastructure::security::jwt_token_service::JwtTokenService;
+use auth_service::infrastructure::security::opaque_token_generator::RandomOpaqueTokenGenerator;
+use auth_service::infrastructure::security::signing_key_generator::RandomSigningKeyGenerator;
+use auth_service::interface::http::oauth;
+use auth_service::OAuthState;
+
+const ISSUER: &str = "https://auth.example.com";
+const LOGIN_URL: &str = "https://app.example.com/login";
+const REDIRECT_URI: &str = "https://app.example.com/callback";
+const VERIFIER: &str = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+
+#[derive(Default)]
+struct MemoryUserRepository {
+    users: Mutex<HashMap<Uuid, User>>,
+}
+
+impl UserRepository for MemoryUserRepository {
+    fn find_by_id(&self, id: Uuid) -> Result<User, AuthError> {
+        self.users
+            .lock()
+            .unwrap()
+            .get(&id)
+            .cloned()
+            .ok_or(AuthError::UserNotFound)
+    }
+
+    fn find_by_email(&self, email: &str) -> Result<User, AuthError> {
+        self.users
+            .lock()
+            .unwrap()
+            .values()
+            .find(|user| user.email().as_str() == email)
+            .cloned()
+            .ok_or(AuthError::UserNotFound)
+    }
+
+    fn exists_by_email(&self, email: &str) -> Result<bool, AuthError> {
+        Ok(self.find_by_email(email).is_ok())
+    }
+
+    fn create(&self, user: &User) -> Result<User, AuthError> {
+        self.users
+            .lock()
+            .unwrap()
+            .insert(user.id().as_uuid(), user.clone());
+        Ok(user.clone())
+    }
+
+    fn update(&self, user: &User) -> Result<User, AuthError> {
+        self.create(user)
+    }
+}
+
+// Sessions that can be ended, as logging out does
+#[derive(Default)]
+struct MemorySessionRepository {
+    sessions: Mutex<HashMap<Uuid, Session>>,
+    revoked: Mutex<HashSet<Uuid>>,
+}
+
+impl MemorySessionRepository {
+    fn revoke(&self, id: Uuid) {
+        self.revoked.lock().unwrap().insert(id);
+    }
+}
+
+impl SessionRepository for MemorySessionRepository {
+    fn create(&self, session: &Session) -> Result<(), AuthError> {
+        self.sessions
+            .lock()
+            .unwrap()
+            .insert(session.id(), session.clone());
+        Ok(())
+    }
+
+    fn find_by_id(&self, id: Uuid) -> Result<Session, AuthError> {
+        self.sessions
+            .lock()
+            .unwrap()
+            .get(&id)
+            .cloned()
+            .ok_or(AuthError::SessionNotFound)
+    }
+
+    fn find_active_for_user(
+        &self,
+        _user_id: Uuid,
+        _now: DateTime<Utc>,
+    ) -> Result<Vec<Session>, AuthError> {
+        Ok(Vec::new())
+    }
+
+    fn touch(
+        &self,
+        _id: Uuid,
+        _now: DateTime<Utc>,
+        _expires_at: DateTime<Utc>,
+    ) -> Result<(), AuthError> {
+        Ok(())
+    }
+
+    fn set_organization(&self, _id: Uuid, _organization_id: Option<Uuid>) -> Result<(), AuthError> {
+        Ok(())
+    }
+
+    fn is_revoked(&self, id: Uuid) -> Result<bool, AuthError> {
+        Ok(self.find_by_id(id).is_err() || self.revoked.lock().unwrap().contains(&id))
+    }
+}
+
+// No service clients take part in the flow
+struct NoOAuthClients;
+
+impl OAuthClientRepository for NoOAuthClients {
+    fn create(&self, _client: &OAuthClient) -> Result<(), AuthError> {
+        Ok(())
+    }
+
+    fn find_by_id(&self, _client_id: Uuid) -> Result<OAuthClient, AuthError> {
+        Err(AuthError::OAuthClientNotFound)
+    }
+
+    fn list(&self) -> Result<Vec<OAuthClient>, AuthError> {
+        Ok(Vec::new())
+    }
+
+    fn revoke(&self, _client_id: Uuid) -> Result<bool, AuthError> {
+        Ok(false)
+    }
+}
+
+#[derive(Default)]
+struct MemoryOidcClientRepository {
+    clients: Mutex<HashMap<String, OidcClient>>,
+}
+
+impl OidcClientRepository for MemoryOidcClientRepository {
+    fn save(&self, client: &OidcClient) -> Result<OidcClient, AuthError> {
+        self.clients
+            .lock()
+            .unwrap()
+            .insert(client.client_id().to_string(), client.clone());
+        Ok(client.clone())
+    }
+
+    fn find_by_id(&self, client_id: &str) -> Result<OidcClient, AuthError> {
+        self.clients
+            .lock()
+            .unwrap()
+            .get(client_id)
+            .cloned()
+            .ok_or(AuthError::InvalidClient)
+    }
+}
+
+#[derive(Default)]
+struct MemoryAuthorizationCodeRepository {
+    codes: Mutex<HashMap<String, AuthorizationCode>>,
+}
+
+impl AuthorizationCodeRepository for MemoryAuthorizationCodeRepository {
+    fn create(&self, code: &AuthorizationCode) -> Result<(), AuthError> {
+        self.codes
+            .lock()
+            .unwrap()
+            .insert(code.code_hash().to_string(), code.clone());
+        Ok(())
+    }
+
+    fn take(&self, code_hash: &str) -> Result<Option<AuthorizationCode>, AuthError> {
+        Ok(self.codes.lock().unwrap().remove(code_hash))
+    }
+
+    fn purge_expired(&self, now: DateTime<Utc>) -> Result<usize, AuthError> {
+        let mut codes = self.codes.lock().unwrap();
+        let before = codes.len();
+        codes.retain(|_, code| code.expires_at() > now);
+        Ok(before - codes.len())
+    }
+}
+
+// One EdDSA key, generated per test app and never rotated
+struct FixedSigningKey(SigningKey);
+
+impl SigningKeyRepository for FixedSigningKey {
+    fn find_verifiable(&self, _now: DateTime<Utc>) -> Result<Vec<SigningKey>, AuthError> {
+        Ok(vec![self.0.clone()])
+    }
+
+    fn rotate(&self, _next: &SigningKey, _verify_until: DateTime<Utc>) -> Result<(), AuthError> {
+        unimplemented!("keys are not rotated in these tests")
+    }
+
+    fn purge_expired(&self, _now: DateTime<Utc>) -> Result<usize, AuthError> {
+        Ok(0)
+    }
+}
+
+// The audit trail is not under test here
+struct NoAuditLog;
+
+impl AuditLog for NoAuditLog {
+    fn record(&self, _event: AuditEvent) {}
+}
+
+struct TestApp {
+    router: Router,
+    tokens: Arc<JwtTokenService>,
+    signing_key: SigningKey,
+    users: Arc<MemoryUserRepository>,
+    sessions: Arc<MemorySessionRepository>,
+}
+
+impl TestApp {
+    fn new() -> Self {
+        Self::with_oidc_provider(true)
+    }
+
+    fn with_oidc_provider(enabled: bool) -> Self {
+        let users = Arc::new(MemoryUserRepository::default());
+        let sessions = Arc::new(MemorySessionRepository::default());
+        let signing_key = RandomSigningKeyGenerator::new()
+            .generate(SigningAlgorithm::EdDsa)
+            .unwrap();
+        let key_ring = JwtKeyRing::new(
+            Arc::new(FixedSigningKey(signing_key.clone())),
+            Duration::ZERO,
+        );
+        let tokens = Arc::new(
+            JwtTokenService::with_key_ring(Arc::new(key_ring), 3600)
+                .with_issuer(ISSUER.into())
+                .with_session_store(sessions.clone()),
+        );
+        let state = OAuthState {
+            users: users.clone(),
+            sessions: sessions.clone(),
+            token_service: tokens.clone(),
+            token_generator: Arc::new(RandomOpaqueTokenGenerator::new()),
+            oauth_clients: Arc::new(NoOAuthClients),
+            service_token_issuer: tokens.clone(),
+            oidc_clients: Arc::new(MemoryOidcClientRepository::default()),
+            authorization_codes: Arc::new(MemoryAuthorizationCodeRepository::default()),
+            id_token_issuer: tokens.clone(),
+            audit_log: Arc::new(NoAuditLog),
+            trust_forwarded_for: false,
+            oidc_provider_enabled: enabled,
+            issuer: ISSUER.to_string(),
+            login_url: LOGIN_URL.to_string(),
+            id_token_signing_alg: "EdDSA".to_string(),
+            access_token_ttl: chrono::Duration::seconds(3600),
+            authorization_code_ttl: chrono::Duration::seconds(60),
+        };
+
+        Self {
+            router: oauth::router(Arc::new(state)),
+            tokens,
+            signing_key,
+            users,
+            sessions,
+        }
+    }
+
+    /// Add a user and sign them in, returning the user and the access token
+    /// of their new session
+    fn sign_in(&self, email: &str, role: Role) -> (User, String) {
+        let email = Email::new(email).unwrap();
+        let password = HashedPassword::from_hash("unused".to_string());
+        let mut user = User::new(email, password, Some("Ada Lovelace".to_string()));
+        user.assign_role(role);
+        self.users.create(&user).unwrap();
+        let session = Session::start(
+            Uuid::new_v4(),
+            user.id().as_uuid(),
+            &SessionClient::default(),
+            Utc::now() + chrono::Duration::days(1),
+        );
+        self.sessions.create(&session).unwrap();
+        let token = self.tokens.create_token(&user, Some(session.id())).unwrap();
+        (user, token)
+    }
+
+    async fn send(&self, request: Request<Body>) -> (StatusCode, Option<String>, Value) {
+        let response = self.router.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let location = response
+            .headers()
+            .get(header::LOCATION)
+            .map(|value| value.to_str().unwrap().to_string());
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+        (status, location, body)
+    }
+
+    /// Register the "web" client as an administrator
+    async fn register_web_app(&self) -> Value {
+        let (_, admin_token) = self.sign_in("admin@example.com", Role::Admin);
+        let request = Request::put("/admin/oidc-clients/web")
+            .header(header::AUTHORIZATION, format!("Bearer {}", admin_token))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(
+                json!({ "name": "Web app", "redirect_uris": [REDIRECT_URI] }).to_string(),
+            ))
+            .unwrap();
+        let (status, _, body) = self.send(request).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        body
+    }
+
+    async fn authorize(&self, token: &str, params: &Value) -> HashMap<String, String> {
+        let request = Request::post("/oauth/authorize")
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(params.to_string()))
+            .unwrap();
+        let (status, _, body) = self.send(request).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+
+        let redirect_to = body["redirect_to"].as_str().unwrap();
+        let query = redirect_to
+            .strip_prefix(&format!("{}?", REDIRECT_URI))
+            .expect("code is sent to the registered redirect URI");
+        serde_urlencoded::from_str(query).unwrap()
+    }
+
+    async fn exchange(&self, code: &str, verifier: &str) -> (StatusCode, Value) {
+        let form = serde_urlencoded::to_string([
+            ("grant_type", "authorization_code"),
+            ("client_id", "web"),
+            ("code", code),
+            ("redirect_uri", REDIRECT_URI),
+            ("code_verifier", verifier),
+        ])
+        .unwrap();
+        let request = Request::post("/oauth/token")
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(Body::from(form))
+            .unwrap();
+        let (status, _, body) = self.send(request).await;
+        (status, body)
+    }
+}
+
+fn authorization_params() -> Value {
+    json!({
+        "response_type": "code",
+        "client_id": "web",
+        "redirect_uri": REDIRECT_URI,
+        "scope": "openid email profile",
+        "state": "af0ifjsldkj",
+        "nonce": "n-0S6_WzA2Mj",
+        "code_challenge": pkce_challenge(VERIFIER),
+        "code_challenge_method": "S256",
+    })
+}
+
+#[tokio::test]
+async fn test_authorization_code_flow() {
+    let app = TestApp::new();
+
+    // An administrator registers the web app
+    let client = app.register_web_app().await;
+    assert_eq!(client["redirect_uris"], json!([REDIRECT_URI]));
+
+    // The app discovers the provider
+    let request = Request::get("/.well-known/openid-configuration")
+        .body(Body::empty())
+        .unwrap();
+    let (status, _, discovery) = app.send(request).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(discovery["issuer"], ISSUER);
+    assert_eq!(
+        discovery["token_endpoint"],
+        format!("{}/oauth/token", ISSUER)
+    );
+    assert_eq!(
+        discovery["code_challenge_methods_supported"],
+        json!(["S256"])
+    );
+
+    // The browser is sent on to the login page with the original request
+    let params = authorization_params();
+    let query = serde_urlencoded::to_string(&params).unwrap();
+    let request = Request::get(format!("/oauth/authorize?{}", query))
+        .body(Body::empty())
+        .unwrap();
+    let (status, location, _) = app.send(request).await;
+    assert!(status.is_redirection());
+    assert_eq!(location, Some(format!("{}?{}", LOGIN_URL, query)));
+
+    // The user signs in and the login page completes the request
+    let (user, user_token) = app.sign_in("ada@example.com", Role::Customer);
+    let callback = app.authorize(&user_token, &params).await;
+    assert_eq!(callback["state"], "af0ifjsldkj");
+
+    // The app redeems the code
+    let (status, tokens) = app.exchange(&callback["code"], VERIFIER).await;
+    assert_eq!(status, StatusCode::OK, "{}", tokens);
+    assert_eq!(tokens["token_type"], "Bearer");
+    assert_eq!(tokens["scope"], "openid email profile");
+
+    let mut validation = Validation::new(Algorithm::EdDSA);
+    validation.set_audience(&["web"]);
+    validation.set_issuer(&[ISSUER]);
+    let id_token = jsonwebtoken::decode::<Value>(
+        tokens["id_token"].as_str().unwrap(),
+        &DecodingKey::from_ed_pem(app.signing_key.public_key_pem().as_bytes()).unwrap(),
+        &validation,
+    )
+    .unwrap()
+    .claims;
+    assert_eq!(id_token["sub"], user.id().as_uuid().to_string());
+    assert_eq!(id_token["nonce"], "n-0S6_WzA2Mj");
+    assert_eq!(id_token["email"], "ada@example.com");
+    assert_eq!(id_token["name"], "Ada Lovelace");
+
+    // Codes work once only
+    let (status, body) = app.exchange(&callback["code"], VERIFIER).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "invalid_grant");
+
+    // The access token identifies the user
+    let request = Request::get("/oauth/userinfo")
+        .header(
+            header::AUTHORIZATION,
+            format!("Bearer {}", tokens["access_token"].as_str().unwrap()),
+        )
+        .body(Body::empty())
+        .unwrap();
+    let (status, _, userinfo) = app.send(request).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(userinfo["sub"], user.id().as_uuid().to_string());
+    assert_eq!(userinfo["email"], "ada@example.com");
+    assert_eq!(userinfo["email_verified"], false);
+    assert_eq!(userinfo["name"], "Ada Lovelace");
+}
+
+#[tokio::test]
+async fn test_userinfo_follows_granted_scopes() {
+    let app = TestApp::new();
+    app.register_web_app().await;
+
+    let (user, user_token) = app.sign_in("ada@example.com", Role::Customer);
+    let mut params = authorization_params();
+    params["scope"] = json!("openid");
+    let callback = app.authorize(&user_token, &params).await;
+    let (status, tokens) = app.exchange(&callback["code"], VERIFIER).await;
+    assert_eq!(status, StatusCode::OK, "{}", tokens);
+    assert_eq!(tokens["scope"], "openid");
+
+    let request = Request::get("/oauth/userinfo")
+        .header(
+            header::AUTHORIZATION,
+            format!("Bearer {}", tokens["access_token"].as_str().unwrap()),
+        )
+        .body(Body::empty())
+        .unwrap();
+    let (status, _, userinfo) = app.send(request).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(userinfo, json!({ "sub": user.id().as_uuid().to_string() }));
+}
+
+#[tokio::test]
+async fn test_code_requires_matching_verifier() {
+    let app = TestApp::new();
+    app.register_web_app().await;
+
+    let (_, user_token) = app.sign_in("ada@example.com", Role::Customer);
+    let callback = app.authorize(&user_token, &authorization_params()).await;
+
+    let wrong_verifier = VERIFIER.replace('d', "e");
+    let (status, body) = app.exchange(&callback["code"], &wrong_verifier).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "invalid_grant");
+
+    // The failed attempt used up the code
+    let (status, _) = app.exchange(&callback["code"], VERIFIER).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_unregistered_redirect_uri_is_not_followed() {
+    let app = TestApp::new();
+    app.register_web_app().await;
+
+    let request = Request::get(
+        "/oauth/authorize?response_type=code&client_id=web&scope=openid\
+         &redirect_uri=https%3A%2F%2Fevil.example.com%2F&code_challenge_method=S256\
+         &code_challenge=E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM",
+    )
+    .body(Body::empty())
+    .unwrap();
+    let (status, location, body) = app.send(request).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(location, None);
+    assert_eq!(body["error"], "invalid_request");
+}
+
+#[tokio::test]
+async fn test_logout_ends_app_access() {
+    let app = TestApp::new();
+    app.register_web_app().await;
+
+    let (_, user_token) = app.sign_in("ada@example.com", Role::Customer);
+    let session_id = app.tokens.validate_token(&user_token).unwrap().session_id;
+    let first = app.authorize(&user_token, &authorization_params()).await;
+    let second = app.authorize(&user_token, &authorization_params()).await;
+    let (status, tokens) = app.exchange(&first["code"], VERIFIER).await;
+    assert_eq!(status, StatusCode::OK, "{}", tokens);
+
+    app.sessions.revoke(session_id.unwrap());
+
+    // The app's access token ends with the session
+    let request = Request::get("/oauth/userinfo")
+        .header(
+            header::AUTHORIZATION,
+            format!("Bearer {}", tokens["access_token"].as_str().unwrap()),
+        )
+        .body(Body::empty())
+        .unwrap();
+    let (status, _, _) = app.send(request).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // And codes issued to the session can no longer be redeemed
+    let (status, body) = app.exchange(&second["code"], VERIFIER).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "invalid_grant");
+}
+
+#[tokio::test]
+async fn test_disabled_provider_serves_no_oidc_endpoints() {
+    let app = TestApp::with_oidc_provider(false);
+
+    let request = Request::get("/.well-known/openid-configuration")
+        .body(Body::empty())
+        .unwrap();
+    let (status, _, _) = app.send(request).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, body) = app.exchange("code", VERIFIER).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "unsupported_grant_type");
+}